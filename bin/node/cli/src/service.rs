@@ -133,12 +133,20 @@ macro_rules! new_full {
 
 		let (builder, mut import_setup, inherent_data_providers) = new_full_start!($config);
 
+		let shared_authority_set = import_setup.as_ref()
+			.map(|(_, grandpa_link, _)| grandpa_link.shared_authority_set().clone());
+
 		let service = builder
 			.with_finality_proof_provider(|client, backend| {
 				// GenesisAuthoritySetProvider is implemented for StorageAndProofProvider
 				let provider = client as Arc<dyn grandpa::StorageAndProofProvider<_, _>>;
 				Ok(Arc::new(grandpa::FinalityProofProvider::new(backend, provider)) as _)
 			})?
+			.with_warp_sync_provider(|_client, backend| {
+				let authority_set = shared_authority_set
+					.ok_or_else(|| "Trying to serve warp sync proofs without GRANDPA link half")?;
+				Ok(Arc::new(grandpa::NetworkProvider::new(backend, authority_set)) as _)
+			})?
 			.build()?;
 
 		let (block_import, grandpa_link, babe_link) = import_setup.take()
@@ -290,6 +298,7 @@ pub fn new_light(config: Configuration)
 -> Result<impl AbstractService, ServiceError> {
 	type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
 	let inherent_data_providers = InherentDataProviders::new();
+	let mut grandpa_light_import = None;
 
 	let service = ServiceBuilder::new_light::<Block, RuntimeApi, node_executor::Executor>(config)?
 		.with_select_chain(|_config, backend| {
@@ -314,6 +323,7 @@ pub fn new_light(config: Configuration)
 				&(client.clone() as Arc<_>),
 				Arc::new(fetch_checker),
 			)?;
+			grandpa_light_import = Some(grandpa_block_import.clone());
 
 			let finality_proof_import = grandpa_block_import.clone();
 			let finality_proof_request_builder =
@@ -341,6 +351,11 @@ pub fn new_light(config: Configuration)
 			let provider = client as Arc<dyn StorageAndProofProvider<_, _>>;
 			Ok(Arc::new(GrandpaFinalityProofProvider::new(backend, provider)) as _)
 		})?
+		.with_warp_sync_provider(|_client, backend| {
+			let grandpa_import = grandpa_light_import.take()
+				.ok_or_else(|| "Trying to start light warp sync without GRANDPA block import")?;
+			Ok(grandpa_import.create_warp_sync_provider(backend.blockchain().clone()))
+		})?
 		.with_rpc_extensions(|builder,| ->
 			Result<RpcExtension, _>
 		{
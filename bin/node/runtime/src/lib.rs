@@ -23,34 +23,38 @@
 use sp_std::prelude::*;
 use frame_support::{
 	construct_runtime, parameter_types, debug,
+	metadata::TypeInfo,
 	weights::Weight,
-	traits::{Currency, Randomness, OnUnbalanced, Imbalance},
+	traits::{Currency, Randomness, OnUnbalanced, Imbalance, KeyOwnerProofSystem, InstanceFilter},
 };
-use sp_core::u32_trait::{_1, _2, _3, _4};
+use codec::{Encode, Decode};
+use sp_core::{crypto::KeyTypeId, u32_trait::{_1, _2, _3, _4}};
 pub use node_primitives::{AccountId, Signature};
 use node_primitives::{AccountIndex, Balance, BlockNumber, Hash, Index, Moment};
 use sp_api::impl_runtime_apis;
 use sp_runtime::{
 	Permill, Perbill, Percent, ApplyExtrinsicResult,
-	impl_opaque_keys, generic, create_runtime_str,
+	impl_opaque_keys, generic, create_runtime_str, RuntimeDebug,
 };
 use sp_runtime::curve::PiecewiseLinear;
 use sp_runtime::transaction_validity::{TransactionValidity, TransactionSource};
 use sp_runtime::traits::{
 	self, BlakeTwo256, Block as BlockT, StaticLookup, SaturatedConversion,
-	ConvertInto, OpaqueKeys,
+	ConvertInto, OpaqueKeys, NumberFor,
 };
 use sp_version::RuntimeVersion;
 #[cfg(any(feature = "std", test))]
 use sp_version::NativeVersion;
 use sp_core::OpaqueMetadata;
-use pallet_grandpa::AuthorityList as GrandpaAuthorityList;
+use pallet_babe::AuthorityId as BabeId;
+use pallet_grandpa::{AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 use pallet_grandpa::fg_primitives;
 use pallet_im_online::sr25519::{AuthorityId as ImOnlineId};
 use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo;
 use pallet_contracts_rpc_runtime_api::ContractExecResult;
-use frame_system::offchain::TransactionSubmitter;
+use frame_system::{EnsureRoot, offchain::TransactionSubmitter};
+use pallet_session::historical as pallet_session_historical;
 use sp_inherents::{InherentData, CheckInherentsResult};
 
 #[cfg(any(feature = "std", test))]
@@ -128,8 +132,8 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 240,
-	impl_version: 1,
+	spec_version: 252,
+	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
 };
 
@@ -190,23 +194,125 @@ impl frame_system::Trait for Runtime {
 	type OnKilledAccount = ();
 }
 
+impl pallet_utility::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+}
+
 parameter_types! {
 	// One storage item; value is size 4+4+16+32 bytes = 56 bytes.
-	pub const MultisigDepositBase: Balance = 30 * CENTS;
+	pub const DepositBase: Balance = 30 * CENTS;
 	// Additional storage item size of 32 bytes.
-	pub const MultisigDepositFactor: Balance = 5 * CENTS;
+	pub const DepositFactor: Balance = 5 * CENTS;
 	pub const MaxSignatories: u16 = 100;
 }
 
-impl pallet_utility::Trait for Runtime {
+impl pallet_multisig::Trait for Runtime {
 	type Event = Event;
 	type Call = Call;
 	type Currency = Balances;
-	type MultisigDepositBase = MultisigDepositBase;
-	type MultisigDepositFactor = MultisigDepositFactor;
+	type DepositBase = DepositBase;
+	type DepositFactor = DepositFactor;
 	type MaxSignatories = MaxSignatories;
 }
 
+parameter_types! {
+	// One storage item; key size 32, value size 16 bytes.
+	pub const ProxyDepositBase: Balance = 20 * CENTS;
+	// Additional storage item size of 33 bytes.
+	pub const ProxyDepositFactor: Balance = 3 * CENTS;
+	pub const MaxProxies: u16 = 32;
+}
+
+/// The type used to represent the kinds of proxying allowed.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum ProxyType {
+	/// Any call may be proxied.
+	Any,
+	/// Any call that cannot be used to move funds out of the account.
+	NonTransfer,
+	/// Only governance related calls.
+	Governance,
+	/// Only staking related calls.
+	Staking,
+}
+
+impl Default for ProxyType { fn default() -> Self { Self::Any } }
+
+impl InstanceFilter<Call> for ProxyType {
+	fn filter(&self, c: &Call) -> bool {
+		match self {
+			ProxyType::Any => true,
+			// Only calls that can not move funds out of the account are allowed. Calls that
+			// dispatch other calls are excluded as their inner calls are not filtered.
+			ProxyType::NonTransfer => matches!(c,
+				Call::System(..) | Call::Babe(..) | Call::Timestamp(..) | Call::Authorship(..)
+					| Call::Indices(pallet_indices::Call::claim(..))
+					| Call::Indices(pallet_indices::Call::free(..))
+					| Call::Staking(..) | Call::Session(..) | Call::Democracy(..)
+					| Call::Council(..) | Call::TechnicalCommittee(..) | Call::Elections(..)
+					| Call::TechnicalMembership(..) | Call::FinalityTracker(..) | Call::Grandpa(..)
+					| Call::Treasury(..) | Call::ImOnline(..) | Call::Identity(..) | Call::Society(..)
+					| Call::Recovery(pallet_recovery::Call::vouch_recovery(..))
+					| Call::Recovery(pallet_recovery::Call::remove_recovery(..))
+					| Call::Recovery(pallet_recovery::Call::close_recovery(..))
+					| Call::Vesting(pallet_vesting::Call::vest(..))
+					| Call::Vesting(pallet_vesting::Call::vest_other(..))
+					| Call::Proxy(pallet_proxy::Call::add_proxy(..))
+					| Call::Proxy(pallet_proxy::Call::remove_proxy(..))
+					| Call::Multisig(pallet_multisig::Call::cancel_as_multi(..))
+			),
+			ProxyType::Governance => matches!(c,
+				Call::Democracy(..) | Call::Council(..) | Call::TechnicalCommittee(..)
+					| Call::Elections(..) | Call::Treasury(..)
+			),
+			ProxyType::Staking => matches!(c, Call::Staking(..)),
+		}
+	}
+	fn is_superset(&self, o: &Self) -> bool {
+		match (self, o) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			(_, ProxyType::Any) => false,
+			(ProxyType::NonTransfer, _) => true,
+			_ => false,
+		}
+	}
+}
+
+impl pallet_proxy::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = MaxProxies;
+}
+
+parameter_types! {
+	pub const AssetDepositBase: Balance = 100 * DOLLARS;
+	pub const MetadataDepositBase: Balance = 10 * DOLLARS;
+	pub const MetadataDepositPerByte: Balance = 1 * DOLLARS;
+	pub const ApprovalDeposit: Balance = 1 * DOLLARS;
+	pub const AccountDeposit: Balance = 1 * DOLLARS;
+	pub const StringLimit: u32 = 50;
+}
+
+impl pallet_assets::Trait for Runtime {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDepositBase = AssetDepositBase;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type AccountDeposit = AccountDeposit;
+	type StringLimit = StringLimit;
+}
+
 parameter_types! {
 	pub const MaximumWeight: Weight = 2_000_000;
 }
@@ -216,6 +322,7 @@ impl pallet_scheduler::Trait for Runtime {
 	type Origin = Origin;
 	type Call = Call;
 	type MaximumWeight = MaximumWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
 }
 
 parameter_types! {
@@ -227,6 +334,22 @@ impl pallet_babe::Trait for Runtime {
 	type EpochDuration = EpochDuration;
 	type ExpectedBlockTime = ExpectedBlockTime;
 	type EpochChangeTrigger = pallet_babe::ExternalTrigger;
+	type Call = Call;
+
+	type KeyOwnerProofSystem = Historical;
+
+	type KeyOwnerProof =
+		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, BabeId)>>::Proof;
+
+	type KeyOwnerIdentification = <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(
+		KeyTypeId,
+		BabeId,
+	)>>::IdentificationTuple;
+
+	type HandleEquivocation = pallet_babe::EquivocationHandler<
+		Offences,
+		TransactionSubmitterOf<()>,
+	>;
 }
 
 parameter_types! {
@@ -292,6 +415,7 @@ impl pallet_authorship::Trait for Runtime {
 }
 
 impl_opaque_keys! {
+	#[derive(TypeInfo)]
 	pub struct SessionKeys {
 		pub grandpa: Grandpa,
 		pub babe: Babe,
@@ -309,7 +433,7 @@ impl pallet_session::Trait for Runtime {
 	type ValidatorId = <Self as frame_system::Trait>::AccountId;
 	type ValidatorIdOf = pallet_staking::StashOf<Self>;
 	type ShouldEndSession = Babe;
-	type SessionManager = Staking;
+	type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, Staking>;
 	type SessionHandler = <SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
 	type Keys = SessionKeys;
 	type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
@@ -477,6 +601,12 @@ parameter_types! {
 	pub const TipFindersFee: Percent = Percent::from_percent(20);
 	pub const TipReportDepositBase: Balance = 1 * DOLLARS;
 	pub const TipReportDepositPerByte: Balance = 1 * CENTS;
+	pub const BountyDepositBase: Balance = 1 * DOLLARS;
+	pub const BountyDepositPerByte: Balance = 1 * CENTS;
+	pub const BountyDepositPayoutDelay: BlockNumber = 8 * DAYS;
+	pub const BountyUpdatePeriod: BlockNumber = 90 * DAYS;
+	pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+	pub const BountyValueMinimum: Balance = 10 * DOLLARS;
 }
 
 impl pallet_treasury::Trait for Runtime {
@@ -494,6 +624,12 @@ impl pallet_treasury::Trait for Runtime {
 	type ProposalBondMinimum = ProposalBondMinimum;
 	type SpendPeriod = SpendPeriod;
 	type Burn = Burn;
+	type BountyDepositBase = BountyDepositBase;
+	type BountyDepositPerByte = BountyDepositPerByte;
+	type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+	type BountyUpdatePeriod = BountyUpdatePeriod;
+	type BountyCuratorDeposit = BountyCuratorDeposit;
+	type BountyValueMinimum = BountyValueMinimum;
 }
 
 parameter_types! {
@@ -561,6 +697,22 @@ impl pallet_authority_discovery::Trait for Runtime {}
 
 impl pallet_grandpa::Trait for Runtime {
 	type Event = Event;
+	type Call = Call;
+
+	type KeyOwnerProofSystem = Historical;
+
+	type KeyOwnerProof =
+		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, GrandpaId)>>::Proof;
+
+	type KeyOwnerIdentification = <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(
+		KeyTypeId,
+		GrandpaId,
+	)>>::IdentificationTuple;
+
+	type HandleEquivocation = pallet_grandpa::EquivocationHandler<
+		Offences,
+		TransactionSubmitterOf<()>,
+	>;
 }
 
 parameter_types! {
@@ -640,6 +792,7 @@ impl pallet_society::Trait for Runtime {
 
 parameter_types! {
 	pub const MinVestedTransfer: Balance = 100 * DOLLARS;
+	pub const MaxVestingSchedules: u32 = 16;
 }
 
 impl pallet_vesting::Trait for Runtime {
@@ -647,6 +800,7 @@ impl pallet_vesting::Trait for Runtime {
 	type Currency = Balances;
 	type BlockNumberToBalance = ConvertInto;
 	type MinVestedTransfer = MinVestedTransfer;
+	type MaxVestingSchedules = MaxVestingSchedules;
 }
 
 construct_runtime!(
@@ -656,8 +810,8 @@ construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic
 	{
 		System: frame_system::{Module, Call, Config, Storage, Event<T>},
-		Utility: pallet_utility::{Module, Call, Storage, Event<T>},
-		Babe: pallet_babe::{Module, Call, Storage, Config, Inherent(Timestamp)},
+		Utility: pallet_utility::{Module, Call, Event},
+		Babe: pallet_babe::{Module, Call, Storage, Config, Inherent(Timestamp), ValidateUnsigned},
 		Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
 		Authorship: pallet_authorship::{Module, Call, Storage, Inherent},
 		Indices: pallet_indices::{Module, Call, Storage, Config<T>, Event<T>},
@@ -671,7 +825,7 @@ construct_runtime!(
 		Elections: pallet_elections_phragmen::{Module, Call, Storage, Event<T>, Config<T>},
 		TechnicalMembership: pallet_membership::<Instance1>::{Module, Call, Storage, Event<T>, Config<T>},
 		FinalityTracker: pallet_finality_tracker::{Module, Call, Inherent},
-		Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event},
+		Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event, ValidateUnsigned},
 		Treasury: pallet_treasury::{Module, Call, Storage, Config, Event<T>},
 		Contracts: pallet_contracts::{Module, Call, Config<T>, Storage, Event<T>},
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
@@ -684,6 +838,10 @@ construct_runtime!(
 		Recovery: pallet_recovery::{Module, Call, Storage, Event<T>},
 		Vesting: pallet_vesting::{Module, Call, Storage, Event<T>, Config<T>},
 		Scheduler: pallet_scheduler::{Module, Call, Storage, Event<T>},
+		Proxy: pallet_proxy::{Module, Call, Storage, Event<T>},
+		Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
+		Assets: pallet_assets::{Module, Call, Storage, Event<T>},
+		Historical: pallet_session_historical::{Module},
 	}
 );
 
@@ -779,6 +937,32 @@ impl_runtime_apis! {
 		fn grandpa_authorities() -> GrandpaAuthorityList {
 			Grandpa::grandpa_authorities()
 		}
+
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: fg_primitives::EquivocationProof<
+				<Block as BlockT>::Hash,
+				NumberFor<Block>,
+			>,
+			key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			Grandpa::submit_unsigned_equivocation_report(
+				equivocation_proof,
+				key_owner_proof,
+			)
+		}
+
+		fn generate_key_ownership_proof(
+			_set_id: fg_primitives::SetId,
+			authority_id: GrandpaId,
+		) -> Option<fg_primitives::OpaqueKeyOwnershipProof> {
+			use codec::Encode;
+
+			Historical::prove((fg_primitives::KEY_TYPE, authority_id))
+				.map(|p| p.encode())
+				.map(fg_primitives::OpaqueKeyOwnershipProof::new)
+		}
 	}
 
 	impl sp_consensus_babe::BabeApi<Block> for Runtime {
@@ -801,6 +985,29 @@ impl_runtime_apis! {
 		fn current_epoch_start() -> sp_consensus_babe::SlotNumber {
 			Babe::current_epoch_start()
 		}
+
+		fn generate_key_ownership_proof(
+			_slot_number: sp_consensus_babe::SlotNumber,
+			authority_id: BabeId,
+		) -> Option<sp_consensus_babe::OpaqueKeyOwnershipProof> {
+			use codec::Encode;
+
+			Historical::prove((sp_consensus_babe::KEY_TYPE, authority_id))
+				.map(|p| p.encode())
+				.map(sp_consensus_babe::OpaqueKeyOwnershipProof::new)
+		}
+
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: sp_consensus_babe::EquivocationProof<<Block as BlockT>::Header>,
+			key_owner_proof: sp_consensus_babe::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			Babe::submit_unsigned_equivocation_report(
+				equivocation_proof,
+				key_owner_proof,
+			)
+		}
 	}
 
 	impl sp_authority_discovery::AuthorityDiscoveryApi<Block> for Runtime {
@@ -860,6 +1067,41 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_multisig_runtime_api::MultisigApi<
+		Block,
+		AccountId,
+		BlockNumber,
+		Balance,
+	> for Runtime {
+		fn pending_multisigs(
+			multi_account: AccountId,
+		) -> Vec<(pallet_multisig::CallHash, pallet_multisig::Multisig<BlockNumber, Balance, AccountId>)> {
+			Multisig::pending_multisigs(&multi_account)
+		}
+	}
+
+	impl pallet_assets_runtime_api::AssetsApi<
+		Block,
+		AccountId,
+		u32,
+		u64,
+	> for Runtime {
+		fn account_balances(account: AccountId, ids: Vec<u32>) -> Vec<(u32, u64)> {
+			Assets::account_balances(&account, ids)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> Weight {
+			Executive::try_runtime_upgrade()
+		}
+
+		fn execute_block_no_check(block: Block) -> Weight {
+			Executive::execute_block_no_check(block)
+		}
+	}
+
 	impl sp_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			SessionKeys::generate(seed)
@@ -900,6 +1142,8 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, b"democracy", Democracy);
 			add_benchmark!(params, batches, b"identity", Identity);
 			add_benchmark!(params, batches, b"im-online", ImOnline);
+			add_benchmark!(params, batches, b"multisig", Multisig);
+			add_benchmark!(params, batches, b"proxy", Proxy);
 			add_benchmark!(params, batches, b"session", SessionBench::<Runtime>);
 			add_benchmark!(params, batches, b"staking", Staking);
 			add_benchmark!(params, batches, b"timestamp", Timestamp);
@@ -941,4 +1185,38 @@ mod tests {
 		is_submit_signed_transaction::<TransactionSubmitterOf<ImOnlineId>>();
 		is_sign_and_submit_transaction::<TransactionSubmitterOf<ImOnlineId>>();
 	}
+
+	#[test]
+	fn non_transfer_proxy_rejects_calls_moving_funds() {
+		let transfer = Call::Balances(pallet_balances::Call::transfer(Address::Id(Default::default()), 1));
+		let recovered = pallet_recovery::Call::as_recovered(Default::default(), Box::new(transfer.clone()));
+		let contract = pallet_contracts::Call::call(Address::Id(Default::default()), 1, 0, Vec::new());
+
+		assert!(!ProxyType::NonTransfer.filter(&transfer));
+		assert!(!ProxyType::NonTransfer.filter(&Call::Recovery(recovered)));
+		assert!(!ProxyType::NonTransfer.filter(&Call::Contracts(contract)));
+		assert!(ProxyType::NonTransfer.filter(&Call::Staking(pallet_staking::Call::chill())));
+	}
+
+	#[test]
+	#[cfg(feature = "metadata-v12")]
+	fn metadata_is_v12_and_resolves_all_types() {
+		use frame_support::metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+
+		let encoded = Runtime::metadata().encode();
+		let metadata = match RuntimeMetadataPrefixed::decode(&mut &encoded[..]).unwrap().1 {
+			RuntimeMetadata::V12(metadata) => metadata,
+			_ => panic!("Expected metadata V12"),
+		};
+
+		assert!(!metadata.modules.is_empty());
+		for module in &metadata.modules {
+			let calls = module.calls.iter().flatten().flat_map(|c| c.arguments.iter().map(|a| a.ty));
+			let events = module.event.iter().flatten().flat_map(|e| e.arguments.iter().cloned());
+			let constants = module.constants.iter().map(|c| c.ty);
+			for ty in calls.chain(events).chain(constants) {
+				assert!(metadata.types.resolve(ty).is_some(), "{:?} refers to an unknown type", module.name);
+			}
+		}
+	}
 }
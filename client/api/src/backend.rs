@@ -32,6 +32,7 @@ use crate::{
 		Backend as BlockchainBackend, well_known_cache_keys
 	},
 	light::RemoteBlockchain,
+	StorageProof,
 	UsageInfo,
 };
 use sp_blockchain;
@@ -141,6 +142,46 @@ pub trait BlockImportOperation<Block: BlockT> {
 	/// Inject storage data into the database replacing any existing data.
 	fn reset_storage(&mut self, storage: Storage) -> sp_blockchain::Result<Block::Hash>;
 
+	/// Inject the complete state of an existing block, e.g. one that was read from a snapshot.
+	/// Returns the root of the imported state, which the caller must check against the block
+	/// header before committing the operation.
+	fn import_state(
+		&mut self,
+		block: Block::Hash,
+		storage: Storage,
+	) -> sp_blockchain::Result<Block::Hash>;
+
+	/// Insert the header of a block that isn't known yet, along with its state imported with
+	/// `import_state`. The block becomes the best and finalized block while its ancestry stays
+	/// unknown, which lets a fresh node start from a snapshot.
+	fn set_snapshot_header(&mut self, header: Block::Header) -> sp_blockchain::Result<()>;
+
+	/// Inject a chunk of the state of an existing block, e.g. one that was downloaded from the
+	/// network. `proof` is a range proof of the values that follow `start` in the top trie, or
+	/// in the given child trie, whose root is `root`. Returns the values of the chunk and
+	/// whether it is the last one of its trie.
+	///
+	/// The trie nodes of the chunk are written when the operation is committed, except for the
+	/// root node of the top trie: the state only becomes available once all chunks have been
+	/// imported and `complete_state_import` is called.
+	fn import_state_chunk(
+		&mut self,
+		child: Option<(&[u8], ChildInfo)>,
+		root: Block::Hash,
+		proof: StorageProof,
+		start: Option<&[u8]>,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)>;
+
+	/// Complete the import of the state of `block`, whose chunks have all been imported with
+	/// `import_state_chunk`, by writing the root node of its top trie. The node is taken from
+	/// `proof`, which can be any of the proofs of the top trie.
+	fn complete_state_import(
+		&mut self,
+		block: Block::Hash,
+		root: Block::Hash,
+		proof: StorageProof,
+	) -> sp_blockchain::Result<()>;
+
 	/// Set storage changes.
 	fn update_storage(
 		&mut self,
@@ -148,6 +189,16 @@ pub trait BlockImportOperation<Block: BlockT> {
 		child_update: ChildStorageCollection,
 	) -> sp_blockchain::Result<()>;
 
+	/// Set offchain database changes collected by offchain indexing.
+	///
+	/// Backends without offchain storage ignore the changes.
+	fn update_offchain_storage(
+		&mut self,
+		_offchain_update: StorageCollection,
+	) -> sp_blockchain::Result<()> {
+		Ok(())
+	}
+
 	/// Inject changes trie data into the database.
 	fn update_changes_trie(
 		&mut self,
@@ -354,6 +405,18 @@ pub trait StorageProvider<Block: BlockT, B: Backend<Block>> {
 		storage_key: Option<&StorageKey>,
 		key: &StorageKey
 	) -> sp_blockchain::Result<Vec<(NumberFor<Block>, u32)>>;
+
+	/// Get the numbers of the blocks within [first; last] at which the top-level `key` has been
+	/// changed, in ascending order.
+	///
+	/// The numbers may include blocks that are not part of the canonical chain.
+	/// Returns Ok(None) if the backend doesn't keep an index of key changes.
+	fn key_history(
+		&self,
+		first: NumberFor<Block>,
+		last: NumberFor<Block>,
+		key: &StorageKey,
+	) -> sp_blockchain::Result<Option<Vec<NumberFor<Block>>>>;
 }
 
 /// Client backend.
@@ -412,6 +475,19 @@ pub trait Backend<Block: BlockT>: AuxStore + Send + Sync {
 	/// Returns a handle to offchain storage.
 	fn offchain_storage(&self) -> Option<Self::OffchainStorage>;
 
+	/// Returns the numbers of the imported blocks within [first; last] at which the top-level
+	/// `key` has been changed, in ascending order.
+	///
+	/// Returns Ok(None) if the backend doesn't keep an index of key changes.
+	fn key_history(
+		&self,
+		_key: &[u8],
+		_first: NumberFor<Block>,
+		_last: NumberFor<Block>,
+	) -> sp_blockchain::Result<Option<Vec<NumberFor<Block>>>> {
+		Ok(None)
+	}
+
 	/// Returns true if state for given block is available.
 	fn have_state_at(&self, hash: &Block::Hash, _number: NumberFor<Block>) -> bool {
 		self.state_at(BlockId::Hash(hash.clone())).is_ok()
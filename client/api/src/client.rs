@@ -17,7 +17,7 @@
 //! A set of APIs supported by the client along with their primitives.
 
 use std::{fmt, collections::HashSet};
-use sp_core::storage::StorageKey;
+use sp_core::storage::{ChildInfo, StorageKey, Storage};
 use sp_runtime::{
 	traits::{Block as BlockT, NumberFor},
 	generic::{BlockId, SignedBlock},
@@ -27,6 +27,7 @@ use sp_consensus::BlockOrigin;
 
 use crate::blockchain::Info;
 use crate::notifications::StorageEventStream;
+use crate::StorageProof;
 use sp_utils::mpsc::TracingUnboundedReceiver;
 use sp_blockchain;
 
@@ -92,6 +93,37 @@ pub trait BlockBackend<Block: BlockT> {
 	fn justification(&self, id: &BlockId<Block>) -> sp_blockchain::Result<Option<Justification>>;
 }
 
+/// Interface for importing block state that was obtained out of band.
+pub trait StateImporter<Block: BlockT> {
+	/// Import the complete state of a block, e.g. state read from a snapshot. The state root is
+	/// checked against the block header before anything is written.
+	///
+	/// The block must either be a finalized block that was imported without state, or be unknown
+	/// and above the last finalized block. In the latter case, it is inserted as the new best and
+	/// finalized block, so that a fresh node can start from a snapshot.
+	fn import_state(&self, header: Block::Header, storage: Storage) -> sp_blockchain::Result<()>;
+
+	/// Import a chunk of the state of a finalized block that was imported without state, e.g.
+	/// state downloaded from the network during fast sync. `proof` is a range proof, generated
+	/// by `ProofProvider::read_proof_collection` or `ProofProvider::read_child_proof_collection`,
+	/// of the values that follow `start` in the top trie or in the given child trie, whose
+	/// storage key and root are `child`. The proof is checked before anything is written.
+	///
+	/// Returns the values of the chunk and whether it is the last one of its trie.
+	fn import_state_chunk(
+		&self,
+		hash: Block::Hash,
+		child: Option<(&[u8], ChildInfo, Block::Hash)>,
+		proof: StorageProof,
+		start: Option<&[u8]>,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)>;
+
+	/// Complete the import of the state of a finalized block, once all chunks of its top trie
+	/// and child tries have been imported with `import_state_chunk`. `proof` can be any of the
+	/// proofs of the top trie.
+	fn complete_state_import(&self, hash: Block::Hash, proof: StorageProof) -> sp_blockchain::Result<()>;
+}
+
 /// Provide a list of potential uncle headers for a given block.
 pub trait ProvideUncles<Block: BlockT> {
 	/// Gets the uncles of the block with `target_hash` going back `max_generation` ancestors.
@@ -82,6 +82,7 @@ impl ExtensionsFactory for () {
 pub struct ExecutionExtensions<Block: traits::Block> {
 	strategies: ExecutionStrategies,
 	keystore: Option<BareCryptoStorePtr>,
+	offchain_indexing: bool,
 	// FIXME: these two are only RwLock because of https://github.com/paritytech/substrate/issues/4587
 	//        remove when fixed.
 	transaction_pool: RwLock<Option<Weak<dyn sp_transaction_pool::OffchainSubmitTransaction<Block>>>>,
@@ -93,6 +94,7 @@ impl<Block: traits::Block> Default for ExecutionExtensions<Block> {
 		Self {
 			strategies: Default::default(),
 			keystore: None,
+			offchain_indexing: false,
 			transaction_pool: RwLock::new(None),
 			extensions_factory: RwLock::new(Box::new(())),
 		}
@@ -107,7 +109,34 @@ impl<Block: traits::Block> ExecutionExtensions<Block> {
 	) -> Self {
 		let transaction_pool = RwLock::new(None);
 		let extensions_factory = Box::new(());
-		Self { strategies, keystore, extensions_factory: RwLock::new(extensions_factory), transaction_pool }
+		Self {
+			strategies,
+			keystore,
+			offchain_indexing: false,
+			extensions_factory: RwLock::new(extensions_factory),
+			transaction_pool,
+		}
+	}
+
+	/// Enable or disable offchain indexing.
+	///
+	/// When enabled, the offchain database changes made by the runtime while
+	/// importing or constructing blocks are written to the offchain database.
+	pub fn set_offchain_indexing(&mut self, enabled: bool) {
+		self.offchain_indexing = enabled;
+	}
+
+	/// Returns `true` if offchain indexing is enabled for the given execution context.
+	///
+	/// Offchain indexing only ever applies to calls whose changes end up being
+	/// imported as part of a block.
+	pub fn offchain_indexing(&self, context: &ExecutionContext) -> bool {
+		self.offchain_indexing && match context {
+			ExecutionContext::BlockConstruction |
+			ExecutionContext::Syncing |
+			ExecutionContext::Importing => true,
+			ExecutionContext::OffchainCall(_) => false,
+		}
 	}
 
 	/// Get a reference to the execution strategies.
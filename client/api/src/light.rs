@@ -250,6 +250,15 @@ pub trait Storage<Block: BlockT>: AuxStore + HeaderBackend<Block> + HeaderMetada
 		aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
 	) -> ClientResult<()>;
 
+	/// Store a header that has been proven final without importing its ancestors
+	/// (e.g. by a warp sync proof). The header becomes both the best and the last
+	/// finalized block. Refuses to import headers at or below the last finalized block.
+	fn import_warp_target(
+		&self,
+		header: Block::Header,
+		aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	) -> ClientResult<()>;
+
 	/// Set an existing block as new best block.
 	fn set_head(&self, block: BlockId<Block>) -> ClientResult<()>;
 
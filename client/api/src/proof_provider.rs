@@ -68,4 +68,35 @@ pub trait ProofProvider<Block: BlockT> {
 		storage_key: Option<&StorageKey>,
 		key: &StorageKey,
 	) -> sp_blockchain::Result<ChangesProof<Block::Header>>;
+
+	/// Given a `BlockId` iterate over all storage values starting at `start_key` exclusively,
+	/// building proofs until size limit is reached. Returns combined proof and the number of
+	/// collected keys.
+	fn read_proof_collection(
+		&self,
+		id: &BlockId<Block>,
+		start_key: Option<&[u8]>,
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, u32)>;
+
+	/// Given a `BlockId` iterate over all values of a child trie starting at `start_key`
+	/// exclusively, building proofs until size limit is reached. Returns combined proof and the
+	/// number of collected keys.
+	fn read_child_proof_collection(
+		&self,
+		id: &BlockId<Block>,
+		storage_key: &[u8],
+		child_info: ChildInfo,
+		start_key: Option<&[u8]>,
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, u32)>;
+
+	/// Verify read storage proof for a set of keys.
+	/// Returns collected key-value pairs and a flag indicating if iteration is complete.
+	fn verify_range_proof(
+		&self,
+		root: Block::Hash,
+		proof: StorageProof,
+		start_key: Option<&[u8]>,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)>;
 }
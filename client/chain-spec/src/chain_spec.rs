@@ -34,6 +34,7 @@ enum GenesisSource<G> {
 	File(PathBuf),
 	Binary(Cow<'static, [u8]>),
 	Factory(Arc<dyn Fn() -> G + Send + Sync>),
+	Storage(Storage),
 }
 
 impl<G> Clone for GenesisSource<G> {
@@ -42,6 +43,7 @@ impl<G> Clone for GenesisSource<G> {
 			GenesisSource::File(ref path) => GenesisSource::File(path.clone()),
 			GenesisSource::Binary(ref d) => GenesisSource::Binary(d.clone()),
 			GenesisSource::Factory(ref f) => GenesisSource::Factory(f.clone()),
+			GenesisSource::Storage(ref s) => GenesisSource::Storage(s.clone()),
 		}
 	}
 }
@@ -67,6 +69,7 @@ impl<G: RuntimeGenesis> GenesisSource<G> {
 				Ok(genesis.genesis)
 			},
 			GenesisSource::Factory(f) => Ok(Genesis::Runtime(f())),
+			GenesisSource::Storage(storage) => Ok(Genesis::Raw(storage.clone().into())),
 		}
 	}
 }
@@ -122,6 +125,31 @@ struct RawGenesis {
 	children: HashMap<StorageKey, ChildRawStorage>,
 }
 
+impl From<Storage> for RawGenesis {
+	fn from(storage: Storage) -> Self {
+		let top = storage.top.into_iter()
+			.map(|(k, v)| (StorageKey(k), StorageData(v)))
+			.collect();
+		let children = storage.children.into_iter()
+			.map(|(sk, child)| {
+				let info = child.child_info.as_ref();
+				let (info, ci_type) = info.info();
+				(
+					StorageKey(sk),
+					ChildRawStorage {
+						data: child.data.into_iter()
+							.map(|(k, v)| (StorageKey(k), StorageData(v)))
+							.collect(),
+						child_info: info.to_vec(),
+						child_type: ci_type,
+					},
+			)})
+			.collect();
+
+		RawGenesis { top, children }
+	}
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
@@ -243,6 +271,11 @@ impl<G, E> ChainSpec<G, E> {
 			genesis: GenesisSource::Factory(Arc::new(constructor)),
 		}
 	}
+
+	/// Replace the genesis of this spec with the given raw storage.
+	pub fn set_storage(&mut self, storage: Storage) {
+		self.genesis = GenesisSource::Storage(storage);
+	}
 }
 
 impl<G, E: serde::de::DeserializeOwned> ChainSpec<G, E> {
@@ -281,29 +314,7 @@ impl<G: RuntimeGenesis, E: serde::Serialize + Clone> ChainSpec<G, E> {
 
 		};
 		let genesis = match (raw, self.genesis.resolve()?) {
-			(true, Genesis::Runtime(g)) => {
-				let storage = g.build_storage()?;
-				let top = storage.top.into_iter()
-					.map(|(k, v)| (StorageKey(k), StorageData(v)))
-					.collect();
-				let children = storage.children.into_iter()
-					.map(|(sk, child)| {
-						let info = child.child_info.as_ref();
-						let (info, ci_type) = info.info();
-						(
-							StorageKey(sk),
-							ChildRawStorage {
-								data: child.data.into_iter()
-									.map(|(k, v)| (StorageKey(k), StorageData(v)))
-									.collect(),
-								child_info: info.to_vec(),
-								child_type: ci_type,
-							},
-					)})
-					.collect();
-
-				Genesis::Raw(RawGenesis { top, children })
-			},
+			(true, Genesis::Runtime(g)) => Genesis::Raw(g.build_storage()?.into()),
 			(_, genesis) => genesis,
 		};
 		let container = Container {
@@ -359,6 +370,10 @@ where
 	fn as_storage_builder(&self) -> &dyn BuildStorage {
 		self
 	}
+
+	fn set_storage(&mut self, storage: Storage) {
+		ChainSpec::set_storage(self, storage)
+	}
 }
 
 #[cfg(test)]
@@ -410,4 +425,18 @@ mod tests {
 
 		assert_eq!(spec.extensions().my_property, "Test Extension");
 	}
+
+	#[test]
+	fn set_storage_replaces_genesis() {
+		let mut spec = TestSpec::from_json_bytes(Cow::Owned(
+			include_bytes!("../res/chain_spec.json").to_vec()
+		)).unwrap();
+		let mut storage = Storage::default();
+		storage.top.insert(b"key".to_vec(), b"value".to_vec());
+		spec.set_storage(storage.clone());
+
+		assert_eq!(spec.build_storage().unwrap().top, storage.top);
+		let json: json::Value = json::from_str(&spec.as_json(false).unwrap()).unwrap();
+		assert_eq!(json["genesis"]["raw"]["top"]["0x6b6579"], "0x76616c7565");
+	}
 }
@@ -117,6 +117,7 @@ pub use sc_chain_spec_derive::{ChainSpecExtension, ChainSpecGroup};
 
 use serde::{Serialize, de::DeserializeOwned};
 use sp_runtime::BuildStorage;
+use sp_core::storage::Storage;
 use sc_network::config::MultiaddrWithPeerId;
 use sc_telemetry::TelemetryEndpoints;
 
@@ -148,4 +149,6 @@ pub trait ChainSpec: BuildStorage + Send {
 	fn as_json(&self, raw: bool) -> Result<String, String>;
 	/// Return StorageBuilder for this spec.
 	fn as_storage_builder(&self) -> &dyn BuildStorage;
+	/// Replace the genesis of this spec with the given raw storage.
+	fn set_storage(&mut self, storage: Storage);
 }
@@ -122,6 +122,37 @@ impl ExecutionStrategy {
 	}
 }
 
+arg_enum! {
+	/// Syncing mode.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub enum SyncMode {
+		// Full sync. Download and verify all blocks.
+		Full,
+		// Download blocks without executing them. Download latest state with proofs.
+		Fast,
+	}
+}
+
+impl Into<sc_network::config::SyncMode> for SyncMode {
+	fn into(self) -> sc_network::config::SyncMode {
+		match self {
+			SyncMode::Full => sc_network::config::SyncMode::Full,
+			SyncMode::Fast => sc_network::config::SyncMode::Fast,
+		}
+	}
+}
+
+arg_enum! {
+	/// Database backend.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub enum Database {
+		// RocksDB.
+		RocksDb,
+		// Parity-DB, a key-value store optimized for trie nodes.
+		ParityDb,
+	}
+}
+
 /// Default value for the `--execution-syncing` parameter.
 pub const DEFAULT_EXECUTION_SYNCING: ExecutionStrategy = ExecutionStrategy::NativeElseWasm;
 /// Default value for the `--execution-import-block` parameter.
@@ -20,7 +20,10 @@ use crate::params::SharedParams;
 use crate::CliConfiguration;
 use log::info;
 use sc_network::config::build_multiaddr;
-use sc_service::{config::MultiaddrWithPeerId, Configuration};
+use sc_service::{config::MultiaddrWithPeerId, snapshot::SnapshotReader, Configuration};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 /// The `build-spec` command used to build a specification.
@@ -30,6 +33,10 @@ pub struct BuildSpecCmd {
 	#[structopt(long = "raw")]
 	pub raw: bool,
 
+	/// Use the state of a snapshot created by `export-state` as raw genesis storage.
+	#[structopt(long = "snapshot", value_name = "PATH", parse(from_os_str))]
+	pub snapshot: Option<PathBuf>,
+
 	/// Disable adding the default bootnode to the specification.
 	///
 	/// By default the `/ip4/127.0.0.1/tcp/30333/p2p/NODE_PEER_ID` bootnode is added to the
@@ -63,6 +70,11 @@ impl BuildSpecCmd {
 			spec.add_boot_node(addr)
 		}
 
+		if let Some(snapshot) = &self.snapshot {
+			let reader = SnapshotReader::new(io::BufReader::new(fs::File::open(snapshot)?))?;
+			spec.set_storage(reader.read_storage()?);
+		}
+
 		let json = sc_service::chain_ops::build_spec(&*spec, raw_output)?;
 
 		print!("{}", json);
@@ -0,0 +1,84 @@
+// Copyright 2018-2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{BlockNumberOrHash, PruningParams, SharedParams};
+use crate::CliConfiguration;
+use log::info;
+use sc_service::{Configuration, ServiceBuilderCommand};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// The `export-state` command used to write a snapshot of the state of a block.
+#[derive(Debug, StructOpt, Clone)]
+pub struct ExportStateCmd {
+	/// Output file name or stdout if unspecified.
+	#[structopt(parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	/// Hash or number of the block to export the state of.
+	///
+	/// Default is the last finalized block.
+	#[structopt(long = "at", value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+}
+
+impl ExportStateCmd {
+	/// Run the export-state command
+	pub fn run<B, BC, BB>(&self, config: Configuration, builder: B) -> error::Result<()>
+	where
+		B: FnOnce(Configuration) -> Result<BC, sc_service::error::Error>,
+		BC: ServiceBuilderCommand<Block = BB> + Unpin,
+		BB: sp_runtime::traits::Block + Debug,
+		<<<BB as BlockT>::Header as HeaderT>::Number as std::str::FromStr>::Err: std::fmt::Debug,
+		<BB as BlockT>::Hash: std::str::FromStr,
+	{
+		if let Some(path) = config.database.path() {
+			info!("DB path: {}", path.display());
+		}
+
+		let block_id = self.at.as_ref().map(|at| at.parse()).transpose()?;
+
+		let file: Box<dyn io::Write> = match &self.output {
+			Some(filename) => Box::new(io::BufWriter::new(fs::File::create(filename)?)),
+			None => Box::new(io::stdout()),
+		};
+
+		builder(config)?.export_state(block_id, file).map_err(Into::into)
+	}
+}
+
+impl CliConfiguration for ExportStateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+}
@@ -0,0 +1,75 @@
+// Copyright 2018-2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{ImportParams, SharedParams};
+use crate::CliConfiguration;
+use sc_service::{Configuration, ServiceBuilderCommand};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// The `import-state` command used to import the state of a block from a snapshot.
+///
+/// The snapshot block has to be either part of the finalized chain of the local database, e.g.
+/// after syncing headers with `--sync fast`, or unknown and above the last finalized block. In
+/// the latter case it becomes the best and finalized block, so a fresh node can start from it.
+#[derive(Debug, StructOpt, Clone)]
+pub struct ImportStateCmd {
+	/// Input file or stdin if unspecified.
+	#[structopt(parse(from_os_str))]
+	pub input: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl ImportStateCmd {
+	/// Run the import-state command
+	pub fn run<B, BC, BB>(&self, config: Configuration, builder: B) -> error::Result<()>
+	where
+		B: FnOnce(Configuration) -> Result<BC, sc_service::error::Error>,
+		BC: ServiceBuilderCommand<Block = BB> + Unpin,
+		BB: sp_runtime::traits::Block + Debug,
+		<<<BB as BlockT>::Header as HeaderT>::Number as std::str::FromStr>::Err: std::fmt::Debug,
+		<BB as BlockT>::Hash: std::str::FromStr,
+	{
+		let file: Box<dyn io::Read> = match &self.input {
+			Some(filename) => Box::new(io::BufReader::new(fs::File::open(filename)?)),
+			None => Box::new(io::stdin()),
+		};
+
+		builder(config)?.import_state(file).map_err(Into::into)
+	}
+}
+
+impl CliConfiguration for ImportStateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
@@ -0,0 +1,95 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::arg_enums::Database;
+use crate::error;
+use crate::params::SharedParams;
+use crate::CliConfiguration;
+use log::info;
+use sc_service::{config::DatabaseConfig, Configuration};
+use sp_runtime::traits::Block as BlockT;
+use structopt::StructOpt;
+
+/// The `migrate-db` command used to copy the chain data into a database with another backend.
+///
+/// Only databases of archive nodes can be migrated.
+#[derive(Debug, StructOpt, Clone)]
+pub struct MigrateDbCmd {
+	/// Database backend the chain data is currently stored in.
+	///
+	/// Parity-DB can't be iterated, so it is only supported as a migration target.
+	#[structopt(
+		long = "database",
+		value_name = "DB",
+		possible_values = &Database::variants(),
+		case_insensitive = true,
+		default_value = "RocksDb"
+	)]
+	pub database: Database,
+
+	/// Database backend to migrate the chain data to.
+	#[structopt(
+		long = "to",
+		value_name = "DB",
+		possible_values = &Database::variants(),
+		case_insensitive = true
+	)]
+	pub target: Database,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl MigrateDbCmd {
+	/// Run the migrate-db command
+	pub fn run<B: BlockT>(&self, config: Configuration) -> error::Result<()> {
+		if self.database == self.target {
+			return Err("Source and target database backends are the same".into());
+		}
+		if self.database == Database::ParityDb {
+			return Err("Migrating from Parity-DB is not supported, as it can't be iterated".into());
+		}
+
+		let (base_path, cache_size) = match &config.database {
+			DatabaseConfig::Path { path, cache_size } => (path.parent(), *cache_size),
+			DatabaseConfig::ParityDb { path } => (path.parent(), 128),
+			DatabaseConfig::Custom(_) => return Err("Cannot migrate custom database implementation".into()),
+		};
+		let base_path = base_path
+			.ok_or_else(|| error::Error::Input("Invalid database path".into()))?
+			.to_path_buf();
+		let target = self.shared_params.database_config(&base_path, cache_size, self.target);
+
+		if let (Some(from), Some(to)) = (config.database.path(), target.path()) {
+			info!("Migrating {} to {}", from.display(), to.display());
+		}
+		let copied = sc_service::chain_ops::migrate_database::<B>(&config, &target)?;
+		info!("Migration complete, {} values copied", copied);
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for MigrateDbCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database(&self) -> error::Result<Option<Database>> {
+		Ok(Some(self.database))
+	}
+}
@@ -17,18 +17,26 @@
 mod build_spec_cmd;
 mod check_block_cmd;
 mod export_blocks_cmd;
+mod export_state_cmd;
 mod import_blocks_cmd;
+mod import_state_cmd;
+mod migrate_db_cmd;
 mod purge_chain_cmd;
 mod revert_cmd;
 mod runcmd;
+mod try_runtime_cmd;
 
 pub use crate::commands::build_spec_cmd::BuildSpecCmd;
 pub use crate::commands::check_block_cmd::CheckBlockCmd;
 pub use crate::commands::export_blocks_cmd::ExportBlocksCmd;
+pub use crate::commands::export_state_cmd::ExportStateCmd;
 pub use crate::commands::import_blocks_cmd::ImportBlocksCmd;
+pub use crate::commands::import_state_cmd::ImportStateCmd;
+pub use crate::commands::migrate_db_cmd::MigrateDbCmd;
 pub use crate::commands::purge_chain_cmd::PurgeChainCmd;
 pub use crate::commands::revert_cmd::RevertCmd;
 pub use crate::commands::runcmd::RunCmd;
+pub use crate::commands::try_runtime_cmd::TryRuntimeCmd;
 use std::fmt::Debug;
 use structopt::StructOpt;
 
@@ -48,6 +56,12 @@ pub enum Subcommand {
 	/// Import blocks from file.
 	ImportBlocks(ImportBlocksCmd),
 
+	/// Export the state of a block to a snapshot file.
+	ExportState(ExportStateCmd),
+
+	/// Import the state of a block from a snapshot file.
+	ImportState(ImportStateCmd),
+
 	/// Validate a single block.
 	CheckBlock(CheckBlockCmd),
 
@@ -56,6 +70,12 @@ pub enum Subcommand {
 
 	/// Remove the whole chain data.
 	PurgeChain(PurgeChainCmd),
+
+	/// Copy the chain data into a database with another backend.
+	MigrateDb(MigrateDbCmd),
+
+	/// Dry-run a runtime upgrade on top of the state of a block.
+	TryRuntime(TryRuntimeCmd),
 }
 
 // TODO: move to config.rs?
@@ -200,13 +220,20 @@ macro_rules! substrate_cli_subcommands {
 				}
 			}
 
+			fn database(&self) -> $crate::Result<::std::option::Option<$crate::Database>> {
+				match self {
+					$($enum::$variant(cmd) => cmd.database()),*
+				}
+			}
+
 			fn database_config(
 				&self,
 				base_path: &::std::path::PathBuf,
 				cache_size: usize,
+				database: $crate::Database,
 			) -> $crate::Result<::sc_service::config::DatabaseConfig> {
 				match self {
-					$($enum::$variant(cmd) => cmd.database_config(base_path, cache_size)),*
+					$($enum::$variant(cmd) => cmd.database_config(base_path, cache_size, database)),*
 				}
 			}
 
@@ -229,6 +256,12 @@ macro_rules! substrate_cli_subcommands {
 				}
 			}
 
+			fn key_history(&self) -> $crate::Result<bool> {
+				match self {
+					$($enum::$variant(cmd) => cmd.key_history()),*
+				}
+			}
+
 			fn chain_id(&self, is_dev: bool) -> $crate::Result<String> {
 				match self {
 					$($enum::$variant(cmd) => cmd.chain_id(is_dev)),*
@@ -253,6 +286,13 @@ macro_rules! substrate_cli_subcommands {
 				}
 			}
 
+			fn wasm_stack_limit(&self)
+			-> $crate::Result<::sc_service::config::DeterministicStackLimit> {
+				match self {
+					$($enum::$variant(cmd) => cmd.wasm_stack_limit()),*
+				}
+			}
+
 			fn execution_strategies(&self, is_dev: bool)
 			-> $crate::Result<::sc_service::config::ExecutionStrategies> {
 				match self {
@@ -320,6 +360,12 @@ macro_rules! substrate_cli_subcommands {
 				}
 			}
 
+			fn offchain_indexing(&self) -> $crate::Result<bool> {
+				match self {
+					$($enum::$variant(cmd) => cmd.offchain_indexing()),*
+				}
+			}
+
 			fn force_authoring(&self) -> $crate::Result<bool> {
 				match self {
 					$($enum::$variant(cmd) => cmd.force_authoring()),*
@@ -373,5 +419,6 @@ macro_rules! substrate_cli_subcommands {
 }
 
 substrate_cli_subcommands!(
-	Subcommand => BuildSpec, ExportBlocks, ImportBlocks, CheckBlock, Revert, PurgeChain
+	Subcommand => BuildSpec, ExportBlocks, ImportBlocks, ExportState, ImportState, CheckBlock, Revert,
+	PurgeChain, MigrateDb, TryRuntime
 );
@@ -17,7 +17,7 @@
 use crate::error;
 use crate::params::SharedParams;
 use crate::CliConfiguration;
-use sc_service::{config::DatabaseConfig, Configuration};
+use sc_service::Configuration;
 use std::fmt::Debug;
 use std::fs;
 use std::io::{self, Write};
@@ -38,9 +38,9 @@ pub struct PurgeChainCmd {
 impl PurgeChainCmd {
 	/// Run the purge command
 	pub fn run(&self, config: Configuration) -> error::Result<()> {
-		let db_path = match &config.database {
-			DatabaseConfig::Path { path, .. } => path,
-			_ => {
+		let db_path = match config.database.path() {
+			Some(path) => path,
+			None => {
 				eprintln!("Cannot purge custom database implementation");
 				return Ok(());
 			}
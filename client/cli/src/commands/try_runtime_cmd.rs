@@ -0,0 +1,100 @@
+// Copyright 2018-2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{BlockNumberOrHash, ImportParams, SharedParams};
+use crate::CliConfiguration;
+use log::info;
+use sc_service::{Configuration, ServiceBuilderCommand};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::fmt::Debug;
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// The `try-runtime` command used to dry-run a runtime upgrade against the state of a block.
+///
+/// The new runtime has to implement the `TryRuntime` runtime api, which runtimes usually only do
+/// when built with their `try-runtime` feature. Nothing is written to the database.
+#[derive(Debug, StructOpt, Clone)]
+pub struct TryRuntimeCmd {
+	/// Hash or number of the block whose state the upgrade is applied on.
+	#[structopt(
+		long = "at",
+		value_name = "HASH or NUMBER",
+		required_unless = "snapshot",
+		conflicts_with = "snapshot",
+	)]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Apply the upgrade on the state read from a snapshot created by `export-state`.
+	#[structopt(long = "snapshot", value_name = "PATH", parse(from_os_str))]
+	pub snapshot: Option<PathBuf>,
+
+	/// Path to the wasm blob of the new runtime.
+	#[structopt(long = "runtime", value_name = "PATH", parse(from_os_str))]
+	pub runtime: PathBuf,
+
+	/// Also execute the child of the given block on top of the upgraded state.
+	#[structopt(long = "execute-block")]
+	pub execute_block: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl TryRuntimeCmd {
+	/// Run the try-runtime command
+	pub fn run<B, BC, BB>(&self, config: Configuration, builder: B) -> error::Result<()>
+	where
+		B: FnOnce(Configuration) -> Result<BC, sc_service::error::Error>,
+		BC: ServiceBuilderCommand<Block = BB> + Unpin,
+		BB: sp_runtime::traits::Block + Debug,
+		<<<BB as BlockT>::Header as HeaderT>::Number as std::str::FromStr>::Err: std::fmt::Debug,
+		<BB as BlockT>::Hash: std::str::FromStr,
+	{
+		let code = fs::read(&self.runtime)?;
+
+		let start = std::time::Instant::now();
+		let builder = builder(config)?;
+		match (&self.at, &self.snapshot) {
+			(_, Some(snapshot)) => {
+				let input = fs::File::open(snapshot)?;
+				builder.try_runtime_snapshot(input, code, self.execute_block)?;
+			},
+			(Some(at), None) => builder.try_runtime(at.parse()?, code, self.execute_block)?,
+			(None, None) => return Err(error::Error::Input("Either `--at` or `--snapshot` is required".into())),
+		}
+		info!("Completed in {} ms.", start.elapsed().as_millis());
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for TryRuntimeCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
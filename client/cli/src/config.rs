@@ -16,6 +16,7 @@
 
 //! Configuration trait for a CLI based on substrate
 
+use crate::arg_enums::Database;
 use crate::error::Result;
 use crate::{
 	init_logger, ImportParams, KeystoreParams, NetworkParams, NodeKeyParams,
@@ -24,9 +25,9 @@ use crate::{
 use app_dirs::{AppDataType, AppInfo};
 use names::{Generator, Name};
 use sc_service::config::{
-	Configuration, DatabaseConfig, ExecutionStrategies, ExtTransport, KeystoreConfig,
-	NetworkConfiguration, NodeKeyConfig, PrometheusConfig, PruningMode, Role, TelemetryEndpoints,
-	TransactionPoolOptions, WasmExecutionMethod,
+	Configuration, DatabaseConfig, DeterministicStackLimit, ExecutionStrategies, ExtTransport,
+	KeystoreConfig, NetworkConfiguration, NodeKeyConfig, PrometheusConfig, PruningMode, Role,
+	TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
 };
 use sc_service::{ChainSpec, TracingReceiver};
 use std::future::Future;
@@ -41,6 +42,9 @@ pub(crate) const NODE_NAME_MAX_LENGTH: usize = 32;
 /// default sub directory to store network config
 pub(crate) const DEFAULT_NETWORK_CONFIG_PATH: &'static str = "network";
 
+/// default sub directory to cache compiled wasm runtimes
+pub(crate) const DEFAULT_WASM_RUNTIME_CACHE_PATH: &'static str = "wasm-cache";
+
 /// A trait that allows converting an object to a Configuration
 pub trait CliConfiguration: Sized {
 	/// Get the SharedParams for this object
@@ -152,11 +156,23 @@ pub trait CliConfiguration: Sized {
 			.unwrap_or(Default::default()))
 	}
 
+	/// Get the database backend variant.
+	///
+	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its `None`.
+	fn database(&self) -> Result<Option<Database>> {
+		Ok(self.import_params().map(|x| x.database()))
+	}
+
 	/// Get the database configuration.
 	///
 	/// By default this is retrieved from `SharedParams`
-	fn database_config(&self, base_path: &PathBuf, cache_size: usize) -> Result<DatabaseConfig> {
-		Ok(self.shared_params().database_config(base_path, cache_size))
+	fn database_config(
+		&self,
+		base_path: &PathBuf,
+		cache_size: usize,
+		database: Database,
+	) -> Result<DatabaseConfig> {
+		Ok(self.shared_params().database_config(base_path, cache_size, database))
 	}
 
 	/// Get the state cache size.
@@ -185,6 +201,13 @@ pub trait CliConfiguration: Sized {
 			.unwrap_or(Ok(Default::default()))
 	}
 
+	/// Returns `true` if the index of storage key changes should be maintained.
+	///
+	/// By default this is retrieved from `PruningParams` if it is available. Otherwise its `false`.
+	fn key_history(&self) -> Result<bool> {
+		Ok(self.pruning_params().map(|x| x.key_history).unwrap_or_default())
+	}
+
 	/// Get the chain ID (string).
 	///
 	/// By default this is retrieved from `SharedParams`.
@@ -209,6 +232,16 @@ pub trait CliConfiguration: Sized {
 			.unwrap_or(Default::default()))
 	}
 
+	/// Get the limit of the logical stack height of wasm runtimes.
+	///
+	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its
+	/// `DeterministicStackLimit::default()`.
+	fn wasm_stack_limit(&self) -> Result<DeterministicStackLimit> {
+		Ok(self.import_params()
+			.map(|x| x.wasm_stack_limit())
+			.unwrap_or_default())
+	}
+
 	/// Get the execution strategies.
 	///
 	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its
@@ -285,6 +318,15 @@ pub trait CliConfiguration: Sized {
 		Ok(Default::default())
 	}
 
+	/// Returns `Ok(true)` if offchain indexing should be enabled
+	///
+	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its `false`.
+	fn offchain_indexing(&self) -> Result<bool> {
+		Ok(self.import_params()
+			.map(|x| x.offchain_indexing())
+			.unwrap_or(Default::default()))
+	}
+
 	/// Returns `Ok(true)` if authoring should be forced
 	///
 	/// By default this is `false`.
@@ -376,6 +418,7 @@ pub trait CliConfiguration: Sized {
 		let net_config_dir = config_dir.join(DEFAULT_NETWORK_CONFIG_PATH);
 		let client_id = C::client_id();
 		let database_cache_size = self.database_cache_size()?.unwrap_or(128);
+		let database = self.database()?.unwrap_or(Database::RocksDb);
 		let node_key = self.node_key(&net_config_dir)?;
 		let role = self.role(is_dev)?;
 		let max_runtime_instances = self.max_runtime_instances()?.unwrap_or(8);
@@ -394,10 +437,11 @@ pub trait CliConfiguration: Sized {
 				node_key,
 			)?,
 			keystore: self.keystore_config(&config_dir)?,
-			database: self.database_config(&config_dir, database_cache_size)?,
+			database: self.database_config(&config_dir, database_cache_size, database)?,
 			state_cache_size: self.state_cache_size()?,
 			state_cache_child_ratio: self.state_cache_child_ratio()?,
 			pruning: self.pruning(is_dev, &role)?,
+			key_history: self.key_history()?,
 			wasm_method: self.wasm_method()?,
 			execution_strategies: self.execution_strategies(is_dev)?,
 			rpc_http: self.rpc_http()?,
@@ -409,6 +453,7 @@ pub trait CliConfiguration: Sized {
 			telemetry_external_transport: self.telemetry_external_transport()?,
 			default_heap_pages: self.default_heap_pages()?,
 			offchain_worker: self.offchain_worker(&role)?,
+			offchain_indexing: self.offchain_indexing()?,
 			force_authoring: self.force_authoring()?,
 			disable_grandpa: self.disable_grandpa()?,
 			dev_key_seed: self.dev_key_seed(is_dev)?,
@@ -416,6 +461,8 @@ pub trait CliConfiguration: Sized {
 			tracing_receiver: self.tracing_receiver()?,
 			chain_spec,
 			max_runtime_instances,
+			wasm_runtime_cache_path: Some(config_dir.join(DEFAULT_WASM_RUNTIME_CACHE_PATH)),
+			wasm_stack_limit: self.wasm_stack_limit()?,
 			announce_block: self.announce_block()?,
 			role,
 		})
@@ -15,7 +15,7 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::arg_enums::{
-	ExecutionStrategy, TracingReceiver, WasmExecutionMethod, DEFAULT_EXECUTION_BLOCK_CONSTRUCTION,
+	Database, ExecutionStrategy, TracingReceiver, WasmExecutionMethod, DEFAULT_EXECUTION_BLOCK_CONSTRUCTION,
 	DEFAULT_EXECUTION_IMPORT_BLOCK, DEFAULT_EXECUTION_OFFCHAIN_WORKER, DEFAULT_EXECUTION_OTHER,
 	DEFAULT_EXECUTION_SYNCING,
 };
@@ -50,10 +50,34 @@ pub struct ImportParams {
 	)]
 	pub wasm_method: WasmExecutionMethod,
 
+	/// Limit of the logical stack height of Wasm runtime code, in number of values.
+	///
+	/// Calls exceeding the limit trap. All nodes of a network must use the same limit, as they
+	/// would otherwise disagree about the outcome of deep calls. Defaults to 65536.
+	#[structopt(long = "wasm-stack-limit", value_name = "VALUES")]
+	pub wasm_stack_limit: Option<u32>,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub execution_strategies: ExecutionStrategiesParams,
 
+	/// Enable offchain indexing.
+	///
+	/// Allows the runtime to write directly to the offchain database while
+	/// importing blocks.
+	#[structopt(long = "enable-offchain-indexing")]
+	pub enable_offchain_indexing: bool,
+
+	/// Select database backend to use.
+	#[structopt(
+		long = "database",
+		value_name = "DB",
+		possible_values = &Database::variants(),
+		case_insensitive = true,
+		default_value = "RocksDb"
+	)]
+	pub database: Database,
+
 	/// Limit the memory the database cache can use.
 	#[structopt(long = "db-cache", value_name = "MiB")]
 	pub database_cache_size: Option<usize>,
@@ -93,11 +117,23 @@ impl ImportParams {
 		self.state_cache_size
 	}
 
+	/// Whether offchain indexing is enabled.
+	pub fn offchain_indexing(&self) -> bool {
+		self.enable_offchain_indexing
+	}
+
 	/// Get the WASM execution method from the parameters
 	pub fn wasm_method(&self) -> sc_service::config::WasmExecutionMethod {
 		self.wasm_method.into()
 	}
 
+	/// Get the limit of the logical stack height of Wasm runtime code from the parameters
+	pub fn wasm_stack_limit(&self) -> sc_service::config::DeterministicStackLimit {
+		self.wasm_stack_limit
+			.map(|logical_max| sc_service::config::DeterministicStackLimit { logical_max })
+			.unwrap_or_default()
+	}
+
 	/// Get execution strategies for the parameters
 	pub fn execution_strategies(
 		&self,
@@ -132,6 +168,11 @@ impl ImportParams {
 	pub fn database_cache_size(&self) -> Option<usize> {
 		self.database_cache_size
 	}
+
+	/// Database backend to use.
+	pub fn database(&self) -> Database {
+		self.database
+	}
 }
 
 /// Execution strategies parameters.
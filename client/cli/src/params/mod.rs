@@ -22,6 +22,8 @@ mod pruning_params;
 mod shared_params;
 mod transaction_pool_params;
 
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::Block as BlockT;
 use std::fmt::Debug;
 use std::str::FromStr;
 
@@ -67,3 +69,35 @@ impl BlockNumber {
 			.map_err(|e| format!("BlockNumber: {} parsing failed because of {:?}", self.0, e))
 	}
 }
+
+/// Wrapper type of `String` that holds either a block hash or a block number.
+#[derive(Debug, Clone)]
+pub struct BlockNumberOrHash(String);
+
+impl FromStr for BlockNumberOrHash {
+	type Err = String;
+
+	fn from_str(block_number_or_hash: &str) -> Result<Self, Self::Err> {
+		Ok(Self(block_number_or_hash.to_owned()))
+	}
+}
+
+impl BlockNumberOrHash {
+	/// Parse the inner value as a `BlockId`.
+	///
+	/// Values that can be parsed as a hash (with or without `0x` prefix) take precedence over
+	/// block numbers.
+	pub fn parse<B: BlockT>(&self) -> Result<BlockId<B>, String>
+	where
+		B::Hash: FromStr,
+	{
+		let input = if self.0.starts_with("0x") { &self.0[2..] } else { &self.0[..] };
+		match FromStr::from_str(input) {
+			Ok(hash) => Ok(BlockId::hash(hash)),
+			Err(_) => self.0
+				.parse::<u32>()
+				.map(|n| BlockId::number(n.into()))
+				.map_err(|_| format!("Invalid block hash or number: {}", self.0)),
+		}
+	}
+}
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::arg_enums::SyncMode;
 use crate::params::node_key_params::NodeKeyParams;
 use sc_network::{
 	config::{NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, TransportConfig},
@@ -85,6 +86,20 @@ pub struct NetworkParams {
 	)]
 	pub max_parallel_downloads: u32,
 
+	/// Blockchain syncing mode.
+	///
+	/// - `Full`: Download and validate full blockchain history.
+	/// - `Fast`: Download blocks without executing them, then download the state of the
+	///   latest finalized block with proofs and continue with full sync from there.
+	#[structopt(
+		long = "sync",
+		value_name = "SYNC_MODE",
+		possible_values = &SyncMode::variants(),
+		case_insensitive = true,
+		default_value = "Full"
+	)]
+	pub sync: SyncMode,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub node_key_params: NodeKeyParams,
@@ -138,6 +153,8 @@ impl NetworkParams {
 				use_yamux_flow_control: self.use_yamux_flow_control,
 			},
 			max_parallel_downloads: self.max_parallel_downloads,
+			sync_mode: self.sync.into(),
+			request_response_protocols: Vec::new(),
 		}
 	}
 }
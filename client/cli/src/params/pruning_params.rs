@@ -28,6 +28,13 @@ pub struct PruningParams {
 	/// 256 blocks.
 	#[structopt(long = "pruning", value_name = "PRUNING_MODE")]
 	pub pruning: Option<String>,
+
+	/// Keep an index of the blocks at which each storage key has been changed.
+	///
+	/// The index is used by the `state_queryStorageHistory` RPC, which reads the
+	/// state of the indexed blocks and is therefore meant for archive nodes.
+	#[structopt(long = "key-history")]
+	pub key_history: bool,
 }
 
 impl PruningParams {
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::arg_enums::Database;
 use sc_service::config::DatabaseConfig;
 use std::path::PathBuf;
 use structopt::StructOpt;
@@ -21,6 +22,9 @@ use structopt::StructOpt;
 /// default sub directory to store database
 const DEFAULT_DB_CONFIG_PATH: &'static str = "db";
 
+/// default sub directory to store a Parity-DB database
+const DEFAULT_PARITY_DB_CONFIG_PATH: &'static str = "paritydb";
+
 /// Shared parameters used by all `CoreParams`.
 #[derive(Debug, StructOpt, Clone)]
 pub struct SharedParams {
@@ -79,10 +83,16 @@ impl SharedParams {
 		&self,
 		base_path: &PathBuf,
 		cache_size: usize,
+		database: Database,
 	) -> DatabaseConfig {
-		DatabaseConfig::Path {
-			path: base_path.join(DEFAULT_DB_CONFIG_PATH),
-			cache_size,
+		match database {
+			Database::RocksDb => DatabaseConfig::Path {
+				path: base_path.join(DEFAULT_DB_CONFIG_PATH),
+				cache_size,
+			},
+			Database::ParityDb => DatabaseConfig::ParityDb {
+				path: base_path.join(DEFAULT_PARITY_DB_CONFIG_PATH),
+			},
 		}
 	}
 
@@ -15,6 +15,7 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 use sc_service::config::TransactionPoolOptions;
+use std::time::Duration;
 use structopt::StructOpt;
 
 /// Parameters used to create the pool configuration.
@@ -27,6 +28,14 @@ pub struct TransactionPoolParams {
 	/// Maximum number of kilobytes of all transactions stored in the pool.
 	#[structopt(long = "pool-kbytes", value_name = "COUNT", default_value = "20480")]
 	pub pool_kbytes: usize,
+
+	/// Maximum number of transactions of a single sender in the transaction pool.
+	#[structopt(long = "pool-sender-limit", value_name = "COUNT", default_value = "512")]
+	pub pool_sender_limit: usize,
+
+	/// Number of seconds transactions evicted from the pool are banned from re-entering it.
+	#[structopt(long = "tx-ban-seconds", value_name = "SECONDS", default_value = "1800")]
+	pub tx_ban_seconds: u64,
 }
 
 impl TransactionPoolParams {
@@ -43,6 +52,11 @@ impl TransactionPoolParams {
 		opts.future.count = self.pool_limit / factor;
 		opts.future.total_bytes = self.pool_kbytes * 1024 / factor;
 
+		// per-sender limit
+		opts.sender.count = self.pool_sender_limit;
+
+		opts.ban_time = Duration::from_secs(self.tx_ban_seconds);
+
 		opts
 	}
 }
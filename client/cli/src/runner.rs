@@ -177,11 +177,15 @@ impl<C: SubstrateCli> Runner<C> {
 			Subcommand::ImportBlocks(cmd) => {
 				run_until_exit(self.tokio_runtime, cmd.run(self.config, builder))
 			}
+			Subcommand::ExportState(cmd) => cmd.run(self.config, builder),
+			Subcommand::ImportState(cmd) => cmd.run(self.config, builder),
 			Subcommand::CheckBlock(cmd) => {
 				run_until_exit(self.tokio_runtime, cmd.run(self.config, builder))
 			}
 			Subcommand::Revert(cmd) => cmd.run(self.config, builder),
 			Subcommand::PurgeChain(cmd) => cmd.run(self.config),
+			Subcommand::MigrateDb(cmd) => cmd.run::<BB>(self.config),
+			Subcommand::TryRuntime(cmd) => cmd.run(self.config, builder),
 		}
 	}
 
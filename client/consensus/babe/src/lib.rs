@@ -687,6 +687,96 @@ impl<Block, Client> BabeVerifier<Block, Client>
 	}
 }
 
+impl<Block, Client> BabeVerifier<Block, Client>
+	where
+		Block: BlockT,
+		Client: HeaderBackend<Block> + ProvideRuntimeApi<Block> + AuxStore,
+		Client::Api: BabeApi<Block, Error = sp_blockchain::Error>,
+{
+	fn check_and_report_equivocation(
+		&self,
+		slot_now: SlotNumber,
+		slot: SlotNumber,
+		header: &Block::Header,
+		author: &AuthorityId,
+	) -> Result<(), Error<Block>> {
+		// check if authorship of this header is an equivocation and return a proof if so.
+		let equivocation_proof = match check_equivocation(
+			&*self.client,
+			slot_now,
+			slot,
+			header,
+			author,
+		).map_err(Error::Client)? {
+			Some(proof) => proof,
+			None => return Ok(()),
+		};
+
+		info!(
+			"Slot author {:?} is equivocating at slot {} with headers {:?} and {:?}",
+			author,
+			slot,
+			equivocation_proof.fst_header().hash(),
+			equivocation_proof.snd_header().hash(),
+		);
+
+		// we submit the equivocation report at the best block, any block that
+		// we import on top of it will be able to include it.
+		let best_id = BlockId::Hash(self.client.info().best_hash);
+
+		// generate a key ownership proof. we start by trying to generate the
+		// key ownership proof at the parent of the equivocating header, this
+		// will make sure that proof generation is successful since it happens
+		// during the on-going session (i.e. session keys are available in the
+		// state to be able to generate the proof). this might fail if the
+		// equivocation happens on the first block of the session, in which case
+		// its parent would be on the previous session. if generation on the
+		// parent header fails we try with best block as well.
+		let generate_key_owner_proof = |block_id: &BlockId<Block>| {
+			self.client
+				.runtime_api()
+				.generate_key_ownership_proof(block_id, slot, author.clone())
+				.map_err(Error::Client)
+		};
+
+		let parent_id = BlockId::Hash(*header.parent_hash());
+		let key_owner_proof = match generate_key_owner_proof(&parent_id)? {
+			Some(proof) => proof,
+			None => match generate_key_owner_proof(&best_id)? {
+				Some(proof) => proof,
+				None => {
+					debug!(target: "babe", "Equivocation offender is not part of the authority set.");
+					return Ok(());
+				}
+			},
+		};
+
+		let equivocation_proof = sp_consensus_babe::EquivocationProof {
+			offender: author.clone(),
+			slot_number: slot,
+			first_header: equivocation_proof.fst_header().clone(),
+			second_header: equivocation_proof.snd_header().clone(),
+		};
+
+		// submit equivocation report at best block.
+		let submitted = self.client
+			.runtime_api()
+			.submit_report_equivocation_unsigned_extrinsic(
+				&best_id,
+				equivocation_proof,
+				key_owner_proof,
+			)
+			.map_err(Error::Client)?;
+
+		match submitted {
+			Some(()) => info!(target: "babe", "Submitted equivocation report for author {:?}", author),
+			None => debug!(target: "babe", "Equivocation reporting is disabled in the runtime."),
+		}
+
+		Ok(())
+	}
+}
+
 impl<Block, Client> Verifier<Block> for BabeVerifier<Block, Client> where
 	Block: BlockT,
 	Client: HeaderMetadata<Block, Error = sp_blockchain::Error> + HeaderBackend<Block> + ProvideRuntimeApi<Block>
@@ -759,21 +849,15 @@ impl<Block, Client> Verifier<Block> for BabeVerifier<Block, Client> where
 				let author = verified_info.author;
 
 				// the header is valid but let's check if there was something else already
-				// proposed at the same slot by the given author
-				if let Some(equivocation_proof) = check_equivocation(
-					&*self.client,
+				// proposed at the same slot by the given author. if there was, we will
+				// report the equivocation to the runtime.
+				if let Err(err) = self.check_and_report_equivocation(
 					slot_now,
-					babe_pre_digest.slot_number(),
+					slot_number,
 					&header,
 					&author,
-				).map_err(|e| e.to_string())? {
-					info!(
-						"Slot author {:?} is equivocating at slot {} with headers {:?} and {:?}",
-						author,
-						babe_pre_digest.slot_number(),
-						equivocation_proof.fst_header().hash(),
-						equivocation_proof.snd_header().hash(),
-					);
+				) {
+					warn!(target: "babe", "Error checking/reporting BABE equivocation: {:?}", err);
 				}
 
 				// if the body is passed through, we need to use the runtime
@@ -905,7 +989,15 @@ impl<Block, Client, Inner> BlockImport<Block> for BabeBlockImport<Block, Client,
 		// early exit if block already in chain, otherwise the check for
 		// epoch changes will error when trying to re-import an epoch change
 		match self.client.status(BlockId::Hash(hash)) {
-			Ok(sp_blockchain::BlockStatus::InChain) => return Ok(ImportResult::AlreadyInChain),
+			Ok(sp_blockchain::BlockStatus::InChain) if !block.import_existing =>
+				return Ok(ImportResult::AlreadyInChain),
+			Ok(sp_blockchain::BlockStatus::InChain) => {
+				// epoch changes and weight were recorded when the block was first imported
+				// (e.g. without state during fast sync), only execute it now.
+				block.take_intermediate::<BabeIntermediate<Block>>(INTERMEDIATE_KEY)?;
+				block.fork_choice = Some(ForkChoiceStrategy::Custom(false));
+				return self.inner.import_block(block, new_cache).map_err(Into::into)
+			},
 			Ok(sp_blockchain::BlockStatus::Unknown) => {},
 			Err(e) => return Err(ConsensusError::ClientImport(e.to_string())),
 		}
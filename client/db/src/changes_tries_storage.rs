@@ -19,7 +19,8 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use hash_db::Prefix;
-use kvdb::{KeyValueDB, DBTransaction};
+use kvdb::DBTransaction;
+use crate::Database;
 use codec::{Decode, Encode};
 use parking_lot::RwLock;
 use sp_blockchain::{Error as ClientError, Result as ClientResult};
@@ -76,7 +77,7 @@ impl<Block: BlockT> From<DbCacheTransactionOps<Block>> for DbChangesTrieStorageT
 /// Stores all tries in separate DB column.
 /// Lock order: meta, tries_meta, cache, build_cache.
 pub struct DbChangesTrieStorage<Block: BlockT> {
-	db: Arc<dyn KeyValueDB>,
+	db: Arc<dyn Database>,
 	meta_column: u32,
 	changes_tries_column: u32,
 	key_lookup_column: u32,
@@ -111,7 +112,7 @@ struct ChangesTriesMeta<Block: BlockT> {
 impl<Block: BlockT> DbChangesTrieStorage<Block> {
 	/// Create new changes trie storage.
 	pub fn new(
-		db: Arc<dyn KeyValueDB>,
+		db: Arc<dyn Database>,
 		meta_column: u32,
 		changes_tries_column: u32,
 		key_lookup_column: u32,
@@ -494,7 +495,7 @@ where
 
 /// Read changes tries metadata from database.
 fn read_tries_meta<Block: BlockT>(
-	db: &dyn KeyValueDB,
+	db: &dyn Database,
 	meta_column: u32,
 ) -> ClientResult<ChangesTriesMeta<Block>> {
 	match db.get(meta_column, meta_keys::CHANGES_TRIES_META).map_err(db_err)? {
@@ -0,0 +1,85 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The key-value database abstraction used by the client backend.
+//!
+//! Every `kvdb::KeyValueDB` (RocksDB, memorydb, ...) is a `Database`. Other
+//! storage engines are plugged in by implementing this trait directly, see
+//! the `parity_db` module.
+
+use std::io;
+use kvdb::{KeyValueDB, DBTransaction, DBValue, IoStats, IoStatsKind};
+
+/// Iterator over the key-value pairs of a single column.
+pub type DatabaseIter<'a> = Box<dyn Iterator<Item = (Box<[u8]>, Box<[u8]>)> + 'a>;
+
+/// Key-value database with a fixed number of columns.
+pub trait Database: Send + Sync {
+	/// Get a value by key.
+	fn get(&self, col: u32, key: &[u8]) -> io::Result<Option<DBValue>>;
+
+	/// Atomically commit a transaction.
+	fn write(&self, transaction: DBTransaction) -> io::Result<()>;
+
+	/// Iterate over all key-value pairs of a column.
+	///
+	/// Iteration is only needed for maintenance (migrations, test helpers), so
+	/// backends that can't enumerate their keys return an error.
+	fn iter<'a>(&'a self, col: u32) -> io::Result<DatabaseIter<'a>>;
+
+	/// Iterate over the key-value pairs of a column whose keys start with `prefix`,
+	/// in ascending key order.
+	///
+	/// Backends that can't enumerate their keys return an error.
+	fn iter_from_prefix<'a>(&'a self, col: u32, prefix: &'a [u8]) -> io::Result<DatabaseIter<'a>>;
+
+	/// Query statistics.
+	///
+	/// Backends that don't collect statistics return empty ones.
+	fn io_stats(&self, _kind: IoStatsKind) -> IoStats {
+		IoStats::empty()
+	}
+
+	/// Whether the state column keeps reference counts of its values.
+	///
+	/// Such backends only remove a value once it has been deleted as many times as
+	/// it was inserted, which allows state pruning without a journal of deleted keys.
+	fn supports_ref_counting(&self) -> bool {
+		false
+	}
+}
+
+impl<T: KeyValueDB + ?Sized> Database for T {
+	fn get(&self, col: u32, key: &[u8]) -> io::Result<Option<DBValue>> {
+		KeyValueDB::get(self, col, key)
+	}
+
+	fn write(&self, transaction: DBTransaction) -> io::Result<()> {
+		KeyValueDB::write(self, transaction)
+	}
+
+	fn iter<'a>(&'a self, col: u32) -> io::Result<DatabaseIter<'a>> {
+		Ok(KeyValueDB::iter(self, col))
+	}
+
+	fn iter_from_prefix<'a>(&'a self, col: u32, prefix: &'a [u8]) -> io::Result<DatabaseIter<'a>> {
+		Ok(KeyValueDB::iter_from_prefix(self, col, prefix))
+	}
+
+	fn io_stats(&self, kind: IoStatsKind) -> IoStats {
+		KeyValueDB::io_stats(self, kind)
+	}
+}
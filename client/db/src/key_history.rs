@@ -0,0 +1,107 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Index of the blocks at which top-level storage keys have been changed.
+//!
+//! The index has an empty entry for every changed key and block, keyed by the
+//! length-prefixed storage key followed by the big-endian block number. Noting a
+//! change is a single write, and the entries of a key are read in ascending block
+//! order with a prefix scan. Blocks are noted when imported, so the index also
+//! contains the numbers of blocks that were later retracted or reverted. Users of
+//! the index must compare the values at the canonical blocks to filter these out.
+
+use kvdb::DBTransaction;
+use codec::Encode;
+use crate::Database;
+
+/// The prefix of the index entries of `key`.
+///
+/// The length prefix keeps the entries of keys that are prefixes of each other apart.
+fn history_prefix(key: &[u8]) -> Vec<u8> {
+	key.encode()
+}
+
+/// Returns the numbers of the blocks within [first; last] at which `key` has been changed,
+/// in ascending order.
+pub fn read_key_history(
+	db: &dyn Database,
+	column: u32,
+	key: &[u8],
+	first: u64,
+	last: u64,
+) -> sp_blockchain::Result<Vec<u64>> {
+	let prefix = history_prefix(key);
+	let entries = db.iter_from_prefix(column, &prefix)
+		.map_err(|_| sp_blockchain::Error::Backend("Error reading value from database".into()))?;
+
+	let mut history = Vec::new();
+	for (entry, _) in entries {
+		let mut number = [0u8; 8];
+		if entry.len() != prefix.len() + number.len() {
+			return Err(sp_blockchain::Error::Backend("Error decoding key history".into()));
+		}
+		number.copy_from_slice(&entry[prefix.len()..]);
+		let number = u64::from_be_bytes(number);
+		if number > last {
+			break;
+		}
+		if number >= first {
+			history.push(number);
+		}
+	}
+	Ok(history)
+}
+
+/// Note in the transaction that the given `keys` have been changed at block `number`.
+pub fn note_key_changes<'a>(
+	tx: &mut DBTransaction,
+	column: u32,
+	number: u64,
+	keys: impl IntoIterator<Item = &'a [u8]>,
+) {
+	for key in keys {
+		let mut entry = history_prefix(key);
+		entry.extend_from_slice(&number.to_be_bytes());
+		tx.put(column, &entry, &[]);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn key_history_is_sorted_and_deduplicated() {
+		let db = ::kvdb_memorydb::create(1);
+
+		for (number, keys) in &[
+			(1u64, vec![&b"a"[..], b"ab"]),
+			(3, vec![b"a"]),
+			(2, vec![b"a"]),
+			(3, vec![b"a"]),
+			(256, vec![b"a"]),
+		] {
+			let mut tx = DBTransaction::new();
+			note_key_changes(&mut tx, 0, *number, keys.iter().cloned());
+			db.write(tx).unwrap();
+		}
+
+		assert_eq!(read_key_history(&db, 0, b"a", 0, 1000).unwrap(), vec![1, 2, 3, 256]);
+		assert_eq!(read_key_history(&db, 0, b"a", 2, 3).unwrap(), vec![2, 3]);
+		assert_eq!(read_key_history(&db, 0, b"ab", 0, 1000).unwrap(), vec![1]);
+		assert!(read_key_history(&db, 0, b"b", 0, 1000).unwrap().is_empty());
+	}
+}
@@ -35,6 +35,11 @@ pub mod bench;
 mod children;
 mod cache;
 mod changes_tries_storage;
+mod database;
+mod key_history;
+mod migrate;
+#[cfg(feature = "with-parity-db")]
+mod parity_db;
 mod storage_cache;
 #[cfg(any(feature = "kvdb-rocksdb", test))]
 mod upgrade;
@@ -56,8 +61,8 @@ use sp_blockchain::{
 	well_known_cache_keys, HeaderBackend,
 };
 use codec::{Decode, Encode};
-use hash_db::Prefix;
-use kvdb::{KeyValueDB, DBTransaction};
+use hash_db::{Prefix, EMPTY_PREFIX};
+use kvdb::DBTransaction;
 use sp_trie::{MemoryDB, PrefixedMemoryDB, prefixed_key};
 use parking_lot::RwLock;
 use sp_core::{ChangesTrieConfiguration, traits::CodeExecutor};
@@ -68,14 +73,15 @@ use sp_runtime::{
 };
 use sp_runtime::traits::{
 	Block as BlockT, Header as HeaderT, NumberFor, Zero, One, SaturatedConversion, HashFor,
+	Hash as HashT,
 };
 use sc_executor::RuntimeInfo;
 use sp_state_machine::{
 	DBValue, ChangesTrieTransaction, ChangesTrieCacheAction, UsageInfo as StateUsageInfo,
-	StorageCollection, ChildStorageCollection,
-	backend::Backend as StateBackend, StateMachineStats,
+	StorageCollection, ChildStorageCollection, StorageProof,
+	backend::Backend as StateBackend, StateMachineStats, read_range_proof_check_with_nodes,
 };
-use crate::utils::{DatabaseType, Meta, db_err, meta_keys, read_db, read_meta};
+use crate::utils::{Meta, db_err, meta_keys, read_db, read_meta};
 use crate::changes_tries_storage::{DbChangesTrieStorage, DbChangesTrieStorageTransaction};
 use sc_client::leaves::{LeafSet, FinalizationDisplaced};
 use sc_state_db::StateDb;
@@ -105,6 +111,9 @@ pub type DbState<B> = sp_state_machine::TrieBackend<
 
 /// Re-export the KVDB trait so that one can pass an implementation of it.
 pub use kvdb;
+pub use crate::database::{Database, DatabaseIter};
+pub use crate::migrate::migrate_database;
+pub use crate::utils::DatabaseType;
 
 /// A reference tracking state.
 ///
@@ -274,6 +283,8 @@ pub struct DatabaseSettings {
 	pub state_cache_child_ratio: Option<(usize, usize)>,
 	/// Pruning mode.
 	pub pruning: PruningMode,
+	/// Maintain an index of the blocks at which each storage key has been changed.
+	pub key_history: bool,
 	/// Where to find the database.
 	pub source: DatabaseSettingsSrc,
 }
@@ -288,8 +299,16 @@ pub enum DatabaseSettingsSrc {
 		cache_size: usize,
 	},
 
+	/// Load a Parity-DB database from a given path.
+	///
+	/// Requires the `with-parity-db` feature.
+	ParityDb {
+		/// Path to the database.
+		path: PathBuf,
+	},
+
 	/// Use a custom already-open database.
-	Custom(Arc<dyn KeyValueDB>),
+	Custom(Arc<dyn Database>),
 }
 
 /// Create an instance of db-backed client.
@@ -347,6 +366,8 @@ pub(crate) mod columns {
 	/// Offchain workers local storage
 	pub const OFFCHAIN: u32 = 9;
 	pub const CACHE: u32 = 10;
+	/// Numbers of the blocks at which storage keys have been changed.
+	pub const KEY_HISTORY: u32 = 11;
 }
 
 struct PendingBlock<Block: BlockT> {
@@ -357,7 +378,7 @@ struct PendingBlock<Block: BlockT> {
 }
 
 // wrapper that implements trait required for state_db
-struct StateMetaDb<'a>(&'a dyn KeyValueDB);
+struct StateMetaDb<'a>(&'a dyn Database);
 
 impl<'a> sc_state_db::MetaDb for StateMetaDb<'a> {
 	type Error = io::Error;
@@ -369,16 +390,18 @@ impl<'a> sc_state_db::MetaDb for StateMetaDb<'a> {
 
 /// Block database
 pub struct BlockchainDb<Block: BlockT> {
-	db: Arc<dyn KeyValueDB>,
+	db: Arc<dyn Database>,
 	meta: Arc<RwLock<Meta<NumberFor<Block>, Block::Hash>>>,
 	leaves: RwLock<LeafSet<Block::Hash, NumberFor<Block>>>,
 	header_metadata_cache: HeaderMetadataCache<Block>,
 }
 
 impl<Block: BlockT> BlockchainDb<Block> {
-	fn new(db: Arc<dyn KeyValueDB>) -> ClientResult<Self> {
+	fn new(db: Arc<dyn Database>) -> ClientResult<Self> {
 		let meta = read_meta::<Block>(&*db, columns::HEADER)?;
-		let leaves = LeafSet::read_from_db(&*db, columns::META, meta_keys::LEAF_PREFIX)?;
+		let leaves = LeafSet::decode_from(
+			db.get(columns::META, meta_keys::LEAF_PREFIX).map_err(db_err)?.as_ref().map(|v| &v[..])
+		)?;
 		Ok(BlockchainDb {
 			db,
 			leaves: RwLock::new(leaves),
@@ -536,6 +559,7 @@ pub struct BlockImportOperation<Block: BlockT> {
 	db_updates: PrefixedMemoryDB<HashFor<Block>>,
 	storage_updates: StorageCollection,
 	child_storage_updates: ChildStorageCollection,
+	offchain_storage_updates: StorageCollection,
 	changes_trie_updates: MemoryDB<HashFor<Block>>,
 	changes_trie_build_cache_update: Option<ChangesTrieCacheAction<Block::Hash, NumberFor<Block>>>,
 	changes_trie_config_update: Option<Option<ChangesTrieConfiguration>>,
@@ -544,6 +568,9 @@ pub struct BlockImportOperation<Block: BlockT> {
 	finalized_blocks: Vec<(BlockId<Block>, Option<Justification>)>,
 	set_head: Option<BlockId<Block>>,
 	commit_state: bool,
+	imported_state: Option<Block::Hash>,
+	imported_state_nodes: Vec<(Vec<u8>, DBValue)>,
+	snapshot_header: Option<Block::Header>,
 }
 
 impl<Block: BlockT> BlockImportOperation<Block> {
@@ -555,6 +582,16 @@ impl<Block: BlockT> BlockImportOperation<Block> {
 			}
 		}
 	}
+
+	fn apply_offchain(&mut self, transaction: &mut DBTransaction) {
+		for (key, maybe_val) in self.offchain_storage_updates.drain(..) {
+			let key: Vec<u8> = sp_core::offchain::STORAGE_PREFIX.iter().chain(&key).cloned().collect();
+			match maybe_val {
+				Some(val) => transaction.put_vec(columns::OFFCHAIN, &key, val),
+				None => transaction.delete(columns::OFFCHAIN, &key),
+			}
+		}
+	}
 }
 
 impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for BlockImportOperation<Block> {
@@ -633,6 +670,74 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 		Ok(root)
 	}
 
+	fn import_state(
+		&mut self,
+		block: Block::Hash,
+		storage: Storage,
+	) -> ClientResult<Block::Hash> {
+		let child_delta = storage.children.into_iter().map(|(storage_key, child_content)|	(
+			storage_key,
+			child_content.data.into_iter().map(|(k, v)| (k, Some(v))), child_content.child_info),
+		);
+
+		let (root, transaction) = self.old_state.full_storage_root(
+			storage.top.into_iter().map(|(k, v)| (k, Some(v))),
+			child_delta,
+		);
+
+		self.db_updates = transaction;
+		self.imported_state = Some(block);
+		Ok(root)
+	}
+
+	fn set_snapshot_header(&mut self, header: Block::Header) -> ClientResult<()> {
+		self.snapshot_header = Some(header);
+		Ok(())
+	}
+
+	fn import_state_chunk(
+		&mut self,
+		child: Option<(&[u8], ChildInfo)>,
+		root: Block::Hash,
+		proof: StorageProof,
+		start: Option<&[u8]>,
+	) -> ClientResult<(Vec<(Vec<u8>, Vec<u8>)>, bool)> {
+		let (values, complete, mut nodes) = read_range_proof_check_with_nodes::<HashFor<Block>>(
+			root,
+			proof,
+			child.map(|(_, child_info)| child_info),
+			start,
+		).map_err(ClientError::from_state)?;
+
+		// The root node of the top trie is only written by `complete_state_import`, so that the
+		// state isn't considered available before it is complete.
+		let held_back = match child {
+			Some(_) => None,
+			None => Some(prefixed_key::<HashFor<Block>>(&root, EMPTY_PREFIX)),
+		};
+		self.imported_state_nodes.extend(nodes.drain().into_iter()
+			.filter(|(key, (_, rc))| *rc > 0 && Some(key) != held_back.as_ref())
+			.map(|(key, (value, _))| (key, value))
+		);
+		Ok((values, complete))
+	}
+
+	fn complete_state_import(
+		&mut self,
+		block: Block::Hash,
+		root: Block::Hash,
+		proof: StorageProof,
+	) -> ClientResult<()> {
+		let node = proof.iter_nodes()
+			.find(|node| <HashFor<Block> as HashT>::hash(node) == root)
+			.ok_or_else(|| ClientError::from(format!("Root node of the state of {:?} is not in the proof", block)))?;
+		let mut db_updates = PrefixedMemoryDB::default();
+		hash_db::HashDB::emplace(&mut db_updates, root, EMPTY_PREFIX, node);
+		self.db_updates = db_updates;
+		self.imported_state = Some(block);
+		Ok(())
+	}
+
 	fn update_changes_trie(
 		&mut self,
 		update: ChangesTrieTransaction<HashFor<Block>, NumberFor<Block>>,
@@ -659,6 +764,14 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 		Ok(())
 	}
 
+	fn update_offchain_storage(
+		&mut self,
+		offchain_update: StorageCollection,
+	) -> ClientResult<()> {
+		self.offchain_storage_updates = offchain_update;
+		Ok(())
+	}
+
 	fn mark_finalized(
 		&mut self,
 		block: BlockId<Block>,
@@ -676,7 +789,7 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 }
 
 struct StorageDb<Block: BlockT> {
-	pub db: Arc<dyn KeyValueDB>,
+	pub db: Arc<dyn Database>,
 	pub state_db: StateDb<Block::Hash, Vec<u8>>,
 }
 
@@ -766,6 +879,7 @@ pub struct Backend<Block: BlockT> {
 	shared_cache: SharedCache<Block>,
 	import_lock: Arc<RwLock<()>>,
 	is_archive: bool,
+	key_history: bool,
 	io_stats: FrozenForDuration<(kvdb::IoStats, StateUsageInfo)>,
 	state_usage: Arc<StateUsageStats>,
 }
@@ -776,35 +890,56 @@ impl<Block: BlockT> Backend<Block> {
 	/// The pruning window is how old a block must be before the state is pruned.
 	pub fn new(config: DatabaseSettings, canonicalization_delay: u64) -> ClientResult<Self> {
 		let db = crate::utils::open_database::<Block>(&config, DatabaseType::Full)?;
-		Self::from_kvdb(db as Arc<_>, canonicalization_delay, &config)
+		Self::from_database(db, canonicalization_delay, &config)
 	}
 
 	/// Create new memory-backed client backend for tests.
 	#[cfg(any(test, feature = "test-helpers"))]
 	pub fn new_test(keep_blocks: u32, canonicalization_delay: u64) -> Self {
+		Self::new_test_with_settings(keep_blocks, canonicalization_delay, false)
+	}
+
+	/// Create new memory-backed client backend for tests, that keeps an index of key changes.
+	#[cfg(any(test, feature = "test-helpers"))]
+	pub fn new_test_with_key_history(keep_blocks: u32, canonicalization_delay: u64) -> Self {
+		Self::new_test_with_settings(keep_blocks, canonicalization_delay, true)
+	}
+
+	#[cfg(any(test, feature = "test-helpers"))]
+	fn new_test_with_settings(keep_blocks: u32, canonicalization_delay: u64, key_history: bool) -> Self {
 		let db = Arc::new(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
 		let db_setting = DatabaseSettings {
 			state_cache_size: 16777216,
 			state_cache_child_ratio: Some((50, 100)),
 			pruning: PruningMode::keep_blocks(keep_blocks),
+			key_history,
 			source: DatabaseSettingsSrc::Custom(db),
 		};
 
 		Self::new(db_setting, canonicalization_delay).expect("failed to create test-db")
 	}
 
-	fn from_kvdb(
-		db: Arc<dyn KeyValueDB>,
+	fn from_database(
+		db: Arc<dyn Database>,
 		canonicalization_delay: u64,
 		config: &DatabaseSettings,
 	) -> ClientResult<Self> {
 		let is_archive_pruning = config.pruning.is_archive();
+		if config.key_history && db.iter_from_prefix(columns::KEY_HISTORY, &[]).is_err() {
+			return Err(sp_blockchain::Error::Backend(
+				"The key history index requires a database backend that supports iteration".into()
+			));
+		}
 		let blockchain = BlockchainDb::new(db.clone())?;
 		let meta = blockchain.meta.clone();
 		let map_e = |e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from(
 			format!("State database error: {:?}", e)
 		);
-		let state_db: StateDb<_, _> = StateDb::new(config.pruning.clone(), &StateMetaDb(&*db))
+		let state_db: StateDb<_, _> = StateDb::new(
+			config.pruning.clone(),
+			db.supports_ref_counting(),
+			&StateMetaDb(&*db),
+		)
 			.map_err(map_e)?;
 		let storage_db = StorageDb {
 			db: db.clone(),
@@ -838,6 +973,7 @@ impl<Block: BlockT> Backend<Block> {
 			),
 			import_lock: Default::default(),
 			is_archive: is_archive_pruning,
+			key_history: config.key_history,
 			io_stats: FrozenForDuration::new(std::time::Duration::from_secs(1)),
 			state_usage: Arc::new(StateUsageStats::new()),
 		})
@@ -853,7 +989,7 @@ impl<Block: BlockT> Backend<Block> {
 
 		// get all headers hashes && sort them by number (could be duplicate)
 		let mut headers: Vec<(NumberFor<Block>, Block::Hash, Block::Header)> = Vec::new();
-		for (_, header) in self.blockchain.db.iter(columns::HEADER) {
+		for (_, header) in self.blockchain.db.iter(columns::HEADER).unwrap() {
 			let header = Block::Header::decode(&mut &header[..]).unwrap();
 			let hash = header.hash();
 			let number = *header.number();
@@ -895,7 +1031,7 @@ impl<Block: BlockT> Backend<Block> {
 	/// Returns total number of blocks (headers) in the block DB.
 	#[cfg(feature = "test-helpers")]
 	pub fn blocks_count(&self) -> u64 {
-		self.blockchain.db.iter(columns::HEADER).count() as u64
+		self.blockchain.db.iter(columns::HEADER).unwrap().count() as u64
 	}
 
 	/// Handle setting head within a transaction. `route_to` should be the last
@@ -1039,8 +1175,15 @@ impl<Block: BlockT> Backend<Block> {
 						implies existence of blocks with all numbers before it; qed")
 			};
 
+			if self.storage.state_db.is_pruned(&hash, new_canonical) {
+				// Blocks imported without state, e.g. headers downloaded ahead of a state
+				// sync, have nothing to canonicalize.
+				trace!(target: "db", "Skipping canonicalization of #{} ({:?}): no state", new_canonical, hash);
+				return Ok(())
+			}
+
 			trace!(target: "db", "Canonicalize block #{} ({:?})", new_canonical, hash);
-			let commit = self.storage.state_db.canonicalize_block(&hash)
+			let commit = self.storage.state_db.canonicalize_block(&hash, &StateMetaDb(&*self.storage.db))
 				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from(format!("State database error: {:?}", e)))?;
 			apply_state_commit(transaction, commit);
 		};
@@ -1055,6 +1198,7 @@ impl<Block: BlockT> Backend<Block> {
 		let mut finalization_displaced_leaves = None;
 
 		operation.apply_aux(&mut transaction);
+		operation.apply_offchain(&mut transaction);
 
 		let mut meta_updates = Vec::with_capacity(operation.finalized_blocks.len());
 		let mut last_finalized_hash = self.blockchain.meta.read().finalized_hash;
@@ -1076,10 +1220,92 @@ impl<Block: BlockT> Backend<Block> {
 			last_finalized_hash = block_hash;
 		}
 
+		let mut snapshot = None;
+		if let Some(header) = operation.snapshot_header {
+			let hash = header.hash();
+			let number = *header.number();
+			let (best_hash, finalized_hash, finalized_number) = {
+				let meta = self.blockchain.meta.read();
+				(meta.best_hash, meta.finalized_hash, meta.finalized_number)
+			};
+			if number <= finalized_number || best_hash != finalized_hash {
+				return Err(ClientError::Backend(format!(
+					"Cannot import snapshot block #{} on top of unfinalized blocks or at or below the last finalized block #{}",
+					number,
+					finalized_number,
+				)));
+			}
+
+			// the header is both best and finalized. Its ancestry is unknown, so there's no
+			// tree route to walk.
+			let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
+			transaction.put(columns::META, meta_keys::BEST_BLOCK, &lookup_key);
+			transaction.put(columns::META, meta_keys::FINALIZED_BLOCK, &lookup_key);
+			utils::insert_hash_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, number, hash)?;
+			utils::insert_number_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, number, hash)?;
+			transaction.put(columns::HEADER, &lookup_key, &header.encode());
+			self.blockchain.insert_header_metadata(hash, CachedHeaderMetadata::from(&header));
+
+			changes_trie_cache_ops = Some(self.changes_tries_storage.commit(
+				&mut transaction,
+				MemoryDB::default(),
+				cache::ComplexBlockId::new(finalized_hash, finalized_number),
+				cache::ComplexBlockId::new(hash, number),
+				&header,
+				true,
+				None,
+				changes_trie_cache_ops,
+			)?);
+
+			let mut leaves = self.blockchain.leaves.write();
+			leaves.import(hash, number, *header.parent_hash());
+			leaves.finalize_height(number);
+			leaves.prepare_transaction(&mut transaction, columns::META, meta_keys::LEAF_PREFIX);
+
+			meta_updates.push((hash, number, true, true));
+			snapshot = Some((number, finalized_hash, finalized_number));
+		}
+
+		for (key, value) in operation.imported_state_nodes {
+			// Nodes at the boundaries of chunks are part of several of them, and the nodes of an
+			// abandoned download may have been written already.
+			if self.storage.db.get(columns::STATE, &key).map_err(db_err)?.is_none() {
+				transaction.put_vec(columns::STATE, &key, value);
+			}
+		}
+
+		if let Some(hash) = operation.imported_state {
+			let number = match snapshot {
+				Some((number, _, _)) => number,
+				None => *self.blockchain.expect_header(BlockId::Hash(hash))?.number(),
+			};
+			let ref_counting = self.storage.db.supports_ref_counting();
+			let mut changeset: sc_state_db::ChangeSet<Vec<u8>> = sc_state_db::ChangeSet::default();
+			for (key, (val, rc)) in operation.db_updates.drain() {
+				if rc > 0 {
+					let count = state_change_count(rc, ref_counting);
+					changeset.inserted.extend(std::iter::repeat((key, val.to_vec())).take(count));
+				}
+			}
+			let commit = self.storage.state_db.import_canonical(
+				&hash,
+				number.saturated_into::<u64>(),
+				changeset,
+				&StateMetaDb(&*self.storage.db),
+			).map_err(|e: sc_state_db::Error<io::Error>|
+				sp_blockchain::Error::from(format!("State database error: {:?}", e))
+			)?;
+			apply_state_commit(&mut transaction, commit);
+			debug!(target: "db", "Imported state of {:?} ({})", hash, number);
+		}
+
 		let imported = if let Some(pending_block) = operation.pending_block {
 			let hash = pending_block.header.hash();
 			let parent_hash = *pending_block.header.parent_hash();
 			let number = pending_block.header.number().clone();
+			// Existing blocks are re-imported to execute them, e.g. after a state sync. Their
+			// position in the block tree doesn't change.
+			let existing = self.blockchain.status(BlockId::Hash(hash))? == sp_blockchain::BlockStatus::InChain;
 
 			// blocks are keyed by number + hash.
 			let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
@@ -1122,22 +1348,24 @@ impl<Block: BlockT> Backend<Block> {
 			}
 
 			let finalized = if operation.commit_state {
+				let ref_counting = self.storage.db.supports_ref_counting();
 				let mut changeset: sc_state_db::ChangeSet<Vec<u8>> = sc_state_db::ChangeSet::default();
 				let mut ops: u64 = 0;
 				let mut bytes: u64 = 0;
 				let mut removal: u64 = 0;
 				let mut bytes_removal: u64 = 0;
 				for (key, (val, rc)) in operation.db_updates.drain() {
+					let count = state_change_count(rc, ref_counting);
 					if rc > 0 {
 						ops += 1;
 						bytes += key.len() as u64 + val.len() as u64;
 
-						changeset.inserted.push((key, val.to_vec()));
+						changeset.inserted.extend(std::iter::repeat((key, val.to_vec())).take(count));
 					} else if rc < 0 {
 						removal += 1;
 						bytes_removal += key.len() as u64;
 
-						changeset.deleted.push(key);
+						changeset.deleted.extend(std::iter::repeat(key).take(count));
 					}
 				}
 				self.state_usage.tally_writes_nodes(ops, bytes);
@@ -1154,6 +1382,14 @@ impl<Block: BlockT> Backend<Block> {
 						}
 				}
 				self.state_usage.tally_writes(ops, bytes);
+				if self.key_history {
+					key_history::note_key_changes(
+						&mut transaction,
+						columns::KEY_HISTORY,
+						number.saturated_into::<u64>(),
+						operation.storage_updates.iter().map(|(key, _)| &key[..]),
+					);
+				}
 				let number_u64 = number.saturated_into::<u64>();
 				let commit = self.storage.state_db.insert_block(
 					&hash,
@@ -1165,6 +1401,15 @@ impl<Block: BlockT> Backend<Block> {
 				)?;
 				apply_state_commit(&mut transaction, commit);
 
+				if existing && number <= self.blockchain.meta.read().finalized_number {
+					// The block was finalized while we only had its header.
+					let commit = self.storage.state_db.canonicalize_block(&hash, &StateMetaDb(&*self.storage.db))
+						.map_err(|e: sc_state_db::Error<io::Error>|
+							sp_blockchain::Error::from(format!("State database error: {:?}", e))
+						)?;
+					apply_state_commit(&mut transaction, commit);
+				}
+
 				// Check if need to finalize. Genesis is always finalized instantly.
 				let finalized = number_u64 == 0 || pending_block.leaf_state.is_final();
 				finalized
@@ -1211,7 +1456,9 @@ impl<Block: BlockT> Backend<Block> {
 
 			debug!(target: "db", "DB Commit {:?} ({}), best = {}", hash, number, is_best);
 
-			let displaced_leaf = {
+			let displaced_leaf = if existing {
+				None
+			} else {
 				let mut leaves = self.blockchain.leaves.write();
 				let displaced_leaf = leaves.import(hash, number, parent_hash);
 				leaves.prepare_transaction(&mut transaction, columns::META, meta_keys::LEAF_PREFIX);
@@ -1219,20 +1466,22 @@ impl<Block: BlockT> Backend<Block> {
 				displaced_leaf
 			};
 
-			let mut children = children::read_children(
-				&*self.storage.db,
-				columns::META,
-				meta_keys::CHILDREN_PREFIX,
-				parent_hash,
-			)?;
-			children.push(hash);
-			children::write_children(
-				&mut transaction,
-				columns::META,
-				meta_keys::CHILDREN_PREFIX,
-				parent_hash,
-				children,
-			);
+			if !existing {
+				let mut children = children::read_children(
+					&*self.storage.db,
+					columns::META,
+					meta_keys::CHILDREN_PREFIX,
+					parent_hash,
+				)?;
+				children.push(hash);
+				children::write_children(
+					&mut transaction,
+					columns::META,
+					meta_keys::CHILDREN_PREFIX,
+					parent_hash,
+					children,
+				);
+			}
 
 			meta_updates.push((hash, number, pending_block.leaf_state.is_best(), finalized));
 
@@ -1294,6 +1543,13 @@ impl<Block: BlockT> Backend<Block> {
 				Some(number),
 				is_best,
 			);
+		} else if let Err(e) = write_result {
+			if let Some((_, finalized_hash, finalized_number)) = snapshot {
+				let mut leaves = self.blockchain.leaves.write();
+				leaves.revert(finalized_hash, finalized_number);
+				leaves.undo();
+			}
+			return Err(e)
 		}
 
 		if let Some(changes_trie_build_cache_update) = operation.changes_trie_build_cache_update {
@@ -1330,9 +1586,12 @@ impl<Block: BlockT> Backend<Block> {
 			let lookup_key = utils::number_and_hash_to_lookup_key(f_num, f_hash.clone())?;
 			transaction.put(columns::META, meta_keys::FINALIZED_BLOCK, &lookup_key);
 
-			let commit = self.storage.state_db.canonicalize_block(&f_hash)
-				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from(format!("State database error: {:?}", e)))?;
-			apply_state_commit(transaction, commit);
+			// Blocks imported without state are finalized, but there is nothing to canonicalize.
+			if !self.storage.state_db.is_pruned(&f_hash, f_num.saturated_into::<u64>()) {
+				let commit = self.storage.state_db.canonicalize_block(&f_hash, &StateMetaDb(&*self.storage.db))
+					.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from(format!("State database error: {:?}", e)))?;
+				apply_state_commit(transaction, commit);
+			}
 
 			if !f_num.is_zero() {
 				let new_changes_trie_cache_ops = self.changes_tries_storage.finalize(
@@ -1357,6 +1616,18 @@ impl<Block: BlockT> Backend<Block> {
 	}
 }
 
+/// Number of times a state node with reference count change `rc` is inserted or deleted.
+///
+/// Reference counting databases need one operation per reference, otherwise a node that is
+/// shared within or between states would be removed while still in use.
+fn state_change_count(rc: i32, ref_counting: bool) -> usize {
+	if ref_counting {
+		rc.abs() as usize
+	} else {
+		1
+	}
+}
+
 fn apply_state_commit(transaction: &mut DBTransaction, commit: sc_state_db::CommitSet<Vec<u8>>) {
 	for (key, val) in commit.data.inserted.into_iter() {
 		transaction.put(columns::STATE, &key[..], &val);
@@ -1412,6 +1683,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			db_updates: PrefixedMemoryDB::default(),
 			storage_updates: Default::default(),
 			child_storage_updates: Default::default(),
+			offchain_storage_updates: Default::default(),
 			changes_trie_config_update: None,
 			changes_trie_updates: MemoryDB::default(),
 			changes_trie_build_cache_update: None,
@@ -1419,6 +1691,9 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			finalized_blocks: Vec::new(),
 			set_head: None,
 			commit_state: false,
+			imported_state: None,
+			imported_state_nodes: Vec::new(),
+			snapshot_header: None,
 		})
 	}
 
@@ -1494,6 +1769,25 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 		Some(self.offchain_storage.clone())
 	}
 
+	fn key_history(
+		&self,
+		key: &[u8],
+		first: NumberFor<Block>,
+		last: NumberFor<Block>,
+	) -> ClientResult<Option<Vec<NumberFor<Block>>>> {
+		if !self.key_history {
+			return Ok(None);
+		}
+		let history = key_history::read_key_history(
+			&*self.storage.db,
+			columns::KEY_HISTORY,
+			key,
+			first.saturated_into::<u64>(),
+			last.saturated_into::<u64>(),
+		)?;
+		Ok(Some(history.into_iter().map(|number| number.saturated_into()).collect()))
+	}
+
 	fn usage_info(&self) -> Option<UsageInfo> {
 		let (io_stats, state_stats) = self.io_stats.take_or_else(||
 			(
@@ -1822,6 +2116,7 @@ pub(crate) mod tests {
 			state_cache_size: 16777216,
 			state_cache_child_ratio: Some((50, 100)),
 			pruning: PruningMode::keep_blocks(1),
+			key_history: false,
 			source: DatabaseSettingsSrc::Custom(backing),
 		}, 0).unwrap();
 		assert_eq!(backend.blockchain().info().best_number, 9);
@@ -1830,6 +2125,49 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn key_history_notes_changed_keys() {
+		let db = Backend::<Block>::new(DatabaseSettings {
+			state_cache_size: 16777216,
+			state_cache_child_ratio: Some((50, 100)),
+			pruning: PruningMode::ArchiveAll,
+			key_history: true,
+			source: DatabaseSettingsSrc::Custom(Arc::new(kvdb_memorydb::create(crate::utils::NUM_COLUMNS))),
+		}, 0).unwrap();
+
+		let mut parent_hash = Default::default();
+		for number in 0..3 {
+			let mut op = db.begin_operation().unwrap();
+			db.begin_state_operation(&mut op, BlockId::Hash(parent_hash)).unwrap();
+			let mut header = Header {
+				number,
+				parent_hash,
+				state_root: Default::default(),
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			};
+
+			let storage = vec![
+				(vec![1], Some(vec![number as u8])),
+				(vec![2], if number == 1 { Some(vec![2]) } else { None }),
+			];
+			let (root, overlay) = op.old_state.storage_root(storage.iter().cloned());
+			op.update_db_storage(overlay).unwrap();
+			header.state_root = root.into();
+			parent_hash = header.hash();
+
+			op.update_storage(storage, Vec::new()).unwrap();
+			op.set_block_data(header, Some(vec![]), None, NewBlockState::Best).unwrap();
+			db.commit_operation(op).unwrap();
+		}
+
+		assert_eq!(db.key_history(&[1], 0, 2).unwrap(), Some(vec![0, 1, 2]));
+		assert_eq!(db.key_history(&[1], 1, 1).unwrap(), Some(vec![1]));
+		assert_eq!(db.key_history(&[2], 0, 2).unwrap(), Some(vec![0, 1, 2]));
+		assert_eq!(db.key_history(&[3], 0, 2).unwrap(), Some(vec![]));
+		assert_eq!(Backend::<Block>::new_test(2, 0).key_history(&[1], 0, 2).unwrap(), None);
+	}
+
 	#[test]
 	fn set_state_data() {
 		let db = Backend::<Block>::new_test(2, 0);
@@ -1916,6 +2254,168 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn import_state_of_header_only_block() {
+		let backend = Backend::<Block>::new_test(2, 0);
+		let genesis = insert_header(&backend, 0, Default::default(), None, Default::default());
+
+		let storage = vec![
+			(vec![1, 3, 5], vec![2, 4, 6]),
+			(vec![1, 2, 3], vec![9, 9, 9]),
+		];
+		let header = Header {
+			number: 1,
+			parent_hash: genesis,
+			state_root: BlakeTwo256::trie_root(storage.clone()),
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let hash = header.hash();
+
+		let mut op = backend.begin_operation().unwrap();
+		op.set_block_data(header.clone(), None, None, NewBlockState::Best).unwrap();
+		backend.commit_operation(op).unwrap();
+		backend.finalize_block(BlockId::Hash(hash), None).unwrap();
+		assert!(!backend.have_state_at(&hash, 1));
+
+		let mut op = backend.begin_operation().unwrap();
+		let root = op.import_state(hash, Storage {
+			top: storage.iter().cloned().collect(),
+			children: Default::default(),
+		}).unwrap();
+		assert_eq!(root, header.state_root);
+		backend.commit_operation(op).unwrap();
+
+		assert!(backend.have_state_at(&hash, 1));
+		let state = backend.state_at(BlockId::Number(1)).unwrap();
+		assert_eq!(state.storage(&[1, 3, 5]).unwrap(), Some(vec![2, 4, 6]));
+		assert_eq!(state.storage(&[1, 2, 3]).unwrap(), Some(vec![9, 9, 9]));
+	}
+
+	#[test]
+	fn import_snapshot_of_unknown_block() {
+		let backend = Backend::<Block>::new_test(2, 0);
+		insert_header(&backend, 0, Default::default(), None, Default::default());
+
+		let storage = vec![(vec![1, 3, 5], vec![2, 4, 6])];
+		let header = Header {
+			number: 5,
+			parent_hash: [1; 32].into(),
+			state_root: BlakeTwo256::trie_root(storage.clone()),
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let hash = header.hash();
+
+		let mut op = backend.begin_operation().unwrap();
+		op.set_snapshot_header(header.clone()).unwrap();
+		op.import_state(hash, Storage {
+			top: storage.iter().cloned().collect(),
+			children: Default::default(),
+		}).unwrap();
+		backend.commit_operation(op).unwrap();
+
+		let info = backend.blockchain().info();
+		assert_eq!((info.best_hash, info.best_number), (hash, 5));
+		assert_eq!((info.finalized_hash, info.finalized_number), (hash, 5));
+		assert_eq!(backend.blockchain().hash(5).unwrap(), Some(hash));
+		assert_eq!(backend.blockchain().leaves().unwrap(), vec![hash]);
+		assert!(backend.have_state_at(&hash, 5));
+		let state = backend.state_at(BlockId::Number(5)).unwrap();
+		assert_eq!(state.storage(&[1, 3, 5]).unwrap(), Some(vec![2, 4, 6]));
+
+		// blocks can be imported on top of it.
+		let child = insert_header(&backend, 6, hash, None, Default::default());
+		assert_eq!(backend.blockchain().info().best_hash, child);
+
+		// but not below it.
+		let mut op = backend.begin_operation().unwrap();
+		op.set_snapshot_header(Header { number: 4, ..header }).unwrap();
+		assert!(backend.commit_operation(op).is_err());
+	}
+
+	#[test]
+	fn import_state_in_chunks() {
+		const CHILD_INFO: ChildInfo<'static> = ChildInfo::new_default(b"unique_id");
+		let child_key = b":child_storage:default:unique_id".to_vec();
+		let backend = Backend::<Block>::new_test(2, 0);
+		let genesis = insert_header(&backend, 0, Default::default(), None, Default::default());
+
+		let mut remote_state = sp_state_machine::InMemoryBackend::<BlakeTwo256>::from(Storage {
+			top: (0u8..64).map(|i| (vec![i; 3], vec![i; 40])).collect(),
+			children: vec![(child_key.clone(), sp_core::storage::StorageChild {
+				data: (0u8..64).map(|i| (vec![i; 2], vec![i; 40])).collect(),
+				child_info: CHILD_INFO.to_owned(),
+			})].into_iter().collect(),
+		});
+		let remote = remote_state.as_trie_backend().unwrap();
+		let state_root = *remote.root();
+		let child_root = remote.storage(&child_key).unwrap()
+			.map(|r| H256::decode(&mut &r[..]).unwrap())
+			.unwrap();
+
+		let header = Header {
+			number: 1,
+			parent_hash: genesis,
+			state_root,
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let hash = header.hash();
+		let mut op = backend.begin_operation().unwrap();
+		op.set_block_data(header, None, None, NewBlockState::Best).unwrap();
+		backend.commit_operation(op).unwrap();
+		backend.finalize_block(BlockId::Hash(hash), None).unwrap();
+
+		let mut root_proof = None;
+		for child in vec![None, Some((&child_key[..], CHILD_INFO, child_root))] {
+			let mut start: Option<Vec<u8>> = None;
+			loop {
+				let (proof, _) = match child {
+					None => sp_state_machine::prove_range_read_with_size_on_trie_backend(
+						remote,
+						512,
+						start.as_deref(),
+					),
+					Some((storage_key, child_info, _)) =>
+						sp_state_machine::prove_child_range_read_with_size_on_trie_backend(
+							remote,
+							storage_key,
+							child_info,
+							512,
+							start.as_deref(),
+						),
+				}.unwrap();
+				if child.is_none() {
+					root_proof = Some(proof.clone());
+				}
+
+				let mut op = backend.begin_operation().unwrap();
+				let (values, complete) = op.import_state_chunk(
+					child.map(|(storage_key, child_info, _)| (storage_key, child_info)),
+					child.map_or(state_root, |(_, _, root)| root),
+					proof,
+					start.as_deref(),
+				).unwrap();
+				backend.commit_operation(op).unwrap();
+				assert!(!backend.have_state_at(&hash, 1));
+				if complete {
+					break;
+				}
+				start = values.last().map(|(k, _)| k.clone());
+			}
+		}
+
+		let mut op = backend.begin_operation().unwrap();
+		op.complete_state_import(hash, state_root, root_proof.unwrap()).unwrap();
+		backend.commit_operation(op).unwrap();
+
+		assert!(backend.have_state_at(&hash, 1));
+		let state = backend.state_at(BlockId::Number(1)).unwrap();
+		assert_eq!(state.storage(&[5, 5, 5]).unwrap(), Some(vec![5; 40]));
+		assert_eq!(state.child_storage(&child_key, CHILD_INFO, &[7, 7]).unwrap(), Some(vec![7; 40]));
+	}
+
 	#[test]
 	fn delete_only_when_negative_rc() {
 		let _ = ::env_logger::try_init();
@@ -2080,6 +2580,129 @@ pub(crate) mod tests {
 		).unwrap().is_none());
 	}
 
+	/// In-memory database that counts references to state nodes, like Parity-DB does.
+	struct RefCountedDb {
+		db: kvdb_memorydb::InMemory,
+		refs: parking_lot::Mutex<HashMap<Vec<u8>, u32>>,
+	}
+
+	impl Database for RefCountedDb {
+		fn get(&self, col: u32, key: &[u8]) -> io::Result<Option<DBValue>> {
+			Database::get(&self.db, col, key)
+		}
+
+		fn write(&self, transaction: DBTransaction) -> io::Result<()> {
+			let mut refs = self.refs.lock();
+			let mut counted = DBTransaction::new();
+			for op in transaction.ops {
+				match op {
+					kvdb::DBOp::Insert { col, key, value } if col == columns::STATE => {
+						let count = refs.entry(key.to_vec()).or_insert(0);
+						*count += 1;
+						if *count == 1 {
+							counted.put_vec(col, &key, value);
+						}
+					},
+					kvdb::DBOp::Delete { col, key } if col == columns::STATE => {
+						if let Some(count) = refs.get_mut(&key[..]) {
+							*count -= 1;
+							if *count == 0 {
+								refs.remove(&key[..]);
+								counted.delete(col, &key);
+							}
+						}
+					},
+					op => counted.ops.push(op),
+				}
+			}
+			Database::write(&self.db, counted)
+		}
+
+		fn iter<'a>(&'a self, col: u32) -> io::Result<DatabaseIter<'a>> {
+			Database::iter(&self.db, col)
+		}
+
+		fn iter_from_prefix<'a>(&'a self, col: u32, prefix: &'a [u8]) -> io::Result<DatabaseIter<'a>> {
+			Database::iter_from_prefix(&self.db, col, prefix)
+		}
+
+		fn supports_ref_counting(&self) -> bool {
+			true
+		}
+	}
+
+	#[test]
+	fn ref_counted_state_keeps_shared_nodes() {
+		let backend = Backend::<Block>::new(DatabaseSettings {
+			state_cache_size: 0,
+			state_cache_child_ratio: None,
+			pruning: PruningMode::keep_blocks(1),
+			key_history: false,
+			source: DatabaseSettingsSrc::Custom(Arc::new(RefCountedDb {
+				db: kvdb_memorydb::create(crate::utils::NUM_COLUMNS),
+				refs: Default::default(),
+			})),
+		}, 0).unwrap();
+		let key = BlakeTwo256::hash(b"hello");
+		let db_key = sp_trie::prefixed_key::<BlakeTwo256>(&key, EMPTY_PREFIX);
+
+		// Imports a block that adds `inserted` and drops `removed` references to the node.
+		let import = |number: u64, parent_hash: H256, inserted: usize, removed: usize| {
+			let mut op = backend.begin_operation().unwrap();
+			let parent = if number == 0 {
+				BlockId::Hash(Default::default())
+			} else {
+				BlockId::Hash(parent_hash)
+			};
+			backend.begin_state_operation(&mut op, parent).unwrap();
+			let mut header = Header {
+				number,
+				parent_hash,
+				state_root: Default::default(),
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			};
+			header.state_root = op.old_state.storage_root(std::iter::empty()).0.into();
+			let hash = header.hash();
+
+			if number == 0 {
+				op.reset_storage(Default::default()).unwrap();
+			}
+			for _ in 0..inserted {
+				op.db_updates.insert(EMPTY_PREFIX, b"hello");
+			}
+			for _ in 0..removed {
+				op.db_updates.remove(&key, EMPTY_PREFIX);
+			}
+			op.set_block_data(header, Some(vec![]), None, NewBlockState::Best).unwrap();
+			backend.commit_operation(op).unwrap();
+			hash
+		};
+
+		let stored = || backend.storage.db.get(columns::STATE, &db_key).unwrap();
+
+		// Blocks 0 and 1 both add a reference to the node, block 2 drops one of them.
+		let hash = import(0, Default::default(), 1, 0);
+		let hash = import(1, hash, 1, 0);
+		let hash = import(2, hash, 0, 1);
+		let hash = import(3, hash, 0, 0);
+		let hash = import(4, hash, 0, 0);
+		assert_eq!(stored().unwrap(), &b"hello"[..]);
+
+		// A node referenced twice by the same state needs two removals as well.
+		let hash = import(5, hash, 2, 0);
+		let hash = import(6, hash, 0, 1);
+		let hash = import(7, hash, 0, 1);
+		let hash = import(8, hash, 0, 0);
+		let hash = import(9, hash, 0, 0);
+		assert_eq!(stored().unwrap(), &b"hello"[..]);
+
+		let hash = import(10, hash, 0, 1);
+		let hash = import(11, hash, 0, 0);
+		import(12, hash, 0, 0);
+		assert!(stored().is_none());
+	}
+
 	#[test]
 	fn tree_route_works() {
 		let backend = Backend::<Block>::new_test(1000, 100);
@@ -2346,4 +2969,27 @@ pub(crate) mod tests {
 			backend.commit_operation(op).unwrap_err();
 		}
 	}
+
+	#[test]
+	fn offchain_storage_updates_are_committed() {
+		use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
+
+		let backend = Backend::<Block>::new_test(10, 10);
+		let mut offchain = backend.offchain_storage().unwrap();
+		offchain.set(STORAGE_PREFIX, b"removed", b"value");
+
+		let block0 = insert_header(&backend, 0, Default::default(), None, Default::default());
+		{
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, BlockId::Hash(block0)).unwrap();
+			op.update_offchain_storage(vec![
+				(b"kept".to_vec(), Some(b"value".to_vec())),
+				(b"removed".to_vec(), None),
+			]).unwrap();
+			backend.commit_operation(op).unwrap();
+		}
+
+		assert_eq!(offchain.get(STORAGE_PREFIX, b"kept"), Some(b"value".to_vec()));
+		assert_eq!(offchain.get(STORAGE_PREFIX, b"removed"), None);
+	}
 }
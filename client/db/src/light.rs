@@ -20,7 +20,7 @@ use std::{sync::Arc, collections::HashMap};
 use std::convert::TryInto;
 use parking_lot::RwLock;
 
-use kvdb::{KeyValueDB, DBTransaction};
+use kvdb::DBTransaction;
 
 use sc_client_api::{backend::{AuxStore, NewBlockState}, UsageInfo};
 use sc_client::blockchain::{
@@ -39,7 +39,7 @@ use sp_runtime::generic::{DigestItem, BlockId};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Zero, One, NumberFor, HashFor};
 use crate::cache::{DbCacheSync, DbCache, ComplexBlockId, EntryType as CacheEntryType};
 use crate::utils::{self, meta_keys, DatabaseType, Meta, db_err, read_db, block_id_to_lookup_key, read_meta};
-use crate::{DatabaseSettings, FrozenForDuration};
+use crate::{DatabaseSettings, Database, FrozenForDuration};
 use log::{trace, warn, debug};
 
 pub(crate) mod columns {
@@ -55,11 +55,14 @@ pub(crate) mod columns {
 const HEADER_CHT_PREFIX: u8 = 0;
 /// Prefix for changes tries roots CHT.
 const CHANGES_TRIE_CHT_PREFIX: u8 = 1;
+/// Number of the last block imported through `import_warp_target` (stored in META column).
+/// Headers below it are not available, so no CHTs can be built for them.
+const WARP_TARGET_KEY: &[u8] = b"warp_target";
 
 /// Light blockchain storage. Stores most recent headers + CHTs for older headers.
 /// Locks order: meta, cache.
 pub struct LightStorage<Block: BlockT> {
-	db: Arc<dyn KeyValueDB>,
+	db: Arc<dyn Database>,
 	meta: RwLock<Meta<NumberFor<Block>, Block::Hash>>,
 	cache: Arc<DbCacheSync<Block>>,
 	header_metadata_cache: HeaderMetadataCache<Block>,
@@ -72,7 +75,7 @@ impl<Block: BlockT> LightStorage<Block> {
 	/// Create new storage with given settings.
 	pub fn new(config: DatabaseSettings) -> ClientResult<Self> {
 		let db = crate::utils::open_database::<Block>(&config, DatabaseType::Light)?;
-		Self::from_kvdb(db as Arc<_>)
+		Self::from_database(db)
 	}
 
 	/// Create new memory-backed `LightStorage` for tests.
@@ -82,10 +85,10 @@ impl<Block: BlockT> LightStorage<Block> {
 
 		let db = Arc::new(::kvdb_memorydb::create(NUM_COLUMNS));
 
-		Self::from_kvdb(db as Arc<_>).expect("failed to create test-db")
+		Self::from_database(db).expect("failed to create test-db")
 	}
 
-	fn from_kvdb(db: Arc<dyn KeyValueDB>) -> ClientResult<Self> {
+	fn from_database(db: Arc<dyn Database>) -> ClientResult<Self> {
 		let meta = read_meta::<Block>(&*db, columns::HEADER)?;
 		let cache = DbCache::new(
 			db.clone(),
@@ -299,6 +302,15 @@ impl<Block: BlockT> LightStorage<Block> {
 		if let Some(new_cht_number) = cht::is_build_required(cht::size(), *header.number()) {
 			let new_cht_start: NumberFor<Block> = cht::start_number(cht::size(), new_cht_number);
 
+			// we can't build CHTs over the range that has been skipped by the warp sync
+			if let Some(warp_target) = self.warp_target()? {
+				if new_cht_start <= warp_target {
+					trace!(target: "db", "Not building CHT#{} below warp sync target #{}",
+						new_cht_number, warp_target);
+					return Ok(());
+				}
+			}
+
 			let mut current_num = new_cht_start;
 			let cht_range = ::std::iter::from_fn(|| {
 				let old_current_num = current_num;
@@ -359,6 +371,16 @@ impl<Block: BlockT> LightStorage<Block> {
 		Ok(())
 	}
 
+	/// Number of the block that has been imported by `import_warp_target`, if any.
+	fn warp_target(&self) -> ClientResult<Option<NumberFor<Block>>> {
+		match self.db.get(columns::META, WARP_TARGET_KEY).map_err(db_err)? {
+			Some(encoded) => NumberFor::<Block>::decode(&mut &encoded[..])
+				.map(Some)
+				.map_err(|_| ClientError::Backend("Error decoding warp sync target".into())),
+			None => Ok(None),
+		}
+	}
+
 	/// Read CHT root of given type for the block.
 	fn read_cht_root(
 		&self,
@@ -504,6 +526,74 @@ impl<Block> LightBlockchainStorage<Block> for LightStorage<Block>
 		Ok(())
 	}
 
+	fn import_warp_target(
+		&self,
+		header: Block::Header,
+		aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	) -> ClientResult<()> {
+		let hash = header.hash();
+		let number = *header.number();
+
+		let (finalized_hash, finalized_number) = {
+			let meta = self.meta.read();
+			(meta.finalized_hash, meta.finalized_number)
+		};
+		if number <= finalized_number {
+			return Err(ClientError::Backend(format!(
+				"Cannot import warp sync target #{} at or below last finalized block #{}",
+				number,
+				finalized_number,
+			)));
+		}
+
+		let mut transaction = DBTransaction::new();
+
+		for (key, maybe_val) in aux_ops {
+			match maybe_val {
+				Some(val) => transaction.put_vec(columns::AUX, &key, val),
+				None => transaction.delete(columns::AUX, &key),
+			}
+		}
+
+		// the header is both best and finalized. Its ancestry is unknown, so there's no
+		// tree route to walk and no CHT to build.
+		let lookup_key = utils::number_and_hash_to_lookup_key(number, &hash)?;
+		transaction.put(columns::META, meta_keys::BEST_BLOCK, &lookup_key);
+		transaction.put(columns::META, meta_keys::FINALIZED_BLOCK, &lookup_key);
+		transaction.put(columns::META, WARP_TARGET_KEY, &number.encode());
+		utils::insert_hash_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, number, hash)?;
+		utils::insert_number_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, number, hash)?;
+		transaction.put(columns::HEADER, &lookup_key, &header.encode());
+
+		self.header_metadata_cache.insert_header_metadata(
+			hash,
+			CachedHeaderMetadata::from(&header),
+		);
+
+		{
+			let mut cache = self.cache.0.write();
+			let cache_ops = cache.transaction(&mut transaction)
+				.on_block_insert(
+					ComplexBlockId::new(finalized_hash, finalized_number),
+					ComplexBlockId::new(hash, number),
+					HashMap::new(),
+					CacheEntryType::Final,
+				)?
+				.into_ops();
+
+			debug!("Light DB Commit warp sync target {:?} ({})", hash, number);
+
+			self.db.write(transaction).map_err(db_err)?;
+			cache.commit(cache_ops)
+				.expect("only fails if cache with given name isn't loaded yet;\
+						cache is already loaded because there are cache_ops; qed");
+		}
+
+		self.update_meta(hash, number, true, true);
+
+		Ok(())
+	}
+
 	fn set_head(&self, id: BlockId<Block>) -> ClientResult<()> {
 		if let Some(header) = self.header(id)? {
 			let hash = header.hash();
@@ -735,12 +825,12 @@ pub(crate) mod tests {
 		let db = LightStorage::new_test();
 
 		let genesis_hash = insert_block(&db, HashMap::new(), || default_header(&Default::default(), 0));
-		assert_eq!(db.db.iter(columns::HEADER).count(), 1);
-		assert_eq!(db.db.iter(columns::KEY_LOOKUP).count(), 2);
+		assert_eq!(db.db.iter(columns::HEADER).unwrap().count(), 1);
+		assert_eq!(db.db.iter(columns::KEY_LOOKUP).unwrap().count(), 2);
 
 		let _ = insert_block(&db, HashMap::new(), || default_header(&genesis_hash, 1));
-		assert_eq!(db.db.iter(columns::HEADER).count(), 2);
-		assert_eq!(db.db.iter(columns::KEY_LOOKUP).count(), 4);
+		assert_eq!(db.db.iter(columns::HEADER).unwrap().count(), 2);
+		assert_eq!(db.db.iter(columns::KEY_LOOKUP).unwrap().count(), 4);
 	}
 
 	#[test]
@@ -758,8 +848,8 @@ pub(crate) mod tests {
 			for number in 0..cht::size() {
 				prev_hash = insert_block(&db, HashMap::new(), || header_producer(&prev_hash, 1 + number));
 			}
-			assert_eq!(db.db.iter(columns::HEADER).count(), 1 + ucht_size);
-			assert_eq!(db.db.iter(columns::CHT).count(), 0);
+			assert_eq!(db.db.iter(columns::HEADER).unwrap().count(), 1 + ucht_size);
+			assert_eq!(db.db.iter(columns::CHT).unwrap().count(), 0);
 
 			// insert next SIZE blocks && ensure that nothing is pruned
 			for number in 0..(cht_size as _) {
@@ -769,8 +859,8 @@ pub(crate) mod tests {
 					|| header_producer(&prev_hash, 1 + cht_size + number),
 				);
 			}
-			assert_eq!(db.db.iter(columns::HEADER).count(), 1 + ucht_size + ucht_size);
-			assert_eq!(db.db.iter(columns::CHT).count(), 0);
+			assert_eq!(db.db.iter(columns::HEADER).unwrap().count(), 1 + ucht_size + ucht_size);
+			assert_eq!(db.db.iter(columns::CHT).unwrap().count(), 0);
 
 			// insert block #{2 * cht::size() + 1} && check that new CHT is created + headers of this CHT are pruned
 			// nothing is yet finalized, so nothing is pruned.
@@ -779,8 +869,8 @@ pub(crate) mod tests {
 				HashMap::new(),
 				|| header_producer(&prev_hash, 1 + cht_size + cht_size),
 			);
-			assert_eq!(db.db.iter(columns::HEADER).count(), 2 + ucht_size + ucht_size);
-			assert_eq!(db.db.iter(columns::CHT).count(), 0);
+			assert_eq!(db.db.iter(columns::HEADER).unwrap().count(), 2 + ucht_size + ucht_size);
+			assert_eq!(db.db.iter(columns::CHT).unwrap().count(), 0);
 
 			// now finalize the block.
 			for i in (0..(ucht_size + ucht_size)).map(|i| i + 1) {
@@ -793,9 +883,9 @@ pub(crate) mod tests {
 		// when headers are created without changes tries roots
 		let db = insert_headers(default_header);
 		let cht_size: u64 = cht::size();
-		assert_eq!(db.db.iter(columns::HEADER).count(), (1 + cht_size + 1) as usize);
-		assert_eq!(db.db.iter(columns::KEY_LOOKUP).count(), (2 * (1 + cht_size + 1)) as usize);
-		assert_eq!(db.db.iter(columns::CHT).count(), 1);
+		assert_eq!(db.db.iter(columns::HEADER).unwrap().count(), (1 + cht_size + 1) as usize);
+		assert_eq!(db.db.iter(columns::KEY_LOOKUP).unwrap().count(), (2 * (1 + cht_size + 1)) as usize);
+		assert_eq!(db.db.iter(columns::CHT).unwrap().count(), 1);
 		assert!((0..cht_size as _).all(|i| db.header(BlockId::Number(1 + i)).unwrap().is_none()));
 		assert!(db.header_cht_root(cht_size, cht_size / 2).unwrap().is_some());
 		assert!(db.header_cht_root(cht_size, cht_size + cht_size / 2).unwrap().is_none());
@@ -804,8 +894,8 @@ pub(crate) mod tests {
 
 		// when headers are created with changes tries roots
 		let db = insert_headers(header_with_changes_trie);
-		assert_eq!(db.db.iter(columns::HEADER).count(), (1 + cht_size + 1) as usize);
-		assert_eq!(db.db.iter(columns::CHT).count(), 2);
+		assert_eq!(db.db.iter(columns::HEADER).unwrap().count(), (1 + cht_size + 1) as usize);
+		assert_eq!(db.db.iter(columns::CHT).unwrap().count(), 2);
 		assert!((0..cht_size as _).all(|i| db.header(BlockId::Number(1 + i)).unwrap().is_none()));
 		assert!(db.header_cht_root(cht_size, cht_size / 2).unwrap().is_some());
 		assert!(db.header_cht_root(cht_size, cht_size + cht_size / 2).unwrap().is_none());
@@ -1134,6 +1224,38 @@ pub(crate) mod tests {
 		assert_eq!(db.header(BlockId::Hash::<Block>(hash0)).unwrap().unwrap().hash(), hash0);
 	}
 
+	#[test]
+	fn warp_target_import_skips_chts_over_missing_headers() {
+		let db = LightStorage::new_test();
+		let cht_size: u64 = cht::size();
+		let hash0 = insert_final_block(&db, HashMap::new(), || default_header(&Default::default(), 0));
+
+		// can't import warp target at or below last finalized block
+		assert!(db.import_warp_target(default_header(&hash0, 0), Vec::new()).is_err());
+
+		let target = default_header(&Hash::random(), cht_size * 2 + 10);
+		let target_hash = target.hash();
+		db.import_warp_target(target, vec![(b"key".to_vec(), Some(b"value".to_vec()))]).unwrap();
+		assert_eq!(db.info().best_hash, target_hash);
+		assert_eq!(db.info().finalized_hash, target_hash);
+		assert_eq!(db.info().finalized_number, cht_size * 2 + 10);
+		assert_eq!(db.get_aux(b"key").unwrap(), Some(b"value".to_vec()));
+		assert_eq!(db.hash(cht_size * 2 + 10).unwrap(), Some(target_hash));
+
+		// finalizing blocks on top of the target doesn't try to build CHT#1,
+		// which covers the skipped headers
+		let mut prev_hash = target_hash;
+		for number in cht_size * 2 + 11..cht_size * 3 + 2 {
+			prev_hash = insert_final_block(&db, HashMap::new(), || default_header(&prev_hash, number));
+		}
+		assert_eq!(db.info().finalized_hash, prev_hash);
+		assert_eq!(db.db.get(columns::CHT, &cht_key(HEADER_CHT_PREFIX, cht_size + 1).unwrap()).unwrap(), None);
+
+		// the target survives reopening the database
+		let db = LightStorage::<Block>::from_kvdb(db.db).unwrap();
+		assert_eq!(db.warp_target().unwrap(), Some(cht_size * 2 + 10));
+	}
+
 	#[test]
 	fn aux_store_works() {
 		let db = LightStorage::<Block>::new_test();
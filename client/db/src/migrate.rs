@@ -0,0 +1,173 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Migration of a database between backends.
+
+use kvdb::DBTransaction;
+use log::info;
+use sp_runtime::traits::Block as BlockT;
+use crate::{
+	DatabaseSettings, DatabaseSettingsSrc,
+	StateMetaDb,
+	utils::{DatabaseType, NUM_COLUMNS, COLUMN_META, db_err, meta_keys, open_database},
+};
+
+/// Maximal number of values written in a single transaction.
+const MAX_BATCH_SIZE: usize = 16 * 1024;
+
+/// Copy the contents of the `source` database into the empty `target` database.
+///
+/// The source backend must support iteration, which rules out Parity-DB: migrating
+/// from it is rejected before either database is opened. Only archive databases can
+/// be migrated, since the pruning journals of the source don't match the reference
+/// counted state of other backends and can't be rebuilt. The meta column is copied last, so
+/// an interrupted migration leaves a target without a best block that can simply
+/// be migrated into again.
+///
+/// Returns the number of copied values.
+pub fn migrate_database<Block: BlockT>(
+	source: &DatabaseSettings,
+	target: &DatabaseSettings,
+	db_type: DatabaseType,
+) -> sp_blockchain::Result<u64> {
+	if let DatabaseSettingsSrc::ParityDb { .. } = source.source {
+		return Err(sp_blockchain::Error::Backend(
+			"Migrating from Parity-DB is not supported, as it can't be iterated".into()
+		));
+	}
+
+	let source = open_database::<Block>(source, db_type)?;
+	let is_archive = sc_state_db::is_archive(&StateMetaDb(&*source))
+		.map_err(|e: sc_state_db::Error<std::io::Error>|
+			sp_blockchain::Error::from(format!("State database error: {:?}", e))
+		)?;
+	if is_archive == Some(false) {
+		return Err(sp_blockchain::Error::Backend(
+			"Only archive databases can be migrated, the state pruning journals can't be rebuilt".into()
+		));
+	}
+	let target = open_database::<Block>(target, db_type)?;
+
+	if target.get(COLUMN_META, meta_keys::BEST_BLOCK).map_err(db_err)?.is_some() {
+		return Err(sp_blockchain::Error::Backend("Target database is not empty".into()));
+	}
+
+	let mut copied = 0u64;
+	for col in (0..NUM_COLUMNS).filter(|col| *col != COLUMN_META).chain(Some(COLUMN_META)) {
+		let mut transaction = DBTransaction::new();
+		for (key, value) in source.iter(col).map_err(db_err)? {
+			transaction.put(col, &key, &value);
+			copied += 1;
+			if transaction.ops.len() >= MAX_BATCH_SIZE {
+				target.write(std::mem::replace(&mut transaction, DBTransaction::new())).map_err(db_err)?;
+			}
+		}
+		target.write(transaction).map_err(db_err)?;
+		info!("Migrated column {}, {} values copied so far", col, copied);
+	}
+
+	Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use sc_state_db::PruningMode;
+	use crate::{Database, DatabaseSettingsSrc, tests::Block};
+	use super::*;
+
+	fn settings(db: Arc<dyn Database>) -> DatabaseSettings {
+		DatabaseSettings {
+			state_cache_size: 0,
+			state_cache_child_ratio: None,
+			pruning: PruningMode::ArchiveAll,
+			key_history: false,
+			source: DatabaseSettingsSrc::Custom(db),
+		}
+	}
+
+	#[test]
+	fn migration_copies_all_columns() {
+		let source: Arc<dyn Database> = Arc::new(kvdb_memorydb::create(NUM_COLUMNS));
+		let target: Arc<dyn Database> = Arc::new(kvdb_memorydb::create(NUM_COLUMNS));
+
+		let mut transaction = DBTransaction::new();
+		transaction.put(COLUMN_META, meta_keys::BEST_BLOCK, b"best");
+		for col in 1..NUM_COLUMNS {
+			transaction.put(col, b"key", &[col as u8]);
+		}
+		source.write(transaction).unwrap();
+
+		let copied = migrate_database::<Block>(
+			&settings(source.clone()),
+			&settings(target.clone()),
+			DatabaseType::Full,
+		).unwrap();
+
+		// all values plus the database type written when opening the source.
+		assert_eq!(copied, NUM_COLUMNS as u64 + 1);
+		for col in 1..NUM_COLUMNS {
+			assert_eq!(target.get(col, b"key").unwrap(), Some(vec![col as u8]));
+		}
+		assert_eq!(target.get(COLUMN_META, meta_keys::BEST_BLOCK).unwrap(), Some(b"best".to_vec()));
+	}
+
+	#[test]
+	fn migration_into_non_empty_database_fails() {
+		let source: Arc<dyn Database> = Arc::new(kvdb_memorydb::create(NUM_COLUMNS));
+		let target: Arc<dyn Database> = Arc::new(kvdb_memorydb::create(NUM_COLUMNS));
+
+		let mut transaction = DBTransaction::new();
+		transaction.put(COLUMN_META, meta_keys::BEST_BLOCK, b"best");
+		target.write(transaction).unwrap();
+
+		assert!(migrate_database::<Block>(
+			&settings(source),
+			&settings(target),
+			DatabaseType::Full,
+		).is_err());
+	}
+
+	#[test]
+	fn migration_from_parity_db_is_rejected() {
+		let target: Arc<dyn Database> = Arc::new(kvdb_memorydb::create(NUM_COLUMNS));
+		let source = DatabaseSettings {
+			source: DatabaseSettingsSrc::ParityDb { path: "/nonexistent/paritydb".into() },
+			..settings(target.clone())
+		};
+
+		assert!(migrate_database::<Block>(&source, &settings(target), DatabaseType::Full).is_err());
+	}
+
+	#[test]
+	fn migration_of_pruned_database_is_rejected() {
+		let source: Arc<dyn Database> = Arc::new(kvdb_memorydb::create(NUM_COLUMNS));
+		let target: Arc<dyn Database> = Arc::new(kvdb_memorydb::create(NUM_COLUMNS));
+		let pruned = DatabaseSettings {
+			pruning: PruningMode::keep_blocks(1),
+			..settings(source.clone())
+		};
+		let backend = crate::Backend::<Block>::new(pruned, 0).unwrap();
+		crate::tests::insert_header(&backend, 0, Default::default(), None, Default::default());
+
+		assert!(migrate_database::<Block>(
+			&settings(source),
+			&settings(target.clone()),
+			DatabaseType::Full,
+		).is_err());
+		assert!(target.get(COLUMN_META, meta_keys::BEST_BLOCK).unwrap().is_none());
+	}
+}
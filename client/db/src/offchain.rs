@@ -15,20 +15,55 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 //! RocksDB-based offchain workers local storage.
+//!
+//! Besides the persistent storage, this also provides the fork-aware storage
+//! used for `StorageKind::LOCAL`. Values set in the context of a block are
+//! kept under the hash of that block and are only visible to the block and its
+//! descendants. Once a block is finalized its values are moved to the finalized
+//! state, while values set at blocks of any other fork are discarded.
 
 use std::{
 	collections::HashMap,
 	sync::Arc,
 };
 
-use crate::columns;
-use kvdb::KeyValueDB;
+use crate::{columns, Database};
+use codec::{Decode, Encode};
+use kvdb::DBTransaction;
 use parking_lot::Mutex;
+use sp_core::offchain::ForkContext;
+
+/// Prefix of the fork-aware values of the finalized state.
+const LOCAL_FINALIZED_PREFIX: &[u8] = b"local_finalized:";
+/// Prefix of the fork-aware values set at a non-finalized block, followed by the block hash.
+const LOCAL_BLOCK_PREFIX: &[u8] = b"local_block:";
+/// Prefix of the journal of a non-finalized block (block number and the keys set at
+/// the block), followed by the block hash.
+const LOCAL_JOURNAL_PREFIX: &[u8] = b"local_journal:";
+
+/// The keys set in fork-aware storage at a given block.
+#[derive(Default, Encode, Decode)]
+struct LocalJournal {
+	number: u64,
+	keys: Vec<Vec<u8>>,
+}
+
+fn local_finalized_key(key: &[u8]) -> Vec<u8> {
+	LOCAL_FINALIZED_PREFIX.iter().chain(key).cloned().collect()
+}
+
+fn local_block_key(hash: &[u8], key: &[u8]) -> Vec<u8> {
+	LOCAL_BLOCK_PREFIX.iter().chain(hash).chain(key).cloned().collect()
+}
+
+fn local_journal_key(hash: &[u8]) -> Vec<u8> {
+	LOCAL_JOURNAL_PREFIX.iter().chain(hash).cloned().collect()
+}
 
 /// Offchain local storage
 #[derive(Clone)]
 pub struct LocalStorage {
-	db: Arc<dyn KeyValueDB>,
+	db: Arc<dyn Database>,
 	locks: Arc<Mutex<HashMap<Vec<u8>, Arc<Mutex<()>>>>>,
 }
 
@@ -47,13 +82,55 @@ impl LocalStorage {
 		Self::new(db as _)
 	}
 
-	/// Create offchain local storage with given `KeyValueDB` backend.
-	pub fn new(db: Arc<dyn KeyValueDB>) -> Self {
+	/// Create offchain local storage with given `Database` backend.
+	pub fn new(db: Arc<dyn Database>) -> Self {
 		Self {
 			db,
 			locks: Default::default(),
 		}
 	}
+
+	/// Run the given closure while holding the lock for the given key.
+	fn with_key_lock<R>(&self, key: &[u8], f: impl FnOnce() -> R) -> R {
+		let key_lock = {
+			let mut locks = self.locks.lock();
+			locks.entry(key.to_vec()).or_default().clone()
+		};
+
+		let result = {
+			let _key_guard = key_lock.lock();
+			f()
+		};
+
+		// clean the lock map if we're the only entry
+		let mut locks = self.locks.lock();
+		{
+			drop(key_lock);
+			let key_lock = locks.get_mut(key);
+			if let Some(_) = key_lock.and_then(Arc::get_mut) {
+				locks.remove(key);
+			}
+		}
+		result
+	}
+
+	fn write(&self, tx: DBTransaction) {
+		if let Err(e) = self.db.write(tx) {
+			log::warn!("Error writing to the offchain DB: {:?}", e);
+		}
+	}
+
+	fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+		self.db.get(columns::OFFCHAIN, key)
+			.ok()
+			.and_then(|x| x)
+			.map(|v| v.to_vec())
+	}
+
+	fn local_journal(&self, hash: &[u8]) -> Option<LocalJournal> {
+		self.read(&local_journal_key(hash))
+			.and_then(|j| LocalJournal::decode(&mut &j[..]).ok())
+	}
 }
 
 impl sp_core::offchain::OffchainStorage for LocalStorage {
@@ -69,10 +146,7 @@ impl sp_core::offchain::OffchainStorage for LocalStorage {
 
 	fn get(&self, prefix: &[u8], key: &[u8]) -> Option<Vec<u8>> {
 		let key: Vec<u8> = prefix.iter().chain(key).cloned().collect();
-		self.db.get(columns::OFFCHAIN, &key)
-			.ok()
-			.and_then(|x| x)
-			.map(|v| v.to_vec())
+		self.read(&key)
 	}
 
 	fn compare_and_set(
@@ -83,34 +157,121 @@ impl sp_core::offchain::OffchainStorage for LocalStorage {
 		new_value: &[u8],
 	) -> bool {
 		let key: Vec<u8> = prefix.iter().chain(item_key).cloned().collect();
-		let key_lock = {
-			let mut locks = self.locks.lock();
-			locks.entry(key.clone()).or_default().clone()
+
+		let mut this = self.clone();
+		self.with_key_lock(&key, || {
+			let val = this.read(&key);
+			let is_set = val.as_ref().map(|x| &**x) == old_value;
+
+			if is_set {
+				this.set(prefix, item_key, new_value)
+			}
+
+			is_set
+		})
+	}
+
+	fn local_set(&mut self, at: &ForkContext, prefix: &[u8], key: &[u8], value: &[u8]) {
+		let key: Vec<u8> = prefix.iter().chain(key).cloned().collect();
+
+		let hash = match at.block_hash() {
+			Some(hash) => hash,
+			None => {
+				let mut tx = self.db.transaction();
+				tx.put(columns::OFFCHAIN, &local_finalized_key(&key), value);
+				return self.write(tx);
+			},
 		};
 
-		let is_set;
-		{
-			let _key_guard = key_lock.lock();
-			let val = self.db.get(columns::OFFCHAIN, &key)
-				.ok()
-				.and_then(|x| x);
-			is_set = val.as_ref().map(|x| &**x) == old_value;
+		// the journal of the block is shared by all keys set at the block.
+		let journal_key = local_journal_key(hash);
+		self.with_key_lock(&journal_key, || {
+			let mut journal = self.local_journal(hash).unwrap_or_else(|| LocalJournal {
+				number: at.number,
+				keys: Vec::new(),
+			});
+
+			let mut tx = self.db.transaction();
+			if !journal.keys.contains(&key) {
+				journal.keys.push(key.clone());
+				tx.put_vec(columns::OFFCHAIN, &journal_key, journal.encode());
+			}
+			tx.put(columns::OFFCHAIN, &local_block_key(hash, &key), value);
+			self.write(tx);
+		})
+	}
+
+	fn local_get(&self, at: &ForkContext, prefix: &[u8], key: &[u8]) -> Option<Vec<u8>> {
+		let key: Vec<u8> = prefix.iter().chain(key).cloned().collect();
+
+		at.ancestry.iter()
+			.find_map(|hash| self.read(&local_block_key(hash, &key)))
+			.or_else(|| self.read(&local_finalized_key(&key)))
+	}
+
+	fn local_compare_and_set(
+		&mut self,
+		at: &ForkContext,
+		prefix: &[u8],
+		item_key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	) -> bool {
+		let key: Vec<u8> = LOCAL_BLOCK_PREFIX.iter()
+			.chain(prefix)
+			.chain(item_key)
+			.cloned()
+			.collect();
+
+		// the value seen at a block might come from any of its ancestors, so we
+		// lock the key for all forks.
+		let mut this = self.clone();
+		self.with_key_lock(&key, || {
+			let is_set = this.local_get(at, prefix, item_key).as_ref().map(|v| &v[..]) == old_value;
 
 			if is_set {
-				self.set(prefix, item_key, new_value)
+				this.local_set(at, prefix, item_key, new_value);
 			}
+
+			is_set
+		})
+	}
+
+	fn local_finalize(&mut self, number: u64, is_canonical: &dyn Fn(u64, &[u8]) -> bool) {
+		let mut finalized = self.db.iter_with_prefix(columns::OFFCHAIN, LOCAL_JOURNAL_PREFIX)
+			.filter_map(|(key, journal)| {
+				let hash = key[LOCAL_JOURNAL_PREFIX.len()..].to_vec();
+				let journal = LocalJournal::decode(&mut &journal[..]).ok()?;
+				Some((journal.number, hash, journal.keys))
+			})
+			.filter(|(block_number, _, _)| *block_number <= number)
+			.collect::<Vec<_>>();
+
+		if finalized.is_empty() {
+			return;
 		}
 
-		// clean the lock map if we're the only entry
-		let mut locks = self.locks.lock();
-		{
-			drop(key_lock);
-			let key_lock = locks.get_mut(&key);
-			if let Some(_) = key_lock.and_then(Arc::get_mut) {
-				locks.remove(&key);
+		// values of later blocks override the ones of earlier blocks.
+		finalized.sort();
+
+		let mut tx = self.db.transaction();
+		for (block_number, hash, keys) in finalized {
+			let canonical = is_canonical(block_number, &hash);
+
+			for key in keys {
+				let block_key = local_block_key(&hash, &key);
+				if canonical {
+					if let Some(value) = self.read(&block_key) {
+						tx.put_vec(columns::OFFCHAIN, &local_finalized_key(&key), value);
+					}
+				}
+				tx.delete(columns::OFFCHAIN, &block_key);
 			}
+
+			tx.delete(columns::OFFCHAIN, &local_journal_key(&hash));
 		}
-		is_set
+
+		self.write(tx);
 	}
 }
 
@@ -145,4 +306,65 @@ mod tests {
 		assert!(storage.locks.lock().is_empty(), "Locks map should be empty!");
 	}
 
+	fn fork(number: u64, ancestry: &[&[u8]]) -> ForkContext {
+		ForkContext {
+			number,
+			ancestry: ancestry.iter().map(|h| h.to_vec()).collect(),
+		}
+	}
+
+	#[test]
+	fn local_storage_should_be_visible_to_descendants_only() {
+		let mut storage = LocalStorage::new_test();
+		let prefix = b"prefix";
+		let key = b"key";
+
+		storage.local_set(&ForkContext::finalized(), prefix, key, b"finalized");
+		storage.local_set(&fork(1, &[b"a1"]), prefix, key, b"a1");
+
+		// visible at the block and its descendants
+		assert_eq!(storage.local_get(&fork(1, &[b"a1"]), prefix, key), Some(b"a1".to_vec()));
+		assert_eq!(storage.local_get(&fork(2, &[b"a2", b"a1"]), prefix, key), Some(b"a1".to_vec()));
+		// but not on a sibling fork
+		assert_eq!(storage.local_get(&fork(1, &[b"b1"]), prefix, key), Some(b"finalized".to_vec()));
+		assert_eq!(storage.local_get(&ForkContext::finalized(), prefix, key), Some(b"finalized".to_vec()));
+	}
+
+	#[test]
+	fn local_compare_and_set_should_respect_fork() {
+		let mut storage = LocalStorage::new_test();
+		let prefix = b"prefix";
+		let key = b"key";
+
+		assert!(storage.local_compare_and_set(&fork(1, &[b"a1"]), prefix, key, None, b"a1"));
+		assert!(storage.local_compare_and_set(&fork(1, &[b"b1"]), prefix, key, None, b"b1"));
+		assert!(!storage.local_compare_and_set(&fork(2, &[b"a2", b"a1"]), prefix, key, None, b"a2"));
+		assert!(storage.local_compare_and_set(&fork(2, &[b"a2", b"a1"]), prefix, key, Some(b"a1"), b"a2"));
+		assert_eq!(storage.local_get(&fork(1, &[b"b1"]), prefix, key), Some(b"b1".to_vec()));
+		assert!(storage.locks.lock().is_empty(), "Locks map should be empty!");
+	}
+
+	#[test]
+	fn local_finalize_should_keep_canonical_and_discard_other_forks() {
+		let mut storage = LocalStorage::new_test();
+		let prefix = b"prefix";
+
+		storage.local_set(&fork(1, &[b"a1"]), prefix, b"k1", b"a1");
+		storage.local_set(&fork(2, &[b"a2", b"a1"]), prefix, b"k1", b"a2");
+		storage.local_set(&fork(1, &[b"b1"]), prefix, b"k2", b"b1");
+		storage.local_set(&fork(3, &[b"a3", b"a2", b"a1"]), prefix, b"k2", b"a3");
+
+		storage.local_finalize(2, &|_, hash| hash == b"a1" || hash == b"a2");
+
+		// canonical values were moved to the finalized state
+		assert_eq!(storage.local_get(&ForkContext::finalized(), prefix, b"k1"), Some(b"a2".to_vec()));
+		// values on the retracted fork were discarded
+		assert_eq!(storage.local_get(&fork(1, &[b"b1"]), prefix, b"k2"), None);
+		// values above the finalized block are kept
+		assert_eq!(storage.local_get(&fork(3, &[b"a3"]), prefix, b"k2"), Some(b"a3".to_vec()));
+		assert_eq!(
+			storage.db.iter_with_prefix(columns::OFFCHAIN, LOCAL_JOURNAL_PREFIX).count(),
+			1,
+		);
+	}
 }
@@ -0,0 +1,78 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! `Database` adapter for Parity-DB.
+
+use std::{io, path::Path, sync::Arc};
+use kvdb::{DBOp, DBTransaction, DBValue};
+use crate::{
+	columns,
+	database::{Database, DatabaseIter},
+	utils::{DatabaseType, NUM_COLUMNS},
+};
+
+/// Parity-DB backed `Database`.
+struct DbAdapter {
+	db: parity_db::Db,
+	ref_counting: bool,
+}
+
+fn to_io_error(e: parity_db::Error) -> io::Error {
+	io::Error::new(io::ErrorKind::Other, format!("Parity-DB error: {:?}", e))
+}
+
+/// Open (or create) a Parity-DB database at the given path.
+pub fn open(path: &Path, db_type: DatabaseType) -> io::Result<Arc<dyn Database>> {
+	let mut config = parity_db::Options::with_columns(path, NUM_COLUMNS as u8);
+	if db_type == DatabaseType::Full {
+		// Trie nodes are keyed by the hash of their value, which lets the
+		// database skip value comparisons and use a uniform index. Reference
+		// counting lets state-db prune without keeping a journal of deleted keys.
+		let state_col = &mut config.columns[columns::STATE as usize];
+		state_col.preimage = true;
+		state_col.uniform = true;
+		state_col.ref_counted = true;
+	}
+	let db = parity_db::Db::open(&config).map_err(to_io_error)?;
+	Ok(Arc::new(DbAdapter { db, ref_counting: db_type == DatabaseType::Full }))
+}
+
+impl Database for DbAdapter {
+	fn get(&self, col: u32, key: &[u8]) -> io::Result<Option<DBValue>> {
+		self.db.get(col as u8, key).map_err(to_io_error)
+	}
+
+	fn write(&self, transaction: DBTransaction) -> io::Result<()> {
+		self.db.commit(transaction.ops.into_iter().map(|op| match op {
+			DBOp::Insert { col, key, value } => (col as u8, key, Some(value)),
+			DBOp::Delete { col, key } => (col as u8, key, None),
+		})).map_err(to_io_error)
+	}
+
+	/// Parity-DB 0.1 stores values in hash-indexed tables and has no way to enumerate
+	/// its keys, so a Parity-DB database can't be iterated, or migrated away from.
+	fn iter<'a>(&'a self, _col: u32) -> io::Result<DatabaseIter<'a>> {
+		Err(io::Error::new(io::ErrorKind::Other, "Parity-DB does not support iteration"))
+	}
+
+	fn iter_from_prefix<'a>(&'a self, _col: u32, _prefix: &'a [u8]) -> io::Result<DatabaseIter<'a>> {
+		Err(io::Error::new(io::ErrorKind::Other, "Parity-DB does not support iteration"))
+	}
+
+	fn supports_ref_counting(&self) -> bool {
+		self.ref_counting
+	}
+}
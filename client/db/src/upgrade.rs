@@ -21,32 +21,48 @@ use std::io::{Read, Write, ErrorKind};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use codec::Encode;
+use codec::{Decode, Encode};
+use kvdb::DBTransaction;
 use kvdb_rocksdb::{Database, DatabaseConfig};
 use parking_lot::RwLock;
 use sp_blockchain::{well_known_cache_keys, Cache};
 use sp_core::ChangesTrieConfiguration;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
 use crate::{
 	cache::{ComplexBlockId, DbCache, DbCacheSync},
-	utils::{DatabaseType, check_database_type, db_err, read_genesis_hash},
+	utils::{DatabaseType, check_database_type, db_err, read_genesis_hash, meta_keys, COLUMN_META},
 };
 
 /// Version file name.
 const VERSION_FILE_NAME: &'static str = "db_version";
 
 /// Current db version.
-const CURRENT_VERSION: u32 = 1;
+const CURRENT_VERSION: u32 = 3;
 
 /// Number of columns in v0.
 const V0_NUM_COLUMNS: u32 = 10;
 
+/// Number of columns in v1.
+const V1_NUM_COLUMNS: u32 = 11;
+
+/// Number of columns in v2.
+const V2_NUM_COLUMNS: u32 = 11;
+
 /// Upgrade database to current version.
 pub fn upgrade_db<Block: BlockT>(db_path: &Path, db_type: DatabaseType) -> sp_blockchain::Result<()> {
 	let db_version = current_version(db_path)?;
 	match db_version {
-		0 => migrate_0_to_1::<Block>(db_path, db_type)?,
-		1 => (),
+		0 => {
+			migrate_0_to_1::<Block>(db_path, db_type)?;
+			migrate_1_to_2::<Block>(db_path, db_type)?;
+			migrate_2_to_3(db_path, db_type)?;
+		},
+		1 => {
+			migrate_1_to_2::<Block>(db_path, db_type)?;
+			migrate_2_to_3(db_path, db_type)?;
+		},
+		2 => migrate_2_to_3(db_path, db_type)?,
+		3 => (),
 		_ => Err(sp_blockchain::Error::Backend(format!("Future database version: {}", db_version)))?,
 	}
 
@@ -102,6 +118,46 @@ fn migrate_0_to_1<Block: BlockT>(db_path: &Path, db_type: DatabaseType) -> sp_bl
 	Ok(())
 }
 
+/// Migration from version1 to version2:
+/// the leaf set of a full database was stored as one key per leaf
+/// (`leaf ++ hash => number`) and is now stored as a single encoded list
+/// under the `leaf` key, so that it can be read without iterating the database.
+fn migrate_1_to_2<Block: BlockT>(db_path: &Path, db_type: DatabaseType) -> sp_blockchain::Result<()> {
+	if db_type != DatabaseType::Full {
+		return Ok(());
+	}
+
+	let db = open_database(db_path, db_type, V1_NUM_COLUMNS)?;
+	let prefix = &meta_keys::LEAF_PREFIX[..];
+	let mut transaction = DBTransaction::new();
+	let mut leaves: Vec<(NumberFor<Block>, Vec<Block::Hash>)> = Vec::new();
+	for (key, value) in db.iter_from_prefix(COLUMN_META, prefix) {
+		if !key.starts_with(prefix) { break }
+		let hash = Block::Hash::decode(&mut &key[prefix.len()..])
+			.map_err(|_| sp_blockchain::Error::Backend("Error decoding leaf hash".into()))?;
+		let number = NumberFor::<Block>::decode(&mut &value[..])
+			.map_err(|_| sp_blockchain::Error::Backend("Error decoding leaf number".into()))?;
+		match leaves.iter_mut().find(|(n, _)| *n == number) {
+			Some((_, hashes)) => hashes.push(hash),
+			None => leaves.push((number, vec![hash])),
+		}
+		transaction.delete(COLUMN_META, &key);
+	}
+	leaves.sort_by(|a, b| b.0.cmp(&a.0));
+	transaction.put_vec(COLUMN_META, prefix, leaves.encode());
+	db.write(transaction).map_err(db_err)?;
+	db.flush().map_err(db_err)
+}
+
+/// Migration from version2 to version3:
+/// the number of columns has changed from 11 to 12, the new column
+/// holds the optional index of storage key changes.
+fn migrate_2_to_3(db_path: &Path, db_type: DatabaseType) -> sp_blockchain::Result<()> {
+	let db = open_database(db_path, db_type, V2_NUM_COLUMNS)?;
+	db.add_column().map_err(db_err)?;
+	db.flush().map_err(db_err)
+}
+
 /// Reads current database version from the file at given path.
 /// If the file does not exist returns 0.
 fn current_version(path: &Path) -> sp_blockchain::Result<u32> {
@@ -166,6 +222,7 @@ mod tests {
 			state_cache_size: 0,
 			state_cache_child_ratio: None,
 			pruning: PruningMode::ArchiveAll,
+			key_history: false,
 			source: DatabaseSettingsSrc::Path { path: db_path.to_owned(), cache_size: 128 },
 		}, DatabaseType::Full).map(|_| ())
 	}
@@ -185,6 +242,72 @@ mod tests {
 		assert_eq!(current_version(db_dir.path()).unwrap(), CURRENT_VERSION);
 	}
 
+	#[test]
+	fn upgrade_from_1_to_2_moves_leaves_into_single_key() {
+		let db_dir = tempfile::TempDir::new().unwrap();
+		let db_path = db_dir.path();
+		let leaves = vec![
+			(3u64, sp_core::H256::from([3u8; 32])),
+			(2u64, sp_core::H256::from([2u8; 32])),
+			(3u64, sp_core::H256::from([4u8; 32])),
+		];
+		{
+			let db = Database::open(
+				&DatabaseConfig::with_columns(V1_NUM_COLUMNS),
+				db_path.to_str().unwrap(),
+			).unwrap();
+			let mut transaction = DBTransaction::new();
+			transaction.put(COLUMN_META, meta_keys::TYPE, DatabaseType::Full.as_str().as_bytes());
+			for (number, hash) in &leaves {
+				let mut key = meta_keys::LEAF_PREFIX.to_vec();
+				key.extend(hash.encode());
+				transaction.put_vec(COLUMN_META, &key, number.encode());
+			}
+			db.write(transaction).unwrap();
+		}
+		fs::write(version_file_path(db_path), b"1").unwrap();
+
+		open_database(db_path).unwrap();
+		assert_eq!(current_version(db_path).unwrap(), CURRENT_VERSION);
+
+		let db = Database::open(
+			&DatabaseConfig::with_columns(crate::utils::NUM_COLUMNS),
+			db_path.to_str().unwrap(),
+		).unwrap();
+		let set = sc_client::leaves::LeafSet::<sp_core::H256, u64>::decode_from(
+			db.get(COLUMN_META, meta_keys::LEAF_PREFIX).unwrap().as_ref().map(|v| &v[..])
+		).unwrap();
+		assert_eq!(set.count(), 2);
+		assert_eq!(set.hashes().len(), 3);
+		assert_eq!(db.iter_from_prefix(COLUMN_META, meta_keys::LEAF_PREFIX).count(), 1);
+	}
+
+	#[test]
+	fn upgrade_from_2_to_3_adds_key_history_column() {
+		let db_dir = tempfile::TempDir::new().unwrap();
+		let db_path = db_dir.path();
+		{
+			let db = Database::open(
+				&DatabaseConfig::with_columns(V2_NUM_COLUMNS),
+				db_path.to_str().unwrap(),
+			).unwrap();
+			let mut transaction = DBTransaction::new();
+			transaction.put(COLUMN_META, meta_keys::TYPE, DatabaseType::Full.as_str().as_bytes());
+			db.write(transaction).unwrap();
+		}
+		fs::write(version_file_path(db_path), b"2").unwrap();
+
+		open_database(db_path).unwrap();
+		assert_eq!(current_version(db_path).unwrap(), CURRENT_VERSION);
+		assert_eq!(
+			Database::open(
+				&DatabaseConfig::with_columns(crate::utils::NUM_COLUMNS),
+				db_path.to_str().unwrap(),
+			).unwrap().num_columns(),
+			crate::utils::NUM_COLUMNS,
+		);
+	}
+
 	#[test]
 	fn upgrade_from_0_to_1_works() {
 		for version_from_file in &[None, Some(0)] {
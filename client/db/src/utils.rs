@@ -20,9 +20,9 @@
 use std::sync::Arc;
 use std::{io, convert::TryInto};
 
-use kvdb::{KeyValueDB, DBTransaction};
+use kvdb::DBTransaction;
 #[cfg(any(feature = "kvdb-rocksdb", test))]
-use kvdb_rocksdb::{Database, DatabaseConfig};
+use kvdb_rocksdb::DatabaseConfig;
 use log::debug;
 
 use codec::Decode;
@@ -32,12 +32,11 @@ use sp_runtime::traits::{
 	Block as BlockT, Header as HeaderT, Zero,
 	UniqueSaturatedFrom, UniqueSaturatedInto,
 };
-use crate::{DatabaseSettings, DatabaseSettingsSrc};
+use crate::{DatabaseSettings, DatabaseSettingsSrc, Database};
 
 /// Number of columns in the db. Must be the same for both full && light dbs.
 /// Otherwise RocksDb will fail to open database && check its type.
-#[cfg(any(feature = "kvdb-rocksdb", feature = "test-helpers", test))]
-pub const NUM_COLUMNS: u32 = 11;
+pub const NUM_COLUMNS: u32 = 12;
 /// Meta column. The set of keys in the column is shared by full && light storages.
 pub const COLUMN_META: u32 = 0;
 
@@ -55,7 +54,7 @@ pub mod meta_keys {
 	pub const CHANGES_TRIES_META: &[u8; 5] = b"ctrie";
 	/// Genesis block hash.
 	pub const GENESIS_HASH: &[u8; 3] = b"gen";
-	/// Leaves prefix list key.
+	/// Leaves list key.
 	pub const LEAF_PREFIX: &[u8; 4] = b"leaf";
 	/// Children prefix list key.
 	pub const CHILDREN_PREFIX: &[u8; 8] = b"children";
@@ -191,7 +190,7 @@ pub fn insert_hash_to_key_mapping<N: TryInto<u32>, H: AsRef<[u8]> + Clone>(
 /// block lookup key is the DB-key header, block and justification are stored under.
 /// looks up lookup key by hash from DB as necessary.
 pub fn block_id_to_lookup_key<Block>(
-	db: &dyn KeyValueDB,
+	db: &dyn Database,
 	key_lookup_col: u32,
 	id: BlockId<Block>
 ) -> Result<Option<Vec<u8>>, sp_blockchain::Error> where
@@ -214,12 +213,12 @@ pub fn db_err(err: io::Error) -> sp_blockchain::Error {
 	sp_blockchain::Error::Backend(format!("{}", err))
 }
 
-/// Open RocksDB database.
+/// Open the database described by the settings.
 pub fn open_database<Block: BlockT>(
 	config: &DatabaseSettings,
 	db_type: DatabaseType,
-) -> sp_blockchain::Result<Arc<dyn KeyValueDB>> {
-	let db: Arc<dyn KeyValueDB> = match &config.source {
+) -> sp_blockchain::Result<Arc<dyn Database>> {
+	let db: Arc<dyn Database> = match &config.source {
 		#[cfg(any(feature = "kvdb-rocksdb", test))]
 		DatabaseSettingsSrc::Path { path, cache_size } => {
 			// first upgrade database to required version
@@ -243,13 +242,20 @@ pub fn open_database<Block: BlockT>(
 
 			db_config.memory_budget = memory_budget;
 
-			Arc::new(Database::open(&db_config, &path).map_err(db_err)?)
+			Arc::new(kvdb_rocksdb::Database::open(&db_config, &path).map_err(db_err)?)
 		},
 		#[cfg(not(any(feature = "kvdb-rocksdb", test)))]
 		DatabaseSettingsSrc::Path { .. } => {
 			let msg = "Try to open RocksDB database with RocksDB disabled".into();
 			return Err(sp_blockchain::Error::Backend(msg));
 		},
+		#[cfg(feature = "with-parity-db")]
+		DatabaseSettingsSrc::ParityDb { path } => crate::parity_db::open(&path, db_type).map_err(db_err)?,
+		#[cfg(not(feature = "with-parity-db"))]
+		DatabaseSettingsSrc::ParityDb { .. } => {
+			let msg = "Try to open Parity-DB database with Parity-DB disabled".into();
+			return Err(sp_blockchain::Error::Backend(msg));
+		},
 		DatabaseSettingsSrc::Custom(db) => db.clone(),
 	};
 
@@ -259,7 +265,7 @@ pub fn open_database<Block: BlockT>(
 }
 
 /// Check database type.
-pub fn check_database_type(db: &dyn KeyValueDB, db_type: DatabaseType) -> sp_blockchain::Result<()> {
+pub fn check_database_type(db: &dyn Database, db_type: DatabaseType) -> sp_blockchain::Result<()> {
 	match db.get(COLUMN_META, meta_keys::TYPE).map_err(db_err)? {
 		Some(stored_type) => {
 			if db_type.as_str().as_bytes() != &*stored_type {
@@ -279,7 +285,7 @@ pub fn check_database_type(db: &dyn KeyValueDB, db_type: DatabaseType) -> sp_blo
 
 /// Read database column entry for the given block.
 pub fn read_db<Block>(
-	db: &dyn KeyValueDB,
+	db: &dyn Database,
 	col_index: u32,
 	col: u32,
 	id: BlockId<Block>
@@ -295,7 +301,7 @@ pub fn read_db<Block>(
 
 /// Read a header from the database.
 pub fn read_header<Block: BlockT>(
-	db: &dyn KeyValueDB,
+	db: &dyn Database,
 	col_index: u32,
 	col: u32,
 	id: BlockId<Block>,
@@ -313,7 +319,7 @@ pub fn read_header<Block: BlockT>(
 
 /// Required header from the database.
 pub fn require_header<Block: BlockT>(
-	db: &dyn KeyValueDB,
+	db: &dyn Database,
 	col_index: u32,
 	col: u32,
 	id: BlockId<Block>,
@@ -325,7 +331,7 @@ pub fn require_header<Block: BlockT>(
 }
 
 /// Read meta from the database.
-pub fn read_meta<Block>(db: &dyn KeyValueDB, col_header: u32) -> Result<
+pub fn read_meta<Block>(db: &dyn Database, col_header: u32) -> Result<
 	Meta<<<Block as BlockT>::Header as HeaderT>::Number, Block::Hash>,
 	sp_blockchain::Error,
 >
@@ -371,7 +377,7 @@ pub fn read_meta<Block>(db: &dyn KeyValueDB, col_header: u32) -> Result<
 }
 
 /// Read genesis hash from database.
-pub fn read_genesis_hash<Hash: Decode>(db: &dyn KeyValueDB) -> sp_blockchain::Result<Option<Hash>> {
+pub fn read_genesis_hash<Hash: Decode>(db: &dyn Database) -> sp_blockchain::Result<Option<Hash>> {
 	match db.get(COLUMN_META, meta_keys::GENESIS_HASH).map_err(db_err)? {
 		Some(h) => match Decode::decode(&mut &h[..]) {
 			Ok(h) => Ok(Some(h)),
@@ -20,4 +20,5 @@
 
 pub mod sandbox;
 pub mod error;
+pub mod stack_limit;
 pub mod wasm_runtime;
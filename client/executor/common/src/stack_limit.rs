@@ -0,0 +1,52 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Deterministic limit of the stack height of wasm code.
+//!
+//! How much native stack a wasm call consumes depends on the execution engine, its version and
+//! the platform, so a deep recursion might succeed on one node and overflow the stack of another.
+//! To avoid that, the code is instrumented before instantiation to keep track of a "logical"
+//! stack height, which is the sum of the locals and the maximal operand stack height of all
+//! active frames. Calls that would exceed the limit execute `unreachable` instead, which traps
+//! the same way with every engine.
+
+use crate::error::WasmError;
+
+/// Default logical stack height limit.
+pub const DEFAULT_LOGICAL_MAX: u32 = 65536;
+
+/// Limit of the logical stack height of wasm code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeterministicStackLimit {
+	/// The maximal logical stack height, in number of values.
+	pub logical_max: u32,
+}
+
+impl Default for DeterministicStackLimit {
+	fn default() -> Self {
+		DeterministicStackLimit { logical_max: DEFAULT_LOGICAL_MAX }
+	}
+}
+
+/// Instrument `code` to trap as soon as its logical stack height exceeds `limit`.
+pub fn instrument(code: &[u8], limit: DeterministicStackLimit) -> Result<Vec<u8>, WasmError> {
+	let module = parity_wasm::deserialize_buffer(code)
+		.map_err(|_| WasmError::CantDeserializeWasm)?;
+	let module = pwasm_utils::stack_height::inject_limiter(module, limit.logical_max)
+		.map_err(|e| WasmError::Other(format!("cannot inject the stack height limiter: {:?}", e)))?;
+	parity_wasm::serialize(module)
+		.map_err(|e| WasmError::Other(format!("cannot serialize the instrumented module: {}", e)))
+}
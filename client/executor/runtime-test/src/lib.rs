@@ -289,8 +289,28 @@ sp_core::wasm_export_functions! {
 		assert_ne!(test_message, message_slice);
 		message_slice.copy_from_slice(test_message);
 	}
+
+	fn test_recursion(depth: u32) -> u32 {
+		let mut sum = 0;
+		recurse(depth, &mut sum);
+		sum
+	}
  }
 
+/// Recurse `depth` times, adding all depths to `sum`.
+///
+/// The address of `local` escapes into the recursive call, so the compiler can't turn the
+/// recursion into a loop.
+#[cfg(not(feature = "std"))]
+#[inline(never)]
+fn recurse(depth: u32, sum: &mut u32) {
+	let mut local = depth;
+	if depth > 0 {
+		recurse(depth - 1, &mut local);
+	}
+	*sum = sum.wrapping_add(local);
+}
+
 #[cfg(not(feature = "std"))]
 fn execute_sandboxed(
 	code: &[u8],
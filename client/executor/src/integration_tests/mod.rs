@@ -15,6 +15,7 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 mod sandbox;
+mod stack_limit;
 
 use codec::{Encode, Decode};
 use hex_literal::hex;
@@ -47,6 +48,8 @@ fn call_in_wasm<E: Externalities>(
 		HostFunctions::host_functions(),
 		true,
 		8,
+		Some(Default::default()),
+		None,
 	);
 	executor.call_in_wasm(
 		&WASM_BINARY[..],
@@ -513,6 +516,8 @@ fn should_trap_when_heap_exhausted(wasm_method: WasmExecutionMethod) {
 		HostFunctions::host_functions(),
 		true,
 		8,
+		None,
+		None,
 	);
 	executor.call_in_wasm(
 		&WASM_BINARY[..],
@@ -532,6 +537,8 @@ fn returns_mutable_static(wasm_method: WasmExecutionMethod) {
 		&WASM_BINARY[..],
 		HostFunctions::host_functions(),
 		true,
+		None,
+		None,
 	).expect("Creates runtime");
 
 	let instance = runtime.new_instance().unwrap();
@@ -566,6 +573,8 @@ fn restoration_of_globals(wasm_method: WasmExecutionMethod) {
 		&WASM_BINARY[..],
 		HostFunctions::host_functions(),
 		true,
+		None,
+		None,
 	).expect("Creates runtime");
 	let instance = runtime.new_instance().unwrap();
 
@@ -586,6 +595,8 @@ fn heap_is_reset_between_calls(wasm_method: WasmExecutionMethod) {
 		&WASM_BINARY[..],
 		HostFunctions::host_functions(),
 		true,
+		None,
+		None,
 	).expect("Creates runtime");
 	let instance = runtime.new_instance().unwrap();
 
@@ -601,3 +612,29 @@ fn heap_is_reset_between_calls(wasm_method: WasmExecutionMethod) {
 	// Cal it a second time to check that the heap was freed.
 	instance.call("check_and_set_in_heap", &params).unwrap();
 }
+
+#[cfg(feature = "wasmtime")]
+#[test]
+fn compiled_runtime_is_cached_on_disk() {
+	let cache_dir = tempfile::tempdir().unwrap();
+	let create_runtime = || crate::wasm_runtime::create_wasm_runtime_with_code(
+		WasmExecutionMethod::Compiled,
+		1024,
+		&WASM_BINARY[..],
+		HostFunctions::host_functions(),
+		true,
+		None,
+		Some(cache_dir.path()),
+	).expect("Creates runtime");
+
+	let runtime = create_runtime();
+	assert!(cache_dir.path().join("cache-config.toml").exists());
+	let artifacts = std::fs::read_dir(cache_dir.path().join("artifacts")).unwrap().count();
+	assert!(artifacts > 0);
+
+	// Loading from the cache must result in a working runtime.
+	drop(runtime);
+	let instance = create_runtime().new_instance().unwrap();
+	let res = instance.call("returns_mutable_static", &[0]).unwrap();
+	assert_eq!(33, u64::decode(&mut &res[..]).unwrap());
+}
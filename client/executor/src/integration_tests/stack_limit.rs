@@ -0,0 +1,91 @@
+// Copyright 2018-2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::HostFunctions;
+use crate::{DeterministicStackLimit, WasmExecutionMethod};
+
+use codec::{Encode, Decode};
+use sc_executor_common::{stack_limit::DEFAULT_LOGICAL_MAX, wasm_runtime::WasmModule};
+use sc_runtime_test::WASM_BINARY;
+use sp_wasm_interface::HostFunctions as _;
+use test_case::test_case;
+
+fn create_runtime(wasm_method: WasmExecutionMethod, logical_max: u32) -> Box<dyn WasmModule> {
+	crate::wasm_runtime::create_wasm_runtime_with_code(
+		wasm_method,
+		1024,
+		&WASM_BINARY[..],
+		HostFunctions::host_functions(),
+		true,
+		Some(DeterministicStackLimit { logical_max }),
+		None,
+	).expect("Creates runtime")
+}
+
+fn recurse(runtime: &dyn WasmModule, depth: u32) -> Result<u32, String> {
+	let instance = runtime.new_instance().expect("Creates instance");
+	instance.call("test_recursion", &depth.encode())
+		.map(|res| u32::decode(&mut &res[..]).unwrap())
+		.map_err(|e| e.to_string())
+}
+
+/// Find the deepest recursion that doesn't exceed the limit.
+fn max_recursion_depth(wasm_method: WasmExecutionMethod, logical_max: u32) -> u32 {
+	let runtime = create_runtime(wasm_method, logical_max);
+	// Every frame has a logical height of at least one.
+	let (mut ok, mut failed) = (0, logical_max);
+	assert!(recurse(&*runtime, ok).is_ok());
+	assert!(recurse(&*runtime, failed).is_err());
+	while failed - ok > 1 {
+		let depth = ok + (failed - ok) / 2;
+		if recurse(&*runtime, depth).is_ok() {
+			ok = depth;
+		} else {
+			failed = depth;
+		}
+	}
+	ok
+}
+
+#[test_case(WasmExecutionMethod::Interpreted)]
+#[cfg_attr(feature = "wasmtime", test_case(WasmExecutionMethod::Compiled))]
+fn recursion_within_limit_works(wasm_method: WasmExecutionMethod) {
+	let runtime = create_runtime(wasm_method, 1024);
+	// 1 + 2 + ... + 10
+	assert_eq!(recurse(&*runtime, 10), Ok(55));
+}
+
+#[test_case(WasmExecutionMethod::Interpreted)]
+#[cfg_attr(feature = "wasmtime", test_case(WasmExecutionMethod::Compiled))]
+fn deep_recursion_traps(wasm_method: WasmExecutionMethod) {
+	let runtime = create_runtime(wasm_method, DEFAULT_LOGICAL_MAX);
+	assert!(recurse(&*runtime, 1_000).is_ok());
+	assert!(recurse(&*runtime, 10_000_000).is_err());
+
+	// The runtime can be used again after the trap.
+	assert!(recurse(&*runtime, 1_000).is_ok());
+}
+
+#[test]
+fn max_recursion_depth_is_deterministic() {
+	let depth = max_recursion_depth(WasmExecutionMethod::Interpreted, 1024);
+	assert!(depth > 0);
+	assert_eq!(max_recursion_depth(WasmExecutionMethod::Interpreted, 1024), depth);
+	assert!(max_recursion_depth(WasmExecutionMethod::Interpreted, 2048) > depth);
+
+	#[cfg(feature = "wasmtime")]
+	assert_eq!(max_recursion_depth(WasmExecutionMethod::Compiled, 1024), depth);
+}
@@ -45,7 +45,7 @@ pub use sp_core::traits::{Externalities, CallInWasm};
 pub use sp_wasm_interface;
 pub use wasm_runtime::WasmExecutionMethod;
 
-pub use sc_executor_common::{error, sandbox};
+pub use sc_executor_common::{error, sandbox, stack_limit::DeterministicStackLimit};
 
 /// Provides runtime information.
 pub trait RuntimeInfo {
@@ -79,6 +79,8 @@ mod tests {
 			sp_io::SubstrateHostFunctions::host_functions(),
 			true,
 			8,
+			None,
+			None,
 		);
 		let res = executor.call_in_wasm(
 			&WASM_BINARY[..],
@@ -22,9 +22,9 @@ use sp_version::{NativeVersion, RuntimeVersion};
 use codec::{Decode, Encode};
 use sp_core::{NativeOrEncoded, traits::{CodeExecutor, Externalities, RuntimeCode}};
 use log::trace;
-use std::{result, panic::{UnwindSafe, AssertUnwindSafe}, sync::Arc};
+use std::{result, panic::{UnwindSafe, AssertUnwindSafe}, path::PathBuf, sync::Arc};
 use sp_wasm_interface::{HostFunctions, Function};
-use sc_executor_common::wasm_runtime::WasmInstance;
+use sc_executor_common::{stack_limit::DeterministicStackLimit, wasm_runtime::WasmInstance};
 
 /// Default num of pages for the heap
 const DEFAULT_HEAP_PAGES: u64 = 1024;
@@ -87,6 +87,10 @@ pub struct WasmExecutor {
 	allow_missing_func_imports: bool,
 	/// The size of the instances cache.
 	max_runtime_instances: usize,
+	/// Limit of the logical stack height the runtimes are instrumented with.
+	stack_limit: Option<DeterministicStackLimit>,
+	/// Directory to cache compiled runtimes in.
+	cache_path: Option<PathBuf>,
 }
 
 impl WasmExecutor {
@@ -98,20 +102,30 @@ impl WasmExecutor {
 	///
 	/// `default_heap_pages` - Number of 64KB pages to allocate for Wasm execution.
 	/// 	Defaults to `DEFAULT_HEAP_PAGES` if `None` is provided.
+	///
+	/// `stack_limit` - Limit of the logical stack height to instrument the code with. Without a
+	/// 	limit, the stack height is only bounded by the native stack of the execution engine.
+	///
+	/// `cache_path` - Directory to cache compiled runtimes in. Only used with
+	/// 	`WasmExecutionMethod::Compiled`.
 	pub fn new(
 		method: WasmExecutionMethod,
 		default_heap_pages: Option<u64>,
 		host_functions: Vec<&'static dyn Function>,
 		allow_missing_func_imports: bool,
 		max_runtime_instances: usize,
+		stack_limit: Option<DeterministicStackLimit>,
+		cache_path: Option<PathBuf>,
 	) -> Self {
 		WasmExecutor {
 			method,
 			default_heap_pages: default_heap_pages.unwrap_or(DEFAULT_HEAP_PAGES),
 			host_functions: Arc::new(host_functions),
-			cache: Arc::new(RuntimeCache::new(max_runtime_instances)),
+			cache: Arc::new(RuntimeCache::new(max_runtime_instances, stack_limit, cache_path.clone())),
 			allow_missing_func_imports,
 			max_runtime_instances,
+			stack_limit,
+			cache_path,
 		}
 	}
 
@@ -188,6 +202,8 @@ impl sp_core::traits::CallInWasm for WasmExecutor {
 				&wasm_code,
 				self.host_functions.to_vec(),
 				self.allow_missing_func_imports,
+				self.stack_limit,
+				self.cache_path.as_deref(),
 			)
 				.map_err(|e| format!("Failed to create module: {:?}", e))?;
 
@@ -221,6 +237,8 @@ pub struct NativeExecutor<D> {
 impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 	/// Create new instance.
 	///
+	/// The fallback Wasm code is instrumented with the default `DeterministicStackLimit`.
+	///
 	/// # Parameters
 	///
 	/// `fallback_method` - Method used to execute fallback Wasm code.
@@ -231,6 +249,26 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 		fallback_method: WasmExecutionMethod,
 		default_heap_pages: Option<u64>,
 		max_runtime_instances: usize,
+	) -> Self {
+		Self::new_with_cache_path(
+			fallback_method,
+			default_heap_pages,
+			max_runtime_instances,
+			None,
+			DeterministicStackLimit::default(),
+		)
+	}
+
+	/// Create new instance that caches compiled runtimes in `cache_path` and instruments the
+	/// fallback Wasm code with the given `stack_limit`.
+	///
+	/// See [`new`](Self::new) for the other parameters.
+	pub fn new_with_cache_path(
+		fallback_method: WasmExecutionMethod,
+		default_heap_pages: Option<u64>,
+		max_runtime_instances: usize,
+		cache_path: Option<PathBuf>,
+		stack_limit: DeterministicStackLimit,
 	) -> Self {
 		let mut host_functions = sp_io::SubstrateHostFunctions::host_functions();
 
@@ -242,6 +280,8 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 			host_functions,
 			false,
 			max_runtime_instances,
+			Some(stack_limit),
+			cache_path,
 		);
 
 		NativeExecutor {
@@ -19,6 +19,7 @@
 //! The primary means of accessing the runtimes is through a cache which saves the reusable
 //! components of the runtime that are expensive to initialize.
 
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use crate::error::{Error, WasmError};
 use parking_lot::Mutex;
@@ -26,7 +27,10 @@ use codec::Decode;
 use sp_core::traits::{Externalities, RuntimeCode, FetchRuntimeCode};
 use sp_version::RuntimeVersion;
 use std::panic::AssertUnwindSafe;
-use sc_executor_common::wasm_runtime::{WasmModule, WasmInstance};
+use sc_executor_common::{
+	stack_limit::{self, DeterministicStackLimit},
+	wasm_runtime::{WasmModule, WasmInstance},
+};
 
 use sp_wasm_interface::Function;
 
@@ -149,14 +153,32 @@ pub struct RuntimeCache {
 	runtimes: Mutex<[Option<Arc<VersionedRuntime>>; MAX_RUNTIMES]>,
 	/// The size of the instances cache for each runtime.
 	max_runtime_instances: usize,
+	/// Limit of the logical stack height the runtimes are instrumented with.
+	stack_limit: Option<DeterministicStackLimit>,
+	/// Directory to cache compiled runtimes in.
+	cache_path: Option<PathBuf>,
 }
 
 impl RuntimeCache {
 	/// Creates a new instance of a runtimes cache.
-	pub fn new(max_runtime_instances: usize) -> RuntimeCache {
+	///
+	/// `max_runtime_instances` specifies the number of runtime instances preserved in an in-memory
+	/// cache for each runtime.
+	///
+	/// `stack_limit` is the limit of the logical stack height the runtimes are instrumented with.
+	///
+	/// `cache_path` allows to specify an optional directory where the executor can store files
+	/// for caching, e.g. the machine code compiled by wasmtime.
+	pub fn new(
+		max_runtime_instances: usize,
+		stack_limit: Option<DeterministicStackLimit>,
+		cache_path: Option<PathBuf>,
+	) -> RuntimeCache {
 		RuntimeCache {
 			runtimes: Default::default(),
 			max_runtime_instances,
+			stack_limit,
+			cache_path,
 		}
 	}
 
@@ -231,6 +253,8 @@ impl RuntimeCache {
 					host_functions.into(),
 					allow_missing_func_imports,
 					self.max_runtime_instances,
+					self.stack_limit,
+					self.cache_path.as_deref(),
 				);
 				if let Err(ref err) = result {
 					log::warn!(target: "wasm-runtime", "Cannot create a runtime: {:?}", err);
@@ -261,28 +285,49 @@ impl RuntimeCache {
 }
 
 /// Create a wasm runtime with the given `code`.
+///
+/// If `stack_limit` is given, the code is instrumented to trap deterministically once its stack
+/// height exceeds the limit, see [`stack_limit`](sc_executor_common::stack_limit).
+///
+/// `cache_path` is only used by the compiled execution method, to cache the machine code on disk.
 pub fn create_wasm_runtime_with_code(
 	wasm_method: WasmExecutionMethod,
 	heap_pages: u64,
 	code: &[u8],
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	stack_limit: Option<DeterministicStackLimit>,
+	cache_path: Option<&Path>,
 ) -> Result<Box<dyn WasmModule>, WasmError> {
+	let instrumented;
+	let code = match stack_limit {
+		Some(limit) => {
+			instrumented = stack_limit::instrument(code, limit)?;
+			&instrumented[..]
+		},
+		None => code,
+	};
+
 	match wasm_method {
-		WasmExecutionMethod::Interpreted =>
+		WasmExecutionMethod::Interpreted => {
+			// The interpreter doesn't compile anything worth caching.
+			let _ = cache_path;
+
 			sc_executor_wasmi::create_runtime(
 				code,
 				heap_pages,
 				host_functions,
 				allow_missing_func_imports
-			).map(|runtime| -> Box<dyn WasmModule> { Box::new(runtime) }),
+			).map(|runtime| -> Box<dyn WasmModule> { Box::new(runtime) })
+		},
 		#[cfg(feature = "wasmtime")]
 		WasmExecutionMethod::Compiled =>
 			sc_executor_wasmtime::create_runtime(
 				code,
 				heap_pages,
 				host_functions,
-				allow_missing_func_imports
+				allow_missing_func_imports,
+				cache_path,
 			).map(|runtime| -> Box<dyn WasmModule> { Box::new(runtime) }),
 	}
 }
@@ -296,6 +341,8 @@ fn create_versioned_wasm_runtime(
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
 	max_instances: usize,
+	stack_limit: Option<DeterministicStackLimit>,
+	cache_path: Option<&Path>,
 ) -> Result<VersionedRuntime, WasmError> {
 	#[cfg(not(target_os = "unknown"))]
 	let time = std::time::Instant::now();
@@ -305,6 +352,8 @@ fn create_versioned_wasm_runtime(
 		&code,
 		host_functions,
 		allow_missing_func_imports,
+		stack_limit,
+		cache_path,
 	)?;
 
 	// Call to determine runtime version.
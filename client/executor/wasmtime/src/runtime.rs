@@ -15,6 +15,8 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Defines the compiled Wasm runtime that uses Wasmtime internally.
+use std::fs;
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -93,15 +95,29 @@ impl WasmInstance for WasmtimeInstance {
 
 /// Create a new `WasmtimeRuntime` given the code. This function performs translation from Wasm to
 /// machine code, which can be computationally heavy.
+///
+/// If `cache_path` is given, the compiled machine code is cached on disk in that directory and
+/// reused the next time the same code is compiled with the same configuration.
 pub fn create_runtime(
 	code: &[u8],
 	heap_pages: u64,
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	cache_path: Option<&Path>,
 ) -> std::result::Result<WasmtimeRuntime, WasmError> {
 	// Create the engine, store and finally the module from the given code.
 	let mut config = Config::new();
 	config.cranelift_opt_level(wasmtime::OptLevel::SpeedAndSize);
+	if let Some(cache_path) = cache_path {
+		if let Err(e) = setup_caching(cache_path, &mut config) {
+			log::warn!(
+				target: "wasm-runtime",
+				"Failed to set up the wasmtime cache in {}, compiling without it: {}",
+				cache_path.display(),
+				e,
+			);
+		}
+	}
 
 	let engine = Engine::new(&config);
 	let store = Store::new(&engine);
@@ -116,6 +132,44 @@ pub fn create_runtime(
 	})
 }
 
+/// Total size of the cached artifacts above which the oldest ones are evicted.
+const CACHE_SIZE_SOFT_LIMIT: &str = "1Gi";
+
+/// Number of cached artifacts above which the oldest ones are evicted.
+const CACHE_FILE_COUNT_SOFT_LIMIT: u64 = 64;
+
+/// Enable the on-disk compilation cache of wasmtime in `cache_path`.
+///
+/// Entries are keyed by a hash of the code, the compiler configuration and the wasmtime version.
+/// Entries that fail to deserialize are treated as missing and get recompiled, and a background
+/// worker evicts the least recently used entries once the limits above are exceeded.
+fn setup_caching(cache_path: &Path, config: &mut Config) -> std::result::Result<(), String> {
+	fs::create_dir_all(cache_path)
+		.map_err(|e| format!("cannot create the cache directory: {}", e))?;
+	// Wasmtime requires an absolute path.
+	let cache_path = cache_path.canonicalize()
+		.map_err(|e| format!("cannot canonicalize the cache directory: {}", e))?;
+
+	let config_path = cache_path.join("cache-config.toml");
+	let config_content = format!(
+		"[cache]\n\
+		enabled = true\n\
+		directory = {:?}\n\
+		cleanup-interval = \"1h\"\n\
+		files-total-size-soft-limit = \"{}\"\n\
+		file-count-soft-limit = {}\n",
+		cache_path.join("artifacts").display().to_string(),
+		CACHE_SIZE_SOFT_LIMIT,
+		CACHE_FILE_COUNT_SOFT_LIMIT,
+	);
+	fs::write(&config_path, config_content)
+		.map_err(|e| format!("cannot write the cache config: {}", e))?;
+
+	config.cache_config_load(&config_path)
+		.map_err(|e| format!("cannot load the cache config: {}", e))?;
+	Ok(())
+}
+
 /// Call a function inside a precompiled Wasm module.
 fn call_method(
 	instance_wrapper: Rc<InstanceWrapper>,
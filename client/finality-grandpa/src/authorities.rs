@@ -30,7 +30,7 @@ use std::ops::Add;
 use std::sync::Arc;
 
 /// A shared authority set.
-pub(crate) struct SharedAuthoritySet<H, N> {
+pub struct SharedAuthoritySet<H, N> {
 	inner: Arc<RwLock<AuthoritySet<H, N>>>,
 }
 
@@ -66,6 +66,11 @@ where N: Add<Output=N> + Ord + Clone + Debug,
 	pub(crate) fn current_authorities(&self) -> VoterSet<AuthorityId> {
 		self.inner.read().current_authorities.iter().cloned().collect()
 	}
+
+	/// Get the block numbers at which the past authority sets ended.
+	pub(crate) fn authority_set_changes(&self) -> AuthoritySetChanges<N> {
+		self.inner.read().authority_set_changes.clone()
+	}
 }
 
 impl<H, N> From<AuthoritySet<H, N>> for SharedAuthoritySet<H, N> {
@@ -97,6 +102,10 @@ pub(crate) struct AuthoritySet<H, N> {
 	// Forced changes are enacted on block depth (not finality), for this reason
 	// only one forced change should exist per fork.
 	pub(crate) pending_forced_changes: Vec<PendingChange<H, N>>,
+	// Track at which blocks the set id changed. This is useful when we need to prove finality for a
+	// given block since we can figure out what set the block belongs to and when the set
+	// started/ended.
+	pub(crate) authority_set_changes: AuthoritySetChanges<N>,
 }
 
 impl<H, N> AuthoritySet<H, N>
@@ -110,6 +119,7 @@ where H: PartialEq,
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		}
 	}
 
@@ -281,6 +291,9 @@ where
 					set_id: self.set_id + 1,
 					pending_standard_changes: ForkTree::new(), // new set, new changes.
 					pending_forced_changes: Vec::new(),
+					// forced changes are not finalized through a justification so we
+					// can't prove the handoff, the chain of set changes is left as is.
+					authority_set_changes: self.authority_set_changes.clone(),
 				}));
 
 				break;
@@ -340,6 +353,8 @@ where
 						"block" => ?change.canon_height
 					);
 
+					// store the set id together with the last block number of the set
+					self.authority_set_changes.append(self.set_id, finalized_number.clone());
 					self.current_authorities = change.next_authorities;
 					self.set_id += 1;
 
@@ -383,6 +398,48 @@ where
 	}
 }
 
+/// Tracks historical authority set changes. We store the block numbers for the last block of each
+/// authority set, once they have been finalized. These blocks are guaranteed to have a
+/// justification unless they were triggered by a forced change.
+#[derive(Debug, Clone, Encode, Decode, PartialEq)]
+pub(crate) struct AuthoritySetChanges<N>(Vec<(u64, N)>);
+
+impl<N> AuthoritySetChanges<N> {
+	/// Create an empty list of authority set changes.
+	pub(crate) fn empty() -> Self {
+		Self(Vec::new())
+	}
+
+	/// Record that the set with the given id ended at the given (finalized) block.
+	pub(crate) fn append(&mut self, set_id: u64, block_number: N) {
+		self.0.push((set_id, block_number));
+	}
+}
+
+impl<N: Ord + Clone> AuthoritySetChanges<N> {
+	/// Get the id and the last block number of the set the given block belongs to, if
+	/// that set has already ended.
+	pub(crate) fn get_set_id(&self, block_number: N) -> Option<(u64, N)> {
+		let idx = self.0
+			.binary_search_by_key(&block_number, |(_, n)| n.clone())
+			.unwrap_or_else(|b| b);
+
+		self.0.get(idx).cloned()
+	}
+
+	/// Iterate over all the recorded set changes, starting with the first one that ends strictly
+	/// after the given block.
+	pub(crate) fn iter_from(&self, block_number: N) -> impl Iterator<Item = &(u64, N)> {
+		let idx = self.0
+			.binary_search_by_key(&block_number, |(_, n)| n.clone())
+			// if a set ended at the given block we start at the next one.
+			.map(|idx| idx + 1)
+			.unwrap_or_else(|b| b);
+
+		self.0[idx..].iter()
+	}
+}
+
 /// Kinds of delays for pending changes.
 #[derive(Debug, Clone, Encode, Decode, PartialEq)]
 pub(crate) enum DelayKind<N> {
@@ -462,6 +519,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let change = |height| {
@@ -507,6 +565,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let change_a = PendingChange {
@@ -574,6 +633,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let set_a = vec![(AuthorityId::from_slice(&[1; 32]), 5)];
@@ -640,6 +700,7 @@ mod tests {
 		assert_eq!(authorities.current_authorities, set_a);
 		assert_eq!(authorities.set_id, 1);
 		assert_eq!(authorities.pending_changes().count(), 0);
+		assert_eq!(authorities.authority_set_changes, AuthoritySetChanges(vec![(0, 15)]));
 	}
 
 	#[test]
@@ -649,6 +710,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let set_a = vec![(AuthorityId::from_slice(&[1; 32]), 5)];
@@ -727,6 +789,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let set_a = vec![(AuthorityId::from_slice(&[1; 32]), 5)];
@@ -793,6 +856,7 @@ mod tests {
 			set_id: 0,
 			pending_standard_changes: ForkTree::new(),
 			pending_forced_changes: Vec::new(),
+			authority_set_changes: AuthoritySetChanges::empty(),
 		};
 
 		let set_a = vec![(AuthorityId::from_slice(&[1; 32]), 5)];
@@ -868,7 +932,32 @@ mod tests {
 				set_id: 1,
 				pending_standard_changes: ForkTree::new(),
 				pending_forced_changes: Vec::new(),
+				authority_set_changes: AuthoritySetChanges::empty(),
 			}),
 		);
 	}
+
+	#[test]
+	fn authority_set_changes_lookup() {
+		let mut changes = AuthoritySetChanges::empty();
+		changes.append(0, 41);
+		changes.append(1, 81);
+		changes.append(2, 121);
+
+		assert_eq!(changes.get_set_id(20), Some((0, 41)));
+		assert_eq!(changes.get_set_id(41), Some((0, 41)));
+		assert_eq!(changes.get_set_id(42), Some((1, 81)));
+		assert_eq!(changes.get_set_id(121), Some((2, 121)));
+		assert_eq!(changes.get_set_id(122), None);
+
+		assert_eq!(
+			changes.iter_from(50).cloned().collect::<Vec<_>>(),
+			vec![(1, 81), (2, 121)],
+		);
+		assert_eq!(
+			changes.iter_from(81).cloned().collect::<Vec<_>>(),
+			vec![(2, 121)],
+		);
+		assert_eq!(changes.iter_from(122).count(), 0);
+	}
 }
@@ -27,7 +27,9 @@ use sp_runtime::traits::{Block as BlockT, NumberFor};
 use log::{info, warn};
 use sp_finality_grandpa::{AuthorityList, SetId, RoundNumber};
 
-use crate::authorities::{AuthoritySet, SharedAuthoritySet, PendingChange, DelayKind};
+use crate::authorities::{
+	AuthoritySet, AuthoritySetChanges, SharedAuthoritySet, PendingChange, DelayKind,
+};
 use crate::consensus_changes::{SharedConsensusChanges, ConsensusChanges};
 use crate::environment::{
 	CompletedRound, CompletedRounds, CurrentRounds, HasVoted, SharedVoterSetState, VoterSetState,
@@ -40,7 +42,7 @@ const CONCLUDED_ROUNDS: &[u8] = b"grandpa_concluded_rounds";
 const AUTHORITY_SET_KEY: &[u8] = b"grandpa_voters";
 const CONSENSUS_CHANGES_KEY: &[u8] = b"grandpa_consensus_changes";
 
-const CURRENT_VERSION: u32 = 2;
+const CURRENT_VERSION: u32 = 3;
 
 /// The voter set state.
 #[derive(Debug, Clone, Encode, Decode)]
@@ -101,7 +103,30 @@ where H: Clone + Debug + PartialEq,
 			current_authorities: self.current_authorities,
 			set_id: self.set_id,
 			pending_forced_changes: Vec::new(),
-			pending_standard_changes
+			pending_standard_changes,
+			authority_set_changes: AuthoritySetChanges::empty(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Encode, Decode, PartialEq)]
+struct V2AuthoritySet<H, N> {
+	current_authorities: AuthorityList,
+	set_id: SetId,
+	pending_standard_changes: ForkTree<H, N, PendingChange<H, N>>,
+	pending_forced_changes: Vec<PendingChange<H, N>>,
+}
+
+impl<H, N> Into<AuthoritySet<H, N>> for V2AuthoritySet<H, N> {
+	fn into(self) -> AuthoritySet<H, N> {
+		AuthoritySet {
+			current_authorities: self.current_authorities,
+			set_id: self.set_id,
+			pending_standard_changes: self.pending_standard_changes,
+			pending_forced_changes: self.pending_forced_changes,
+			// the set changes before the migration are unknown, we can only
+			// start tracking them from now on.
+			authority_set_changes: AuthoritySetChanges::empty(),
 		}
 	}
 }
@@ -195,10 +220,13 @@ fn migrate_from_version1<Block: BlockT, B, G>(
 		backend.insert_aux(&[(VERSION_KEY, s)], &[])
 	)?;
 
-	if let Some(set) = load_decode::<_, AuthoritySet<Block::Hash, NumberFor<Block>>>(
+	if let Some(old_set) = load_decode::<_, V2AuthoritySet<Block::Hash, NumberFor<Block>>>(
 		backend,
 		AUTHORITY_SET_KEY,
 	)? {
+		let set: AuthoritySet<Block::Hash, NumberFor<Block>> = old_set.into();
+		backend.insert_aux(&[(AUTHORITY_SET_KEY, set.encode().as_slice())], &[])?;
+
 		let set_id = set.current().0;
 
 		let completed_rounds = |number, state, base| CompletedRounds::new(
@@ -257,6 +285,50 @@ fn migrate_from_version1<Block: BlockT, B, G>(
 	Ok(None)
 }
 
+fn migrate_from_version2<Block: BlockT, B, G>(
+	backend: &B,
+	genesis_round: &G,
+) -> ClientResult<Option<(
+	AuthoritySet<Block::Hash, NumberFor<Block>>,
+	VoterSetState<Block>,
+)>> where B: AuxStore,
+		  G: Fn() -> RoundState<Block::Hash, NumberFor<Block>>,
+{
+	CURRENT_VERSION.using_encoded(|s|
+		backend.insert_aux(&[(VERSION_KEY, s)], &[])
+	)?;
+
+	if let Some(old_set) = load_decode::<_, V2AuthoritySet<Block::Hash, NumberFor<Block>>>(
+		backend,
+		AUTHORITY_SET_KEY,
+	)? {
+		let new_set: AuthoritySet<Block::Hash, NumberFor<Block>> = old_set.into();
+		backend.insert_aux(&[(AUTHORITY_SET_KEY, new_set.encode().as_slice())], &[])?;
+
+		let set_state = match load_decode::<_, VoterSetState<Block>>(
+			backend,
+			SET_STATE_KEY,
+		)? {
+			Some(state) => state,
+			None => {
+				let state = genesis_round();
+				let base = state.prevote_ghost
+					.expect("state is for completed round; completed rounds must have a prevote ghost; qed.");
+
+				VoterSetState::live(
+					new_set.current().0,
+					&new_set,
+					base,
+				)
+			}
+		};
+
+		return Ok(Some((new_set, set_state)));
+	}
+
+	Ok(None)
+}
+
 /// Load or initialize persistent data from backend.
 pub(crate) fn load_persistent<Block: BlockT, B, G>(
 	backend: &B,
@@ -295,6 +367,15 @@ pub(crate) fn load_persistent<Block: BlockT, B, G>(
 			}
 		},
 		Some(2) => {
+			if let Some((new_set, set_state)) = migrate_from_version2::<Block, _, _>(backend, &make_genesis_round)? {
+				return Ok(PersistentData {
+					authority_set: new_set.into(),
+					consensus_changes: Arc::new(consensus_changes.into()),
+					set_state: set_state.into(),
+				});
+			}
+		},
+		Some(3) => {
 			if let Some(set) = load_decode::<_, AuthoritySet<Block::Hash, NumberFor<Block>>>(
 				backend,
 				AUTHORITY_SET_KEY,
@@ -491,7 +572,7 @@ mod test {
 
 		assert_eq!(
 			load_decode::<_, u32>(&client, VERSION_KEY).unwrap(),
-			Some(2),
+			Some(3),
 		);
 
 		let PersistentData { authority_set, set_state, .. } = load_persistent::<substrate_test_runtime_client::runtime::Block, _, _>(
@@ -508,6 +589,7 @@ mod test {
 				pending_standard_changes: ForkTree::new(),
 				pending_forced_changes: Vec::new(),
 				set_id,
+				authority_set_changes: AuthoritySetChanges::empty(),
 			},
 		);
 
@@ -547,7 +629,7 @@ mod test {
 		};
 
 		{
-			let authority_set = AuthoritySet::<H256, u64> {
+			let authority_set = V2AuthoritySet::<H256, u64> {
 				current_authorities: authorities.clone(),
 				pending_standard_changes: ForkTree::new(),
 				pending_forced_changes: Vec::new(),
@@ -581,7 +663,7 @@ mod test {
 
 		assert_eq!(
 			load_decode::<_, u32>(&client, VERSION_KEY).unwrap(),
-			Some(2),
+			Some(3),
 		);
 
 		let PersistentData { authority_set, set_state, .. } = load_persistent::<substrate_test_runtime_client::runtime::Block, _, _>(
@@ -598,6 +680,7 @@ mod test {
 				pending_standard_changes: ForkTree::new(),
 				pending_forced_changes: Vec::new(),
 				set_id,
+				authority_set_changes: AuthoritySetChanges::empty(),
 			},
 		);
 
@@ -622,6 +705,76 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn load_decode_from_v2_migrates_data_format() {
+		let client = substrate_test_runtime_client::new();
+
+		let authorities = vec![(AuthorityId::default(), 100)];
+		let set_id = 3;
+
+		{
+			let authority_set = V2AuthoritySet::<H256, u64> {
+				current_authorities: authorities.clone(),
+				pending_standard_changes: ForkTree::new(),
+				pending_forced_changes: Vec::new(),
+				set_id,
+			};
+
+			let genesis_state = (H256::random(), 32);
+			let voter_set_state: VoterSetState<substrate_test_runtime_client::runtime::Block> =
+				VoterSetState::live(
+					set_id,
+					&authority_set.clone().into(),
+					genesis_state
+				);
+
+			client.insert_aux(
+				&[
+					(AUTHORITY_SET_KEY, authority_set.encode().as_slice()),
+					(SET_STATE_KEY, voter_set_state.encode().as_slice()),
+					(VERSION_KEY, 2u32.encode().as_slice()),
+				],
+				&[],
+			).unwrap();
+		}
+
+		assert_eq!(
+			load_decode::<_, u32>(&client, VERSION_KEY).unwrap(),
+			Some(2),
+		);
+
+		// should perform the migration
+		load_persistent::<substrate_test_runtime_client::runtime::Block, _, _>(
+			&client,
+			H256::random(),
+			0,
+			|| unreachable!(),
+		).unwrap();
+
+		assert_eq!(
+			load_decode::<_, u32>(&client, VERSION_KEY).unwrap(),
+			Some(3),
+		);
+
+		let PersistentData { authority_set, .. } = load_persistent::<substrate_test_runtime_client::runtime::Block, _, _>(
+			&client,
+			H256::random(),
+			0,
+			|| unreachable!(),
+		).unwrap();
+
+		assert_eq!(
+			*authority_set.inner().read(),
+			AuthoritySet {
+				current_authorities: authorities.clone(),
+				pending_standard_changes: ForkTree::new(),
+				pending_forced_changes: Vec::new(),
+				set_id,
+				authority_set_changes: AuthoritySetChanges::empty(),
+			},
+		);
+	}
+
 	#[test]
 	fn write_read_concluded_rounds() {
 		let client = substrate_test_runtime_client::new();
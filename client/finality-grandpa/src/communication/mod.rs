@@ -610,29 +610,7 @@ impl<B: BlockT, N: Network<B>> Clone for NetworkBridge<B, N> {
 	}
 }
 
-/// Encode round message localized to a given round and set id.
-pub(crate) fn localized_payload<E: Encode>(
-	round: RoundNumber,
-	set_id: SetIdNumber,
-	message: &E,
-) -> Vec<u8> {
-	let mut buf = Vec::new();
-	localized_payload_with_buffer(round, set_id, message, &mut buf);
-	buf
-}
-
-/// Encode round message localized to a given round and set id using the given
-/// buffer. The given buffer will be cleared and the resulting encoded payload
-/// will always be written to the start of the buffer.
-pub(crate) fn localized_payload_with_buffer<E: Encode>(
-	round: RoundNumber,
-	set_id: SetIdNumber,
-	message: &E,
-	buf: &mut Vec<u8>,
-) {
-	buf.clear();
-	(message, round, set_id).encode_to(buf)
-}
+pub(crate) use sp_finality_grandpa::{localized_payload, localized_payload_with_buffer};
 
 /// Type-safe wrapper around a round number.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Encode, Decode)]
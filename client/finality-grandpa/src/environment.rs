@@ -34,7 +34,7 @@ use finality_grandpa::{
 	BlockNumberOps, Equivocation, Error as GrandpaError, round::State as RoundState,
 	voter, voter_set::VoterSet,
 };
-use sp_core::Pair;
+use sp_core::{Pair, ExecutionContext};
 use sp_runtime::generic::BlockId;
 use sp_runtime::traits::{
 	Block as BlockT, Header as HeaderT, NumberFor, One, Zero,
@@ -54,7 +54,10 @@ use crate::consensus_changes::SharedConsensusChanges;
 use crate::justification::GrandpaJustification;
 use crate::until_imported::UntilVoteTargetImported;
 use crate::voting_rule::VotingRule;
-use sp_finality_grandpa::{AuthorityId, AuthoritySignature, SetId, RoundNumber};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_finality_grandpa::{
+	AuthorityId, AuthoritySignature, EquivocationProof, GrandpaApi, SetId, RoundNumber,
+};
 use prometheus_endpoint::{Gauge, U64, register, PrometheusError};
 
 type HistoricalVotes<Block> = finality_grandpa::HistoricalVotes<
@@ -431,6 +434,114 @@ impl<Backend, Block: BlockT, C, N: NetworkT<Block>, SC, VR> Environment<Backend,
 	}
 }
 
+impl<BE, Block, C, N, SC, VR> Environment<BE, Block, C, N, SC, VR>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	C: crate::ClientForGrandpa<Block, BE>,
+	C::Api: GrandpaApi<Block, Error = sp_blockchain::Error>,
+	N: NetworkT<Block>,
+	SC: SelectChain<Block> + 'static,
+{
+	/// Report the given equivocation to the GRANDPA runtime module. This method
+	/// generates a session membership proof of the offender and then submits an
+	/// extrinsic to report the equivocation. In particular, the session membership
+	/// proof must be generated at the block at which the given set was active which
+	/// isn't necessarily the best block if there are pending authority set changes.
+	fn report_equivocation(
+		&self,
+		equivocation: sp_finality_grandpa::Equivocation<Block::Hash, NumberFor<Block>>,
+	) -> Result<(), Error> {
+		let local_id = crate::is_voter(&self.voters, &self.config.keystore);
+		if local_id.map(|pair| &pair.public() == equivocation.offender()).unwrap_or(false) {
+			return Ok(());
+		}
+
+		let is_descendent_of = is_descendent_of(&*self.client, None);
+
+		let best_header = self.select_chain
+			.best_chain()
+			.map_err(|e| Error::Blockchain(e.to_string()))?;
+
+		// the runtime starts tracking the next set as soon as a change is
+		// signaled, so we must find the latest block in the best chain before
+		// any pending change was signaled, that block is the latest one at
+		// which the current set is still the one known to the runtime.
+		let next_change = self.authority_set.inner().read()
+			.pending_changes()
+			.filter(|change| {
+				change.canon_hash == best_header.hash() ||
+					is_descendent_of(&change.canon_hash, &best_header.hash()).unwrap_or(false)
+			})
+			.min_by_key(|change| change.canon_height)
+			.map(|change| change.canon_hash);
+
+		let current_set_latest_hash = match next_change {
+			Some(canon_hash) => {
+				let header = self.client.header(BlockId::Hash(canon_hash))?
+					.ok_or_else(|| Error::Blockchain(format!(
+						"Header of pending change {:?} not found in the database", canon_hash,
+					)))?;
+
+				// the parent of the block that signaled the change is the
+				// latest block in the current set.
+				*header.parent_hash()
+			},
+			// there is no pending change, the latest block for the current set is
+			// the best block.
+			None => best_header.hash(),
+		};
+
+		let runtime_api = self.client.runtime_api();
+		let current_set_latest_id = BlockId::Hash(current_set_latest_hash);
+		let best_id = BlockId::Hash(best_header.hash());
+
+		// equivocation reporting was only added in version 3 of the runtime API.
+		let has_reporting_api = |at: &BlockId<Block>| runtime_api
+			.has_api_with::<dyn GrandpaApi<Block, Error = sp_blockchain::Error>, _>(at, |v| v >= 3)
+			.map_err(Error::Client);
+
+		if !has_reporting_api(&current_set_latest_id)? || !has_reporting_api(&best_id)? {
+			debug!(target: "afg", "Runtime does not support equivocation reporting.");
+			return Ok(());
+		}
+
+		// generate key ownership proof at that block
+		let key_owner_proof = match runtime_api
+			.generate_key_ownership_proof(
+				&current_set_latest_id,
+				self.set_id,
+				equivocation.offender().clone(),
+			)
+			.map_err(Error::Client)?
+		{
+			Some(proof) => proof,
+			None => {
+				debug!(target: "afg", "Equivocation offender is not part of the authority set.");
+				return Ok(());
+			},
+		};
+
+		// submit equivocation report at **best** block, in an offchain context
+		// so that the keystore and transaction pool extensions are available.
+		let equivocation_proof = EquivocationProof::new(self.set_id, equivocation);
+		let submitted = runtime_api
+			.submit_report_equivocation_unsigned_extrinsic_with_context(
+				&best_id,
+				ExecutionContext::OffchainCall(None),
+				equivocation_proof,
+				key_owner_proof,
+			)
+			.map_err(Error::Client)?;
+
+		if submitted.is_none() {
+			debug!(target: "afg", "Equivocation reporting is disabled in the runtime.");
+		}
+
+		Ok(())
+	}
+}
+
 impl<BE, Block: BlockT, C, N, SC, VR>
 	finality_grandpa::Chain<Block::Hash, NumberFor<Block>>
 for Environment<BE, Block, C, N, SC, VR>
@@ -580,6 +691,7 @@ where
 	Block: 'static,
 	B: Backend<Block>,
 	C: crate::ClientForGrandpa<Block, B> + 'static,
+	C::Api: GrandpaApi<Block, Error = sp_blockchain::Error>,
  	N: NetworkT<Block> + 'static + Send,
 	SC: SelectChain<Block> + 'static,
 	VR: VotingRule<Block, C>,
@@ -929,7 +1041,9 @@ where
 		equivocation: ::finality_grandpa::Equivocation<Self::Id, Prevote<Block>, Self::Signature>
 	) {
 		warn!(target: "afg", "Detected prevote equivocation in the finality worker: {:?}", equivocation);
-		// nothing yet; this could craft misbehavior reports of some kind.
+		if let Err(err) = self.report_equivocation(equivocation.into()) {
+			warn!(target: "afg", "Error reporting prevote equivocation: {:?}", err);
+		}
 	}
 
 	fn precommit_equivocation(
@@ -938,7 +1052,9 @@ where
 		equivocation: Equivocation<Self::Id, Precommit<Block>, Self::Signature>
 	) {
 		warn!(target: "afg", "Detected precommit equivocation in the finality worker: {:?}", equivocation);
-		// nothing yet
+		if let Err(err) = self.report_equivocation(equivocation.into()) {
+			warn!(target: "afg", "Error reporting precommit equivocation: {:?}", err);
+		}
 	}
 }
 
@@ -180,7 +180,7 @@ impl<'a, Block: 'a + BlockT> Drop for PendingSetChanges<'a, Block> {
 	}
 }
 
-fn find_scheduled_change<B: BlockT>(header: &B::Header)
+pub(crate) fn find_scheduled_change<B: BlockT>(header: &B::Header)
 	-> Option<ScheduledChange<NumberFor<B>>>
 {
 	let id = OpaqueDigestItemId::Consensus(&GRANDPA_ENGINE_ID);
@@ -422,7 +422,14 @@ impl<BE, Block: BlockT, Client, SC> BlockImport<Block>
 		// early exit if block already in chain, otherwise the check for
 		// authority changes will error when trying to re-import a change block
 		match self.inner.status(BlockId::Hash(hash)) {
-			Ok(BlockStatus::InChain) => return Ok(ImportResult::AlreadyInChain),
+			Ok(BlockStatus::InChain) if !block.import_existing => return Ok(ImportResult::AlreadyInChain),
+			Ok(BlockStatus::InChain) => {
+				// authority set changes and justifications were handled when the block was
+				// first imported (e.g. without state during fast sync), only execute it now.
+				block.justification = None;
+				block.finalized = false;
+				return (&*self.inner).import_block(block, new_cache)
+			},
 			Ok(BlockStatus::Unknown) => {},
 			Err(e) => return Err(ConsensusError::ClientImport(e.to_string()).into()),
 		}
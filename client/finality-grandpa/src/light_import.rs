@@ -17,9 +17,11 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use log::{info, trace, warn};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use sc_client::light::blockchain::Blockchain as LightBlockchain;
 use sc_client_api::{
 	backend::{AuxStore, Backend, Finalizer, TransactionFor},
+	light::Storage as LightStorage,
 };
 use sp_blockchain::{HeaderBackend, Error as ClientError, well_known_cache_keys};
 use parity_scale_codec::{Encode, Decode};
@@ -28,7 +30,9 @@ use sp_consensus::{
 	BlockOrigin, BlockImport, FinalityProofImport, BlockImportParams, ImportResult, ImportedAux,
 	BlockCheckParams, Error as ConsensusError,
 };
-use sc_network::config::{BoxFinalityProofRequestBuilder, FinalityProofRequestBuilder};
+use sc_network::config::{
+	BoxFinalityProofRequestBuilder, FinalityProofRequestBuilder, WarpSyncProvider, WarpSyncVerification,
+};
 use sp_runtime::Justification;
 use sp_runtime::traits::{NumberFor, Block as BlockT, Header as HeaderT, DigestFor};
 use sp_finality_grandpa::{self, AuthorityList};
@@ -42,6 +46,7 @@ use crate::finality_proof::{
 	AuthoritySetForFinalityChecker, ProvableJustification, make_finality_proof_request,
 };
 use crate::justification::GrandpaJustification;
+use crate::warp_proof::verify_encoded_proof;
 
 /// LightAuthoritySet is saved under this key in aux storage.
 const LIGHT_AUTHORITY_SET_KEY: &[u8] = b"grandpa_voters";
@@ -115,6 +120,27 @@ impl<BE, Block: BlockT, Client> GrandpaLightBlockImport<BE, Block, Client> {
 	pub fn create_finality_proof_request_builder(&self) -> BoxFinalityProofRequestBuilder<Block> {
 		Box::new(GrandpaFinalityProofRequestBuilder(self.data.clone())) as _
 	}
+
+	/// Create a warp sync provider, which verifies warp sync proofs against the authority set
+	/// tracked by this importer and imports the warp sync target into the given blockchain.
+	pub fn create_warp_sync_provider<S>(
+		&self,
+		blockchain: Arc<LightBlockchain<S>>,
+	) -> Arc<dyn WarpSyncProvider<Block>> where
+		NumberFor<Block>: finality_grandpa::BlockNumberOps,
+		S: LightStorage<Block> + 'static,
+	{
+		let progress = {
+			let data = self.data.read();
+			(data.authority_set.set_id(), data.authority_set.authorities())
+		};
+
+		Arc::new(LightWarpSyncProvider {
+			blockchain,
+			data: self.data.clone(),
+			progress: Mutex::new(progress),
+		})
+	}
 }
 
 impl<BE, Block: BlockT, Client> BlockImport<Block>
@@ -236,6 +262,50 @@ impl<B: BlockT> FinalityProofRequestBuilder<B> for GrandpaFinalityProofRequestBu
 	}
 }
 
+/// Warp sync provider of light clients.
+struct LightWarpSyncProvider<Block: BlockT, S> {
+	blockchain: Arc<LightBlockchain<S>>,
+	data: Arc<RwLock<LightImportData<Block>>>,
+	/// The latest authority set that has been proven by the warp sync proofs received so far.
+	progress: Mutex<(sp_finality_grandpa::SetId, AuthorityList)>,
+}
+
+impl<Block: BlockT, S> WarpSyncProvider<Block> for LightWarpSyncProvider<Block, S> where
+	NumberFor<Block>: finality_grandpa::BlockNumberOps,
+	S: LightStorage<Block>,
+{
+	fn generate(&self, _begin: Block::Hash) -> Result<Vec<u8>, ClientError> {
+		Err(ClientError::Msg("Light clients can't generate warp sync proofs".into()))
+	}
+
+	fn verify(&self, proof: &[u8]) -> Result<WarpSyncVerification<Block>, ClientError> {
+		let (verification, set_id, _) = verify_encoded_proof::<Block>(proof, &self.progress)?;
+		trace!(target: "afg", "Verified warp sync proof, now at authority set {}", set_id);
+		Ok(verification)
+	}
+
+	fn import_target(&self, target: Block::Header) -> Result<(), ClientError> {
+		let (set_id, authorities) = self.progress.lock().clone();
+		let hash = target.hash();
+		let number = *target.number();
+
+		// the authority set is stored in the same transaction as the header, so that we don't
+		// end up with a finalized block and a stale set after a restart.
+		let authority_set = LightAuthoritySet { set_id, authorities: authorities.clone() };
+		self.blockchain.storage().import_warp_target(
+			target,
+			vec![(LIGHT_AUTHORITY_SET_KEY.to_vec(), Some(authority_set.encode()))],
+		)?;
+
+		let mut data = self.data.write();
+		data.last_finalized = hash;
+		data.authority_set.update(set_id, authorities);
+
+		info!(target: "afg", "Imported warp sync target #{} ({}) with authority set {}", number, hash, set_id);
+		Ok(())
+	}
+}
+
 /// Try to import new block.
 fn do_import_block<B, C, Block: BlockT, J>(
 	mut client: C,
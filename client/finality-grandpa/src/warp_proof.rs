@@ -0,0 +1,442 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! GRANDPA warp sync proof generation and check.
+//!
+//! A warp sync proof lets a node that knows the authority set at some finalized block B
+//! verify the finality of a much more recent block, without downloading the headers
+//! in between. It is made of one fragment per authority set change since B: the last
+//! block of each set (which signals the next set through a `ScheduledChange` digest)
+//! together with its justification, signed by the set that is ending. The proof is
+//! optionally terminated with the latest justification known for the current set.
+//!
+//! Proofs are capped in size. An unfinished proof ends at a set change and the
+//! requester should continue from its last block.
+//!
+//! Authority set changes that have been forced (rather than finalized) can't be proven this
+//! way, so proofs stop at the first forced change.
+
+use std::sync::Arc;
+
+use finality_grandpa::BlockNumberOps;
+use parity_scale_codec::{Decode, Encode};
+use parking_lot::Mutex;
+use sc_client_api::backend::Backend;
+use sc_network::config::{WarpSyncProvider, WarpSyncVerification};
+use sp_blockchain::{Backend as BlockchainBackend, Error as ClientError, Result as ClientResult};
+use sp_finality_grandpa::{AuthorityList, SetId};
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor, One, Saturating, Zero};
+
+use crate::authorities::{AuthoritySetChanges, SharedAuthoritySet};
+use crate::import::find_scheduled_change;
+use crate::justification::GrandpaJustification;
+
+/// The maximum size in bytes of a warp sync proof.
+const MAX_WARP_SYNC_PROOF_SIZE: usize = 8 * 1024 * 1024;
+
+/// The maximum number of blocks below the last finalized block that are searched for the latest
+/// justification. Justifications are stored at least every `justification_period` blocks, so
+/// this only needs to cover a few periods.
+const MAX_JUSTIFICATION_SEARCH_DEPTH: u32 = 4096;
+
+/// A proof of an authority set change.
+#[derive(Decode, Encode)]
+pub struct WarpSyncFragment<Block: BlockT> {
+	/// The last block that the given authority set finalized. This block should contain a
+	/// digest signaling an authority set change from which we can fetch the next authority
+	/// set, unless this is the last fragment of a finished proof.
+	pub header: Block::Header,
+	/// A justification for the header above which proves its finality. In order to validate
+	/// it the verifier must be aware of the authorities and set id that the justification
+	/// refers to.
+	pub justification: GrandpaJustification<Block>,
+}
+
+/// An accumulated proof of multiple authority set changes.
+#[derive(Decode, Encode)]
+pub struct WarpSyncProof<Block: BlockT> {
+	proofs: Vec<WarpSyncFragment<Block>>,
+	is_finished: bool,
+}
+
+impl<Block: BlockT> WarpSyncProof<Block> {
+	/// Generate a proof of all the authority set changes that have been finalized after the
+	/// given block, up to the current authority set and capped by `MAX_WARP_SYNC_PROOF_SIZE`.
+	pub(crate) fn generate<B>(
+		blockchain: &B,
+		begin: Block::Hash,
+		set_changes: &AuthoritySetChanges<NumberFor<Block>>,
+	) -> ClientResult<WarpSyncProof<Block>>
+	where
+		B: BlockchainBackend<Block>,
+	{
+		let begin_number = blockchain.number(begin)?
+			.ok_or_else(|| ClientError::UnknownBlock(format!("{}", begin)))?;
+
+		let info = blockchain.info();
+		if begin_number > info.finalized_number || blockchain.hash(begin_number)? != Some(begin) {
+			return Err(ClientError::Msg(
+				"Warp sync proofs can only be generated from a finalized block".into(),
+			));
+		}
+
+		let mut proofs = Vec::new();
+		let mut proofs_encoded_len = 0;
+		let mut proof_limit_reached = false;
+
+		for (_, last_block) in set_changes.iter_from(begin_number) {
+			let header = blockchain.header(BlockId::Number(*last_block))?
+				.ok_or_else(|| ClientError::UnknownBlock(format!("{}", last_block)))?;
+
+			// the last block of a set must signal the change to the next set. If it doesn't,
+			// the set has been replaced through a forced change and the chain of trust in
+			// authority set handoffs is broken, so we can't go any further.
+			if find_scheduled_change::<Block>(&header).is_none() {
+				break;
+			}
+
+			let justification = blockchain.justification(BlockId::Number(*last_block))?
+				.ok_or_else(|| ClientError::Msg(
+					format!("Missing justification for authority set change at #{}", last_block),
+				))?;
+			let justification = GrandpaJustification::<Block>::decode(&mut &justification[..])
+				.map_err(|_| ClientError::JustificationDecode)?;
+
+			let fragment = WarpSyncFragment { header, justification };
+			let fragment_size = fragment.encoded_size();
+
+			// leave some room for the encoding of the vector length and `is_finished`.
+			if proofs_encoded_len + fragment_size >= MAX_WARP_SYNC_PROOF_SIZE - 50 {
+				proof_limit_reached = true;
+				break;
+			}
+
+			proofs_encoded_len += fragment_size;
+			proofs.push(fragment);
+		}
+
+		let is_finished = if proof_limit_reached {
+			false
+		} else {
+			// try to finish the proof with the latest justification of the current set.
+			let limit = proofs.last()
+				.map(|fragment| *fragment.header.number() + One::one())
+				.unwrap_or(begin_number + One::one());
+
+			if let Some(fragment) = latest_justification(blockchain, limit, info.finalized_number)? {
+				proofs.push(fragment);
+			}
+
+			true
+		};
+
+		Ok(WarpSyncProof { proofs, is_finished })
+	}
+
+	/// Verify the proof against the authority set with the given id and authorities. On
+	/// success, the set id and authorities we end up with are returned, together with the
+	/// header of the last block that has been proven final.
+	pub(crate) fn verify(
+		&self,
+		set_id: SetId,
+		authorities: AuthorityList,
+	) -> ClientResult<(SetId, AuthorityList, Block::Header)>
+	where
+		NumberFor<Block>: BlockNumberOps,
+	{
+		let mut current_set_id = set_id;
+		let mut current_authorities = authorities;
+
+		for (fragment_num, fragment) in self.proofs.iter().enumerate() {
+			let voters = current_authorities.iter().cloned().collect();
+			fragment.justification.verify(current_set_id, &voters)?;
+
+			if fragment.justification.commit.target_hash != fragment.header.hash() {
+				return Err(ClientError::BadJustification(
+					"mismatch between header and justification".into(),
+				));
+			}
+
+			if let Some(scheduled_change) = find_scheduled_change::<Block>(&fragment.header) {
+				current_authorities = scheduled_change.next_authorities;
+				current_set_id += 1;
+			} else if fragment_num != self.proofs.len() - 1 || !self.is_finished {
+				// only the last fragment of a finished proof may not signal a set change.
+				return Err(ClientError::BadJustification(
+					"header is missing authority set change digest".into(),
+				));
+			}
+		}
+
+		let target = self.proofs.last()
+			.map(|fragment| fragment.header.clone())
+			.ok_or_else(|| ClientError::BadJustification("empty warp sync proof".into()))?;
+
+		Ok((current_set_id, current_authorities, target))
+	}
+
+	/// Whether the proof reaches the current authority set of the node that generated it.
+	pub(crate) fn is_finished(&self) -> bool {
+		self.is_finished
+	}
+}
+
+/// Find the most recent justification for a block in the `[limit; finalized]` range, searching
+/// at most `MAX_JUSTIFICATION_SEARCH_DEPTH` blocks.
+fn latest_justification<Block: BlockT, B: BlockchainBackend<Block>>(
+	blockchain: &B,
+	limit: NumberFor<Block>,
+	finalized: NumberFor<Block>,
+) -> ClientResult<Option<WarpSyncFragment<Block>>> {
+	let limit = std::cmp::max(limit, finalized.saturating_sub(MAX_JUSTIFICATION_SEARCH_DEPTH.into()));
+	let mut number = finalized;
+	while number >= limit {
+		if let Some(justification) = blockchain.justification(BlockId::Number(number))? {
+			let header = blockchain.header(BlockId::Number(number))?
+				.ok_or_else(|| ClientError::UnknownBlock(format!("{}", number)))?;
+			let justification = GrandpaJustification::<Block>::decode(&mut &justification[..])
+				.map_err(|_| ClientError::JustificationDecode)?;
+
+			return Ok(Some(WarpSyncFragment { header, justification }));
+		}
+
+		if number.is_zero() {
+			break;
+		}
+		number = number - One::one();
+	}
+
+	Ok(None)
+}
+
+/// Verify a SCALE-encoded warp sync proof received from a peer, advancing the given authority
+/// set if it is valid.
+pub(crate) fn verify_encoded_proof<Block: BlockT>(
+	proof: &[u8],
+	authority_set: &Mutex<(SetId, AuthorityList)>,
+) -> ClientResult<(WarpSyncVerification<Block>, SetId, AuthorityList)>
+where
+	NumberFor<Block>: BlockNumberOps,
+{
+	let proof = WarpSyncProof::<Block>::decode(&mut &proof[..])
+		.map_err(|_| ClientError::BadJustification("failed to decode warp sync proof".into()))?;
+
+	let mut authority_set = authority_set.lock();
+	let (set_id, authorities) = authority_set.clone();
+	let (set_id, authorities, target) = proof.verify(set_id, authorities)?;
+	*authority_set = (set_id, authorities.clone());
+
+	let verification = if proof.is_finished() {
+		WarpSyncVerification::Complete(target)
+	} else {
+		WarpSyncVerification::Partial(target.hash())
+	};
+
+	Ok((verification, set_id, authorities))
+}
+
+/// Serves warp sync proofs to the network from a full node.
+pub struct NetworkProvider<Block: BlockT, BE> {
+	backend: Arc<BE>,
+	authority_set: SharedAuthoritySet<Block::Hash, NumberFor<Block>>,
+}
+
+impl<Block: BlockT, BE> NetworkProvider<Block, BE> {
+	/// Create a new provider, using the authority set tracked by the GRANDPA block import
+	/// (see `LinkHalf::shared_authority_set`).
+	pub fn new(
+		backend: Arc<BE>,
+		authority_set: SharedAuthoritySet<Block::Hash, NumberFor<Block>>,
+	) -> Self {
+		NetworkProvider { backend, authority_set }
+	}
+}
+
+impl<Block: BlockT, BE> WarpSyncProvider<Block> for NetworkProvider<Block, BE>
+where
+	BE: Backend<Block> + 'static,
+{
+	fn generate(&self, begin: Block::Hash) -> ClientResult<Vec<u8>> {
+		let proof = WarpSyncProof::<Block>::generate(
+			self.backend.blockchain(),
+			begin,
+			&self.authority_set.authority_set_changes(),
+		)?;
+
+		Ok(proof.encode())
+	}
+
+	fn verify(&self, _proof: &[u8]) -> ClientResult<WarpSyncVerification<Block>> {
+		Err(ClientError::Msg("Warp sync is only supported by light clients".into()))
+	}
+
+	fn import_target(&self, _target: Block::Header) -> ClientResult<()> {
+		Err(ClientError::Msg("Warp sync is only supported by light clients".into()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::communication;
+	use sc_client_api::NewBlockState;
+	use sp_finality_grandpa::{ConsensusLog, ScheduledChange, GRANDPA_ENGINE_ID};
+	use sp_keyring::Ed25519Keyring;
+	use sp_runtime::generic::DigestItem;
+	use substrate_test_runtime_client::runtime::{Block, Header, H256};
+	use substrate_test_runtime_client::sc_client::in_mem::Blockchain as InMemoryBlockchain;
+
+	fn header(number: u64, parent_hash: H256, next_authorities: Option<&[Ed25519Keyring]>) -> Header {
+		let mut header = Header::new(number, Default::default(), Default::default(), parent_hash, Default::default());
+		if let Some(next_authorities) = next_authorities {
+			let change = ScheduledChange {
+				next_authorities: next_authorities.iter().map(|k| (k.public().into(), 1)).collect(),
+				delay: 0,
+			};
+			header.digest_mut().push(DigestItem::Consensus(
+				GRANDPA_ENGINE_ID,
+				ConsensusLog::ScheduledChange(change).encode(),
+			));
+		}
+		header
+	}
+
+	fn justification(
+		blockchain: &Arc<InMemoryBlockchain<Block>>,
+		header: &Header,
+		set_id: SetId,
+		signer: Ed25519Keyring,
+	) -> Vec<u8> {
+		let round = 1;
+		let precommit = finality_grandpa::Precommit {
+			target_hash: header.hash(),
+			target_number: *header.number(),
+		};
+
+		let msg = finality_grandpa::Message::Precommit(precommit.clone());
+		let encoded = communication::localized_payload(round, set_id, &msg);
+		let precommit = finality_grandpa::SignedPrecommit {
+			precommit,
+			signature: signer.sign(&encoded[..]).into(),
+			id: signer.public().into(),
+		};
+
+		let commit = finality_grandpa::Commit {
+			target_hash: header.hash(),
+			target_number: *header.number(),
+			precommits: vec![precommit],
+		};
+
+		GrandpaJustification::from_commit(blockchain, round, commit).unwrap().encode()
+	}
+
+	fn authorities(keys: &[Ed25519Keyring]) -> AuthorityList {
+		keys.iter().map(|k| (k.public().into(), 1)).collect()
+	}
+
+	/// Builds a chain of 12 blocks where Alice hands over to Bob at #5, Bob hands over to
+	/// Charlie at #10 and Charlie finalizes #12.
+	fn test_blockchain() -> (Arc<InMemoryBlockchain<Block>>, AuthoritySetChanges<u64>, Vec<Header>) {
+		let blockchain = Arc::new(InMemoryBlockchain::<Block>::new());
+		let mut headers: Vec<Header> = Vec::new();
+		let mut set_changes = AuthoritySetChanges::empty();
+
+		for number in 0..=12 {
+			let parent_hash = headers.last().map(|h| h.hash()).unwrap_or_default();
+			let (next_authorities, signer) = match number {
+				5 => (Some(&[Ed25519Keyring::Bob][..]), Some((0, Ed25519Keyring::Alice))),
+				10 => (Some(&[Ed25519Keyring::Charlie][..]), Some((1, Ed25519Keyring::Bob))),
+				12 => (None, Some((2, Ed25519Keyring::Charlie))),
+				_ => (None, None),
+			};
+
+			let header = header(number, parent_hash, next_authorities);
+			let justification = signer.map(|(set_id, signer)| justification(&blockchain, &header, set_id, signer));
+			blockchain.insert(header.hash(), header.clone(), justification, None, NewBlockState::Final).unwrap();
+			if next_authorities.is_some() {
+				set_changes.append(signer.unwrap().0, number);
+			}
+			headers.push(header);
+		}
+
+		(blockchain, set_changes, headers)
+	}
+
+	#[test]
+	fn warp_proof_from_genesis_reaches_latest_justification() {
+		let (blockchain, set_changes, headers) = test_blockchain();
+
+		let proof = WarpSyncProof::<Block>::generate(&*blockchain, headers[0].hash(), &set_changes).unwrap();
+		assert!(proof.is_finished());
+		assert_eq!(
+			proof.proofs.iter().map(|f| *f.header.number()).collect::<Vec<_>>(),
+			vec![5, 10, 12],
+		);
+
+		let (set_id, next_authorities, target) = proof.verify(
+			0,
+			authorities(&[Ed25519Keyring::Alice]),
+		).unwrap();
+		assert_eq!(set_id, 2);
+		assert_eq!(next_authorities, authorities(&[Ed25519Keyring::Charlie]));
+		assert_eq!(target, headers[12]);
+
+		// the proof doesn't verify against another initial set.
+		assert!(proof.verify(0, authorities(&[Ed25519Keyring::Bob])).is_err());
+		assert!(proof.verify(1, authorities(&[Ed25519Keyring::Alice])).is_err());
+	}
+
+	#[test]
+	fn warp_proof_skips_known_set_changes() {
+		let (blockchain, set_changes, headers) = test_blockchain();
+
+		let proof = WarpSyncProof::<Block>::generate(&*blockchain, headers[5].hash(), &set_changes).unwrap();
+		assert_eq!(
+			proof.proofs.iter().map(|f| *f.header.number()).collect::<Vec<_>>(),
+			vec![10, 12],
+		);
+
+		let (set_id, _, target) = proof.verify(1, authorities(&[Ed25519Keyring::Bob])).unwrap();
+		assert_eq!(set_id, 2);
+		assert_eq!(target, headers[12]);
+	}
+
+	#[test]
+	fn unfinished_warp_proof_must_end_with_set_change() {
+		let (blockchain, set_changes, headers) = test_blockchain();
+
+		let mut proof = WarpSyncProof::<Block>::generate(&*blockchain, headers[0].hash(), &set_changes).unwrap();
+		proof.is_finished = false;
+		assert!(proof.verify(0, authorities(&[Ed25519Keyring::Alice])).is_err());
+
+		// without the final justification it's a valid partial proof.
+		proof.proofs.pop();
+		let authority_set = Mutex::new((0, authorities(&[Ed25519Keyring::Alice])));
+		let (verification, set_id, _) = verify_encoded_proof::<Block>(&proof.encode(), &authority_set).unwrap();
+		assert!(matches!(verification, WarpSyncVerification::Partial(hash) if hash == headers[10].hash()));
+		assert_eq!(set_id, 2);
+		assert_eq!(*authority_set.lock(), (2, authorities(&[Ed25519Keyring::Charlie])));
+	}
+
+	#[test]
+	fn warp_proof_requires_finalized_begin() {
+		let (blockchain, set_changes, _) = test_blockchain();
+		let unknown = header(3, Default::default(), None);
+
+		assert!(WarpSyncProof::<Block>::generate(&*blockchain, unknown.hash(), &set_changes).is_err());
+	}
+}
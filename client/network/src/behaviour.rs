@@ -18,8 +18,13 @@ use crate::{
 	config::Role,
 	debug_info, discovery::DiscoveryBehaviour, discovery::DiscoveryOut,
 	Event, ObservedRole, DhtEvent, ExHashT,
+	request_responses::{self, RequestFailure},
 };
-use crate::protocol::{self, light_client_handler, message::Roles, CustomMessageOutcome, Protocol};
+use crate::protocol::{
+	self, light_client_handler, message::Roles, state_sync::StateSyncOut, warp_sync::WarpSyncOut,
+	CustomMessageOutcome, Protocol,
+};
+use futures::channel::oneshot;
 use libp2p::NetworkBehaviour;
 use libp2p::core::{Multiaddr, PeerId, PublicKey};
 use libp2p::kad::record;
@@ -45,6 +50,12 @@ pub struct Behaviour<B: BlockT, H: ExHashT> {
 	block_requests: protocol::BlockRequests<B>,
 	/// Light client request handling.
 	light_client_handler: protocol::LightClientHandler<B>,
+	/// Warp sync request handling.
+	warp_sync: protocol::WarpSync<B>,
+	/// State sync request handling.
+	state_sync: protocol::StateSync<B>,
+	/// Generic request-response protocols.
+	request_responses: request_responses::RequestResponsesBehaviour,
 
 	/// Queue of events to produce for the outside.
 	#[behaviour(ignore)]
@@ -78,6 +89,9 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 		discovery_only_if_under_num: u64,
 		block_requests: protocol::BlockRequests<B>,
 		light_client_handler: protocol::LightClientHandler<B>,
+		warp_sync: protocol::WarpSync<B>,
+		state_sync: protocol::StateSync<B>,
+		request_responses: request_responses::RequestResponsesBehaviour,
 	) -> Self {
 		Behaviour {
 			substrate,
@@ -91,6 +105,9 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 			).await,
 			block_requests,
 			light_client_handler,
+			warp_sync,
+			state_sync,
+			request_responses,
 			events: Vec::new(),
 			role,
 		}
@@ -120,6 +137,19 @@ impl<B: BlockT, H: ExHashT> Behaviour<B, H> {
 		self.debug_info.node(peer_id)
 	}
 
+	/// Initiates sending a request on one of the registered request-response protocols.
+	///
+	/// The outcome of the request is sent on `response_tx`.
+	pub fn send_request(
+		&mut self,
+		target: &PeerId,
+		protocol: &str,
+		request: Vec<u8>,
+		response_tx: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	) {
+		self.request_responses.send_request(target, protocol, request, response_tx)
+	}
+
 	/// Registers a new notifications protocol.
 	///
 	/// After that, you can call `write_notifications`.
@@ -228,12 +258,33 @@ Behaviour<B, H> {
 			},
 			CustomMessageOutcome::PeerNewBest(peer_id, number) => {
 				self.light_client_handler.update_best_block(&peer_id, number);
+				self.warp_sync.update_best_block(&peer_id, number);
+				self.state_sync.update_best_block(&peer_id, number);
 			}
+			CustomMessageOutcome::StateSyncTarget(header) => self.state_sync.start(header),
 			CustomMessageOutcome::None => {}
 		}
 	}
 }
 
+impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<WarpSyncOut> for
+Behaviour<B, H> {
+	fn inject_event(&mut self, event: WarpSyncOut) {
+		match event {
+			WarpSyncOut::Finished => self.substrate.on_warp_sync_finished(),
+		}
+	}
+}
+
+impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<StateSyncOut> for
+Behaviour<B, H> {
+	fn inject_event(&mut self, event: StateSyncOut) {
+		match event {
+			StateSyncOut::Finished => self.substrate.on_state_sync_finished(),
+		}
+	}
+}
+
 impl<B: BlockT, H: ExHashT> NetworkBehaviourEventProcess<debug_info::DebugInfoEvent>
 	for Behaviour<B, H> {
 	fn inject_event(&mut self, event: debug_info::DebugInfoEvent) {
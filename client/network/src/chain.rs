@@ -17,18 +17,18 @@
 //! Blockchain access trait
 
 use sp_blockchain::{Error, HeaderBackend, HeaderMetadata};
-use sc_client_api::{BlockBackend, ProofProvider};
+use sc_client_api::{BlockBackend, ProofProvider, StateImporter};
 use sp_runtime::traits::{Block as BlockT, BlockIdTo};
 
 /// Local client abstraction for the network.
 pub trait Client<Block: BlockT>: HeaderBackend<Block> + ProofProvider<Block> + BlockIdTo<Block, Error = Error>
-	+ BlockBackend<Block> + HeaderMetadata<Block, Error = Error> + Send + Sync
+	+ BlockBackend<Block> + HeaderMetadata<Block, Error = Error> + StateImporter<Block> + Send + Sync
 {}
 
 impl<Block: BlockT, T> Client<Block> for T
 	where
 		T: HeaderBackend<Block> + ProofProvider<Block> + BlockIdTo<Block, Error = Error>
-			+ BlockBackend<Block> + HeaderMetadata<Block, Error = Error> + Send + Sync
+			+ BlockBackend<Block> + HeaderMetadata<Block, Error = Error> + StateImporter<Block> + Send + Sync
 {}
 
 /// Finality proof provider.
@@ -42,3 +42,33 @@ impl<Block: BlockT> FinalityProofProvider<Block> for () {
 		Ok(None)
 	}
 }
+
+/// Result of verifying a warp sync proof.
+#[derive(Debug)]
+pub enum WarpSyncVerification<Block: BlockT> {
+	/// The proof is valid but doesn't reach the latest authority set. The next proof should be
+	/// requested starting at the given block.
+	Partial(Block::Hash),
+	/// The proof is valid and proves the finality of the given header, which is the warp sync
+	/// target.
+	Complete(Block::Header),
+}
+
+/// Warp sync proof provider.
+///
+/// Warp sync proofs allow a node to verify the finality of a recent block without downloading
+/// all the headers in between, by only following the handoffs between finality authority sets.
+pub trait WarpSyncProvider<Block: BlockT>: Send + Sync {
+	/// Generate a proof of the authority set changes that happened since the given finalized
+	/// block. The proof can be incomplete, in which case the requester should ask for the rest
+	/// starting at the last block it has verified.
+	fn generate(&self, begin: Block::Hash) -> Result<Vec<u8>, Error>;
+
+	/// Verify a proof received from a peer against the authority set we have verified so far,
+	/// and advance it if the proof is valid.
+	fn verify(&self, proof: &[u8]) -> Result<WarpSyncVerification<Block>, Error>;
+
+	/// Import the target header of a completely verified proof as our new best and finalized
+	/// block.
+	fn import_target(&self, target: Block::Header) -> Result<(), Error>;
+}
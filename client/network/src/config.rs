@@ -19,8 +19,9 @@
 //! The [`Params`] struct is the struct that must be passed in order to initialize the networking.
 //! See the documentation of [`Params`].
 
-pub use crate::chain::{Client, FinalityProofProvider};
+pub use crate::chain::{Client, FinalityProofProvider, WarpSyncProvider, WarpSyncVerification};
 pub use crate::on_demand_layer::{AlwaysBadChecker, OnDemand};
+pub use crate::request_responses::{IncomingRequest, ProtocolConfig as RequestResponseConfig};
 pub use crate::service::{TransactionPool, EmptyTransactionPool};
 pub use libp2p::{identity, core::PublicKey, wasm_ext::ExtTransport, build_multiaddr};
 
@@ -63,6 +64,12 @@ pub struct Params<B: BlockT, H: ExHashT> {
 	/// from us.
 	pub finality_proof_provider: Option<Arc<dyn FinalityProofProvider<B>>>,
 
+	/// Warp sync proof provider.
+	///
+	/// This object, if `Some`, is used to answer warp sync requests from other nodes. Light
+	/// clients also use it to verify the proofs they receive when warp syncing from genesis.
+	pub warp_sync_provider: Option<Arc<dyn WarpSyncProvider<B>>>,
+
 	/// How to build requests for proofs of finality.
 	///
 	/// This object, if `Some`, is used when we need a proof of finality from another node.
@@ -333,6 +340,11 @@ pub struct NetworkConfiguration {
 	pub transport: TransportConfig,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
+	/// How to sync the chain when we're far behind our peers.
+	pub sync_mode: SyncMode,
+	/// Request-response protocols to support in addition to the built-in ones. Requests can be
+	/// sent on these protocols through `NetworkService::request`.
+	pub request_response_protocols: Vec<RequestResponseConfig>,
 }
 
 impl NetworkConfiguration {
@@ -362,6 +374,8 @@ impl NetworkConfiguration {
 				use_yamux_flow_control: false,
 			},
 			max_parallel_downloads: 5,
+			sync_mode: SyncMode::Full,
+			request_response_protocols: Vec::new(),
 		}
 	}
 }
@@ -435,6 +449,22 @@ pub enum TransportConfig {
 	MemoryOnly,
 }
 
+/// Strategy for syncing the chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncMode {
+	/// Download and import every block, executing all of them.
+	Full,
+	/// Download the headers first, then download the state of the latest finalized block and
+	/// continue with full sync from there.
+	Fast,
+}
+
+impl Default for SyncMode {
+	fn default() -> Self {
+		SyncMode::Full
+	}
+}
+
 /// The policy for connections to non-reserved peers.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum NonReservedPeerMode {
@@ -238,6 +238,7 @@ mod debug_info;
 mod discovery;
 mod on_demand_layer;
 mod protocol;
+mod request_responses;
 mod service;
 mod transport;
 mod utils;
@@ -248,6 +249,7 @@ pub mod network_state;
 
 pub use service::{NetworkService, NetworkStateInfo, NetworkWorker, ExHashT, ReportHandle};
 pub use protocol::PeerInfo;
+pub use request_responses::RequestFailure;
 pub use protocol::event::{Event, DhtEvent, ObservedRole};
 pub use protocol::sync::SyncState;
 pub use libp2p::{Multiaddr, PeerId};
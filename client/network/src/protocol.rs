@@ -43,7 +43,7 @@ use message::generic::{Message as GenericMessage, ConsensusMessage, Roles};
 use prometheus_endpoint::{Registry, Gauge, GaugeVec, HistogramVec, PrometheusError, Opts, register, U64};
 use sync::{ChainSync, SyncState};
 use crate::service::{TransactionPool, ExHashT};
-use crate::config::BoxFinalityProofRequestBuilder;
+use crate::config::{BoxFinalityProofRequestBuilder, SyncMode};
 use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::Arc;
@@ -73,10 +73,14 @@ pub mod block_requests;
 pub mod message;
 pub mod event;
 pub mod light_client_handler;
+pub mod state_sync;
 pub mod sync;
+pub mod warp_sync;
 
 pub use block_requests::BlockRequests;
 pub use light_client_handler::LightClientHandler;
+pub use state_sync::StateSync;
+pub use warp_sync::WarpSync;
 
 const REQUEST_TIMEOUT_SEC: u64 = 40;
 /// Interval at which we perform time based maintenance
@@ -289,6 +293,8 @@ pub struct ProtocolConfig {
 	pub roles: Roles,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
+	/// How to sync the chain.
+	pub sync_mode: SyncMode,
 }
 
 impl Default for ProtocolConfig {
@@ -296,6 +302,7 @@ impl Default for ProtocolConfig {
 		ProtocolConfig {
 			roles: Roles::FULL,
 			max_parallel_downloads: 5,
+			sync_mode: SyncMode::Full,
 		}
 	}
 }
@@ -334,6 +341,7 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 			finality_proof_request_builder,
 			block_announce_validator,
 			config.max_parallel_downloads,
+			config.sync_mode,
 		);
 
 		let important_peers = {
@@ -1330,6 +1338,59 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 		self.sync.on_block_finalized(&hash, *header.number())
 	}
 
+	/// Call this before starting a warp sync. Block requests are suspended until
+	/// `on_warp_sync_finished` is called.
+	pub fn begin_warp_sync(&mut self) {
+		self.sync.begin_warp_sync()
+	}
+
+	/// Call this when the warp sync has finished, whether it has managed to import its target
+	/// or not. Regular sync resumes from our current best block.
+	pub fn on_warp_sync_finished(&mut self) {
+		for result in self.sync.on_warp_sync_finished() {
+			match result {
+				Ok((id, req)) => {
+					let msg = GenericMessage::BlockRequest(req);
+					send_request(
+						&mut self.behaviour,
+						&mut self.context_data.stats,
+						&mut self.context_data.peers,
+						&id,
+						msg
+					)
+				}
+				Err(sync::BadPeer(id, repu)) => {
+					self.behaviour.disconnect_peer(&id);
+					self.peerset_handle.report_peer(id, repu)
+				}
+			}
+		}
+	}
+
+	/// Call this when the state download requested through `CustomMessageOutcome::StateSyncTarget`
+	/// has finished, whether the state has been imported or not. Full sync resumes from the last
+	/// block we have the state of.
+	pub fn on_state_sync_finished(&mut self) {
+		for result in self.sync.on_state_sync_finished() {
+			match result {
+				Ok((id, req)) => {
+					let msg = GenericMessage::BlockRequest(req);
+					send_request(
+						&mut self.behaviour,
+						&mut self.context_data.stats,
+						&mut self.context_data.peers,
+						&id,
+						msg
+					)
+				}
+				Err(sync::BadPeer(id, repu)) => {
+					self.behaviour.disconnect_peer(&id);
+					self.peerset_handle.report_peer(id, repu)
+				}
+			}
+		}
+	}
+
 	fn on_remote_call_request(
 		&mut self,
 		who: PeerId,
@@ -1776,6 +1837,9 @@ pub enum CustomMessageOutcome<B: BlockT> {
 	NotificationsReceived { remote: PeerId, messages: Vec<(ConsensusEngineId, Bytes)> },
 	/// Peer has a reported a new head of chain.
 	PeerNewBest(PeerId, NumberFor<B>),
+	/// Fast sync has downloaded the headers, and the state of the given finalized block should
+	/// now be downloaded. `Protocol::on_state_sync_finished` must be called once it's done.
+	StateSyncTarget(B::Header),
 	None,
 }
 
@@ -1868,6 +1932,10 @@ impl<B: BlockT, H: ExHashT> NetworkBehaviour for Protocol<B, H> {
 			self.propagate_extrinsics();
 		}
 
+		if let Some(target) = self.sync.state_sync_target() {
+			return Poll::Ready(NetworkBehaviourAction::GenerateEvent(CustomMessageOutcome::StateSyncTarget(target)));
+		}
+
 		for (id, r) in self.sync.block_requests() {
 			send_request(
 				&mut self.behaviour,
@@ -0,0 +1,1018 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! [`NetworkBehaviour`] implementation which handles state sync requests.
+//!
+//! A state sync request contains the SCALE-encoded hash of a block, the storage key of a child
+//! trie (or nothing for the top trie) and the storage key to start at, and is answered with a
+//! storage proof of the values that follow this key. The size of a single proof is limited, so
+//! the complete state is transferred in several chunks. Proofs are generated on a dedicated
+//! thread, and every peer may only have one request served at a time.
+//!
+//! When started (see [`StateSync::start`]), the behaviour also downloads the state of the given
+//! block: it requests chunks of the top trie and then of every child trie from peers one at a
+//! time. Every chunk is verified and written to the database on a dedicated import thread as
+//! soon as it arrives, and the state becomes available once all of them have been imported. A
+//! [`StateSyncOut::Finished`] event is produced once it is done, whether the state could be
+//! imported or not, after which regular sync should take over.
+//!
+//! Only default child tries, whose storage key starts with `:child_storage:default:`, can be
+//! downloaded.
+
+use bytes::Bytes;
+use codec::{Encode, Decode};
+use crate::{
+	chain::Client,
+	config::ProtocolId,
+};
+use futures::{channel::oneshot, future::BoxFuture, prelude::*, stream::FuturesUnordered};
+use libp2p::{
+	core::{
+		ConnectedPoint,
+		Multiaddr,
+		PeerId,
+		upgrade::{InboundUpgrade, ReadOneError, UpgradeInfo},
+		upgrade::{OutboundUpgrade, read_one, write_one}
+	},
+	swarm::{
+		NegotiatedSubstream,
+		NetworkBehaviour,
+		NetworkBehaviourAction,
+		OneShotHandler,
+		PollParameters,
+		SubstreamProtocol
+	}
+};
+use sc_client_api::StorageProof;
+use sc_peerset::ReputationChange;
+use sp_consensus::BlockStatus;
+use sp_core::storage::{well_known_keys, ChildInfo};
+use sp_runtime::{generic::BlockId, traits::{Block, Header, NumberFor}};
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	iter,
+	sync::{Arc, mpsc},
+	thread,
+	time::Duration,
+	task::{Context, Poll}
+};
+use wasm_timer::Instant;
+
+/// Reputation change for a peer when a state sync request timed out.
+const TIMEOUT_REPUTATION_CHANGE: i32 = -(1 << 8);
+
+/// Number of failed requests after which we give up on state syncing.
+const MAX_FAILED_REQUESTS: usize = 8;
+
+/// Reputation change for a peer sending requests faster than we serve them.
+const FLOOD_REPUTATION_CHANGE: i32 = -(1 << 10);
+
+/// Number of requests waiting for the proof worker after which further requests are dropped.
+const MAX_QUEUED_REQUESTS: usize = 32;
+
+/// Approximate size of the proof in a single response.
+const MAX_PROOF_SIZE: usize = 2 * 1024 * 1024;
+
+/// Prefix of the storage keys of default child tries. The rest of the key is the unique id of
+/// the child trie.
+const DEFAULT_CHILD_STORAGE_KEY_PREFIX: &[u8] = b":child_storage:default:";
+
+/// Configuration options for `StateSync` behaviour.
+#[derive(Debug, Clone)]
+pub struct Config {
+	max_request_size: usize,
+	max_response_size: usize,
+	inactivity_timeout: Duration,
+	request_timeout: Duration,
+	protocol: Bytes,
+}
+
+impl Config {
+	/// Create a fresh configuration with the following options:
+	///
+	/// - max. request size = 4 KiB
+	/// - max. response size = 16 MiB
+	/// - inactivity timeout = 15s
+	/// - request timeout = 40s
+	pub fn new(id: &ProtocolId) -> Self {
+		let mut c = Config {
+			max_request_size: 4 * 1024,
+			max_response_size: 16 * 1024 * 1024,
+			inactivity_timeout: Duration::from_secs(15),
+			request_timeout: Duration::from_secs(40),
+			protocol: Bytes::new(),
+		};
+		c.set_protocol(id);
+		c
+	}
+
+	/// Limit the max. length in bytes of a request.
+	pub fn set_max_request_size(&mut self, v: usize) -> &mut Self {
+		self.max_request_size = v;
+		self
+	}
+
+	/// Limit the max. length in bytes of a response.
+	pub fn set_max_response_size(&mut self, v: usize) -> &mut Self {
+		self.max_response_size = v;
+		self
+	}
+
+	/// Limit the max. duration the connection may remain inactive before closing it.
+	pub fn set_inactivity_timeout(&mut self, v: Duration) -> &mut Self {
+		self.inactivity_timeout = v;
+		self
+	}
+
+	/// Limit the max. request duration.
+	pub fn set_request_timeout(&mut self, v: Duration) -> &mut Self {
+		self.request_timeout = v;
+		self
+	}
+
+	/// Set protocol to use for upgrade negotiation.
+	pub fn set_protocol(&mut self, id: &ProtocolId) -> &mut Self {
+		let mut v = Vec::new();
+		v.extend_from_slice(b"/");
+		v.extend_from_slice(id.as_bytes());
+		v.extend_from_slice(b"/sync/state");
+		self.protocol = v.into();
+		self
+	}
+}
+
+/// A request for a chunk of the state of a block.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+struct StateRequest<H> {
+	/// The block to read the state of.
+	block: H,
+	/// The storage key of the child trie to read, or `None` to read the top trie.
+	child: Option<Vec<u8>>,
+	/// The response starts after this key. Empty to start at the beginning of the trie.
+	start: Vec<u8>,
+}
+
+/// Event produced by the `StateSync` behaviour.
+#[derive(Debug)]
+pub enum StateSyncOut {
+	/// Our state sync has finished, either by importing the state or by giving up.
+	Finished,
+}
+
+/// The state sync behaviour.
+pub struct StateSync<B: Block> {
+	/// This behaviour's configuration.
+	config: Config,
+	/// Blockchain client.
+	chain: Arc<dyn Client<B>>,
+	/// Handle to use for reporting misbehaviour of peers.
+	peerset: sc_peerset::PeersetHandle,
+	/// Sends the requests to serve to the proof worker thread.
+	proof_worker: Option<mpsc::SyncSender<ProofRequest<B>>>,
+	/// Sends the chunks of our own state sync to the import worker thread.
+	import_worker: Option<mpsc::Sender<ImportRequest<B>>>,
+	/// Futures awaiting the proofs of remote requests and sending them back, resolving to the
+	/// requesting peer.
+	responses: FuturesUnordered<BoxFuture<'static, PeerId>>,
+	/// Peers whose request is being served.
+	serving: HashSet<PeerId>,
+	/// Best block number of the peers we're connected to, if known.
+	peers: HashMap<PeerId, Option<NumberFor<B>>>,
+	/// State of our own state sync, if one is in progress.
+	sync: Option<SyncState<B>>,
+	/// Events to produce for the outside.
+	events: VecDeque<StateSyncOut>,
+	/// Identifier of the next request we send.
+	next_request_id: u64,
+}
+
+/// State of our own state sync.
+struct SyncState<B: Block> {
+	/// The block we're downloading the state of.
+	target: B::Header,
+	/// The child trie we're downloading, or `None` while downloading the top trie.
+	trie: Option<ChildTrie<B::Hash>>,
+	/// The last key we have imported from the current trie. The next chunk starts after it.
+	last_key: Vec<u8>,
+	/// Child tries found in the top trie that remain to be downloaded.
+	pending_children: VecDeque<ChildTrie<B::Hash>>,
+	/// A proof of the top trie, needed to complete the import. Kept from the first chunk.
+	root_proof: Option<StorageProof>,
+	/// Number of values imported so far.
+	imported: usize,
+	/// The request currently in flight, if any.
+	outstanding: Option<OutstandingRequest>,
+	/// The job of the import worker we're waiting for, if any.
+	importing: Option<BoxFuture<'static, ImportOutcome>>,
+	/// Peers that failed to answer one of our requests.
+	failed_peers: HashSet<PeerId>,
+	/// Total number of failed requests.
+	failures: usize,
+}
+
+/// A child trie of the state we're downloading.
+#[derive(Debug, Clone)]
+struct ChildTrie<H> {
+	/// The storage key of the child trie in the top trie.
+	storage_key: Vec<u8>,
+	/// The root of the child trie, read from the top trie.
+	root: H,
+}
+
+/// Returns the child info of the child trie stored under the given key, if it is a default
+/// child trie.
+fn default_child_info(storage_key: &[u8]) -> Option<ChildInfo> {
+	if well_known_keys::is_child_trie_key_valid(storage_key) {
+		Some(ChildInfo::new_default(&storage_key[DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..]))
+	} else {
+		None
+	}
+}
+
+/// A request we have sent and are awaiting the response for.
+struct OutstandingRequest {
+	id: u64,
+	peer: PeerId,
+	timestamp: Instant,
+}
+
+/// A remote request to generate a proof for on the proof worker.
+struct ProofRequest<B: Block> {
+	request: StateRequest<B::Hash>,
+	response: oneshot::Sender<Result<Vec<u8>, sp_blockchain::Error>>,
+}
+
+/// A job of the import worker.
+enum ImportRequest<B: Block> {
+	/// Import a chunk of the given trie. The proof is sent back along with the result if
+	/// `keep_proof` is set.
+	Chunk {
+		block: B::Hash,
+		trie: Option<ChildTrie<B::Hash>>,
+		proof: StorageProof,
+		start: Vec<u8>,
+		keep_proof: bool,
+		response: oneshot::Sender<(ChunkResult, Option<StorageProof>)>,
+	},
+	/// Complete the import once all chunks have been imported.
+	Complete {
+		block: B::Hash,
+		proof: StorageProof,
+		response: oneshot::Sender<sp_blockchain::Result<()>>,
+	},
+}
+
+/// The values of an imported chunk and whether it is the last one of its trie.
+type ChunkResult = sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)>;
+
+/// Result of a job of the import worker.
+enum ImportOutcome {
+	/// A chunk received from the given peer has been imported, or rejected.
+	Chunk {
+		peer: PeerId,
+		result: ChunkResult,
+		proof: Option<StorageProof>,
+	},
+	/// The import has been completed.
+	Complete(sp_blockchain::Result<()>),
+}
+
+/// Spawn a worker thread running `work`, so that the network is not blocked by it.
+fn spawn_worker(name: &str, work: impl FnOnce() + Send + 'static) -> bool {
+	match thread::Builder::new().name(name.into()).spawn(work) {
+		Ok(_) => true,
+		Err(e) => {
+			log::warn!("❗️ Failed to spawn the {} worker: {}", name, e);
+			false
+		}
+	}
+}
+
+/// Spawn the thread generating the proofs of remote requests. The thread exits once the
+/// returned sender is dropped.
+fn spawn_proof_worker<B: Block>(chain: Arc<dyn Client<B>>) -> Option<mpsc::SyncSender<ProofRequest<B>>> {
+	let (sender, receiver) = mpsc::sync_channel::<ProofRequest<B>>(MAX_QUEUED_REQUESTS);
+	let spawned = spawn_worker("state-sync-proofs", move || {
+		for ProofRequest { request, response } in receiver {
+			let block = BlockId::Hash(request.block);
+			let start = if request.start.is_empty() { None } else { Some(&request.start[..]) };
+			let proof = match request.child.as_ref().and_then(|key| Some((key, default_child_info(key)?))) {
+				Some((storage_key, child_info)) =>
+					chain.read_child_proof_collection(&block, storage_key, child_info, start, MAX_PROOF_SIZE),
+				None => chain.read_proof_collection(&block, start, MAX_PROOF_SIZE),
+			};
+			let _ = response.send(proof.map(|(proof, count)| {
+				log::trace!(target: "sync", "generated state proof of {} values", count);
+				proof.encode()
+			}));
+		}
+	});
+	if spawned { Some(sender) } else { None }
+}
+
+/// Spawn the thread importing the chunks of our own state sync. The thread exits once the
+/// returned sender is dropped.
+fn spawn_import_worker<B: Block>(chain: Arc<dyn Client<B>>) -> Option<mpsc::Sender<ImportRequest<B>>> {
+	let (sender, receiver) = mpsc::channel::<ImportRequest<B>>();
+	let spawned = spawn_worker("state-sync-import", move || {
+		for request in receiver {
+			match request {
+				ImportRequest::Chunk { block, trie, proof, start, keep_proof, response } => {
+					let kept = if keep_proof { Some(proof.clone()) } else { None };
+					let start = if start.is_empty() { None } else { Some(&start[..]) };
+					let child = trie.as_ref().and_then(|trie| {
+						Some((&trie.storage_key[..], default_child_info(&trie.storage_key)?, trie.root))
+					});
+					let _ = response.send((chain.import_state_chunk(block, child, proof, start), kept));
+				}
+				ImportRequest::Complete { block, proof, response } => {
+					let _ = response.send(chain.complete_state_import(block, proof));
+				}
+			}
+		}
+	});
+	if spawned { Some(sender) } else { None }
+}
+
+impl<B> StateSync<B>
+where
+	B: Block,
+{
+	/// Construct a new state sync handler.
+	pub fn new(
+		config: Config,
+		chain: Arc<dyn Client<B>>,
+		peerset: sc_peerset::PeersetHandle,
+	) -> Self {
+		StateSync {
+			config,
+			proof_worker: spawn_proof_worker(chain.clone()),
+			import_worker: spawn_import_worker(chain.clone()),
+			chain,
+			peerset,
+			responses: FuturesUnordered::new(),
+			serving: HashSet::new(),
+			peers: HashMap::new(),
+			sync: None,
+			events: VecDeque::new(),
+			next_request_id: 1,
+		}
+	}
+
+	/// Start downloading the state of the given block.
+	///
+	/// Does nothing (apart from producing a `Finished` event) if we have the state already.
+	pub fn start(&mut self, target: B::Header) {
+		let hash = target.hash();
+		if let Ok(BlockStatus::InChainWithState) = self.chain.block_status(&BlockId::Hash(hash)) {
+			log::debug!(target: "sync", "State of #{} ({}) is already available", target.number(), hash);
+			self.events.push_back(StateSyncOut::Finished);
+			return
+		}
+		if self.import_worker.is_none() {
+			log::warn!("❗️ Can't download state without the import worker");
+			self.events.push_back(StateSyncOut::Finished);
+			return
+		}
+
+		log::info!("⏩ Downloading state of block #{} ({})", target.number(), hash);
+		self.sync = Some(SyncState {
+			target,
+			trie: None,
+			last_key: Vec::new(),
+			pending_children: VecDeque::new(),
+			root_proof: None,
+			imported: 0,
+			outstanding: None,
+			importing: None,
+			failed_peers: HashSet::new(),
+			failures: 0,
+		});
+	}
+
+	/// Is our own state sync in progress?
+	pub fn is_syncing(&self) -> bool {
+		self.sync.is_some()
+	}
+
+	/// Call this when we receive a new best block announcement from a peer.
+	pub fn update_best_block(&mut self, peer: &PeerId, num: NumberFor<B>) {
+		if let Some(best) = self.peers.get_mut(peer) {
+			log::trace!(target: "sync", "new best block for {:?}: {:?}", peer, num);
+			*best = Some(num)
+		}
+	}
+
+	fn next_request_id(&mut self) -> u64 {
+		let id = self.next_request_id;
+		self.next_request_id += 1;
+		id
+	}
+
+	/// Record that the outstanding request to the given peer has failed.
+	fn on_request_failed(&mut self, peer: &PeerId) {
+		if let Some(state) = &mut self.sync {
+			state.outstanding = None;
+			state.failed_peers.insert(peer.clone());
+			state.failures += 1;
+		}
+	}
+
+	/// Stop our own state sync and notify the outside.
+	fn finish(&mut self) {
+		self.sync = None;
+		self.events.push_back(StateSyncOut::Finished);
+	}
+
+	/// Callback, invoked when a new state sync request has been received from remote.
+	///
+	/// Hands the request over to the proof worker and returns the receiver of the proof.
+	fn on_request(
+		&mut self,
+		peer: &PeerId,
+		request: &[u8],
+	) -> Result<oneshot::Receiver<Result<Vec<u8>, sp_blockchain::Error>>, Error> {
+		let worker = self.proof_worker.as_ref().ok_or(Error::NotSupported)?;
+		let request = StateRequest::<B::Hash>::decode(&mut &request[..])?;
+		if request.child.as_ref().map_or(false, |key| default_child_info(key).is_none()) {
+			return Err(Error::UnsupportedChildTrie)
+		}
+		if self.serving.contains(peer) {
+			return Err(Error::TooManyRequests)
+		}
+
+		log::trace!(target: "sync", "state request from peer {} for {} in {:?} starting at {:?}",
+			peer, request.block, request.child, request.start);
+
+		let (response, receiver) = oneshot::channel();
+		worker.try_send(ProofRequest { request, response }).map_err(|e| match e {
+			mpsc::TrySendError::Full(_) => Error::Busy,
+			mpsc::TrySendError::Disconnected(_) => Error::NotSupported,
+		})?;
+		self.serving.insert(peer.clone());
+		Ok(receiver)
+	}
+
+	/// Callback, invoked when a response to our own request has been received.
+	///
+	/// Hands the proof over to the import worker, which verifies and imports it.
+	fn on_response(&mut self, peer: PeerId, response: Vec<u8>) {
+		let proof = match StorageProof::decode(&mut &response[..]) {
+			Ok(proof) => proof,
+			Err(e) => {
+				log::debug!(target: "sync", "Invalid state response from peer {}: {}", peer, e);
+				self.on_request_failed(&peer);
+				self.peerset.report_peer(peer, ReputationChange::new_fatal("invalid state proof"));
+				return
+			}
+		};
+
+		let worker = match &self.import_worker {
+			Some(worker) => worker,
+			None => return,
+		};
+		let state = match &mut self.sync {
+			Some(state) => state,
+			None => return,
+		};
+
+		let (response, receiver) = oneshot::channel();
+		let request = ImportRequest::Chunk {
+			block: state.target.hash(),
+			trie: state.trie.clone(),
+			proof,
+			start: state.last_key.clone(),
+			keep_proof: state.trie.is_none() && state.root_proof.is_none(),
+			response,
+		};
+		if worker.send(request).is_ok() {
+			state.outstanding = None;
+			state.importing = Some(receiver
+				.map(move |result| match result {
+					Ok((result, proof)) => ImportOutcome::Chunk { peer, result, proof },
+					Err(_) => ImportOutcome::Complete(Err("State sync import worker stopped".into())),
+				})
+				.boxed());
+		} else {
+			log::warn!("❗️ State sync import worker stopped");
+			self.finish();
+		}
+	}
+
+	/// Callback, invoked when the import worker has finished a job.
+	fn on_imported(&mut self, outcome: ImportOutcome) {
+		let (peer, values, complete, proof) = match outcome {
+			ImportOutcome::Chunk { peer, result: Ok((values, complete)), proof } => (peer, values, complete, proof),
+			ImportOutcome::Chunk { peer, result: Err(sp_blockchain::Error::Execution(e)), .. } => {
+				log::debug!(target: "sync", "Invalid state response from peer {}: {}", peer, e);
+				self.on_request_failed(&peer);
+				self.peerset.report_peer(peer, ReputationChange::new_fatal("invalid state proof"));
+				return
+			}
+			ImportOutcome::Chunk { result: Err(e), .. } | ImportOutcome::Complete(Err(e)) => {
+				log::warn!("❗️ Failed to import downloaded state: {}", e);
+				self.finish();
+				return
+			}
+			ImportOutcome::Complete(Ok(())) => {
+				if let Some(state) = &self.sync {
+					log::info!("⏩ Imported state of block #{} ({})", state.target.number(), state.target.hash());
+				}
+				self.finish();
+				return
+			}
+		};
+
+		if values.is_empty() && !complete {
+			log::debug!(target: "sync", "Empty state response from peer {}", peer);
+			self.on_request_failed(&peer);
+			return
+		}
+
+		let state = match &mut self.sync {
+			Some(state) => state,
+			None => return,
+		};
+		log::trace!(target: "sync", "Imported {} state values from peer {}", values.len(), peer);
+
+		if state.trie.is_none() {
+			if proof.is_some() {
+				state.root_proof = proof;
+			}
+			for (key, value) in values.iter().filter(|(key, _)| well_known_keys::is_child_storage_key(key)) {
+				let root = match default_child_info(key).and_then(|_| B::Hash::decode(&mut &value[..]).ok()) {
+					Some(root) => root,
+					None => {
+						log::warn!("❗️ State contains an unsupported child trie at {:?}", key);
+						self.finish();
+						return
+					}
+				};
+				state.pending_children.push_back(ChildTrie { storage_key: key.clone(), root });
+			}
+		}
+
+		state.imported += values.len();
+		if let Some((key, _)) = values.last() {
+			state.last_key = key.clone();
+		}
+		if !complete {
+			return
+		}
+
+		state.last_key.clear();
+		state.trie = state.pending_children.pop_front();
+		if let Some(trie) = &state.trie {
+			log::debug!(target: "sync", "Downloading child trie {:?}", trie.storage_key);
+			return
+		}
+
+		log::debug!(target: "sync", "Downloaded {} state values, completing the import", state.imported);
+		let proof = state.root_proof.take().unwrap_or_else(StorageProof::empty);
+		let (response, receiver) = oneshot::channel();
+		let request = ImportRequest::Complete { block: state.target.hash(), proof, response };
+		let sent = self.import_worker.as_ref().map_or(false, |worker| worker.send(request).is_ok());
+		if !sent {
+			log::warn!("❗️ State sync import worker stopped");
+			self.finish();
+			return
+		}
+		state.importing = Some(receiver
+			.map(|result| ImportOutcome::Complete(
+				result.unwrap_or_else(|_| Err("State sync import worker stopped".into()))
+			))
+			.boxed());
+	}
+
+	/// Pick a peer to send our next request to. We only ask peers that know the target block.
+	fn available_peer(&self) -> Option<PeerId> {
+		let state = self.sync.as_ref()?;
+		let number = *state.target.number();
+		self.peers.iter()
+			.filter(|(peer, _)| !state.failed_peers.contains(peer))
+			.filter_map(|(peer, best)| best.map(|best| (peer, best)))
+			.filter(|(_, best)| *best >= number)
+			.max_by_key(|(_, best)| *best)
+			.map(|(peer, _)| peer.clone())
+	}
+}
+
+impl<B> NetworkBehaviour for StateSync<B>
+where
+	B: Block
+{
+	type ProtocolsHandler = OneShotHandler<InboundProtocol, OutboundProtocol, Event<NegotiatedSubstream>>;
+	type OutEvent = StateSyncOut;
+
+	fn new_handler(&mut self) -> Self::ProtocolsHandler {
+		let p = InboundProtocol {
+			max_request_size: self.config.max_request_size,
+			protocol: self.config.protocol.clone(),
+		};
+		OneShotHandler::new(SubstreamProtocol::new(p), self.config.inactivity_timeout)
+	}
+
+	fn addresses_of_peer(&mut self, _: &PeerId) -> Vec<Multiaddr> {
+		Vec::new()
+	}
+
+	fn inject_connected(&mut self, peer: PeerId, _: ConnectedPoint) {
+		self.peers.insert(peer, None);
+	}
+
+	fn inject_disconnected(&mut self, peer: &PeerId, _: ConnectedPoint) {
+		self.peers.remove(peer);
+
+		let is_outstanding = self.sync.as_ref()
+			.and_then(|state| state.outstanding.as_ref())
+			.map_or(false, |request| &request.peer == peer);
+		if is_outstanding {
+			log::debug!(target: "sync", "peer {} disconnected during state request", peer);
+			self.on_request_failed(peer);
+		}
+	}
+
+	fn inject_node_event(&mut self, peer: PeerId, event: Event<NegotiatedSubstream>) {
+		match event {
+			// An incoming request from remote has been received.
+			Event::Request(request, mut stream) => {
+				match self.on_request(&peer, &request) {
+					Ok(proof) => {
+						let future = async move {
+							match proof.await {
+								Ok(Ok(response)) => {
+									log::trace!(target: "sync", "sending state response to peer {}", peer);
+									if let Err(e) = write_one(&mut stream, response).await {
+										log::debug!("error writing state response: {}", e)
+									}
+								}
+								Ok(Err(e)) => log::debug!("error generating state proof for peer {}: {}", peer, e),
+								Err(_) => log::debug!("state sync proof worker stopped"),
+							}
+							peer
+						};
+						self.responses.push(future.boxed())
+					}
+					Err(Error::BadRequest(_)) | Err(Error::UnsupportedChildTrie) => {
+						self.peerset.report_peer(peer, ReputationChange::new(-(1 << 12), "bad state request"))
+					}
+					Err(Error::TooManyRequests) => {
+						self.peerset.report_peer(peer, ReputationChange::new(FLOOD_REPUTATION_CHANGE, "too many state requests"))
+					}
+					Err(e) => log::debug!("error handling state request from peer {}: {}", peer, e)
+				}
+			}
+			// A response to one of our own requests has been received.
+			Event::Response(id, response) => {
+				let is_expected = self.sync.as_ref()
+					.and_then(|state| state.outstanding.as_ref())
+					.map_or(false, |request| request.id == id && request.peer == peer);
+				if !is_expected {
+					log::debug!("unexpected state response {} from peer {}", id, peer);
+					self.peerset.report_peer(peer, ReputationChange::new_fatal("unexpected state response"));
+					return
+				}
+
+				self.on_response(peer, response)
+			}
+		}
+	}
+
+	fn poll(&mut self, cx: &mut Context, _: &mut impl PollParameters) -> Poll<NetworkBehaviourAction<OutboundProtocol, StateSyncOut>> {
+		// Process response sending futures.
+		while let Poll::Ready(Some(peer)) = self.responses.poll_next_unpin(cx) {
+			self.serving.remove(&peer);
+		}
+
+		// Process the result of the import worker.
+		let imported = self.sync.as_mut()
+			.and_then(|state| state.importing.as_mut())
+			.and_then(|importing| match importing.poll_unpin(cx) {
+				Poll::Ready(outcome) => Some(outcome),
+				Poll::Pending => None,
+			});
+		if let Some(outcome) = imported {
+			if let Some(state) = &mut self.sync {
+				state.importing = None;
+			}
+			self.on_imported(outcome);
+		}
+
+		if let Some(event) = self.events.pop_front() {
+			return Poll::Ready(NetworkBehaviourAction::GenerateEvent(event))
+		}
+
+		// Look for our request having timed out.
+		let now = Instant::now();
+		let timed_out = self.sync.as_ref()
+			.and_then(|state| state.outstanding.as_ref())
+			.filter(|request| now > request.timestamp + self.config.request_timeout)
+			.map(|request| request.peer.clone());
+		if let Some(peer) = timed_out {
+			log::debug!(target: "sync", "state request to {} timed out", peer);
+			self.on_request_failed(&peer);
+			self.peerset.report_peer(peer, ReputationChange::new(TIMEOUT_REPUTATION_CHANGE, "state request timeout"));
+		}
+
+		let (request, failures, idle) = match &self.sync {
+			Some(state) => {
+				let request = StateRequest {
+					block: state.target.hash(),
+					child: state.trie.as_ref().map(|trie| trie.storage_key.clone()),
+					start: state.last_key.clone(),
+				};
+				(request, state.failures, state.outstanding.is_none() && state.importing.is_none())
+			}
+			None => return Poll::Pending,
+		};
+
+		if failures >= MAX_FAILED_REQUESTS {
+			log::warn!("❗️ Giving up on state sync after {} failed requests", failures);
+			self.finish();
+			return Poll::Ready(NetworkBehaviourAction::GenerateEvent(
+				self.events.pop_front().expect("`finish` has just pushed an event; qed")
+			))
+		}
+
+		if !idle {
+			return Poll::Pending
+		}
+
+		if let Some(peer) = self.available_peer() {
+			let id = self.next_request_id();
+			log::trace!(target: "sync", "sending state request {} to peer {}", id, peer);
+			let protocol = OutboundProtocol {
+				request: request.encode(),
+				request_id: id,
+				max_response_size: self.config.max_response_size,
+				protocol: self.config.protocol.clone(),
+			};
+			if let Some(state) = &mut self.sync {
+				state.outstanding = Some(OutstandingRequest { id, peer: peer.clone(), timestamp: now });
+			}
+			return Poll::Ready(NetworkBehaviourAction::SendEvent { peer_id: peer, event: protocol })
+		}
+
+		Poll::Pending
+	}
+}
+
+/// Possible errors while handling state sync requests.
+#[derive(Debug, derive_more::Display, derive_more::From)]
+pub enum Error {
+	/// The proof worker could not be started.
+	#[display(fmt = "state sync is not supported")]
+	NotSupported,
+	/// The request could not be decoded.
+	#[display(fmt = "bad request: {}", _0)]
+	BadRequest(codec::Error),
+	/// The request is for a child trie that is not a default child trie.
+	#[display(fmt = "unsupported child trie")]
+	UnsupportedChildTrie,
+	/// The peer sent a request while another one was being served.
+	#[display(fmt = "too many requests")]
+	TooManyRequests,
+	/// Too many requests are waiting for their proofs.
+	#[display(fmt = "proof worker is busy")]
+	Busy,
+}
+
+/// Output type of inbound and outbound substream upgrades.
+#[derive(Debug)]
+pub enum Event<T> {
+	/// Incoming request from remote and substream to use for the response.
+	Request(Vec<u8>, T),
+	/// Incoming response from remote.
+	Response(u64, Vec<u8>),
+}
+
+/// Substream upgrade protocol.
+///
+/// Reads incoming requests from remote.
+#[derive(Debug, Clone)]
+pub struct InboundProtocol {
+	/// The max. request length in bytes.
+	max_request_size: usize,
+	/// The protocol to use for upgrade negotiation.
+	protocol: Bytes,
+}
+
+impl UpgradeInfo for InboundProtocol {
+	type Info = Bytes;
+	type InfoIter = iter::Once<Self::Info>;
+
+	fn protocol_info(&self) -> Self::InfoIter {
+		iter::once(self.protocol.clone())
+	}
+}
+
+impl<T> InboundUpgrade<T> for InboundProtocol
+where
+	T: AsyncRead + AsyncWrite + Unpin + Send + 'static
+{
+	type Output = Event<T>;
+	type Error = ReadOneError;
+	type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+	fn upgrade_inbound(self, mut s: T, _: Self::Info) -> Self::Future {
+		let future = async move {
+			let vec = read_one(&mut s, self.max_request_size).await?;
+			Ok(Event::Request(vec, s))
+		};
+		future.boxed()
+	}
+}
+
+/// Substream upgrade protocol.
+///
+/// Sends a request to remote and awaits the response.
+#[derive(Debug, Clone)]
+pub struct OutboundProtocol {
+	/// The SCALE-encoded request.
+	request: Vec<u8>,
+	/// Local identifier for the request. Used to associate it with a response.
+	request_id: u64,
+	/// The max. response length in bytes.
+	max_response_size: usize,
+	/// The protocol to use for upgrade negotiation.
+	protocol: Bytes,
+}
+
+impl UpgradeInfo for OutboundProtocol {
+	type Info = Bytes;
+	type InfoIter = iter::Once<Self::Info>;
+
+	fn protocol_info(&self) -> Self::InfoIter {
+		iter::once(self.protocol.clone())
+	}
+}
+
+impl<T> OutboundUpgrade<T> for OutboundProtocol
+where
+	T: AsyncRead + AsyncWrite + Unpin + Send + 'static
+{
+	type Output = Event<T>;
+	type Error = ReadOneError;
+	type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+	fn upgrade_outbound(self, mut s: T, _: Self::Info) -> Self::Future {
+		let future = async move {
+			write_one(&mut s, &self.request).await?;
+			let vec = read_one(&mut s, self.max_response_size).await?;
+			Ok(Event::Response(self.request_id, vec))
+		};
+		future.boxed()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert_matches::assert_matches;
+	use codec::{Decode, Encode};
+	use futures::{executor, prelude::*};
+	use libp2p::{
+		PeerId,
+		Multiaddr,
+		swarm::{NetworkBehaviour, NetworkBehaviourAction, PollParameters},
+	};
+	use sc_client_api::{ProofProvider, StorageProof};
+	use sp_blockchain::HeaderBackend;
+	use sp_core::storage::ChildInfo;
+	use sp_runtime::{generic::BlockId, traits::Header};
+	use std::{iter, pin::Pin, sync::Arc, task::Poll};
+	use substrate_test_runtime_client::{
+		runtime::{Block, Hash},
+		DefaultTestClientBuilderExt,
+		TestClientBuilder,
+		TestClientBuilderExt,
+	};
+	use super::{Error, OutboundProtocol, StateRequest, StateSync, StateSyncOut};
+	use crate::config::ProtocolId;
+
+	struct EmptyPollParams(PeerId);
+
+	impl PollParameters for EmptyPollParams {
+		type SupportedProtocolsIter = iter::Empty<Vec<u8>>;
+		type ListenedAddressesIter = iter::Empty<Multiaddr>;
+		type ExternalAddressesIter = iter::Empty<Multiaddr>;
+
+		fn supported_protocols(&self) -> Self::SupportedProtocolsIter {
+			iter::empty()
+		}
+
+		fn listened_addresses(&self) -> Self::ListenedAddressesIter {
+			iter::empty()
+		}
+
+		fn external_addresses(&self) -> Self::ExternalAddressesIter {
+			iter::empty()
+		}
+
+		fn local_peer_id(&self) -> &PeerId {
+			&self.0
+		}
+	}
+
+	fn peerset() -> (sc_peerset::Peerset, sc_peerset::PeersetHandle) {
+		let cfg = sc_peerset::PeersetConfig {
+			in_peers: 128,
+			out_peers: 128,
+			bootnodes: Vec::new(),
+			reserved_only: false,
+			priority_groups: Vec::new(),
+		};
+		sc_peerset::Peerset::from_config(cfg)
+	}
+
+	fn poll(mut b: &mut StateSync<Block>) -> Poll<NetworkBehaviourAction<OutboundProtocol, StateSyncOut>> {
+		let mut p = EmptyPollParams(PeerId::random());
+		match future::poll_fn(|cx| Pin::new(&mut b).poll(cx, &mut p)).now_or_never() {
+			Some(a) => Poll::Ready(a),
+			None    => Poll::Pending
+		}
+	}
+
+	fn request(behaviour: &mut StateSync<Block>, request: StateRequest<Hash>) -> Result<StorageProof, Error> {
+		let receiver = behaviour.on_request(&PeerId::random(), &request.encode())?;
+		let response = executor::block_on(receiver).unwrap().unwrap();
+		Ok(StorageProof::decode(&mut &response[..]).unwrap())
+	}
+
+	#[test]
+	fn serves_complete_state_in_chunks() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let pset = peerset();
+		let config = super::Config::new(&ProtocolId::from(&b"foo"[..]));
+		let mut behaviour = StateSync::new(config, client.clone(), pset.1);
+		let genesis = client.header(&BlockId::Number(0)).unwrap().unwrap();
+
+		let mut collected = Vec::new();
+		loop {
+			let start = collected.last().map(|(k, _): &(Vec<u8>, Vec<u8>)| k.clone()).unwrap_or_default();
+			let state_request = StateRequest { block: genesis.hash(), child: None, start: start.clone() };
+			let proof = request(&mut behaviour, state_request).unwrap();
+			let start = if start.is_empty() { None } else { Some(&start[..]) };
+			let (values, complete) = client.verify_range_proof(genesis.state_root, proof, start).unwrap();
+			collected.extend(values);
+			if complete {
+				break
+			}
+		}
+
+		assert!(!collected.is_empty());
+		let keys: Vec<_> = collected.iter().map(|(k, _)| k.clone()).collect();
+		let mut sorted = keys.clone();
+		sorted.sort();
+		sorted.dedup();
+		assert_eq!(keys, sorted);
+	}
+
+	#[test]
+	fn serves_default_child_tries_only() {
+		const CHILD_INFO: ChildInfo<'static> = ChildInfo::new_default(b"unique_id");
+		let storage_key = b":child_storage:default:unique_id".to_vec();
+		let client = Arc::new(TestClientBuilder::new()
+			.add_extra_child_storage(storage_key.clone(), CHILD_INFO, b"key".to_vec(), b"value".to_vec())
+			.build());
+		let pset = peerset();
+		let config = super::Config::new(&ProtocolId::from(&b"foo"[..]));
+		let mut behaviour = StateSync::new(config, client.clone(), pset.1);
+		let genesis = client.header(&BlockId::Number(0)).unwrap().unwrap();
+
+		let state_request = StateRequest { block: genesis.hash(), child: Some(storage_key), start: Vec::new() };
+		assert_ne!(request(&mut behaviour, state_request).unwrap(), StorageProof::empty());
+
+		let state_request = StateRequest {
+			block: genesis.hash(),
+			child: Some(b":child_storage:other:unique_id".to_vec()),
+			start: Vec::new(),
+		};
+		assert_matches!(request(&mut behaviour, state_request), Err(Error::UnsupportedChildTrie));
+	}
+
+	#[test]
+	fn finishes_immediately_if_state_is_available() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let pset = peerset();
+		let config = super::Config::new(&ProtocolId::from(&b"foo"[..]));
+		let mut behaviour = StateSync::new(config, client.clone(), pset.1);
+		let genesis = client.header(&BlockId::Number(0)).unwrap().unwrap();
+
+		behaviour.start(genesis);
+		assert!(!behaviour.is_syncing());
+		assert_matches!(poll(&mut behaviour), Poll::Ready(NetworkBehaviourAction::GenerateEvent(StateSyncOut::Finished)));
+	}
+}
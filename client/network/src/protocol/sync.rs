@@ -34,7 +34,7 @@ use sp_consensus::{BlockOrigin, BlockStatus,
 	import_queue::{IncomingBlock, BlockImportResult, BlockImportError}
 };
 use crate::{
-	config::BoxFinalityProofRequestBuilder,
+	config::{BoxFinalityProofRequestBuilder, SyncMode},
 	protocol::message::{self, generic::FinalityProofRequest, BlockAnnounce, BlockAttributes, BlockRequest, BlockResponse,
 	FinalityProofResponse, Roles},
 };
@@ -140,6 +140,17 @@ pub struct ChainSync<B: BlockT> {
 	max_parallel_downloads: u32,
 	/// Total number of processed blocks (imported or failed).
 	processed_blocks: usize,
+	/// Block requests are suspended while a warp sync is in progress, since the downloaded
+	/// blocks would be discarded once the warp sync target is imported.
+	warp_sync_in_progress: bool,
+	/// How we are syncing. Fast sync switches to `SyncMode::Full` once the state of a finalized
+	/// block has been downloaded.
+	mode: SyncMode,
+	/// Block requests are suspended while the state of a finalized block is being downloaded.
+	state_sync_in_progress: bool,
+	/// Blocks up to this number have been imported without state during fast sync, and must be
+	/// imported again along with their bodies.
+	reimport_up_to: Option<NumberFor<B>>,
 }
 
 /// All the data we have about a Peer that we are trying to sync with
@@ -296,10 +307,13 @@ impl<B: BlockT> ChainSync<B> {
 		request_builder: Option<BoxFinalityProofRequestBuilder<B>>,
 		block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 		max_parallel_downloads: u32,
+		mode: SyncMode,
 	) -> Self {
 		let mut required_block_attributes = BlockAttributes::HEADER | BlockAttributes::JUSTIFICATION;
+		// Light clients don't have any state to download.
+		let mode = if role.is_full() { mode } else { SyncMode::Full };
 
-		if role.is_full() {
+		if role.is_full() && mode == SyncMode::Full {
 			required_block_attributes |= BlockAttributes::BODY
 		}
 
@@ -321,6 +335,10 @@ impl<B: BlockT> ChainSync<B> {
 			block_announce_validator,
 			max_parallel_downloads,
 			processed_blocks: 0,
+			warp_sync_in_progress: false,
+			mode,
+			state_sync_in_progress: false,
+			reimport_up_to: None,
 		}
 	}
 
@@ -442,6 +460,20 @@ impl<B: BlockT> ChainSync<B> {
 
 				Ok(Some(ancestry_request::<B>(common_best)))
 			}
+			Ok(BlockStatus::InChainPruned) if best_number > self.best_queued_number => {
+				// We only have the header of the peer's best block, which happens after a fast
+				// sync. The blocks need to be downloaded again starting at our best queued block.
+				debug!(target:"sync", "New peer with best hash {} ({}) known without state.", best_hash, best_number);
+				self.peers.insert(who.clone(), PeerSync {
+					common_number: self.best_queued_number,
+					best_hash,
+					best_number,
+					state: PeerSyncState::Available,
+					recently_announced: Default::default(),
+				});
+				self.is_idle = false;
+				Ok(None)
+			}
 			Ok(BlockStatus::Queued) | Ok(BlockStatus::InChainWithState) | Ok(BlockStatus::InChainPruned) => {
 				debug!(target:"sync", "New peer with known best hash {} ({}).", best_hash, best_number);
 				self.peers.insert(who.clone(), PeerSync {
@@ -579,7 +611,7 @@ impl<B: BlockT> ChainSync<B> {
 
 	/// Get an iterator over all block requests of all peers.
 	pub fn block_requests(&mut self) -> impl Iterator<Item = (PeerId, BlockRequest<B>)> + '_ {
-		if self.is_idle {
+		if self.is_idle || self.warp_sync_in_progress || self.state_sync_in_progress {
 			return Either::Left(std::iter::empty())
 		}
 		if self.queue_blocks.len() > MAX_IMPORTING_BLOCKS {
@@ -671,10 +703,15 @@ impl<B: BlockT> ChainSync<B> {
 							self.blocks.clear_peer_download(&who);
 							self.blocks.insert(*start_block, blocks, who);
 							peer.state = PeerSyncState::Available;
+							let reimport_up_to = self.reimport_up_to;
 							self.blocks
 								.drain(self.best_queued_number + One::one())
 								.into_iter()
 								.map(|block_data| {
+									let import_existing = match (&block_data.block.header, reimport_up_to) {
+										(Some(header), Some(n)) => *header.number() <= n,
+										_ => false,
+									};
 									IncomingBlock {
 										hash: block_data.block.hash,
 										header: block_data.block.header,
@@ -682,7 +719,7 @@ impl<B: BlockT> ChainSync<B> {
 										justification: block_data.block.justification,
 										origin: block_data.origin,
 										allow_missing_state: true,
-										import_existing: false,
+										import_existing,
 									}
 								}).collect()
 						}
@@ -956,6 +993,11 @@ impl<B: BlockT> ChainSync<B> {
 					if number > self.best_imported_number {
 						self.best_imported_number = number;
 					}
+
+					if self.reimport_up_to.map_or(false, |n| number >= n) {
+						debug!(target: "sync", "Finished importing blocks previously imported without state");
+						self.reimport_up_to = None;
+					}
 				},
 				Err(BlockImportError::IncompleteHeader(who)) => {
 					if let Some(peer) = who {
@@ -1180,13 +1222,100 @@ impl<B: BlockT> ChainSync<B> {
 		self.is_idle = false;
 	}
 
+	/// Suspend block requests until `on_warp_sync_finished` is called.
+	pub fn begin_warp_sync(&mut self) {
+		debug!(target: "sync", "Suspending block requests during warp sync");
+		self.warp_sync_in_progress = true;
+	}
+
+	/// Call when the warp sync has finished, successfully or not. Block requests are resumed
+	/// from our (possibly new) best block.
+	pub fn on_warp_sync_finished<'a>(&'a mut self)
+		-> impl Iterator<Item = Result<(PeerId, BlockRequest<B>), BadPeer>> + 'a
+	{
+		self.warp_sync_in_progress = false;
+		self.restart()
+	}
+
+	/// Returns the block whose state should be downloaded, if we're fast syncing and have
+	/// caught up with the headers of our peers. Block requests are suspended until
+	/// `on_state_sync_finished` is called.
+	pub fn state_sync_target(&mut self) -> Option<B::Header> {
+		if self.mode != SyncMode::Fast || self.state_sync_in_progress || self.warp_sync_in_progress {
+			return None
+		}
+		if self.peers.is_empty() || !self.queue_blocks.is_empty() || self.status().state != SyncState::Idle {
+			return None
+		}
+
+		let info = self.client.info();
+		match self.client.header(BlockId::Hash(info.finalized_hash)) {
+			Ok(Some(header)) => {
+				debug!(
+					target: "sync",
+					"Headers downloaded up to #{}, requesting state of #{} ({})",
+					info.best_number,
+					info.finalized_number,
+					info.finalized_hash,
+				);
+				self.state_sync_in_progress = true;
+				Some(header)
+			}
+			Ok(None) => None,
+			Err(e) => {
+				warn!(target: "sync", "💔 Error reading finalized header: {:?}", e);
+				None
+			}
+		}
+	}
+
+	/// Call when the state download has finished, successfully or not. We switch to full sync
+	/// and import the blocks we only have the headers of again, starting at the last block
+	/// we have the state of.
+	pub fn on_state_sync_finished<'a>(&'a mut self)
+		-> impl Iterator<Item = Result<(PeerId, BlockRequest<B>), BadPeer>> + 'a
+	{
+		self.state_sync_in_progress = false;
+		self.mode = SyncMode::Full;
+		if self.role.is_full() {
+			self.required_block_attributes |= BlockAttributes::BODY;
+		}
+
+		let info = self.client.info();
+		let base = if self.block_status(&info.finalized_hash).ok() == Some(BlockStatus::InChainWithState) {
+			info.finalized_number
+		} else {
+			// The state could not be imported, so all blocks have to be executed.
+			Zero::zero()
+		};
+		if info.best_number > base {
+			self.reimport_up_to = Some(info.best_number);
+		}
+		// `best_imported_number` refers to the blocks we have state for from now on.
+		self.best_imported_number = base;
+		self.restart()
+	}
+
 	/// Restart the sync process.
 	fn restart<'a>(&'a mut self) -> impl Iterator<Item = Result<(PeerId, BlockRequest<B>), BadPeer>> + 'a {
 		self.processed_blocks = 0;
 		self.blocks.clear();
 		let info = self.client.info();
-		self.best_queued_hash = info.best_hash;
-		self.best_queued_number = std::cmp::max(info.best_number, self.best_imported_number);
+		match self.reimport_up_to {
+			Some(_) => {
+				// Our best block doesn't have state, resume from the last block imported with
+				// state instead.
+				self.best_queued_number = self.best_imported_number;
+				self.best_queued_hash = self.client.hash(self.best_imported_number)
+					.ok()
+					.flatten()
+					.unwrap_or(info.genesis_hash);
+			}
+			None => {
+				self.best_queued_hash = info.best_hash;
+				self.best_queued_number = std::cmp::max(info.best_number, self.best_imported_number);
+			}
+		}
 		self.is_idle = false;
 		debug!(target:"sync", "Restarted with {} ({})", self.best_queued_number, self.best_queued_hash);
 		let old_peers = std::mem::replace(&mut self.peers, HashMap::new());
@@ -1446,6 +1575,7 @@ mod test {
 			None,
 			block_announce_validator,
 			1,
+			SyncMode::Full,
 		);
 
 		let (a1_hash, a1_number) = {
@@ -0,0 +1,823 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! [`NetworkBehaviour`] implementation which handles warp sync requests.
+//!
+//! A warp sync request contains the SCALE-encoded hash of a finalized block and is answered
+//! with a proof, generated by the [`WarpSyncProvider`], of the finality of a more recent block.
+//! The format of the proof is opaque to the networking layer. Proofs are generated on a dedicated
+//! thread, and every peer may only have one request served at a time and at most one request
+//! per `MIN_REQUEST_INTERVAL`.
+//!
+//! When started (see [`WarpSync::start`]), the behaviour also drives our own warp sync: it
+//! requests proofs from peers one at a time, verifies them and finally imports the target
+//! block. A [`WarpSyncOut::Finished`] event is produced once it is done, whether the target
+//! could be imported or not, after which regular sync should take over.
+
+use bytes::Bytes;
+use codec::{Encode, Decode};
+use crate::{
+	chain::{WarpSyncProvider, WarpSyncVerification},
+	config::ProtocolId,
+};
+use futures::{channel::oneshot, future::BoxFuture, prelude::*, stream::FuturesUnordered};
+use libp2p::{
+	core::{
+		ConnectedPoint,
+		Multiaddr,
+		PeerId,
+		upgrade::{InboundUpgrade, ReadOneError, UpgradeInfo},
+		upgrade::{OutboundUpgrade, read_one, write_one}
+	},
+	swarm::{
+		NegotiatedSubstream,
+		NetworkBehaviour,
+		NetworkBehaviourAction,
+		OneShotHandler,
+		PollParameters,
+		SubstreamProtocol
+	}
+};
+use sc_peerset::ReputationChange;
+use sp_runtime::traits::{Block, Header, NumberFor};
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	iter,
+	sync::{Arc, mpsc},
+	thread,
+	time::Duration,
+	task::{Context, Poll}
+};
+use wasm_timer::Instant;
+
+/// Reputation change for a peer when a warp sync request timed out.
+const TIMEOUT_REPUTATION_CHANGE: i32 = -(1 << 8);
+
+/// Number of failed requests after which we give up on warp syncing.
+const MAX_FAILED_REQUESTS: usize = 8;
+
+/// Reputation change for a peer sending requests faster than we serve them.
+const FLOOD_REPUTATION_CHANGE: i32 = -(1 << 10);
+
+/// Minimal time between two requests of the same peer that we serve.
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of requests waiting for the proof worker after which further requests are dropped.
+const MAX_QUEUED_REQUESTS: usize = 32;
+
+/// Configuration options for `WarpSync` behaviour.
+#[derive(Debug, Clone)]
+pub struct Config {
+	max_request_size: usize,
+	max_response_size: usize,
+	inactivity_timeout: Duration,
+	request_timeout: Duration,
+	protocol: Bytes,
+}
+
+impl Config {
+	/// Create a fresh configuration with the following options:
+	///
+	/// - max. request size = 1 KiB
+	/// - max. response size = 16 MiB
+	/// - inactivity timeout = 15s
+	/// - request timeout = 30s
+	pub fn new(id: &ProtocolId) -> Self {
+		let mut c = Config {
+			max_request_size: 1024,
+			max_response_size: 16 * 1024 * 1024,
+			inactivity_timeout: Duration::from_secs(15),
+			request_timeout: Duration::from_secs(30),
+			protocol: Bytes::new(),
+		};
+		c.set_protocol(id);
+		c
+	}
+
+	/// Limit the max. length in bytes of a request.
+	pub fn set_max_request_size(&mut self, v: usize) -> &mut Self {
+		self.max_request_size = v;
+		self
+	}
+
+	/// Limit the max. length in bytes of a response.
+	pub fn set_max_response_size(&mut self, v: usize) -> &mut Self {
+		self.max_response_size = v;
+		self
+	}
+
+	/// Limit the max. duration the connection may remain inactive before closing it.
+	pub fn set_inactivity_timeout(&mut self, v: Duration) -> &mut Self {
+		self.inactivity_timeout = v;
+		self
+	}
+
+	/// Limit the max. request duration.
+	pub fn set_request_timeout(&mut self, v: Duration) -> &mut Self {
+		self.request_timeout = v;
+		self
+	}
+
+	/// Set protocol to use for upgrade negotiation.
+	pub fn set_protocol(&mut self, id: &ProtocolId) -> &mut Self {
+		let mut v = Vec::new();
+		v.extend_from_slice(b"/");
+		v.extend_from_slice(id.as_bytes());
+		v.extend_from_slice(b"/sync/warp");
+		self.protocol = v.into();
+		self
+	}
+}
+
+/// Event produced by the `WarpSync` behaviour.
+#[derive(Debug)]
+pub enum WarpSyncOut {
+	/// Our warp sync has finished, either by importing the target or by giving up.
+	Finished,
+}
+
+/// The warp sync behaviour.
+pub struct WarpSync<B: Block> {
+	/// This behaviour's configuration.
+	config: Config,
+	/// Generates and verifies warp sync proofs. Requests are ignored if `None`.
+	provider: Option<Arc<dyn WarpSyncProvider<B>>>,
+	/// Handle to use for reporting misbehaviour of peers.
+	peerset: sc_peerset::PeersetHandle,
+	/// Sends the requests to serve to the proof worker thread. `None` if there is no provider.
+	proof_worker: Option<mpsc::SyncSender<ProofRequest<B>>>,
+	/// Futures awaiting the proofs of remote requests and sending them back, resolving to the
+	/// requesting peer.
+	responses: FuturesUnordered<BoxFuture<'static, PeerId>>,
+	/// Peers whose request is being served, and when the last request of a peer was accepted.
+	serving: HashSet<PeerId>,
+	last_request: HashMap<PeerId, Instant>,
+	/// Best block number of the peers we're connected to, if known.
+	peers: HashMap<PeerId, Option<NumberFor<B>>>,
+	/// State of our own warp sync, if one is in progress.
+	sync: Option<SyncState<B>>,
+	/// Events to produce for the outside.
+	events: VecDeque<WarpSyncOut>,
+	/// Identifier of the next request we send.
+	next_request_id: u64,
+}
+
+/// State of our own warp sync.
+struct SyncState<B: Block> {
+	/// Block to request the next proof from. This is the last block we have verified.
+	begin: B::Hash,
+	/// The request currently in flight, if any.
+	outstanding: Option<OutstandingRequest>,
+	/// Peers that failed to answer one of our requests.
+	failed_peers: HashSet<PeerId>,
+	/// Total number of failed requests.
+	failures: usize,
+}
+
+/// A remote request to generate a proof for on the proof worker.
+struct ProofRequest<B: Block> {
+	begin: B::Hash,
+	response: oneshot::Sender<Result<Vec<u8>, sp_blockchain::Error>>,
+}
+
+/// Spawn the thread generating the proofs of remote requests, so that the network is not blocked
+/// by it. The thread exits once the returned sender is dropped.
+fn spawn_proof_worker<B: Block>(
+	provider: Arc<dyn WarpSyncProvider<B>>,
+) -> Option<mpsc::SyncSender<ProofRequest<B>>> {
+	let (sender, receiver) = mpsc::sync_channel::<ProofRequest<B>>(MAX_QUEUED_REQUESTS);
+	let spawned = thread::Builder::new()
+		.name("warp-sync-proofs".into())
+		.spawn(move || {
+			for request in receiver {
+				let _ = request.response.send(provider.generate(request.begin));
+			}
+		});
+	match spawned {
+		Ok(_) => Some(sender),
+		Err(e) => {
+			log::warn!("❗️ Failed to spawn the warp sync proof worker: {}", e);
+			None
+		}
+	}
+}
+
+/// A request we have sent and are awaiting the response for.
+struct OutstandingRequest {
+	id: u64,
+	peer: PeerId,
+	timestamp: Instant,
+}
+
+impl<B> WarpSync<B>
+where
+	B: Block,
+{
+	/// Construct a new warp sync handler.
+	pub fn new(
+		config: Config,
+		provider: Option<Arc<dyn WarpSyncProvider<B>>>,
+		peerset: sc_peerset::PeersetHandle,
+	) -> Self {
+		WarpSync {
+			config,
+			proof_worker: provider.clone().and_then(spawn_proof_worker),
+			provider,
+			peerset,
+			responses: FuturesUnordered::new(),
+			serving: HashSet::new(),
+			last_request: HashMap::new(),
+			peers: HashMap::new(),
+			sync: None,
+			events: VecDeque::new(),
+			next_request_id: 1,
+		}
+	}
+
+	/// Start warp syncing from the given finalized block.
+	///
+	/// Does nothing (apart from producing a `Finished` event) if there is no provider to
+	/// verify the proofs.
+	pub fn start(&mut self, begin: B::Hash) {
+		if self.provider.is_none() {
+			log::debug!(target: "sync", "No provider available to verify warp sync proofs");
+			self.events.push_back(WarpSyncOut::Finished);
+			return
+		}
+
+		log::debug!(target: "sync", "Starting warp sync from {}", begin);
+		self.sync = Some(SyncState {
+			begin,
+			outstanding: None,
+			failed_peers: HashSet::new(),
+			failures: 0,
+		});
+	}
+
+	/// Is our own warp sync in progress?
+	pub fn is_syncing(&self) -> bool {
+		self.sync.is_some()
+	}
+
+	/// Call this when we receive a new best block announcement from a peer.
+	pub fn update_best_block(&mut self, peer: &PeerId, num: NumberFor<B>) {
+		if let Some(best) = self.peers.get_mut(peer) {
+			log::trace!(target: "sync", "new best block for {:?}: {:?}", peer, num);
+			*best = Some(num)
+		}
+	}
+
+	fn next_request_id(&mut self) -> u64 {
+		let id = self.next_request_id;
+		self.next_request_id += 1;
+		id
+	}
+
+	/// Record that the outstanding request to the given peer has failed.
+	fn on_request_failed(&mut self, peer: &PeerId) {
+		if let Some(state) = &mut self.sync {
+			state.outstanding = None;
+			state.failed_peers.insert(peer.clone());
+			state.failures += 1;
+		}
+	}
+
+	/// Stop our own warp sync and notify the outside.
+	fn finish(&mut self) {
+		self.sync = None;
+		self.events.push_back(WarpSyncOut::Finished);
+	}
+
+	/// Callback, invoked when a new warp sync request has been received from remote.
+	///
+	/// Hands the request over to the proof worker and returns the receiver of the proof.
+	fn on_request(
+		&mut self,
+		peer: &PeerId,
+		request: &[u8],
+	) -> Result<oneshot::Receiver<Result<Vec<u8>, sp_blockchain::Error>>, Error> {
+		let worker = self.proof_worker.as_ref().ok_or(Error::NotSupported)?;
+		let begin = B::Hash::decode(&mut &request[..]).map_err(Error::BadRequest)?;
+
+		let now = Instant::now();
+		let too_soon = self.last_request.get(peer)
+			.map_or(false, |last| now < *last + MIN_REQUEST_INTERVAL);
+		if too_soon || self.serving.contains(peer) {
+			return Err(Error::TooManyRequests)
+		}
+
+		log::trace!(target: "sync", "warp sync request from peer {} starting at {}", peer, begin);
+
+		let (response, receiver) = oneshot::channel();
+		worker.try_send(ProofRequest { begin, response }).map_err(|e| match e {
+			mpsc::TrySendError::Full(_) => Error::Busy,
+			mpsc::TrySendError::Disconnected(_) => Error::NotSupported,
+		})?;
+		self.serving.insert(peer.clone());
+		self.last_request.insert(peer.clone(), now);
+		Ok(receiver)
+	}
+
+	/// Callback, invoked when a response to our own request has been received.
+	fn on_response(&mut self, peer: PeerId, response: Vec<u8>) {
+		let provider = match &self.provider {
+			Some(provider) => provider.clone(),
+			None => return,
+		};
+
+		match provider.verify(&response) {
+			Ok(WarpSyncVerification::Partial(next)) => {
+				log::debug!(target: "sync", "Verified partial warp sync proof from {}, continuing at {}", peer, next);
+				if let Some(state) = &mut self.sync {
+					state.outstanding = None;
+					state.begin = next;
+				}
+			}
+			Ok(WarpSyncVerification::Complete(target)) => {
+				let hash = target.hash();
+				let number = *target.number();
+				match provider.import_target(target) {
+					Ok(()) => log::info!("⏩ Warp sync imported block #{} ({})", number, hash),
+					Err(e) => log::warn!("❗️ Failed to import warp sync target #{} ({}): {}", number, hash, e),
+				}
+				self.finish();
+			}
+			Err(e) => {
+				log::debug!(target: "sync", "Invalid warp sync proof from peer {}: {}", peer, e);
+				self.on_request_failed(&peer);
+				self.peerset.report_peer(peer, ReputationChange::new_fatal("invalid warp sync proof"));
+			}
+		}
+	}
+
+	/// Pick a peer to send our next request to. We prefer peers with the highest best block.
+	fn available_peer(&self) -> Option<PeerId> {
+		let state = self.sync.as_ref()?;
+		self.peers.iter()
+			.filter(|(peer, _)| !state.failed_peers.contains(peer))
+			.filter_map(|(peer, best)| best.map(|best| (peer, best)))
+			.max_by_key(|(_, best)| *best)
+			.map(|(peer, _)| peer.clone())
+	}
+}
+
+impl<B> NetworkBehaviour for WarpSync<B>
+where
+	B: Block
+{
+	type ProtocolsHandler = OneShotHandler<InboundProtocol, OutboundProtocol, Event<NegotiatedSubstream>>;
+	type OutEvent = WarpSyncOut;
+
+	fn new_handler(&mut self) -> Self::ProtocolsHandler {
+		let p = InboundProtocol {
+			max_request_size: self.config.max_request_size,
+			protocol: self.config.protocol.clone(),
+		};
+		OneShotHandler::new(SubstreamProtocol::new(p), self.config.inactivity_timeout)
+	}
+
+	fn addresses_of_peer(&mut self, _: &PeerId) -> Vec<Multiaddr> {
+		Vec::new()
+	}
+
+	fn inject_connected(&mut self, peer: PeerId, _: ConnectedPoint) {
+		self.peers.insert(peer, None);
+	}
+
+	fn inject_disconnected(&mut self, peer: &PeerId, _: ConnectedPoint) {
+		self.peers.remove(peer);
+		self.last_request.remove(peer);
+
+		let is_outstanding = self.sync.as_ref()
+			.and_then(|state| state.outstanding.as_ref())
+			.map_or(false, |request| &request.peer == peer);
+		if is_outstanding {
+			log::debug!(target: "sync", "peer {} disconnected during warp sync request", peer);
+			self.on_request_failed(peer);
+		}
+	}
+
+	fn inject_node_event(&mut self, peer: PeerId, event: Event<NegotiatedSubstream>) {
+		match event {
+			// An incoming request from remote has been received.
+			Event::Request(request, mut stream) => {
+				match self.on_request(&peer, &request) {
+					Ok(proof) => {
+						let future = async move {
+							match proof.await {
+								Ok(Ok(response)) => {
+									log::trace!(target: "sync", "sending warp sync response to peer {}", peer);
+									if let Err(e) = write_one(&mut stream, response).await {
+										log::debug!("error writing warp sync response: {}", e)
+									}
+								}
+								Ok(Err(e)) => log::debug!("error generating warp sync proof for peer {}: {}", peer, e),
+								Err(_) => log::debug!("warp sync proof worker stopped"),
+							}
+							peer
+						};
+						self.responses.push(future.boxed())
+					}
+					Err(Error::BadRequest(_)) => {
+						self.peerset.report_peer(peer, ReputationChange::new(-(1 << 12), "bad warp sync request"))
+					}
+					Err(Error::TooManyRequests) => {
+						self.peerset.report_peer(peer, ReputationChange::new(FLOOD_REPUTATION_CHANGE, "too many warp sync requests"))
+					}
+					Err(e) => log::debug!("error handling warp sync request from peer {}: {}", peer, e)
+				}
+			}
+			// A response to one of our own requests has been received.
+			Event::Response(id, response) => {
+				let is_expected = self.sync.as_ref()
+					.and_then(|state| state.outstanding.as_ref())
+					.map_or(false, |request| request.id == id && request.peer == peer);
+				if !is_expected {
+					log::debug!("unexpected warp sync response {} from peer {}", id, peer);
+					self.peerset.report_peer(peer, ReputationChange::new_fatal("unexpected warp sync response"));
+					return
+				}
+
+				self.on_response(peer, response)
+			}
+		}
+	}
+
+	fn poll(&mut self, cx: &mut Context, _: &mut impl PollParameters) -> Poll<NetworkBehaviourAction<OutboundProtocol, WarpSyncOut>> {
+		// Process response sending futures.
+		while let Poll::Ready(Some(peer)) = self.responses.poll_next_unpin(cx) {
+			self.serving.remove(&peer);
+		}
+
+		if let Some(event) = self.events.pop_front() {
+			return Poll::Ready(NetworkBehaviourAction::GenerateEvent(event))
+		}
+
+		// Look for our request having timed out.
+		let now = Instant::now();
+		let timed_out = self.sync.as_ref()
+			.and_then(|state| state.outstanding.as_ref())
+			.filter(|request| now > request.timestamp + self.config.request_timeout)
+			.map(|request| request.peer.clone());
+		if let Some(peer) = timed_out {
+			log::debug!(target: "sync", "warp sync request to {} timed out", peer);
+			self.on_request_failed(&peer);
+			self.peerset.report_peer(peer, ReputationChange::new(TIMEOUT_REPUTATION_CHANGE, "warp sync request timeout"));
+		}
+
+		let (begin, failures, idle) = match &self.sync {
+			Some(state) => (state.begin, state.failures, state.outstanding.is_none()),
+			None => return Poll::Pending,
+		};
+
+		if failures >= MAX_FAILED_REQUESTS {
+			log::warn!("❗️ Giving up on warp sync after {} failed requests", failures);
+			self.finish();
+			return Poll::Ready(NetworkBehaviourAction::GenerateEvent(
+				self.events.pop_front().expect("`finish` has just pushed an event; qed")
+			))
+		}
+
+		if !idle {
+			return Poll::Pending
+		}
+
+		if let Some(peer) = self.available_peer() {
+			let id = self.next_request_id();
+			log::trace!(target: "sync", "sending warp sync request {} to peer {}", id, peer);
+			let protocol = OutboundProtocol {
+				request: begin.encode(),
+				request_id: id,
+				max_response_size: self.config.max_response_size,
+				protocol: self.config.protocol.clone(),
+			};
+			if let Some(state) = &mut self.sync {
+				state.outstanding = Some(OutstandingRequest { id, peer: peer.clone(), timestamp: now });
+			}
+			return Poll::Ready(NetworkBehaviourAction::SendEvent { peer_id: peer, event: protocol })
+		}
+
+		Poll::Pending
+	}
+}
+
+/// Possible errors while handling warp sync requests.
+#[derive(Debug, derive_more::Display, derive_more::From)]
+pub enum Error {
+	/// We don't have a provider to generate proofs.
+	#[display(fmt = "warp sync is not supported")]
+	NotSupported,
+	/// The request could not be decoded.
+	#[display(fmt = "bad request: {}", _0)]
+	BadRequest(codec::Error),
+	/// The peer sent a request while another one was being served or too soon after the last.
+	#[display(fmt = "too many requests")]
+	TooManyRequests,
+	/// Too many requests are waiting for their proofs.
+	#[display(fmt = "proof worker is busy")]
+	Busy,
+}
+
+/// Output type of inbound and outbound substream upgrades.
+#[derive(Debug)]
+pub enum Event<T> {
+	/// Incoming request from remote and substream to use for the response.
+	Request(Vec<u8>, T),
+	/// Incoming response from remote.
+	Response(u64, Vec<u8>),
+}
+
+/// Substream upgrade protocol.
+///
+/// Reads incoming requests from remote.
+#[derive(Debug, Clone)]
+pub struct InboundProtocol {
+	/// The max. request length in bytes.
+	max_request_size: usize,
+	/// The protocol to use for upgrade negotiation.
+	protocol: Bytes,
+}
+
+impl UpgradeInfo for InboundProtocol {
+	type Info = Bytes;
+	type InfoIter = iter::Once<Self::Info>;
+
+	fn protocol_info(&self) -> Self::InfoIter {
+		iter::once(self.protocol.clone())
+	}
+}
+
+impl<T> InboundUpgrade<T> for InboundProtocol
+where
+	T: AsyncRead + AsyncWrite + Unpin + Send + 'static
+{
+	type Output = Event<T>;
+	type Error = ReadOneError;
+	type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+	fn upgrade_inbound(self, mut s: T, _: Self::Info) -> Self::Future {
+		let future = async move {
+			let vec = read_one(&mut s, self.max_request_size).await?;
+			Ok(Event::Request(vec, s))
+		};
+		future.boxed()
+	}
+}
+
+/// Substream upgrade protocol.
+///
+/// Sends a request to remote and awaits the response.
+#[derive(Debug, Clone)]
+pub struct OutboundProtocol {
+	/// The SCALE-encoded request.
+	request: Vec<u8>,
+	/// Local identifier for the request. Used to associate it with a response.
+	request_id: u64,
+	/// The max. response length in bytes.
+	max_response_size: usize,
+	/// The protocol to use for upgrade negotiation.
+	protocol: Bytes,
+}
+
+impl UpgradeInfo for OutboundProtocol {
+	type Info = Bytes;
+	type InfoIter = iter::Once<Self::Info>;
+
+	fn protocol_info(&self) -> Self::InfoIter {
+		iter::once(self.protocol.clone())
+	}
+}
+
+impl<T> OutboundUpgrade<T> for OutboundProtocol
+where
+	T: AsyncRead + AsyncWrite + Unpin + Send + 'static
+{
+	type Output = Event<T>;
+	type Error = ReadOneError;
+	type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+	fn upgrade_outbound(self, mut s: T, _: Self::Info) -> Self::Future {
+		let future = async move {
+			write_one(&mut s, &self.request).await?;
+			let vec = read_one(&mut s, self.max_response_size).await?;
+			Ok(Event::Response(self.request_id, vec))
+		};
+		future.boxed()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert_matches::assert_matches;
+	use futures::prelude::*;
+	use libp2p::{
+		PeerId,
+		Multiaddr,
+		core::ConnectedPoint,
+		swarm::{NetworkBehaviour, NetworkBehaviourAction, PollParameters},
+	};
+	use parking_lot::Mutex;
+	use sp_runtime::{generic::Header, traits::{BlakeTwo256, Block as BlockT}};
+	use std::{iter, pin::Pin, sync::Arc, task::Poll};
+	use super::{Event, OutboundProtocol, WarpSync, WarpSyncOut};
+	use crate::chain::{WarpSyncProvider, WarpSyncVerification};
+	use crate::config::ProtocolId;
+
+	type Block = sp_runtime::generic::Block<Header<u64, BlakeTwo256>, substrate_test_runtime::Extrinsic>;
+
+	/// Accepts proofs `[0]` (partial) and `[1]` (complete) and records imported targets.
+	#[derive(Default)]
+	struct DummyProvider {
+		imported: Mutex<Vec<<Block as BlockT>::Header>>,
+	}
+
+	fn dummy_header() -> <Block as BlockT>::Header {
+		Header {
+			parent_hash: Default::default(),
+			number: 42,
+			state_root: Default::default(),
+			extrinsics_root: Default::default(),
+			digest: Default::default(),
+		}
+	}
+
+	impl WarpSyncProvider<Block> for DummyProvider {
+		fn generate(&self, _begin: <Block as BlockT>::Hash) -> Result<Vec<u8>, sp_blockchain::Error> {
+			Ok(vec![1])
+		}
+
+		fn verify(&self, proof: &[u8]) -> Result<WarpSyncVerification<Block>, sp_blockchain::Error> {
+			match proof {
+				[0] => Ok(WarpSyncVerification::Partial([1; 32].into())),
+				[1] => Ok(WarpSyncVerification::Complete(dummy_header())),
+				_ => Err(sp_blockchain::Error::BadJustification("invalid proof".into())),
+			}
+		}
+
+		fn import_target(&self, target: <Block as BlockT>::Header) -> Result<(), sp_blockchain::Error> {
+			self.imported.lock().push(target);
+			Ok(())
+		}
+	}
+
+	struct EmptyPollParams(PeerId);
+
+	impl PollParameters for EmptyPollParams {
+		type SupportedProtocolsIter = iter::Empty<Vec<u8>>;
+		type ListenedAddressesIter = iter::Empty<Multiaddr>;
+		type ExternalAddressesIter = iter::Empty<Multiaddr>;
+
+		fn supported_protocols(&self) -> Self::SupportedProtocolsIter {
+			iter::empty()
+		}
+
+		fn listened_addresses(&self) -> Self::ListenedAddressesIter {
+			iter::empty()
+		}
+
+		fn external_addresses(&self) -> Self::ExternalAddressesIter {
+			iter::empty()
+		}
+
+		fn local_peer_id(&self) -> &PeerId {
+			&self.0
+		}
+	}
+
+	fn peerset() -> (sc_peerset::Peerset, sc_peerset::PeersetHandle) {
+		let cfg = sc_peerset::PeersetConfig {
+			in_peers: 128,
+			out_peers: 128,
+			bootnodes: Vec::new(),
+			reserved_only: false,
+			priority_groups: Vec::new(),
+		};
+		sc_peerset::Peerset::from_config(cfg)
+	}
+
+	fn make_behaviour(provider: Arc<DummyProvider>, ps: sc_peerset::PeersetHandle) -> WarpSync<Block> {
+		let config = super::Config::new(&ProtocolId::from(&b"foo"[..]));
+		WarpSync::new(config, Some(provider as Arc<_>), ps)
+	}
+
+	fn empty_dialer() -> ConnectedPoint {
+		ConnectedPoint::Dialer { address: Multiaddr::empty() }
+	}
+
+	fn poll(mut b: &mut WarpSync<Block>) -> Poll<NetworkBehaviourAction<OutboundProtocol, WarpSyncOut>> {
+		let mut p = EmptyPollParams(PeerId::random());
+		match future::poll_fn(|cx| Pin::new(&mut b).poll(cx, &mut p)).now_or_never() {
+			Some(a) => Poll::Ready(a),
+			None    => Poll::Pending
+		}
+	}
+
+	fn expect_request(behaviour: &mut WarpSync<Block>, expected_peer: &PeerId) -> u64 {
+		match poll(behaviour) {
+			Poll::Ready(NetworkBehaviourAction::SendEvent { peer_id, event }) => {
+				assert_eq!(&peer_id, expected_peer);
+				event.request_id
+			}
+			_ => panic!("expected a warp sync request"),
+		}
+	}
+
+	#[test]
+	fn does_not_send_requests_when_not_syncing() {
+		let pset = peerset();
+		let mut behaviour = make_behaviour(Default::default(), pset.1);
+		let peer = PeerId::random();
+
+		behaviour.inject_connected(peer.clone(), empty_dialer());
+		behaviour.update_best_block(&peer, 100);
+
+		assert!(!behaviour.is_syncing());
+		assert_matches!(poll(&mut behaviour), Poll::Pending);
+	}
+
+	#[test]
+	fn follows_partial_proofs_and_imports_target() {
+		let pset = peerset();
+		let provider = Arc::new(DummyProvider::default());
+		let mut behaviour = make_behaviour(provider.clone(), pset.1);
+		let peer = PeerId::random();
+
+		behaviour.start(Default::default());
+		behaviour.inject_connected(peer.clone(), empty_dialer());
+
+		// peers without a known best block are not asked.
+		assert_matches!(poll(&mut behaviour), Poll::Pending);
+		behaviour.update_best_block(&peer, 100);
+
+		let id = expect_request(&mut behaviour, &peer);
+		behaviour.inject_node_event(peer.clone(), Event::Response(id, vec![0]));
+		assert_eq!(behaviour.sync.as_ref().unwrap().begin, [1; 32].into());
+
+		let id = expect_request(&mut behaviour, &peer);
+		behaviour.inject_node_event(peer.clone(), Event::Response(id, vec![1]));
+		assert!(!behaviour.is_syncing());
+		assert_eq!(*provider.imported.lock(), vec![dummy_header()]);
+		assert_matches!(poll(&mut behaviour), Poll::Ready(NetworkBehaviourAction::GenerateEvent(WarpSyncOut::Finished)));
+	}
+
+	#[test]
+	fn avoids_peers_with_invalid_proofs() {
+		let pset = peerset();
+		let mut behaviour = make_behaviour(Default::default(), pset.1);
+		let peer0 = PeerId::random();
+		let peer1 = PeerId::random();
+
+		behaviour.start(Default::default());
+		behaviour.inject_connected(peer0.clone(), empty_dialer());
+		behaviour.inject_connected(peer1.clone(), empty_dialer());
+		behaviour.update_best_block(&peer0, 100);
+		behaviour.update_best_block(&peer1, 50);
+
+		let id = expect_request(&mut behaviour, &peer0);
+		behaviour.inject_node_event(peer0.clone(), Event::Response(id, vec![2]));
+		assert_eq!(behaviour.sync.as_ref().unwrap().failures, 1);
+
+		// the next request goes to the remaining peer.
+		let id = expect_request(&mut behaviour, &peer1);
+
+		// unexpected responses are ignored.
+		behaviour.inject_node_event(peer0.clone(), Event::Response(id, vec![1]));
+		assert!(behaviour.is_syncing());
+
+		// a disconnect counts as a failed request as well.
+		behaviour.inject_disconnected(&peer1, empty_dialer());
+		assert_eq!(behaviour.sync.as_ref().unwrap().failures, 2);
+		assert_matches!(poll(&mut behaviour), Poll::Pending);
+	}
+
+	#[test]
+	fn gives_up_after_too_many_failures() {
+		let pset = peerset();
+		let mut behaviour = make_behaviour(Default::default(), pset.1);
+
+		behaviour.start(Default::default());
+		for _ in 0..super::MAX_FAILED_REQUESTS {
+			let peer = PeerId::random();
+			behaviour.inject_connected(peer.clone(), empty_dialer());
+			behaviour.update_best_block(&peer, 100);
+			let id = expect_request(&mut behaviour, &peer);
+			behaviour.inject_node_event(peer, Event::Response(id, vec![2]));
+		}
+
+		assert_matches!(poll(&mut behaviour), Poll::Ready(NetworkBehaviourAction::GenerateEvent(WarpSyncOut::Finished)));
+		assert!(!behaviour.is_syncing());
+	}
+}
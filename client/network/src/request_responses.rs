@@ -0,0 +1,614 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Collection of generic request-response protocols.
+//!
+//! The [`RequestResponsesBehaviour`] implements [`NetworkBehaviour`] for all the protocols
+//! registered through the network configuration (see [`ProtocolConfig`]). Each protocol is
+//! identified by its name, which is used for substream negotiation.
+//!
+//! A request consists of opening a substream, sending the request bytes and reading the response
+//! bytes. Requests and responses are opaque to this module.
+//!
+//! Incoming requests are sent to the `inbound_queue` of the corresponding protocol, if any,
+//! together with a channel to send back the response. Dropping that channel refuses the request.
+//!
+//! > **Note**: Any error on a substream (including a remote not supporting the protocol) closes
+//! >			the connection to that remote, as is the case for the other request protocols of
+//! >			this crate. Only send requests to peers that are known to support the protocol.
+
+use bytes::Bytes;
+use futures::{
+	channel::{mpsc, oneshot},
+	future::{self, BoxFuture, Either},
+	prelude::*,
+	stream::FuturesUnordered,
+};
+use futures_timer::Delay;
+use libp2p::{
+	core::{
+		ConnectedPoint,
+		Multiaddr,
+		PeerId,
+		upgrade::{InboundUpgrade, ReadOneError, UpgradeInfo},
+		upgrade::{OutboundUpgrade, read_one, write_one}
+	},
+	swarm::{
+		NegotiatedSubstream,
+		NetworkBehaviour,
+		NetworkBehaviourAction,
+		OneShotHandler,
+		PollParameters,
+		SubstreamProtocol
+	}
+};
+use std::{
+	borrow::Cow,
+	collections::{HashMap, HashSet, VecDeque},
+	time::Duration,
+	task::{Context, Poll}
+};
+use wasm_timer::Instant;
+
+/// Duration after which a connection without any ongoing request is closed by the handler.
+const INACTIVITY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Configuration for a single request-response protocol.
+#[derive(Debug, Clone)]
+pub struct ProtocolConfig {
+	/// Name of the protocol on the wire. Should be something like `/foo/bar`.
+	pub name: Cow<'static, str>,
+
+	/// Maximum allowed size, in bytes, of a request.
+	///
+	/// Any request larger than this value will be declined as a way to avoid allocating too
+	/// much memory for it.
+	pub max_request_size: usize,
+
+	/// Maximum allowed size, in bytes, of a response.
+	///
+	/// Any response larger than this value will be declined as a way to avoid allocating too
+	/// much memory for it.
+	pub max_response_size: usize,
+
+	/// Duration after which emitted requests are considered timed out.
+	///
+	/// This is also the time given to the local node to answer incoming requests.
+	pub request_timeout: Duration,
+
+	/// Channel on which the networking service will send incoming requests.
+	///
+	/// If this is `None`, then the local node will not advertise support for this protocol
+	/// towards other peers, and incoming requests are refused. If this is `Some`, the queue is
+	/// bounded, and requests that arrive while it is full are refused.
+	pub inbound_queue: Option<mpsc::Sender<IncomingRequest>>,
+}
+
+/// A single request received by a peer on a request-response protocol.
+#[derive(Debug)]
+pub struct IncomingRequest {
+	/// Who sent the request.
+	pub peer: PeerId,
+
+	/// Request sent by the remote. Will always be smaller than
+	/// [`ProtocolConfig::max_request_size`].
+	pub payload: Vec<u8>,
+
+	/// Channel to send back the response to.
+	///
+	/// Dropping this sender, or not answering within [`ProtocolConfig::request_timeout`],
+	/// refuses the request.
+	pub pending_response: oneshot::Sender<Vec<u8>>,
+}
+
+/// Error in a request.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display)]
+pub enum RequestFailure {
+	/// We are not currently connected to the requested peer.
+	#[display(fmt = "we are not connected to the requested peer")]
+	NotConnected,
+	/// Given protocol hasn't been registered.
+	#[display(fmt = "the given protocol has not been registered")]
+	UnknownProtocol,
+	/// The remote didn't answer within the protocol's request timeout.
+	#[display(fmt = "the request timed out")]
+	Timeout,
+	/// The connection to the remote was closed before it answered, either because of a network
+	/// error, because the remote refused the request, or because the network worker shut down.
+	#[display(fmt = "the connection was closed before a response was received")]
+	ConnectionClosed,
+}
+
+impl std::error::Error for RequestFailure {}
+
+/// Error when registering a protocol.
+#[derive(Debug, derive_more::Display)]
+pub enum RegisterError {
+	/// A protocol has been specified multiple times.
+	#[display(fmt = "request-response protocol {} is registered multiple times", _0)]
+	DuplicateProtocol(Cow<'static, str>),
+}
+
+/// A request we have sent and are awaiting the response for.
+struct PendingRequest {
+	/// The peer the request has been sent to.
+	peer: PeerId,
+	/// When the request expires.
+	deadline: Instant,
+	/// Channel to report the outcome of the request to.
+	response_tx: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+}
+
+/// Implementation of `NetworkBehaviour` that provides support for request-response protocols.
+pub struct RequestResponsesBehaviour {
+	/// The registered protocols, indexed by name.
+	protocols: HashMap<Bytes, ProtocolConfig>,
+	/// Peers we are connected to.
+	peers: HashSet<PeerId>,
+	/// Requests we have sent and whose response hasn't been received yet.
+	pending_requests: HashMap<u64, PendingRequest>,
+	/// Futures answering remote requests.
+	responses: FuturesUnordered<BoxFuture<'static, ()>>,
+	/// Requests to send out on the next call to `poll`.
+	outbound: VecDeque<(PeerId, OutboundProtocol)>,
+	/// Identifier of the next request we send.
+	next_request_id: u64,
+}
+
+impl RequestResponsesBehaviour {
+	/// Creates a new behaviour. Must be passed a list of supported protocols. Returns an error if
+	/// the same protocol is passed twice.
+	pub fn new(list: impl IntoIterator<Item = ProtocolConfig>) -> Result<Self, RegisterError> {
+		let mut protocols = HashMap::new();
+		for protocol in list {
+			let name = Bytes::copy_from_slice(protocol.name.as_bytes());
+			if protocols.contains_key(&name) {
+				return Err(RegisterError::DuplicateProtocol(protocol.name))
+			}
+			protocols.insert(name, protocol);
+		}
+
+		Ok(RequestResponsesBehaviour {
+			protocols,
+			peers: HashSet::new(),
+			pending_requests: HashMap::new(),
+			responses: FuturesUnordered::new(),
+			outbound: VecDeque::new(),
+			next_request_id: 1,
+		})
+	}
+
+	/// Initiates sending a request.
+	///
+	/// The outcome of the request is sent on `response_tx`.
+	pub fn send_request(
+		&mut self,
+		target: &PeerId,
+		protocol: &str,
+		request: Vec<u8>,
+		response_tx: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	) {
+		let (name, config) = match self.protocols.get_key_value(protocol.as_bytes()) {
+			Some(protocol) => protocol,
+			None => {
+				let _ = response_tx.send(Err(RequestFailure::UnknownProtocol));
+				return
+			}
+		};
+
+		if !self.peers.contains(target) {
+			let _ = response_tx.send(Err(RequestFailure::NotConnected));
+			return
+		}
+
+		let request_id = self.next_request_id;
+		self.next_request_id += 1;
+
+		log::trace!(target: "sub-libp2p", "sending request {} on {} to peer {}", request_id, protocol, target);
+
+		let outbound = OutboundProtocol {
+			request,
+			request_id,
+			max_response_size: config.max_response_size,
+			protocol: name.clone(),
+		};
+		self.pending_requests.insert(request_id, PendingRequest {
+			peer: target.clone(),
+			deadline: Instant::now() + config.request_timeout,
+			response_tx,
+		});
+		self.outbound.push_back((target.clone(), outbound));
+	}
+
+	/// Callback, invoked when a new request has been received from remote.
+	fn on_request(&mut self, peer: PeerId, protocol: Bytes, request: Vec<u8>, mut stream: NegotiatedSubstream) {
+		let config = match self.protocols.get_mut(&protocol) {
+			Some(config) => config,
+			None => return,
+		};
+		let queue = match &mut config.inbound_queue {
+			Some(queue) => queue,
+			None => return,
+		};
+
+		let (response_tx, response_rx) = oneshot::channel();
+		let request = IncomingRequest { peer: peer.clone(), payload: request, pending_response: response_tx };
+		if let Err(e) = queue.try_send(request) {
+			log::debug!(
+				target: "sub-libp2p",
+				"dropping request from {} on {}: {}",
+				peer,
+				config.name,
+				if e.is_full() { "queue is full" } else { "queue is closed" },
+			);
+			return
+		}
+
+		let timeout = Delay::new(config.request_timeout);
+		let future = async move {
+			match future::select(response_rx, timeout).await {
+				Either::Left((Ok(response), _)) => {
+					if let Err(e) = write_one(&mut stream, response).await {
+						log::debug!(target: "sub-libp2p", "error writing response to {}: {}", peer, e)
+					}
+				}
+				// The request has been refused or not answered in time. Dropping the stream
+				// signals it to the remote.
+				Either::Left((Err(_), _)) | Either::Right(_) => {}
+			}
+		};
+		self.responses.push(future.boxed())
+	}
+
+	/// Callback, invoked when a response to one of our requests has been received.
+	fn on_response(&mut self, peer: PeerId, request_id: u64, response: Vec<u8>) {
+		match self.pending_requests.remove(&request_id) {
+			Some(request) if request.peer == peer => {
+				let _ = request.response_tx.send(Ok(response));
+			}
+			Some(request) => {
+				// Responses always arrive on the connection the request has been sent on.
+				log::error!(target: "sub-libp2p", "response {} from {} expected from {}", request_id, peer, request.peer);
+				self.pending_requests.insert(request_id, request);
+			}
+			// The request has already timed out.
+			None => log::debug!(target: "sub-libp2p", "obsolete response {} from {}", request_id, peer),
+		}
+	}
+}
+
+impl NetworkBehaviour for RequestResponsesBehaviour {
+	type ProtocolsHandler = OneShotHandler<InboundProtocol, OutboundProtocol, Event<NegotiatedSubstream>>;
+	type OutEvent = void::Void;
+
+	fn new_handler(&mut self) -> Self::ProtocolsHandler {
+		let protocols = self.protocols.iter()
+			.filter(|(_, config)| config.inbound_queue.is_some())
+			.map(|(name, config)| (name.clone(), config.max_request_size))
+			.collect();
+		OneShotHandler::new(SubstreamProtocol::new(InboundProtocol { protocols }), INACTIVITY_TIMEOUT)
+	}
+
+	fn addresses_of_peer(&mut self, _: &PeerId) -> Vec<Multiaddr> {
+		Vec::new()
+	}
+
+	fn inject_connected(&mut self, peer: PeerId, _: ConnectedPoint) {
+		self.peers.insert(peer);
+	}
+
+	fn inject_disconnected(&mut self, peer: &PeerId, _: ConnectedPoint) {
+		self.peers.remove(peer);
+
+		let failed = self.pending_requests.iter()
+			.filter(|(_, request)| &request.peer == peer)
+			.map(|(id, _)| *id)
+			.collect::<Vec<_>>();
+		for id in failed {
+			if let Some(request) = self.pending_requests.remove(&id) {
+				let _ = request.response_tx.send(Err(RequestFailure::ConnectionClosed));
+			}
+		}
+		self.outbound.retain(|(target, _)| target != peer);
+	}
+
+	fn inject_node_event(&mut self, peer: PeerId, event: Event<NegotiatedSubstream>) {
+		match event {
+			Event::Request { protocol, request, stream } => self.on_request(peer, protocol, request, stream),
+			Event::Response { request_id, response } => self.on_response(peer, request_id, response),
+		}
+	}
+
+	fn poll(&mut self, cx: &mut Context, _: &mut impl PollParameters) -> Poll<NetworkBehaviourAction<OutboundProtocol, void::Void>> {
+		// Process response sending futures.
+		while let Poll::Ready(Some(_)) = self.responses.poll_next_unpin(cx) {}
+
+		// Fail the requests that have timed out.
+		let now = Instant::now();
+		let expired = self.pending_requests.iter()
+			.filter(|(_, request)| now >= request.deadline)
+			.map(|(id, _)| *id)
+			.collect::<Vec<_>>();
+		for id in expired {
+			if let Some(request) = self.pending_requests.remove(&id) {
+				log::debug!(target: "sub-libp2p", "request {} to {} timed out", id, request.peer);
+				let _ = request.response_tx.send(Err(RequestFailure::Timeout));
+			}
+		}
+
+		while let Some((peer_id, event)) = self.outbound.pop_front() {
+			// Don't bother sending requests that have already timed out.
+			if self.pending_requests.contains_key(&event.request_id) {
+				return Poll::Ready(NetworkBehaviourAction::SendEvent { peer_id, event })
+			}
+		}
+
+		Poll::Pending
+	}
+}
+
+/// Output type of inbound and outbound substream upgrades.
+#[derive(Debug)]
+pub enum Event<T> {
+	/// Incoming request from remote and substream to use for the response.
+	Request {
+		/// Name of the protocol the request has been received on.
+		protocol: Bytes,
+		/// The request bytes.
+		request: Vec<u8>,
+		/// The substream to write the response to.
+		stream: T,
+	},
+	/// Incoming response from remote.
+	Response {
+		/// Local identifier of the request this is a response to.
+		request_id: u64,
+		/// The response bytes.
+		response: Vec<u8>,
+	},
+}
+
+/// Substream upgrade protocol.
+///
+/// Reads incoming requests from remote, on any of the protocols we accept requests for.
+#[derive(Debug, Clone)]
+pub struct InboundProtocol {
+	/// The protocols to use for upgrade negotiation, with the max. request length in bytes.
+	protocols: Vec<(Bytes, usize)>,
+}
+
+impl UpgradeInfo for InboundProtocol {
+	type Info = Bytes;
+	type InfoIter = Vec<Self::Info>;
+
+	fn protocol_info(&self) -> Self::InfoIter {
+		self.protocols.iter().map(|(name, _)| name.clone()).collect()
+	}
+}
+
+impl<T> InboundUpgrade<T> for InboundProtocol
+where
+	T: AsyncRead + AsyncWrite + Unpin + Send + 'static
+{
+	type Output = Event<T>;
+	type Error = ReadOneError;
+	type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+	fn upgrade_inbound(self, mut s: T, protocol: Self::Info) -> Self::Future {
+		let max_request_size = self.protocols.iter()
+			.find(|(name, _)| *name == protocol)
+			.map(|(_, max)| *max)
+			.unwrap_or(0);
+		let future = async move {
+			let request = read_one(&mut s, max_request_size).await?;
+			Ok(Event::Request { protocol, request, stream: s })
+		};
+		future.boxed()
+	}
+}
+
+/// Substream upgrade protocol.
+///
+/// Sends a request to remote and awaits the response.
+#[derive(Debug, Clone)]
+pub struct OutboundProtocol {
+	/// The request bytes.
+	request: Vec<u8>,
+	/// Local identifier for the request. Used to associate it with a response.
+	request_id: u64,
+	/// The max. response length in bytes.
+	max_response_size: usize,
+	/// The protocol to use for upgrade negotiation.
+	protocol: Bytes,
+}
+
+impl UpgradeInfo for OutboundProtocol {
+	type Info = Bytes;
+	type InfoIter = std::iter::Once<Self::Info>;
+
+	fn protocol_info(&self) -> Self::InfoIter {
+		std::iter::once(self.protocol.clone())
+	}
+}
+
+impl<T> OutboundUpgrade<T> for OutboundProtocol
+where
+	T: AsyncRead + AsyncWrite + Unpin + Send + 'static
+{
+	type Output = Event<T>;
+	type Error = ReadOneError;
+	type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+	fn upgrade_outbound(self, mut s: T, _: Self::Info) -> Self::Future {
+		let future = async move {
+			write_one(&mut s, &self.request).await?;
+			let response = read_one(&mut s, self.max_response_size).await?;
+			Ok(Event::Response { request_id: self.request_id, response })
+		};
+		future.boxed()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert_matches::assert_matches;
+	use futures::{channel::{mpsc, oneshot}, prelude::*};
+	use libp2p::{
+		PeerId,
+		Multiaddr,
+		core::ConnectedPoint,
+		swarm::{NetworkBehaviour, NetworkBehaviourAction, PollParameters},
+	};
+	use std::{iter, pin::Pin, task::Poll, time::Duration};
+	use super::{Event, OutboundProtocol, ProtocolConfig, RegisterError, RequestFailure, RequestResponsesBehaviour};
+
+	struct EmptyPollParams(PeerId);
+
+	impl PollParameters for EmptyPollParams {
+		type SupportedProtocolsIter = iter::Empty<Vec<u8>>;
+		type ListenedAddressesIter = iter::Empty<Multiaddr>;
+		type ExternalAddressesIter = iter::Empty<Multiaddr>;
+
+		fn supported_protocols(&self) -> Self::SupportedProtocolsIter {
+			iter::empty()
+		}
+
+		fn listened_addresses(&self) -> Self::ListenedAddressesIter {
+			iter::empty()
+		}
+
+		fn external_addresses(&self) -> Self::ExternalAddressesIter {
+			iter::empty()
+		}
+
+		fn local_peer_id(&self) -> &PeerId {
+			&self.0
+		}
+	}
+
+	fn protocol(name: &'static str, request_timeout: Duration) -> ProtocolConfig {
+		let (tx, _rx) = mpsc::channel(8);
+		ProtocolConfig {
+			name: name.into(),
+			max_request_size: 1024,
+			max_response_size: 1024,
+			request_timeout,
+			inbound_queue: Some(tx),
+		}
+	}
+
+	fn make_behaviour(request_timeout: Duration) -> RequestResponsesBehaviour {
+		RequestResponsesBehaviour::new(vec![protocol("/test/1", request_timeout)]).unwrap()
+	}
+
+	fn empty_dialer() -> ConnectedPoint {
+		ConnectedPoint::Dialer { address: Multiaddr::empty() }
+	}
+
+	fn poll(mut b: &mut RequestResponsesBehaviour) -> Poll<NetworkBehaviourAction<OutboundProtocol, void::Void>> {
+		let mut p = EmptyPollParams(PeerId::random());
+		match future::poll_fn(|cx| Pin::new(&mut b).poll(cx, &mut p)).now_or_never() {
+			Some(a) => Poll::Ready(a),
+			None    => Poll::Pending
+		}
+	}
+
+	fn expect_request(behaviour: &mut RequestResponsesBehaviour, expected_peer: &PeerId) -> u64 {
+		match poll(behaviour) {
+			Poll::Ready(NetworkBehaviourAction::SendEvent { peer_id, event }) => {
+				assert_eq!(&peer_id, expected_peer);
+				event.request_id
+			}
+			_ => panic!("expected a request"),
+		}
+	}
+
+	#[test]
+	fn duplicate_protocols_are_rejected() {
+		let protocols = vec![
+			protocol("/test/1", Duration::from_secs(10)),
+			protocol("/test/1", Duration::from_secs(10)),
+		];
+		assert_matches!(
+			RequestResponsesBehaviour::new(protocols),
+			Err(RegisterError::DuplicateProtocol(name)) if name == "/test/1"
+		);
+	}
+
+	#[test]
+	fn requests_to_unknown_protocols_or_peers_fail() {
+		let mut behaviour = make_behaviour(Duration::from_secs(10));
+		let peer = PeerId::random();
+
+		let (tx, mut rx) = oneshot::channel();
+		behaviour.send_request(&peer, "/test/1", vec![1], tx);
+		assert_eq!(rx.try_recv(), Ok(Some(Err(RequestFailure::NotConnected))));
+
+		behaviour.inject_connected(peer.clone(), empty_dialer());
+		let (tx, mut rx) = oneshot::channel();
+		behaviour.send_request(&peer, "/test/2", vec![1], tx);
+		assert_eq!(rx.try_recv(), Ok(Some(Err(RequestFailure::UnknownProtocol))));
+
+		assert_matches!(poll(&mut behaviour), Poll::Pending);
+	}
+
+	#[test]
+	fn responses_are_delivered() {
+		let mut behaviour = make_behaviour(Duration::from_secs(10));
+		let peer = PeerId::random();
+		behaviour.inject_connected(peer.clone(), empty_dialer());
+
+		let (tx, mut rx) = oneshot::channel();
+		behaviour.send_request(&peer, "/test/1", vec![1], tx);
+		let id = expect_request(&mut behaviour, &peer);
+		assert_eq!(rx.try_recv(), Ok(None));
+
+		// responses from other peers are ignored.
+		behaviour.inject_node_event(PeerId::random(), Event::Response { request_id: id, response: vec![3] });
+		assert_eq!(rx.try_recv(), Ok(None));
+
+		behaviour.inject_node_event(peer, Event::Response { request_id: id, response: vec![2] });
+		assert_eq!(rx.try_recv(), Ok(Some(Ok(vec![2]))));
+	}
+
+	#[test]
+	fn disconnect_fails_pending_requests() {
+		let mut behaviour = make_behaviour(Duration::from_secs(10));
+		let peer = PeerId::random();
+		behaviour.inject_connected(peer.clone(), empty_dialer());
+
+		let (tx, mut rx) = oneshot::channel();
+		behaviour.send_request(&peer, "/test/1", vec![1], tx);
+		behaviour.inject_disconnected(&peer, empty_dialer());
+
+		assert_eq!(rx.try_recv(), Ok(Some(Err(RequestFailure::ConnectionClosed))));
+		assert_matches!(poll(&mut behaviour), Poll::Pending);
+	}
+
+	#[test]
+	fn requests_time_out() {
+		let mut behaviour = make_behaviour(Duration::from_secs(0));
+		let peer = PeerId::random();
+		behaviour.inject_connected(peer.clone(), empty_dialer());
+
+		let (tx, mut rx) = oneshot::channel();
+		behaviour.send_request(&peer, "/test/1", vec![1], tx);
+
+		// the request expires before it could be sent.
+		assert_matches!(poll(&mut behaviour), Poll::Pending);
+		assert_eq!(rx.try_recv(), Ok(Some(Err(RequestFailure::Timeout))));
+	}
+}
@@ -34,9 +34,10 @@ use crate::{
 	},
 	on_demand_layer::AlwaysBadChecker,
 	protocol::{self, event::Event, light_client_handler, sync::SyncState, PeerInfo, Protocol},
+	request_responses::{self, RequestFailure},
 	transport, ReputationChange,
 };
-use futures::prelude::*;
+use futures::{channel::oneshot, prelude::*};
 use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedSender, TracingUnboundedReceiver};
 use libp2p::swarm::{NetworkBehaviour, SwarmBuilder, SwarmEvent};
 use libp2p::{kad::record, Multiaddr, PeerId};
@@ -48,7 +49,7 @@ use prometheus_endpoint::{
 use sc_peerset::PeersetHandle;
 use sp_consensus::import_queue::{BlockImportError, BlockImportResult, ImportQueue, Link};
 use sp_runtime::{
-	traits::{Block as BlockT, NumberFor},
+	traits::{Block as BlockT, NumberFor, Zero},
 	ConsensusEngineId,
 };
 use std::{
@@ -264,10 +265,11 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 
 		let num_connected = Arc::new(AtomicUsize::new(0));
 		let is_major_syncing = Arc::new(AtomicBool::new(false));
-		let (protocol, peerset_handle) = Protocol::new(
+		let (mut protocol, peerset_handle) = Protocol::new(
 			protocol::ProtocolConfig {
 				roles: From::from(&params.role),
 				max_parallel_downloads: params.network_config.max_parallel_downloads,
+				sync_mode: params.network_config.sync_mode,
 			},
 			params.chain.clone(),
 			params.transaction_pool,
@@ -292,10 +294,37 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				let config = protocol::block_requests::Config::new(&params.protocol_id);
 				protocol::BlockRequests::new(config, params.chain.clone())
 			};
+			let warp_sync = {
+				let config = protocol::warp_sync::Config::new(&params.protocol_id);
+				let mut warp_sync = protocol::WarpSync::new(
+					config,
+					params.warp_sync_provider.clone(),
+					peerset_handle.clone(),
+				);
+				// Light clients starting from genesis skip straight to the latest authority set.
+				let info = params.chain.info();
+				if matches!(params.role, Role::Light)
+					&& info.finalized_number.is_zero()
+					&& params.warp_sync_provider.is_some()
+				{
+					warp_sync.start(info.finalized_hash);
+					protocol.begin_warp_sync();
+				}
+				warp_sync
+			};
+			let state_sync = {
+				let config = protocol::state_sync::Config::new(&params.protocol_id);
+				protocol::StateSync::new(config, params.chain.clone(), peerset_handle.clone())
+			};
 			let light_client_handler = {
 				let config = protocol::light_client_handler::Config::new(&params.protocol_id);
 				protocol::LightClientHandler::new(config, params.chain, checker, peerset_handle.clone())
 			};
+			let request_responses = request_responses::RequestResponsesBehaviour::new(
+				params.network_config.request_response_protocols.iter().cloned(),
+			).map_err(|request_responses::RegisterError::DuplicateProtocol(protocol)| {
+				Error::DuplicateRequestResponseProtocol { protocol }
+			})?;
 			let behaviour = futures::executor::block_on(Behaviour::new(
 				protocol,
 				params.role,
@@ -312,7 +341,10 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				},
 				u64::from(params.network_config.out_peers) + 15,
 				block_requests,
-				light_client_handler
+				light_client_handler,
+				warp_sync,
+				state_sync,
+				request_responses,
 			));
 			let (transport, bandwidth) = {
 				let (config_mem, config_wasm, flowctrl) = match params.network_config.transport {
@@ -541,6 +573,36 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkService<B, H> {
 		});
 	}
 
+	/// Sends a single request to a peer on one of the request-response protocols registered in
+	/// the network configuration, and waits for its response.
+	///
+	/// The request and response are opaque bytes, whose format is defined by the protocol. We
+	/// must already be connected to the target, otherwise `RequestFailure::NotConnected` is
+	/// returned.
+	///
+	/// > **Note**: A remote that doesn't support the protocol will drop the connection, so you
+	/// >			should only send requests to peers that are known to support it.
+	pub async fn request(
+		&self,
+		target: PeerId,
+		protocol: impl Into<Cow<'static, str>>,
+		request: Vec<u8>,
+	) -> Result<Vec<u8>, RequestFailure> {
+		let (tx, rx) = oneshot::channel();
+		let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::Request {
+			target,
+			protocol: protocol.into(),
+			request,
+			pending_response: tx,
+		});
+
+		match rx.await {
+			Ok(v) => v,
+			// The channel can only be closed if the network worker no longer exists.
+			Err(_) => Err(RequestFailure::ConnectionClosed),
+		}
+	}
+
 	/// Returns a stream containing the events that happen on the network.
 	///
 	/// If this method is called multiple times, the events are duplicated.
@@ -778,6 +840,12 @@ enum ServiceToWorkerMsg<B: BlockT, H: ExHashT> {
 		engine_id: ConsensusEngineId,
 		protocol_name: Cow<'static, [u8]>,
 	},
+	Request {
+		target: PeerId,
+		protocol: Cow<'static, str>,
+		request: Vec<u8>,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	},
 	DisconnectPeer(PeerId),
 }
 
@@ -996,6 +1064,8 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 					this.network_service
 						.register_notifications_protocol(engine_id, protocol_name);
 				},
+				ServiceToWorkerMsg::Request { target, protocol, request, pending_response } =>
+					this.network_service.send_request(&target, &protocol, request, pending_response),
 				ServiceToWorkerMsg::DisconnectPeer(who) =>
 					this.network_service.user_protocol_mut().disconnect_peer(&who),
 			}
@@ -617,6 +617,7 @@ pub trait TestNetFactory: Sized {
 			finality_proof_provider: self.make_finality_proof_provider(
 				PeersClient::Full(client.clone(), backend.clone()),
 			),
+			warp_sync_provider: None,
 			finality_proof_request_builder,
 			on_demand: None,
 			transaction_pool: Arc::new(EmptyTransactionPool),
@@ -693,6 +694,7 @@ pub trait TestNetFactory: Sized {
 			finality_proof_provider: self.make_finality_proof_provider(
 				PeersClient::Light(client.clone(), backend.clone())
 			),
+			warp_sync_provider: None,
 			finality_proof_request_builder,
 			on_demand: None,
 			transaction_pool: Arc::new(EmptyTransactionPool),
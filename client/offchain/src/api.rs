@@ -23,11 +23,10 @@ use std::{
 
 use sp_core::offchain::OffchainStorage;
 use futures::Future;
-use log::error;
 use sc_network::{PeerId, Multiaddr, NetworkStateInfo};
 use codec::{Encode, Decode};
 use sp_core::offchain::{
-	Externalities as OffchainExt, HttpRequestId, Timestamp, HttpRequestStatus, HttpError,
+	Externalities as OffchainExt, ForkContext, HttpRequestId, Timestamp, HttpRequestStatus, HttpError,
 	OpaqueNetworkState, OpaquePeerId, OpaqueMultiaddr, StorageKind,
 };
 pub use sp_offchain::STORAGE_PREFIX;
@@ -48,6 +47,8 @@ mod timestamp;
 pub(crate) struct Api<Storage> {
 	/// Offchain Workers database.
 	db: Storage,
+	/// The block the offchain worker is running at, used for the fork-aware storage.
+	fork_context: ForkContext,
 	/// A NetworkState provider.
 	network_state: Arc<dyn NetworkStateInfo + Send + Sync>,
 	/// Is this node a potential validator?
@@ -56,16 +57,6 @@ pub(crate) struct Api<Storage> {
 	http: http::HttpApi,
 }
 
-fn unavailable_yet<R: Default>(name: &str) -> R {
-	error!(
-		"The {:?} API is not available for offchain workers yet. Follow \
-		https://github.com/paritytech/substrate/issues/1458 for details", name
-	);
-	Default::default()
-}
-
-const LOCAL_DB: &str = "LOCAL (fork-aware) DB";
-
 impl<Storage: OffchainStorage> OffchainExt for Api<Storage> {
 	fn is_validator(&self) -> bool {
 		self.is_validator
@@ -96,7 +87,7 @@ impl<Storage: OffchainStorage> OffchainExt for Api<Storage> {
 	fn local_storage_set(&mut self, kind: StorageKind, key: &[u8], value: &[u8]) {
 		match kind {
 			StorageKind::PERSISTENT => self.db.set(STORAGE_PREFIX, key, value),
-			StorageKind::LOCAL => unavailable_yet(LOCAL_DB),
+			StorageKind::LOCAL => self.db.local_set(&self.fork_context, STORAGE_PREFIX, key, value),
 		}
 	}
 
@@ -111,14 +102,20 @@ impl<Storage: OffchainStorage> OffchainExt for Api<Storage> {
 			StorageKind::PERSISTENT => {
 				self.db.compare_and_set(STORAGE_PREFIX, key, old_value, new_value)
 			},
-			StorageKind::LOCAL => unavailable_yet(LOCAL_DB),
+			StorageKind::LOCAL => self.db.local_compare_and_set(
+				&self.fork_context,
+				STORAGE_PREFIX,
+				key,
+				old_value,
+				new_value,
+			),
 		}
 	}
 
 	fn local_storage_get(&mut self, kind: StorageKind, key: &[u8]) -> Option<Vec<u8>> {
 		match kind {
 			StorageKind::PERSISTENT => self.db.get(STORAGE_PREFIX, key),
-			StorageKind::LOCAL => unavailable_yet(LOCAL_DB),
+			StorageKind::LOCAL => self.db.local_get(&self.fork_context, STORAGE_PREFIX, key),
 		}
 	}
 
@@ -251,6 +248,7 @@ impl AsyncApi {
 	/// Creates new Offchain extensions API implementation  an the asynchronous processing part.
 	pub fn new<S: OffchainStorage>(
 		db: S,
+		fork_context: ForkContext,
 		network_state: Arc<dyn NetworkStateInfo + Send + Sync>,
 		is_validator: bool,
 	) -> (Api<S>, AsyncApi) {
@@ -258,6 +256,7 @@ impl AsyncApi {
 
 		let api = Api {
 			db,
+			fork_context,
 			network_state,
 			is_validator,
 			http: http_api,
@@ -298,12 +297,17 @@ mod tests {
 	}
 
 	fn offchain_api() -> (Api<LocalStorage>, AsyncApi) {
+		offchain_api_at(ForkContext::finalized())
+	}
+
+	fn offchain_api_at(fork_context: ForkContext) -> (Api<LocalStorage>, AsyncApi) {
 		let _ = env_logger::try_init();
 		let db = LocalStorage::new_test();
 		let mock = Arc::new(MockNetworkStateInfo());
 
 		AsyncApi::new(
 			db,
+			fork_context,
 			mock,
 			false,
 		)
@@ -390,6 +394,28 @@ mod tests {
 		assert_eq!(api.local_storage_get(kind, key), Some(b"value".to_vec()));
 	}
 
+	#[test]
+	fn should_set_and_get_fork_aware_local_storage() {
+		// given
+		let kind = StorageKind::LOCAL;
+		let fork_context = ForkContext {
+			number: 2,
+			ancestry: vec![b"block2".to_vec(), b"block1".to_vec()],
+		};
+		let mut api = offchain_api_at(fork_context).0;
+		let key = b"test";
+
+		// when
+		assert_eq!(api.local_storage_get(kind, key), None);
+		api.local_storage_set(kind, key, b"value");
+		assert_eq!(api.local_storage_compare_and_set(kind, key, Some(b"value"), b"xxx"), true);
+
+		// then
+		assert_eq!(api.local_storage_get(kind, key), Some(b"xxx".to_vec()));
+		assert_eq!(api.local_storage_get(StorageKind::PERSISTENT, key), None);
+		assert_eq!(api.db.local_get(&ForkContext::finalized(), STORAGE_PREFIX, key), None);
+	}
+
 	#[test]
 	fn should_convert_network_states() {
 		// given
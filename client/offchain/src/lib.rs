@@ -41,13 +41,18 @@ use sp_api::{ApiExt, ProvideRuntimeApi};
 use futures::future::Future;
 use log::{debug, warn};
 use sc_network::NetworkStateInfo;
-use sp_core::{offchain::{self, OffchainStorage}, ExecutionContext};
-use sp_runtime::{generic::BlockId, traits::{self, Header}};
+use sp_blockchain::HeaderBackend;
+use sp_core::{offchain::{self, ForkContext, OffchainStorage}, ExecutionContext};
+use sp_runtime::{generic::BlockId, traits::{self, Header, SaturatedConversion}};
 
 mod api;
 
 pub use sp_offchain::{OffchainWorkerApi, STORAGE_PREFIX};
 
+/// The maximal number of non-finalized ancestors of a block whose fork-aware
+/// storage values are visible to the offchain workers of the block.
+const MAX_FORK_CONTEXT_DEPTH: usize = 256;
+
 /// An offchain workers manager.
 pub struct OffchainWorkers<Client, Storage, Block: traits::Block> {
 	client: Arc<Client>,
@@ -84,10 +89,51 @@ impl<Client, Storage, Block> OffchainWorkers<
 	Block,
 > where
 	Block: traits::Block,
-	Client: ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	Client: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
 	Client::Api: OffchainWorkerApi<Block>,
 	Storage: OffchainStorage + 'static,
 {
+	/// Prune the fork-aware offchain storage on block finalization.
+	///
+	/// Values set at finalized blocks are moved to the finalized state, while
+	/// values set at blocks of any other fork are discarded.
+	pub fn on_block_finalized(&self) {
+		let client = &self.client;
+		let finalized = client.info().finalized_number.saturated_into::<u64>();
+		let is_canonical = |number: u64, hash: &[u8]| {
+			client.hash(number.saturated_into())
+				.ok()
+				.and_then(|h| h)
+				.map_or(false, |h| h.as_ref() == hash)
+		};
+
+		self.db.clone().local_finalize(finalized, &is_canonical);
+	}
+
+	/// Build the fork context for the given block, i.e. the block and its
+	/// non-finalized ancestors.
+	///
+	/// At most `MAX_FORK_CONTEXT_DEPTH` ancestors are included, values set at
+	/// older non-finalized blocks are not visible until they get finalized.
+	fn fork_context(&self, header: &Block::Header) -> ForkContext {
+		let finalized = self.client.info().finalized_number.saturated_into::<u64>();
+		let number = (*header.number()).saturated_into::<u64>();
+
+		let mut ancestry = Vec::new();
+		let mut current = Some(header.clone());
+		while let Some(header) = current.take() {
+			if (*header.number()).saturated_into::<u64>() <= finalized
+				|| ancestry.len() >= MAX_FORK_CONTEXT_DEPTH
+			{
+				break;
+			}
+			ancestry.push(header.hash().as_ref().to_vec());
+			current = self.client.header(BlockId::Hash(*header.parent_hash())).ok().and_then(|h| h);
+		}
+
+		ForkContext { number, ancestry }
+	}
+
 	/// Start the offchain workers after given block.
 	#[must_use]
 	pub fn on_block_imported(
@@ -117,6 +163,7 @@ impl<Client, Storage, Block> OffchainWorkers<
 		if version > 0 {
 			let (api, runner) = api::AsyncApi::new(
 				self.db.clone(),
+				self.fork_context(header),
 				network_state.clone(),
 				is_validator,
 			);
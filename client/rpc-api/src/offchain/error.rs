@@ -22,11 +22,15 @@ use jsonrpc_core as rpc;
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Offchain RPC errors.
-#[derive(Debug, derive_more::Display, derive_more::From)]
-pub enum Error {
-	/// Unavailable storage kind error.
-	#[display(fmt="This storage kind is not available yet.")]
-	UnavailableStorageKind,
+///
+/// All storage kinds are available, so none of the offchain RPC calls can fail currently.
+#[derive(Debug)]
+pub enum Error {}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, _: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match *self {}
+	}
 }
 
 impl std::error::Error for Error {
@@ -35,17 +39,8 @@ impl std::error::Error for Error {
 	}
 }
 
-/// Base error code for all offchain errors.
-const BASE_ERROR: i64 = 5000;
-
 impl From<Error> for rpc::Error {
 	fn from(e: Error) -> Self {
-		match e {
-			Error::UnavailableStorageKind => rpc::Error {
-				code: rpc::ErrorCode::ServerError(BASE_ERROR + 1),
-				message: "This storage kind is not available yet" .into(),
-				data: None,
-			},
-		}
+		match e {}
 	}
 }
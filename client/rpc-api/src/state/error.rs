@@ -49,6 +49,9 @@ pub enum Error {
 		/// Maximum allowed value
 		max: u32,
 	},
+	/// The node doesn't keep an index of storage key changes.
+	#[display(fmt = "Storage key history is not available. Start the node with `--key-history` to enable it.")]
+	KeyHistoryUnavailable,
 }
 
 impl std::error::Error for Error {
@@ -76,6 +79,11 @@ impl From<Error> for rpc::Error {
 				message: format!("{}", e),
 				data: None,
 			},
+			Error::KeyHistoryUnavailable => rpc::Error {
+				code: rpc::ErrorCode::ServerError(BASE_ERROR + 3),
+				message: format!("{}", e),
+				data: None,
+			},
 			e => errors::internal(e),
 		}
 	}
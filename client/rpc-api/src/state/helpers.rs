@@ -0,0 +1,30 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Substrate state API helpers.
+
+use sp_core::storage::StorageChangeSet;
+use serde::{Serialize, Deserialize};
+
+/// A page of the change history of storage entries.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageHistory<Hash> {
+	/// Initial state of the queried keys followed by the changes of their values.
+	pub changes: Vec<StorageChangeSet<Hash>>,
+	/// Block to continue the query from, if not all changes fit into this page.
+	pub next: Option<Hash>,
+}
@@ -17,6 +17,7 @@
 //! Substrate state API.
 
 pub mod error;
+pub mod helpers;
 
 use jsonrpc_core::Result as RpcResult;
 use jsonrpc_core::futures::Future;
@@ -27,6 +28,7 @@ use sp_core::storage::{StorageKey, StorageData, StorageChangeSet};
 use sp_version::RuntimeVersion;
 use self::error::FutureResult;
 
+pub use self::helpers::StorageHistory;
 pub use self::gen_client::Client as StateClient;
 
 /// Substrate state API
@@ -144,6 +146,21 @@ pub trait StateApi<Hash> {
 		at: Option<Hash>,
 	) -> FutureResult<Vec<StorageChangeSet<Hash>>>;
 
+	/// Query the change history of storage entries (by key) between two blocks, using the
+	/// index of key changes kept by archive nodes.
+	///
+	/// NOTE The first change set contains the state of all keys at `from` and is followed by
+	/// at most `count` change sets. If there are more changes, the query can be continued from
+	/// the returned `next` block.
+	#[rpc(name = "state_queryStorageHistory")]
+	fn query_storage_history(
+		&self,
+		keys: Vec<StorageKey>,
+		from: Hash,
+		to: Option<Hash>,
+		count: u32,
+	) -> FutureResult<StorageHistory<Hash>>;
+
 	/// New runtime version subscription
 	#[pubsub(
 		subscription = "state_runtimeVersion",
@@ -21,10 +21,10 @@ mod tests;
 
 /// Re-export the API for backward compatibility.
 pub use sc_rpc_api::offchain::*;
-use self::error::{Error, Result};
+use self::error::Result;
 use sp_core::{
 	Bytes,
-	offchain::{OffchainStorage, StorageKind},
+	offchain::{ForkContext, OffchainStorage, StorageKind},
 };
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -47,21 +47,28 @@ impl<T: OffchainStorage> Offchain<T> {
 
 impl<T: OffchainStorage + 'static> OffchainApi for Offchain<T> {
 	/// Set offchain local storage under given key and prefix.
+	///
+	/// `LOCAL` values are set in the finalized state of the fork-aware storage.
 	fn set_local_storage(&self, kind: StorageKind, key: Bytes, value: Bytes) -> Result<()> {
-		let prefix = match kind {
-			StorageKind::PERSISTENT => sp_offchain::STORAGE_PREFIX,
-			StorageKind::LOCAL => return Err(Error::UnavailableStorageKind),
-		};
-		self.storage.write().set(prefix, &*key, &*value);
+		let prefix = sp_offchain::STORAGE_PREFIX;
+		match kind {
+			StorageKind::PERSISTENT => self.storage.write().set(prefix, &*key, &*value),
+			StorageKind::LOCAL => self.storage.write()
+				.local_set(&ForkContext::finalized(), prefix, &*key, &*value),
+		}
 		Ok(())
 	}
 
 	/// Get offchain local storage under given key and prefix.
+	///
+	/// `LOCAL` values are read from the finalized state of the fork-aware storage.
 	fn get_local_storage(&self, kind: StorageKind, key: Bytes) -> Result<Option<Bytes>> {
-		let prefix = match kind {
-			StorageKind::PERSISTENT => sp_offchain::STORAGE_PREFIX,
-			StorageKind::LOCAL => return Err(Error::UnavailableStorageKind),
+		let prefix = sp_offchain::STORAGE_PREFIX;
+		let value = match kind {
+			StorageKind::PERSISTENT => self.storage.read().get(prefix, &*key),
+			StorageKind::LOCAL => self.storage.read()
+				.local_get(&ForkContext::finalized(), prefix, &*key),
 		};
-		Ok(self.storage.read().get(prefix, &*key).map(Into::into))
+		Ok(value.map(Into::into))
 	}
 }
@@ -34,3 +34,24 @@ fn local_storage_should_work() {
 		Ok(Some(ref v)) if *v == value
 	);
 }
+
+#[test]
+fn fork_aware_local_storage_should_work() {
+	let storage = InMemOffchainStorage::default();
+	let offchain = Offchain::new(storage);
+	let key = Bytes(b"offchain_storage".to_vec());
+	let value = Bytes(b"offchain_value".to_vec());
+
+	assert_matches!(
+		offchain.set_local_storage(StorageKind::LOCAL, key.clone(), value.clone()),
+		Ok(())
+	);
+	assert_matches!(
+		offchain.get_local_storage(StorageKind::LOCAL, key.clone()),
+		Ok(Some(ref v)) if *v == value
+	);
+	assert_matches!(
+		offchain.get_local_storage(StorageKind::PERSISTENT, key),
+		Ok(None)
+	);
+}
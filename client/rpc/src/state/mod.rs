@@ -42,6 +42,8 @@ use sp_blockchain::{HeaderMetadata, HeaderBackend};
 
 const STORAGE_KEYS_PAGED_MAX_COUNT: u32 = 1000;
 
+const STORAGE_HISTORY_MAX_COUNT: u32 = 1000;
+
 /// State backend API.
 pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
 	where
@@ -170,6 +172,18 @@ pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
 		at: Option<Block::Hash>
 	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>>;
 
+	/// Query the change history of storage entries (by key) using the index of key changes.
+	///
+	/// NOTE The first change set contains the state of all keys at `from` and is followed by
+	/// at most `count` change sets.
+	fn query_storage_history(
+		&self,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		keys: Vec<StorageKey>,
+		count: u32,
+	) -> FutureResult<StorageHistory<Block::Hash>>;
+
 	/// New runtime version subscription
 	fn subscribe_runtime_version(
 		&self,
@@ -372,6 +386,24 @@ impl<Block, Client> StateApi<Block::Hash> for State<Block, Client>
 		self.backend.query_storage_at(keys, at)
 	}
 
+	fn query_storage_history(
+		&self,
+		keys: Vec<StorageKey>,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		count: u32,
+	) -> FutureResult<StorageHistory<Block::Hash>> {
+		if count > STORAGE_HISTORY_MAX_COUNT {
+			return Box::new(result(Err(
+				Error::InvalidCount {
+					value: count,
+					max: STORAGE_HISTORY_MAX_COUNT,
+				}
+			)));
+		}
+		self.backend.query_storage_history(from, to, keys, count)
+	}
+
 	fn subscribe_storage(
 		&self,
 		meta: Self::Metadata,
@@ -16,7 +16,7 @@
 
 //! State API backend for full nodes.
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
 use std::ops::Range;
 use futures::{future, StreamExt as _, TryStreamExt as _};
@@ -33,12 +33,14 @@ use sp_core::{
 };
 use sp_version::RuntimeVersion;
 use sp_runtime::{
-	generic::BlockId, traits::{Block as BlockT, NumberFor, SaturatedConversion, CheckedSub},
+	generic::BlockId, traits::{Block as BlockT, NumberFor, SaturatedConversion, CheckedSub, One},
 };
 
 use sp_api::{Metadata, ProvideRuntimeApi, CallApiAt};
 
-use super::{StateBackend, error::{FutureResult, Error, Result}, client_err, child_resolution_error};
+use super::{
+	StateBackend, StorageHistory, error::{FutureResult, Error, Result}, client_err, child_resolution_error,
+};
 use std::marker::PhantomData;
 use sc_client_api::{CallExecutor, StorageProvider, ExecutorProvider};
 
@@ -410,6 +412,67 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 		self.query_storage(at, Some(at), keys)
 	}
 
+	fn query_storage_history(
+		&self,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		keys: Vec<StorageKey>,
+		count: u32,
+	) -> FutureResult<StorageHistory<Block::Hash>> {
+		let call_fn = move || {
+			let range = self.split_query_storage_range(from, to)?;
+			let first_number = range.first_number;
+			let last_number = first_number + (range.hashes.len() - 1).saturated_into();
+			let from_id = BlockId::Hash(range.hashes[0].clone());
+
+			let mut initial = StorageChangeSet { block: range.hashes[0].clone(), changes: Vec::new() };
+			let mut last_values = Vec::with_capacity(keys.len());
+			let mut histories = Vec::with_capacity(keys.len());
+			for key in &keys {
+				let value = self.client.storage(&from_id, key).map_err(client_err)?;
+				initial.changes.push((key.clone(), value.clone()));
+				last_values.push(value);
+				histories.push(self.client
+					.key_history(first_number + One::one(), last_number, key)
+					.map_err(client_err)?
+					.ok_or(Error::KeyHistoryUnavailable)?
+				);
+			}
+
+			// visit the blocks in ascending order, so that reading values stops once the page is full.
+			let blocks: BTreeSet<NumberFor<Block>> = histories.iter().flatten().cloned().collect();
+			let mut changes = vec![initial];
+			let mut next = None;
+			for block in blocks {
+				let block_hash = range.hashes[(block - first_number).saturated_into::<usize>()].clone();
+				let mut change_set = StorageChangeSet { block: block_hash, changes: Vec::new() };
+				for (index, key) in keys.iter().enumerate() {
+					if histories[index].binary_search(&block).is_err() {
+						continue;
+					}
+					// the index also contains blocks of retracted forks, so compare the values
+					// at the canonical blocks.
+					let value_at_block = self.client.storage(&BlockId::Hash(block_hash), key)
+						.map_err(client_err)?;
+					if last_values[index] != value_at_block {
+						change_set.changes.push((key.clone(), value_at_block.clone()));
+						last_values[index] = value_at_block;
+					}
+				}
+				if change_set.changes.is_empty() {
+					continue;
+				}
+				if changes.len() > count as usize {
+					next = Some(change_set.block);
+					break;
+				}
+				changes.push(change_set);
+			}
+			Ok(StorageHistory { changes, next })
+		};
+		Box::new(result(call_fn()))
+	}
+
 	fn subscribe_runtime_version(
 		&self,
 		_meta: crate::metadata::Metadata,
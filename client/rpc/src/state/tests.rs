@@ -419,6 +419,79 @@ fn should_query_storage() {
 	);
 }
 
+#[test]
+fn should_require_key_history_to_query_storage_history() {
+	let core = tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let api = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())));
+	let genesis_hash = client.genesis_hash();
+
+	assert_matches!(
+		api.query_storage_history(vec![StorageKey(vec![1])], genesis_hash, None, 10).wait(),
+		Err(Error::KeyHistoryUnavailable)
+	);
+	assert_matches!(
+		api.query_storage_history(vec![StorageKey(vec![1])], genesis_hash, None, 1001).wait(),
+		Err(Error::InvalidCount { value: 1001, max: 1000 })
+	);
+}
+
+#[test]
+fn should_query_storage_history() {
+	let core = tokio::runtime::Runtime::new().unwrap();
+	let mut client = Arc::new(TestClientBuilder::with_key_history().build());
+	let api = new_full(client.clone(), Subscriptions::new(Arc::new(core.executor())));
+
+	let mut add_block = |changes: Vec<(Vec<u8>, Option<Vec<u8>>)>| {
+		let mut builder = client.new_block(Default::default()).unwrap();
+		for (key, value) in changes {
+			builder.push_storage_change(key, value).unwrap();
+		}
+		let block = builder.build().unwrap().block;
+		let hash = block.header.hash();
+		client.import(BlockOrigin::Own, block).unwrap();
+		hash
+	};
+	let block1_hash = add_block(vec![(vec![1], Some(vec![1])), (vec![2], Some(vec![2]))]);
+	// fake change of key 1
+	let block2_hash = add_block(vec![(vec![1], Some(vec![1])), (vec![2], None)]);
+	let block3_hash = add_block(vec![(vec![1], Some(vec![3]))]);
+	let genesis_hash = client.genesis_hash();
+	let keys = vec![StorageKey(vec![1]), StorageKey(vec![2])];
+
+	let result = api.query_storage_history(keys.clone(), genesis_hash, None, 1).wait().unwrap();
+	assert_eq!(result.changes, vec![
+		StorageChangeSet {
+			block: genesis_hash,
+			changes: vec![(StorageKey(vec![1]), None), (StorageKey(vec![2]), None)],
+		},
+		StorageChangeSet {
+			block: block1_hash,
+			changes: vec![
+				(StorageKey(vec![1]), Some(StorageData(vec![1]))),
+				(StorageKey(vec![2]), Some(StorageData(vec![2]))),
+			],
+		},
+	]);
+	assert_eq!(result.next, Some(block2_hash));
+
+	let result = api.query_storage_history(keys, block2_hash, Some(block3_hash), 10).wait().unwrap();
+	assert_eq!(result.changes, vec![
+		StorageChangeSet {
+			block: block2_hash,
+			changes: vec![
+				(StorageKey(vec![1]), Some(StorageData(vec![1]))),
+				(StorageKey(vec![2]), None),
+			],
+		},
+		StorageChangeSet {
+			block: block3_hash,
+			changes: vec![(StorageKey(vec![1]), Some(StorageData(vec![3])))],
+		},
+	]);
+	assert_eq!(result.next, None);
+}
+
 #[test]
 fn should_split_ranges() {
 	assert_eq!(split_range(1, None), (0..1, None));
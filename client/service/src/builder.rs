@@ -36,7 +36,7 @@ use futures::{
 };
 use sc_keystore::{Store as Keystore};
 use log::{info, warn, error};
-use sc_network::config::{Role, FinalityProofProvider, OnDemand, BoxFinalityProofRequestBuilder};
+use sc_network::config::{Role, FinalityProofProvider, WarpSyncProvider, OnDemand, BoxFinalityProofRequestBuilder};
 use sc_network::{NetworkService, NetworkStateInfo};
 use parking_lot::{Mutex, RwLock};
 use sp_runtime::generic::BlockId;
@@ -66,6 +66,7 @@ pub type BackgroundTask = Pin<Box<dyn Future<Output=()> + Send>>;
 /// - [`with_select_chain`](ServiceBuilder::with_select_chain)
 /// - [`with_import_queue`](ServiceBuilder::with_import_queue)
 /// - [`with_finality_proof_provider`](ServiceBuilder::with_finality_proof_provider)
+/// - [`with_warp_sync_provider`](ServiceBuilder::with_warp_sync_provider)
 /// - [`with_transaction_pool`](ServiceBuilder::with_transaction_pool)
 ///
 /// After this is done, call [`build`](ServiceBuilder::build) to construct the service.
@@ -76,7 +77,7 @@ pub type BackgroundTask = Pin<Box<dyn Future<Output=()> + Send>>;
 pub struct ServiceBuilder<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp,
 	TExPool, TRpc, Backend>
 {
-	config: Configuration,
+	pub (crate) config: Configuration,
 	pub (crate) client: Arc<TCl>,
 	backend: Arc<Backend>,
 	tasks_builder: TaskManagerBuilder,
@@ -86,6 +87,7 @@ pub struct ServiceBuilder<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp,
 	pub (crate) import_queue: TImpQu,
 	finality_proof_request_builder: Option<TFprb>,
 	finality_proof_provider: Option<TFpp>,
+	warp_sync_provider: Option<Arc<dyn WarpSyncProvider<TBl>>>,
 	transaction_pool: Arc<TExPool>,
 	rpc_extensions: TRpc,
 	remote_backend: Option<Arc<dyn RemoteBlockchain<TBl>>>,
@@ -156,6 +158,33 @@ pub fn new_full_client<TBl, TRtApi, TExecDisp>(
 	new_full_parts(config).map(|parts| parts.0)
 }
 
+/// Settings to open the given database with, taking caching and pruning from the config.
+pub(crate) fn database_settings(
+	config: &Configuration,
+	database: &DatabaseConfig,
+) -> sc_client_db::DatabaseSettings {
+	sc_client_db::DatabaseSettings {
+		state_cache_size: config.state_cache_size,
+		state_cache_child_ratio:
+			config.state_cache_child_ratio.map(|v| (v, 100)),
+		pruning: config.pruning.clone(),
+		key_history: config.key_history,
+		source: match database {
+			DatabaseConfig::Path { path, cache_size } =>
+				sc_client_db::DatabaseSettingsSrc::Path {
+					path: path.clone(),
+					cache_size: *cache_size,
+				},
+			DatabaseConfig::ParityDb { path } =>
+				sc_client_db::DatabaseSettingsSrc::ParityDb {
+					path: path.clone(),
+				},
+			DatabaseConfig::Custom(db) =>
+				sc_client_db::DatabaseSettingsSrc::Custom(db.clone()),
+		},
+	}
+}
+
 fn new_full_parts<TBl, TRtApi, TExecDisp>(
 	config: &Configuration,
 ) -> Result<TFullParts<TBl, TRtApi, TExecDisp>,	Error> where
@@ -172,10 +201,12 @@ fn new_full_parts<TBl, TRtApi, TExecDisp>(
 
 	let tasks_builder = TaskManagerBuilder::new();
 
-	let executor = NativeExecutor::<TExecDisp>::new(
+	let executor = NativeExecutor::<TExecDisp>::new_with_cache_path(
 		config.wasm_method,
 		config.default_heap_pages,
 		config.max_runtime_instances,
+		config.wasm_runtime_cache_path.clone(),
+		config.wasm_stack_limit,
 	);
 
 	let chain_spec = &config.chain_spec;
@@ -188,26 +219,13 @@ fn new_full_parts<TBl, TRtApi, TExecDisp>(
 		.unwrap_or_default();
 
 	let (client, backend) = {
-		let db_config = sc_client_db::DatabaseSettings {
-			state_cache_size: config.state_cache_size,
-			state_cache_child_ratio:
-			config.state_cache_child_ratio.map(|v| (v, 100)),
-			pruning: config.pruning.clone(),
-			source: match &config.database {
-				DatabaseConfig::Path { path, cache_size } =>
-					sc_client_db::DatabaseSettingsSrc::Path {
-						path: path.clone(),
-						cache_size: *cache_size,
-					},
-				DatabaseConfig::Custom(db) =>
-					sc_client_db::DatabaseSettingsSrc::Custom(db.clone()),
-			},
-		};
+		let db_config = database_settings(&config, &config.database);
 
-		let extensions = sc_client_api::execution_extensions::ExecutionExtensions::new(
+		let mut extensions = sc_client_api::execution_extensions::ExecutionExtensions::new(
 			config.execution_strategies.clone(),
 			Some(keystore.clone()),
 		);
+		extensions.set_offchain_indexing(config.offchain_indexing);
 
 		sc_client_db::new_client(
 			db_config,
@@ -256,6 +274,7 @@ impl ServiceBuilder<(), (), (), (), (), (), (), (), (), (), ()> {
 			import_queue: (),
 			finality_proof_request_builder: None,
 			finality_proof_provider: None,
+			warp_sync_provider: None,
 			transaction_pool: Arc::new(()),
 			rpc_extensions: Default::default(),
 			remote_backend: None,
@@ -290,28 +309,16 @@ impl ServiceBuilder<(), (), (), (), (), (), (), (), (), (), ()> {
 			KeystoreConfig::InMemory => Keystore::new_in_memory(),
 		};
 
-		let executor = NativeExecutor::<TExecDisp>::new(
+		let executor = NativeExecutor::<TExecDisp>::new_with_cache_path(
 			config.wasm_method,
 			config.default_heap_pages,
 			config.max_runtime_instances,
+			config.wasm_runtime_cache_path.clone(),
+			config.wasm_stack_limit,
 		);
 
 		let db_storage = {
-			let db_settings = sc_client_db::DatabaseSettings {
-				state_cache_size: config.state_cache_size,
-				state_cache_child_ratio:
-					config.state_cache_child_ratio.map(|v| (v, 100)),
-				pruning: config.pruning.clone(),
-				source: match &config.database {
-					DatabaseConfig::Path { path, cache_size } =>
-						sc_client_db::DatabaseSettingsSrc::Path {
-							path: path.clone(),
-							cache_size: *cache_size,
-						},
-					DatabaseConfig::Custom(db) =>
-						sc_client_db::DatabaseSettingsSrc::Custom(db.clone()),
-				},
-			};
+			let db_settings = database_settings(&config, &config.database);
 			sc_client_db::light::LightStorage::new(db_settings)?
 		};
 		let light_blockchain = sc_client::light::new_light_blockchain(db_storage);
@@ -344,6 +351,7 @@ impl ServiceBuilder<(), (), (), (), (), (), (), (), (), (), ()> {
 			import_queue: (),
 			finality_proof_request_builder: None,
 			finality_proof_provider: None,
+			warp_sync_provider: None,
 			transaction_pool: Arc::new(()),
 			rpc_extensions: Default::default(),
 			remote_backend: Some(remote_blockchain),
@@ -417,6 +425,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			import_queue: self.import_queue,
 			finality_proof_request_builder: self.finality_proof_request_builder,
 			finality_proof_provider: self.finality_proof_provider,
+			warp_sync_provider: self.warp_sync_provider,
 			transaction_pool: self.transaction_pool,
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
@@ -460,6 +469,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			import_queue,
 			finality_proof_request_builder: self.finality_proof_request_builder,
 			finality_proof_provider: self.finality_proof_provider,
+			warp_sync_provider: self.warp_sync_provider,
 			transaction_pool: self.transaction_pool,
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
@@ -498,6 +508,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			import_queue: self.import_queue,
 			finality_proof_request_builder: self.finality_proof_request_builder,
 			finality_proof_provider,
+			warp_sync_provider: self.warp_sync_provider,
 			transaction_pool: self.transaction_pool,
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
@@ -526,6 +537,16 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 		self.with_opt_finality_proof_provider(|client, backend| build(client, backend).map(Option::Some))
 	}
 
+	/// Defines the provider of warp sync proofs. It is used to answer warp sync requests from
+	/// other nodes and, on light clients, to warp sync when starting from genesis.
+	pub fn with_warp_sync_provider(
+		mut self,
+		build: impl FnOnce(Arc<TCl>, Arc<Backend>) -> Result<Arc<dyn WarpSyncProvider<TBl>>, Error>
+	) -> Result<Self, Error> {
+		self.warp_sync_provider = Some(build(self.client.clone(), self.backend.clone())?);
+		Ok(self)
+	}
+
 	/// Defines which import queue to use.
 	pub fn with_import_queue_and_opt_fprb<UImpQu, UFprb>(
 		self,
@@ -560,6 +581,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			import_queue,
 			finality_proof_request_builder: fprb,
 			finality_proof_provider: self.finality_proof_provider,
+			warp_sync_provider: self.warp_sync_provider,
 			transaction_pool: self.transaction_pool,
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
@@ -620,6 +642,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			import_queue: self.import_queue,
 			finality_proof_request_builder: self.finality_proof_request_builder,
 			finality_proof_provider: self.finality_proof_provider,
+			warp_sync_provider: self.warp_sync_provider,
 			transaction_pool: Arc::new(transaction_pool),
 			rpc_extensions: self.rpc_extensions,
 			remote_backend: self.remote_backend,
@@ -648,6 +671,7 @@ impl<TBl, TRtApi, TCl, TFchr, TSc, TImpQu, TFprb, TFpp, TExPool, TRpc, Backend>
 			import_queue: self.import_queue,
 			finality_proof_request_builder: self.finality_proof_request_builder,
 			finality_proof_provider: self.finality_proof_provider,
+			warp_sync_provider: self.warp_sync_provider,
 			transaction_pool: self.transaction_pool,
 			rpc_extensions,
 			remote_backend: self.remote_backend,
@@ -691,6 +715,43 @@ pub trait ServiceBuilderCommand {
 		self,
 		block: BlockId<Self::Block>
 	) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send>>;
+
+	/// Dry-run a runtime upgrade to `code` on top of the state of `block`.
+	///
+	/// Executes the `OnRuntimeUpgrade` hooks of the new runtime and, if `execute_block` is set,
+	/// the child of `block` afterwards. Nothing is written to the database.
+	fn try_runtime(
+		&self,
+		block: BlockId<Self::Block>,
+		code: Vec<u8>,
+		execute_block: bool,
+	) -> Result<(), Error>;
+
+	/// Like `try_runtime`, but on top of the state read from a snapshot.
+	///
+	/// The block following the snapshot is taken from the local database.
+	fn try_runtime_snapshot(
+		&self,
+		input: impl Read,
+		code: Vec<u8>,
+		execute_block: bool,
+	) -> Result<(), Error>;
+
+	/// Write a snapshot of the state of `block`, or of the last finalized block if `None`.
+	fn export_state(
+		&self,
+		block: Option<BlockId<Self::Block>>,
+		output: impl Write,
+	) -> Result<(), Error>;
+
+	/// Import the state from a snapshot.
+	///
+	/// The snapshot block has to be either known and finalized already, or unknown and above
+	/// the last finalized block, in which case it becomes the best and finalized block.
+	fn import_state(
+		&self,
+		input: impl Read,
+	) -> Result<(), Error>;
 }
 
 impl<TBl, TRtApi, TBackend, TExec, TSc, TImpQu, TExPool, TRpc>
@@ -759,6 +820,7 @@ ServiceBuilder<
 			import_queue,
 			finality_proof_request_builder,
 			finality_proof_provider,
+			warp_sync_provider,
 			transaction_pool,
 			rpc_extensions,
 			remote_backend,
@@ -825,6 +887,7 @@ ServiceBuilder<
 			network_config: config.network.clone(),
 			chain: client.clone(),
 			finality_proof_provider,
+			warp_sync_provider,
 			finality_proof_request_builder,
 			on_demand: on_demand.clone(),
 			transaction_pool: transaction_pool_adapter.clone() as _,
@@ -903,6 +966,13 @@ ServiceBuilder<
 						}
 					};
 
+					// the fork-aware offchain storage is pruned on finality
+					if let ChainEvent::Finalized { .. } = event {
+						if let Some(offchain) = offchain.as_ref().and_then(|o| o.upgrade()) {
+							offchain.on_block_finalized();
+						}
+					}
+
 					let txpool = txpool.upgrade();
 					if let Some(txpool) = txpool.as_ref() {
 						notifications_spawn_handle.spawn(
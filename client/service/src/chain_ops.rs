@@ -19,11 +19,13 @@
 use crate::error;
 use crate::builder::{ServiceBuilderCommand, ServiceBuilder};
 use crate::error::Error;
+use crate::config::{Configuration, DatabaseConfig, Role};
+use crate::snapshot::{self, SnapshotReader, SnapshotWriter};
 use sc_chain_spec::ChainSpec;
 use log::{warn, info};
 use futures::{future, prelude::*};
 use sp_runtime::traits::{
-	Block as BlockT, NumberFor, One, Zero, Header, SaturatedConversion
+	Block as BlockT, NumberFor, One, Zero, Header, SaturatedConversion, HashFor,
 };
 use sp_runtime::generic::{BlockId, SignedBlock};
 use codec::{Decode, Encode, IoReader};
@@ -32,16 +34,36 @@ use sp_consensus::{
 	BlockOrigin,
 	import_queue::{IncomingBlock, Link, BlockImportError, BlockImportResult, ImportQueue},
 };
-use sc_executor::{NativeExecutor, NativeExecutionDispatch};
+use sc_executor::{NativeExecutor, NativeExecutionDispatch, WasmExecutor, sp_wasm_interface::HostFunctions};
+use sp_core::{storage::{well_known_keys, ChildInfo}, traits::{CallInWasm, Externalities}};
+use sp_state_machine::{
+	Backend as StateBackend, Ext, InMemoryBackend, OverlayedChanges, StorageTransactionCache,
+};
 
 use std::{io::{Read, Write, Seek}, pin::Pin};
-use sc_client_api::BlockBackend;
+use sc_client_api::{BlockBackend, StateImporter};
 
 /// Build a chain spec json
 pub fn build_spec(spec: &dyn ChainSpec, raw: bool) -> error::Result<String> {
 	Ok(spec.as_json(raw)?)
 }
 
+/// Copy the database of the node into the empty `target` database, which may use another backend.
+///
+/// Returns the number of copied values.
+pub fn migrate_database<TBl: BlockT>(
+	config: &Configuration,
+	target: &DatabaseConfig,
+) -> error::Result<u64> {
+	let db_type = match config.role {
+		Role::Light => sc_client_db::DatabaseType::Light,
+		_ => sc_client_db::DatabaseType::Full,
+	};
+	let source = crate::builder::database_settings(config, &config.database);
+	let target = crate::builder::database_settings(config, target);
+	Ok(sc_client_db::migrate_database::<TBl>(&source, &target, db_type)?)
+}
+
 impl<
 	TBl, TRtApi, TBackend,
 	TExecDisp, TFchr, TSc, TImpQu, TFprb, TFpp,
@@ -296,4 +318,191 @@ impl<
 			Err(e) => Box::pin(future::err(format!("Error reading block: {:?}", e).into())),
 		}
 	}
+
+	fn try_runtime(
+		&self,
+		block_id: BlockId<TBl>,
+		code: Vec<u8>,
+		execute_block: bool,
+	) -> Result<(), Error> {
+		let header = self.client.header(&block_id)?.ok_or("Unknown block")?;
+		let next_block = if execute_block {
+			let number = *header.number() + One::one();
+			let block = self.client.block(&BlockId::Number(number))?
+				.ok_or_else(|| format!("Block #{} is not known, nothing to execute", number))?;
+			Some(block.block)
+		} else {
+			None
+		};
+
+		let state = self.client.state_at(&block_id)?;
+		let executor = try_runtime_executor::<TExecDisp>(&self.config);
+		dry_run_upgrade(&state, &header, next_block, code, &executor)
+	}
+
+	fn try_runtime_snapshot(
+		&self,
+		input: impl Read,
+		code: Vec<u8>,
+		execute_block: bool,
+	) -> Result<(), Error> {
+		let reader = SnapshotReader::new(input)?;
+		let header: TBl::Header = reader.header()?;
+		let storage = reader.read_storage()?;
+		if snapshot::storage_root::<HashFor<TBl>>(storage.clone()) != *header.state_root() {
+			return Err("Snapshot state doesn't match the state root of its header".into());
+		}
+
+		let next_block = if execute_block {
+			let number = *header.number() + One::one();
+			let block = self.client.block(&BlockId::Number(number))?
+				.filter(|block| block.block.header().parent_hash() == &header.hash())
+				.ok_or_else(|| format!("Child of the snapshot block #{} is not known, nothing to execute", number))?;
+			Some(block.block)
+		} else {
+			None
+		};
+
+		let state = InMemoryBackend::<HashFor<TBl>>::from(storage);
+		let executor = try_runtime_executor::<TExecDisp>(&self.config);
+		dry_run_upgrade(&state, &header, next_block, code, &executor)
+	}
+
+	fn export_state(
+		&self,
+		block_id: Option<BlockId<TBl>>,
+		output: impl Write,
+	) -> Result<(), Error> {
+		let block_id = block_id.unwrap_or_else(|| BlockId::Hash(self.client.chain_info().finalized_hash));
+		let header = self.client.header(&block_id)?.ok_or("Unknown block")?;
+		let state = self.client.state_at(&block_id)?;
+
+		info!("Exporting state of #{} ({})", header.number(), header.hash());
+		let mut writer = SnapshotWriter::new(output, &header)?;
+		let mut result = Ok(());
+		let mut count = 0u64;
+		// The roots of child tries are stored in the top trie under their storage keys. They
+		// aren't exported, as they are recalculated from the content of the child tries.
+		let mut child_tries = Vec::new();
+		state.for_key_values_with_prefix(&[], |key, value| {
+			if result.is_err() {
+				return
+			}
+			if well_known_keys::is_child_storage_key(key) {
+				child_tries.push(key.to_vec());
+			} else {
+				count += 1;
+				result = writer.write_top(key, value);
+			}
+		});
+		result?;
+
+		for storage_key in child_tries {
+			// The information needed to read the content of a child trie isn't part of the state,
+			// but default child tries use the rest of their storage key as unique id.
+			if !well_known_keys::is_child_trie_key_valid(&storage_key) {
+				return Err(format!("State contains an unsupported child trie at {:?}", storage_key).into());
+			}
+			let start = well_known_keys::CHILD_STORAGE_KEY_PREFIX.len() + b"default:".len();
+			let child_info = ChildInfo::new_default(&storage_key[start..]);
+			writer.start_child_trie(&storage_key, child_info)?;
+			state.for_keys_in_child_storage(&storage_key, child_info, |key| {
+				if result.is_err() {
+					return
+				}
+				count += 1;
+				result = state.child_storage(&storage_key, child_info, key)
+					.map_err(|e| Error::Other(format!("Error reading state: {:?}", e)))
+					.and_then(|value| writer.write_child(key, &value.unwrap_or_default()));
+			});
+			result?;
+		}
+		writer.finish()?;
+		info!("Exported {} keys", count);
+
+		Ok(())
+	}
+
+	fn import_state(
+		&self,
+		input: impl Read,
+	) -> Result<(), Error> {
+		let reader = SnapshotReader::new(input)?;
+		let header: TBl::Header = reader.header()?;
+		info!("Importing state of #{} ({})", header.number(), header.hash());
+		let storage = reader.read_storage()?;
+		let count = storage.top.len() + storage.children.values().map(|c| c.data.len()).sum::<usize>();
+		// The state root is checked against the header by the client, which also inserts the
+		// header if the block isn't known yet.
+		self.client.import_state(header, storage)?;
+		info!("Imported {} keys", count);
+
+		Ok(())
+	}
+}
+
+/// Create the executor used to dry-run runtime upgrades.
+fn try_runtime_executor<D: NativeExecutionDispatch>(config: &Configuration) -> WasmExecutor {
+	let mut host_functions = sp_io::SubstrateHostFunctions::host_functions();
+	host_functions.extend(D::ExtendHostFunctions::host_functions());
+	WasmExecutor::new(
+		config.wasm_method,
+		config.default_heap_pages,
+		host_functions,
+		false,
+		1,
+		Some(config.wasm_stack_limit),
+		config.wasm_runtime_cache_path.clone(),
+	)
+}
+
+/// Apply `code` on top of `state` and call the `TryRuntime` api of the new runtime.
+fn dry_run_upgrade<TBl: BlockT, S: StateBackend<HashFor<TBl>>>(
+	state: &S,
+	header: &TBl::Header,
+	next_block: Option<TBl>,
+	code: Vec<u8>,
+	executor: &WasmExecutor,
+) -> Result<(), Error> {
+	info!(
+		"Dry-running runtime upgrade on top of #{} ({})",
+		header.number(),
+		header.hash(),
+	);
+
+	let mut overlay = OverlayedChanges::default();
+	let mut cache = StorageTransactionCache::<_, HashFor<TBl>, NumberFor<TBl>>::default();
+	{
+		let mut ext = Ext::new(&mut overlay, &mut cache, state, None, None);
+		ext.set_storage(well_known_keys::CODE.to_vec(), code.clone());
+
+		let call = |ext: &mut dyn Externalities, method: &str, data: &[u8]| {
+			executor.call_in_wasm(&code, None, method, data, ext)
+				.map_err(|e| Error::Other(format!("`{}` failed: {}", method, e)))
+				.and_then(|res| u64::decode(&mut &res[..])
+					.map_err(|e| Error::Other(format!("Invalid weight returned by `{}`: {:?}", method, e)))
+				)
+		};
+
+		let weight = call(&mut ext, "TryRuntime_on_runtime_upgrade", &[])?;
+		info!("Runtime upgrade consumed {} weight", weight);
+
+		if let Some(block) = next_block {
+			let number = *block.header().number();
+			let weight = call(&mut ext, "TryRuntime_execute_block_no_check", &block.encode())?;
+			info!("Block #{} consumed {} weight after the upgrade", number, weight);
+		}
+	}
+
+	overlay.commit_prospective();
+	let changes = overlay.drain_storage_changes(state, None, header.hash(), &mut cache)?;
+	let (mut keys, mut bytes) = (0usize, 0usize);
+	let child_changes = changes.child_storage_changes.iter().flat_map(|(_, c)| c.iter());
+	for (key, value) in changes.main_storage_changes.iter().chain(child_changes) {
+		keys += 1;
+		bytes += key.len() + value.as_ref().map_or(0, |v| v.len());
+	}
+	info!("Storage diff: {} keys changed, {} bytes", keys, bytes);
+
+	Ok(())
 }
@@ -17,10 +17,10 @@
 //! Service configuration.
 
 pub use sc_client::ExecutionStrategies;
-pub use sc_client_db::{kvdb::KeyValueDB, PruningMode};
+pub use sc_client_db::{kvdb::KeyValueDB, Database, PruningMode};
 pub use sc_network::Multiaddr;
 pub use sc_network::config::{ExtTransport, MultiaddrWithPeerId, NetworkConfiguration, Role, NodeKeyConfig};
-pub use sc_executor::WasmExecutionMethod;
+pub use sc_executor::{DeterministicStackLimit, WasmExecutionMethod};
 
 use std::{future::Future, path::{PathBuf, Path}, pin::Pin, net::SocketAddr, sync::Arc};
 pub use sc_transaction_pool::txpool::Options as TransactionPoolOptions;
@@ -53,6 +53,8 @@ pub struct Configuration {
 	pub state_cache_child_ratio: Option<usize>,
 	/// Pruning settings.
 	pub pruning: PruningMode,
+	/// Maintain an index of the blocks at which each storage key has been changed.
+	pub key_history: bool,
 	/// Chain configuration.
 	pub chain_spec: Box<dyn ChainSpec>,
 	/// Wasm execution method.
@@ -78,6 +80,8 @@ pub struct Configuration {
 	pub default_heap_pages: Option<u64>,
 	/// Should offchain workers be executed.
 	pub offchain_worker: bool,
+	/// Should the runtime be allowed to write to the offchain database during block import.
+	pub offchain_indexing: bool,
 	/// Enable authoring even when offline.
 	pub force_authoring: bool,
 	/// Disable GRANDPA when running in validator mode
@@ -96,6 +100,11 @@ pub struct Configuration {
 	///
 	/// The default value is 8.
 	pub max_runtime_instances: usize,
+	/// Directory to cache compiled wasm runtimes in. `None` if disabled.
+	pub wasm_runtime_cache_path: Option<PathBuf>,
+	/// Limit of the logical stack height wasm runtimes are instrumented with. Calls exceeding it
+	/// trap, so all nodes of a network must use the same limit.
+	pub wasm_stack_limit: DeterministicStackLimit,
 	/// Announce block automatically after they have been imported
 	pub announce_block: bool,
 }
@@ -127,7 +136,7 @@ impl KeystoreConfig {
 /// Configuration of the database of the client.
 #[derive(Clone)]
 pub enum DatabaseConfig {
-	/// Database file at a specific path. Recommended for most uses.
+	/// RocksDB database file at a specific path. Recommended for most uses.
 	Path {
 		/// Path to the database.
 		path: PathBuf,
@@ -135,8 +144,24 @@ pub enum DatabaseConfig {
 		cache_size: usize,
 	},
 
+	/// Parity-DB database at a specific path.
+	ParityDb {
+		/// Path to the database.
+		path: PathBuf,
+	},
+
 	/// A custom implementation of an already-open database.
-	Custom(Arc<dyn KeyValueDB>),
+	Custom(Arc<dyn Database>),
+}
+
+impl DatabaseConfig {
+	/// Returns the path of the database, if it is stored on disk.
+	pub fn path(&self) -> Option<&Path> {
+		match self {
+			Self::Path { path, .. } | Self::ParityDb { path } => Some(path),
+			Self::Custom(_) => None,
+		}
+	}
 }
 
 /// Configuration of the Prometheus endpoint.
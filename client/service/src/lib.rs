@@ -23,6 +23,7 @@ pub mod config;
 #[macro_use]
 pub mod chain_ops;
 pub mod error;
+pub mod snapshot;
 
 mod metrics;
 mod builder;
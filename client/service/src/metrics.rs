@@ -17,7 +17,7 @@
 use std::convert::TryFrom;
 
 use crate::NetworkStatus;
-use prometheus_endpoint::{register, Gauge, U64, F64, Registry, PrometheusError, Opts, GaugeVec};
+use prometheus_endpoint::{register, Gauge, U64, F64, Registry, PrometheusError, Opts, GaugeVec, CounterVec};
 use sc_client::ClientInfo;
 use sc_telemetry::{telemetry, SUBSTRATE_INFO};
 use sp_runtime::traits::{NumberFor, Block, SaturatedConversion, UniqueSaturatedInto};
@@ -50,6 +50,7 @@ struct PrometheusMetrics {
 	block_height: GaugeVec<U64>,
 	number_leaves: Gauge<U64>,
 	ready_transactions_number: Gauge<U64>,
+	evicted_transactions: CounterVec<U64>,
 
 	// I/O
 	network_per_sec_bytes: GaugeVec<U64>,
@@ -125,6 +126,11 @@ impl PrometheusMetrics {
 				"ready_transactions_number", "Number of transactions in the ready queue",
 			)?, registry)?,
 
+			evicted_transactions: register(CounterVec::new(
+				Opts::new("evicted_transactions", "Number of transactions evicted from the pool by its limits"),
+				&["limit"]
+			)?, registry)?,
+
 			// I/ O
 			network_per_sec_bytes: register(GaugeVec::new(
 				Opts::new("network_per_sec_bytes", "Networking bytes per second"),
@@ -386,6 +392,11 @@ impl MetricsService {
 			}
 
 			metrics.ready_transactions_number.set(txpool_status.ready as u64);
+			// the pool reports totals, the counters only advance by the evictions since the last update.
+			for (limit, total) in &[("pool", txpool_status.evicted), ("sender", txpool_status.sender_evicted)] {
+				let counter = metrics.evicted_transactions.with_label_values(&[limit]);
+				counter.inc_by((*total as u64).saturating_sub(counter.get()));
+			}
 
 			if let Some(best_seen_block) = best_seen_block {
 				metrics.block_height.with_label_values(&["sync_target"]).set(best_seen_block);
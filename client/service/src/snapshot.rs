@@ -0,0 +1,235 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State snapshots.
+//!
+//! A snapshot contains the header of a block together with all key/value pairs of its state,
+//! stored as a stream of SCALE encoded entries:
+//!
+//! ```text
+//! MAGIC ++ VERSION ++ Vec<u8> (encoded header) ++ Entry* ++ Entry::End
+//! ```
+//!
+//! Key/value pairs that follow an `Entry::ChildTrie` belong to that child trie. The roots of child
+//! tries are not part of the top trie entries.
+//!
+//! The writer streams the entries to its output, so exporting the state doesn't keep it in
+//! memory. The reader collects the entries into a [`Storage`] though, as checking the state root
+//! and importing the state need all of it.
+
+use crate::error::Error;
+use codec::{Codec, Decode, Encode, IoReader};
+use sp_core::{Hasher, storage::{ChildInfo, Storage, StorageChild}};
+use sp_state_machine::{Backend as StateBackend, InMemoryBackend};
+use std::io::{Read, Write};
+
+/// Magic bytes every snapshot starts with.
+pub const MAGIC: &[u8; 8] = b"substate";
+
+/// Version of the snapshot format produced by this module.
+pub const VERSION: u32 = 1;
+
+#[derive(Encode, Decode)]
+enum Entry {
+	/// Key/value pair of the top trie.
+	Top(Vec<u8>, Vec<u8>),
+	/// Start of a child trie.
+	ChildTrie { storage_key: Vec<u8>, child_type: u32, child_info: Vec<u8> },
+	/// Key/value pair of the last started child trie.
+	Child(Vec<u8>, Vec<u8>),
+	/// End of the snapshot.
+	End,
+}
+
+/// Writes a state snapshot to `W`.
+pub struct SnapshotWriter<W> {
+	output: W,
+	in_child_trie: bool,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+	/// Start a new snapshot of the state of the block with the given `header`.
+	pub fn new(mut output: W, header: &impl Encode) -> Result<Self, Error> {
+		output.write_all(MAGIC)?;
+		output.write_all(&VERSION.encode())?;
+		output.write_all(&header.encode().encode())?;
+		Ok(SnapshotWriter { output, in_child_trie: false })
+	}
+
+	/// Write a key/value pair of the top trie.
+	///
+	/// All top trie entries have to be written before the first child trie.
+	pub fn write_top(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+		if self.in_child_trie {
+			return Err("Top trie entries must be written before child tries".into());
+		}
+		self.write(Entry::Top(key.to_vec(), value.to_vec()))
+	}
+
+	/// Start a new child trie. All pairs written with `write_child` until the next call
+	/// belong to it.
+	pub fn start_child_trie(&mut self, storage_key: &[u8], child_info: ChildInfo) -> Result<(), Error> {
+		let (info, child_type) = child_info.info();
+		self.in_child_trie = true;
+		self.write(Entry::ChildTrie {
+			storage_key: storage_key.to_vec(),
+			child_type,
+			child_info: info.to_vec(),
+		})
+	}
+
+	/// Write a key/value pair of the current child trie.
+	pub fn write_child(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+		if !self.in_child_trie {
+			return Err("No child trie started".into());
+		}
+		self.write(Entry::Child(key.to_vec(), value.to_vec()))
+	}
+
+	/// Finish the snapshot and return the output.
+	pub fn finish(mut self) -> Result<W, Error> {
+		self.write(Entry::End)?;
+		self.output.flush()?;
+		Ok(self.output)
+	}
+
+	fn write(&mut self, entry: Entry) -> Result<(), Error> {
+		self.output.write_all(&entry.encode())?;
+		Ok(())
+	}
+}
+
+/// Reads a state snapshot from `R`.
+pub struct SnapshotReader<R> {
+	input: IoReader<R>,
+	header: Vec<u8>,
+}
+
+impl<R: Read> SnapshotReader<R> {
+	/// Open a snapshot and read its header.
+	pub fn new(input: R) -> Result<Self, Error> {
+		let mut input = IoReader(input);
+		let magic = <[u8; 8]>::decode(&mut input)
+			.map_err(|_| Error::Other("Not a state snapshot".into()))?;
+		if &magic != MAGIC {
+			return Err("Not a state snapshot".into());
+		}
+		let version = u32::decode(&mut input)
+			.map_err(|e| format!("Error reading snapshot version: {}", e.what()))?;
+		if version != VERSION {
+			return Err(format!("Unsupported snapshot version {}, expected {}", version, VERSION).into());
+		}
+		let header = Vec::<u8>::decode(&mut input)
+			.map_err(|e| format!("Error reading snapshot header: {}", e.what()))?;
+		Ok(SnapshotReader { input, header })
+	}
+
+	/// Decode the header of the block the snapshot was taken at.
+	pub fn header<H: Decode>(&self) -> Result<H, Error> {
+		H::decode(&mut &self.header[..])
+			.map_err(|e| format!("Error decoding snapshot header: {}", e.what()).into())
+	}
+
+	/// Read all remaining entries of the snapshot. The complete state is kept in memory.
+	pub fn read_storage(mut self) -> Result<Storage, Error> {
+		let mut storage = Storage::default();
+		let mut child_trie: Option<Vec<u8>> = None;
+		loop {
+			let entry = Entry::decode(&mut self.input)
+				.map_err(|e| format!("Error reading snapshot entry: {}", e.what()))?;
+			match entry {
+				Entry::Top(key, value) => {
+					storage.top.insert(key, value);
+				},
+				Entry::ChildTrie { storage_key, child_type, child_info } => {
+					let child_info = ChildInfo::resolve_child_info(child_type, &child_info)
+						.ok_or_else(|| format!("Unknown child trie type {}", child_type))?
+						.to_owned();
+					storage.children.insert(storage_key.clone(), StorageChild {
+						data: Default::default(),
+						child_info,
+					});
+					child_trie = Some(storage_key);
+				},
+				Entry::Child(key, value) => {
+					let child = child_trie.as_ref()
+						.and_then(|storage_key| storage.children.get_mut(storage_key))
+						.ok_or("Child trie entry outside of a child trie")?;
+					child.data.insert(key, value);
+				},
+				Entry::End => return Ok(storage),
+			}
+		}
+	}
+}
+
+/// Calculate the state root of `storage`, including all child tries.
+pub fn storage_root<H: Hasher>(storage: Storage) -> H::Out where H::Out: Ord + Codec {
+	let child_tries = storage.children.iter()
+		.map(|(storage_key, child)| (
+			storage_key.clone(),
+			std::iter::empty::<(Vec<u8>, Option<Vec<u8>>)>(),
+			child.child_info.clone(),
+		))
+		.collect::<Vec<_>>();
+	InMemoryBackend::<H>::from(storage).full_storage_root(std::iter::empty(), child_tries).0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::Blake2Hasher;
+
+	const CHILD_INFO: ChildInfo<'static> = ChildInfo::new_default(b"unique_id");
+
+	#[test]
+	fn snapshot_roundtrip() {
+		let header = (42u64, [1u8; 32]);
+		let mut writer = SnapshotWriter::new(Vec::new(), &header).unwrap();
+		writer.write_top(b"a", b"1").unwrap();
+		writer.write_top(b"b", b"2").unwrap();
+		writer.start_child_trie(b":child_storage:default:c", CHILD_INFO).unwrap();
+		writer.write_child(b"d", b"3").unwrap();
+		assert!(writer.write_top(b"e", b"4").is_err());
+		let data = writer.finish().unwrap();
+
+		let reader = SnapshotReader::new(&data[..]).unwrap();
+		assert_eq!(reader.header::<(u64, [u8; 32])>().unwrap(), header);
+		let storage = reader.read_storage().unwrap();
+		assert_eq!(storage.top.len(), 2);
+		assert_eq!(storage.top.get(&b"b"[..]), Some(&b"2".to_vec()));
+		let child = storage.children.get(&b":child_storage:default:c"[..]).unwrap();
+		assert_eq!(child.child_info, CHILD_INFO.to_owned());
+		assert_eq!(child.data.get(&b"d"[..]), Some(&b"3".to_vec()));
+
+		let root = storage_root::<Blake2Hasher>(storage.clone());
+		assert_ne!(root, storage_root::<Blake2Hasher>(Storage { children: Default::default(), ..storage }));
+	}
+
+	#[test]
+	fn rejects_invalid_snapshots() {
+		assert!(SnapshotReader::new(&b"not a snapshot"[..]).is_err());
+
+		let mut data = MAGIC.to_vec();
+		data.extend((VERSION + 1).encode());
+		assert!(SnapshotReader::new(&data[..]).is_err());
+
+		// Missing `End`.
+		let mut data = SnapshotWriter::new(Vec::new(), &1u64).unwrap().finish().unwrap();
+		data.pop();
+		assert!(SnapshotReader::new(&data[..]).unwrap().read_storage().is_err());
+	}
+}
@@ -25,12 +25,12 @@ use parking_lot::{Mutex, RwLock};
 use codec::{Encode, Decode};
 use hash_db::Prefix;
 use sp_core::{
-	ChangesTrieConfiguration, convert_hash, traits::CodeExecutor,
+	ChangesTrieConfiguration, convert_hash, traits::CodeExecutor, ExecutionContext,
 	NativeOrEncoded, storage::{StorageKey, StorageData, well_known_keys, ChildInfo},
 };
 use sc_telemetry::{telemetry, SUBSTRATE_INFO};
 use sp_runtime::{
-	Justification, BuildStorage,
+	Justification, BuildStorage, Storage,
 	generic::{BlockId, SignedBlock, DigestItem},
 	traits::{
 		Block as BlockT, Header as HeaderT, Zero, NumberFor, HashFor, SaturatedConversion, One,
@@ -39,7 +39,9 @@ use sp_runtime::{
 };
 use sp_state_machine::{
 	DBValue, Backend as StateBackend, ChangesTrieAnchorBlockId,
-	prove_read, prove_child_read, ChangesTrieRootsStorage, ChangesTrieStorage,
+	prove_read, prove_child_read, prove_range_read_with_size, prove_child_range_read_with_size,
+	read_range_proof_check,
+	ChangesTrieRootsStorage, ChangesTrieStorage,
 	ChangesTrieConfigurationRange, key_changes, key_changes_proof,
 };
 use sc_executor::{RuntimeVersion, RuntimeInfo};
@@ -70,7 +72,7 @@ pub use sc_client_api::{
 	},
 	client::{
 		ImportNotifications, FinalityNotification, FinalityNotifications, BlockImportNotification,
-		ClientInfo, BlockchainEvents, BlockBackend, ProvideUncles, BadBlocks, ForkBlocks,
+		ClientInfo, BlockchainEvents, BlockBackend, StateImporter, ProvideUncles, BadBlocks, ForkBlocks,
 		BlockOf,
 	},
 	execution_extensions::{ExecutionExtensions, ExecutionStrategies},
@@ -656,8 +658,12 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		let info = self.backend.blockchain().info();
 
 		// the block is lower than our last finalized block so it must revert
-		// finality, refusing import.
-		if *import_headers.post().number() <= info.finalized_number {
+		// finality, refusing import. Existing blocks that were imported without
+		// state (e.g. during fast sync) may still be executed.
+		let number = *import_headers.post().number();
+		if number <= info.finalized_number
+			&& !(import_existing && !self.backend.have_state_at(&hash, number))
+		{
 			return Err(sp_blockchain::Error::NotInFinalizedChain);
 		}
 
@@ -687,10 +693,18 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 
 				operation.op.update_cache(new_cache);
 
-				let (main_sc, child_sc, tx, _, changes_trie_tx) = storage_changes.into_inner();
+				let (
+					main_sc,
+					child_sc,
+					tx,
+					_,
+					changes_trie_tx,
+					offchain_sc,
+				) = storage_changes.into_inner();
 
 				operation.op.update_db_storage(tx)?;
 				operation.op.update_storage(main_sc.clone(), child_sc.clone())?;
+				operation.op.update_offchain_storage(offchain_sc)?;
 
 				if let Some(changes_trie_transaction) = changes_trie_tx {
 					operation.op.update_changes_trie(changes_trie_transaction)?;
@@ -792,9 +806,15 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			// block.
 			(true, ref mut storage_changes @ None, Some(ref body)) => {
 				let runtime_api = self.runtime_api();
+				let execution_context = if import_block.origin == BlockOrigin::NetworkInitialSync {
+					ExecutionContext::Syncing
+				} else {
+					ExecutionContext::Importing
+				};
 
-				runtime_api.execute_block(
+				runtime_api.execute_block_with_context(
 					&at,
+					execution_context,
 					Block::new(import_block.header.clone(), body.clone()),
 				)?;
 
@@ -1160,6 +1180,51 @@ impl<B, E, Block, RA> ProofProvider<Block> for Client<B, E, Block, RA> where
 			cht::size(),
 		)
 	}
+
+	fn read_proof_collection(
+		&self,
+		id: &BlockId<Block>,
+		start_key: Option<&[u8]>,
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, u32)> {
+		let state = self.state_at(id)?;
+		Ok(prove_range_read_with_size::<_, HashFor<Block>>(
+			state,
+			size_limit,
+			start_key,
+		)?)
+	}
+
+	fn read_child_proof_collection(
+		&self,
+		id: &BlockId<Block>,
+		storage_key: &[u8],
+		child_info: ChildInfo,
+		start_key: Option<&[u8]>,
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, u32)> {
+		let state = self.state_at(id)?;
+		Ok(prove_child_range_read_with_size::<_, HashFor<Block>>(
+			state,
+			storage_key,
+			child_info,
+			size_limit,
+			start_key,
+		)?)
+	}
+
+	fn verify_range_proof(
+		&self,
+		root: Block::Hash,
+		proof: StorageProof,
+		start_key: Option<&[u8]>,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)> {
+		Ok(read_range_proof_check::<HashFor<Block>>(
+			root,
+			proof,
+			start_key,
+		)?)
+	}
 }
 
 
@@ -1392,6 +1457,15 @@ impl<B, E, Block, RA> StorageProvider<Block, B> for Client<B, E, Block, RA> wher
 
 		Ok(result)
 	}
+
+	fn key_history(
+		&self,
+		first: NumberFor<Block>,
+		last: NumberFor<Block>,
+		key: &StorageKey,
+	) -> sp_blockchain::Result<Option<Vec<NumberFor<Block>>>> {
+		self.backend.key_history(&key.0, first, last)
+	}
 }
 
 impl<B, E, Block, RA> HeaderMetadata<Block> for Client<B, E, Block, RA> where
@@ -1541,6 +1615,10 @@ impl<B, E, Block, RA> CallApiAt<Block> for Client<B, E, Block, RA> where
 		let core_api = params.core_api;
 		let at = params.at;
 
+		if self.execution_extensions.offchain_indexing(&params.context) {
+			params.overlayed_changes.borrow_mut().set_offchain_indexing(true);
+		}
+
 		let (manager, extensions) = self.execution_extensions.manager_and_extensions(
 			at,
 			params.context,
@@ -1649,7 +1727,8 @@ impl<B, E, Block, RA> sp_consensus::BlockImport<Block> for &Client<B, E, Block,
 		{
 			BlockStatus::InChainWithState | BlockStatus::Queued if !import_existing  => return Ok(ImportResult::AlreadyInChain),
 			BlockStatus::InChainWithState | BlockStatus::Queued => {},
-			BlockStatus::InChainPruned => return Ok(ImportResult::AlreadyInChain),
+			BlockStatus::InChainPruned if !import_existing => return Ok(ImportResult::AlreadyInChain),
+			BlockStatus::InChainPruned => {},
 			BlockStatus::Unknown => {},
 			BlockStatus::KnownBad => return Ok(ImportResult::KnownBad),
 		}
@@ -1861,6 +1940,75 @@ where
 	}
 }
 
+impl<B, E, Block, RA> Client<B, E, Block, RA>
+	where
+		B: backend::Backend<Block>,
+		E: CallExecutor<Block>,
+		Block: BlockT,
+{
+	/// Returns the header of the block whose state is to be imported, which must be finalized.
+	fn state_import_header(&self, hash: Block::Hash) -> sp_blockchain::Result<Block::Header> {
+		let header = self.backend.blockchain().expect_header(BlockId::Hash(hash))?;
+		let number = *header.number();
+		let info = self.backend.blockchain().info();
+		if number > info.finalized_number || self.backend.blockchain().hash(number)? != Some(hash) {
+			return Err(sp_blockchain::Error::NotInFinalizedChain);
+		}
+		Ok(header)
+	}
+}
+
+impl<B, E, Block, RA> StateImporter<Block> for Client<B, E, Block, RA>
+	where
+		B: backend::Backend<Block>,
+		E: CallExecutor<Block>,
+		Block: BlockT,
+{
+	fn import_state(&self, header: Block::Header, storage: Storage) -> sp_blockchain::Result<()> {
+		self.lock_import_and_run(|operation| {
+			let hash = header.hash();
+			if self.backend.blockchain().header(BlockId::Hash(hash))?.is_some() {
+				self.state_import_header(hash)?;
+			} else {
+				operation.op.set_snapshot_header(header.clone())?;
+			}
+			let root = operation.op.import_state(hash, storage)?;
+			if &root != header.state_root() {
+				return Err(Error::InvalidStateRoot);
+			}
+			Ok(())
+		})
+	}
+
+	fn import_state_chunk(
+		&self,
+		hash: Block::Hash,
+		child: Option<(&[u8], ChildInfo, Block::Hash)>,
+		proof: StorageProof,
+		start: Option<&[u8]>,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)> {
+		self.lock_import_and_run(|operation| {
+			let header = self.state_import_header(hash)?;
+			match child {
+				Some((storage_key, child_info, root)) => operation.op.import_state_chunk(
+					Some((storage_key, child_info)),
+					root,
+					proof,
+					start,
+				),
+				None => operation.op.import_state_chunk(None, *header.state_root(), proof, start),
+			}
+		})
+	}
+
+	fn complete_state_import(&self, hash: Block::Hash, proof: StorageProof) -> sp_blockchain::Result<()> {
+		self.lock_import_and_run(|operation| {
+			let header = self.state_import_header(hash)?;
+			operation.op.complete_state_import(hash, *header.state_root(), proof)
+		})
+	}
+}
+
 impl<B, E, Block, RA> BlockBackend<Block> for Client<B, E, Block, RA>
 	where
 		B: backend::Backend<Block>,
@@ -3022,6 +3170,7 @@ pub(crate) mod tests {
 				state_cache_size: 1 << 20,
 				state_cache_child_ratio: None,
 				pruning: PruningMode::ArchiveAll,
+				key_history: false,
 				source: DatabaseSettingsSrc::Path {
 					path: tmp.path().into(),
 					cache_size: 128,
@@ -3224,6 +3373,7 @@ pub(crate) mod tests {
 					state_cache_size: 1 << 20,
 					state_cache_child_ratio: None,
 					pruning: PruningMode::keep_blocks(1),
+					key_history: false,
 					source: DatabaseSettingsSrc::Path {
 						path: tmp.path().into(),
 						cache_size: 128,
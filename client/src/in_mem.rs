@@ -19,7 +19,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use parking_lot::RwLock;
-use sp_core::storage::well_known_keys;
+use sp_core::storage::{well_known_keys, ChildInfo, OwnedChildInfo};
 use sp_core::offchain::storage::{
 	InMemOffchainStorage as OffchainStorage
 };
@@ -28,7 +28,7 @@ use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Zero, NumberFor, Ha
 use sp_runtime::{Justification, Storage};
 use sp_state_machine::{
 	ChangesTrieTransaction, InMemoryBackend, Backend as StateBackend, StorageCollection,
-	ChildStorageCollection,
+	ChildStorageCollection, StorageProof, read_range_proof_check_with_nodes,
 };
 use sp_blockchain::{CachedHeaderMetadata, HeaderMetadata};
 
@@ -182,6 +182,21 @@ impl<Block: BlockT> Blockchain<Block> {
 		Ok(())
 	}
 
+	/// Insert the header of a block with unknown ancestry as the best and finalized block.
+	pub fn insert_snapshot_header(&self, header: <Block as BlockT>::Header) {
+		let hash = header.hash();
+		let number = *header.number();
+		let mut storage = self.storage.write();
+		storage.leaves.import(hash, number, *header.parent_hash());
+		storage.leaves.finalize_height(number);
+		storage.blocks.insert(hash, StoredBlock::new(header, None, None));
+		storage.hashes.insert(number, hash);
+		storage.best_hash = hash;
+		storage.best_number = number;
+		storage.finalized_hash = hash;
+		storage.finalized_number = number;
+	}
+
 	/// Get total number of blocks.
 	pub fn blocks_count(&self) -> usize {
 		self.storage.read().blocks.len()
@@ -418,6 +433,37 @@ impl<Block: BlockT> sc_client_api::light::Storage<Block> for Blockchain<Block>
 		Ok(())
 	}
 
+	fn import_warp_target(
+		&self,
+		header: Block::Header,
+		aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	) -> sp_blockchain::Result<()> {
+		let hash = header.hash();
+		let number = *header.number();
+		{
+			let mut storage = self.storage.write();
+			if number <= storage.finalized_number {
+				return Err(sp_blockchain::Error::Backend(format!(
+					"Cannot import warp sync target #{} at or below last finalized block #{}",
+					number,
+					storage.finalized_number,
+				)));
+			}
+
+			storage.leaves = LeafSet::new();
+			storage.leaves.import(hash, number, header.parent_hash().clone());
+			storage.blocks.insert(hash, StoredBlock::new(header, None, None));
+			storage.hashes.insert(number, hash);
+			storage.best_hash = hash;
+			storage.best_number = number;
+			storage.finalized_hash = hash;
+			storage.finalized_number = number;
+		}
+
+		self.write_aux(aux_ops);
+		Ok(())
+	}
+
 	fn set_head(&self, id: BlockId<Block>) -> sp_blockchain::Result<()> {
 		Blockchain::set_head(self, id)
 	}
@@ -465,6 +511,9 @@ pub struct BlockImportOperation<Block: BlockT> {
 	pending_cache: HashMap<CacheKeyId, Vec<u8>>,
 	old_state: InMemoryBackend<HashFor<Block>>,
 	new_state: Option<InMemoryBackend<HashFor<Block>>>,
+	imported_state: Option<Block::Hash>,
+	imported_state_chunks: Vec<(Option<(Vec<u8>, OwnedChildInfo)>, StorageCollection)>,
+	snapshot_header: Option<Block::Header>,
 	aux: Vec<(Vec<u8>, Option<Vec<u8>>)>,
 	finalized_blocks: Vec<(BlockId<Block>, Option<Justification>)>,
 	set_head: Option<BlockId<Block>>,
@@ -529,6 +578,47 @@ impl<Block: BlockT> backend::BlockImportOperation<Block> for BlockImportOperatio
 		Ok(root)
 	}
 
+	fn import_state(&mut self, block: Block::Hash, storage: Storage) -> sp_blockchain::Result<Block::Hash> {
+		let root = self.reset_storage(storage)?;
+		self.imported_state = Some(block);
+		Ok(root)
+	}
+
+	fn set_snapshot_header(&mut self, header: Block::Header) -> sp_blockchain::Result<()> {
+		self.snapshot_header = Some(header);
+		Ok(())
+	}
+
+	fn import_state_chunk(
+		&mut self,
+		child: Option<(&[u8], ChildInfo)>,
+		root: Block::Hash,
+		proof: StorageProof,
+		start: Option<&[u8]>,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)> {
+		let (values, complete, _) = read_range_proof_check_with_nodes::<HashFor<Block>>(
+			root,
+			proof,
+			child.map(|(_, child_info)| child_info),
+			start,
+		).map_err(sp_blockchain::Error::from_state)?;
+		self.imported_state_chunks.push((
+			child.map(|(storage_key, child_info)| (storage_key.to_vec(), child_info.to_owned())),
+			values.iter().map(|(k, v)| (k.clone(), Some(v.clone()))).collect(),
+		));
+		Ok((values, complete))
+	}
+
+	fn complete_state_import(
+		&mut self,
+		block: Block::Hash,
+		_root: Block::Hash,
+		_proof: StorageProof,
+	) -> sp_blockchain::Result<()> {
+		self.imported_state = Some(block);
+		Ok(())
+	}
+
 	fn insert_aux<I>(&mut self, ops: I) -> sp_blockchain::Result<()>
 		where I: IntoIterator<Item=(Vec<u8>, Option<Vec<u8>>)>
 	{
@@ -566,6 +656,8 @@ impl<Block: BlockT> backend::BlockImportOperation<Block> for BlockImportOperatio
 /// > struct for testing purposes. Do **NOT** use in production.
 pub struct Backend<Block: BlockT> where Block::Hash: Ord {
 	states: RwLock<HashMap<Block::Hash, InMemoryBackend<HashFor<Block>>>>,
+	/// Chunks of the state being imported with `import_state_chunk`.
+	imported_state_chunks: RwLock<Vec<(Option<(Vec<u8>, OwnedChildInfo)>, StorageCollection)>>,
 	blockchain: Blockchain<Block>,
 	import_lock: RwLock<()>,
 }
@@ -575,6 +667,7 @@ impl<Block: BlockT> Backend<Block> where Block::Hash: Ord {
 	pub fn new() -> Self {
 		Backend {
 			states: RwLock::new(HashMap::new()),
+			imported_state_chunks: Default::default(),
 			blockchain: Blockchain::new(),
 			import_lock: Default::default(),
 		}
@@ -610,6 +703,9 @@ impl<Block: BlockT> backend::Backend<Block> for Backend<Block> where Block::Hash
 			pending_cache: Default::default(),
 			old_state,
 			new_state: None,
+			imported_state: None,
+			imported_state_chunks: Vec::new(),
+			snapshot_header: None,
 			aux: Default::default(),
 			finalized_blocks: Default::default(),
 			set_head: None,
@@ -625,13 +721,28 @@ impl<Block: BlockT> backend::Backend<Block> for Backend<Block> where Block::Hash
 		Ok(())
 	}
 
-	fn commit_operation(&self, operation: Self::BlockImportOperation) -> sp_blockchain::Result<()> {
+	fn commit_operation(&self, mut operation: Self::BlockImportOperation) -> sp_blockchain::Result<()> {
 		if !operation.finalized_blocks.is_empty() {
 			for (block, justification) in operation.finalized_blocks {
 				self.blockchain.finalize_header(block, justification)?;
 			}
 		}
 
+		if let Some(header) = operation.snapshot_header {
+			self.blockchain.insert_snapshot_header(header);
+		}
+
+		self.imported_state_chunks.write().append(&mut operation.imported_state_chunks);
+		if let Some(hash) = operation.imported_state {
+			let state = match operation.new_state.take() {
+				Some(state) => state,
+				// The state has been imported in chunks.
+				None => InMemoryBackend::default()
+					.update(std::mem::replace(&mut *self.imported_state_chunks.write(), Vec::new())),
+			};
+			self.states.write().insert(hash, state);
+		}
+
 		if let Some(pending_block) = operation.pending_block {
 			let old_state = &operation.old_state;
 			let (header, body, justification) = pending_block.block.into_inner();
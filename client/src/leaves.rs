@@ -76,22 +76,26 @@ impl<H, N> LeafSet<H, N> where
 		}
 	}
 
-	/// Read the leaf list from the DB, using given prefix for keys.
-	pub fn read_from_db(db: &dyn KeyValueDB, column: u32, prefix: &[u8]) -> Result<Self> {
-		let mut storage = BTreeMap::new();
+	/// Read the leaf list from the DB, stored under the given key.
+	pub fn read_from_db(db: &dyn KeyValueDB, column: u32, key: &[u8]) -> Result<Self> {
+		let encoded = db.get(column, key)
+			.map_err(|e| Error::Backend(format!("Error reading leaves: {}", e)))?;
+		Self::decode_from(encoded.as_ref().map(|v| &v[..]))
+	}
 
-		for (key, value) in db.iter_from_prefix(column, prefix) {
-			if !key.starts_with(prefix) { break }
-			let raw_hash = &mut &key[prefix.len()..];
-			let hash = match Decode::decode(raw_hash) {
-				Ok(hash) => hash,
-				Err(_) => return Err(Error::Backend("Error decoding hash".into())),
-			};
-			let number = match Decode::decode(&mut &value[..]) {
-				Ok(number) => number,
-				Err(_) => return Err(Error::Backend("Error decoding number".into())),
+	/// Restore the leaf list from its encoded form, as written by `prepare_transaction`.
+	///
+	/// `None` stands for a database without any stored leaves.
+	pub fn decode_from(encoded: Option<&[u8]>) -> Result<Self> {
+		let mut storage = BTreeMap::new();
+		if let Some(mut encoded) = encoded {
+			let leaves: Vec<(N, Vec<H>)> = match Decode::decode(&mut encoded) {
+				Ok(leaves) => leaves,
+				Err(_) => return Err(Error::Backend("Error decoding leaves".into())),
 			};
-			storage.entry(Reverse(number)).or_insert_with(Vec::new).push(hash);
+			for (number, hashes) in leaves {
+				storage.insert(Reverse(number), hashes);
+			}
 		}
 		Ok(Self {
 			storage,
@@ -201,18 +205,17 @@ impl<H, N> LeafSet<H, N> where
 	}
 
 	/// Write the leaf list to the database transaction.
-	pub fn prepare_transaction(&mut self, tx: &mut DBTransaction, column: u32, prefix: &[u8]) {
-		let mut buf = prefix.to_vec();
-		for LeafSetItem { hash, number } in self.pending_added.drain(..) {
-			hash.using_encoded(|s| buf.extend(s));
-			tx.put_vec(column, &buf[..], number.0.encode());
-			buf.truncate(prefix.len()); // reuse allocation.
-		}
-		for hash in self.pending_removed.drain(..) {
-			hash.using_encoded(|s| buf.extend(s));
-			tx.delete(column, &buf[..]);
-			buf.truncate(prefix.len()); // reuse allocation.
+	///
+	/// The whole list is stored as a single value so that reading it back
+	/// doesn't require iterating over the database.
+	pub fn prepare_transaction(&mut self, tx: &mut DBTransaction, column: u32, key: &[u8]) {
+		if self.pending_added.is_empty() && self.pending_removed.is_empty() {
+			return;
 		}
+		let leaves: Vec<_> = self.storage.iter().map(|(n, h)| (n.0.clone(), h.clone())).collect();
+		tx.put_vec(column, key, leaves.encode());
+		self.pending_added.clear();
+		self.pending_removed.clear();
 	}
 
 	#[cfg(test)]
@@ -28,7 +28,7 @@ use sp_core::storage::{well_known_keys, ChildInfo, OwnedChildInfo};
 use sp_core::offchain::storage::InMemOffchainStorage;
 use sp_state_machine::{
 	Backend as StateBackend, TrieBackend, InMemoryBackend, ChangesTrieTransaction,
-	StorageCollection, ChildStorageCollection,
+	StorageCollection, ChildStorageCollection, StorageProof,
 };
 use sp_runtime::{generic::BlockId, Justification, Storage};
 use sp_runtime::traits::{Block as BlockT, NumberFor, Zero, Header, HashFor};
@@ -332,6 +332,33 @@ impl<S, Block> BlockImportOperation<Block> for ImportOperation<Block, S>
 		Ok(storage_root)
 	}
 
+	fn import_state(&mut self, _block: Block::Hash, _storage: Storage) -> ClientResult<Block::Hash> {
+		Err(ClientError::NotAvailableOnLightClient)
+	}
+
+	fn set_snapshot_header(&mut self, _header: Block::Header) -> ClientResult<()> {
+		Err(ClientError::NotAvailableOnLightClient)
+	}
+
+	fn import_state_chunk(
+		&mut self,
+		_child: Option<(&[u8], ChildInfo)>,
+		_root: Block::Hash,
+		_proof: StorageProof,
+		_start: Option<&[u8]>,
+	) -> ClientResult<(Vec<(Vec<u8>, Vec<u8>)>, bool)> {
+		Err(ClientError::NotAvailableOnLightClient)
+	}
+
+	fn complete_state_import(
+		&mut self,
+		_block: Block::Hash,
+		_root: Block::Hash,
+		_proof: StorageProof,
+	) -> ClientResult<()> {
+		Err(ClientError::NotAvailableOnLightClient)
+	}
+
 	fn insert_aux<I>(&mut self, ops: I) -> ClientResult<()>
 		where I: IntoIterator<Item=(Vec<u8>, Option<Vec<u8>>)>
 	{
@@ -289,6 +289,14 @@ pub mod tests {
 			Ok(())
 		}
 
+		fn import_warp_target(
+			&self,
+			_header: Header,
+			_aux_ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+		) -> ClientResult<()> {
+			Err(ClientError::Backend("Test error".into()))
+		}
+
 		fn set_head(&self, _block: BlockId<Block>) -> ClientResult<()> {
 			Err(ClientError::Backend("Test error".into()))
 		}
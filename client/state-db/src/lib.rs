@@ -28,6 +28,11 @@
 //! # Pruning.
 //! See `RefWindow` for pruning algorithm details. `StateDb` prunes on each canonicalization until pruning
 //! constraints are satisfied.
+//!
+//! # Reference counting.
+//! If the backing database counts references to trie nodes, i.e. each insertion of a key adds a
+//! reference and each deletion removes one, `StateDb` can be created with `ref_counting` enabled.
+//! The pruning window then doesn't track re-inserted nodes and keeps no deleted keys in memory.
 
 mod noncanonical;
 mod pruning;
@@ -191,6 +196,14 @@ fn to_meta_key<S: Codec>(suffix: &[u8], data: &S) -> Vec<u8> {
 	buffer
 }
 
+/// Returns whether the database was created with an archive pruning mode.
+///
+/// `None` if no block has been written to the database yet.
+pub fn is_archive<D: MetaDb>(db: &D) -> Result<Option<bool>, Error<D::Error>> {
+	let db_mode = db.get_meta(&to_meta_key(PRUNING_MODE, &())).map_err(Error::Db)?;
+	Ok(db_mode.map(|mode| mode != PRUNING_MODE_CONSTRAINED))
+}
+
 struct StateDbSync<BlockHash: Hash, Key: Hash> {
 	mode: PruningMode,
 	non_canonical: NonCanonicalOverlay<BlockHash, Key>,
@@ -201,6 +214,7 @@ struct StateDbSync<BlockHash: Hash, Key: Hash> {
 impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<BlockHash, Key> {
 	fn new<D: MetaDb>(
 		mode: PruningMode,
+		ref_counting: bool,
 		db: &D,
 	) -> Result<StateDbSync<BlockHash, Key>, Error<D::Error>> {
 		trace!(target: "state-db", "StateDb settings: {:?}", mode);
@@ -214,7 +228,7 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 				max_mem: Some(_),
 				..
 			}) => unimplemented!(),
-			PruningMode::Constrained(_) => Some(RefWindow::new(db)?),
+			PruningMode::Constrained(_) => Some(RefWindow::new(db, ref_counting)?),
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
 		};
 
@@ -271,10 +285,11 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 		}
 	}
 
-	fn canonicalize_block<E: fmt::Debug>(
+	fn canonicalize_block<D: MetaDb>(
 		&mut self,
 		hash: &BlockHash,
-	) -> Result<CommitSet<Key>, Error<E>> {
+		db: &D,
+	) -> Result<CommitSet<Key>, Error<D::Error>> {
 		let mut commit = CommitSet::default();
 		if self.mode == PruningMode::ArchiveAll {
 			return Ok(commit)
@@ -290,7 +305,30 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 		if let Some(ref mut pruning) = self.pruning {
 			pruning.note_canonical(&hash, &mut commit);
 		}
-		self.prune(&mut commit);
+		self.prune(&mut commit, db)?;
+		Ok(commit)
+	}
+
+	fn import_canonical<D: MetaDb>(
+		&mut self,
+		hash: &BlockHash,
+		number: u64,
+		changeset: ChangeSet<Key>,
+		db: &D,
+	) -> Result<CommitSet<Key>, Error<D::Error>> {
+		let mut commit = CommitSet {
+			data: changeset,
+			meta: Default::default(),
+		};
+		if self.mode == PruningMode::ArchiveAll {
+			commit.data.deleted.clear();
+			return Ok(commit)
+		}
+		self.non_canonical.import_canonical(hash, number, &mut commit)?;
+		if let Some(ref mut pruning) = self.pruning {
+			pruning.note_canonical(&hash, &mut commit);
+		}
+		self.prune(&mut commit, db)?;
 		Ok(commit)
 	}
 
@@ -316,7 +354,7 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 		}
 	}
 
-	fn prune(&mut self, commit: &mut CommitSet<Key>) {
+	fn prune<D: MetaDb>(&mut self, commit: &mut CommitSet<Key>, db: &D) -> Result<(), Error<D::Error>> {
 		if let (&mut Some(ref mut pruning), &PruningMode::Constrained(ref constraints)) = (&mut self.pruning, &self.mode) {
 			loop {
 				if pruning.window_size() <= constraints.max_blocks.unwrap_or(0) as u64 {
@@ -331,9 +369,10 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 				if pruning.next_hash().map_or(false, |h| pinned.contains_key(&h)) {
 					break;
 				}
-				pruning.prune_one(commit);
+				pruning.prune_one(commit, db)?;
 			}
 		}
+		Ok(())
 	}
 
 	/// Revert all non-canonical blocks with the best block number.
@@ -436,12 +475,15 @@ pub struct StateDb<BlockHash: Hash, Key: Hash> {
 
 impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash, Key> {
 	/// Creates a new instance. Does not expect any metadata in the database.
+	///
+	/// `ref_counting` must only be enabled if the database counts references to state nodes.
 	pub fn new<D: MetaDb>(
 		mode: PruningMode,
+		ref_counting: bool,
 		db: &D,
 	) -> Result<StateDb<BlockHash, Key>, Error<D::Error>> {
 		Ok(StateDb {
-			db: RwLock::new(StateDbSync::new(mode, db)?)
+			db: RwLock::new(StateDbSync::new(mode, ref_counting, db)?)
 		})
 	}
 
@@ -457,11 +499,26 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 	}
 
 	/// Finalize a previously inserted block.
-	pub fn canonicalize_block<E: fmt::Debug>(
+	///
+	/// `db` is used to read the pruning journal of blocks that leave the pruning window.
+	pub fn canonicalize_block<D: MetaDb>(
 		&self,
 		hash: &BlockHash,
-	) -> Result<CommitSet<Key>, Error<E>> {
-		self.db.write().canonicalize_block(hash)
+		db: &D,
+	) -> Result<CommitSet<Key>, Error<D::Error>> {
+		self.db.write().canonicalize_block(hash, db)
+	}
+
+	/// Import the complete state of a block as canonical, e.g. after downloading it from the
+	/// network. No other blocks may be pending canonicalization.
+	pub fn import_canonical<D: MetaDb>(
+		&self,
+		hash: &BlockHash,
+		number: u64,
+		changeset: ChangeSet<Key>,
+		db: &D,
+	) -> Result<CommitSet<Key>, Error<D::Error>> {
+		self.db.write().import_canonical(hash, number, changeset, db)
 	}
 
 	/// Prevents pruning of specified block and its descendants.
@@ -518,14 +575,24 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 mod tests {
 	use std::io;
 	use sp_core::H256;
-	use crate::{StateDb, PruningMode, Constraints};
+	use crate::{StateDb, PruningMode, Constraints, CommitSet};
 	use crate::test::{make_db, make_changeset, TestDb};
 
 	fn make_test_db(settings: PruningMode) -> (TestDb, StateDb<H256, H256>) {
+		make_test_db_with(settings, false)
+	}
+
+	fn make_test_db_with(settings: PruningMode, ref_counting: bool) -> (TestDb, StateDb<H256, H256>) {
 		let mut db = make_db(&[91, 921, 922, 93, 94]);
-		let state_db = StateDb::new(settings, &db).unwrap();
+		let state_db = StateDb::new(settings, ref_counting, &db).unwrap();
+		let commit = |db: &mut TestDb, commit: &CommitSet<H256>| if ref_counting {
+			db.commit_ref_counted(commit)
+		} else {
+			db.commit(commit)
+		};
 
-		db.commit(
+		commit(
+			&mut db,
 			&state_db
 				.insert_block::<io::Error>(
 					&H256::from_low_u64_be(1),
@@ -535,7 +602,8 @@ mod tests {
 				)
 				.unwrap(),
 		);
-		db.commit(
+		commit(
+			&mut db,
 			&state_db
 				.insert_block::<io::Error>(
 					&H256::from_low_u64_be(21),
@@ -545,7 +613,8 @@ mod tests {
 				)
 				.unwrap(),
 		);
-		db.commit(
+		commit(
+			&mut db,
 			&state_db
 				.insert_block::<io::Error>(
 					&H256::from_low_u64_be(22),
@@ -555,7 +624,8 @@ mod tests {
 				)
 				.unwrap(),
 		);
-		db.commit(
+		commit(
+			&mut db,
 			&state_db
 				.insert_block::<io::Error>(
 					&H256::from_low_u64_be(3),
@@ -566,9 +636,11 @@ mod tests {
 				.unwrap(),
 		);
 		state_db.apply_pending();
-		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(1)).unwrap());
+		let canonicalized = state_db.canonicalize_block(&H256::from_low_u64_be(1), &db).unwrap();
+		commit(&mut db, &canonicalized);
 		state_db.apply_pending();
-		db.commit(
+		commit(
+			&mut db,
 			&state_db
 				.insert_block::<io::Error>(
 					&H256::from_low_u64_be(4),
@@ -579,9 +651,11 @@ mod tests {
 				.unwrap(),
 		);
 		state_db.apply_pending();
-		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(21)).unwrap());
+		let canonicalized = state_db.canonicalize_block(&H256::from_low_u64_be(21), &db).unwrap();
+		commit(&mut db, &canonicalized);
 		state_db.apply_pending();
-		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(3)).unwrap());
+		let canonicalized = state_db.canonicalize_block(&H256::from_low_u64_be(3), &db).unwrap();
+		commit(&mut db, &canonicalized);
 		state_db.apply_pending();
 
 		(db, state_db)
@@ -635,10 +709,83 @@ mod tests {
 		assert!(db.data_eq(&make_db(&[1, 21, 3, 921, 922, 93, 94])));
 	}
 
+	#[test]
+	fn prune_window_0_ref_counted() {
+		let (db, _) = make_test_db_with(PruningMode::Constrained(Constraints {
+			max_blocks: Some(0),
+			max_mem: None,
+		}), true);
+		assert!(db.data_eq(&make_db(&[21, 3, 922, 94])));
+	}
+
+	#[test]
+	fn prune_window_1_ref_counted() {
+		let (db, sdb) = make_test_db_with(PruningMode::Constrained(Constraints {
+			max_blocks: Some(1),
+			max_mem: None,
+		}), true);
+		assert!(sdb.is_pruned(&H256::from_low_u64_be(1), 1));
+		assert!(!sdb.is_pruned(&H256::from_low_u64_be(3), 3));
+		assert!(db.data_eq(&make_db(&[21, 3, 922, 93, 94])));
+	}
+
+	#[test]
+	fn import_canonical_works() {
+		let mut db = make_db(&[]);
+		let state_db = StateDb::new(PruningMode::keep_blocks(2), false, &db).unwrap();
+		db.commit(
+			&state_db
+				.insert_block::<io::Error>(
+					&H256::from_low_u64_be(0),
+					0,
+					&H256::from_low_u64_be(0),
+					make_changeset(&[], &[]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		db.commit(&state_db.canonicalize_block(&H256::from_low_u64_be(0), &db).unwrap());
+		state_db.apply_pending();
+
+		db.commit(
+			&state_db
+				.import_canonical(&H256::from_low_u64_be(10), 10, make_changeset(&[10, 11], &[]), &db)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		assert_eq!(state_db.best_canonical(), Some(10));
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(10), 10));
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(5), 5));
+		assert!(db.data_eq(&make_db(&[10, 11])));
+
+		// new blocks are built on top of the imported state.
+		db.commit(
+			&state_db
+				.insert_block::<io::Error>(
+					&H256::from_low_u64_be(11),
+					11,
+					&H256::from_low_u64_be(10),
+					make_changeset(&[12], &[]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(11), 11));
+	}
+
+	#[test]
+	fn import_canonical_requires_empty_overlay() {
+		let (db, state_db) = make_test_db(PruningMode::keep_blocks(2));
+		assert!(
+			state_db.import_canonical(&H256::from_low_u64_be(10), 10, make_changeset(&[10], &[]), &db)
+				.is_err()
+		);
+	}
+
 	#[test]
 	fn detects_incompatible_mode() {
 		let mut db = make_db(&[]);
-		let state_db = StateDb::new(PruningMode::ArchiveAll, &db).unwrap();
+		let state_db = StateDb::new(PruningMode::ArchiveAll, false, &db).unwrap();
 		db.commit(
 			&state_db
 			.insert_block::<io::Error>(
@@ -650,7 +797,7 @@ mod tests {
 			.unwrap(),
 		);
 		let new_mode = PruningMode::Constrained(Constraints { max_blocks: Some(2), max_mem: None });
-		let state_db: Result<StateDb<H256, H256>, _> = StateDb::new(new_mode, &db);
+		let state_db: Result<StateDb<H256, H256>, _> = StateDb::new(new_mode, false, &db);
 		assert!(state_db.is_err());
 	}
 }
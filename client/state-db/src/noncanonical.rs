@@ -37,6 +37,7 @@ pub struct NonCanonicalOverlay<BlockHash: Hash, Key: Hash> {
 	parents: HashMap<BlockHash, BlockHash>,
 	pending_canonicalizations: Vec<BlockHash>,
 	pending_insertions: Vec<BlockHash>,
+	pending_import: Option<(BlockHash, u64)>,
 	values: HashMap<Key, (u32, DBValue)>, //ref counted
 	//would be deleted but kept around because block is pinned, ref counted.
 	pinned: HashMap<BlockHash, u32>,
@@ -181,6 +182,7 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 			parents,
 			pending_canonicalizations: Default::default(),
 			pending_insertions: Default::default(),
+			pending_import: None,
 			pinned: Default::default(),
 			pinned_insertions: Default::default(),
 			values: values,
@@ -271,6 +273,9 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	}
 
 	pub fn last_canonicalized_block_number(&self) -> Option<u64> {
+		if let Some((_, n)) = self.pending_import.as_ref() {
+			return Some(*n);
+		}
 		match self.last_canonicalized.as_ref().map(|&(_, n)| n) {
 			Some(n) => Some(n + self.pending_canonicalizations.len() as u64),
 			None if !self.pending_canonicalizations.is_empty() => Some(self.pending_canonicalizations.len() as u64),
@@ -333,6 +338,28 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 		Ok(())
 	}
 
+	/// Mark a block whose state was written directly to the database, bypassing the overlay,
+	/// as the last canonicalized block. The overlay must be empty and the block must be above
+	/// the current last canonicalized block.
+	pub fn import_canonical<E: fmt::Debug>(
+		&mut self,
+		hash: &BlockHash,
+		number: u64,
+		commit: &mut CommitSet<Key>,
+	) -> Result<(), Error<E>> {
+		trace!(target: "state-db", "Importing canonical {:?} (#{})", hash, number);
+		if !self.levels.is_empty() || !self.pending_canonicalizations.is_empty() {
+			return Err(Error::InvalidBlock);
+		}
+		if self.last_canonicalized.as_ref().map_or(false, |&(_, n)| number <= n) {
+			return Err(Error::InvalidBlockNumber);
+		}
+		let canonicalized = (hash.clone(), number);
+		commit.meta.inserted.push((to_meta_key(LAST_CANONICAL, &()), canonicalized.encode()));
+		self.pending_import = Some(canonicalized);
+		Ok(())
+	}
+
 	fn apply_canonicalizations(&mut self) {
 		let last = self.pending_canonicalizations.last().cloned();
 		let count = self.pending_canonicalizations.len() as u64;
@@ -369,6 +396,9 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 			let last_canonicalized = (hash, self.last_canonicalized.as_ref().map(|(_, n)| n + count).unwrap_or(count - 1));
 			self.last_canonicalized = Some(last_canonicalized);
 		}
+		if let Some(imported) = self.pending_import.take() {
+			self.last_canonicalized = Some(imported);
+		}
 	}
 
 	/// Get a value from the node overlay. This searches in every existing changeset.
@@ -426,6 +456,7 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	/// Revert all pending changes
 	pub fn revert_pending(&mut self) {
 		self.pending_canonicalizations.clear();
+		self.pending_import = None;
 		self.revert_insertions();
 	}
 
@@ -21,6 +21,11 @@
 //! If a node is re-inserted into the window it gets removed from
 //! the death list.
 //! The changes are journaled in the DB.
+//!
+//! If the backing database keeps reference counts for trie nodes, every insertion
+//! and deletion is simply passed to the database and re-insertions need no special
+//! handling. In that case the list of deleted nodes is only kept in the journal and
+//! read back when the block is pruned, so the window holds just the block hashes.
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use codec::{Encode, Decode};
@@ -39,6 +44,9 @@ pub struct RefWindow<BlockHash: Hash, Key: Hash> {
 	death_index: HashMap<Key, u64>,
 	/// Block number that corresponds to the front of `death_rows`.
 	pending_number: u64,
+	/// The backing database counts references. `death_index` is unused and only
+	/// pending `death_rows` hold their deleted keys.
+	ref_counted: bool,
 	/// Number of call of `note_canonical` after
 	/// last call `apply_pending` or `revert_pending`
 	pending_canonicalizations: usize,
@@ -66,7 +74,7 @@ fn to_journal_key(block: u64) -> Vec<u8> {
 }
 
 impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
-	pub fn new<D: MetaDb>(db: &D) -> Result<RefWindow<BlockHash, Key>, Error<D::Error>> {
+	pub fn new<D: MetaDb>(db: &D, ref_counted: bool) -> Result<RefWindow<BlockHash, Key>, Error<D::Error>> {
 		let last_pruned = db.get_meta(&to_meta_key(LAST_PRUNED, &()))
 			.map_err(|e| Error::Db(e))?;
 		let pending_number: u64 = match last_pruned {
@@ -78,6 +86,7 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 			death_rows: Default::default(),
 			death_index: Default::default(),
 			pending_number: pending_number,
+			ref_counted,
 			pending_canonicalizations: 0,
 			pending_prunings: 0,
 		};
@@ -89,7 +98,8 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 				Some(record) => {
 					let record: JournalRecord<BlockHash, Key> = Decode::decode(&mut record.as_slice())?;
 					trace!(target: "state-db", "Pruning journal entry {} ({} inserted, {} deleted)", block, record.inserted.len(), record.deleted.len());
-					pruning.import(&record.hash, journal_key, record.inserted.into_iter(), record.deleted);
+					let deleted = if ref_counted { Vec::new() } else { record.deleted };
+					pruning.import(&record.hash, journal_key, record.inserted.into_iter(), deleted);
 				},
 				None => break,
 			}
@@ -99,17 +109,19 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 	}
 
 	fn import<I: IntoIterator<Item=Key>>(&mut self, hash: &BlockHash, journal_key: Vec<u8>, inserted: I, deleted: Vec<Key>) {
-		// remove all re-inserted keys from death rows
-		for k in inserted {
-			if let Some(block) = self.death_index.remove(&k) {
-				self.death_rows[(block - self.pending_number) as usize].deleted.remove(&k);
+		if !self.ref_counted {
+			// remove all re-inserted keys from death rows
+			for k in inserted {
+				if let Some(block) = self.death_index.remove(&k) {
+					self.death_rows[(block - self.pending_number) as usize].deleted.remove(&k);
+				}
 			}
-		}
 
-		// add new keys
-		let imported_block = self.pending_number + self.death_rows.len() as u64;
-		for k in deleted.iter() {
-			self.death_index.insert(k.clone(), imported_block);
+			// add new keys
+			let imported_block = self.pending_number + self.death_rows.len() as u64;
+			for k in deleted.iter() {
+				self.death_index.insert(k.clone(), imported_block);
+			}
 		}
 		self.death_rows.push_back(
 			DeathRow {
@@ -141,23 +153,44 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 	}
 
 	/// Prune next block. Expects at least one block in the window. Adds changes to `commit`.
-	pub fn prune_one(&mut self, commit: &mut CommitSet<Key>) {
+	pub fn prune_one<D: MetaDb>(&mut self, commit: &mut CommitSet<Key>, db: &D) -> Result<(), Error<D::Error>> {
+		let first_pending = self.death_rows.len() - self.pending_canonicalizations;
 		if let Some(pruned) = self.death_rows.get(self.pending_prunings) {
-			trace!(target: "state-db", "Pruning {:?} ({} deleted)", pruned.hash, pruned.deleted.len());
 			let index = self.pending_number + self.pending_prunings as u64;
-			commit.data.deleted.extend(pruned.deleted.iter().cloned());
+			if self.ref_counted && self.pending_prunings < first_pending {
+				// deleted keys of applied blocks are only stored in the journal.
+				let record = db.get_meta(&pruned.journal_key).map_err(|e| Error::Db(e))?;
+				let deleted = match record {
+					Some(record) => JournalRecord::<BlockHash, Key>::decode(&mut record.as_slice())?.deleted,
+					None => {
+						warn!(target: "state-db", "Missing pruning journal entry {}", index);
+						Vec::new()
+					},
+				};
+				trace!(target: "state-db", "Pruning {:?} ({} deleted)", pruned.hash, deleted.len());
+				commit.data.deleted.extend(deleted);
+			} else {
+				trace!(target: "state-db", "Pruning {:?} ({} deleted)", pruned.hash, pruned.deleted.len());
+				commit.data.deleted.extend(pruned.deleted.iter().cloned());
+			}
 			commit.meta.inserted.push((to_meta_key(LAST_PRUNED, &()), index.encode()));
 			commit.meta.deleted.push(pruned.journal_key.clone());
 			self.pending_prunings += 1;
 		} else {
 			warn!(target: "state-db", "Trying to prune when there's nothing to prune");
 		}
+		Ok(())
 	}
 
 	/// Add a change set to the window. Creates a journal record and pushes it to `commit`
 	pub fn note_canonical(&mut self, hash: &BlockHash, commit: &mut CommitSet<Key>) {
 		trace!(target: "state-db", "Adding to pruning window: {:?} ({} inserted, {} deleted)", hash, commit.data.inserted.len(), commit.data.deleted.len());
-		let inserted = commit.data.inserted.iter().map(|(k, _)| k.clone()).collect();
+		let inserted = if self.ref_counted {
+			// re-insertions are accounted for by the database.
+			Vec::new()
+		} else {
+			commit.data.inserted.iter().map(|(k, _)| k.clone()).collect()
+		};
 		let deleted = ::std::mem::replace(&mut commit.data.deleted, Vec::new());
 		let journal_record = JournalRecord {
 			hash: hash.clone(),
@@ -173,6 +206,13 @@ impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 
 	/// Apply all pending changes
 	pub fn apply_pending(&mut self) {
+		if self.ref_counted {
+			// the journal records are in the database now.
+			let canonicalized = self.death_rows.len() - self.pending_canonicalizations;
+			for row in self.death_rows.iter_mut().skip(canonicalized) {
+				row.deleted = Default::default();
+			}
+		}
 		self.pending_canonicalizations = 0;
 		for _ in 0 .. self.pending_prunings {
 			let pruned = self.death_rows.pop_front().expect("pending_prunings is always < death_rows.len()");
@@ -207,7 +247,7 @@ mod tests {
 	use crate::test::{make_db, make_commit, TestDb};
 
 	fn check_journal(pruning: &RefWindow<H256, H256>, db: &TestDb) {
-		let restored: RefWindow<H256, H256> = RefWindow::new(db).unwrap();
+		let restored: RefWindow<H256, H256> = RefWindow::new(db, false).unwrap();
 		assert_eq!(pruning.pending_number, restored.pending_number);
 		assert_eq!(pruning.death_rows, restored.death_rows);
 		assert_eq!(pruning.death_index, restored.death_index);
@@ -216,7 +256,7 @@ mod tests {
 	#[test]
 	fn created_from_empty_db() {
 		let db = make_db(&[]);
-		let pruning: RefWindow<H256, H256> = RefWindow::new(&db).unwrap();
+		let pruning: RefWindow<H256, H256> = RefWindow::new(&db, false).unwrap();
 		assert_eq!(pruning.pending_number, 0);
 		assert!(pruning.death_rows.is_empty());
 		assert!(pruning.death_index.is_empty());
@@ -225,9 +265,9 @@ mod tests {
 	#[test]
 	fn prune_empty() {
 		let db = make_db(&[]);
-		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db).unwrap();
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db, false).unwrap();
 		let mut commit = CommitSet::default();
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		assert_eq!(pruning.pending_number, 0);
 		assert!(pruning.death_rows.is_empty());
 		assert!(pruning.death_index.is_empty());
@@ -238,7 +278,7 @@ mod tests {
 	#[test]
 	fn prune_one() {
 		let mut db = make_db(&[1, 2, 3]);
-		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db).unwrap();
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db, false).unwrap();
 		let mut commit = make_commit(&[4, 5], &[1, 3]);
 		let h = H256::random();
 		pruning.note_canonical(&h, &mut commit);
@@ -253,7 +293,7 @@ mod tests {
 		check_journal(&pruning, &db);
 
 		let mut commit = CommitSet::default();
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		assert!(!pruning.have_block(&h));
 		db.commit(&commit);
 		pruning.apply_pending();
@@ -267,7 +307,7 @@ mod tests {
 	#[test]
 	fn prune_two() {
 		let mut db = make_db(&[1, 2, 3]);
-		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db).unwrap();
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db, false).unwrap();
 		let mut commit = make_commit(&[4], &[1]);
 		pruning.note_canonical(&H256::random(), &mut commit);
 		db.commit(&commit);
@@ -280,12 +320,12 @@ mod tests {
 		check_journal(&pruning, &db);
 
 		let mut commit = CommitSet::default();
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		db.commit(&commit);
 		pruning.apply_pending();
 		assert!(db.data_eq(&make_db(&[2, 3, 4, 5])));
 		let mut commit = CommitSet::default();
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		db.commit(&commit);
 		pruning.apply_pending();
 		assert!(db.data_eq(&make_db(&[3, 4, 5])));
@@ -295,7 +335,7 @@ mod tests {
 	#[test]
 	fn prune_two_pending() {
 		let mut db = make_db(&[1, 2, 3]);
-		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db).unwrap();
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db, false).unwrap();
 		let mut commit = make_commit(&[4], &[1]);
 		pruning.note_canonical(&H256::random(), &mut commit);
 		db.commit(&commit);
@@ -304,11 +344,11 @@ mod tests {
 		db.commit(&commit);
 		assert!(db.data_eq(&make_db(&[1, 2, 3, 4, 5])));
 		let mut commit = CommitSet::default();
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		db.commit(&commit);
 		assert!(db.data_eq(&make_db(&[2, 3, 4, 5])));
 		let mut commit = CommitSet::default();
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		db.commit(&commit);
 		pruning.apply_pending();
 		assert!(db.data_eq(&make_db(&[3, 4, 5])));
@@ -318,7 +358,7 @@ mod tests {
 	#[test]
 	fn reinserted_survives() {
 		let mut db = make_db(&[1, 2, 3]);
-		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db).unwrap();
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db, false).unwrap();
 		let mut commit = make_commit(&[], &[2]);
 		pruning.note_canonical(&H256::random(), &mut commit);
 		db.commit(&commit);
@@ -334,14 +374,14 @@ mod tests {
 		check_journal(&pruning, &db);
 
 		let mut commit = CommitSet::default();
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		db.commit(&commit);
 		assert!(db.data_eq(&make_db(&[1, 2, 3])));
 		let mut commit = CommitSet::default();
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		db.commit(&commit);
 		assert!(db.data_eq(&make_db(&[1, 2, 3])));
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		db.commit(&commit);
 		assert!(db.data_eq(&make_db(&[1, 3])));
 		pruning.apply_pending();
@@ -351,7 +391,7 @@ mod tests {
 	#[test]
 	fn reinserted_survive_pending() {
 		let mut db = make_db(&[1, 2, 3]);
-		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db).unwrap();
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db, false).unwrap();
 		let mut commit = make_commit(&[], &[2]);
 		pruning.note_canonical(&H256::random(), &mut commit);
 		db.commit(&commit);
@@ -364,17 +404,89 @@ mod tests {
 		assert!(db.data_eq(&make_db(&[1, 2, 3])));
 
 		let mut commit = CommitSet::default();
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		db.commit(&commit);
 		assert!(db.data_eq(&make_db(&[1, 2, 3])));
 		let mut commit = CommitSet::default();
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		db.commit(&commit);
 		assert!(db.data_eq(&make_db(&[1, 2, 3])));
-		pruning.prune_one(&mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
 		db.commit(&commit);
 		assert!(db.data_eq(&make_db(&[1, 3])));
 		pruning.apply_pending();
 		assert_eq!(pruning.pending_number, 3);
 	}
+
+	#[test]
+	fn ref_counted_prune_reads_journal() {
+		let mut db = make_db(&[1, 2, 3]);
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db, true).unwrap();
+		let mut commit = make_commit(&[4, 5], &[1, 3]);
+		let h = H256::random();
+		pruning.note_canonical(&h, &mut commit);
+		db.commit_ref_counted(&commit);
+		pruning.apply_pending();
+		assert!(pruning.have_block(&h));
+		assert!(pruning.death_index.is_empty());
+		assert!(pruning.death_rows[0].deleted.is_empty());
+		assert!(db.data_eq(&make_db(&[1, 2, 3, 4, 5])));
+
+		let restored: RefWindow<H256, H256> = RefWindow::new(&db, true).unwrap();
+		assert_eq!(pruning.death_rows, restored.death_rows);
+
+		let mut commit = CommitSet::default();
+		pruning.prune_one(&mut commit, &db).unwrap();
+		db.commit_ref_counted(&commit);
+		pruning.apply_pending();
+		assert!(!pruning.have_block(&h));
+		assert!(db.data_eq(&make_db(&[2, 4, 5])));
+		assert_eq!(pruning.pending_number, 1);
+	}
+
+	#[test]
+	fn ref_counted_prune_pending() {
+		let mut db = make_db(&[1, 2, 3]);
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db, true).unwrap();
+		let mut commit = make_commit(&[4], &[1]);
+		pruning.note_canonical(&H256::random(), &mut commit);
+		pruning.prune_one(&mut commit, &db).unwrap();
+		db.commit_ref_counted(&commit);
+		pruning.apply_pending();
+		assert!(db.data_eq(&make_db(&[2, 3, 4])));
+		assert!(pruning.death_rows.is_empty());
+		assert_eq!(pruning.pending_number, 1);
+	}
+
+	#[test]
+	fn ref_counted_reinserted_survives() {
+		let mut db = make_db(&[1, 2, 3]);
+		let mut pruning: RefWindow<H256, H256> = RefWindow::new(&db, true).unwrap();
+		let mut commit = make_commit(&[], &[2]);
+		pruning.note_canonical(&H256::random(), &mut commit);
+		db.commit_ref_counted(&commit);
+		let mut commit = make_commit(&[2], &[]);
+		pruning.note_canonical(&H256::random(), &mut commit);
+		db.commit_ref_counted(&commit);
+		let mut commit = make_commit(&[], &[2]);
+		pruning.note_canonical(&H256::random(), &mut commit);
+		db.commit_ref_counted(&commit);
+		pruning.apply_pending();
+		assert!(db.data_eq(&make_db(&[1, 2, 3])));
+
+		let mut commit = CommitSet::default();
+		pruning.prune_one(&mut commit, &db).unwrap();
+		db.commit_ref_counted(&commit);
+		assert!(db.data_eq(&make_db(&[1, 2, 3])));
+		let mut commit = CommitSet::default();
+		pruning.prune_one(&mut commit, &db).unwrap();
+		db.commit_ref_counted(&commit);
+		assert!(db.data_eq(&make_db(&[1, 2, 3])));
+		let mut commit = CommitSet::default();
+		pruning.prune_one(&mut commit, &db).unwrap();
+		db.commit_ref_counted(&commit);
+		assert!(db.data_eq(&make_db(&[1, 3])));
+		pruning.apply_pending();
+		assert_eq!(pruning.pending_number, 3);
+	}
 }
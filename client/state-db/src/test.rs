@@ -24,6 +24,8 @@ use crate::{DBValue, ChangeSet, CommitSet, MetaDb, NodeDb};
 pub struct TestDb {
 	pub data: HashMap<H256, DBValue>,
 	pub meta: HashMap<Vec<u8>, DBValue>,
+	/// Reference counts of nodes, only maintained by `commit_ref_counted`.
+	pub refs: HashMap<H256, u32>,
 }
 
 impl MetaDb for TestDb {
@@ -56,6 +58,27 @@ impl TestDb {
 		}
 	}
 
+	/// Commit to a database that keeps reference counts for nodes: every insertion
+	/// adds a reference and a node is only removed once all its references are deleted.
+	pub fn commit_ref_counted(&mut self, commit: &CommitSet<H256>) {
+		for (k, v) in commit.data.inserted.iter() {
+			*self.refs.entry(*k).or_insert(0) += 1;
+			self.data.insert(*k, v.clone());
+		}
+		for k in commit.data.deleted.iter() {
+			let refs = self.refs.entry(*k).or_insert(1);
+			*refs -= 1;
+			if *refs == 0 {
+				self.refs.remove(k);
+				self.data.remove(k);
+			}
+		}
+		self.meta.extend(commit.meta.inserted.iter().cloned());
+		for k in commit.meta.deleted.iter() {
+			self.meta.remove(k);
+		}
+	}
+
 	pub fn data_eq(&self, other: &TestDb) -> bool {
 		self.data == other.data
 	}
@@ -89,6 +112,7 @@ pub fn make_db(inserted: &[u64]) -> TestDb {
 			})
 			.collect(),
 		meta: Default::default(),
+		refs: inserted.iter().map(|v| (H256::from_low_u64_be(*v), 1)).collect(),
 	}
 }
 
@@ -19,7 +19,8 @@
 //! For a more full-featured pool, have a look at the `pool` module.
 
 use std::{
-	collections::HashSet,
+	cmp,
+	collections::{HashMap, HashSet},
 	fmt,
 	hash,
 	sync::Arc,
@@ -226,23 +227,30 @@ pub struct BasePool<Hash: hash::Hash + Eq, Ex> {
 	/// transactions to future in case they were just stuck in verification.
 	recently_pruned: [HashSet<Tag>; RECENTLY_PRUNED_TAGS],
 	recently_pruned_index: usize,
+	/// Number of transactions evicted because the queues were full.
+	evicted: usize,
+	/// Number of transactions evicted because their sender had too many transactions in the pool.
+	sender_evicted: usize,
 }
 
 impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> Default for BasePool<Hash, Ex> {
 	fn default() -> Self {
-		Self::new(false)
+		Self::new(false, DEFAULT_SENDER_TAG_PREFIX)
 	}
 }
 
 impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash, Ex> {
-	/// Create new pool given reject_future_transactions flag.
-	pub fn new(reject_future_transactions: bool) -> Self {
+	/// Create new pool given reject_future_transactions flag and the number of bytes of the
+	/// provided tag identifying the sender of a transaction, see `SenderLimit`.
+	pub fn new(reject_future_transactions: bool, sender_tag_prefix: usize) -> Self {
 		BasePool {
 			reject_future_transactions,
-			future: Default::default(),
-			ready: Default::default(),
+			future: FutureTransactions::new(sender_tag_prefix),
+			ready: ReadyTransactions::new(sender_tag_prefix),
 			recently_pruned: Default::default(),
 			recently_pruned_index: 0,
+			evicted: 0,
+			sender_evicted: 0,
 		}
 	}
 
@@ -396,30 +404,39 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		self.ready.by_hash(hash)
 	}
 
-	/// Makes sure that the transactions in the queues stay within provided limits.
+	/// Makes sure that the transactions in the queues and their senders stay within provided limits.
 	///
 	/// Removes and returns worst transactions from the queues and all transactions that depend on them.
+	/// The sender limit is enforced first for the senders of the `imported` transactions, see
+	/// `enforce_sender_limit`.
 	/// Technically the worst transaction should be evaluated by computing the entire pending set.
-	/// We use a simplified approach to remove the transaction that occupies the pool for the longest time.
-	pub fn enforce_limits(&mut self, ready: &Limit, future: &Limit) -> Vec<Arc<Transaction<Hash, Ex>>> {
-		let mut removed = vec![];
+	/// We use a simplified approach and remove the transaction with the lowest priority, preferring
+	/// the ones that pay less priority per byte and then the ones occupying the pool for the longest time.
+	pub fn enforce_limits(
+		&mut self,
+		ready: &Limit,
+		future: &Limit,
+		sender: &SenderLimit,
+		imported: &[Hash],
+	) -> Vec<Arc<Transaction<Hash, Ex>>> {
+		let mut removed = self.enforce_sender_limit(sender, imported);
+		self.sender_evicted += removed.len();
+		let sender_removed = removed.len();
 
 		while ready.is_exceeded(self.ready.len(), self.ready.bytes()) {
 			// find the worst transaction
 			let minimal = self.ready
 				.fold(|minimal, current| {
 					let transaction = &current.transaction;
+					let key = (eviction_key(&transaction.transaction), transaction.insertion_id);
 					match minimal {
-						None => Some(transaction.clone()),
-						Some(ref tx) if tx.insertion_id > transaction.insertion_id => {
-							Some(transaction.clone())
-						},
-						other => other,
+						Some((minimal_key, tx)) if minimal_key <= key => Some((minimal_key, tx)),
+						_ => Some((key, transaction.transaction.clone())),
 					}
 				});
 
-			if let Some(minimal) = minimal {
-				removed.append(&mut self.remove_subtree(&[minimal.transaction.hash.clone()]))
+			if let Some((_, minimal)) = minimal {
+				removed.append(&mut self.remove_subtree(&[minimal.hash.clone()]))
 			} else {
 				break;
 			}
@@ -429,25 +446,70 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 			// find the worst transaction
 			let minimal = self.future
 				.fold(|minimal, current| {
+					let key = (eviction_key(&current.transaction), current.imported_at);
 					match minimal {
-						None => Some(current.clone()),
-						Some(ref tx) if tx.imported_at > current.imported_at => {
-							Some(current.clone())
-						},
-						other => other,
+						Some((minimal_key, tx)) if minimal_key <= key => Some((minimal_key, tx)),
+						_ => Some((key, current.transaction.clone())),
 					}
 				});
 
-			if let Some(minimal) = minimal {
-				removed.append(&mut self.remove_subtree(&[minimal.transaction.hash.clone()]))
+			if let Some((_, minimal)) = minimal {
+				removed.append(&mut self.remove_subtree(&[minimal.hash.clone()]))
 			} else {
 				break;
 			}
 		}
 
+		self.evicted += removed.len() - sender_removed;
+		removed
+	}
+
+	/// Returns true if the sender of any of the given transactions exceeds the provided limit.
+	pub fn is_sender_limit_exceeded(&self, limit: &SenderLimit, hashes: &[Hash]) -> bool {
+		self.senders_of(hashes).iter().any(|sender| self.sender_count(sender) > limit.count)
+	}
+
+	/// Makes sure that the senders of the given transactions stay within the provided limit.
+	///
+	/// Transactions of both queues count towards the limit of their sender. Senders exceeding
+	/// it lose their future transactions with the lowest priority first and then their most
+	/// recent ready transactions, so that the ones closest to inclusion are kept.
+	/// Removed transactions are returned along with all transactions that depended on them.
+	fn enforce_sender_limit(&mut self, limit: &SenderLimit, hashes: &[Hash]) -> Vec<Arc<Transaction<Hash, Ex>>> {
+		let mut removed = vec![];
+		for sender in self.senders_of(hashes) {
+			while self.sender_count(&sender) > limit.count {
+				let future = self.future.senders().get(&sender).cloned().collect::<Vec<_>>();
+				let worst = self.future.by_hashes(&future)
+					.into_iter()
+					.flatten()
+					.min_by_key(|transaction| eviction_key(transaction))
+					.or_else(|| self.ready.latest_by_sender(&sender));
+
+				match worst {
+					Some(worst) => removed.append(&mut self.remove_subtree(&[worst.hash.clone()])),
+					None => break,
+				}
+			}
+		}
+
 		removed
 	}
 
+	/// Returns the senders of the given transactions.
+	fn senders_of(&self, hashes: &[Hash]) -> HashSet<Vec<u8>> {
+		self.by_hashes(hashes)
+			.into_iter()
+			.flatten()
+			.filter_map(|transaction| self.ready.senders().sender(&transaction).map(|sender| sender.to_vec()))
+			.collect()
+	}
+
+	/// Returns the number of transactions of the sender in both queues.
+	fn sender_count(&self, sender: &[u8]) -> usize {
+		self.ready.senders().count(sender) + self.future.senders().count(sender)
+	}
+
 	/// Removes all transactions represented by the hashes and all other transactions
 	/// that depend on them.
 	///
@@ -516,10 +578,23 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 			ready_bytes: self.ready.bytes(),
 			future: self.future.len(),
 			future_bytes: self.future.bytes(),
+			evicted: self.evicted,
+			sender_evicted: self.sender_evicted,
 		}
 	}
 }
 
+/// Key ordering transactions for eviction, the lowest one is evicted first.
+///
+/// The transaction priority is usually derived from the fee it pays, so among
+/// equal priorities the transactions paying less per byte go first.
+fn eviction_key<Hash, Ex>(transaction: &Transaction<Hash, Ex>) -> (Priority, Priority) {
+	(
+		transaction.priority,
+		transaction.priority / cmp::max(transaction.bytes, 1) as Priority,
+	)
+}
+
 /// Queue limits
 #[derive(Debug, Clone)]
 pub struct Limit {
@@ -536,6 +611,87 @@ impl Limit {
 	}
 }
 
+/// Number of bytes of the provided tag identifying the sender by default, i.e. the length of the
+/// account ids used by FRAME.
+pub const DEFAULT_SENDER_TAG_PREFIX: usize = 32;
+
+/// Limit of transactions of a single sender.
+///
+/// The sender is identified by the first `tag_prefix` bytes of the first tag provided by a
+/// transaction, e.g. the account id of the `(account, nonce)` tags used by FRAME.
+#[derive(Debug, Clone)]
+pub struct SenderLimit {
+	/// Maximal number of transactions of a single sender in both queues.
+	pub count: usize,
+	/// Number of bytes of the provided tag identifying the sender.
+	pub tag_prefix: usize,
+}
+
+/// Hashes of the transactions in a queue, grouped by their sender.
+///
+/// Kept up to date on every insertion and removal, so that the transactions of a sender can be
+/// found without going through the whole queue.
+#[derive(Debug, parity_util_mem::MallocSizeOf)]
+pub struct SenderTransactions<Hash: hash::Hash + Eq> {
+	/// Number of bytes of the provided tag identifying the sender.
+	tag_prefix: usize,
+	/// Hashes of the transactions of every sender in the queue.
+	by_sender: HashMap<Vec<u8>, HashSet<Hash>>,
+}
+
+impl<Hash: hash::Hash + Eq + Clone> SenderTransactions<Hash> {
+	/// Create an empty index identifying senders by `tag_prefix` bytes of their provided tag.
+	pub fn new(tag_prefix: usize) -> Self {
+		SenderTransactions {
+			tag_prefix,
+			by_sender: Default::default(),
+		}
+	}
+
+	/// Returns the part of the tags of the transaction identifying its sender.
+	pub fn sender<'a, Ex>(&self, transaction: &'a Transaction<Hash, Ex>) -> Option<&'a [u8]> {
+		transaction.provides.first().map(|tag| &tag[..cmp::min(self.tag_prefix, tag.len())])
+	}
+
+	/// Adds the transaction to its sender.
+	pub fn insert<Ex>(&mut self, transaction: &Transaction<Hash, Ex>) {
+		if let Some(sender) = self.sender(transaction) {
+			self.by_sender.entry(sender.to_vec()).or_default().insert(transaction.hash.clone());
+		}
+	}
+
+	/// Removes the transaction from its sender.
+	pub fn remove<Ex>(&mut self, transaction: &Transaction<Hash, Ex>) {
+		if let Some(sender) = self.sender(transaction) {
+			let is_empty = match self.by_sender.get_mut(sender) {
+				Some(hashes) => {
+					hashes.remove(&transaction.hash);
+					hashes.is_empty()
+				},
+				None => false,
+			};
+			if is_empty {
+				self.by_sender.remove(sender);
+			}
+		}
+	}
+
+	/// Removes all transactions.
+	pub fn clear(&mut self) {
+		self.by_sender.clear();
+	}
+
+	/// Returns the hashes of the transactions of the sender.
+	pub fn get<'a>(&'a self, sender: &[u8]) -> impl Iterator<Item=&'a Hash> + 'a {
+		self.by_sender.get(sender).into_iter().flatten()
+	}
+
+	/// Returns the number of transactions of the sender.
+	pub fn count(&self, sender: &[u8]) -> usize {
+		self.by_sender.get(sender).map_or(0, HashSet::len)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1194,4 +1350,100 @@ source: TransactionSource::External, requires: [03,02], provides: [04], data: [4
 		assert_eq!(pool.reject_future_transactions, true);
 		assert_eq!(pool.future.len(), 1);
 	}
+
+	fn transaction(
+		hash: Hash,
+		priority: u64,
+		requires: Vec<Vec<u8>>,
+		provides: Vec<Vec<u8>>,
+	) -> Transaction<Hash, Vec<u8>> {
+		Transaction {
+			data: vec![hash as u8],
+			bytes: 1,
+			hash,
+			priority,
+			valid_till: 64u64,
+			requires,
+			provides,
+			propagate: true,
+			source: Source::External,
+		}
+	}
+
+	#[test]
+	fn should_evict_lowest_priority_when_enforcing_limits() {
+		// given
+		let mut pool = pool();
+		pool.import(transaction(1, 5, vec![], vec![vec![1]])).unwrap();
+		pool.import(transaction(2, 1, vec![], vec![vec![2]])).unwrap();
+		pool.import(transaction(3, 3, vec![], vec![vec![3]])).unwrap();
+		pool.import(transaction(4, 1, vec![vec![9]], vec![vec![4]])).unwrap();
+		pool.import(transaction(5, 2, vec![vec![9]], vec![vec![5]])).unwrap();
+		let limit = Limit { count: 2, total_bytes: 100 };
+		let future_limit = Limit { count: 1, total_bytes: 100 };
+		let sender_limit = SenderLimit { count: 100, tag_prefix: 1 };
+
+		// when
+		let removed = pool.enforce_limits(&limit, &future_limit, &sender_limit, &[5]);
+
+		// then
+		let mut removed = removed.iter().map(|tx| tx.hash).collect::<Vec<_>>();
+		removed.sort();
+		assert_eq!(removed, vec![2, 4]);
+		assert_eq!(pool.status().evicted, 2);
+		assert_eq!(pool.status().sender_evicted, 0);
+	}
+
+	#[test]
+	fn should_enforce_sender_limit() {
+		// given
+		let mut pool = BasePool::new(false, 1);
+		// sender 1 has a chain of three ready transactions and one future one.
+		pool.import(transaction(1, 5, vec![], vec![vec![1, 0]])).unwrap();
+		pool.import(transaction(2, 5, vec![vec![1, 0]], vec![vec![1, 1]])).unwrap();
+		pool.import(transaction(3, 5, vec![vec![1, 1]], vec![vec![1, 2]])).unwrap();
+		pool.import(transaction(4, 9, vec![vec![1, 5]], vec![vec![1, 6]])).unwrap();
+		// sender 2 is within the limit.
+		pool.import(transaction(5, 1, vec![], vec![vec![2, 0]])).unwrap();
+		pool.import(transaction(6, 1, vec![vec![2, 0]], vec![vec![2, 1]])).unwrap();
+		let queue_limit = Limit { count: 100, total_bytes: 100 };
+		let limit = SenderLimit { count: 2, tag_prefix: 1 };
+		assert!(!pool.is_sender_limit_exceeded(&limit, &[5, 6]));
+		assert!(pool.is_sender_limit_exceeded(&limit, &[4]));
+
+		// when
+		let removed = pool.enforce_limits(&queue_limit, &queue_limit, &limit, &[4, 5, 6]);
+
+		// then
+		let mut removed = removed.iter().map(|tx| tx.hash).collect::<Vec<_>>();
+		removed.sort();
+		assert_eq!(removed, vec![3, 4]);
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<Vec<_>>(), vec![1, 2, 5, 6]);
+		assert_eq!(pool.future.len(), 0);
+		assert_eq!(pool.status().sender_evicted, 2);
+		assert_eq!(pool.status().evicted, 0);
+	}
+
+	#[test]
+	fn should_track_transactions_by_sender() {
+		// given
+		let mut pool = BasePool::new(false, 1);
+		pool.import(transaction(2, 5, vec![vec![1, 0]], vec![vec![1, 1]])).unwrap();
+		assert_eq!(pool.future.senders().count(&[1]), 1);
+
+		// when
+		pool.import(transaction(1, 5, vec![], vec![vec![1, 0]])).unwrap();
+		pool.import(transaction(3, 5, vec![], vec![vec![2, 0]])).unwrap();
+
+		// then
+		assert_eq!(pool.future.senders().count(&[1]), 0);
+		assert_eq!(pool.ready.senders().count(&[1]), 2);
+		assert_eq!(pool.ready.latest_by_sender(&[1]).map(|tx| tx.hash), Some(2));
+
+		pool.prune_tags(vec![vec![1, 0]]);
+		assert_eq!(pool.ready.senders().count(&[1]), 1);
+		pool.remove_subtree(&[2, 3]);
+		assert_eq!(pool.ready.senders().count(&[1]), 0);
+		assert_eq!(pool.ready.senders().count(&[2]), 0);
+	}
 }
@@ -27,7 +27,7 @@ use sp_runtime::transaction_validity::{
 };
 use wasm_timer::Instant;
 
-use crate::base_pool::Transaction;
+use crate::base_pool::{Transaction, SenderTransactions, DEFAULT_SENDER_TAG_PREFIX};
 
 #[cfg_attr(not(target_os = "unknown"), derive(parity_util_mem::MallocSizeOf))]
 /// Transaction with partially satisfied dependencies.
@@ -117,14 +117,13 @@ pub struct FutureTransactions<Hash: hash::Hash + Eq, Ex> {
 	wanted_tags: HashMap<Tag, HashSet<Hash>>,
 	/// Transactions waiting for a particular other transaction
 	waiting: HashMap<Hash, WaitingTransaction<Hash, Ex>>,
+	/// Transactions waiting, grouped by their sender.
+	senders: SenderTransactions<Hash>,
 }
 
-impl<Hash: hash::Hash + Eq, Ex> Default for FutureTransactions<Hash, Ex> {
+impl<Hash: hash::Hash + Eq + Clone, Ex> Default for FutureTransactions<Hash, Ex> {
 	fn default() -> Self {
-		FutureTransactions {
-			wanted_tags: Default::default(),
-			waiting: Default::default(),
-		}
+		Self::new(DEFAULT_SENDER_TAG_PREFIX)
 	}
 }
 
@@ -136,6 +135,15 @@ qed
 #";
 
 impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
+	/// Create an empty queue, identifying senders by `sender_tag_prefix` bytes of their provided tag.
+	pub fn new(sender_tag_prefix: usize) -> Self {
+		FutureTransactions {
+			wanted_tags: Default::default(),
+			waiting: Default::default(),
+			senders: SenderTransactions::new(sender_tag_prefix),
+		}
+	}
+
 	/// Import transaction to Future queue.
 	///
 	/// Only transactions that don't have all their tags satisfied should occupy
@@ -153,6 +161,7 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
 		}
 
 		// Add the transaction to a by-hash waiting map
+		self.senders.insert(&tx.transaction);
 		self.waiting.insert(tx.transaction.hash.clone(), tx);
 	}
 
@@ -161,6 +170,11 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
 		self.waiting.contains_key(hash)
 	}
 
+	/// Borrows the transactions in this queue grouped by their sender.
+	pub fn senders(&self) -> &SenderTransactions<Hash> {
+		&self.senders
+	}
+
 	/// Returns a list of known transactions
 	pub fn by_hashes(&self, hashes: &[Hash]) -> Vec<Option<Arc<Transaction<Hash, Ex>>>> {
 		hashes.iter().map(|h| self.waiting.get(h).map(|x| x.transaction.clone())).collect()
@@ -184,6 +198,7 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
 
 					if is_ready {
 						let tx = self.waiting.remove(&hash).expect(WAITING_PROOF);
+						self.senders.remove(&tx.transaction);
 						became_ready.push(tx);
 					}
 				}
@@ -200,6 +215,7 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
 		let mut removed = vec![];
 		for hash in hashes {
 			if let Some(waiting_tx) = self.waiting.remove(hash) {
+				self.senders.remove(&waiting_tx.transaction);
 				// remove from wanted_tags as well
 				for tag in waiting_tx.missing_tags {
 					let remove = if let Some(wanted) = self.wanted_tags.get_mut(&tag) {
@@ -232,6 +248,7 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
 	/// Removes and returns all future transactions.
 	pub fn clear(&mut self) -> Vec<Arc<Transaction<Hash, Ex>>> {
 		self.wanted_tags.clear();
+		self.senders.clear();
 		self.waiting.drain().map(|(_, tx)| tx.transaction).collect()
 	}
 
@@ -18,6 +18,7 @@ use std::{
 	hash,
 	collections::HashMap,
 	sync::Arc,
+	time::Duration,
 };
 
 use crate::base_pool as base;
@@ -100,8 +101,12 @@ pub struct Options {
 	pub ready: base::Limit,
 	/// Future queue limits.
 	pub future: base::Limit,
+	/// Limit of transactions of a single sender.
+	pub sender: base::SenderLimit,
 	/// Reject future transactions.
 	pub reject_future_transactions: bool,
+	/// How long transactions removed from the pool are prevented from entering it again.
+	pub ban_time: Duration,
 }
 
 impl Default for Options {
@@ -115,7 +120,12 @@ impl Default for Options {
 				count: 512,
 				total_bytes: 1 * 1024 * 1024,
 			},
+			sender: base::SenderLimit {
+				count: 512,
+				tag_prefix: base::DEFAULT_SENDER_TAG_PREFIX,
+			},
 			reject_future_transactions: false,
+			ban_time: Duration::from_secs(60 * 30),
 		}
 	}
 }
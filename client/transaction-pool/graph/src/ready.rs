@@ -31,7 +31,7 @@ use sp_runtime::transaction_validity::{
 use sp_transaction_pool::error;
 
 use crate::future::WaitingTransaction;
-use crate::base_pool::Transaction;
+use crate::base_pool::{Transaction, SenderTransactions, DEFAULT_SENDER_TAG_PREFIX};
 
 /// An in-pool transaction reference.
 ///
@@ -114,15 +114,25 @@ pub struct ReadyTransactions<Hash: hash::Hash + Eq, Ex> {
 	ready: Arc<RwLock<HashMap<Hash, ReadyTx<Hash, Ex>>>>,
 	/// Best transactions that are ready to be included to the block without any other previous transaction.
 	best: BTreeSet<TransactionRef<Hash, Ex>>,
+	/// Transactions that are ready, grouped by their sender.
+	senders: SenderTransactions<Hash>,
 }
 
-impl<Hash: hash::Hash + Eq, Ex> Default for ReadyTransactions<Hash, Ex> {
+impl<Hash: hash::Hash + Eq + Clone, Ex> Default for ReadyTransactions<Hash, Ex> {
 	fn default() -> Self {
+		Self::new(DEFAULT_SENDER_TAG_PREFIX)
+	}
+}
+
+impl<Hash: hash::Hash + Eq + Clone, Ex> ReadyTransactions<Hash, Ex> {
+	/// Create an empty queue, identifying senders by `sender_tag_prefix` bytes of their provided tag.
+	pub fn new(sender_tag_prefix: usize) -> Self {
 		ReadyTransactions {
 			insertion_id: Default::default(),
 			provided_tags: Default::default(),
 			ready: Default::default(),
 			best: Default::default(),
+			senders: SenderTransactions::new(sender_tag_prefix),
 		}
 	}
 }
@@ -202,6 +212,8 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 			transaction
 		};
 
+		self.senders.insert(&transaction.transaction);
+
 		// insert to best if it doesn't require any other transaction to be included before it
 		if goes_to_best {
 			self.best.insert(transaction.clone());
@@ -230,6 +242,20 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 		self.ready.read().contains_key(hash)
 	}
 
+	/// Borrows the transactions in this queue grouped by their sender.
+	pub fn senders(&self) -> &SenderTransactions<Hash> {
+		&self.senders
+	}
+
+	/// Returns the most recently imported transaction of the sender.
+	pub fn latest_by_sender(&self, sender: &[u8]) -> Option<Arc<Transaction<Hash, Ex>>> {
+		let ready = self.ready.read();
+		self.senders.get(sender)
+			.filter_map(|hash| ready.get(hash))
+			.max_by_key(|tx| tx.transaction.insertion_id)
+			.map(|tx| tx.transaction.transaction.clone())
+	}
+
 	/// Retrive transaction by hash
 	pub fn by_hash(&self, hash: &Hash) -> Option<Arc<Transaction<Hash, Ex>>> {
 		self.by_hashes(&[hash.clone()]).into_iter().next().unwrap_or(None)
@@ -298,6 +324,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 
 				// remove from best
 				self.best.remove(&tx.transaction);
+				self.senders.remove(&tx.transaction.transaction);
 
 				if removed_some_tags {
 					// remove all transactions that the current one unlocks
@@ -332,6 +359,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex> ReadyTransactions<Hash, Ex> {
 			if let Some(tx) = res {
 				let unlocks = tx.unlocks;
 				let tx = tx.transaction.transaction;
+				self.senders.remove(&tx);
 
 				// prune previous transactions as well
 				{
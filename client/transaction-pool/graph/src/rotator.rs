@@ -46,8 +46,15 @@ pub struct PoolRotator<Hash> {
 
 impl<Hash: hash::Hash + Eq> Default for PoolRotator<Hash> {
 	fn default() -> Self {
+		Self::new(Duration::from_secs(60 * 30))
+	}
+}
+
+impl<Hash: hash::Hash + Eq> PoolRotator<Hash> {
+	/// New rotator instance banning extrinsics for the given time.
+	pub fn new(ban_time: Duration) -> Self {
 		PoolRotator {
-			ban_time: Duration::from_secs(60 * 30),
+			ban_time,
 			banned_until: Default::default(),
 		}
 	}
@@ -114,14 +114,15 @@ where
 impl<B: ChainApi> ValidatedPool<B> {
 	/// Create a new transaction pool.
 	pub fn new(options: Options, api: Arc<B>) -> Self {
-		let base_pool = base::BasePool::new(options.reject_future_transactions);
+		let base_pool = base::BasePool::new(options.reject_future_transactions, options.sender.tag_prefix);
+		let rotator = PoolRotator::new(options.ban_time);
 		ValidatedPool {
 			options,
 			listener: Default::default(),
 			api,
 			pool: RwLock::new(base_pool),
 			import_notification_sinks: Default::default(),
-			rotator: Default::default(),
+			rotator,
 		}
 	}
 
@@ -144,8 +145,9 @@ impl<B: ChainApi> ValidatedPool<B> {
 			.collect::<Vec<_>>();
 
 		// only enforce limits if there is at least one imported transaction
-		let removed = if results.iter().any(|res| res.is_ok()) {
-			self.enforce_limits()
+		let imported = results.iter().filter_map(|res| res.as_ref().ok().cloned()).collect::<Vec<_>>();
+		let removed = if !imported.is_empty() {
+			self.enforce_limits(&imported)
 		} else {
 			Default::default()
 		};
@@ -182,26 +184,33 @@ impl<B: ChainApi> ValidatedPool<B> {
 		}
 	}
 
-	fn enforce_limits(&self) -> HashSet<ExHash<B>> {
-		let status = self.pool.read().status();
+	fn enforce_limits(&self, imported: &[ExHash<B>]) -> HashSet<ExHash<B>> {
 		let ready_limit = &self.options.ready;
 		let future_limit = &self.options.future;
+		let sender_limit = &self.options.sender;
+		let (status, sender_exceeded) = {
+			let pool = self.pool.read();
+			(pool.status(), pool.is_sender_limit_exceeded(sender_limit, imported))
+		};
 
 		debug!(target: "txpool", "Pool Status: {:?}", status);
 		if ready_limit.is_exceeded(status.ready, status.ready_bytes)
 			|| future_limit.is_exceeded(status.future, status.future_bytes)
+			// only the senders of the imported transactions may have exceeded their limit.
+			|| sender_exceeded
 		{
 			debug!(
 				target: "txpool",
-				"Enforcing limits ({}/{}kB ready, {}/{}kB future",
+				"Enforcing limits ({}/{}kB ready, {}/{}kB future, {} per sender",
 				ready_limit.count, ready_limit.total_bytes / 1024,
 				future_limit.count, future_limit.total_bytes / 1024,
+				sender_limit.count,
 			);
 
 			// clean up the pool
 			let removed = {
 				let mut pool = self.pool.write();
-				let removed = pool.enforce_limits(ready_limit, future_limit)
+				let removed = pool.enforce_limits(ready_limit, future_limit, sender_limit, imported)
 					.into_iter().map(|x| x.hash.clone()).collect::<HashSet<_>>();
 				// ban all removed transactions
 				self.rotator.ban(&Instant::now(), removed.iter().map(|x| x.clone()));
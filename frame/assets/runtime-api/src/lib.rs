@@ -0,0 +1,34 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for assets module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	pub trait AssetsApi<AccountId, AssetId, Balance> where
+		AccountId: Codec,
+		AssetId: Codec,
+		Balance: Codec,
+	{
+		/// Returns the balances of the given assets held by the account, omitting the ones it
+		/// doesn't hold.
+		fn account_balances(account: AccountId, ids: Vec<AssetId>) -> Vec<(AssetId, Balance)>;
+	}
+}
@@ -21,11 +21,14 @@
 //! ## Overview
 //!
 //! The Assets module provides functionality for asset management of fungible asset classes
-//! with a fixed supply, including:
+//! with a managed supply, including:
 //!
+//! * Asset Creation
 //! * Asset Issuance
 //! * Asset Transfer
+//! * Asset Freezing
 //! * Asset Destruction
+//! * Delegated Asset Transfers ("Approval API")
 //!
 //! To use it in your runtime, you need to implement the assets [`Trait`](./trait.Trait.html).
 //!
@@ -33,10 +36,22 @@
 //!
 //! ### Terminology
 //!
-//! * **Asset issuance:** The creation of a new asset, whose total supply will belong to the
-//!   account that issues the asset.
-//! * **Asset transfer:** The action of transferring assets from one account to another.
-//! * **Asset destruction:** The process of an account removing its entire holding of an asset.
+//! * **Asset class:** A fungible asset, created through `create` or `force_create`, whose
+//!   units are held in accounts and whose supply is managed by its team.
+//! * **Owner:** The account which created the asset class. It has reserved a deposit for it,
+//!   manages its metadata and may reassign the other roles.
+//! * **Issuer:** The account which may mint new units of the asset.
+//! * **Admin:** The account which may burn units from, forcibly transfer out of and thaw any
+//!   account.
+//! * **Freezer:** The account which may freeze accounts or the entire asset class.
+//! * **Minimum balance:** The smallest non-zero balance an account may hold of an asset. An
+//!   account whose balance would drop below it is reaped and any remainder is moved along with
+//!   the transfer, or burned.
+//! * **Holder deposit:** The funds reserved for every account holding an asset, from the
+//!   account which minted or transferred the first units into it. They are returned once the
+//!   holding is reaped.
+//! * **Approval:** An amount of an asset that a delegate account may transfer out of the
+//!   owner's account on the owner's behalf.
 //! * **Fungible asset:** An asset whose units are interchangeable.
 //! * **Non-fungible asset:** An asset for which each unit has unique characteristics.
 //!
@@ -44,20 +59,34 @@
 //!
 //! The assets system in Substrate is designed to make the following possible:
 //!
-//! * Issue a unique asset to its creator's account.
-//! * Move assets between accounts.
-//! * Remove an account's balance of an asset when requested by that account's owner and update
-//!   the asset's total supply.
+//! * Create a unique asset class, managed by a team of privileged accounts.
+//! * Issue and burn units of an asset class.
+//! * Move assets between accounts, directly or through an approved delegate.
+//! * Freeze the holdings of individual accounts, or of the entire asset class.
+//! * Describe an asset class with a name, symbol and number of decimals.
 //!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
 //!
-//! * `issue` - Issues the total supply of a new fungible asset to the account of the caller of the function.
+//! * `create` - Creates a new asset class, reserving a deposit from the caller.
+//! * `force_create` - Creates a new asset class without reserving a deposit.
+//! * `destroy` - Destroys an asset class along with all of its holdings.
+//! * `mint` - Increases the holding of an account and the total supply of an asset.
+//! * `burn` - Reduces the holding of an account and the total supply of an asset.
 //! * `transfer` - Transfers an `amount` of units of fungible asset `id` from the balance of
 //! the function caller's account (`origin`) to a `target` account.
-//! * `destroy` - Destroys the entire holding of a fungible asset `id` associated with the account
-//! that called the function.
+//! * `force_transfer` - Transfers units of an asset between two accounts on behalf of the admin.
+//! * `freeze` - Disallows further transfers out of an account.
+//! * `thaw` - Allows transfers out of a previously frozen account again.
+//! * `freeze_asset` - Disallows further transfers of any units of an asset class.
+//! * `thaw_asset` - Allows transfers of a previously frozen asset class again.
+//! * `transfer_ownership` - Changes the owner of an asset class.
+//! * `set_team` - Changes the issuer, admin and freezer of an asset class.
+//! * `set_metadata` - Sets the name, symbol and decimals of an asset class.
+//! * `approve_transfer` - Approves a delegate to transfer units out of the caller's account.
+//! * `cancel_approval` - Cancels a previous approval.
+//! * `transfer_approved` - Transfers units out of an account that approved the caller.
 //!
 //! Please refer to the [`Call`](./enum.Call.html) enum and its associated variants for documentation on each function.
 //!
@@ -66,6 +95,7 @@
 //!
 //! * `balance` - Get the asset `id` balance of `who`.
 //! * `total_supply` - Get the total supply of an asset `id`.
+//! * `account_balances` - Get the balances of the given assets held by `who`.
 //!
 //! Please refer to the [`Module`](./struct.Module.html) struct for details on publicly available functions.
 //!
@@ -73,7 +103,6 @@
 //!
 //! The following example shows how to use the Assets module in your runtime by exposing public functions to:
 //!
-//! * Issue a new fungible asset for a token distribution event (airdrop).
 //! * Query the fungible asset holding balance of an account.
 //! * Query the total supply of a fungible asset that has been issued.
 //!
@@ -92,24 +121,14 @@
 //!
 //! decl_module! {
 //! 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-//! 		pub fn issue_token_airdrop(origin) -> dispatch::DispatchResult {
-//! 			let sender = ensure_signed(origin).map_err(|e| e.as_str())?;
+//! 		pub fn check_asset_share(origin, id: T::AssetId) -> dispatch::DispatchResult {
+//! 			let sender = ensure_signed(origin)?;
 //!
-//! 			const ACCOUNT_ALICE: u64 = 1;
-//! 			const ACCOUNT_BOB: u64 = 2;
-//! 			const COUNT_AIRDROP_RECIPIENTS: u64 = 2;
-//! 			const TOKENS_FIXED_SUPPLY: u64 = 100;
+//! 			let balance = <assets::Module<T>>::balance(id, sender);
+//! 			let total_supply = <assets::Module<T>>::total_supply(id);
 //!
-//! 			ensure!(!COUNT_AIRDROP_RECIPIENTS.is_zero(), "Divide by zero error.");
-//!
-//! 			let asset_id = Self::next_asset_id();
-//!
-//! 			<NextAssetId<T>>::mutate(|asset_id| *asset_id += 1);
-//! 			<Balances<T>>::insert((asset_id, &ACCOUNT_ALICE), TOKENS_FIXED_SUPPLY / COUNT_AIRDROP_RECIPIENTS);
-//! 			<Balances<T>>::insert((asset_id, &ACCOUNT_BOB), TOKENS_FIXED_SUPPLY / COUNT_AIRDROP_RECIPIENTS);
-//! 			<TotalSupply<T>>::insert(asset_id, TOKENS_FIXED_SUPPLY);
-//!
-//! 			Self::deposit_event(RawEvent::Issued(asset_id, sender, TOKENS_FIXED_SUPPLY));
+//! 			// Only holders of at least a tenth of the supply may proceed.
+//! 			ensure!(balance * 10.into() >= total_supply, "Not enough of the asset held.");
 //! 			Ok(())
 //! 		}
 //! 	}
@@ -132,10 +151,24 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{Parameter, decl_module, decl_event, decl_storage, decl_error, ensure};
-use sp_runtime::traits::{Member, AtLeast32Bit, Zero, StaticLookup};
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use frame_support::{
+	Parameter, decl_module, decl_event, decl_storage, decl_error, ensure,
+	IterableStorageDoubleMap, metadata::TypeInfo,
+	traits::{Currency, ReservableCurrency, EnsureOrigin, Get, BalanceStatus},
+	weights::{SimpleDispatchInfo, FunctionOf, DispatchClass, Weight},
+	dispatch::{DispatchResult, DispatchError},
+};
+use sp_runtime::{RuntimeDebug, traits::{Member, AtLeast32Bit, Zero, StaticLookup, One, CheckedAdd}};
 use frame_system::{self as system, ensure_signed};
-use sp_runtime::traits::One;
+
+type DepositBalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type AssetBalanceOf<T> = AssetBalance<
+	<T as Trait>::Balance,
+	<T as frame_system::Trait>::AccountId,
+	DepositBalanceOf<T>,
+>;
 
 /// The module configuration trait.
 pub trait Trait: frame_system::Trait {
@@ -147,57 +180,691 @@ pub trait Trait: frame_system::Trait {
 
 	/// The arithmetic type of asset identifier.
 	type AssetId: Parameter + AtLeast32Bit + Default + Copy;
+
+	/// The currency mechanism, used for paying for deposits.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The origin which may forcibly create or destroy an asset.
+	type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The amount of funds that must be reserved when creating a new asset class.
+	type AssetDepositBase: Get<DepositBalanceOf<Self>>;
+
+	/// The basic amount of funds that must be reserved when adding metadata to an asset.
+	type MetadataDepositBase: Get<DepositBalanceOf<Self>>;
+
+	/// The additional funds that must be reserved for every byte of an asset's name and symbol.
+	type MetadataDepositPerByte: Get<DepositBalanceOf<Self>>;
+
+	/// The amount of funds that must be reserved when approving a delegate for transfers.
+	type ApprovalDeposit: Get<DepositBalanceOf<Self>>;
+
+	/// The amount of funds that must be reserved for every account holding an asset.
+	type AccountDeposit: Get<DepositBalanceOf<Self>>;
+
+	/// The maximum length of an asset's name or symbol.
+	type StringLimit: Get<u32>;
+}
+
+/// The details of an asset class.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct AssetDetails<Balance, AccountId, DepositBalance> {
+	/// Can change `owner`, `issuer`, `freezer` and `admin` accounts, and set the metadata.
+	pub owner: AccountId,
+	/// Can mint tokens.
+	pub issuer: AccountId,
+	/// Can thaw tokens, force transfers and burn tokens from any account.
+	pub admin: AccountId,
+	/// Can freeze tokens.
+	pub freezer: AccountId,
+	/// The total supply across all accounts.
+	pub supply: Balance,
+	/// The balance reserved from the owner for this asset class.
+	pub deposit: DepositBalance,
+	/// The smallest non-zero balance an account may hold.
+	pub min_balance: Balance,
+	/// The total number of accounts holding this asset.
+	pub accounts: u32,
+	/// The total number of approvals of this asset.
+	pub approvals: u32,
+	/// Whether transfers of this asset are currently disallowed.
+	pub is_frozen: bool,
+}
+
+/// The holding of a single account in an asset class.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug, TypeInfo)]
+pub struct AssetBalance<Balance, AccountId, DepositBalance> {
+	/// The balance.
+	pub balance: Balance,
+	/// Whether transfers out of this account are currently disallowed.
+	pub is_frozen: bool,
+	/// The account which reserved the deposit for this holding.
+	pub depositor: AccountId,
+	/// The balance reserved from the depositor for this holding.
+	pub deposit: DepositBalance,
+}
+
+/// The metadata of an asset class.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Default, RuntimeDebug, TypeInfo)]
+pub struct AssetMetadata<DepositBalance> {
+	/// The balance reserved from the owner for this metadata.
+	pub deposit: DepositBalance,
+	/// The user friendly name of this asset, e.g. "Bitcoin".
+	pub name: Vec<u8>,
+	/// The ticker symbol of this asset, e.g. "BTC".
+	pub symbol: Vec<u8>,
+	/// The number of decimals this asset uses to represent one unit.
+	pub decimals: u8,
+}
+
+/// An amount of an asset that a delegate may transfer on behalf of its owner.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct Approval<Balance, DepositBalance> {
+	/// The amount which may still be transferred by the delegate.
+	pub amount: Balance,
+	/// The balance reserved from the owner for this approval.
+	pub deposit: DepositBalance,
 }
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		type Error = Error<T>;
 
+		/// The amount of funds that must be reserved when creating a new asset class.
+		const AssetDepositBase: DepositBalanceOf<T> = T::AssetDepositBase::get();
+
+		/// The basic amount of funds that must be reserved when adding metadata to an asset.
+		const MetadataDepositBase: DepositBalanceOf<T> = T::MetadataDepositBase::get();
+
+		/// The additional funds that must be reserved for every byte of metadata.
+		const MetadataDepositPerByte: DepositBalanceOf<T> = T::MetadataDepositPerByte::get();
+
+		/// The amount of funds that must be reserved when approving a delegate for transfers.
+		const ApprovalDeposit: DepositBalanceOf<T> = T::ApprovalDeposit::get();
+
+		/// The amount of funds that must be reserved for every account holding an asset.
+		const AccountDeposit: DepositBalanceOf<T> = T::AccountDeposit::get();
+
+		/// The maximum length of an asset's name or symbol.
+		const StringLimit: u32 = T::StringLimit::get();
+
 		fn deposit_event() = default;
-		/// Issue a new class of fungible assets. There are, and will only ever be, `total`
-		/// such assets and they'll all belong to the `origin` initially. It will have an
-		/// identifier `AssetId` instance: this will be specified in the `Issued` event.
-		#[weight = frame_support::weights::SimpleDispatchInfo::default()]
-		fn issue(origin, #[compact] total: T::Balance) {
-			let origin = ensure_signed(origin)?;
+
+		/// Issue a new class of fungible assets. It will initially have no supply and will be
+		/// identified by the next `AssetId`: this will be specified in the `Created` event.
+		///
+		/// The origin must be Signed and will become the owner of the asset class, reserving
+		/// `AssetDepositBase` for it. The `admin` becomes the issuer, admin and freezer.
+		///
+		/// - `admin`: The initial issuer, admin and freezer of the asset class.
+		/// - `min_balance`: The smallest non-zero balance an account may hold. Must be non-zero.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - One storage read and two storage writes, one balance reserve.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn create(origin,
+			admin: <T::Lookup as StaticLookup>::Source,
+			#[compact] min_balance: T::Balance,
+		) {
+			let owner = ensure_signed(origin)?;
+			let admin = T::Lookup::lookup(admin)?;
+			ensure!(!min_balance.is_zero(), Error::<T>::MinBalanceZero);
+
+			let deposit = T::AssetDepositBase::get();
+			T::Currency::reserve(&owner, deposit)?;
+
+			let id = Self::next_asset_id();
+			<NextAssetId<T>>::mutate(|id| *id += One::one());
+
+			Asset::<T>::insert(id, AssetDetails {
+				owner: owner.clone(),
+				issuer: admin.clone(),
+				admin: admin.clone(),
+				freezer: admin.clone(),
+				supply: Zero::zero(),
+				deposit,
+				min_balance,
+				accounts: 0,
+				approvals: 0,
+				is_frozen: false,
+			});
+			Self::deposit_event(RawEvent::Created(id, owner, admin));
+		}
+
+		/// Issue a new class of fungible assets without reserving a deposit.
+		///
+		/// The origin must be `ForceOrigin`. The `owner` will be given every role of the asset
+		/// class.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - One storage read and two storage writes.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedOperational(50_000)]
+		fn force_create(origin,
+			owner: <T::Lookup as StaticLookup>::Source,
+			#[compact] min_balance: T::Balance,
+		) {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			ensure!(!min_balance.is_zero(), Error::<T>::MinBalanceZero);
 
 			let id = Self::next_asset_id();
 			<NextAssetId<T>>::mutate(|id| *id += One::one());
 
-			<Balances<T>>::insert((id, &origin), total);
-			<TotalSupply<T>>::insert(id, total);
+			Asset::<T>::insert(id, AssetDetails {
+				owner: owner.clone(),
+				issuer: owner.clone(),
+				admin: owner.clone(),
+				freezer: owner.clone(),
+				supply: Zero::zero(),
+				deposit: Zero::zero(),
+				min_balance,
+				accounts: 0,
+				approvals: 0,
+				is_frozen: false,
+			});
+			Self::deposit_event(RawEvent::ForceCreated(id, owner));
+		}
+
+		/// Destroy an asset class along with all of its holdings, approvals and metadata,
+		/// returning every deposit.
+		///
+		/// The origin must be either `ForceOrigin` or Signed by the owner of the asset.
+		///
+		/// - `accounts_witness`: An upper bound on the number of accounts holding the asset.
+		/// - `approvals_witness`: An upper bound on the number of approvals of the asset.
+		///
+		/// # <weight>
+		/// - `O(A + P)` where `A` is the number of holding accounts and `P` the number of
+		///   approvals of the asset.
+		/// - 20,000 weight per account in `accounts_witness` and per approval in
+		///   `approvals_witness`, each returning a deposit.
+		/// # </weight>
+		#[weight = FunctionOf(
+			|args: (&T::AssetId, &u32, &u32)| {
+				let deposits = args.1.saturating_add(*args.2) as Weight;
+				(100_000 as Weight).saturating_add(deposits.saturating_mul(20_000))
+			},
+			DispatchClass::Normal,
+			true
+		)]
+		fn destroy(origin,
+			#[compact] id: T::AssetId,
+			#[compact] accounts_witness: u32,
+			#[compact] approvals_witness: u32,
+		) {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = maybe_check_owner {
+				ensure!(details.owner == check_owner, Error::<T>::NoPermission);
+			}
+			ensure!(details.accounts <= accounts_witness, Error::<T>::BadWitness);
+			ensure!(details.approvals <= approvals_witness, Error::<T>::BadWitness);
 
-			Self::deposit_event(RawEvent::Issued(id, origin, total));
+			let metadata_deposit = Metadata::<T>::take(id).map_or(Zero::zero(), |m| m.deposit);
+			T::Currency::unreserve(&details.owner, details.deposit.saturating_add(metadata_deposit));
+			for ((owner, _), approval) in Approvals::<T>::drain(id) {
+				T::Currency::unreserve(&owner, approval.deposit);
+			}
+			for (_, account) in Account::<T>::drain(id) {
+				T::Currency::unreserve(&account.depositor, account.deposit);
+			}
+			Asset::<T>::remove(id);
+
+			Self::deposit_event(RawEvent::Destroyed(id));
+		}
+
+		/// Mint units of an asset into an account, increasing the total supply.
+		///
+		/// The origin must be Signed by the issuer of the asset. If `beneficiary` did not hold
+		/// the asset before, `amount` must be at least the minimum balance and the issuer
+		/// reserves `AccountDeposit` for the new holding.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - Two storage reads and two storage writes, one balance reserve.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn mint(origin,
+			#[compact] id: T::AssetId,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.issuer, Error::<T>::NoPermission);
+				details.supply = details.supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+
+				let mut account = Account::<T>::get(id, &beneficiary);
+				let new_balance = account.balance.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+				ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
+				if account.balance.is_zero() {
+					account = Self::new_account(&origin)?;
+					details.accounts = details.accounts.saturating_add(1);
+				}
+				account.balance = new_balance;
+				Account::<T>::insert(id, &beneficiary, &account);
+
+				Self::deposit_event(RawEvent::Issued(id, beneficiary.clone(), amount));
+				Ok(())
+			})
+		}
+
+		/// Burn units of an asset from an account, reducing the total supply.
+		///
+		/// The origin must be Signed by the admin of the asset. At most the balance of `who` is
+		/// burned; if what remains would be below the minimum balance, it is burned too and the
+		/// account is reaped.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - Two storage reads and two storage writes.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn burn(origin,
+			#[compact] id: T::AssetId,
+			who: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.admin, Error::<T>::NoPermission);
+
+				let mut account = Account::<T>::get(id, &who);
+				ensure!(!account.balance.is_zero(), Error::<T>::BalanceZero);
+				let mut burned = amount.min(account.balance);
+				account.balance -= burned;
+				if account.balance < details.min_balance {
+					burned += account.balance;
+					details.accounts = details.accounts.saturating_sub(1);
+					Self::reap_account(id, &who, &account);
+				} else {
+					Account::<T>::insert(id, &who, &account);
+				}
+				details.supply = details.supply.saturating_sub(burned);
+
+				Self::deposit_event(RawEvent::Burned(id, who.clone(), burned));
+				Ok(())
+			})
 		}
 
 		/// Move some assets from one holder to another.
-		#[weight = frame_support::weights::SimpleDispatchInfo::default()]
+		///
+		/// Fails if either the asset or the sender's account is frozen. If the sender would be
+		/// left with less than the minimum balance, its entire balance is transferred. If
+		/// `target` did not hold the asset before, the sender reserves `AccountDeposit` for it.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - Three storage reads and three storage writes, one balance reserve.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
 		fn transfer(origin,
 			#[compact] id: T::AssetId,
 			target: <T::Lookup as StaticLookup>::Source,
 			#[compact] amount: T::Balance
 		) {
 			let origin = ensure_signed(origin)?;
-			let origin_account = (id, origin.clone());
-			let origin_balance = <Balances<T>>::get(&origin_account);
 			let target = T::Lookup::lookup(target)?;
-			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-			ensure!(origin_balance >= amount, Error::<T>::BalanceLow);
 
-			Self::deposit_event(RawEvent::Transferred(id, origin, target.clone(), amount));
-			<Balances<T>>::insert(origin_account, origin_balance - amount);
-			<Balances<T>>::mutate((id, target), |balance| *balance += amount);
+			let transferred = Self::do_transfer(id, &origin, &target, amount, &origin, true)?;
+			Self::deposit_event(RawEvent::Transferred(id, origin, target, transferred));
 		}
 
-		/// Destroy any assets of `id` owned by `origin`.
-		#[weight = frame_support::weights::SimpleDispatchInfo::default()]
-		fn destroy(origin, #[compact] id: T::AssetId) {
+		/// Move some assets from one holder to another on behalf of the admin, regardless of
+		/// any freezing.
+		///
+		/// The origin must be Signed by the admin of the asset. At most the balance of `source`
+		/// is transferred. If `dest` did not hold the asset before, the admin reserves
+		/// `AccountDeposit` for it.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - Four storage reads and three storage writes, one balance reserve.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn force_transfer(origin,
+			#[compact] id: T::AssetId,
+			source: <T::Lookup as StaticLookup>::Source,
+			dest: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
 			let origin = ensure_signed(origin)?;
-			let balance = <Balances<T>>::take((id, &origin));
-			ensure!(!balance.is_zero(), Error::<T>::BalanceZero);
+			let source = T::Lookup::lookup(source)?;
+			let dest = T::Lookup::lookup(dest)?;
+
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.admin, Error::<T>::NoPermission);
 
-			<TotalSupply<T>>::mutate(id, |total_supply| *total_supply -= balance);
-			Self::deposit_event(RawEvent::Destroyed(id, origin, balance));
+			let amount = amount.min(Account::<T>::get(id, &source).balance);
+			let transferred = Self::do_transfer(id, &source, &dest, amount, &origin, false)?;
+			Self::deposit_event(RawEvent::ForceTransferred(id, source, dest, transferred));
+		}
+
+		/// Disallow further transfers out of an account.
+		///
+		/// The origin must be Signed by the freezer of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - Two storage reads and one storage write.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(30_000)]
+		fn freeze(origin, #[compact] id: T::AssetId, who: <T::Lookup as StaticLookup>::Source) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.freezer, Error::<T>::NoPermission);
+			ensure!(Account::<T>::contains_key(id, &who), Error::<T>::BalanceZero);
+
+			Account::<T>::mutate(id, &who, |a| a.is_frozen = true);
+			Self::deposit_event(RawEvent::Frozen(id, who));
+		}
+
+		/// Allow transfers out of a previously frozen account again.
+		///
+		/// The origin must be Signed by the admin of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - Two storage reads and one storage write.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(30_000)]
+		fn thaw(origin, #[compact] id: T::AssetId, who: <T::Lookup as StaticLookup>::Source) {
+			let origin = ensure_signed(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.admin, Error::<T>::NoPermission);
+			ensure!(Account::<T>::contains_key(id, &who), Error::<T>::BalanceZero);
+
+			Account::<T>::mutate(id, &who, |a| a.is_frozen = false);
+			Self::deposit_event(RawEvent::Thawed(id, who));
+		}
+
+		/// Disallow further transfers of any units of an asset class.
+		///
+		/// The origin must be Signed by the freezer of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - One storage read and one storage write.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(30_000)]
+		fn freeze_asset(origin, #[compact] id: T::AssetId) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.freezer, Error::<T>::NoPermission);
+				details.is_frozen = true;
+
+				Self::deposit_event(RawEvent::AssetFrozen(id));
+				Ok(())
+			})
+		}
+
+		/// Allow transfers of a previously frozen asset class again.
+		///
+		/// The origin must be Signed by the admin of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - One storage read and one storage write.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(30_000)]
+		fn thaw_asset(origin, #[compact] id: T::AssetId) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.admin, Error::<T>::NoPermission);
+				details.is_frozen = false;
+
+				Self::deposit_event(RawEvent::AssetThawed(id));
+				Ok(())
+			})
+		}
+
+		/// Change the owner of an asset class, moving its deposits to the new owner.
+		///
+		/// The origin must be Signed by the current owner of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - Two storage reads and one storage write, one reserved balance transfer.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn transfer_ownership(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.owner, Error::<T>::NoPermission);
+				if details.owner == owner { return Ok(()) }
+
+				let metadata_deposit = Metadata::<T>::get(id).map_or(Zero::zero(), |m| m.deposit);
+				let deposit = details.deposit.saturating_add(metadata_deposit);
+				T::Currency::repatriate_reserved(&details.owner, &owner, deposit, BalanceStatus::Reserved)?;
+				details.owner = owner.clone();
+
+				Self::deposit_event(RawEvent::OwnerChanged(id, owner.clone()));
+				Ok(())
+			})
+		}
+
+		/// Change the issuer, admin and freezer of an asset class.
+		///
+		/// The origin must be Signed by the owner of the asset.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - One storage read and one storage write.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(30_000)]
+		fn set_team(origin,
+			#[compact] id: T::AssetId,
+			issuer: <T::Lookup as StaticLookup>::Source,
+			admin: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let issuer = T::Lookup::lookup(issuer)?;
+			let admin = T::Lookup::lookup(admin)?;
+			let freezer = T::Lookup::lookup(freezer)?;
+
+			Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(origin == details.owner, Error::<T>::NoPermission);
+				details.issuer = issuer.clone();
+				details.admin = admin.clone();
+				details.freezer = freezer.clone();
+
+				Self::deposit_event(RawEvent::TeamChanged(id, issuer.clone(), admin.clone(), freezer.clone()));
+				Ok(())
+			})
+		}
+
+		/// Set the name, symbol and decimals of an asset class.
+		///
+		/// The origin must be Signed by the owner of the asset. A deposit of
+		/// `MetadataDepositBase` plus `MetadataDepositPerByte` for every byte of `name` and
+		/// `symbol` is held for the metadata; any previous deposit is adjusted accordingly.
+		/// Passing an empty `name` and `symbol` clears the metadata and returns its deposit.
+		///
+		/// # <weight>
+		/// - `O(N + S)` where `N` and `S` are the lengths of `name` and `symbol`, both bounded
+		///   by `StringLimit`.
+		/// - Two storage reads and one storage write, one balance reserve or unreserve.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn set_metadata(origin,
+			#[compact] id: T::AssetId,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			ensure!(name.len() <= T::StringLimit::get() as usize, Error::<T>::BadMetadata);
+			ensure!(symbol.len() <= T::StringLimit::get() as usize, Error::<T>::BadMetadata);
+
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			ensure!(origin == details.owner, Error::<T>::NoPermission);
+
+			Metadata::<T>::try_mutate_exists(id, |metadata| -> DispatchResult {
+				let old_deposit = metadata.take().map_or(Zero::zero(), |m| m.deposit);
+				if name.is_empty() && symbol.is_empty() {
+					T::Currency::unreserve(&origin, old_deposit);
+					Self::deposit_event(RawEvent::MetadataCleared(id));
+					return Ok(())
+				}
+
+				let new_deposit = T::MetadataDepositPerByte::get()
+					.saturating_mul(((name.len() + symbol.len()) as u32).into())
+					.saturating_add(T::MetadataDepositBase::get());
+				if new_deposit > old_deposit {
+					T::Currency::reserve(&origin, new_deposit - old_deposit)?;
+				} else {
+					T::Currency::unreserve(&origin, old_deposit - new_deposit);
+				}
+
+				*metadata = Some(AssetMetadata {
+					deposit: new_deposit,
+					name: name.clone(),
+					symbol: symbol.clone(),
+					decimals,
+				});
+				Self::deposit_event(RawEvent::MetadataSet(id, name.clone(), symbol.clone(), decimals));
+				Ok(())
+			})
+		}
+
+		/// Approve `delegate` to transfer up to `amount` of an asset out of the sender's account.
+		///
+		/// If an approval already exists, `amount` is added to it. Otherwise `ApprovalDeposit`
+		/// is reserved from the sender until the approval is used up or cancelled.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - Two storage reads and two storage writes, one balance reserve.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn approve_transfer(origin,
+			#[compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
+			let owner = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+			let mut details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+
+			let key = (owner.clone(), delegate.clone());
+			let mut approved = match Approvals::<T>::get(id, &key) {
+				Some(approved) => approved,
+				None => {
+					let deposit = T::ApprovalDeposit::get();
+					T::Currency::reserve(&owner, deposit)?;
+					details.approvals = details.approvals.saturating_add(1);
+					Asset::<T>::insert(id, details);
+					Approval { amount: Zero::zero(), deposit }
+				}
+			};
+			approved.amount = approved.amount.saturating_add(amount);
+			Approvals::<T>::insert(id, &key, approved);
+
+			Self::deposit_event(RawEvent::ApprovedTransfer(id, owner, delegate, amount));
+		}
+
+		/// Cancel an approval of `delegate` to transfer out of the sender's account, returning
+		/// its deposit.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - Two storage reads and two storage writes, one balance unreserve.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(30_000)]
+		fn cancel_approval(origin,
+			#[compact] id: T::AssetId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+		) {
+			let owner = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+
+			let approval = Approvals::<T>::take(id, (owner.clone(), delegate.clone()))
+				.ok_or(Error::<T>::Unapproved)?;
+			T::Currency::unreserve(&owner, approval.deposit);
+			Self::approval_removed(id);
+
+			Self::deposit_event(RawEvent::ApprovalCancelled(id, owner, delegate));
+		}
+
+		/// Transfer some assets out of `owner`'s account, using an approval given to the sender.
+		///
+		/// The approved amount is reduced by the amount transferred; once it reaches zero the
+		/// approval is removed and its deposit returned to `owner`. The transfer is subject to
+		/// the same rules as `transfer`, except that it fails if moving the dust of `owner`
+		/// along would exceed the approved amount.
+		///
+		/// # <weight>
+		/// - `O(1)`
+		/// - Four storage reads and five storage writes, one balance reserve.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn transfer_approved(origin,
+			#[compact] id: T::AssetId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			destination: <T::Lookup as StaticLookup>::Source,
+			#[compact] amount: T::Balance,
+		) {
+			let delegate = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			let destination = T::Lookup::lookup(destination)?;
+
+			let key = (owner.clone(), delegate.clone());
+			let mut approved = Approvals::<T>::get(id, &key).ok_or(Error::<T>::Unapproved)?;
+			ensure!(amount <= approved.amount, Error::<T>::Unapproved);
+
+			// The dust left behind in `owner`'s account would be swept along, which must be
+			// covered by the approval too.
+			let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+			let balance = Account::<T>::get(id, &owner).balance;
+			if owner != destination && balance.saturating_sub(amount) < details.min_balance {
+				ensure!(balance <= approved.amount, Error::<T>::Unapproved);
+			}
+
+			let transferred = Self::do_transfer(id, &owner, &destination, amount, &owner, true)?;
+
+			let remaining = approved.amount.saturating_sub(transferred);
+			if remaining.is_zero() {
+				T::Currency::unreserve(&owner, approved.deposit);
+				Approvals::<T>::remove(id, &key);
+				Self::approval_removed(id);
+			} else {
+				approved.amount = remaining;
+				Approvals::<T>::insert(id, &key, approved);
+			}
+
+			Self::deposit_event(RawEvent::TransferredApproved(id, owner, delegate, destination, transferred));
 		}
 	}
 }
@@ -208,12 +875,43 @@ decl_event! {
 		<T as Trait>::Balance,
 		<T as Trait>::AssetId,
 	{
-		/// Some assets were issued.
+		/// An asset class was created. (asset_id, owner, admin)
+		Created(AssetId, AccountId, AccountId),
+		/// An asset class was created without a deposit. (asset_id, owner)
+		ForceCreated(AssetId, AccountId),
+		/// Some assets were issued. (asset_id, beneficiary, amount)
 		Issued(AssetId, AccountId, Balance),
-		/// Some assets were transferred.
+		/// Some assets were transferred. (asset_id, from, to, amount)
 		Transferred(AssetId, AccountId, AccountId, Balance),
-		/// Some assets were destroyed.
-		Destroyed(AssetId, AccountId, Balance),
+		/// Some assets were burned. (asset_id, who, amount)
+		Burned(AssetId, AccountId, Balance),
+		/// Some assets were forcibly transferred by the admin. (asset_id, from, to, amount)
+		ForceTransferred(AssetId, AccountId, AccountId, Balance),
+		/// The issuer, admin and freezer of an asset changed. (asset_id, issuer, admin, freezer)
+		TeamChanged(AssetId, AccountId, AccountId, AccountId),
+		/// The owner of an asset changed. (asset_id, owner)
+		OwnerChanged(AssetId, AccountId),
+		/// An account was frozen. (asset_id, who)
+		Frozen(AssetId, AccountId),
+		/// An account was thawed. (asset_id, who)
+		Thawed(AssetId, AccountId),
+		/// An asset class was frozen.
+		AssetFrozen(AssetId),
+		/// An asset class was thawed.
+		AssetThawed(AssetId),
+		/// An asset class was destroyed.
+		Destroyed(AssetId),
+		/// The metadata of an asset was set. (asset_id, name, symbol, decimals)
+		MetadataSet(AssetId, Vec<u8>, Vec<u8>, u8),
+		/// The metadata of an asset was cleared.
+		MetadataCleared(AssetId),
+		/// A delegate was approved to transfer some assets. (asset_id, owner, delegate, amount)
+		ApprovedTransfer(AssetId, AccountId, AccountId, Balance),
+		/// An approval was cancelled. (asset_id, owner, delegate)
+		ApprovalCancelled(AssetId, AccountId, AccountId),
+		/// Some assets were transferred by an approved delegate.
+		/// (asset_id, owner, delegate, destination, amount)
+		TransferredApproved(AssetId, AccountId, AccountId, AccountId, Balance),
 	}
 }
 
@@ -225,17 +923,41 @@ decl_error! {
 		BalanceLow,
 		/// Balance should be non-zero
 		BalanceZero,
+		/// The signing account has no permission to do the operation.
+		NoPermission,
+		/// The given asset ID is unknown.
+		Unknown,
+		/// The origin account or the asset is frozen.
+		Frozen,
+		/// An arithmetic overflow occurred.
+		Overflow,
+		/// The minimum balance of an asset should be non-zero.
+		MinBalanceZero,
+		/// The given witness is below the number of accounts or approvals of the asset.
+		BadWitness,
+		/// The name or symbol is too long.
+		BadMetadata,
+		/// No approval exists that would allow the transfer.
+		Unapproved,
 	}
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as Assets {
-		/// The number of units of assets held by any given account.
-		Balances: map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => T::Balance;
+		/// Details of an asset class.
+		Asset get(fn asset): map hasher(blake2_128_concat) T::AssetId
+			=> Option<AssetDetails<T::Balance, T::AccountId, DepositBalanceOf<T>>>;
+		/// The holdings of a specific account for a specific asset.
+		Account: double_map hasher(blake2_128_concat) T::AssetId, hasher(blake2_128_concat) T::AccountId
+			=> AssetBalanceOf<T>;
+		/// Approved transfers of an asset, keyed by the owner and the delegate.
+		Approvals: double_map hasher(blake2_128_concat) T::AssetId,
+			hasher(blake2_128_concat) (T::AccountId, T::AccountId)
+			=> Option<Approval<T::Balance, DepositBalanceOf<T>>>;
+		/// The metadata of an asset class.
+		Metadata: map hasher(blake2_128_concat) T::AssetId => Option<AssetMetadata<DepositBalanceOf<T>>>;
 		/// The next asset identifier up for grabs.
 		NextAssetId get(fn next_asset_id): T::AssetId;
-		/// The total unit supply of an asset.
-		TotalSupply: map hasher(twox_64_concat) T::AssetId => T::Balance;
 	}
 }
 
@@ -245,12 +967,90 @@ impl<T: Trait> Module<T> {
 
 	/// Get the asset `id` balance of `who`.
 	pub fn balance(id: T::AssetId, who: T::AccountId) -> T::Balance {
-		<Balances<T>>::get((id, who))
+		Account::<T>::get(id, who).balance
 	}
 
 	/// Get the total supply of an asset `id`.
 	pub fn total_supply(id: T::AssetId) -> T::Balance {
-		<TotalSupply<T>>::get(id)
+		Asset::<T>::get(id).map_or(Zero::zero(), |details| details.supply)
+	}
+
+	/// Get the balances of the assets `ids` held by `who`, omitting the ones it doesn't hold.
+	pub fn account_balances(who: &T::AccountId, ids: Vec<T::AssetId>) -> Vec<(T::AssetId, T::Balance)> {
+		ids.into_iter()
+			.map(|id| (id, Account::<T>::get(id, who).balance))
+			.filter(|(_, balance)| !balance.is_zero())
+			.collect()
+	}
+
+	/// Move `amount` of asset `id` from `source` to `dest`, returning the amount actually moved.
+	///
+	/// If `source` would be left with less than the minimum balance, its entire balance is
+	/// moved and the account is reaped. If `dest` did not hold the asset before, its deposit is
+	/// reserved from `depositor`. Freezing is only respected if `check_frozen` is set.
+	fn do_transfer(
+		id: T::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		depositor: &T::AccountId,
+		check_frozen: bool,
+	) -> Result<T::Balance, DispatchError> {
+		ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+		let mut source_account = Account::<T>::get(id, source);
+		ensure!(source_account.balance >= amount, Error::<T>::BalanceLow);
+
+		Asset::<T>::try_mutate(id, |maybe_details| -> Result<T::Balance, DispatchError> {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			if check_frozen {
+				ensure!(!details.is_frozen && !source_account.is_frozen, Error::<T>::Frozen);
+			}
+			if source == dest { return Ok(amount) }
+
+			let mut amount = amount;
+			if source_account.balance - amount < details.min_balance {
+				amount = source_account.balance;
+			}
+
+			let mut dest_account = Account::<T>::get(id, dest);
+			let new_balance = dest_account.balance.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+			ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
+			if dest_account.balance.is_zero() {
+				dest_account = Self::new_account(depositor)?;
+				details.accounts = details.accounts.saturating_add(1);
+			}
+			dest_account.balance = new_balance;
+			Account::<T>::insert(id, dest, &dest_account);
+
+			source_account.balance -= amount;
+			if source_account.balance.is_zero() {
+				details.accounts = details.accounts.saturating_sub(1);
+				Self::reap_account(id, source, &source_account);
+			} else {
+				Account::<T>::insert(id, source, &source_account);
+			}
+			Ok(amount)
+		})
+	}
+
+	/// Create an empty holding, reserving its deposit from `depositor`.
+	fn new_account(depositor: &T::AccountId) -> Result<AssetBalanceOf<T>, DispatchError> {
+		let deposit = T::AccountDeposit::get();
+		T::Currency::reserve(depositor, deposit)?;
+		Ok(AssetBalance { balance: Zero::zero(), is_frozen: false, depositor: depositor.clone(), deposit })
+	}
+
+	/// Remove the holding of `who`, returning its deposit.
+	fn reap_account(id: T::AssetId, who: &T::AccountId, account: &AssetBalanceOf<T>) {
+		T::Currency::unreserve(&account.depositor, account.deposit);
+		Account::<T>::remove(id, who);
+	}
+
+	/// Note that one of the approvals of asset `id` was removed.
+	fn approval_removed(id: T::AssetId) {
+		Asset::<T>::mutate(id, |maybe_details| if let Some(details) = maybe_details {
+			details.approvals = details.approvals.saturating_sub(1);
+		});
 	}
 }
 
@@ -262,7 +1062,7 @@ mod tests {
 	use sp_core::H256;
 	// The testing primitives are very useful for avoiding having to work with signatures
 	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
-	use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+	use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup, BadOrigin}, testing::Header};
 
 	impl_outer_origin! {
 		pub enum Origin for Test  where system = frame_system {}
@@ -296,27 +1096,82 @@ mod tests {
 		type MaximumBlockLength = MaximumBlockLength;
 		type Version = ();
 		type ModuleToIndex = ();
-		type AccountData = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
 	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+	impl pallet_balances::Trait for Test {
+		type Balance = u64;
+		type DustRemoval = ();
+		type Event = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+	}
+	parameter_types! {
+		pub const AssetDepositBase: u64 = 10;
+		pub const MetadataDepositBase: u64 = 2;
+		pub const MetadataDepositPerByte: u64 = 1;
+		pub const ApprovalDeposit: u64 = 1;
+		pub const AccountDeposit: u64 = 1;
+		pub const StringLimit: u32 = 8;
+	}
 	impl Trait for Test {
 		type Event = ();
 		type Balance = u64;
 		type AssetId = u32;
+		type Currency = Balances;
+		type ForceOrigin = frame_system::EnsureRoot<u64>;
+		type AssetDepositBase = AssetDepositBase;
+		type MetadataDepositBase = MetadataDepositBase;
+		type MetadataDepositPerByte = MetadataDepositPerByte;
+		type ApprovalDeposit = ApprovalDeposit;
+		type AccountDeposit = AccountDeposit;
+		type StringLimit = StringLimit;
 	}
+	type System = frame_system::Module<Test>;
+	type Balances = pallet_balances::Module<Test>;
 	type Assets = Module<Test>;
 
 	// This function basically just builds a genesis storage key/value store according to
 	// our desired mockup.
 	fn new_test_ext() -> sp_io::TestExternalities {
-		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100), (3, 100)],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	/// Create asset 0 owned by account 1, with account 1 in every role, and mint 100 units
+	/// into account 1.
+	fn create_and_mint() {
+		assert_ok!(Assets::force_create(Origin::ROOT, 1, 1));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
 	}
 
 	#[test]
 	fn issuing_asset_units_to_issuer_should_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			create_and_mint();
+			assert_eq!(Assets::balance(0, 1), 100);
+		});
+	}
+
+	#[test]
+	fn creating_asset_should_reserve_deposit() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(Assets::create(Origin::signed(1), 2, 0), Error::<Test>::MinBalanceZero);
+			assert_ok!(Assets::create(Origin::signed(1), 2, 1));
+			assert_eq!(Balances::reserved_balance(1), 10);
+			let details = Assets::asset(0).unwrap();
+			assert_eq!(details.owner, 1);
+			assert_eq!((details.issuer, details.admin, details.freezer), (2, 2, 2));
+
+			assert_noop!(Assets::mint(Origin::signed(1), 0, 1, 100), Error::<Test>::NoPermission);
+			assert_ok!(Assets::mint(Origin::signed(2), 0, 1, 100));
 			assert_eq!(Assets::balance(0, 1), 100);
 		});
 	}
@@ -324,7 +1179,7 @@ mod tests {
 	#[test]
 	fn querying_total_supply_should_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			create_and_mint();
 			assert_eq!(Assets::balance(0, 1), 100);
 			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
 			assert_eq!(Assets::balance(0, 1), 50);
@@ -333,7 +1188,7 @@ mod tests {
 			assert_eq!(Assets::balance(0, 1), 50);
 			assert_eq!(Assets::balance(0, 2), 19);
 			assert_eq!(Assets::balance(0, 3), 31);
-			assert_ok!(Assets::destroy(Origin::signed(3), 0));
+			assert_ok!(Assets::burn(Origin::signed(1), 0, 3, u64::max_value()));
 			assert_eq!(Assets::total_supply(0), 69);
 		});
 	}
@@ -341,7 +1196,7 @@ mod tests {
 	#[test]
 	fn transferring_amount_above_available_balance_should_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			create_and_mint();
 			assert_eq!(Assets::balance(0, 1), 100);
 			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
 			assert_eq!(Assets::balance(0, 1), 50);
@@ -352,12 +1207,12 @@ mod tests {
 	#[test]
 	fn transferring_amount_more_than_available_balance_should_not_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			create_and_mint();
 			assert_eq!(Assets::balance(0, 1), 100);
 			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
 			assert_eq!(Assets::balance(0, 1), 50);
 			assert_eq!(Assets::balance(0, 2), 50);
-			assert_ok!(Assets::destroy(Origin::signed(1), 0));
+			assert_ok!(Assets::burn(Origin::signed(1), 0, 1, u64::max_value()));
 			assert_eq!(Assets::balance(0, 1), 0);
 			assert_noop!(Assets::transfer(Origin::signed(1), 0, 1, 50), Error::<Test>::BalanceLow);
 		});
@@ -366,7 +1221,7 @@ mod tests {
 	#[test]
 	fn transferring_less_than_one_unit_should_not_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			create_and_mint();
 			assert_eq!(Assets::balance(0, 1), 100);
 			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 0), Error::<Test>::AmountZero);
 		});
@@ -375,27 +1230,300 @@ mod tests {
 	#[test]
 	fn transferring_more_units_than_total_supply_should_not_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			create_and_mint();
 			assert_eq!(Assets::balance(0, 1), 100);
 			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 101), Error::<Test>::BalanceLow);
 		});
 	}
 
 	#[test]
-	fn destroying_asset_balance_with_positive_balance_should_work() {
+	fn min_balance_should_be_respected_and_dust_reaped() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
-			assert_eq!(Assets::balance(0, 1), 100);
-			assert_ok!(Assets::destroy(Origin::signed(1), 0));
+			assert_ok!(Assets::force_create(Origin::ROOT, 1, 10));
+			assert_noop!(Assets::mint(Origin::signed(1), 0, 1, 9), Error::<Test>::BalanceLow);
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+			assert_eq!(Assets::asset(0).unwrap().accounts, 1);
+
+			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 9), Error::<Test>::BalanceLow);
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 10));
+			assert_eq!(Assets::asset(0).unwrap().accounts, 2);
+
+			// Leaving less than the minimum balance moves the dust along.
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 85));
+			assert_eq!(Assets::balance(0, 1), 0);
+			assert_eq!(Assets::balance(0, 2), 100);
+			assert!(!Account::<Test>::contains_key(0, 1));
+			assert_eq!(Assets::asset(0).unwrap().accounts, 1);
+
+			// Burning down to less than the minimum balance burns the dust too.
+			assert_ok!(Assets::burn(Origin::signed(1), 0, 2, 91));
+			assert_eq!(Assets::balance(0, 2), 0);
+			assert_eq!(Assets::total_supply(0), 0);
+			assert_eq!(Assets::asset(0).unwrap().accounts, 0);
+		});
+	}
+
+	#[test]
+	fn burning_asset_balance_with_zero_balance_should_not_work() {
+		new_test_ext().execute_with(|| {
+			create_and_mint();
+			assert_eq!(Assets::balance(0, 2), 0);
+			assert_noop!(Assets::burn(Origin::signed(1), 0, 2, 10), Error::<Test>::BalanceZero);
+			assert_noop!(Assets::burn(Origin::signed(2), 0, 1, 10), Error::<Test>::NoPermission);
+		});
+	}
+
+	#[test]
+	fn force_transfer_should_work() {
+		new_test_ext().execute_with(|| {
+			create_and_mint();
+			assert_ok!(Assets::freeze(Origin::signed(1), 0, 1));
+			assert_noop!(
+				Assets::force_transfer(Origin::signed(2), 0, 1, 2, 50),
+				Error::<Test>::NoPermission
+			);
+			assert_ok!(Assets::force_transfer(Origin::signed(1), 0, 1, 2, 50));
+			assert_eq!(Assets::balance(0, 1), 50);
+			assert_eq!(Assets::balance(0, 2), 50);
+			// At most the whole balance is moved.
+			assert_ok!(Assets::force_transfer(Origin::signed(1), 0, 2, 3, 60));
+			assert_eq!(Assets::balance(0, 2), 0);
+			assert_eq!(Assets::balance(0, 3), 50);
+		});
+	}
+
+	#[test]
+	fn freezing_accounts_and_assets_should_work() {
+		new_test_ext().execute_with(|| {
+			create_and_mint();
+			assert_noop!(Assets::freeze(Origin::signed(1), 0, 2), Error::<Test>::BalanceZero);
+			assert_noop!(Assets::freeze(Origin::signed(2), 0, 1), Error::<Test>::NoPermission);
+
+			assert_ok!(Assets::freeze(Origin::signed(1), 0, 1));
+			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 50), Error::<Test>::Frozen);
+			assert_ok!(Assets::thaw(Origin::signed(1), 0, 1));
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+
+			assert_ok!(Assets::freeze_asset(Origin::signed(1), 0));
+			assert_noop!(Assets::transfer(Origin::signed(2), 0, 1, 10), Error::<Test>::Frozen);
+			// Frozen assets may still be minted.
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 3, 10));
+			assert_ok!(Assets::thaw_asset(Origin::signed(1), 0));
+			assert_ok!(Assets::transfer(Origin::signed(2), 0, 1, 10));
 		});
 	}
 
 	#[test]
-	fn destroying_asset_balance_with_zero_balance_should_not_work() {
+	fn team_and_ownership_changes_should_work() {
 		new_test_ext().execute_with(|| {
-			assert_ok!(Assets::issue(Origin::signed(1), 100));
+			assert_ok!(Assets::create(Origin::signed(1), 1, 1));
+			assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 4], vec![0u8; 2], 12));
+			assert_eq!(Balances::reserved_balance(1), 10 + 2 + 6);
+
+			assert_noop!(Assets::set_team(Origin::signed(2), 0, 2, 2, 2), Error::<Test>::NoPermission);
+			assert_ok!(Assets::set_team(Origin::signed(1), 0, 2, 3, 3));
+			assert_noop!(Assets::mint(Origin::signed(1), 0, 1, 10), Error::<Test>::NoPermission);
+			assert_ok!(Assets::mint(Origin::signed(2), 0, 1, 10));
+			assert_ok!(Assets::burn(Origin::signed(3), 0, 1, 5));
+
+			assert_noop!(
+				Assets::transfer_ownership(Origin::signed(2), 0, 2),
+				Error::<Test>::NoPermission
+			);
+			assert_ok!(Assets::transfer_ownership(Origin::signed(1), 0, 2));
+			assert_eq!(Assets::asset(0).unwrap().owner, 2);
+			assert_eq!(Balances::reserved_balance(1), 0);
+			// The deposits of the asset on top of the holder deposit of account 1.
+			assert_eq!(Balances::reserved_balance(2), 18 + 1);
+		});
+	}
+
+	#[test]
+	fn set_metadata_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 8], vec![0u8; 8], 12),
+				Error::<Test>::Unknown
+			);
+			assert_ok!(Assets::create(Origin::signed(1), 1, 1));
+			assert_noop!(
+				Assets::set_metadata(Origin::signed(2), 0, vec![0u8; 8], vec![0u8; 8], 12),
+				Error::<Test>::NoPermission
+			);
+			assert_noop!(
+				Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 9], vec![0u8; 8], 12),
+				Error::<Test>::BadMetadata
+			);
+
+			assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 8], vec![0u8; 8], 12));
+			assert_eq!(Balances::reserved_balance(1), 10 + 2 + 16);
+			// Shrinking the metadata returns part of the deposit.
+			assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 4], vec![0u8; 2], 12));
+			assert_eq!(Balances::reserved_balance(1), 10 + 2 + 6);
+			// Clearing it returns all of it.
+			assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![], vec![], 0));
+			assert_eq!(Balances::reserved_balance(1), 10);
+			assert!(!Metadata::<Test>::contains_key(0));
+		});
+	}
+
+	#[test]
+	fn approval_lifecycle_should_work() {
+		new_test_ext().execute_with(|| {
+			create_and_mint();
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 30));
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 20));
+			assert_eq!(Balances::reserved_balance(1), 1 + 1);
+			assert_eq!(Assets::asset(0).unwrap().approvals, 1);
+
+			assert_noop!(
+				Assets::transfer_approved(Origin::signed(3), 0, 1, 3, 10),
+				Error::<Test>::Unapproved
+			);
+			assert_noop!(
+				Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 51),
+				Error::<Test>::Unapproved
+			);
+			assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 40));
+			assert_eq!(Assets::balance(0, 1), 60);
+			assert_eq!(Assets::balance(0, 3), 40);
+			assert_eq!(Approvals::<Test>::get(0, (1, 2)).unwrap().amount, 10);
+
+			// Using up the approval returns the deposit, leaving those of the holdings of 1 and 3.
+			assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 10));
+			assert!(Approvals::<Test>::get(0, (1, 2)).is_none());
+			assert_eq!(Assets::asset(0).unwrap().approvals, 0);
+			assert_eq!(Balances::reserved_balance(1), 2);
+		});
+	}
+
+	#[test]
+	fn transfer_approved_should_not_sweep_dust_beyond_approval() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::ROOT, 1, 10));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 95));
+
+			// The remaining 5 units would be swept along, exceeding the approval.
+			assert_noop!(
+				Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 95),
+				Error::<Test>::Unapproved
+			);
+			assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 90));
+			assert_eq!(Assets::balance(0, 1), 10);
+			assert_eq!(Assets::balance(0, 3), 90);
+			assert_eq!(Approvals::<Test>::get(0, (1, 2)).unwrap().amount, 5);
+
+			// Once the approval covers the dust, it is moved along and used up.
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 5));
+			assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 5));
+			assert_eq!(Assets::balance(0, 1), 0);
+			assert_eq!(Assets::balance(0, 3), 100);
+			assert!(Approvals::<Test>::get(0, (1, 2)).is_none());
+		});
+	}
+
+	#[test]
+	fn cancel_approval_should_work() {
+		new_test_ext().execute_with(|| {
+			create_and_mint();
+			assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+			assert_noop!(Assets::cancel_approval(Origin::signed(1), 0, 3), Error::<Test>::Unapproved);
+			assert_ok!(Assets::cancel_approval(Origin::signed(1), 0, 2));
+			assert_eq!(Balances::reserved_balance(1), 1);
+			assert_eq!(Assets::asset(0).unwrap().approvals, 0);
+			assert_noop!(
+				Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 10),
+				Error::<Test>::Unapproved
+			);
+		});
+	}
+
+	#[test]
+	fn destroying_asset_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::create(Origin::signed(1), 1, 1));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+			assert_ok!(Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 4], vec![0u8; 2], 12));
+			assert_ok!(Assets::approve_transfer(Origin::signed(2), 0, 3, 50));
+
+			assert_noop!(Assets::destroy(Origin::signed(2), 0, 2, 1), Error::<Test>::NoPermission);
+			assert_noop!(Assets::destroy(Origin::signed(1), 0, 1, 1), Error::<Test>::BadWitness);
+			assert_noop!(Assets::destroy(Origin::signed(1), 0, 2, 0), Error::<Test>::BadWitness);
+			assert_ok!(Assets::destroy(Origin::signed(1), 0, 2, 1));
+
+			assert!(Assets::asset(0).is_none());
+			assert_eq!(Assets::balance(0, 1), 0);
 			assert_eq!(Assets::balance(0, 2), 0);
-			assert_noop!(Assets::destroy(Origin::signed(2), 0), Error::<Test>::BalanceZero);
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_noop!(Assets::destroy(Origin::ROOT, 0, 0, 0), Error::<Test>::Unknown);
+		});
+	}
+
+	#[test]
+	fn destroy_weight_depends_on_witnesses() {
+		use frame_support::weights::GetDispatchInfo;
+
+		let weight = |accounts, approvals| Call::<Test>::destroy(0, accounts, approvals)
+			.get_dispatch_info()
+			.weight;
+		assert_eq!(weight(0, 0), 100_000);
+		assert_eq!(weight(2, 0), 140_000);
+		assert_eq!(weight(2, 3), 200_000);
+	}
+
+	#[test]
+	fn holder_deposits_should_work() {
+		new_test_ext().execute_with(|| {
+			create_and_mint();
+			assert_eq!(Balances::reserved_balance(1), 1);
+
+			// The sender pays for the holding of the recipient, until it is reaped.
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+			assert_eq!(Balances::reserved_balance(1), 2);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_ok!(Assets::transfer(Origin::signed(2), 0, 1, 50));
+			assert_eq!(Balances::reserved_balance(1), 1);
+
+			// The admin pays when forcing a transfer.
+			assert_ok!(Assets::set_team(Origin::signed(1), 0, 1, 3, 1));
+			assert_ok!(Assets::force_transfer(Origin::signed(3), 0, 1, 2, 10));
+			assert_eq!(Balances::reserved_balance(3), 1);
+			assert_ok!(Assets::burn(Origin::signed(3), 0, 2, 10));
+			assert_eq!(Balances::reserved_balance(3), 0);
+
+			// New holdings can't be created without funds for the deposit.
+			assert_ok!(Assets::force_create(Origin::ROOT, 4, 1));
+			assert_noop!(
+				Assets::mint(Origin::signed(4), 1, 1, 10),
+				pallet_balances::Error::<Test, _>::InsufficientBalance
+			);
+		});
+	}
+
+	#[test]
+	fn force_origin_checks_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(Assets::force_create(Origin::signed(1), 1, 1), BadOrigin);
+			create_and_mint();
+			assert_ok!(Assets::destroy(Origin::ROOT, 0, 1, 0));
+			assert!(Assets::asset(0).is_none());
+		});
+	}
+
+	#[test]
+	fn account_balances_should_work() {
+		new_test_ext().execute_with(|| {
+			create_and_mint();
+			assert_ok!(Assets::force_create(Origin::ROOT, 1, 1));
+			assert_ok!(Assets::force_create(Origin::ROOT, 1, 1));
+			assert_ok!(Assets::mint(Origin::signed(1), 2, 1, 30));
+
+			assert_eq!(Assets::account_balances(&1, vec![0, 1, 2, 3]), vec![(0, 100), (2, 30)]);
+			assert_eq!(Assets::account_balances(&1, vec![2]), vec![(2, 30)]);
+			assert!(Assets::account_balances(&2, vec![0, 1, 2]).is_empty());
 		});
 	}
 }
@@ -0,0 +1,276 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An opt-in utility module for reporting equivocations.
+//!
+//! This module defines an offence type for BABE equivocations
+//! and some utility traits to wire together:
+//! - a key ownership proof system (e.g. to prove that a given authority was
+//! part of a session);
+//! - a system for reporting offences;
+//! - a system for submitting unsigned transactions;
+//!
+//! These can be used in an offchain context in order to submit equivocation
+//! reporting extrinsics (from the client that's importing BABE blocks).
+//! And in a runtime context, so that the BABE module can validate the
+//! equivocation proofs in the extrinsic and report the offences.
+//!
+//! IMPORTANT:
+//! When using this module for enabling equivocation reporting it is required
+//! that the `ValidateUnsigned` for the BABE pallet is used in the runtime
+//! definition.
+
+use sp_std::prelude::*;
+
+use codec::Encode;
+use frame_support::{debug, traits::KeyOwnerProofSystem};
+use frame_system::offchain::SubmitUnsignedTransaction;
+use sp_consensus_babe::{EquivocationProof, SlotNumber};
+use sp_runtime::{
+	transaction_validity::{
+		InvalidTransaction, TransactionLongevity, TransactionPriority, TransactionSource,
+		TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+	DispatchResult, Perbill, RuntimeDebug,
+};
+use sp_staking::{
+	offence::{Kind, Offence, OffenceError, ReportOffence},
+	SessionIndex,
+};
+
+use super::{Call, Module, Trait};
+
+/// A trait with utility methods for handling equivocation reports in BABE.
+/// The trait provides methods for reporting an offence triggered by a valid
+/// equivocation report, checking whether an offence was already reported, and
+/// for creating and submitting equivocation report extrinsics (useful only in
+/// offchain context).
+pub trait HandleEquivocation<T: Trait> {
+	/// Report an offence proved by the given reporters.
+	fn report_offence(
+		reporters: Vec<T::AccountId>,
+		offence: BabeEquivocationOffence<T::KeyOwnerIdentification>,
+	) -> Result<(), OffenceError>;
+
+	/// Returns true if all of the offenders at the given time slot have already been reported.
+	fn is_known_offence(
+		offenders: &[T::KeyOwnerIdentification],
+		time_slot: &SlotNumber,
+	) -> bool;
+
+	/// Create and dispatch an equivocation report extrinsic.
+	fn submit_equivocation_report(
+		equivocation_proof: EquivocationProof<T::Header>,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> DispatchResult;
+}
+
+impl<T: Trait> HandleEquivocation<T> for () {
+	fn report_offence(
+		_reporters: Vec<T::AccountId>,
+		_offence: BabeEquivocationOffence<T::KeyOwnerIdentification>,
+	) -> Result<(), OffenceError> {
+		Ok(())
+	}
+
+	fn is_known_offence(
+		_offenders: &[T::KeyOwnerIdentification],
+		_time_slot: &SlotNumber,
+	) -> bool {
+		true
+	}
+
+	fn submit_equivocation_report(
+		_equivocation_proof: EquivocationProof<T::Header>,
+		_key_owner_proof: T::KeyOwnerProof,
+	) -> DispatchResult {
+		Ok(())
+	}
+}
+
+/// Generic equivocation handler. This type implements `HandleEquivocation`
+/// using existing subsystems that are part of frame (type bounds described
+/// below) and will dispatch to them directly, it's only purpose is to wire all
+/// subsystems together.
+pub struct EquivocationHandler<R, S> {
+	_phantom: sp_std::marker::PhantomData<(R, S)>,
+}
+
+impl<R, S> Default for EquivocationHandler<R, S> {
+	fn default() -> Self {
+		Self {
+			_phantom: Default::default(),
+		}
+	}
+}
+
+impl<T, R, S> HandleEquivocation<T> for EquivocationHandler<R, S>
+where
+	T: Trait,
+	// A system for reporting offences after valid equivocation reports are
+	// processed.
+	R: ReportOffence<
+		T::AccountId,
+		T::KeyOwnerIdentification,
+		BabeEquivocationOffence<T::KeyOwnerIdentification>,
+	>,
+	// A transaction submitter for the unsigned equivocation report extrinsic.
+	S: SubmitUnsignedTransaction<T, <T as Trait>::Call>,
+{
+	fn report_offence(
+		reporters: Vec<T::AccountId>,
+		offence: BabeEquivocationOffence<T::KeyOwnerIdentification>,
+	) -> Result<(), OffenceError> {
+		R::report_offence(reporters, offence)
+	}
+
+	fn is_known_offence(
+		offenders: &[T::KeyOwnerIdentification],
+		time_slot: &SlotNumber,
+	) -> bool {
+		R::is_known_offence(offenders, time_slot)
+	}
+
+	fn submit_equivocation_report(
+		equivocation_proof: EquivocationProof<T::Header>,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> DispatchResult {
+		let call = Call::<T>::report_equivocation_unsigned(equivocation_proof, key_owner_proof);
+
+		match S::submit_unsigned(call) {
+			Ok(()) => debug::info!("Submitted BABE equivocation report."),
+			Err(e) => debug::error!("Error submitting equivocation report: {:?}", e),
+		}
+
+		Ok(())
+	}
+}
+
+/// A `ValidateUnsigned` implementation that restricts calls to `report_equivocation_unsigned`
+/// to local calls (i.e. extrinsics generated on this node) or that already in a block. This
+/// guarantees that only block authors can include unsigned equivocation reports.
+impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+		if let Call::report_equivocation_unsigned(equivocation_proof, key_owner_proof) = call {
+			// discard equivocation report not coming from the local node
+			match source {
+				TransactionSource::Local | TransactionSource::InBlock => { /* allowed */ }
+				_ => {
+					debug::warn!(
+						target: "babe",
+						"rejecting unsigned report equivocation transaction because it is not local/in-block."
+					);
+
+					return InvalidTransaction::Call.into();
+				}
+			}
+
+			// check report staleness
+			is_known_offence::<T>(equivocation_proof, key_owner_proof)?;
+
+			Ok(ValidTransaction {
+				priority: TransactionPriority::max_value(),
+				requires: vec![],
+				provides: vec![(
+					equivocation_proof.offender.clone(),
+					equivocation_proof.slot_number,
+				).encode()],
+				// the report stays valid until the offence is known, at which
+				// point it will be discarded as stale.
+				longevity: TransactionLongevity::max_value(),
+				// We don't propagate this. This can never be included on a remote node.
+				propagate: false,
+			})
+		} else {
+			InvalidTransaction::Call.into()
+		}
+	}
+}
+
+fn is_known_offence<T: Trait>(
+	equivocation_proof: &EquivocationProof<T::Header>,
+	key_owner_proof: &T::KeyOwnerProof,
+) -> Result<(), TransactionValidityError> {
+	// check the membership proof to extract the offender's id
+	let key = (
+		sp_consensus_babe::KEY_TYPE,
+		equivocation_proof.offender.clone(),
+	);
+
+	let offender = T::KeyOwnerProofSystem::check_proof(key, key_owner_proof.clone())
+		.ok_or(InvalidTransaction::BadProof)?;
+
+	// check if the offence has already been reported,
+	// and if so then we can discard the report.
+	let is_known_offence = T::HandleEquivocation::is_known_offence(
+		&[offender],
+		&equivocation_proof.slot_number,
+	);
+
+	if is_known_offence {
+		Err(InvalidTransaction::Stale.into())
+	} else {
+		Ok(())
+	}
+}
+
+/// A BABE equivocation offence report.
+///
+/// When a validator released two or more blocks at the same slot.
+#[derive(RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Clone, PartialEq, Eq))]
+pub struct BabeEquivocationOffence<FullIdentification> {
+	/// A babe slot number in which this incident happened.
+	pub slot: SlotNumber,
+	/// The session index in which the incident happened.
+	pub session_index: SessionIndex,
+	/// The size of the validator set at the time of the offence.
+	pub validator_set_count: u32,
+	/// The authority that produced the equivocation.
+	pub offender: FullIdentification,
+}
+
+impl<FullIdentification: Clone> Offence<FullIdentification>
+	for BabeEquivocationOffence<FullIdentification>
+{
+	const ID: Kind = *b"babe:equivocatio";
+	type TimeSlot = SlotNumber;
+
+	fn offenders(&self) -> Vec<FullIdentification> {
+		vec![self.offender.clone()]
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.session_index
+	}
+
+	fn validator_set_count(&self) -> u32 {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> Self::TimeSlot {
+		self.slot
+	}
+
+	fn slash_fraction(offenders_count: u32, validator_set_count: u32) -> Perbill {
+		// the formula is min((3k / n)^2, 1)
+		let x = Perbill::from_rational_approximation(3 * offenders_count, validator_set_count);
+		// _ ^ 2
+		x.square()
+	}
+}
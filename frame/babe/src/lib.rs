@@ -16,6 +16,10 @@
 
 //! Consensus extension module for BABE consensus. Collects on-chain randomness
 //! from VRF outputs and manages epoch transitions.
+//!
+//! It also handles equivocation reports: a valid report is checked against a
+//! key ownership proof and the offender is reported through the
+//! `HandleEquivocation` handler (see the `equivocation` module).
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![forbid(unused_must_use, unsafe_code, unused_variables, unused_must_use)]
@@ -24,33 +28,36 @@ use pallet_timestamp;
 
 use sp_std::{result, prelude::*};
 use frame_support::{
-	decl_storage, decl_module, traits::{FindAuthor, Get, Randomness as RandomnessT},
+	decl_storage, decl_module, decl_error, Parameter,
+	traits::{FindAuthor, Get, KeyOwnerProofSystem, Randomness as RandomnessT},
 	weights::{Weight, SimpleDispatchInfo, WeighData},
 };
+use frame_system::{ensure_none, ensure_signed};
 use sp_timestamp::OnTimestampSet;
-use sp_runtime::{generic::DigestItem, ConsensusEngineId, Perbill};
+use sp_runtime::{generic::DigestItem, ConsensusEngineId, DispatchResult, KeyTypeId};
 use sp_runtime::traits::{IsMember, SaturatedConversion, Saturating, Hash, One};
-use sp_staking::{
-	SessionIndex,
-	offence::{Offence, Kind},
-};
+use sp_session::{GetSessionNumber, GetValidatorCount};
 
 use codec::{Encode, Decode};
 use sp_inherents::{InherentIdentifier, InherentData, ProvideInherent, MakeFatalError};
 use sp_consensus_babe::{
-	BABE_ENGINE_ID, ConsensusLog, BabeAuthorityWeight, SlotNumber,
+	BABE_ENGINE_ID, ConsensusLog, BabeAuthorityWeight, EquivocationProof, SlotNumber,
 	inherents::{INHERENT_IDENTIFIER, BabeInherentData},
 	digests::{NextEpochDescriptor, RawPreDigest},
 };
 use sp_consensus_vrf::schnorrkel;
 pub use sp_consensus_babe::{AuthorityId, VRF_OUTPUT_LENGTH, RANDOMNESS_LENGTH, PUBLIC_KEY_LENGTH};
 
+mod equivocation;
+
 #[cfg(all(feature = "std", test))]
 mod tests;
 
 #[cfg(all(feature = "std", test))]
 mod mock;
 
+pub use equivocation::{BabeEquivocationOffence, EquivocationHandler, HandleEquivocation};
+
 pub trait Trait: pallet_timestamp::Trait {
 	/// The amount of time, in slots, that each epoch should last.
 	type EpochDuration: Get<SlotNumber>;
@@ -68,6 +75,33 @@ pub trait Trait: pallet_timestamp::Trait {
 	/// Typically, the `ExternalTrigger` type should be used. An internal trigger should only be used
 	/// when no other module is responsible for changing authority set.
 	type EpochChangeTrigger: EpochChangeTrigger;
+
+	/// The function call.
+	type Call: From<Call<Self>>;
+
+	/// The proof of key ownership, used for validating equivocation reports.
+	/// The proof must include the session index and validator count of the
+	/// session at which the equivocation occurred.
+	type KeyOwnerProof: Parameter + GetSessionNumber + GetValidatorCount;
+
+	/// The identification of a key owner, used when reporting equivocations.
+	type KeyOwnerIdentification: Parameter;
+
+	/// A system for proving ownership of keys, i.e. that a given key was part
+	/// of a validator set, needed for validating equivocation reports.
+	type KeyOwnerProofSystem: KeyOwnerProofSystem<
+		(KeyTypeId, AuthorityId),
+		Proof = Self::KeyOwnerProof,
+		IdentificationTuple = Self::KeyOwnerIdentification,
+	>;
+
+	/// The equivocation handling subsystem, defines methods to report an
+	/// offence (after the equivocation has been validated) and for submitting a
+	/// transaction to report an equivocation (from an offchain context).
+	/// NOTE: when enabling equivocation handling (i.e. this type isn't set to
+	/// `()`) you must use this pallet's `ValidateUnsigned` in the runtime
+	/// definition.
+	type HandleEquivocation: HandleEquivocation<Self>;
 }
 
 /// Trigger an epoch change, if any should take place.
@@ -166,9 +200,22 @@ decl_storage! {
 	}
 }
 
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// A key ownership proof provided as part of an equivocation report is invalid.
+		InvalidKeyOwnershipProof,
+		/// An equivocation proof provided as part of an equivocation report is invalid.
+		InvalidEquivocationProof,
+		/// A given equivocation report is valid but already previously reported.
+		DuplicateOffenceReport,
+	}
+}
+
 decl_module! {
 	/// The BABE Pallet
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
 		/// The number of **slots** that an epoch takes. We couple sessions to
 		/// epochs, i.e. we start a new session once the new epoch begins.
 		const EpochDuration: u64 = T::EpochDuration::get();
@@ -201,6 +248,48 @@ decl_module! {
 			// remove temporary "environment" entry from storage
 			Lateness::<T>::kill();
 		}
+
+		/// Report authority equivocation/misbehavior. This method will verify
+		/// the equivocation proof and validate the given key ownership proof
+		/// against the extracted offender. If both are valid, the offence will
+		/// be reported.
+		#[weight = SimpleDispatchInfo::FixedNormal(1_000_000)]
+		fn report_equivocation(
+			origin,
+			equivocation_proof: EquivocationProof<T::Header>,
+			key_owner_proof: T::KeyOwnerProof,
+		) {
+			let reporter = ensure_signed(origin)?;
+
+			Self::do_report_equivocation(
+				Some(reporter),
+				equivocation_proof,
+				key_owner_proof,
+			)?;
+		}
+
+		/// Report authority equivocation/misbehavior. This method will verify
+		/// the equivocation proof and validate the given key ownership proof
+		/// against the extracted offender. If both are valid, the offence will
+		/// be reported.
+		///
+		/// This extrinsic must be called unsigned and it is expected that only
+		/// block authors will call it (validated in `ValidateUnsigned`), the
+		/// offence is reported without any reporters.
+		#[weight = SimpleDispatchInfo::FixedOperational(1_000_000)]
+		fn report_equivocation_unsigned(
+			origin,
+			equivocation_proof: EquivocationProof<T::Header>,
+			key_owner_proof: T::KeyOwnerProof,
+		) {
+			ensure_none(origin)?;
+
+			Self::do_report_equivocation(
+				None,
+				equivocation_proof,
+				key_owner_proof,
+			)?;
+		}
 	}
 }
 
@@ -252,52 +341,6 @@ impl<T: Trait> pallet_session::ShouldEndSession<T::BlockNumber> for Module<T> {
 	}
 }
 
-// TODO [slashing]: @marcio use this, remove the dead_code annotation.
-/// A BABE equivocation offence report.
-///
-/// When a validator released two or more blocks at the same slot.
-struct BabeEquivocationOffence<FullIdentification> {
-	/// A babe slot number in which this incident happened.
-	slot: u64,
-	/// The session index in which the incident happened.
-	session_index: SessionIndex,
-	/// The size of the validator set at the time of the offence.
-	validator_set_count: u32,
-	/// The authority that produced the equivocation.
-	offender: FullIdentification,
-}
-
-impl<FullIdentification: Clone> Offence<FullIdentification> for BabeEquivocationOffence<FullIdentification> {
-	const ID: Kind = *b"babe:equivocatio";
-	type TimeSlot = u64;
-
-	fn offenders(&self) -> Vec<FullIdentification> {
-		vec![self.offender.clone()]
-	}
-
-	fn session_index(&self) -> SessionIndex {
-		self.session_index
-	}
-
-	fn validator_set_count(&self) -> u32 {
-		self.validator_set_count
-	}
-
-	fn time_slot(&self) -> Self::TimeSlot {
-		self.slot
-	}
-
-	fn slash_fraction(
-		offenders_count: u32,
-		validator_set_count: u32,
-	) -> Perbill {
-		// the formula is min((3k / n)^2, 1)
-		let x = Perbill::from_rational_approximation(3 * offenders_count, validator_set_count);
-		// _ ^ 2
-		x.square()
-	}
-}
-
 impl<T: Trait> Module<T> {
 	/// Determine the BABE slot duration based on the Timestamp module configuration.
 	pub fn slot_duration() -> T::Moment {
@@ -504,6 +547,61 @@ impl<T: Trait> Module<T> {
 			Authorities::put(authorities);
 		}
 	}
+
+	fn do_report_equivocation(
+		reporter: Option<T::AccountId>,
+		equivocation_proof: EquivocationProof<T::Header>,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> DispatchResult {
+		let offender = equivocation_proof.offender.clone();
+		let slot_number = equivocation_proof.slot_number;
+
+		// validate the equivocation proof (check that both headers target the
+		// same slot and that the seals are valid and signed by the offender).
+		sp_consensus_babe::check_equivocation_proof(equivocation_proof)
+			.map_err(|_| Error::<T>::InvalidEquivocationProof)?;
+
+		let validator_set_count = key_owner_proof.validator_count();
+		let session_index = key_owner_proof.session();
+
+		// sessions are coupled to epochs, so the epoch of the equivocation
+		// slot must match the session of the key ownership proof.
+		let epoch_index = slot_number.saturating_sub(GenesisSlot::get()) / T::EpochDuration::get();
+
+		if epoch_index != session_index as u64 {
+			Err(Error::<T>::InvalidKeyOwnershipProof)?
+		}
+
+		// validate the key ownership proof extracting the id of the offender.
+		let offender = T::KeyOwnerProofSystem::check_proof(
+			(sp_consensus_babe::KEY_TYPE, offender),
+			key_owner_proof,
+		).ok_or(Error::<T>::InvalidKeyOwnershipProof)?;
+
+		// report to the offences module rewarding the sender.
+		T::HandleEquivocation::report_offence(
+			reporter.into_iter().collect(),
+			BabeEquivocationOffence {
+				slot: slot_number,
+				session_index,
+				validator_set_count,
+				offender,
+			},
+		).map_err(|_| Error::<T>::DuplicateOffenceReport)?;
+
+		Ok(())
+	}
+
+	/// Submits an extrinsic to report an equivocation. This method will create
+	/// an unsigned extrinsic with a call to `report_equivocation_unsigned` and
+	/// will push the transaction to the pool. Only useful in an offchain context.
+	pub fn submit_unsigned_equivocation_report(
+		equivocation_proof: EquivocationProof<T::Header>,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> Option<()> {
+		T::HandleEquivocation::submit_equivocation_report(equivocation_proof, key_owner_proof)
+			.ok()
+	}
 }
 
 impl<T: Trait> OnTimestampSet<T::Moment> for Module<T> {
@@ -16,31 +16,45 @@
 
 //! Test utilities
 
-use codec::Encode;
-use super::{Trait, Module, GenesisConfig, CurrentSlot};
+use std::cell::RefCell;
+
+use codec::{Encode, Decode};
+use super::{
+	Trait, Module, GenesisConfig, CurrentSlot, AuthorityId, BabeEquivocationOffence,
+	EquivocationHandler,
+};
 use sp_runtime::{
-	Perbill, impl_opaque_keys,
-	testing::{Header, UintAuthorityId, Digest, DigestItem},
-	traits::IdentityLookup,
+	Perbill, KeyTypeId, impl_opaque_keys,
+	testing::{Header, UintAuthorityId, Digest, DigestItem, TestXt},
+	traits::{Header as _, IdentityLookup},
 };
 use frame_system::InitKind;
 use frame_support::{
-	impl_outer_origin, parameter_types, StorageValue,
-	traits::OnInitialize,
+	impl_outer_dispatch, impl_outer_origin, parameter_types, StorageValue,
+	traits::{KeyOwnerProofSystem, OnInitialize},
 	weights::Weight,
 };
 use sp_io;
-use sp_core::H256;
+use sp_core::{H256, Pair};
+use sp_consensus_babe::{AuthorityPair, EquivocationProof, SlotNumber};
 use sp_consensus_vrf::schnorrkel::{RawVRFOutput, RawVRFProof};
+use sp_session::{GetSessionNumber, GetValidatorCount, ValidatorCount};
+use sp_staking::{SessionIndex, offence::{ReportOffence, OffenceError}};
 
 impl_outer_origin!{
 	pub enum Origin for Test  where system = frame_system {}
 }
 
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		babe::Babe,
+	}
+}
+
 type DummyValidatorId = u64;
 
 // Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Decode, Encode)]
 pub struct Test;
 
 parameter_types! {
@@ -58,7 +72,7 @@ impl frame_system::Trait for Test {
 	type Origin = Origin;
 	type Index = u64;
 	type BlockNumber = u64;
-	type Call = ();
+	type Call = Call;
 	type Hash = H256;
 	type Version = ();
 	type Hashing = sp_runtime::traits::BlakeTwo256;
@@ -104,12 +118,110 @@ impl Trait for Test {
 	type EpochDuration = EpochDuration;
 	type ExpectedBlockTime = ExpectedBlockTime;
 	type EpochChangeTrigger = crate::ExternalTrigger;
+	type Call = Call;
+	type KeyOwnerProof = TestKeyOwnerProof;
+	type KeyOwnerIdentification = AuthorityId;
+	type KeyOwnerProofSystem = TestKeyOwnerProofSystem;
+	type HandleEquivocation = EquivocationHandler<OffenceHandler, SubmitTransaction>;
+}
+
+/// An extrinsic type used for tests.
+pub type Extrinsic = TestXt<Call, ()>;
+type SubmitTransaction = frame_system::offchain::TransactionSubmitter<(), Call, Extrinsic>;
+type Offence = BabeEquivocationOffence<AuthorityId>;
+
+thread_local! {
+	pub static OFFENCES: RefCell<Vec<(Vec<u64>, Offence)>> = RefCell::new(vec![]);
+}
+
+/// A mock offence report handler.
+pub struct OffenceHandler;
+impl ReportOffence<u64, AuthorityId, Offence> for OffenceHandler {
+	fn report_offence(reporters: Vec<u64>, offence: Offence) -> Result<(), OffenceError> {
+		if OFFENCES.with(|l| l.borrow().iter().any(|(_, o)| o == &offence)) {
+			return Err(OffenceError::DuplicateReport);
+		}
+		OFFENCES.with(|l| l.borrow_mut().push((reporters, offence)));
+		Ok(())
+	}
+
+	fn is_known_offence(offenders: &[AuthorityId], time_slot: &SlotNumber) -> bool {
+		OFFENCES.with(|l| offenders.iter().all(|offender|
+			l.borrow().iter().any(|(_, o)| &o.offender == offender && &o.slot == time_slot)
+		))
+	}
+}
+
+/// A mock proof of key ownership, proving that the key was part of the
+/// authority set at the given session.
+#[derive(Clone, PartialEq, Eq, Debug, Decode, Encode)]
+pub struct TestKeyOwnerProof {
+	pub session: SessionIndex,
+	pub validator_count: ValidatorCount,
+}
+
+impl GetSessionNumber for TestKeyOwnerProof {
+	fn session(&self) -> SessionIndex {
+		self.session
+	}
+}
+
+impl GetValidatorCount for TestKeyOwnerProof {
+	fn validator_count(&self) -> ValidatorCount {
+		self.validator_count
+	}
+}
+
+/// A mock key ownership proof system which proves membership of keys in the
+/// current authority set at the current session.
+pub struct TestKeyOwnerProofSystem;
+impl KeyOwnerProofSystem<(KeyTypeId, AuthorityId)> for TestKeyOwnerProofSystem {
+	type Proof = TestKeyOwnerProof;
+	type IdentificationTuple = AuthorityId;
+
+	fn prove(key: (KeyTypeId, AuthorityId)) -> Option<Self::Proof> {
+		let authorities = Babe::authorities();
+		if key.0 != sp_consensus_babe::KEY_TYPE || !authorities.iter().any(|(id, _)| id == &key.1) {
+			return None;
+		}
+
+		Some(TestKeyOwnerProof {
+			session: Session::current_index(),
+			validator_count: authorities.len() as ValidatorCount,
+		})
+	}
+
+	fn check_proof(key: (KeyTypeId, AuthorityId), proof: Self::Proof) -> Option<AuthorityId> {
+		if Self::prove(key.clone())? == proof {
+			Some(key.1)
+		} else {
+			None
+		}
+	}
 }
 
 pub fn new_test_ext(authorities: Vec<DummyValidatorId>) -> sp_io::TestExternalities {
+	new_test_ext_with_authorities(
+		authorities.into_iter().map(|a| UintAuthorityId(a).to_public_key()).collect(),
+	)
+}
+
+pub fn new_test_ext_with_pairs(
+	authorities_len: usize,
+) -> (Vec<AuthorityPair>, sp_io::TestExternalities) {
+	let pairs = (0..authorities_len)
+		.map(|i| AuthorityPair::from_seed(&[i as u8; 32]))
+		.collect::<Vec<_>>();
+
+	let public = pairs.iter().map(|p| p.public()).collect();
+
+	(pairs, new_test_ext_with_authorities(public))
+}
+
+pub fn new_test_ext_with_authorities(authorities: Vec<AuthorityId>) -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	GenesisConfig {
-		authorities: authorities.into_iter().map(|a| (UintAuthorityId(a).to_public_key(), 1)).collect(),
+		authorities: authorities.into_iter().map(|a| (a, 1)).collect(),
 	}.assimilate_storage::<Test>(&mut t).unwrap();
 	t.into()
 }
@@ -152,6 +264,61 @@ pub fn make_pre_digest(
 	Digest { logs: vec![log] }
 }
 
+/// Creates an equivocation at the current block, by generating two headers
+/// for the given slot which are signed by the given authority.
+pub fn generate_equivocation_proof(
+	offender_authority_index: u32,
+	offender_authority_pair: &AuthorityPair,
+	slot_number: SlotNumber,
+) -> EquivocationProof<Header> {
+	let current_block = System::block_number();
+
+	let make_header = |state_root| {
+		let pre_digest = make_pre_digest(
+			offender_authority_index,
+			slot_number,
+			RawVRFOutput([1; 32]),
+			RawVRFProof([0xff; 64]),
+		);
+
+		Header::new(
+			current_block,
+			Default::default(),
+			state_root,
+			System::parent_hash(),
+			pre_digest,
+		)
+	};
+
+	// sign the header prehash, adding it to the block as the seal digest item
+	let seal_header = |header: &mut Header| {
+		let prehash = header.hash();
+		let seal = <DigestItem as sp_consensus_babe::digests::CompatibleDigestItem>::babe_seal(
+			offender_authority_pair.sign(prehash.as_ref()),
+		);
+		header.digest_mut().push(seal);
+	};
+
+	// generate two headers at the given slot, which only differ in their state root
+	let mut h1 = make_header(H256::repeat_byte(1));
+	let mut h2 = make_header(H256::repeat_byte(2));
+
+	// sign them with the offender's key
+	seal_header(&mut h1);
+	seal_header(&mut h2);
+
+	EquivocationProof {
+		slot_number,
+		offender: offender_authority_pair.public(),
+		first_header: h1,
+		second_header: h2,
+	}
+}
+
+pub fn key_owner_proof(pair: &AuthorityPair) -> TestKeyOwnerProof {
+	TestKeyOwnerProofSystem::prove((sp_consensus_babe::KEY_TYPE, pair.public())).unwrap()
+}
+
 pub type System = frame_system::Module<Test>;
 pub type Babe = Module<Test>;
 pub type Session = pallet_session::Module<Test>;
@@ -18,9 +18,21 @@
 
 use super::*;
 use mock::*;
-use frame_support::traits::OnFinalize;
+use codec::Encode;
+use frame_support::{
+	assert_err, assert_ok,
+	traits::OnFinalize,
+	unsigned::ValidateUnsigned,
+};
+use frame_system::RawOrigin;
 use pallet_session::ShouldEndSession;
+use sp_core::Pair;
+use sp_consensus_babe::AuthorityPair;
 use sp_consensus_vrf::schnorrkel::{RawVRFOutput, RawVRFProof};
+use sp_runtime::{
+	traits::Header as _,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+};
 
 const EMPTY_RANDOMNESS: [u8; 32] = [
 	74, 25, 49, 128, 53, 97, 244, 49,
@@ -134,3 +146,205 @@ fn can_predict_next_epoch_change() {
 		assert_eq!(Babe::next_expected_epoch_change(System::block_number()), Some(5 + 2));
 	})
 }
+
+#[test]
+fn report_equivocation_current_session_works() {
+	let (pairs, mut ext) = new_test_ext_with_pairs(3);
+
+	ext.execute_with(|| {
+		go_to_block(1, 1);
+
+		// we will use the validator at index 1 as the offending authority
+		let offending_authority_index = 1;
+		let offending_authority_pair = &pairs[offending_authority_index];
+
+		let equivocation_proof = generate_equivocation_proof(
+			offending_authority_index as u32,
+			offending_authority_pair,
+			CurrentSlot::get(),
+		);
+
+		assert_ok!(Babe::report_equivocation(
+			Origin::signed(42),
+			equivocation_proof,
+			key_owner_proof(offending_authority_pair),
+		));
+
+		OFFENCES.with(|l| {
+			let offences = l.borrow();
+			assert_eq!(offences.len(), 1);
+			assert_eq!(offences[0].0, vec![42]);
+			assert_eq!(
+				offences[0].1,
+				BabeEquivocationOffence {
+					slot: 1,
+					session_index: 0,
+					validator_set_count: 3,
+					offender: offending_authority_pair.public(),
+				},
+			);
+		});
+	});
+}
+
+#[test]
+fn report_equivocation_invalid_key_owner_proof() {
+	let (pairs, mut ext) = new_test_ext_with_pairs(3);
+
+	ext.execute_with(|| {
+		go_to_block(1, 1);
+
+		let offending_authority_pair = &pairs[1];
+		let equivocation_proof = generate_equivocation_proof(
+			1,
+			offending_authority_pair,
+			CurrentSlot::get(),
+		);
+
+		// a key ownership proof for a different session is rejected
+		let mut proof = key_owner_proof(offending_authority_pair);
+		proof.session = 1;
+
+		assert_err!(
+			Babe::report_equivocation(Origin::signed(42), equivocation_proof, proof),
+			Error::<Test>::InvalidKeyOwnershipProof,
+		);
+
+		// an equivocation by an authority which isn't part of the set
+		// can't have a valid key ownership proof
+		let unknown_pair = AuthorityPair::from_seed(&[42; 32]);
+		let equivocation_proof = generate_equivocation_proof(
+			1,
+			&unknown_pair,
+			CurrentSlot::get(),
+		);
+
+		assert_err!(
+			Babe::report_equivocation(
+				Origin::signed(42),
+				equivocation_proof,
+				key_owner_proof(offending_authority_pair),
+			),
+			Error::<Test>::InvalidKeyOwnershipProof,
+		);
+
+		assert!(OFFENCES.with(|l| l.borrow().is_empty()));
+	});
+}
+
+#[test]
+fn report_equivocation_invalid_equivocation_proof() {
+	let (pairs, mut ext) = new_test_ext_with_pairs(3);
+
+	ext.execute_with(|| {
+		go_to_block(1, 1);
+
+		let offending_authority_pair = &pairs[1];
+		let slot = CurrentSlot::get();
+
+		let assert_invalid_equivocation = |equivocation_proof| {
+			assert_err!(
+				Babe::report_equivocation(
+					Origin::signed(42),
+					equivocation_proof,
+					key_owner_proof(offending_authority_pair),
+				),
+				Error::<Test>::InvalidEquivocationProof,
+			)
+		};
+
+		// both headers are the same
+		let mut equivocation_proof = generate_equivocation_proof(1, offending_authority_pair, slot);
+		equivocation_proof.second_header = equivocation_proof.first_header.clone();
+		assert_invalid_equivocation(equivocation_proof);
+
+		// the slot in the proof doesn't match the slot in the headers
+		let mut equivocation_proof = generate_equivocation_proof(1, offending_authority_pair, slot);
+		equivocation_proof.slot_number = slot + 1;
+		assert_invalid_equivocation(equivocation_proof);
+
+		// the headers target different slots
+		let mut equivocation_proof = generate_equivocation_proof(1, offending_authority_pair, slot);
+		equivocation_proof.second_header =
+			generate_equivocation_proof(1, offending_authority_pair, slot + 1).second_header;
+		assert_invalid_equivocation(equivocation_proof);
+
+		// the headers claim to be authored by different authorities
+		let mut equivocation_proof = generate_equivocation_proof(1, offending_authority_pair, slot);
+		equivocation_proof.second_header =
+			generate_equivocation_proof(2, offending_authority_pair, slot).second_header;
+		assert_invalid_equivocation(equivocation_proof);
+
+		// the headers are signed by a different authority than the offender
+		let mut equivocation_proof = generate_equivocation_proof(1, &pairs[2], slot);
+		equivocation_proof.offender = offending_authority_pair.public();
+		assert_invalid_equivocation(equivocation_proof);
+
+		// the second header is not sealed
+		let mut equivocation_proof = generate_equivocation_proof(1, offending_authority_pair, slot);
+		equivocation_proof.second_header.digest_mut().pop();
+		assert_invalid_equivocation(equivocation_proof);
+	});
+}
+
+#[test]
+fn report_equivocation_validate_unsigned_prevents_duplicates() {
+	let (pairs, mut ext) = new_test_ext_with_pairs(3);
+
+	ext.execute_with(|| {
+		go_to_block(1, 1);
+
+		let offending_authority_pair = &pairs[1];
+		let equivocation_proof = generate_equivocation_proof(
+			1,
+			offending_authority_pair,
+			CurrentSlot::get(),
+		);
+		let key_owner_proof = key_owner_proof(offending_authority_pair);
+
+		let call = crate::Call::report_equivocation_unsigned(
+			equivocation_proof.clone(),
+			key_owner_proof.clone(),
+		);
+
+		// only local/in-block reports are allowed
+		assert_eq!(
+			<Babe as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into(),
+		);
+
+		let tx_tag = (offending_authority_pair.public(), CurrentSlot::get());
+		let validity = <Babe as ValidateUnsigned>::validate_unsigned(
+			TransactionSource::Local,
+			&call,
+		).unwrap();
+		assert_eq!(validity.provides, vec![tx_tag.encode()]);
+		assert!(!validity.propagate);
+
+		// the report is still valid during block import
+		assert_ok!(<Babe as ValidateUnsigned>::pre_dispatch(&call));
+
+		// we submit the report
+		assert_ok!(Babe::report_equivocation_unsigned(
+			RawOrigin::None.into(),
+			equivocation_proof.clone(),
+			key_owner_proof.clone(),
+		));
+
+		// the report should now be considered stale and the transaction is invalid
+		assert_eq!(
+			<Babe as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &call),
+			InvalidTransaction::Stale.into(),
+		);
+		assert_err!(
+			<Babe as ValidateUnsigned>::pre_dispatch(&call),
+			InvalidTransaction::Stale,
+		);
+
+		// and reporting it again through the signed extrinsic fails
+		assert_err!(
+			Babe::report_equivocation(Origin::signed(42), equivocation_proof, key_owner_proof),
+			Error::<Test>::DuplicateOffenceReport,
+		);
+	});
+}
@@ -123,6 +123,7 @@ use frame_support::dispatch::{DispatchResult, Dispatchable};
 use frame_support::{
 	Parameter, decl_module, decl_event, decl_storage, decl_error, storage::child,
 	parameter_types, IsSubType,
+	metadata::TypeInfo,
 	weights::DispatchInfo,
 };
 use frame_support::traits::{OnUnbalanced, Currency, Get, Time, Randomness};
@@ -145,7 +146,8 @@ pub trait ComputeDispatchFee<Call, Balance> {
 
 /// Information for managing an account and its sub trie abstraction.
 /// This is the required info to cache for an account
-#[derive(Encode, Decode, RuntimeDebug)]
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo)]
+#[type_info(skip_type_params(T))]
 pub enum ContractInfo<T: Trait> {
 	Alive(AliveContractInfo<T>),
 	Tombstone(TombstoneContractInfo<T>),
@@ -208,7 +210,7 @@ pub type AliveContractInfo<T> =
 
 /// Information for managing an account and its sub trie abstraction.
 /// This is the required info to cache for an account.
-#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub struct RawAliveContractInfo<CodeHash, Balance, BlockNumber> {
 	/// Unique ID for the subtree encoded as a bytes vector.
 	pub trie_id: TrieId,
@@ -240,7 +242,8 @@ pub(crate) fn trie_unique_id(trie_id: &[u8]) -> child::ChildInfo {
 pub type TombstoneContractInfo<T> =
 	RawTombstoneContractInfo<<T as frame_system::Trait>::Hash, <T as frame_system::Trait>::Hashing>;
 
-#[derive(Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[derive(Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[type_info(skip_type_params(Hasher))]
 pub struct RawTombstoneContractInfo<H, Hasher>(H, PhantomData<Hasher>);
 
 impl<H, Hasher> RawTombstoneContractInfo<H, Hasher>
@@ -974,7 +977,7 @@ impl<T: Trait> Config<T> {
 
 /// Definition of the cost schedule and other parameterizations for wasm vm.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub struct Schedule {
 	/// Version of the schedule.
 	pub version: u32,
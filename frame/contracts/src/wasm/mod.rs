@@ -24,6 +24,7 @@ use crate::gas::GasMeter;
 
 use sp_std::prelude::*;
 use codec::{Encode, Decode};
+use frame_support::metadata::TypeInfo;
 use sp_sandbox;
 
 #[macro_use]
@@ -38,7 +39,7 @@ use self::code_cache::load as load_code;
 pub use self::code_cache::save as save_code;
 
 /// A prepared wasm module ready for execution.
-#[derive(Clone, Encode, Decode)]
+#[derive(Clone, Encode, Decode, TypeInfo)]
 pub struct PrefabWasmModule {
 	/// Version of the schedule with which the code was instrumented.
 	#[codec(compact)]
@@ -82,16 +82,6 @@ fn account_vote<T: Trait>() -> AccountVote<BalanceOf<T>> {
 	}
 }
 
-fn open_activate_proxy<T: Trait>(u: u32) -> Result<T::AccountId, &'static str> {
-	let caller = funded_account::<T>("caller", u);
-	let proxy = funded_account::<T>("proxy", u);
-
-	Democracy::<T>::open_proxy(RawOrigin::Signed(proxy.clone()).into(), caller.clone())?;
-	Democracy::<T>::activate_proxy(RawOrigin::Signed(caller).into(), proxy.clone())?;
-
-	Ok(proxy)
-}
-
 benchmarks! {
 	_ { }
 
@@ -137,22 +127,6 @@ benchmarks! {
 
 	}: _(RawOrigin::Signed(caller), referendum_index, account_vote)
 
-	proxy_vote {
-		let r in 1 .. MAX_REFERENDUMS;
-
-		let caller = funded_account::<T>("caller", r);
-		let proxy = open_activate_proxy::<T>(r)?;
-		let account_vote = account_vote::<T>();
-
-		for i in 0 .. r {
-			let ref_idx = add_referendum::<T>(i)?;
-			Democracy::<T>::vote(RawOrigin::Signed(caller.clone()).into(), ref_idx, account_vote.clone())?;
-		}
-
-		let referendum_index = r - 1;
-
-	}: _(RawOrigin::Signed(proxy), referendum_index, account_vote)
-
 	emergency_cancel {
 		let u in 1 .. MAX_USERS;
 
@@ -244,38 +218,6 @@ benchmarks! {
 		let referendum_index = add_referendum::<T>(u)?;
 	}: _(RawOrigin::Root, referendum_index)
 
-	open_proxy {
-		let u in 1 .. MAX_USERS;
-
-		let caller: T::AccountId = funded_account::<T>("caller", u);
-		let proxy: T::AccountId = funded_account::<T>("proxy", u);
-
-	}: _(RawOrigin::Signed(proxy), caller)
-
-	activate_proxy {
-		let u in 1 .. MAX_USERS;
-
-		let caller: T::AccountId = funded_account::<T>("caller", u);
-		let proxy: T::AccountId = funded_account::<T>("proxy", u);
-		Democracy::<T>::open_proxy(RawOrigin::Signed(proxy.clone()).into(), caller.clone())?;
-
-	}: _(RawOrigin::Signed(caller), proxy)
-
-	close_proxy {
-		let u in 1 .. MAX_USERS;
-
-		let proxy = open_activate_proxy::<T>(u)?;
-
-	}: _(RawOrigin::Signed(proxy))
-
-	deactivate_proxy {
-		let u in 1 .. MAX_USERS;
-
-		let caller = funded_account::<T>("caller", u);
-		let proxy = open_activate_proxy::<T>(u)?;
-
-	}: _(RawOrigin::Signed(caller), proxy)
-
 	delegate {
 		let u in 1 .. MAX_USERS;
 
@@ -406,44 +348,6 @@ benchmarks! {
 
 	}: _(RawOrigin::Signed(caller), other, referendum_index)
 
-	proxy_delegate {
-		let u in 1 .. MAX_USERS;
-
-		let other: T::AccountId = account("other", u, SEED);
-		let proxy = open_activate_proxy::<T>(u)?;
-		let conviction = Conviction::Locked1x;
-		let balance = 1u32;
-
-	}: _(RawOrigin::Signed(proxy), other, conviction, balance.into())
-
-	proxy_undelegate {
-		let r in 1 .. MAX_REFERENDUMS;
-
-		let other = funded_account::<T>("other", 0);
-		let account_vote = account_vote::<T>();
-
-		for i in 0 .. r {
-			let ref_idx = add_referendum::<T>(i)?;
-			Democracy::<T>::vote(RawOrigin::Signed(other.clone()).into(), ref_idx, account_vote.clone())?;
-		}
-
-		let proxy = open_activate_proxy::<T>(r)?;
-		let conviction = Conviction::Locked1x;
-		let balance = 1u32;
-		Democracy::<T>::proxy_delegate(RawOrigin::Signed(proxy.clone()).into(), other, conviction, balance.into())?;
-
-	}: _(RawOrigin::Signed(proxy))
-
-	proxy_remove_vote {
-		let u in 1 .. MAX_USERS;
-
-		let referendum_index = add_referendum::<T>(u)?;
-		let account_vote = account_vote::<T>();
-		let proxy = open_activate_proxy::<T>(u)?;
-
-		Democracy::<T>::proxy_vote(RawOrigin::Signed(proxy.clone()).into(), referendum_index, account_vote)?;
-
-	}: _(RawOrigin::Signed(proxy), referendum_index)
 }
 
 #[cfg(test)]
@@ -458,7 +362,6 @@ mod tests {
 			assert_ok!(test_benchmark_propose::<Test>());
 			assert_ok!(test_benchmark_second::<Test>());
 			assert_ok!(test_benchmark_vote::<Test>());
-			assert_ok!(test_benchmark_proxy_vote::<Test>());
 			assert_ok!(test_benchmark_emergency_cancel::<Test>());
 			assert_ok!(test_benchmark_external_propose::<Test>());
 			assert_ok!(test_benchmark_external_propose_majority::<Test>());
@@ -467,10 +370,6 @@ mod tests {
 			assert_ok!(test_benchmark_veto_external::<Test>());
 			assert_ok!(test_benchmark_cancel_referendum::<Test>());
 			assert_ok!(test_benchmark_cancel_queued::<Test>());
-			assert_ok!(test_benchmark_open_proxy::<Test>());
-			assert_ok!(test_benchmark_activate_proxy::<Test>());
-			assert_ok!(test_benchmark_close_proxy::<Test>());
-			assert_ok!(test_benchmark_deactivate_proxy::<Test>());
 			assert_ok!(test_benchmark_delegate::<Test>());
 			assert_ok!(test_benchmark_undelegate::<Test>());
 			assert_ok!(test_benchmark_clear_public_proposals::<Test>());
@@ -480,9 +379,6 @@ mod tests {
 			assert_ok!(test_benchmark_unlock::<Test>());
 			assert_ok!(test_benchmark_remove_vote::<Test>());
 			assert_ok!(test_benchmark_remove_other_vote::<Test>());
-			assert_ok!(test_benchmark_proxy_delegate::<Test>());
-			assert_ok!(test_benchmark_proxy_undelegate::<Test>());
-			assert_ok!(test_benchmark_proxy_remove_vote::<Test>());
 		});
 	}
 }
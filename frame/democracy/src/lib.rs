@@ -51,8 +51,6 @@
 //! account or an external origin) suggests that the system adopt.
 //! - **Referendum:** A proposal that is in the process of being voted on for
 //!   either acceptance or rejection as a change to the system.
-//! - **Proxy:** An account that has full voting power on behalf of a separate "Stash" account
-//!   that holds the funds.
 //! - **Delegation:** The act of granting your voting power to the decisions of another account for
 //!   up to a certain conviction.
 //!
@@ -92,18 +90,6 @@
 //! - `reap_vote` - Remove some account's expired votes.
 //! - `unlock` - Redetermine the account's balance lock, potentially making tokens available.
 //!
-//! Proxy administration:
-//! - `activate_proxy` - Activates a proxy that is already open to the sender.
-//! - `close_proxy` - Clears the proxy status, called by the proxy.
-//! - `deactivate_proxy` - Deactivates a proxy back to the open status, called by the stash.
-//! - `open_proxy` - Opens a proxy account on behalf of the sender.
-//!
-//! Proxy actions:
-//! - `proxy_vote` - Votes in a referendum on behalf of a stash account.
-//! - `proxy_unvote` - Cancel a previous vote, done on behalf of the voter by a proxy.
-//! - `proxy_delegate` - Delegate voting power, done on behalf of the voter by a proxy.
-//! - `proxy_undelegate` - Stop delegating voting power, done on behalf of the voter by a proxy.
-//!
 //! Preimage actions:
 //! - `note_preimage` - Registers the preimage for an upcoming proposal, requires
 //!   a deposit that is returned once the proposal is enacted.
@@ -171,7 +157,8 @@ use sp_runtime::{
 use codec::{Ref, Encode, Decode};
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, ensure, Parameter,
-	weights::{SimpleDispatchInfo, Weight, WeighData},
+	metadata::TypeInfo,
+	weights::{SimpleDispatchInfo, Weight, WeighData, ROCKS_DB_WEIGHT},
 	traits::{
 		Currency, ReservableCurrency, LockableCurrency, WithdrawReason, LockIdentifier, Get,
 		OnUnbalanced, BalanceStatus, schedule::Named as ScheduleNamed, EnsureOrigin
@@ -186,7 +173,7 @@ mod types;
 pub use vote_threshold::{Approved, VoteThreshold};
 pub use vote::{Vote, AccountVote, Voting};
 pub use conviction::Conviction;
-pub use types::{ReferendumInfo, ReferendumStatus, ProxyState, Tally, UnvoteScope, Delegations};
+pub use types::{ReferendumInfo, ReferendumStatus, Tally, UnvoteScope, Delegations};
 
 #[cfg(test)]
 mod tests;
@@ -279,7 +266,7 @@ pub trait Trait: frame_system::Trait + Sized {
 	type Scheduler: ScheduleNamed<Self::BlockNumber, Self::Proposal>;
 }
 
-#[derive(Clone, Encode, Decode, RuntimeDebug)]
+#[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum PreimageStatus<AccountId, Balance, BlockNumber> {
 	/// The preimage is imminently needed at the argument.
 	Missing(BlockNumber),
@@ -338,12 +325,6 @@ decl_storage! {
 		/// have recorded. The second item is the total amount of delegations, that will be added.
 		pub VotingOf: map hasher(twox_64_concat) T::AccountId => Voting<BalanceOf<T>, T::AccountId, T::BlockNumber>;
 
-		/// Who is able to vote for whom. Value is the fund-holding account, key is the
-		/// vote-transaction-sending account.
-		// TODO: Refactor proxy into its own pallet.
-		// https://github.com/paritytech/substrate/issues/5322
-		pub Proxy get(fn proxy): map hasher(twox_64_concat) T::AccountId => Option<ProxyState<T::AccountId>>;
-
 		/// Accounts for which there are locks in action which may be removed at some point in the
 		/// future. The value is the block number at which the lock expires and may be removed.
 		pub Locks get(locks): map hasher(twox_64_concat) T::AccountId => Option<T::BlockNumber>;
@@ -420,8 +401,6 @@ decl_error! {
 		ValueLow,
 		/// Proposal does not exist
 		ProposalMissing,
-		/// Not a proxy
-		NotProxy,
 		/// Unknown index
 		BadIndex,
 		/// Cannot cancel the same proposal twice
@@ -438,10 +417,6 @@ decl_error! {
 		NoProposal,
 		/// Identity may not veto a proposal twice
 		AlreadyVetoed,
-		/// Already a proxy
-		AlreadyProxy,
-		/// Wrong proxy
-		WrongProxy,
 		/// Not delegated
 		NotDelegated,
 		/// Preimage already noted
@@ -464,12 +439,6 @@ decl_error! {
 		NotLocked,
 		/// The lock on the account to be unlocked has not yet expired.
 		NotExpired,
-		/// A proxy-pairing was attempted to an account that was not open.
-		NotOpen,
-		/// A proxy-pairing was attempted to an account that was open to another account.
-		WrongOpen,
-		/// A proxy-de-pairing was attempted to an account that was not active.
-		NotActive,
 		/// The given account did not vote on the referendum.
 		NotVoter,
 		/// The actor has no permission to conduct the action.
@@ -527,8 +496,10 @@ decl_module! {
 
 		fn on_runtime_upgrade() -> Weight {
 			Self::migrate();
+			let removed_proxies = Self::remove_proxies();
 
 			SimpleDispatchInfo::default().weigh_data(())
+				.saturating_add(ROCKS_DB_WEIGHT.reads_writes(removed_proxies, removed_proxies.saturating_mul(2)))
 		}
 
 		/// Propose a sensitive action to be taken.
@@ -609,28 +580,6 @@ decl_module! {
 			Self::try_vote(&who, ref_index, vote)
 		}
 
-		/// Vote in a referendum on behalf of a stash. If `vote.is_aye()`, the vote is to enact
-		/// the proposal; otherwise it is a vote to keep the status quo.
-		///
-		/// The dispatch origin of this call must be _Signed_.
-		///
-		/// - `ref_index`: The index of the referendum to proxy vote for.
-		/// - `vote`: The vote configuration.
-		///
-		/// # <weight>
-		/// - `O(1)`.
-		/// - One DB change, one DB entry.
-		/// # </weight>
-		#[weight = SimpleDispatchInfo::FixedNormal(200_000)]
-		fn proxy_vote(origin,
-			#[compact] ref_index: ReferendumIndex,
-			vote: AccountVote<BalanceOf<T>>,
-		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			let voter = Self::proxy(who).and_then(|a| a.as_active()).ok_or(Error::<T>::NotProxy)?;
-			Self::try_vote(&voter, ref_index, vote)
-		}
-
 		/// Schedule an emergency cancellation of a referendum. Cannot happen twice to the same
 		/// referendum.
 		///
@@ -851,77 +800,6 @@ decl_module! {
 			SimpleDispatchInfo::default().weigh_data(())
 		}
 
-		/// Specify a proxy that is already open to us. Called by the stash.
-		///
-		/// NOTE: Used to be called `set_proxy`.
-		///
-		/// The dispatch origin of this call must be _Signed_.
-		///
-		/// - `proxy`: The account that will be activated as proxy.
-		///
-		/// # <weight>
-		/// - One extra DB entry.
-		/// # </weight>
-		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
-		fn activate_proxy(origin, proxy: T::AccountId) {
-			let who = ensure_signed(origin)?;
-			Proxy::<T>::try_mutate(&proxy, |a| match a.take() {
-				None => Err(Error::<T>::NotOpen),
-				Some(ProxyState::Active(_)) => Err(Error::<T>::AlreadyProxy),
-				Some(ProxyState::Open(x)) if &x == &who => {
-					*a = Some(ProxyState::Active(who));
-					Ok(())
-				}
-				Some(ProxyState::Open(_)) => Err(Error::<T>::WrongOpen),
-			})?;
-		}
-
-		/// Clear the proxy. Called by the proxy.
-		///
-		/// NOTE: Used to be called `resign_proxy`.
-		///
-		/// The dispatch origin of this call must be _Signed_.
-		///
-		/// # <weight>
-		/// - One DB clear.
-		/// # </weight>
-		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
-		fn close_proxy(origin) {
-			let who = ensure_signed(origin)?;
-			Proxy::<T>::mutate(&who, |a| {
-				if a.is_some() {
-					system::Module::<T>::dec_ref(&who);
-				}
-				*a = None;
-			});
-		}
-
-		/// Deactivate the proxy, but leave open to this account. Called by the stash.
-		///
-		/// The proxy must already be active.
-		///
-		/// NOTE: Used to be called `remove_proxy`.
-		///
-		/// The dispatch origin of this call must be _Signed_.
-		///
-		/// - `proxy`: The account that will be deactivated as proxy.
-		///
-		/// # <weight>
-		/// - One DB clear.
-		/// # </weight>
-		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
-		fn deactivate_proxy(origin, proxy: T::AccountId) {
-			let who = ensure_signed(origin)?;
-			Proxy::<T>::try_mutate(&proxy, |a| match a.take() {
-				None | Some(ProxyState::Open(_)) => Err(Error::<T>::NotActive),
-				Some(ProxyState::Active(x)) if &x == &who => {
-					*a = Some(ProxyState::Open(who));
-					Ok(())
-				}
-				Some(ProxyState::Active(_)) => Err(Error::<T>::WrongProxy),
-			})?;
-		}
-
 		/// Delegate the voting power (with some given conviction) of the sending account.
 		///
 		/// The balance delegated is locked for as long as it's delegated, and thereafter for the
@@ -1102,30 +980,6 @@ decl_module! {
 			Self::update_lock(&target);
 		}
 
-		/// Become a proxy.
-		///
-		/// This must be called prior to a later `activate_proxy`.
-		///
-		/// Origin must be a Signed.
-		///
-		/// - `target`: The account whose votes will later be proxied.
-		///
-		/// `close_proxy` must be called before the account can be destroyed.
-		///
-		/// # <weight>
-		/// - One extra DB entry.
-		/// # </weight>
-		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
-		fn open_proxy(origin, target: T::AccountId) {
-			let who = ensure_signed(origin)?;
-			Proxy::<T>::mutate(&who, |a| {
-				if a.is_none() {
-					system::Module::<T>::inc_ref(&who);
-				}
-				*a = Some(ProxyState::Open(target));
-			});
-		}
-
 		/// Remove a vote for a referendum.
 		///
 		/// If:
@@ -1184,80 +1038,6 @@ decl_module! {
 			Ok(())
 		}
 
-		/// Delegate the voting power (with some given conviction) of a proxied account.
-		///
-		/// The balance delegated is locked for as long as it's delegated, and thereafter for the
-		/// time appropriate for the conviction's lock period.
-		///
-		/// The dispatch origin of this call must be _Signed_, and the signing account must have
-		/// been set as the proxy account for `target`.
-		///
-		/// - `target`: The account whole voting power shall be delegated and whose balance locked.
-		///   This account must either:
-		///   - be delegating already; or
-		///   - have no voting activity (if there is, then it will need to be removed/consolidated
-		///     through `reap_vote` or `unvote`).
-		/// - `to`: The account whose voting the `target` account's voting power will follow.
-		/// - `conviction`: The conviction that will be attached to the delegated votes. When the
-		///   account is undelegated, the funds will be locked for the corresponding period.
-		/// - `balance`: The amount of the account's balance to be used in delegating. This must
-		///   not be more than the account's current balance.
-		///
-		/// Emits `Delegated`.
-		///
-		/// # <weight>
-		/// # </weight>
-		#[weight = SimpleDispatchInfo::FixedNormal(500_000)]
-		pub fn proxy_delegate(origin,
-			to: T::AccountId,
-			conviction: Conviction,
-			balance: BalanceOf<T>,
-		) {
-			let who = ensure_signed(origin)?;
-			let target = Self::proxy(who).and_then(|a| a.as_active()).ok_or(Error::<T>::NotProxy)?;
-			Self::try_delegate(target, to, conviction, balance)?;
-		}
-
-		/// Undelegate the voting power of a proxied account.
-		///
-		/// Tokens may be unlocked following once an amount of time consistent with the lock period
-		/// of the conviction with which the delegation was issued.
-		///
-		/// The dispatch origin of this call must be _Signed_ and the signing account must be a
-		/// proxy for some other account which is currently delegating.
-		///
-		/// Emits `Undelegated`.
-		///
-		/// # <weight>
-		/// - O(1).
-		/// # </weight>
-		#[weight = SimpleDispatchInfo::FixedNormal(500_000)]
-		fn proxy_undelegate(origin) {
-			let who = ensure_signed(origin)?;
-			let target = Self::proxy(who).and_then(|a| a.as_active()).ok_or(Error::<T>::NotProxy)?;
-			Self::try_undelegate(target)?;
-		}
-
-		/// Remove a proxied vote for a referendum.
-		///
-		/// Exactly equivalent to `remove_vote` except that it operates on the account that the
-		/// sender is a proxy for.
-		///
-		/// The dispatch origin of this call must be _Signed_ and the signing account must be a
-		/// proxy for some other account which has a registered vote for the referendum of `index`.
-		///
-		/// - `index`: The index of referendum of the vote to be removed.
-		///
-		/// # <weight>
-		/// - `O(R + log R)` where R is the number of referenda that `target` has voted on.
-		/// # </weight>
-		#[weight = SimpleDispatchInfo::FixedNormal(10_000)]
-		fn proxy_remove_vote(origin, index: ReferendumIndex) -> DispatchResult {
-			let who = ensure_signed(origin)?;
-			let target = Self::proxy(who).and_then(|a| a.as_active()).ok_or(Error::<T>::NotProxy)?;
-			Self::try_remove_vote(&target, index, UnvoteScope::Any)
-		}
-
 		/// Enact a proposal from a referendum. For now we just make the weight be the maximum.
 		#[weight = SimpleDispatchInfo::MaxNormal]
 		fn enact_proposal(origin, proposal_hash: T::Hash, index: ReferendumIndex) -> DispatchResult {
@@ -1287,6 +1067,22 @@ impl<T: Trait> Module<T> {
 		}
 	}
 
+	/// Remove the democracy proxies, which are superseded by the `proxy` module, along with the
+	/// account references they hold. Returns the number of proxies removed.
+	///
+	/// Without this, the proxy accounts could never be reaped, as `close_proxy` no longer exists.
+	fn remove_proxies() -> Weight {
+		use frame_support::{Twox64Concat, migration::StorageKeyIterator};
+		let mut removed: Weight = 0;
+		for (proxy, _) in StorageKeyIterator::<T::AccountId, types::ProxyState<T::AccountId>, Twox64Concat>
+			::new(b"Democracy", b"Proxy").drain()
+		{
+			system::Module::<T>::dec_ref(&proxy);
+			removed = removed.saturating_add(1);
+		}
+		removed
+	}
+
 	// exposed immutables.
 
 	/// Get the amount locked in support of `proposal`; `None` if proposal isn't a valid proposal
@@ -1313,16 +1109,6 @@ impl<T: Trait> Module<T> {
 
 	// Exposed mutables.
 
-	#[cfg(feature = "std")]
-	pub fn force_proxy(stash: T::AccountId, proxy: T::AccountId) {
-		Proxy::<T>::mutate(&proxy, |o| {
-			if o.is_none() {
-				system::Module::<T>::inc_ref(&proxy);
-			}
-			*o = Some(ProxyState::Active(stash))
-		})
-	}
-
 	/// Start a referendum.
 	pub fn internal_start_referendum(
 		proposal_hash: T::Hash,
@@ -29,7 +29,7 @@ use sp_runtime::{
 	testing::Header, Perbill,
 };
 use pallet_balances::{BalanceLock, Error as BalancesError};
-use frame_system::EnsureSignedBy;
+use frame_system::{EnsureSignedBy, EnsureRoot};
 
 mod cancellation;
 mod delegation;
@@ -97,6 +97,7 @@ impl pallet_scheduler::Trait for Test {
 	type Origin = Origin;
 	type Call = Call;
 	type MaximumWeight = MaximumWeight;
+	type ScheduleOrigin = EnsureRoot<u64>;
 }
 impl pallet_balances::Trait for Test {
 	type Balance = u64;
@@ -14,91 +14,31 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-//! The tests for functionality concerning proxying.
+//! The tests for the removal of the democracy proxies.
 
 use super::*;
+use frame_support::{
+	StorageHasher, Twox64Concat, storage::migration::{get_storage_value, put_storage_value},
+	traits::OnRuntimeUpgrade,
+};
+use crate::types::ProxyState;
 
 #[test]
-fn proxy_should_work() {
+fn migration_removes_proxies() {
 	new_test_ext().execute_with(|| {
-		assert_eq!(Democracy::proxy(10), None);
-		assert!(System::allow_death(&10));
-
-		assert_noop!(Democracy::activate_proxy(Origin::signed(1), 10), Error::<Test>::NotOpen);
-
-		assert_ok!(Democracy::open_proxy(Origin::signed(10), 1));
+		for (proxy, state) in vec![(10u64, ProxyState::Open(1u64)), (11, ProxyState::Active(2))] {
+			System::inc_ref(&proxy);
+			put_storage_value(b"Democracy", b"Proxy", &Twox64Concat::hash(&proxy.encode()), state);
+		}
 		assert!(!System::allow_death(&10));
-		assert_eq!(Democracy::proxy(10), Some(ProxyState::Open(1)));
-
-		assert_noop!(Democracy::activate_proxy(Origin::signed(2), 10), Error::<Test>::WrongOpen);
-		assert_ok!(Democracy::activate_proxy(Origin::signed(1), 10));
-		assert_eq!(Democracy::proxy(10), Some(ProxyState::Active(1)));
+		assert!(!System::allow_death(&11));
 
-		// Can't set when already set.
-		assert_noop!(Democracy::activate_proxy(Origin::signed(2), 10), Error::<Test>::AlreadyProxy);
+		let weight = <Democracy as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(weight, SimpleDispatchInfo::default().weigh_data(()) + ROCKS_DB_WEIGHT.reads_writes(2, 4));
 
-		// But this works because 11 isn't proxying.
-		assert_ok!(Democracy::open_proxy(Origin::signed(11), 2));
-		assert_ok!(Democracy::activate_proxy(Origin::signed(2), 11));
-		assert_eq!(Democracy::proxy(10), Some(ProxyState::Active(1)));
-		assert_eq!(Democracy::proxy(11), Some(ProxyState::Active(2)));
-
-		// 2 cannot fire 1's proxy:
-		assert_noop!(Democracy::deactivate_proxy(Origin::signed(2), 10), Error::<Test>::WrongProxy);
-
-		// 1 deactivates their proxy:
-		assert_ok!(Democracy::deactivate_proxy(Origin::signed(1), 10));
-		assert_eq!(Democracy::proxy(10), Some(ProxyState::Open(1)));
-		// but the proxy account cannot be killed until the proxy is closed.
-		assert!(!System::allow_death(&10));
-
-		// and then 10 closes it completely:
-		assert_ok!(Democracy::close_proxy(Origin::signed(10)));
-		assert_eq!(Democracy::proxy(10), None);
 		assert!(System::allow_death(&10));
-
-		// 11 just closes without 2's "permission".
-		assert_ok!(Democracy::close_proxy(Origin::signed(11)));
-		assert_eq!(Democracy::proxy(11), None);
 		assert!(System::allow_death(&11));
+		let key = Twox64Concat::hash(&10u64.encode());
+		assert_eq!(get_storage_value::<ProxyState<u64>>(b"Democracy", b"Proxy", &key), None);
 	});
 }
-
-#[test]
-fn voting_and_removing_votes_should_work_with_proxy() {
-	new_test_ext().execute_with(|| {
-		System::set_block_number(0);
-		assert_ok!(propose_set_balance_and_note(1, 2, 1));
-
-		fast_forward_to(2);
-		let r = 0;
-		assert_ok!(Democracy::open_proxy(Origin::signed(10), 1));
-		assert_ok!(Democracy::activate_proxy(Origin::signed(1), 10));
-
-		assert_ok!(Democracy::proxy_vote(Origin::signed(10), r, aye(1)));
-		assert_eq!(tally(r), Tally { ayes: 1, nays: 0, turnout: 10 });
-
-		assert_ok!(Democracy::proxy_remove_vote(Origin::signed(10), r));
-		assert_eq!(tally(r), Tally { ayes: 0, nays: 0, turnout: 0 });
-	});
-}
-
-#[test]
-fn delegation_and_undelegation_should_work_with_proxy() {
-	new_test_ext().execute_with(|| {
-		System::set_block_number(0);
-		assert_ok!(propose_set_balance_and_note(1, 2, 1));
-		fast_forward_to(2);
-		let r = 0;
-		assert_ok!(Democracy::open_proxy(Origin::signed(10), 1));
-		assert_ok!(Democracy::activate_proxy(Origin::signed(1), 10));
-		assert_ok!(Democracy::vote(Origin::signed(2), r, aye(2)));
-
-		assert_ok!(Democracy::proxy_delegate(Origin::signed(10), 2, Conviction::None, 10));
-		assert_eq!(tally(r), Tally { ayes: 3, nays: 0, turnout: 30 });
-
-		assert_ok!(Democracy::proxy_undelegate(Origin::signed(10)));
-		assert_eq!(tally(r), Tally { ayes: 2, nays: 0, turnout: 20 });
-	});
-}
-
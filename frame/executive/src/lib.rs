@@ -78,7 +78,7 @@
 
 use sp_std::{prelude::*, marker::PhantomData};
 use frame_support::{
-	storage::StorageValue, weights::{GetDispatchInfo, DispatchInfo},
+	storage::StorageValue, weights::{GetDispatchInfo, DispatchInfo, Weight},
 	traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade, OffchainWorker},
 };
 use sp_runtime::{
@@ -245,6 +245,35 @@ where
 		Self::final_checks(&header);
 	}
 
+	/// Execute all `OnRuntimeUpgrade` hooks of this runtime and return the aggregated weight.
+	///
+	/// This is only meant for dry-running migrations against existing state, e.g. with the
+	/// `try-runtime` command. Regular block execution calls the hooks by itself.
+	pub fn try_runtime_upgrade() -> Weight {
+		// Record the upgrade, so that executing a block afterwards doesn't run the migrations again.
+		let _ = Self::runtime_upgraded();
+		// System is not part of `AllModules`, so we need to call this manually.
+		let weight = <frame_system::Module::<System> as OnRuntimeUpgrade>::on_runtime_upgrade();
+		weight.saturating_add(<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade())
+	}
+
+	/// Execute `block` like `execute_block`, but without checking the resulting digest and
+	/// storage root against the header. Returns the weight consumed by the block.
+	///
+	/// This allows dry-running a block on top of a runtime it wasn't built with.
+	pub fn execute_block_no_check(block: Block) -> Weight {
+		Self::initialize_block(block.header());
+		Self::initial_checks(&block);
+
+		let (header, extrinsics) = block.deconstruct();
+		Self::execute_extrinsics_with_book_keeping(extrinsics, *header.number());
+		let weight = <frame_system::Module<System>>::all_extrinsics_weight();
+
+		// remove temporaries
+		<frame_system::Module<System>>::finalize();
+		weight
+	}
+
 	/// Execute given extrinsics and take care of post-extrinsics book-keeping.
 	fn execute_extrinsics_with_book_keeping(extrinsics: Vec<Block::Extrinsic>, block_number: NumberFor<Block>) {
 		extrinsics.into_iter().for_each(Self::apply_extrinsic_no_note);
@@ -644,6 +673,25 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn execute_block_no_check_ignores_state_root() {
+		new_test_ext(1).execute_with(|| {
+			let weight = Executive::execute_block_no_check(Block {
+				header: Header {
+					parent_hash: [69u8; 32].into(),
+					number: 1,
+					state_root: [0u8; 32].into(),
+					extrinsics_root: hex!("03170a2e7597b7b7e3d84c05391d139a62b157e78786d8c082f29dcf4c111314").into(),
+					digest: Digest { logs: vec![], },
+				},
+				extrinsics: vec![],
+			});
+			// only the `on_initialize` weight of the custom module.
+			assert_eq!(weight, 175);
+			assert_eq!(Executive::try_runtime_upgrade(), 0);
+		});
+	}
+
 	#[test]
 	fn bad_extrinsic_not_inserted() {
 		let mut t = new_test_ext(1);
@@ -18,19 +18,39 @@
 
 #![cfg(test)]
 
-use sp_runtime::{Perbill, DigestItem, traits::IdentityLookup, testing::{Header, UintAuthorityId}};
+use std::cell::RefCell;
+
+use sp_runtime::{
+	Perbill, DigestItem, KeyTypeId,
+	traits::IdentityLookup,
+	testing::{Header, UintAuthorityId, TestXt},
+};
 use sp_io;
-use frame_support::{impl_outer_origin, impl_outer_event, parameter_types, weights::Weight};
+use frame_support::{
+	impl_outer_origin, impl_outer_event, impl_outer_dispatch, parameter_types,
+	traits::KeyOwnerProofSystem, weights::Weight,
+};
 use sp_core::H256;
 use codec::{Encode, Decode};
-use crate::{AuthorityId, AuthorityList, GenesisConfig, Trait, Module, ConsensusLog};
+use crate::{
+	AuthorityId, AuthorityList, GenesisConfig, Trait, Module, ConsensusLog,
+	EquivocationHandler, GrandpaEquivocationOffence, GrandpaTimeSlot,
+};
 use sp_finality_grandpa::GRANDPA_ENGINE_ID;
+use sp_session::{GetSessionNumber, GetValidatorCount, ValidatorCount};
+use sp_staking::{SessionIndex, offence::{ReportOffence, OffenceError}};
 
 use frame_system as system;
 impl_outer_origin!{
 	pub enum Origin for Test  where system = frame_system {}
 }
 
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		grandpa::Grandpa,
+	}
+}
+
 pub fn grandpa_log(log: ConsensusLog<u64>) -> DigestItem<H256> {
 	DigestItem::Consensus(GRANDPA_ENGINE_ID, log.encode())
 }
@@ -41,6 +61,87 @@ pub struct Test;
 
 impl Trait for Test {
 	type Event = TestEvent;
+	type Call = Call;
+	type KeyOwnerProof = TestKeyOwnerProof;
+	type KeyOwnerIdentification = AuthorityId;
+	type KeyOwnerProofSystem = TestKeyOwnerProofSystem;
+	type HandleEquivocation = EquivocationHandler<OffenceHandler, SubmitTransaction>;
+}
+
+/// An extrinsic type used for tests.
+pub type Extrinsic = TestXt<Call, ()>;
+type SubmitTransaction = frame_system::offchain::TransactionSubmitter<(), Call, Extrinsic>;
+type Offence = GrandpaEquivocationOffence<AuthorityId>;
+
+thread_local! {
+	pub static OFFENCES: RefCell<Vec<(Vec<u64>, Offence)>> = RefCell::new(vec![]);
+	pub static SESSION_INDEX: RefCell<SessionIndex> = RefCell::new(0);
+}
+
+/// A mock offence report handler.
+pub struct OffenceHandler;
+impl ReportOffence<u64, AuthorityId, Offence> for OffenceHandler {
+	fn report_offence(reporters: Vec<u64>, offence: Offence) -> Result<(), OffenceError> {
+		if OFFENCES.with(|l| l.borrow().iter().any(|(_, o)| o == &offence)) {
+			return Err(OffenceError::DuplicateReport);
+		}
+		OFFENCES.with(|l| l.borrow_mut().push((reporters, offence)));
+		Ok(())
+	}
+
+	fn is_known_offence(offenders: &[AuthorityId], time_slot: &GrandpaTimeSlot) -> bool {
+		OFFENCES.with(|l| offenders.iter().all(|offender|
+			l.borrow().iter().any(|(_, o)| &o.offender == offender && &o.time_slot == time_slot)
+		))
+	}
+}
+
+/// A mock proof of key ownership, proving that the key was part of the
+/// authority set at the given session.
+#[derive(Clone, PartialEq, Eq, Debug, Decode, Encode)]
+pub struct TestKeyOwnerProof {
+	pub session: SessionIndex,
+	pub validator_count: ValidatorCount,
+}
+
+impl GetSessionNumber for TestKeyOwnerProof {
+	fn session(&self) -> SessionIndex {
+		self.session
+	}
+}
+
+impl GetValidatorCount for TestKeyOwnerProof {
+	fn validator_count(&self) -> ValidatorCount {
+		self.validator_count
+	}
+}
+
+/// A mock key ownership proof system which proves membership of keys in the
+/// current authority set, using the session index set in `SESSION_INDEX`.
+pub struct TestKeyOwnerProofSystem;
+impl KeyOwnerProofSystem<(KeyTypeId, AuthorityId)> for TestKeyOwnerProofSystem {
+	type Proof = TestKeyOwnerProof;
+	type IdentificationTuple = AuthorityId;
+
+	fn prove(key: (KeyTypeId, AuthorityId)) -> Option<Self::Proof> {
+		let authorities = Grandpa::grandpa_authorities();
+		if key.0 != sp_finality_grandpa::KEY_TYPE || !authorities.iter().any(|(id, _)| id == &key.1) {
+			return None;
+		}
+
+		Some(TestKeyOwnerProof {
+			session: SESSION_INDEX.with(|s| *s.borrow()),
+			validator_count: authorities.len() as ValidatorCount,
+		})
+	}
+
+	fn check_proof(key: (KeyTypeId, AuthorityId), proof: Self::Proof) -> Option<AuthorityId> {
+		if Self::prove(key.clone())? == proof {
+			Some(key.1)
+		} else {
+			None
+		}
+	}
 }
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
@@ -52,7 +153,7 @@ impl frame_system::Trait for Test {
 	type Origin = Origin;
 	type Index = u64;
 	type BlockNumber = u64;
-	type Call = ();
+	type Call = Call;
 	type Hash = H256;
 	type Hashing = sp_runtime::traits::BlakeTwo256;
 	type AccountId = u64;
@@ -88,9 +189,13 @@ pub fn to_authorities(vec: Vec<(u64, u64)>) -> AuthorityList {
 }
 
 pub fn new_test_ext(authorities: Vec<(u64, u64)>) -> sp_io::TestExternalities {
+	new_test_ext_with_authorities(to_authorities(authorities))
+}
+
+pub fn new_test_ext_with_authorities(authorities: AuthorityList) -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	GenesisConfig {
-		authorities: to_authorities(authorities),
+		authorities,
 	}.assimilate_storage::<Test>(&mut t).unwrap();
 	t.into()
 }
@@ -18,12 +18,18 @@
 
 #![cfg(test)]
 
-use sp_runtime::{testing::{H256, Digest}, traits::Header};
-use frame_support::traits::OnFinalize;
+use sp_runtime::{
+	DispatchError,
+	testing::{H256, Digest},
+	traits::Header,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+};
+use sp_core::Pair;
+use frame_support::{assert_err, assert_ok, traits::OnFinalize, unsigned::ValidateUnsigned};
 use crate::mock::*;
-use frame_system::{EventRecord, Phase};
+use frame_system::{EventRecord, Phase, RawOrigin};
 use codec::{Decode, Encode};
-use fg_primitives::ScheduledChange;
+use fg_primitives::{AuthorityPair, ScheduledChange};
 use super::*;
 
 fn initialize_block(number: u64, parent_hash: H256) {
@@ -319,3 +325,253 @@ fn time_slot_have_sane_ord() {
 	];
 	assert!(FIXTURE.windows(2).all(|f| f[0] < f[1]));
 }
+
+fn authority_pairs() -> Vec<AuthorityPair> {
+	(1..=3u8).map(|i| AuthorityPair::from_seed(&[i; 32])).collect()
+}
+
+fn authorities_of(pairs: &[AuthorityPair]) -> AuthorityList {
+	pairs.iter().map(|pair| (pair.public(), 1)).collect()
+}
+
+fn signed_prevote(
+	pair: &AuthorityPair,
+	set_id: SetId,
+	round: u64,
+	target_hash: H256,
+	target_number: u64,
+) -> (grandpa::Prevote<H256, u64>, fg_primitives::AuthoritySignature) {
+	let prevote = grandpa::Prevote { target_hash, target_number };
+	let payload = fg_primitives::localized_payload(
+		round,
+		set_id,
+		&grandpa::Message::Prevote(prevote.clone()),
+	);
+
+	(prevote, pair.sign(&payload))
+}
+
+fn prevote_equivocation_proof(
+	pair: &AuthorityPair,
+	set_id: SetId,
+	round: u64,
+	first: (H256, u64),
+	second: (H256, u64),
+) -> EquivocationProof<H256, u64> {
+	EquivocationProof::new(
+		set_id,
+		fg_primitives::Equivocation::Prevote(grandpa::Equivocation {
+			round_number: round,
+			identity: pair.public(),
+			first: signed_prevote(pair, set_id, round, first.0, first.1),
+			second: signed_prevote(pair, set_id, round, second.0, second.1),
+		}),
+	)
+}
+
+fn key_owner_proof(pair: &AuthorityPair) -> TestKeyOwnerProof {
+	TestKeyOwnerProofSystem::prove((fg_primitives::KEY_TYPE, pair.public())).unwrap()
+}
+
+#[test]
+fn report_equivocation_current_set_works() {
+	let pairs = authority_pairs();
+	new_test_ext_with_authorities(authorities_of(&pairs)).execute_with(|| {
+		let equivocation_proof = prevote_equivocation_proof(
+			&pairs[0],
+			0,
+			1,
+			(H256::repeat_byte(1), 10),
+			(H256::repeat_byte(2), 10),
+		);
+
+		assert_ok!(Grandpa::report_equivocation(
+			Origin::signed(42),
+			equivocation_proof,
+			key_owner_proof(&pairs[0]),
+		));
+
+		OFFENCES.with(|l| assert_eq!(*l.borrow(), vec![(
+			vec![42],
+			GrandpaEquivocationOffence {
+				time_slot: GrandpaTimeSlot { set_id: 0, round: 1 },
+				session_index: 0,
+				validator_set_count: 3,
+				offender: pairs[0].public(),
+			},
+		)]));
+	});
+}
+
+#[test]
+fn report_equivocation_unsigned_has_no_reporters() {
+	let pairs = authority_pairs();
+	new_test_ext_with_authorities(authorities_of(&pairs)).execute_with(|| {
+		let equivocation_proof = prevote_equivocation_proof(
+			&pairs[1],
+			0,
+			1,
+			(H256::repeat_byte(1), 10),
+			(H256::repeat_byte(2), 10),
+		);
+
+		assert_err!(
+			Grandpa::report_equivocation_unsigned(
+				Origin::signed(42),
+				equivocation_proof.clone(),
+				key_owner_proof(&pairs[1]),
+			),
+			DispatchError::BadOrigin,
+		);
+
+		assert_ok!(Grandpa::report_equivocation_unsigned(
+			RawOrigin::None.into(),
+			equivocation_proof.clone(),
+			key_owner_proof(&pairs[1]),
+		));
+
+		OFFENCES.with(|l| {
+			let offences = l.borrow();
+			assert_eq!(offences.len(), 1);
+			assert!(offences[0].0.is_empty());
+			assert_eq!(offences[0].1.offender, pairs[1].public());
+		});
+
+		// reporting the same equivocation again fails.
+		assert_err!(
+			Grandpa::report_equivocation_unsigned(
+				RawOrigin::None.into(),
+				equivocation_proof,
+				key_owner_proof(&pairs[1]),
+			),
+			Error::<Test>::DuplicateOffenceReport,
+		);
+	});
+}
+
+#[test]
+fn report_equivocation_invalid_key_owner_proof() {
+	let pairs = authority_pairs();
+	new_test_ext_with_authorities(authorities_of(&pairs)).execute_with(|| {
+		let equivocation_proof = prevote_equivocation_proof(
+			&pairs[0],
+			0,
+			1,
+			(H256::repeat_byte(1), 10),
+			(H256::repeat_byte(2), 10),
+		);
+
+		// the proof was generated for a different session.
+		let mut invalid_proof = key_owner_proof(&pairs[0]);
+		invalid_proof.session = 1;
+
+		assert_err!(
+			Grandpa::report_equivocation(Origin::signed(42), equivocation_proof, invalid_proof),
+			Error::<Test>::InvalidKeyOwnershipProof,
+		);
+
+		OFFENCES.with(|l| assert!(l.borrow().is_empty()));
+	});
+}
+
+#[test]
+fn report_equivocation_invalid_equivocation_proof() {
+	let pairs = authority_pairs();
+	new_test_ext_with_authorities(authorities_of(&pairs)).execute_with(|| {
+		let assert_invalid_equivocation_proof = |equivocation_proof| {
+			assert_err!(
+				Grandpa::report_equivocation(
+					Origin::signed(42),
+					equivocation_proof,
+					key_owner_proof(&pairs[0]),
+				),
+				Error::<Test>::InvalidEquivocationProof,
+			);
+		};
+
+		let hash = H256::repeat_byte(1);
+
+		// both votes target the same block.
+		assert_invalid_equivocation_proof(prevote_equivocation_proof(
+			&pairs[0],
+			0,
+			1,
+			(hash, 10),
+			(hash, 10),
+		));
+
+		// votes are signed with a different key than the offender's.
+		assert_invalid_equivocation_proof(EquivocationProof::new(
+			0,
+			fg_primitives::Equivocation::Prevote(grandpa::Equivocation {
+				round_number: 1,
+				identity: pairs[0].public(),
+				first: signed_prevote(&pairs[1], 0, 1, H256::repeat_byte(1), 10),
+				second: signed_prevote(&pairs[1], 0, 1, H256::repeat_byte(2), 10),
+			}),
+		));
+
+		// the equivocation happened on an unknown set id.
+		assert_invalid_equivocation_proof(prevote_equivocation_proof(
+			&pairs[0],
+			1,
+			1,
+			(H256::repeat_byte(1), 10),
+			(H256::repeat_byte(2), 10),
+		));
+
+		OFFENCES.with(|l| assert!(l.borrow().is_empty()));
+	});
+}
+
+#[test]
+fn report_equivocation_validate_unsigned_prevents_duplicates() {
+	let pairs = authority_pairs();
+	new_test_ext_with_authorities(authorities_of(&pairs)).execute_with(|| {
+		let equivocation_proof = prevote_equivocation_proof(
+			&pairs[2],
+			0,
+			1,
+			(H256::repeat_byte(1), 10),
+			(H256::repeat_byte(2), 10),
+		);
+		let call = crate::Call::report_equivocation_unsigned(
+			equivocation_proof.clone(),
+			key_owner_proof(&pairs[2]),
+		);
+
+		// only local/in-block reports are allowed
+		assert_eq!(
+			<Grandpa as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into(),
+		);
+
+		let tx_tag = (pairs[2].public(), 0u64, 1u64);
+		let validity = <Grandpa as ValidateUnsigned>::validate_unsigned(
+			TransactionSource::Local,
+			&call,
+		).unwrap();
+		assert_eq!(validity.provides, vec![tx_tag.encode()]);
+		assert!(!validity.propagate);
+
+		// the report is still valid during block import
+		assert_ok!(<Grandpa as ValidateUnsigned>::pre_dispatch(&call));
+
+		// we submit the report
+		assert_ok!(Grandpa::report_equivocation_unsigned(
+			RawOrigin::None.into(),
+			equivocation_proof,
+			key_owner_proof(&pairs[2]),
+		));
+
+		// the report should now be considered stale and the transaction is invalid
+		assert_eq!(
+			<Grandpa as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &call),
+			InvalidTransaction::Stale.into(),
+		);
+		assert_err!(
+			<Grandpa as ValidateUnsigned>::pre_dispatch(&call),
+			InvalidTransaction::Stale,
+		);
+	});
+}
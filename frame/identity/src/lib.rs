@@ -73,6 +73,7 @@ use sp_runtime::{DispatchResult, RuntimeDebug};
 use sp_runtime::traits::{StaticLookup, Zero, AppendZerosInput};
 use frame_support::{
 	decl_module, decl_event, decl_storage, ensure, decl_error,
+	metadata::{TypeInfo, Registry, Type, TypeDef, Variant, Field, type_path},
 	traits::{Currency, ReservableCurrency, OnUnbalanced, Get, BalanceStatus, EnsureOrigin},
 	weights::SimpleDispatchInfo,
 };
@@ -180,6 +181,41 @@ impl Encode for Data {
 }
 impl codec::EncodeLike for Data {}
 
+/// Describes the encoding, where raw data of `n` bytes uses the leading byte `n + 1`.
+impl TypeInfo for Data {
+	fn type_info(registry: &mut Registry) -> Type {
+		macro_rules! raw {
+			( $( $n:expr ),* ) => {
+				vec![
+					Variant::new("None", Vec::new(), 0),
+					Variant::new("Raw0", Vec::new(), 1),
+					$( Variant::new(
+						concat!("Raw", stringify!($n)),
+						vec![Field::unnamed(registry.register::<[u8; $n]>())],
+						$n + 1,
+					), )*
+				]
+			}
+		}
+
+		let mut variants = raw!(
+			1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+			17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32
+		);
+		let hash = registry.register::<[u8; 32]>();
+		variants.push(Variant::new("BlakeTwo256", vec![Field::unnamed(hash)], 34));
+		variants.push(Variant::new("Sha256", vec![Field::unnamed(hash)], 35));
+		variants.push(Variant::new("Keccak256", vec![Field::unnamed(hash)], 36));
+		variants.push(Variant::new("ShaThree256", vec![Field::unnamed(hash)], 37));
+
+		Type {
+			path: type_path(module_path!(), "Data"),
+			params: Vec::new(),
+			def: TypeDef::Variant(variants),
+		}
+	}
+}
+
 impl Default for Data {
 	fn default() -> Self {
 		Self::None
@@ -193,7 +229,7 @@ pub type RegistrarIndex = u32;
 ///
 /// NOTE: Registrars may pay little attention to some fields. Registrars may want to make clear
 /// which fields their attestation is relevant for by off-chain means.
-#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 pub enum Judgement<
 	Balance: Encode + Decode + Copy + Clone + Debug + Eq + PartialEq
 > {
@@ -272,12 +308,17 @@ impl Decode for IdentityFields {
 		Ok(Self(<BitFlags<IdentityField>>::from_bits(field as u64).map_err(|_| "invalid value")?))
 	}
 }
+impl TypeInfo for IdentityFields {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type::wrapper(type_path(module_path!(), "IdentityFields"), registry.register::<u64>())
+	}
+}
 
 /// Information concerning the identity of the controller of an account.
 ///
 /// NOTE: This should be stored at the end of the storage item to facilitate the addition of extra
 /// fields in a backwards compatible way through a specialized `Decode` impl.
-#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 #[cfg_attr(test, derive(Default))]
 pub struct IdentityInfo {
 	/// Additional fields of the identity that are not catered for with the struct's explicit
@@ -329,7 +370,7 @@ pub struct IdentityInfo {
 ///
 /// NOTE: This is stored separately primarily to facilitate the addition of extra fields in a
 /// backwards compatible way through a specialized `Decode` impl.
-#[derive(Clone, Encode, Eq, PartialEq, RuntimeDebug)]
+#[derive(Clone, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 pub struct Registration<
 	Balance: Encode + Decode + Copy + Clone + Debug + Eq + PartialEq
 > {
@@ -364,7 +405,7 @@ impl<
 }
 
 /// Information concerning a registrar.
-#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 pub struct RegistrarInfo<
 	Balance: Encode + Decode + Clone + Debug + Eq + PartialEq,
 	AccountId: Encode + Decode + Clone + Debug + Eq + PartialEq
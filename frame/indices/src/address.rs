@@ -18,9 +18,10 @@
 
 #[cfg(feature = "std")]
 use std::fmt;
-use sp_std::convert::TryInto;
+use sp_std::{convert::TryInto, prelude::*};
 use crate::Member;
 use codec::{Encode, Decode, Input, Output, Error};
+use frame_support::metadata::{TypeInfo, Registry, Type, TypeDef, Variant, Field, type_path};
 
 /// An indices-aware address, which can be either a direct `AccountId` or
 /// an index.
@@ -120,6 +121,29 @@ impl<AccountId, AccountIndex> codec::EncodeLike for Address<AccountId, AccountIn
 	AccountIndex: Member + Encode + PartialOrd<AccountIndex> + Ord + Copy + From<u32> + TryInto<u32>,
 {}
 
+/// Describes the custom encoding: the variants are identified by their leading byte, with the
+/// indices below `0xf0` being encoded in that byte alone.
+impl<AccountId, AccountIndex> TypeInfo for Address<AccountId, AccountIndex> where
+	AccountId: Member + TypeInfo + 'static,
+	AccountIndex: Member + TypeInfo + 'static,
+{
+	fn type_info(registry: &mut Registry) -> Type {
+		let account_id = registry.register::<AccountId>();
+		let account_index = registry.register::<AccountIndex>();
+		let variants = vec![
+			Variant::new("Index16", vec![Field::unnamed(registry.register::<u16>())], 0xfc),
+			Variant::new("Index32", vec![Field::unnamed(registry.register::<u32>())], 0xfd),
+			Variant::new("Index", vec![Field::unnamed(account_index)], 0xfe),
+			Variant::new("Id", vec![Field::unnamed(account_id)], 0xff),
+		];
+		Type {
+			path: type_path(module_path!(), "Address"),
+			params: vec![account_id, account_index],
+			def: TypeDef::Variant(variants),
+		}
+	}
+}
+
 impl<AccountId, AccountIndex> Default for Address<AccountId, AccountIndex> where
 	AccountId: Member + Default,
 	AccountIndex: Member,
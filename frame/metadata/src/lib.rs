@@ -30,6 +30,12 @@ use codec::{Encode, Output};
 use sp_std::vec::Vec;
 use sp_core::RuntimeDebug;
 
+pub mod registry;
+
+pub use registry::{
+	TypeInfo, TypeIndex, Registry, PortableRegistry, Type, TypeDef, TypeDefPrimitive, Field, Variant,
+};
+
 #[cfg(feature = "std")]
 type StringBuf = String;
 
@@ -363,6 +369,8 @@ pub enum RuntimeMetadata {
 	V10(RuntimeMetadataDeprecated),
 	/// Version 11 for runtime metadata.
 	V11(RuntimeMetadataV11),
+	/// Version 12 for runtime metadata, with a portable type registry.
+	V12(RuntimeMetadataV12),
 }
 
 /// Enum that should fail.
@@ -411,6 +419,105 @@ pub struct ModuleMetadata {
 type ODFnA<T> = Option<DFnA<T>>;
 type DFnA<T> = DecodeDifferent<FnEncode<&'static [T]>, Vec<T>>;
 
+/// The metadata of a runtime, with all types described in a portable registry.
+#[derive(Eq, Encode, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct RuntimeMetadataV12 {
+	/// All the types referenced by the modules.
+	pub types: PortableRegistry,
+	/// Metadata of all the modules.
+	pub modules: Vec<ModuleMetadataV12>,
+	/// Metadata of the extrinsic.
+	pub extrinsic: ExtrinsicMetadata,
+}
+
+/// All metadata about an runtime module, referring to types by their index in the registry.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct ModuleMetadataV12 {
+	pub name: DecodeDifferentStr,
+	pub storage: Option<StorageMetadataV12>,
+	pub calls: Option<Vec<FunctionMetadataV12>>,
+	pub event: Option<Vec<EventMetadataV12>>,
+	pub constants: Vec<ModuleConstantMetadataV12>,
+	pub errors: DecodeDifferentArray<ErrorMetadata>,
+}
+
+/// All the metadata about a function.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct FunctionMetadataV12 {
+	pub name: DecodeDifferentStr,
+	pub arguments: Vec<FunctionArgumentMetadataV12>,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+}
+
+/// All the metadata about a function argument.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct FunctionArgumentMetadataV12 {
+	pub name: DecodeDifferentStr,
+	pub ty: TypeIndex,
+}
+
+/// All the metadata about an event.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct EventMetadataV12 {
+	pub name: DecodeDifferentStr,
+	pub arguments: Vec<TypeIndex>,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+}
+
+/// All metadata of the storage.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct StorageMetadataV12 {
+	/// The common prefix used by all storage entries.
+	pub prefix: DecodeDifferentStr,
+	pub entries: Vec<StorageEntryMetadataV12>,
+}
+
+/// All the metadata about one storage entry.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct StorageEntryMetadataV12 {
+	pub name: DecodeDifferentStr,
+	pub modifier: StorageEntryModifier,
+	pub ty: StorageEntryTypeV12,
+	pub default: ByteGetter,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+}
+
+/// A storage entry type.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub enum StorageEntryTypeV12 {
+	Plain(TypeIndex),
+	Map {
+		hasher: StorageHasher,
+		key: TypeIndex,
+		value: TypeIndex,
+	},
+	DoubleMap {
+		hasher: StorageHasher,
+		key1: TypeIndex,
+		key2: TypeIndex,
+		value: TypeIndex,
+		key2_hasher: StorageHasher,
+	},
+}
+
+/// All the metadata about one module constant.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct ModuleConstantMetadataV12 {
+	pub name: DecodeDifferentStr,
+	pub ty: TypeIndex,
+	pub value: ByteGetter,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+}
+
 impl Into<sp_core::OpaqueMetadata> for RuntimeMetadataPrefixed {
 	fn into(self) -> sp_core::OpaqueMetadata {
 		sp_core::OpaqueMetadata::new(self.encode())
@@ -422,3 +529,9 @@ impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataLastVersion {
 		RuntimeMetadataPrefixed(META_RESERVED, RuntimeMetadata::V11(self))
 	}
 }
+
+impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataV12 {
+	fn into(self) -> RuntimeMetadataPrefixed {
+		RuntimeMetadataPrefixed(META_RESERVED, RuntimeMetadata::V12(self))
+	}
+}
@@ -0,0 +1,520 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Portable type registry used by metadata V12.
+//!
+//! Every type that is referenced from the metadata is described once in the [`Registry`] and
+//! referred to by its index afterwards. This allows clients to decode any call, event or storage
+//! value of a runtime without maintaining a separate type definition file.
+//!
+//! Types describe themselves by implementing [`TypeInfo`], which is usually derived.
+
+#[cfg(feature = "std")]
+use serde::Serialize;
+#[cfg(feature = "std")]
+use codec::Decode;
+use codec::{Encode, Compact};
+use sp_std::{
+	prelude::*,
+	any::TypeId,
+	collections::{btree_map::{BTreeMap, Entry}, btree_set::BTreeSet},
+	marker::PhantomData,
+};
+use sp_core::RuntimeDebug;
+use crate::{DecodeDifferent, DecodeDifferentArray, DecodeDifferentStr, StringBuf};
+
+/// Index of a type in the [`PortableRegistry`].
+pub type TypeIndex = u32;
+
+/// A type that can describe itself in the [`Registry`].
+pub trait TypeInfo {
+	/// Returns the description of `Self`.
+	///
+	/// Any type referenced by `Self` needs to be registered in `registry` to obtain its index.
+	fn type_info(registry: &mut Registry) -> Type;
+}
+
+/// Collects the descriptions of all types referenced by the metadata.
+///
+/// Each type is only described once, every further registration returns the same index.
+#[derive(Default)]
+pub struct Registry {
+	indices: BTreeMap<TypeId, TypeIndex>,
+	types: Vec<Option<Type>>,
+}
+
+impl Registry {
+	/// Create a new empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register `T` and all the types it references, returning the index of `T`.
+	pub fn register<T: TypeInfo + ?Sized + 'static>(&mut self) -> TypeIndex {
+		let index = match self.indices.entry(TypeId::of::<T>()) {
+			Entry::Occupied(entry) => return *entry.get(),
+			Entry::Vacant(entry) => *entry.insert(self.types.len() as TypeIndex),
+		};
+
+		// The index is reserved before describing the type, so recursive types resolve to it.
+		self.types.push(None);
+		let ty = T::type_info(self);
+		self.types[index as usize] = Some(ty);
+
+		index
+	}
+
+	/// Convert into the registry that is put into the metadata.
+	pub fn into_portable(self) -> PortableRegistry {
+		PortableRegistry {
+			types: self.types
+				.into_iter()
+				.map(|ty| ty.expect("Every type is described before `register` returns; qed"))
+				.collect(),
+		}
+	}
+}
+
+/// All the types referenced by the metadata, addressed by their [`TypeIndex`].
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug, Default)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct PortableRegistry {
+	pub types: Vec<Type>,
+}
+
+impl PortableRegistry {
+	/// Returns the type at the given index.
+	pub fn resolve(&self, index: TypeIndex) -> Option<&Type> {
+		self.types.get(index as usize)
+	}
+}
+
+/// Description of a single type.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct Type {
+	/// The module path and the name of the type, empty for built-in types.
+	pub path: Vec<DecodeDifferentStr>,
+	/// The generic parameters the type is instantiated with.
+	pub params: Vec<TypeIndex>,
+	/// The shape of the type.
+	pub def: TypeDef,
+}
+
+impl Type {
+	/// Create a type without path or generic parameters.
+	pub fn builtin(def: TypeDef) -> Self {
+		Type { path: Vec::new(), params: Vec::new(), def }
+	}
+
+	/// Create a tuple struct without generic parameters that wraps the type at `inner`.
+	pub fn wrapper(path: Vec<DecodeDifferentStr>, inner: TypeIndex) -> Self {
+		Type { path, params: Vec::new(), def: TypeDef::Composite(vec![Field::unnamed(inner)]) }
+	}
+}
+
+/// The shape of a type.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub enum TypeDef {
+	/// A struct with named or unnamed fields.
+	Composite(Vec<Field>),
+	/// An enum.
+	Variant(Vec<Variant>),
+	/// A variable length sequence of the given type.
+	Sequence(TypeIndex),
+	/// A fixed length array of the given type.
+	Array { len: u32, ty: TypeIndex },
+	/// A tuple of the given types.
+	Tuple(Vec<TypeIndex>),
+	/// A built-in primitive.
+	Primitive(TypeDefPrimitive),
+	/// The given type encoded with the `Compact` encoding.
+	Compact(TypeIndex),
+}
+
+/// A field of a struct or of an enum variant.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct Field {
+	/// The name of the field, `None` for tuple-like fields.
+	pub name: Option<DecodeDifferentStr>,
+	pub ty: TypeIndex,
+}
+
+impl Field {
+	/// Create a named field.
+	pub fn named(name: &'static str, ty: TypeIndex) -> Self {
+		Field { name: Some(DecodeDifferent::Encode(name)), ty }
+	}
+
+	/// Create an unnamed field.
+	pub fn unnamed(ty: TypeIndex) -> Self {
+		Field { name: None, ty }
+	}
+}
+
+/// A variant of an enum.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct Variant {
+	pub name: DecodeDifferentStr,
+	pub fields: Vec<Field>,
+	/// The index that is used to encode the variant.
+	pub index: u8,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+}
+
+impl Variant {
+	/// Create a variant without documentation.
+	pub fn new(name: &'static str, fields: Vec<Field>, index: u8) -> Self {
+		Variant {
+			name: DecodeDifferent::Encode(name),
+			fields,
+			index,
+			documentation: DecodeDifferent::Encode(&[]),
+		}
+	}
+}
+
+/// A built-in primitive type.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub enum TypeDefPrimitive {
+	Bool,
+	Char,
+	Str,
+	U8,
+	U16,
+	U32,
+	U64,
+	U128,
+	I8,
+	I16,
+	I32,
+	I64,
+	I128,
+}
+
+/// Split a `module_path!()` and a type name into a type path.
+pub fn type_path(module_path: &'static str, name: &'static str) -> Vec<DecodeDifferentStr> {
+	module_path.split("::")
+		.chain(sp_std::iter::once(name))
+		.map(DecodeDifferent::Encode)
+		.collect()
+}
+
+macro_rules! impl_primitives {
+	( $( $ty:ty => $primitive:ident, )* ) => {
+		$(
+			impl TypeInfo for $ty {
+				fn type_info(_: &mut Registry) -> Type {
+					Type::builtin(TypeDef::Primitive(TypeDefPrimitive::$primitive))
+				}
+			}
+		)*
+	}
+}
+
+impl_primitives! {
+	bool => Bool,
+	char => Char,
+	str => Str,
+	u8 => U8,
+	u16 => U16,
+	u32 => U32,
+	u64 => U64,
+	u128 => U128,
+	i8 => I8,
+	i16 => I16,
+	i32 => I32,
+	i64 => I64,
+	i128 => I128,
+}
+
+#[cfg(feature = "std")]
+impl TypeInfo for String {
+	fn type_info(_: &mut Registry) -> Type {
+		Type::builtin(TypeDef::Primitive(TypeDefPrimitive::Str))
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for [T] {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type::builtin(TypeDef::Sequence(registry.register::<T>()))
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Vec<T> {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type::builtin(TypeDef::Sequence(registry.register::<T>()))
+	}
+}
+
+impl<T: Ord + TypeInfo + 'static> TypeInfo for BTreeSet<T> {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type::builtin(TypeDef::Sequence(registry.register::<T>()))
+	}
+}
+
+impl<K: Ord + TypeInfo + 'static, V: TypeInfo + 'static> TypeInfo for BTreeMap<K, V> {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type::builtin(TypeDef::Sequence(registry.register::<(K, V)>()))
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Box<T> {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type {
+			path: vec![DecodeDifferent::Encode("Box")],
+			params: Vec::new(),
+			def: TypeDef::Composite(vec![Field::unnamed(registry.register::<T>())]),
+		}
+	}
+}
+
+impl<T: ?Sized + 'static> TypeInfo for PhantomData<T> {
+	fn type_info(_: &mut Registry) -> Type {
+		Type::builtin(TypeDef::Tuple(Vec::new()))
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Compact<T> {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type::builtin(TypeDef::Compact(registry.register::<T>()))
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Option<T> {
+	fn type_info(registry: &mut Registry) -> Type {
+		let ty = registry.register::<T>();
+		Type {
+			path: vec![DecodeDifferent::Encode("Option")],
+			params: vec![ty],
+			def: TypeDef::Variant(vec![
+				Variant::new("None", Vec::new(), 0),
+				Variant::new("Some", vec![Field::unnamed(ty)], 1),
+			]),
+		}
+	}
+}
+
+impl<T: TypeInfo + 'static, E: TypeInfo + 'static> TypeInfo for Result<T, E> {
+	fn type_info(registry: &mut Registry) -> Type {
+		let ok = registry.register::<T>();
+		let err = registry.register::<E>();
+		Type {
+			path: vec![DecodeDifferent::Encode("Result")],
+			params: vec![ok, err],
+			def: TypeDef::Variant(vec![
+				Variant::new("Ok", vec![Field::unnamed(ok)], 0),
+				Variant::new("Err", vec![Field::unnamed(err)], 1),
+			]),
+		}
+	}
+}
+
+macro_rules! impl_arrays {
+	( $( $n:expr ),* ) => {
+		$(
+			impl<T: TypeInfo + 'static> TypeInfo for [T; $n] {
+				fn type_info(registry: &mut Registry) -> Type {
+					Type::builtin(TypeDef::Array { len: $n, ty: registry.register::<T>() })
+				}
+			}
+		)*
+	}
+}
+
+impl_arrays!(
+	1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+	17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
+	40, 48, 56, 64, 72, 96, 128, 160, 192, 224, 256
+);
+
+macro_rules! impl_tuples {
+	( $first:ident $(, $rest:ident )* ) => {
+		impl<$first: TypeInfo + 'static $(, $rest: TypeInfo + 'static )*> TypeInfo
+			for ($first, $( $rest, )*)
+		{
+			fn type_info(registry: &mut Registry) -> Type {
+				Type::builtin(TypeDef::Tuple(vec![
+					registry.register::<$first>() $(, registry.register::<$rest>() )*
+				]))
+			}
+		}
+
+		impl_tuples!($( $rest ),*);
+	};
+	() => {
+		impl TypeInfo for () {
+			fn type_info(_: &mut Registry) -> Type {
+				Type::builtin(TypeDef::Tuple(Vec::new()))
+			}
+		}
+	};
+}
+
+impl_tuples!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+macro_rules! impl_wrappers {
+	( $( $ty:ty => $module_path:expr, $name:expr, $inner:ty; )* ) => {
+		$(
+			impl TypeInfo for $ty {
+				fn type_info(registry: &mut Registry) -> Type {
+					Type::wrapper(type_path($module_path, $name), registry.register::<$inner>())
+				}
+			}
+		)*
+	}
+}
+
+impl_wrappers! {
+	sp_core::H160 => "primitive_types", "H160", [u8; 20];
+	sp_core::H256 => "primitive_types", "H256", [u8; 32];
+	sp_core::H512 => "primitive_types", "H512", [u8; 64];
+	sp_core::crypto::AccountId32 => "sp_core::crypto", "AccountId32", [u8; 32];
+	sp_core::crypto::KeyTypeId => "sp_core::crypto", "KeyTypeId", [u8; 4];
+	sp_core::sr25519::Public => "sp_core::sr25519", "Public", [u8; 32];
+	sp_core::sr25519::Signature => "sp_core::sr25519", "Signature", [u8; 64];
+	sp_core::ed25519::Public => "sp_core::ed25519", "Public", [u8; 32];
+	sp_core::ed25519::Signature => "sp_core::ed25519", "Signature", [u8; 64];
+	sp_core::offchain::OpaquePeerId => "sp_core::offchain", "OpaquePeerId", Vec<u8>;
+	sp_core::offchain::OpaqueMultiaddr => "sp_core::offchain", "OpaqueMultiaddr", Vec<u8>;
+	sp_arithmetic::Percent => "sp_arithmetic::per_things", "Percent", u8;
+	sp_arithmetic::PerU16 => "sp_arithmetic::per_things", "PerU16", u16;
+	sp_arithmetic::Permill => "sp_arithmetic::per_things", "Permill", u32;
+	sp_arithmetic::Perbill => "sp_arithmetic::per_things", "Perbill", u32;
+	sp_arithmetic::Perquintill => "sp_arithmetic::per_things", "Perquintill", u64;
+	sp_arithmetic::Fixed64 => "sp_arithmetic::fixed64", "Fixed64", i64;
+	sp_arithmetic::Fixed128 => "sp_arithmetic::fixed_point", "Fixed128", i128;
+	sp_arithmetic::FixedU128 => "sp_arithmetic::fixed_point", "FixedU128", u128;
+}
+
+impl TypeInfo for sp_core::ChangesTrieConfiguration {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type {
+			path: type_path("sp_core::changes_trie", "ChangesTrieConfiguration"),
+			params: Vec::new(),
+			def: TypeDef::Composite(vec![
+				Field::named("digest_interval", registry.register::<u32>()),
+				Field::named("digest_levels", registry.register::<u32>()),
+			]),
+		}
+	}
+}
+
+impl TypeInfo for sp_core::offchain::OpaqueNetworkState {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type {
+			path: type_path("sp_core::offchain", "OpaqueNetworkState"),
+			params: Vec::new(),
+			def: TypeDef::Composite(vec![
+				Field::named("peer_id", registry.register::<sp_core::offchain::OpaquePeerId>()),
+				Field::named(
+					"external_addresses",
+					registry.register::<Vec<sp_core::offchain::OpaqueMultiaddr>>(),
+				),
+			]),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct Node {
+		_next: Option<Box<Node>>,
+	}
+
+	impl TypeInfo for Node {
+		fn type_info(registry: &mut Registry) -> Type {
+			Type {
+				path: type_path("tests", "Node"),
+				params: Vec::new(),
+				def: TypeDef::Composite(vec![
+					Field::named("next", registry.register::<Option<Box<Node>>>()),
+				]),
+			}
+		}
+	}
+
+	#[test]
+	fn types_are_registered_once() {
+		let mut registry = Registry::new();
+		let a = registry.register::<Vec<u32>>();
+		let b = registry.register::<u32>();
+		assert_eq!(a, registry.register::<Vec<u32>>());
+		assert_eq!(b, registry.register::<u32>());
+
+		let registry = registry.into_portable();
+		assert_eq!(registry.types.len(), 2);
+		assert_eq!(registry.resolve(a).unwrap().def, TypeDef::Sequence(b));
+		assert_eq!(registry.resolve(b).unwrap().def, TypeDef::Primitive(TypeDefPrimitive::U32));
+	}
+
+	#[test]
+	fn compact_and_tuples_work() {
+		let mut registry = Registry::new();
+		let compact = registry.register::<Compact<u64>>();
+		let tuple = registry.register::<(u8, [u8; 32])>();
+
+		let registry = registry.into_portable();
+		let u64_index = match registry.resolve(compact).unwrap().def {
+			TypeDef::Compact(ty) => ty,
+			ref def => panic!("Unexpected def {:?}", def),
+		};
+		assert_eq!(
+			registry.resolve(u64_index).unwrap().def,
+			TypeDef::Primitive(TypeDefPrimitive::U64),
+		);
+		match registry.resolve(tuple).unwrap().def {
+			TypeDef::Tuple(ref fields) => assert_eq!(
+				registry.resolve(fields[1]).unwrap().def,
+				TypeDef::Array { len: 32, ty: fields[0] },
+			),
+			ref def => panic!("Unexpected def {:?}", def),
+		}
+	}
+
+	#[test]
+	fn recursive_types_work() {
+		let mut registry = Registry::new();
+		let node = registry.register::<Node>();
+
+		let registry = registry.into_portable();
+		let option = match registry.resolve(node).unwrap().def {
+			TypeDef::Composite(ref fields) => fields[0].ty,
+			ref def => panic!("Unexpected def {:?}", def),
+		};
+		let boxed = registry.resolve(option).unwrap().params[0];
+		assert_eq!(
+			registry.resolve(boxed).unwrap().def,
+			TypeDef::Composite(vec![Field::unnamed(node)]),
+		);
+	}
+
+	#[test]
+	fn registry_encoding_roundtrips() {
+		let mut registry = Registry::new();
+		registry.register::<Result<Option<sp_core::H256>, Vec<u8>>>();
+		let registry = registry.into_portable();
+
+		let encoded = registry.encode();
+		assert_eq!(PortableRegistry::decode(&mut &encoded[..]).unwrap(), registry);
+	}
+}
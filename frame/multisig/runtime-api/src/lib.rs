@@ -0,0 +1,100 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for multisig module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Encode, Codec, Decode};
+use sp_runtime::RuntimeDebug;
+use frame_metadata::{TypeInfo, Registry, Type, TypeDef, Field, registry::type_path};
+
+/// The hash of a call, as used to identify a multisig operation.
+pub type CallHash = [u8; 32];
+
+/// A global extrinsic index, formed as the extrinsic index within a block, together with that
+/// block's height. This allows a transaction in which a multisig operation of a particular
+/// composite was created to be uniquely identified.
+#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+pub struct Timepoint<BlockNumber> {
+	/// The height of the chain at the point in time.
+	pub height: BlockNumber,
+	/// The index of the extrinsic at the point in time.
+	pub index: u32,
+}
+
+/// An open multisig operation.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+pub struct Multisig<BlockNumber, Balance, AccountId> {
+	/// The extrinsic when the multisig operation was opened.
+	pub when: Timepoint<BlockNumber>,
+	/// The amount held in reserve of the `depositor`, to be returned once the operation ends.
+	pub deposit: Balance,
+	/// The account who opened it (i.e. the first to approve it).
+	pub depositor: AccountId,
+	/// The approvals achieved so far, including the depositor. Always sorted.
+	pub approvals: Vec<AccountId>,
+}
+
+impl<BlockNumber: TypeInfo + 'static> TypeInfo for Timepoint<BlockNumber> {
+	fn type_info(registry: &mut Registry) -> Type {
+		let height = registry.register::<BlockNumber>();
+		Type {
+			path: type_path(module_path!(), "Timepoint"),
+			params: vec![height],
+			def: TypeDef::Composite(vec![
+				Field::named("height", height),
+				Field::named("index", registry.register::<u32>()),
+			]),
+		}
+	}
+}
+
+impl<BlockNumber, Balance, AccountId> TypeInfo for Multisig<BlockNumber, Balance, AccountId> where
+	BlockNumber: TypeInfo + 'static,
+	Balance: TypeInfo + 'static,
+	AccountId: TypeInfo + 'static,
+{
+	fn type_info(registry: &mut Registry) -> Type {
+		let balance = registry.register::<Balance>();
+		let account_id = registry.register::<AccountId>();
+		Type {
+			path: type_path(module_path!(), "Multisig"),
+			params: vec![registry.register::<BlockNumber>(), balance, account_id],
+			def: TypeDef::Composite(vec![
+				Field::named("when", registry.register::<Timepoint<BlockNumber>>()),
+				Field::named("deposit", balance),
+				Field::named("depositor", account_id),
+				Field::named("approvals", registry.register::<Vec<AccountId>>()),
+			]),
+		}
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait MultisigApi<AccountId, BlockNumber, Balance> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Balance: Codec,
+	{
+		/// Returns all open multisig operations of the given multi-account, each together with
+		/// the hash of the call it is for.
+		fn pending_multisigs(
+			multi_account: AccountId,
+		) -> Vec<(CallHash, Multisig<BlockNumber, Balance, AccountId>)>;
+	}
+}
@@ -0,0 +1,678 @@
+// Copyright 2019-2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Multisig Module
+//! A module for doing multisig dispatch.
+//!
+//! - [`multisig::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! This module contains functionality for multi-signature dispatch, a (potentially) stateful
+//! operation, allowing multiple signed origins (accounts) to coordinate and dispatch a call from a
+//! well-known origin, derivable deterministically from the set of account IDs and the threshold
+//! number of accounts from the set that must approve it. In the case that the threshold is just
+//! one then this is a stateless operation. This is useful for multisig wallets where cryptographic
+//! threshold signatures are not available or desired.
+//!
+//! The encoded call may optionally be stored on chain, against a deposit, by any of the
+//! approvers. Once stored, the remaining approvers only ever need to provide its hash.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `as_multi` - Approve and if possible dispatch a call from a composite origin formed from a
+//!   number of signed origins.
+//! * `approve_as_multi` - Approve a call from a composite origin.
+//! * `cancel_as_multi` - Cancel a call from a composite origin.
+//! * `as_multi_threshold_1` - Dispatch a call from a composite origin with a threshold of one.
+//!
+//! [`Call`]: ./enum.Call.html
+//! [`Trait`]: ./trait.Trait.html
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use sp_io::hashing::blake2_256;
+use frame_support::{
+	decl_module, decl_event, decl_error, decl_storage, Parameter, ensure, IterableStorageDoubleMap,
+	metadata::TypeInfo,
+};
+use frame_support::{traits::{Get, ReservableCurrency, Currency},
+	weights::{Weight, GetDispatchInfo, DispatchClass, FunctionOf, ROCKS_DB_WEIGHT},
+	dispatch::{DispatchResultWithPostInfo, PostDispatchInfo},
+};
+use frame_system::{self as system, ensure_signed};
+use sp_runtime::{DispatchError, DispatchResult, RuntimeDebug, traits::Dispatchable};
+
+pub use pallet_multisig_runtime_api::{CallHash, Timepoint, Multisig};
+
+mod tests;
+mod benchmarking;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
+/// Just a bunch of bytes, but they should decode to a valid `Call`.
+pub type OpaqueCall = Vec<u8>;
+
+/// Configuration trait.
+pub trait Trait: frame_system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+	/// The overarching call type.
+	type Call: Parameter + Dispatchable<Origin=Self::Origin, PostInfo=PostDispatchInfo>
+		+ GetDispatchInfo + From<frame_system::Call<Self>>;
+
+	/// The currency mechanism.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The base amount of currency needed to reserve for creating a multisig execution or to store
+	/// a dispatch call for later.
+	///
+	/// This is held for an additional storage item whose value size is
+	/// `4 + sizeof((BlockNumber, Balance, AccountId))` bytes.
+	type DepositBase: Get<BalanceOf<Self>>;
+
+	/// The amount of currency needed per unit threshold when creating a multisig execution, and
+	/// per 32 bytes of call data when storing a dispatch call.
+	///
+	/// This is held for adding 32 bytes more into a pre-existing storage value.
+	type DepositFactor: Get<BalanceOf<Self>>;
+
+	/// The maximum amount of signatories allowed in the multisig.
+	type MaxSignatories: Get<u16>;
+}
+
+/// The storage layout of the module.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+enum Releases {
+	/// Open multisig operations are stored by the `Utility` module.
+	V1_0_0,
+	/// Open multisig operations are stored by this module.
+	V2_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Multisig {
+		/// The set of open multisig operations.
+		pub Multisigs: double_map
+			hasher(twox_64_concat) T::AccountId, hasher(blake2_128_concat) CallHash
+			=> Option<Multisig<T::BlockNumber, BalanceOf<T>, T::AccountId>>;
+
+		/// The encoded calls of open multisig operations, stored by their hash together with the
+		/// account that stored them and the deposit held for doing so.
+		pub Calls: map hasher(identity) CallHash => Option<(OpaqueCall, T::AccountId, BalanceOf<T>)>;
+
+		/// The storage layout in use, used to migrate the storage of older releases.
+		StorageVersion: Releases;
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Threshold must be 2 or greater.
+		MinimumThreshold,
+		/// Call is already approved by this signatory.
+		AlreadyApproved,
+		/// Call doesn't need any (more) approvals.
+		NoApprovalsNeeded,
+		/// There are too few signatories in the list.
+		TooFewSignatories,
+		/// There are too many signatories in the list.
+		TooManySignatories,
+		/// The signatories were provided out of order; they should be ordered.
+		SignatoriesOutOfOrder,
+		/// The sender was contained in the other signatories; it shouldn't be.
+		SenderInSignatories,
+		/// Multisig operation not found when attempting to cancel.
+		NotFound,
+		/// Only the account that originally created the multisig is able to cancel it.
+		NotOwner,
+		/// No timepoint was given, yet the multisig operation is already underway.
+		NoTimepoint,
+		/// A different timepoint was given to the multisig operation that is underway.
+		WrongTimepoint,
+		/// A timepoint was given, yet no multisig operation is underway.
+		UnexpectedTimepoint,
+		/// The maximum weight information provided was too low.
+		WeightTooLow,
+	}
+}
+
+decl_event! {
+	/// Events type.
+	pub enum Event<T> where
+		AccountId = <T as system::Trait>::AccountId,
+		BlockNumber = <T as system::Trait>::BlockNumber
+	{
+		/// A new multisig operation has begun. First param is the account that is approving,
+		/// second is the multisig account, third is hash of the call.
+		NewMultisig(AccountId, AccountId, CallHash),
+		/// A multisig operation has been approved by someone. First param is the account that is
+		/// approving, third is the multisig account, fourth is hash of the call.
+		MultisigApproval(AccountId, Timepoint<BlockNumber>, AccountId, CallHash),
+		/// A multisig operation has been executed. First param is the account that is
+		/// approving, third is the multisig account, fourth is hash of the call to be executed.
+		MultisigExecuted(AccountId, Timepoint<BlockNumber>, AccountId, CallHash, DispatchResult),
+		/// A multisig operation has been cancelled. First param is the account that is
+		/// cancelling, third is the multisig account, fourth is hash of the call.
+		MultisigCancelled(AccountId, Timepoint<BlockNumber>, AccountId, CallHash),
+	}
+}
+
+/// The weight of a multisig approval, not counting the dispatch of the call itself.
+///
+/// - `10,000` per signatory, including the sender.
+/// - One unit per byte of the call data, if any, for hashing and possibly storing it.
+fn approval_weight(other_signatories: usize, call_len: usize) -> Weight {
+	10_000u32.saturating_mul(other_signatories as Weight + 1).saturating_add(call_len as Weight)
+}
+
+/// Either the encoded call to be dispatched, together with whether it should be stored on
+/// chain, or just its hash.
+enum CallOrHash {
+	Call(OpaqueCall, bool),
+	Hash(CallHash),
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		/// Deposit one of this module's events by using the default implementation.
+		fn deposit_event() = default;
+
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get() == Releases::V1_0_0 {
+				StorageVersion::put(Releases::V2_0_0);
+				let migrated = Self::migrate();
+				// Each operation is read and removed from the old storage and written to the new
+				// one, the storage version is read and written.
+				ROCKS_DB_WEIGHT.reads_writes(migrated.saturating_add(1), migrated.saturating_mul(2).saturating_add(1))
+			} else {
+				ROCKS_DB_WEIGHT.reads(1)
+			}
+		}
+
+		/// Immediately dispatch a multi-signature call using a single approval from the caller.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `other_signatories`: The accounts (other than the sender) who are part of the
+		/// multi-signature, but do not participate in the approval process.
+		/// - `call`: The call to be executed.
+		///
+		/// Result is equivalent to the dispatched result.
+		///
+		/// # <weight>
+		/// - `O(S + Call)` where `S` is the number of signatories.
+		/// - The weight of the `call` + 10,000 per signatory.
+		/// # </weight>
+		#[weight = FunctionOf(
+			|args: (&Vec<T::AccountId>, &Box<<T as Trait>::Call>)| {
+				args.1.get_dispatch_info().weight.saturating_add(approval_weight(args.0.len(), 0))
+			},
+			|args: (&Vec<T::AccountId>, &Box<<T as Trait>::Call>)| args.1.get_dispatch_info().class,
+			true
+		)]
+		fn as_multi_threshold_1(origin,
+			other_signatories: Vec<T::AccountId>,
+			call: Box<<T as Trait>::Call>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let max_sigs = T::MaxSignatories::get() as usize;
+			ensure!(!other_signatories.is_empty(), Error::<T>::TooFewSignatories);
+			ensure!(other_signatories.len() < max_sigs, Error::<T>::TooManySignatories);
+			let signatories = Self::ensure_sorted_and_insert(other_signatories, who)?;
+
+			let id = Self::multi_account_id(&signatories, 1);
+			call.dispatch(frame_system::RawOrigin::Signed(id).into())
+				.map(|_| ()).map_err(|e| e.error)
+		}
+
+		/// Register approval for a dispatch to be made from a deterministic composite account if
+		/// approved by a total of `threshold - 1` of `other_signatories`.
+		///
+		/// If there are enough, then dispatch the call.
+		///
+		/// Payment: `DepositBase` will be reserved if this is the first approval, plus
+		/// `threshold` times `DepositFactor`. It is returned once this dispatch happens or
+		/// is cancelled.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The total number of approvals for this dispatch before it is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve this
+		/// dispatch. May not be empty.
+		/// - `maybe_timepoint`: If this is the first approval, then this must be `None`. If it is
+		/// not the first approval, then it must be `Some`, with the timepoint (block number and
+		/// transaction index) of the first approval transaction.
+		/// - `call`: The encoded call to be executed.
+		/// - `store_call`: Whether to store the encoded call on chain, so that later approvals
+		/// need only provide its hash. This requires a further deposit of `DepositBase` plus
+		/// `DepositFactor` per 32 bytes of call data, which is returned once this dispatch happens
+		/// or is cancelled.
+		/// - `max_weight`: The maximum weight that the dispatch of `call` is allowed to consume.
+		///
+		/// NOTE: Unless this is the final approval, you will generally want to use
+		/// `approve_as_multi` instead, since it only requires a hash of the call.
+		///
+		/// Result is equivalent to the dispatched result if `threshold` is exactly `1`. Otherwise
+		/// on success, result is `Ok` and the result from the interior call, if it was executed,
+		/// may be found in the deposited `MultisigExecuted` event.
+		///
+		/// # <weight>
+		/// - `O(S + Z + Call)`.
+		/// - Up to one balance-reserve or unreserve operation.
+		/// - One passthrough operation, one insert, both `O(S)` where `S` is the number of
+		///   signatories. `S` is capped by `MaxSignatories`, with weight being proportional.
+		/// - One call hash, of complexity `O(Z)` where `Z` is the length of the call.
+		/// - One encode & hash, both of complexity `O(S)`.
+		/// - Up to one binary search and insert (`O(logS + S)`).
+		/// - I/O: 1 read `O(S)`, up to 1 mutate `O(S)`. Up to one remove.
+		/// - Up to one insert or remove of the stored call, `O(Z)`.
+		/// - One event.
+		/// - The weight of the `call`, bounded by `max_weight`. Only the weight actually consumed
+		///   is accounted for once the dispatch is done.
+		/// - Storage: inserts one item, value size bounded by `MaxSignatories`, with a
+		///   deposit taken for its lifetime of
+		///   `DepositBase + threshold * DepositFactor`.
+		/// # </weight>
+		#[weight = FunctionOf(
+			|args: (&u16, &Vec<T::AccountId>, &Option<Timepoint<T::BlockNumber>>, &OpaqueCall, &bool, &Weight)| {
+				approval_weight(args.1.len(), args.3.len()).saturating_add(*args.5)
+			},
+			DispatchClass::Normal,
+			true
+		)]
+		fn as_multi(origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			maybe_timepoint: Option<Timepoint<T::BlockNumber>>,
+			call: OpaqueCall,
+			store_call: bool,
+			max_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::operate(
+				who,
+				threshold,
+				other_signatories,
+				maybe_timepoint,
+				CallOrHash::Call(call, store_call),
+				max_weight,
+			)
+		}
+
+		/// Register approval for a dispatch to be made from a deterministic composite account if
+		/// approved by a total of `threshold - 1` of `other_signatories`.
+		///
+		/// If this approval is the final one needed and the call was stored on chain through a
+		/// previous `as_multi`, then the call is dispatched.
+		///
+		/// Payment: `DepositBase` will be reserved if this is the first approval, plus
+		/// `threshold` times `DepositFactor`. It is returned once this dispatch happens or
+		/// is cancelled.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The total number of approvals for this dispatch before it is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve this
+		/// dispatch. May not be empty.
+		/// - `maybe_timepoint`: If this is the first approval, then this must be `None`. If it is
+		/// not the first approval, then it must be `Some`, with the timepoint (block number and
+		/// transaction index) of the first approval transaction.
+		/// - `call_hash`: The hash of the call to be executed.
+		/// - `max_weight`: The maximum weight that the dispatch of the stored call, if any, is
+		/// allowed to consume.
+		///
+		/// NOTE: If this is the final approval and the call was not stored, you will want to use
+		/// `as_multi` instead.
+		///
+		/// # <weight>
+		/// - `O(S)`.
+		/// - Up to one balance-reserve or unreserve operation.
+		/// - One passthrough operation, one insert, both `O(S)` where `S` is the number of
+		///   signatories. `S` is capped by `MaxSignatories`, with weight being proportional.
+		/// - One encode & hash, both of complexity `O(S)`.
+		/// - Up to one binary search and insert (`O(logS + S)`).
+		/// - I/O: 1 read `O(S)`, up to 1 mutate `O(S)`. Up to one remove.
+		/// - One event.
+		/// - The weight of the stored call, if dispatched, bounded by `max_weight`.
+		/// - Storage: inserts one item, value size bounded by `MaxSignatories`, with a
+		///   deposit taken for its lifetime of
+		///   `DepositBase + threshold * DepositFactor`.
+		/// # </weight>
+		#[weight = FunctionOf(
+			|args: (&u16, &Vec<T::AccountId>, &Option<Timepoint<T::BlockNumber>>, &CallHash, &Weight)| {
+				approval_weight(args.1.len(), 0).saturating_add(*args.4)
+			},
+			DispatchClass::Normal,
+			true
+		)]
+		fn approve_as_multi(origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			maybe_timepoint: Option<Timepoint<T::BlockNumber>>,
+			call_hash: CallHash,
+			max_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::operate(
+				who,
+				threshold,
+				other_signatories,
+				maybe_timepoint,
+				CallOrHash::Hash(call_hash),
+				max_weight,
+			)
+		}
+
+		/// Cancel a pre-existing, on-going multisig transaction. Any deposit reserved previously
+		/// for this operation, including for storing its call, will be unreserved on success.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `threshold`: The total number of approvals for this dispatch before it is executed.
+		/// - `other_signatories`: The accounts (other than the sender) who can approve this
+		/// dispatch. May not be empty.
+		/// - `timepoint`: The timepoint (block number and transaction index) of the first approval
+		/// transaction for this dispatch.
+		/// - `call_hash`: The hash of the call to be executed.
+		///
+		/// # <weight>
+		/// - `O(S)`.
+		/// - Up to two balance-unreserve operations.
+		/// - One passthrough operation, one insert, both `O(S)` where `S` is the number of
+		///   signatories. `S` is capped by `MaxSignatories`, with weight being proportional.
+		/// - One encode & hash, both of complexity `O(S)`.
+		/// - One event.
+		/// - I/O: 1 read `O(S)`, one remove, up to one remove of the stored call.
+		/// - Storage: removes up to two items.
+		/// # </weight>
+		#[weight = FunctionOf(
+			|args: (&u16, &Vec<T::AccountId>, &Timepoint<T::BlockNumber>, &CallHash)| {
+				approval_weight(args.1.len(), 0)
+			},
+			DispatchClass::Normal,
+			true
+		)]
+		fn cancel_as_multi(origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			timepoint: Timepoint<T::BlockNumber>,
+			call_hash: CallHash,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(threshold >= 2, Error::<T>::MinimumThreshold);
+			let max_sigs = T::MaxSignatories::get() as usize;
+			ensure!(!other_signatories.is_empty(), Error::<T>::TooFewSignatories);
+			ensure!(other_signatories.len() < max_sigs, Error::<T>::TooManySignatories);
+			let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
+
+			let id = Self::multi_account_id(&signatories, threshold);
+
+			let m = <Multisigs<T>>::get(&id, call_hash)
+				.ok_or(Error::<T>::NotFound)?;
+			ensure!(m.when == timepoint, Error::<T>::WrongTimepoint);
+			ensure!(m.depositor == who, Error::<T>::NotOwner);
+
+			let _ = T::Currency::unreserve(&m.depositor, m.deposit);
+			<Multisigs<T>>::remove(&id, call_hash);
+			Self::clear_call(&call_hash);
+
+			Self::deposit_event(RawEvent::MultisigCancelled(who, timepoint, id, call_hash));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Derive a multi-account ID from the sorted list of accounts and the threshold that are
+	/// required.
+	///
+	/// NOTE: `who` must be sorted. If it is not, then you'll get the wrong answer.
+	pub fn multi_account_id(who: &[T::AccountId], threshold: u16) -> T::AccountId {
+		let entropy = (b"modlpy/utilisuba", who, threshold).using_encoded(blake2_256);
+		T::AccountId::decode(&mut &entropy[..]).unwrap_or_default()
+	}
+
+	/// The current `Timepoint`.
+	pub fn timepoint() -> Timepoint<T::BlockNumber> {
+		Timepoint {
+			height: <system::Module<T>>::block_number(),
+			index: <system::Module<T>>::extrinsic_index().unwrap_or_default(),
+		}
+	}
+
+	/// All open multisig operations of the multi-account `who`, by the hash of their call.
+	pub fn pending_multisigs(
+		who: &T::AccountId,
+	) -> Vec<(CallHash, Multisig<T::BlockNumber, BalanceOf<T>, T::AccountId>)> {
+		<Multisigs<T>>::iter(who).collect()
+	}
+
+	fn operate(
+		who: T::AccountId,
+		threshold: u16,
+		other_signatories: Vec<T::AccountId>,
+		maybe_timepoint: Option<Timepoint<T::BlockNumber>>,
+		call_or_hash: CallOrHash,
+		max_weight: Weight,
+	) -> DispatchResultWithPostInfo {
+		ensure!(threshold >= 2, Error::<T>::MinimumThreshold);
+		let max_sigs = T::MaxSignatories::get() as usize;
+		ensure!(!other_signatories.is_empty(), Error::<T>::TooFewSignatories);
+		let other_signatories_len = other_signatories.len();
+		ensure!(other_signatories_len < max_sigs, Error::<T>::TooManySignatories);
+		let signatories = Self::ensure_sorted_and_insert(other_signatories, who.clone())?;
+
+		let id = Self::multi_account_id(&signatories, threshold);
+
+		let (call_hash, call_len, maybe_call, store) = match call_or_hash {
+			CallOrHash::Call(call, store) => (blake2_256(&call), call.len(), Some(call), store),
+			CallOrHash::Hash(h) => (h, 0, None, false),
+		};
+		let weight = approval_weight(other_signatories_len, call_len);
+
+		if let Some(mut m) = <Multisigs<T>>::get(&id, call_hash) {
+			let timepoint = maybe_timepoint.ok_or(Error::<T>::NoTimepoint)?;
+			ensure!(m.when == timepoint, Error::<T>::WrongTimepoint);
+
+			// We only bother with the approval if we're below threshold.
+			let maybe_pos = m.approvals.binary_search(&who).err()
+				.filter(|_| m.approvals.len() < threshold as usize);
+			// Bump approvals if not yet voted and the vote is needed.
+			if let Some(pos) = maybe_pos {
+				m.approvals.insert(pos, who.clone());
+			}
+
+			// We only bother fetching/decoding the call if we know that we're ready to execute.
+			let maybe_approved_call = if m.approvals.len() >= threshold as usize {
+				Self::get_call(&call_hash, maybe_call.as_ref())
+			} else {
+				None
+			};
+
+			if let Some(call) = maybe_approved_call {
+				let call_weight = call.get_dispatch_info().weight;
+				ensure!(call_weight <= max_weight, Error::<T>::WeightTooLow);
+
+				// Clean up storage before executing the call to avoid a possibility of reentrancy
+				// attack.
+				<Multisigs<T>>::remove(&id, call_hash);
+				Self::clear_call(&call_hash);
+				let _ = T::Currency::unreserve(&m.depositor, m.deposit);
+
+				let result = call.dispatch(frame_system::RawOrigin::Signed(id.clone()).into());
+				let actual_weight = match &result {
+					Ok(post_info) => post_info.actual_weight,
+					Err(err) => err.post_info.actual_weight,
+				}.unwrap_or(call_weight);
+				Self::deposit_event(RawEvent::MultisigExecuted(
+					who, timepoint, id, call_hash, result.map(|_| ()).map_err(|e| e.error)
+				));
+				Ok(Some(weight.saturating_add(actual_weight)).into())
+			} else {
+				// We cannot dispatch the call now; either it isn't available, or it is, but we
+				// don't have threshold approvals even with our signature.
+
+				// Store the call if desired.
+				let stored = if let Some(data) = maybe_call.filter(|_| store) {
+					Self::store_call_and_reserve(who.clone(), &call_hash, data)?
+				} else {
+					false
+				};
+
+				if maybe_pos.is_some() {
+					// Record approval.
+					<Multisigs<T>>::insert(&id, call_hash, m);
+					Self::deposit_event(RawEvent::MultisigApproval(who, timepoint, id, call_hash));
+				} else if !stored {
+					// If we already approved and didn't store the call, then this was useless and
+					// we report an error.
+					if m.approvals.binary_search(&who).is_ok() {
+						Err(Error::<T>::AlreadyApproved)?
+					} else {
+						Err(Error::<T>::NoApprovalsNeeded)?
+					}
+				}
+
+				Ok(Some(weight).into())
+			}
+		} else {
+			ensure!(maybe_timepoint.is_none(), Error::<T>::UnexpectedTimepoint);
+
+			// Just start the operation by recording it in storage.
+			let deposit = T::DepositBase::get() + T::DepositFactor::get() * threshold.into();
+			T::Currency::reserve(&who, deposit)?;
+
+			// Store the call if desired.
+			if let Some(data) = maybe_call.filter(|_| store) {
+				Self::store_call_and_reserve(who.clone(), &call_hash, data)
+					.map_err(|e| {
+						let _ = T::Currency::unreserve(&who, deposit);
+						e
+					})?;
+			}
+
+			<Multisigs<T>>::insert(&id, call_hash, Multisig {
+				when: Self::timepoint(),
+				deposit,
+				depositor: who.clone(),
+				approvals: vec![who.clone()],
+			});
+			Self::deposit_event(RawEvent::NewMultisig(who, id, call_hash));
+
+			Ok(Some(weight).into())
+		}
+	}
+
+	/// Place a call's encoded data in storage, reserving funds as appropriate.
+	///
+	/// We store `data` here because storing `call` would result in needing another `.encode`.
+	///
+	/// Returns `true` if the call was stored, `false` if it was already there.
+	fn store_call_and_reserve(
+		who: T::AccountId,
+		hash: &CallHash,
+		data: OpaqueCall,
+	) -> Result<bool, DispatchError> {
+		if <Calls<T>>::contains_key(hash) {
+			return Ok(false)
+		}
+		let deposit = T::DepositBase::get()
+			+ T::DepositFactor::get() * BalanceOf::<T>::from(((data.len() + 31) / 32) as u32);
+		T::Currency::reserve(&who, deposit)?;
+		<Calls<T>>::insert(hash, (data, who, deposit));
+		Ok(true)
+	}
+
+	/// Attempt to decode and return the call, provided by the user or from storage.
+	fn get_call(hash: &CallHash, maybe_known: Option<&OpaqueCall>) -> Option<<T as Trait>::Call> {
+		maybe_known.map_or_else(
+			|| <Calls<T>>::get(hash).and_then(|(data, ..)| Decode::decode(&mut &data[..]).ok()),
+			|data| Decode::decode(&mut &data[..]).ok(),
+		)
+	}
+
+	/// Remove the stored call of a multisig operation, if any, returning its deposit.
+	fn clear_call(hash: &CallHash) {
+		if let Some((_, who, deposit)) = <Calls<T>>::take(hash) {
+			let _ = T::Currency::unreserve(&who, deposit);
+		}
+	}
+
+	/// Check that signatories is sorted and doesn't contain sender, then insert sender.
+	fn ensure_sorted_and_insert(other_signatories: Vec<T::AccountId>, who: T::AccountId)
+		-> Result<Vec<T::AccountId>, DispatchError>
+	{
+		let mut signatories = other_signatories;
+		let mut maybe_last = None;
+		let mut index = 0;
+		for item in signatories.iter() {
+			if let Some(last) = maybe_last {
+				ensure!(last < item, Error::<T>::SignatoriesOutOfOrder);
+			}
+			if item <= &who {
+				ensure!(item != &who, Error::<T>::SenderInSignatories);
+				index += 1;
+			}
+			maybe_last = Some(item);
+		}
+		signatories.insert(index, who);
+		Ok(signatories)
+	}
+
+	/// Move any open multisig operations out of the storage of the `Utility` module, which used
+	/// to provide multisig dispatch before this module was split out of it.
+	///
+	/// Returns the number of migrated operations.
+	fn migrate() -> Weight {
+		use frame_support::migration::StorageIterator;
+
+		// Keys are `twox_64(AccountId) ++ AccountId ++ blake2_128(CallHash) ++ CallHash`.
+		fn decode_key<AccountId: Decode>(raw: &[u8]) -> Option<(AccountId, CallHash)> {
+			let mut input = raw.get(8..)?;
+			let who = AccountId::decode(&mut input).ok()?;
+			let mut input = input.get(16..)?;
+			let call_hash = CallHash::decode(&mut input).ok()?;
+			Some((who, call_hash))
+		}
+
+		let mut migrated: Weight = 0;
+		for (raw_key, multisig) in StorageIterator::<
+			Multisig<T::BlockNumber, BalanceOf<T>, T::AccountId>
+		>::new(b"Utility", b"Multisigs").drain() {
+			if let Some((who, call_hash)) = decode_key::<T::AccountId>(&raw_key) {
+				<Multisigs<T>>::insert(who, call_hash, multisig);
+			}
+			migrated = migrated.saturating_add(1);
+		}
+		migrated
+	}
+}
@@ -0,0 +1,526 @@
+// Copyright 2019-2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+// Tests for Multisig Pallet
+
+#![cfg(test)]
+
+use super::*;
+
+use frame_support::{
+	assert_ok, assert_noop, impl_outer_origin, parameter_types, impl_outer_dispatch,
+	impl_outer_event, StorageHasher, Twox64Concat, Blake2_128Concat,
+};
+use sp_core::H256;
+use sp_runtime::{Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header};
+use crate as multisig;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		pallet_balances<T>,
+		multisig<T>,
+	}
+}
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		frame_system::System,
+		pallet_balances::Balances,
+		multisig::Multisig,
+	}
+}
+
+// For testing the pallet, we construct most of a mock runtime. This means
+// first constructing a configuration type (`Test`) which `impl`s each of the
+// configuration traits of pallets we want to use.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl frame_system::Trait for Test {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Call = Call;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+parameter_types! {
+	pub const DepositBase: u64 = 1;
+	pub const DepositFactor: u64 = 1;
+	pub const MaxSignatories: u16 = 3;
+}
+impl Trait for Test {
+	type Event = TestEvent;
+	type Call = Call;
+	type Currency = Balances;
+	type DepositBase = DepositBase;
+	type DepositFactor = DepositFactor;
+	type MaxSignatories = MaxSignatories;
+}
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type Multisig = Module<Test>;
+
+use pallet_balances::Call as BalancesCall;
+use pallet_balances::Error as BalancesError;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 10), (2, 10), (3, 10), (4, 10), (5, 10)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn last_event() -> TestEvent {
+	system::Module::<Test>::events().pop().map(|e| e.event).expect("Event expected")
+}
+
+fn expect_event<E: Into<TestEvent>>(e: E) {
+	assert_eq!(last_event(), e.into());
+}
+
+fn now() -> Timepoint<u64> {
+	Multisig::timepoint()
+}
+
+fn transfer(dest: u64, value: u64) -> (OpaqueCall, CallHash, Weight) {
+	let call = Call::Balances(BalancesCall::transfer(dest, value));
+	(call.encode(), call.using_encoded(blake2_256), call.get_dispatch_info().weight)
+}
+
+#[test]
+fn multisig_deposit_is_taken_and_returned() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let (data, _, weight) = transfer(6, 15);
+		assert_ok!(Multisig::as_multi(Origin::signed(1), 2, vec![2, 3], None, data.clone(), false, 0));
+		assert_eq!(Balances::free_balance(1), 2);
+		assert_eq!(Balances::reserved_balance(1), 3);
+
+		assert_ok!(Multisig::as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), data, false, weight));
+		assert_eq!(Balances::free_balance(1), 5);
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn multisig_deposit_is_taken_and_returned_with_call_storage() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let (data, hash, weight) = transfer(6, 15);
+		assert_ok!(Multisig::as_multi(Origin::signed(1), 2, vec![2, 3], None, data, true, 0));
+		assert_eq!(Balances::free_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(1), 5);
+
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), hash, weight));
+		assert_eq!(Balances::free_balance(1), 5);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(6), 15);
+	});
+}
+
+#[test]
+fn multisig_deposit_is_taken_and_returned_with_alt_call_storage() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 3);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let (data, hash, weight) = transfer(6, 15);
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash.clone(), 0));
+		assert_eq!(Balances::free_balance(1), 1);
+		assert_eq!(Balances::reserved_balance(1), 4);
+
+		assert_ok!(Multisig::as_multi(Origin::signed(2), 3, vec![1, 3], Some(now()), data, true, 0));
+		assert_eq!(Balances::free_balance(2), 3);
+		assert_eq!(Balances::reserved_balance(2), 2);
+		assert_eq!(Balances::free_balance(1), 1);
+		assert_eq!(Balances::reserved_balance(1), 4);
+
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(3), 3, vec![1, 2], Some(now()), hash, weight));
+		assert_eq!(Balances::free_balance(1), 5);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(2), 5);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(6), 15);
+	});
+}
+
+#[test]
+fn cancel_multisig_returns_deposit() {
+	new_test_ext().execute_with(|| {
+		let (_, hash, _) = transfer(6, 15);
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash.clone(), 0));
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(2), 3, vec![1, 3], Some(now()), hash.clone(), 0));
+		assert_eq!(Balances::free_balance(1), 6);
+		assert_eq!(Balances::reserved_balance(1), 4);
+		assert_ok!(
+			Multisig::cancel_as_multi(Origin::signed(1), 3, vec![2, 3], now(), hash.clone()),
+		);
+		assert_eq!(Balances::free_balance(1), 10);
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn cancel_multisig_returns_call_deposit() {
+	new_test_ext().execute_with(|| {
+		let (data, hash, _) = transfer(6, 15);
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash.clone(), 0));
+		assert_ok!(Multisig::as_multi(Origin::signed(2), 3, vec![1, 3], Some(now()), data, true, 0));
+		assert_eq!(Balances::reserved_balance(2), 2);
+		assert!(Calls::<Test>::contains_key(hash));
+		assert_ok!(
+			Multisig::cancel_as_multi(Origin::signed(1), 3, vec![2, 3], now(), hash.clone()),
+		);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert!(!Calls::<Test>::contains_key(hash));
+	});
+}
+
+#[test]
+fn timepoint_checking_works() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let (data, hash, weight) = transfer(6, 15);
+
+		assert_noop!(
+			Multisig::approve_as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), hash.clone(), 0),
+			Error::<Test>::UnexpectedTimepoint,
+		);
+
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 2, vec![2, 3], None, hash, 0));
+
+		assert_noop!(
+			Multisig::as_multi(Origin::signed(2), 2, vec![1, 3], None, data.clone(), false, weight),
+			Error::<Test>::NoTimepoint,
+		);
+		let later = Timepoint { index: 1, .. now() };
+		assert_noop!(
+			Multisig::as_multi(Origin::signed(2), 2, vec![1, 3], Some(later), data, false, weight),
+			Error::<Test>::WrongTimepoint,
+		);
+	});
+}
+
+#[test]
+fn multisig_2_of_3_works_with_call_storing() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let (data, hash, weight) = transfer(6, 15);
+		assert_ok!(Multisig::as_multi(Origin::signed(1), 2, vec![2, 3], None, data, true, 0));
+		assert_eq!(Balances::free_balance(6), 0);
+
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), hash, weight));
+		assert_eq!(Balances::free_balance(6), 15);
+	});
+}
+
+#[test]
+fn multisig_2_of_3_works() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let (data, hash, weight) = transfer(6, 15);
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 2, vec![2, 3], None, hash, 0));
+		assert_eq!(Balances::free_balance(6), 0);
+
+		assert_ok!(Multisig::as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), data, false, weight));
+		assert_eq!(Balances::free_balance(6), 15);
+	});
+}
+
+#[test]
+fn multisig_3_of_3_works() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 3);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let (data, hash, weight) = transfer(6, 15);
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash.clone(), 0));
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(2), 3, vec![1, 3], Some(now()), hash.clone(), 0));
+		assert_eq!(Balances::free_balance(6), 0);
+
+		assert_ok!(Multisig::as_multi(Origin::signed(3), 3, vec![1, 2], Some(now()), data, false, weight));
+		assert_eq!(Balances::free_balance(6), 15);
+	});
+}
+
+#[test]
+fn cancel_multisig_works() {
+	new_test_ext().execute_with(|| {
+		let (_, hash, _) = transfer(6, 15);
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash.clone(), 0));
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(2), 3, vec![1, 3], Some(now()), hash.clone(), 0));
+		assert_noop!(
+			Multisig::cancel_as_multi(Origin::signed(2), 3, vec![1, 3], now(), hash.clone()),
+			Error::<Test>::NotOwner,
+		);
+		assert_ok!(
+			Multisig::cancel_as_multi(Origin::signed(1), 3, vec![2, 3], now(), hash.clone()),
+		);
+	});
+}
+
+#[test]
+fn multisig_2_of_3_as_multi_works() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let (data, _, weight) = transfer(6, 15);
+		assert_ok!(Multisig::as_multi(Origin::signed(1), 2, vec![2, 3], None, data.clone(), false, 0));
+		assert_eq!(Balances::free_balance(6), 0);
+
+		assert_ok!(Multisig::as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), data, false, weight));
+		assert_eq!(Balances::free_balance(6), 15);
+	});
+}
+
+#[test]
+fn multisig_2_of_3_as_multi_with_many_calls_works() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let (data1, _, weight1) = transfer(6, 10);
+		let (data2, _, weight2) = transfer(7, 5);
+
+		assert_ok!(Multisig::as_multi(Origin::signed(1), 2, vec![2, 3], None, data1.clone(), false, 0));
+		assert_ok!(Multisig::as_multi(Origin::signed(2), 2, vec![1, 3], None, data2.clone(), false, 0));
+		assert_ok!(Multisig::as_multi(Origin::signed(3), 2, vec![1, 2], Some(now()), data2, false, weight2));
+		assert_ok!(Multisig::as_multi(Origin::signed(3), 2, vec![1, 2], Some(now()), data1, false, weight1));
+
+		assert_eq!(Balances::free_balance(6), 10);
+		assert_eq!(Balances::free_balance(7), 5);
+	});
+}
+
+#[test]
+fn multisig_2_of_3_cannot_reissue_same_call() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let (data, hash, weight) = transfer(6, 10);
+		assert_ok!(Multisig::as_multi(Origin::signed(1), 2, vec![2, 3], None, data.clone(), false, 0));
+		assert_ok!(Multisig::as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), data.clone(), false, weight));
+		assert_eq!(Balances::free_balance(multi), 5);
+
+		assert_ok!(Multisig::as_multi(Origin::signed(1), 2, vec![2, 3], None, data.clone(), false, 0));
+		assert_ok!(Multisig::as_multi(Origin::signed(3), 2, vec![1, 2], Some(now()), data, false, weight));
+
+		let err = DispatchError::from(BalancesError::<Test, _>::InsufficientBalance).stripped();
+		expect_event(RawEvent::MultisigExecuted(3, now(), multi, hash, Err(err)));
+	});
+}
+
+#[test]
+fn minimum_threshold_check_works() {
+	new_test_ext().execute_with(|| {
+		let (data, _, _) = transfer(6, 15);
+		assert_noop!(
+			Multisig::as_multi(Origin::signed(1), 0, vec![2], None, data.clone(), false, 0),
+			Error::<Test>::MinimumThreshold,
+		);
+		assert_noop!(
+			Multisig::as_multi(Origin::signed(1), 1, vec![2], None, data, false, 0),
+			Error::<Test>::MinimumThreshold,
+		);
+	});
+}
+
+#[test]
+fn too_many_signatories_fails() {
+	new_test_ext().execute_with(|| {
+		let (data, _, _) = transfer(6, 15);
+		assert_noop!(
+			Multisig::as_multi(Origin::signed(1), 2, vec![2, 3, 4], None, data, false, 0),
+			Error::<Test>::TooManySignatories,
+		);
+	});
+}
+
+#[test]
+fn duplicate_approvals_are_ignored() {
+	new_test_ext().execute_with(|| {
+		let (_, hash, _) = transfer(6, 15);
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 2, vec![2, 3], None, hash.clone(), 0));
+		assert_noop!(
+			Multisig::approve_as_multi(Origin::signed(1), 2, vec![2, 3], Some(now()), hash.clone(), 0),
+			Error::<Test>::AlreadyApproved,
+		);
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), hash.clone(), 0));
+		assert_noop!(
+			Multisig::approve_as_multi(Origin::signed(3), 2, vec![1, 2], Some(now()), hash.clone(), 0),
+			Error::<Test>::NoApprovalsNeeded,
+		);
+	});
+}
+
+#[test]
+fn multisig_1_of_3_works() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 1);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let call = Box::new(Call::Balances(BalancesCall::transfer(6, 15)));
+		let hash = call.using_encoded(blake2_256);
+		assert_noop!(
+			Multisig::approve_as_multi(Origin::signed(1), 1, vec![2, 3], None, hash.clone(), 0),
+			Error::<Test>::MinimumThreshold,
+		);
+		assert_noop!(
+			Multisig::as_multi_threshold_1(Origin::signed(4), vec![2, 3], call.clone()),
+			BalancesError::<Test, _>::InsufficientBalance,
+		);
+		assert_ok!(Multisig::as_multi_threshold_1(Origin::signed(1), vec![2, 3], call));
+
+		assert_eq!(Balances::free_balance(6), 15);
+	});
+}
+
+#[test]
+fn weight_check_works() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert_ok!(Balances::transfer(Origin::signed(1), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(2), multi, 5));
+		assert_ok!(Balances::transfer(Origin::signed(3), multi, 5));
+
+		let (data, _, weight) = transfer(6, 15);
+		assert_ok!(Multisig::as_multi(Origin::signed(1), 2, vec![2, 3], None, data.clone(), false, 0));
+		assert_eq!(Balances::free_balance(6), 0);
+
+		assert_noop!(
+			Multisig::as_multi(Origin::signed(2), 2, vec![1, 3], Some(now()), data, false, weight - 1),
+			Error::<Test>::WeightTooLow,
+		);
+	});
+}
+
+#[test]
+fn pending_multisigs_works() {
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		assert!(Multisig::pending_multisigs(&multi).is_empty());
+
+		let (_, hash1, _) = transfer(6, 15);
+		let (_, hash2, _) = transfer(7, 5);
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 2, vec![2, 3], None, hash1, 0));
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(2), 2, vec![1, 3], None, hash2, 0));
+
+		let mut pending = Multisig::pending_multisigs(&multi);
+		pending.sort_by_key(|(hash, _)| *hash);
+		let mut expected = vec![
+			(hash1, multisig::Multisig { when: now(), deposit: 3, depositor: 1, approvals: vec![1] }),
+			(hash2, multisig::Multisig { when: now(), deposit: 3, depositor: 2, approvals: vec![2] }),
+		];
+		expected.sort_by_key(|(hash, _)| *hash);
+		assert_eq!(pending, expected);
+	});
+}
+
+#[test]
+fn migration_from_utility_works() {
+	use frame_support::traits::OnRuntimeUpgrade;
+
+	new_test_ext().execute_with(|| {
+		let multi = Multisig::multi_account_id(&[1, 2, 3][..], 2);
+		let (_, hash, _) = transfer(6, 15);
+		let op = multisig::Multisig { when: now(), deposit: 3, depositor: 1, approvals: vec![1] };
+		let mut suffix = Twox64Concat::hash(&multi.encode());
+		suffix.extend(Blake2_128Concat::hash(&hash.encode()));
+		frame_support::migration::put_storage_value(b"Utility", b"Multisigs", &suffix, op.clone());
+
+		let weight = <Multisig as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+		assert_eq!(weight, ROCKS_DB_WEIGHT.reads_writes(2, 3));
+		assert_eq!(Multisigs::<Test>::get(&multi, hash), Some(op));
+		assert!(!frame_support::migration::have_storage_value(b"Utility", b"Multisigs", &suffix));
+
+		// The migration only runs once.
+		frame_support::migration::put_storage_value(b"Utility", b"Multisigs", &suffix, 0u8);
+		assert_eq!(<Multisig as OnRuntimeUpgrade>::on_runtime_upgrade(), ROCKS_DB_WEIGHT.reads(1));
+		assert!(frame_support::migration::have_storage_value(b"Utility", b"Multisigs", &suffix));
+	});
+}
@@ -171,6 +171,13 @@ where
 
 		Ok(())
 	}
+
+	fn is_known_offence(offenders: &[T::IdentificationTuple], time_slot: &O::TimeSlot) -> bool {
+		offenders.iter().all(|offender| {
+			let report_id = Self::report_id::<O>(time_slot, offender);
+			<Reports<T>>::contains_key(&report_id)
+		})
+	}
 }
 
 impl<T: Trait> Module<T> {
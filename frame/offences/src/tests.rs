@@ -50,6 +50,29 @@ fn should_report_an_authority_and_trigger_on_offence() {
 	});
 }
 
+#[test]
+fn should_know_about_reported_offences() {
+	new_test_ext().execute_with(|| {
+		// given
+		let time_slot = 42;
+		assert!(!<Offences as ReportOffence<_, _, Offence<u64>>>::is_known_offence(&[5], &time_slot));
+
+		let offence = Offence {
+			validator_set_count: 5,
+			time_slot,
+			offenders: vec![5],
+		};
+
+		// when
+		Offences::report_offence(vec![], offence).unwrap();
+
+		// then
+		assert!(<Offences as ReportOffence<_, _, Offence<u64>>>::is_known_offence(&[5], &time_slot));
+		assert!(!<Offences as ReportOffence<_, _, Offence<u64>>>::is_known_offence(&[5, 6], &time_slot));
+		assert!(!<Offences as ReportOffence<_, _, Offence<u64>>>::is_known_offence(&[5], &(time_slot + 1)));
+	});
+}
+
 #[test]
 fn should_not_report_the_same_authority_twice_in_the_same_slot() {
 	new_test_ext().execute_with(|| {
@@ -0,0 +1,108 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+// Benchmarks for Proxy Pallet
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, account};
+use sp_runtime::traits::Bounded;
+
+use crate::Module as Proxy;
+
+const SEED: u32 = 0;
+
+fn add_proxies<T: Trait>(n: u32) -> Result<(), &'static str> {
+	let caller: T::AccountId = account("caller", 0, SEED);
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	for i in 0..n {
+		Proxy::<T>::add_proxy(
+			RawOrigin::Signed(caller.clone()).into(),
+			account("target", i, SEED),
+			T::ProxyType::default()
+		)?;
+	}
+	Ok(())
+}
+
+benchmarks! {
+	_ {
+		let p in 1 .. (T::MaxProxies::get() - 1).into() => add_proxies::<T>(p)?;
+	}
+
+	proxy {
+		let p in ...;
+		// In this case the caller is the "target" proxy
+		let caller: T::AccountId = account("target", p - 1, SEED);
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		// ... and "real" is the traditional caller. This is not a typo.
+		let real: T::AccountId = account("caller", 0, SEED);
+		let call: <T as Trait>::Call = frame_system::Call::<T>::remark(vec![]).into();
+	}: _(RawOrigin::Signed(caller), real, Some(T::ProxyType::default()), Box::new(call))
+
+	add_proxy {
+		let p in ...;
+		let caller: T::AccountId = account("caller", 0, SEED);
+	}: _(RawOrigin::Signed(caller), account("target", T::MaxProxies::get().into(), SEED), T::ProxyType::default())
+
+	remove_proxy {
+		let p in ...;
+		let caller: T::AccountId = account("caller", 0, SEED);
+	}: _(RawOrigin::Signed(caller), account("target", 0, SEED), T::ProxyType::default())
+
+	remove_proxies {
+		let p in ...;
+		let caller: T::AccountId = account("caller", 0, SEED);
+	}: _(RawOrigin::Signed(caller))
+
+	anonymous {
+		let p in ...;
+	}: _(
+		RawOrigin::Signed(account("caller", 0, SEED)),
+		T::ProxyType::default(),
+		0
+	)
+
+	kill_anonymous {
+		let p in ...;
+		let caller: T::AccountId = account("caller", 0, SEED);
+		Proxy::<T>::anonymous(RawOrigin::Signed(caller.clone()).into(), T::ProxyType::default(), 0)?;
+		let height = system::Module::<T>::block_number();
+		let ext_index = system::Module::<T>::extrinsic_index().unwrap_or_default();
+		let anon = Proxy::<T>::anonymous_account(&caller, &T::ProxyType::default(), 0, None);
+	}: _(RawOrigin::Signed(anon), caller, T::ProxyType::default(), 0, height, ext_index)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_proxy::<Test>());
+			assert_ok!(test_benchmark_add_proxy::<Test>());
+			assert_ok!(test_benchmark_remove_proxy::<Test>());
+			assert_ok!(test_benchmark_remove_proxies::<Test>());
+			assert_ok!(test_benchmark_anonymous::<Test>());
+			assert_ok!(test_benchmark_kill_anonymous::<Test>());
+		});
+	}
+}
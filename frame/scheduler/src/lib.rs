@@ -40,6 +40,13 @@
 //! \## Interface
 //!
 //! \### Dispatchable Functions
+//!
+//! * `schedule` - schedule a dispatch, which may be periodic, to occur at a specified block and
+//!   with a specified priority.
+//! * `cancel` - cancel a scheduled dispatch, specified by block number and index.
+//! * `schedule_named` - augments the `schedule` interface with an additional `Vec<u8>` parameter
+//!   that can be used for identification.
+//! * `cancel_named` - the named complement to the cancel function.
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -48,9 +55,10 @@ use sp_std::prelude::*;
 use codec::{Encode, Decode};
 use sp_runtime::{RuntimeDebug, traits::{Zero, One}};
 use frame_support::{
-	dispatch::{Dispatchable, DispatchResult, Parameter}, decl_module, decl_storage, decl_event,
-	traits::{Get, schedule},
-	weights::{GetDispatchInfo, Weight},
+	dispatch::{Dispatchable, DispatchResult, DispatchError, Parameter},
+	decl_module, decl_storage, decl_event, decl_error, metadata::TypeInfo,
+	traits::{Get, schedule, EnsureOrigin},
+	weights::{GetDispatchInfo, Weight, SimpleDispatchInfo, ROCKS_DB_WEIGHT},
 };
 use frame_system::{self as system};
 
@@ -72,6 +80,9 @@ pub trait Trait: system::Trait {
 	/// The maximum weight that may be scheduled per block for any dispatchables of less priority
 	/// than `schedule::HARD_DEADLINE`.
 	type MaximumWeight: Get<Weight>;
+
+	/// Required origin to schedule or cancel calls.
+	type ScheduleOrigin: EnsureOrigin<<Self as system::Trait>::Origin>;
 }
 
 /// Just a simple index for naming period tasks.
@@ -80,7 +91,7 @@ pub type PeriodicIndex = u32;
 pub type TaskAddress<BlockNumber> = (BlockNumber, u32);
 
 /// Information regarding an item to be executed in the future.
-#[derive(Clone, RuntimeDebug, Encode, Decode)]
+#[derive(Clone, RuntimeDebug, Encode, Decode, TypeInfo)]
 pub struct Scheduled<Call, BlockNumber> {
 	/// The unique identity for this task, if there is one.
 	maybe_id: Option<Vec<u8>>,
@@ -105,16 +116,103 @@ decl_storage! {
 
 decl_event!(
 	pub enum Event<T> where <T as system::Trait>::BlockNumber {
-		Scheduled(BlockNumber),
+		/// A task was scheduled at the given block and index.
+		Scheduled(BlockNumber, u32),
+		/// A scheduled task was canceled from the given block and index.
+		Canceled(BlockNumber, u32),
+		/// A task was dispatched with the given result.
 		Dispatched(TaskAddress<BlockNumber>, Option<Vec<u8>>, DispatchResult),
 	}
 );
 
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// A task with the given name is already scheduled.
+		FailedToSchedule,
+		/// The given task could not be found, so it cannot be canceled.
+		FailedToCancel,
+	}
+}
+
 decl_module! {
 	// Simple declaration of the `Module` type. Lets the macro know what its working on.
 	pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
+		type Error = Error<T>;
+
 		fn deposit_event() = default;
 
+		/// Anonymously schedule a task. If `when` is not in the future, the task is scheduled
+		/// for the next block.
+		///
+		/// The dispatch origin for this call must be `ScheduleOrigin`.
+		///
+		/// # <weight>
+		/// - One storage mutation (codec `O(A)` where `A` is the length of the agenda at `when`).
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(20_000 + ROCKS_DB_WEIGHT.reads_writes(1, 1))]
+		fn schedule(origin,
+			when: T::BlockNumber,
+			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+			priority: schedule::Priority,
+			call: Box<<T as Trait>::Call>,
+		) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			let _ = Self::do_schedule(when, maybe_periodic, priority, *call);
+		}
+
+		/// Cancel an anonymously scheduled task.
+		///
+		/// The dispatch origin for this call must be `ScheduleOrigin`.
+		///
+		/// # <weight>
+		/// - One storage mutation (codec `O(A)` where `A` is the length of the agenda at `when`).
+		/// - Up to one storage removal.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(20_000 + ROCKS_DB_WEIGHT.reads_writes(1, 2))]
+		fn cancel(origin, when: T::BlockNumber, index: u32) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			Self::do_cancel((when, index))?;
+		}
+
+		/// Schedule a named task. If `when` is not in the future, the task is scheduled for the
+		/// next block.
+		///
+		/// The dispatch origin for this call must be `ScheduleOrigin`.
+		///
+		/// # <weight>
+		/// - One storage mutation (codec `O(A)` where `A` is the length of the agenda at `when`).
+		/// - One storage read and one storage write of the lookup.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(20_000 + ROCKS_DB_WEIGHT.reads_writes(2, 2))]
+		fn schedule_named(origin,
+			id: Vec<u8>,
+			when: T::BlockNumber,
+			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+			priority: schedule::Priority,
+			call: Box<<T as Trait>::Call>,
+		) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			Self::do_schedule_named(id, when, maybe_periodic, priority, *call)?;
+		}
+
+		/// Cancel a named scheduled task.
+		///
+		/// The dispatch origin for this call must be `ScheduleOrigin`.
+		///
+		/// # <weight>
+		/// - One storage mutation (codec `O(A)` where `A` is the length of the agenda at `when`).
+		/// - One storage read and one storage removal of the lookup.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(20_000 + ROCKS_DB_WEIGHT.reads_writes(2, 2))]
+		fn cancel_named(origin, id: Vec<u8>) {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			Self::do_cancel_named(id)?;
+		}
+
 		fn on_initialize(now: T::BlockNumber) -> Weight {
 			let limit = T::MaximumWeight::get();
 			let mut queued = Agenda::<T>::take(now).into_iter()
@@ -171,15 +269,22 @@ decl_module! {
 	}
 }
 
-impl<T: Trait> schedule::Anon<T::BlockNumber, <T as Trait>::Call> for Module<T> {
-	type Address = TaskAddress<T::BlockNumber>;
+impl<T: Trait> Module<T> {
+	/// Tasks due at or before the current block would never be executed, so they are moved to
+	/// the next block instead.
+	fn resolve_time(when: T::BlockNumber) -> T::BlockNumber {
+		let earliest = system::Module::<T>::block_number() + One::one();
+		when.max(earliest)
+	}
 
-	fn schedule(
+	fn do_schedule(
 		when: T::BlockNumber,
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		call: <T as Trait>::Call
-	) -> Self::Address {
+	) -> TaskAddress<T::BlockNumber> {
+		let when = Self::resolve_time(when);
+
 		// sanitize maybe_periodic
 		let maybe_periodic = maybe_periodic
 			.filter(|p| p.1 > 1 && !p.0.is_zero())
@@ -187,37 +292,37 @@ impl<T: Trait> schedule::Anon<T::BlockNumber, <T as Trait>::Call> for Module<T>
 			.map(|(p, c)| (p, c - 1));
 		let s = Some(Scheduled { maybe_id: None, priority, call, maybe_periodic });
 		Agenda::<T>::append_or_insert(when, &[s][..]);
-		(when, Agenda::<T>::decode_len(when).unwrap_or(1) as u32 - 1)
+		let index = Agenda::<T>::decode_len(when).unwrap_or(1) as u32 - 1;
+		Self::deposit_event(RawEvent::Scheduled(when, index));
+		(when, index)
 	}
 
-	fn cancel((when, index): Self::Address) -> Result<(), ()> {
+	fn do_cancel((when, index): TaskAddress<T::BlockNumber>) -> Result<(), DispatchError> {
 		if let Some(s) = Agenda::<T>::mutate(when, |agenda| agenda.get_mut(index as usize).and_then(Option::take)) {
 			if let Some(id) = s.maybe_id {
-				Lookup::<T>::remove(id)
+				Lookup::<T>::remove(id);
 			}
+			Self::deposit_event(RawEvent::Canceled(when, index));
 			Ok(())
 		} else {
-			Err(())
+			Err(Error::<T>::FailedToCancel.into())
 		}
 	}
-}
-
-impl<T: Trait> schedule::Named<T::BlockNumber, <T as Trait>::Call> for Module<T> {
-	type Address = TaskAddress<T::BlockNumber>;
 
-	fn schedule_named(
-		id: impl Encode,
+	fn do_schedule_named(
+		id: Vec<u8>,
 		when: T::BlockNumber,
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		call: <T as Trait>::Call,
-	) -> Result<Self::Address, ()> {
-		// determine id and ensure it is unique
-		let id = id.encode();
+	) -> Result<TaskAddress<T::BlockNumber>, DispatchError> {
+		// ensure id is unique
 		if Lookup::<T>::contains_key(&id) {
-			return Err(())
+			return Err(Error::<T>::FailedToSchedule.into())
 		}
 
+		let when = Self::resolve_time(when);
+
 		// sanitize maybe_periodic
 		let maybe_periodic = maybe_periodic
 			.filter(|p| p.1 > 1 && !p.0.is_zero())
@@ -229,37 +334,75 @@ impl<T: Trait> schedule::Named<T::BlockNumber, <T as Trait>::Call> for Module<T>
 		let index = Agenda::<T>::decode_len(when).unwrap_or(1) as u32 - 1;
 		let address = (when, index);
 		Lookup::<T>::insert(&id, &address);
+		Self::deposit_event(RawEvent::Scheduled(when, index));
 		Ok(address)
 	}
 
-	fn cancel_named(id: impl Encode) -> Result<(), ()> {
-		if let Some((when, index)) = id.using_encoded(|d| Lookup::<T>::take(d)) {
+	fn do_cancel_named(id: Vec<u8>) -> Result<(), DispatchError> {
+		if let Some((when, index)) = Lookup::<T>::take(id) {
 			let i = index as usize;
 			Agenda::<T>::mutate(when, |agenda| if let Some(s) = agenda.get_mut(i) { *s = None });
+			Self::deposit_event(RawEvent::Canceled(when, index));
 			Ok(())
 		} else {
-			Err(())
+			Err(Error::<T>::FailedToCancel.into())
 		}
 	}
 }
 
+impl<T: Trait> schedule::Anon<T::BlockNumber, <T as Trait>::Call> for Module<T> {
+	type Address = TaskAddress<T::BlockNumber>;
+
+	fn schedule(
+		when: T::BlockNumber,
+		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+		priority: schedule::Priority,
+		call: <T as Trait>::Call
+	) -> Self::Address {
+		Self::do_schedule(when, maybe_periodic, priority, call)
+	}
+
+	fn cancel(address: Self::Address) -> Result<(), ()> {
+		Self::do_cancel(address).map_err(|_| ())
+	}
+}
+
+impl<T: Trait> schedule::Named<T::BlockNumber, <T as Trait>::Call> for Module<T> {
+	type Address = TaskAddress<T::BlockNumber>;
+
+	fn schedule_named(
+		id: impl Encode,
+		when: T::BlockNumber,
+		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+		priority: schedule::Priority,
+		call: <T as Trait>::Call,
+	) -> Result<Self::Address, ()> {
+		Self::do_schedule_named(id.encode(), when, maybe_periodic, priority, call).map_err(|_| ())
+	}
+
+	fn cancel_named(id: impl Encode) -> Result<(), ()> {
+		Self::do_cancel_named(id.encode()).map_err(|_| ())
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 
 	use frame_support::{
 		impl_outer_event, impl_outer_origin, impl_outer_dispatch, parameter_types, assert_ok,
-		traits::{OnInitialize, OnFinalize, schedule::{Anon, Named}},
+		assert_noop, traits::{OnInitialize, OnFinalize},
 		weights::{DispatchClass, FunctionOf}
 	};
 	use sp_core::H256;
 	// The testing primitives are very useful for avoiding having to work with signatures
 	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
 	use sp_runtime::{
-		Perbill,
+		Perbill, DispatchError,
 		testing::Header,
 		traits::{BlakeTwo256, IdentityLookup},
 	};
+	use frame_system::EnsureRoot;
 	use crate as scheduler;
 
 	mod logger {
@@ -367,6 +510,7 @@ mod tests {
 		type Origin = Origin;
 		type Call = Call;
 		type MaximumWeight = MaximumWeight;
+		type ScheduleOrigin = EnsureRoot<u64>;
 	}
 	type System = system::Module<Test>;
 	type Logger = logger::Module<Test>;
@@ -390,7 +534,7 @@ mod tests {
 	#[test]
 	fn basic_scheduling_works() {
 		new_test_ext().execute_with(|| {
-			Scheduler::schedule(4, None, 127, Call::Logger(logger::Call::log(42, 1000)));
+			Scheduler::do_schedule(4, None, 127, Call::Logger(logger::Call::log(42, 1000)));
 			run_to_block(3);
 			assert!(logger::log().is_empty());
 			run_to_block(4);
@@ -404,7 +548,7 @@ mod tests {
 	fn periodic_scheduling_works() {
 		new_test_ext().execute_with(|| {
 			// at #4, every 3 blocks, 3 times.
-			Scheduler::schedule(4, Some((3, 3)), 127, Call::Logger(logger::Call::log(42, 1000)));
+			Scheduler::do_schedule(4, Some((3, 3)), 127, Call::Logger(logger::Call::log(42, 1000)));
 			run_to_block(3);
 			assert!(logger::log().is_empty());
 			run_to_block(4);
@@ -426,12 +570,12 @@ mod tests {
 	fn cancel_named_scheduling_works_with_normal_cancel() {
 		new_test_ext().execute_with(|| {
 			// at #4.
-			Scheduler::schedule_named(1u32, 4, None, 127, Call::Logger(logger::Call::log(69, 1000))).unwrap();
-			let i = Scheduler::schedule(4, None, 127, Call::Logger(logger::Call::log(42, 1000)));
+			Scheduler::do_schedule_named(1u32.encode(), 4, None, 127, Call::Logger(logger::Call::log(69, 1000))).unwrap();
+			let i = Scheduler::do_schedule(4, None, 127, Call::Logger(logger::Call::log(42, 1000)));
 			run_to_block(3);
 			assert!(logger::log().is_empty());
-			assert_ok!(Scheduler::cancel_named(1u32));
-			assert_ok!(Scheduler::cancel(i));
+			assert_ok!(Scheduler::do_cancel_named(1u32.encode()));
+			assert_ok!(Scheduler::do_cancel(i));
 			run_to_block(100);
 			assert!(logger::log().is_empty());
 		});
@@ -441,17 +585,17 @@ mod tests {
 	fn cancel_named_periodic_scheduling_works() {
 		new_test_ext().execute_with(|| {
 			// at #4, every 3 blocks, 3 times.
-			Scheduler::schedule_named(1u32, 4, Some((3, 3)), 127, Call::Logger(logger::Call::log(42, 1000))).unwrap();
+			Scheduler::do_schedule_named(1u32.encode(), 4, Some((3, 3)), 127, Call::Logger(logger::Call::log(42, 1000))).unwrap();
 			// same id results in error.
-			assert!(Scheduler::schedule_named(1u32, 4, None, 127, Call::Logger(logger::Call::log(69, 1000))).is_err());
+			assert!(Scheduler::do_schedule_named(1u32.encode(), 4, None, 127, Call::Logger(logger::Call::log(69, 1000))).is_err());
 			// different id is ok.
-			Scheduler::schedule_named(2u32, 8, None, 127, Call::Logger(logger::Call::log(69, 1000))).unwrap();
+			Scheduler::do_schedule_named(2u32.encode(), 8, None, 127, Call::Logger(logger::Call::log(69, 1000))).unwrap();
 			run_to_block(3);
 			assert!(logger::log().is_empty());
 			run_to_block(4);
 			assert_eq!(logger::log(), vec![42u32]);
 			run_to_block(6);
-			assert_ok!(Scheduler::cancel_named(1u32));
+			assert_ok!(Scheduler::do_cancel_named(1u32.encode()));
 			run_to_block(100);
 			assert_eq!(logger::log(), vec![42u32, 69u32]);
 		});
@@ -460,8 +604,8 @@ mod tests {
 	#[test]
 	fn scheduler_respects_weight_limits() {
 		new_test_ext().execute_with(|| {
-			Scheduler::schedule(4, None, 127, Call::Logger(logger::Call::log(42, 6000)));
-			Scheduler::schedule(4, None, 127, Call::Logger(logger::Call::log(69, 6000)));
+			Scheduler::do_schedule(4, None, 127, Call::Logger(logger::Call::log(42, 6000)));
+			Scheduler::do_schedule(4, None, 127, Call::Logger(logger::Call::log(69, 6000)));
 			run_to_block(4);
 			assert_eq!(logger::log(), vec![42u32]);
 			run_to_block(5);
@@ -472,8 +616,8 @@ mod tests {
 	#[test]
 	fn scheduler_respects_hard_deadlines_more() {
 		new_test_ext().execute_with(|| {
-			Scheduler::schedule(4, None, 0, Call::Logger(logger::Call::log(42, 6000)));
-			Scheduler::schedule(4, None, 0, Call::Logger(logger::Call::log(69, 6000)));
+			Scheduler::do_schedule(4, None, 0, Call::Logger(logger::Call::log(42, 6000)));
+			Scheduler::do_schedule(4, None, 0, Call::Logger(logger::Call::log(69, 6000)));
 			run_to_block(4);
 			assert_eq!(logger::log(), vec![42u32, 69u32]);
 		});
@@ -482,8 +626,8 @@ mod tests {
 	#[test]
 	fn scheduler_respects_priority_ordering() {
 		new_test_ext().execute_with(|| {
-			Scheduler::schedule(4, None, 1, Call::Logger(logger::Call::log(42, 6000)));
-			Scheduler::schedule(4, None, 0, Call::Logger(logger::Call::log(69, 6000)));
+			Scheduler::do_schedule(4, None, 1, Call::Logger(logger::Call::log(42, 6000)));
+			Scheduler::do_schedule(4, None, 0, Call::Logger(logger::Call::log(69, 6000)));
 			run_to_block(4);
 			assert_eq!(logger::log(), vec![69u32, 42u32]);
 		});
@@ -492,9 +636,9 @@ mod tests {
 	#[test]
 	fn scheduler_respects_priority_ordering_with_soft_deadlines() {
 		new_test_ext().execute_with(|| {
-			Scheduler::schedule(4, None, 255, Call::Logger(logger::Call::log(42, 5000)));
-			Scheduler::schedule(4, None, 127, Call::Logger(logger::Call::log(69, 5000)));
-			Scheduler::schedule(4, None, 126, Call::Logger(logger::Call::log(2600, 6000)));
+			Scheduler::do_schedule(4, None, 255, Call::Logger(logger::Call::log(42, 5000)));
+			Scheduler::do_schedule(4, None, 127, Call::Logger(logger::Call::log(69, 5000)));
+			Scheduler::do_schedule(4, None, 126, Call::Logger(logger::Call::log(2600, 6000)));
 			run_to_block(4);
 			assert_eq!(logger::log(), vec![2600u32]);
 			run_to_block(5);
@@ -505,10 +649,10 @@ mod tests {
 	#[test]
 	fn initialize_weight_is_correct() {
 		new_test_ext().execute_with(|| {
-			Scheduler::schedule(1, None, 255, Call::Logger(logger::Call::log(3, 1000)));
-			Scheduler::schedule(1, None, 128, Call::Logger(logger::Call::log(42, 5000)));
-			Scheduler::schedule(1, None, 127, Call::Logger(logger::Call::log(69, 5000)));
-			Scheduler::schedule(1, None, 126, Call::Logger(logger::Call::log(2600, 6000)));
+			Scheduler::do_schedule(1, None, 255, Call::Logger(logger::Call::log(3, 1000)));
+			Scheduler::do_schedule(1, None, 128, Call::Logger(logger::Call::log(42, 5000)));
+			Scheduler::do_schedule(1, None, 127, Call::Logger(logger::Call::log(69, 5000)));
+			Scheduler::do_schedule(1, None, 126, Call::Logger(logger::Call::log(2600, 6000)));
 			let weight = Scheduler::on_initialize(1);
 			assert_eq!(weight, 6000);
 			let weight = Scheduler::on_initialize(2);
@@ -519,4 +663,96 @@ mod tests {
 			assert_eq!(weight, 0);
 		});
 	}
+
+	#[test]
+	fn root_calls_works() {
+		new_test_ext().execute_with(|| {
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			assert_ok!(Scheduler::schedule_named(Origin::ROOT, 1u32.encode(), 4, None, 127, call));
+			assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, 127, call2));
+			run_to_block(3);
+			// Scheduled calls are in the agenda.
+			assert_eq!(Agenda::<Test>::get(4).len(), 2);
+			assert!(logger::log().is_empty());
+			assert_ok!(Scheduler::cancel_named(Origin::ROOT, 1u32.encode()));
+			assert_ok!(Scheduler::cancel(Origin::ROOT, 4, 1));
+			// Scheduled calls are made NONE, so should not effect state
+			run_to_block(100);
+			assert!(logger::log().is_empty());
+		});
+	}
+
+	#[test]
+	fn scheduling_in_the_past_runs_in_the_next_block() {
+		new_test_ext().execute_with(|| {
+			run_to_block(3);
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			assert_ok!(Scheduler::schedule_named(Origin::ROOT, 1u32.encode(), 3, None, 127, call));
+			assert_ok!(Scheduler::schedule(Origin::ROOT, 1, None, 127, call2));
+			assert!(Agenda::<Test>::get(1).is_empty());
+			assert!(Agenda::<Test>::get(3).is_empty());
+			assert_eq!(Agenda::<Test>::get(4).len(), 2);
+			assert_eq!(Lookup::<Test>::get(1u32.encode()), Some((4, 0)));
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![69u32, 42u32]);
+		});
+	}
+
+	#[test]
+	fn root_calls_fail_on_unknown_or_duplicate_tasks() {
+		new_test_ext().execute_with(|| {
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
+			assert_ok!(Scheduler::schedule_named(Origin::ROOT, 1u32.encode(), 4, None, 127, call.clone()));
+			assert_noop!(
+				Scheduler::schedule_named(Origin::ROOT, 1u32.encode(), 5, None, 127, call),
+				Error::<Test>::FailedToSchedule,
+			);
+			assert_noop!(Scheduler::cancel_named(Origin::ROOT, 2u32.encode()), Error::<Test>::FailedToCancel);
+			assert_noop!(Scheduler::cancel(Origin::ROOT, 4, 1), Error::<Test>::FailedToCancel);
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![69u32]);
+		});
+	}
+
+	#[test]
+	fn should_use_origin() {
+		new_test_ext().execute_with(|| {
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			assert_noop!(
+				Scheduler::schedule_named(system::RawOrigin::Signed(1).into(), 1u32.encode(), 4, None, 127, call),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Scheduler::schedule(system::RawOrigin::Signed(1).into(), 4, None, 127, call2),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), 4, None, 127, Call::Logger(logger::Call::log(69, 1000))
+			));
+			assert_noop!(
+				Scheduler::cancel_named(system::RawOrigin::Signed(2).into(), 1u32.encode()),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(Scheduler::cancel(system::RawOrigin::Signed(2).into(), 4, 0), DispatchError::BadOrigin);
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![69u32]);
+		});
+	}
+
+	#[test]
+	fn scheduling_calls_are_normal_and_weighed_by_storage_access() {
+		let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
+		let infos = vec![
+			scheduler::Call::<Test>::schedule(4, None, 127, call.clone()).get_dispatch_info(),
+			scheduler::Call::<Test>::cancel(4, 0).get_dispatch_info(),
+			scheduler::Call::<Test>::schedule_named(1u32.encode(), 4, None, 127, call).get_dispatch_info(),
+			scheduler::Call::<Test>::cancel_named(1u32.encode()).get_dispatch_info(),
+		];
+		let weights = infos.iter().map(|info| info.weight).collect::<Vec<_>>();
+		assert_eq!(weights, vec![145_000, 245_000, 270_000, 270_000]);
+		assert!(infos.iter().all(|info| info.class == DispatchClass::Normal));
+	}
 }
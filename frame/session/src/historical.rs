@@ -30,13 +30,12 @@ use codec::{Encode, Decode};
 use sp_runtime::{KeyTypeId, RuntimeDebug};
 use sp_runtime::traits::{Convert, OpaqueKeys};
 use frame_support::{decl_module, decl_storage};
-use frame_support::{Parameter, print};
+use frame_support::{Parameter, print, metadata::TypeInfo};
 use sp_trie::{MemoryDB, Trie, TrieMut, Recorder, EMPTY_PREFIX};
 use sp_trie::trie_types::{TrieDBMut, TrieDB};
+use sp_session::{GetSessionNumber, GetValidatorCount, ValidatorCount};
 use super::{SessionIndex, Module as SessionModule};
 
-type ValidatorCount = u32;
-
 /// Trait necessary for the historical module.
 pub trait Trait: super::Trait {
 	/// Full identification of the validator.
@@ -254,10 +253,11 @@ impl<T: Trait> ProvingTrie<T> {
 }
 
 /// Proof of ownership of a specific key.
-#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug, TypeInfo)]
 pub struct Proof {
 	session: SessionIndex,
 	trie_nodes: Vec<Vec<u8>>,
+	validator_count: ValidatorCount,
 }
 
 impl Proof {
@@ -267,6 +267,18 @@ impl Proof {
 	}
 }
 
+impl GetSessionNumber for Proof {
+	fn session(&self) -> SessionIndex {
+		self.session
+	}
+}
+
+impl GetValidatorCount for Proof {
+	fn validator_count(&self) -> ValidatorCount {
+		self.validator_count
+	}
+}
+
 impl<T: Trait, D: AsRef<[u8]>> frame_support::traits::KeyOwnerProofSystem<(KeyTypeId, D)>
 	for Module<T>
 {
@@ -279,7 +291,9 @@ impl<T: Trait, D: AsRef<[u8]>> frame_support::traits::KeyOwnerProofSystem<(KeyTy
 			.filter_map(|validator| {
 				T::FullIdentificationOf::convert(validator.clone())
 					.map(|full_id| (validator, full_id))
-			});
+			})
+			.collect::<Vec<_>>();
+		let count = validators.len() as ValidatorCount;
 		let trie = ProvingTrie::<T>::generate_for(validators).ok()?;
 
 		let (id, data) = key;
@@ -287,6 +301,7 @@ impl<T: Trait, D: AsRef<[u8]>> frame_support::traits::KeyOwnerProofSystem<(KeyTy
 		trie.prove(id, data.as_ref()).map(|trie_nodes| Proof {
 			session,
 			trie_nodes,
+			validator_count: count,
 		})
 	}
 
@@ -340,6 +355,8 @@ mod tests {
 
 			let encoded_key_1 = UintAuthorityId(1).encode();
 			let proof = Historical::prove((DUMMY, &encoded_key_1[..])).unwrap();
+			assert_eq!(GetSessionNumber::session(&proof), Session::current_index());
+			assert_eq!(proof.validator_count() as usize, Session::validators().len());
 
 			// proof-checking in the same session is OK.
 			assert!(Historical::check_proof((DUMMY, &encoded_key_1[..]), proof.clone()).is_some());
@@ -22,6 +22,8 @@
 
 mod storage;
 mod construct_runtime;
+mod transactional;
+mod type_info;
 
 use proc_macro::TokenStream;
 
@@ -290,3 +292,55 @@ pub fn decl_storage(input: TokenStream) -> TokenStream {
 pub fn construct_runtime(input: TokenStream) -> TokenStream {
 	construct_runtime::construct_runtime(input)
 }
+
+/// Execute the annotated function in a new storage transaction.
+///
+/// The return type of the annotated function must be `Result`. All changes to storage performed
+/// by the annotated function are discarded if it returns `Err`, or committed if `Ok`.
+///
+/// # Example
+///
+/// ```nocompile
+/// #[transactional]
+/// fn value_commits(v: u32) -> result::Result<u32, &'static str> {
+/// 	Value::set(v);
+/// 	Ok(v)
+/// }
+///
+/// #[transactional]
+/// fn value_rollbacks(v: u32) -> result::Result<u32, &'static str> {
+/// 	Value::set(v);
+/// 	Err("nah")
+/// }
+/// ```
+///
+/// Inside `decl_module!` the attribute has to be placed after the `#[weight]` attribute of the
+/// dispatchable.
+#[proc_macro_attribute]
+pub fn transactional(attr: TokenStream, input: TokenStream) -> TokenStream {
+	transactional::transactional(attr, input).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+/// Derive `TypeInfo`, describing the type in the metadata V12 type registry.
+///
+/// All type parameters are required to implement `TypeInfo` as well, unless they are listed in
+/// `#[type_info(skip_type_params(..))]`. Fields whose types mention a skipped parameter, e.g.
+/// `T::AccountId`, are required to implement `TypeInfo` instead. The `#[codec(skip)]`,
+/// `#[codec(compact)]` and `#[codec(index = "..")]` attributes are taken into account.
+///
+/// # Example
+///
+/// ```nocompile
+/// #[derive(Encode, Decode, TypeInfo)]
+/// #[type_info(skip_type_params(T))]
+/// struct Ledger<Balance, T> {
+/// 	#[codec(compact)]
+/// 	total: Balance,
+/// 	#[codec(skip)]
+/// 	_phantom: PhantomData<T>,
+/// }
+/// ```
+#[proc_macro_derive(TypeInfo, attributes(type_info))]
+pub fn derive_type_info(input: TokenStream) -> TokenStream {
+	type_info::derive_type_info(input).unwrap_or_else(|e| e.to_compile_error().into())
+}
@@ -65,6 +65,50 @@ fn storage_line_metadata_type(scrate: &TokenStream, line: &StorageLineDefExt) ->
 	}
 }
 
+/// Returns the `StorageEntryTypeV12` of the line, together with all types it registers.
+fn storage_line_metadata_type_v12(
+	scrate: &TokenStream,
+	line: &StorageLineDefExt,
+) -> (TokenStream, Vec<syn::Type>) {
+	let value_type = &line.value_type;
+	match &line.storage_type {
+		StorageLineTypeDef::Simple(_) => {
+			let ty = quote!{
+				#scrate::metadata::StorageEntryTypeV12::Plain(registry.register::<#value_type>())
+			};
+			(ty, vec![value_type.clone()])
+		},
+		StorageLineTypeDef::Map(map) => {
+			let hasher = map.hasher.into_metadata();
+			let key = &map.key;
+			let ty = quote!{
+				#scrate::metadata::StorageEntryTypeV12::Map {
+					hasher: #scrate::metadata::#hasher,
+					key: registry.register::<#key>(),
+					value: registry.register::<#value_type>(),
+				}
+			};
+			(ty, vec![key.clone(), value_type.clone()])
+		},
+		StorageLineTypeDef::DoubleMap(map) => {
+			let hasher1 = map.hasher1.into_metadata();
+			let hasher2 = map.hasher2.into_metadata();
+			let key1 = &map.key1;
+			let key2 = &map.key2;
+			let ty = quote!{
+				#scrate::metadata::StorageEntryTypeV12::DoubleMap {
+					hasher: #scrate::metadata::#hasher1,
+					key1: registry.register::<#key1>(),
+					key2: registry.register::<#key2>(),
+					value: registry.register::<#value_type>(),
+					key2_hasher: #scrate::metadata::#hasher2,
+				}
+			};
+			(ty, vec![key1.clone(), key2.clone(), value_type.clone()])
+		},
+	}
+}
+
 fn default_byte_getter(
 	scrate: &TokenStream,
 	line: &StorageLineDefExt,
@@ -141,6 +185,8 @@ fn default_byte_getter(
 
 pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStream {
 	let mut entries = TokenStream::new();
+	let mut entries_v12 = TokenStream::new();
+	let mut registered_types = Vec::new();
 	let mut default_byte_getter_struct_defs = TokenStream::new();
 
 	for line in def.storage_lines.iter() {
@@ -153,6 +199,8 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 		};
 
 		let ty = storage_line_metadata_type(scrate, line);
+		let (ty_v12, line_types) = storage_line_metadata_type_v12(scrate, line);
+		registered_types.extend(line_types);
 
 		let (
 			default_byte_getter_struct_def,
@@ -181,8 +229,21 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 			},
 		};
 
+		let entry_v12 = quote! {
+			entries.push(#scrate::metadata::StorageEntryMetadataV12 {
+				name: #scrate::metadata::DecodeDifferent::Encode(#str_name),
+				modifier: #modifier,
+				ty: #ty_v12,
+				default: #scrate::metadata::DecodeDifferent::Encode(
+					#scrate::metadata::DefaultByteGetter(&#default_byte_getter_struct_instance)
+				),
+				documentation: #scrate::metadata::DecodeDifferent::Encode(&[ #docs ]),
+			});
+		};
+
 		default_byte_getter_struct_defs.extend(default_byte_getter_struct_def);
 		entries.extend(entry);
+		entries_v12.extend(entry_v12);
 	}
 
 	let prefix = if let Some(instance) = &def.module_instance {
@@ -213,5 +274,23 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 				#store_metadata
 			}
 		}
+
+		#scrate::__metadata_v12! {
+			impl#module_impl #module_struct #where_clause {
+				#[doc(hidden)]
+				pub fn storage_metadata_v12(
+					registry: &mut #scrate::metadata::Registry,
+				) -> #scrate::metadata::StorageMetadataV12
+					where #( #registered_types: #scrate::metadata::TypeInfo + 'static, )*
+				{
+					let mut entries = #scrate::sp_std::vec::Vec::new();
+					#entries_v12
+					#scrate::metadata::StorageMetadataV12 {
+						prefix: #scrate::metadata::DecodeDifferent::Encode(#prefix),
+						entries,
+					}
+				}
+			}
+		}
 	)
 }
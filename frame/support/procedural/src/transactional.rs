@@ -0,0 +1,44 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of the `transactional` attribute macro.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{ItemFn, Result};
+use frame_support_procedural_tools::generate_crate_access_2018;
+
+pub fn transactional(_attr: TokenStream, input: TokenStream) -> Result<TokenStream> {
+	let ItemFn { attrs, vis, sig, block } = syn::parse(input)?;
+
+	let crate_ = generate_crate_access_2018()?;
+	let output = quote! {
+		#(#attrs)*
+		#vis #sig {
+			use #crate_::storage::{with_transaction, TransactionOutcome};
+			with_transaction(|| {
+				let r = (|| { #block })();
+				if r.is_ok() {
+					TransactionOutcome::Commit(r)
+				} else {
+					TransactionOutcome::Rollback(r)
+				}
+			})
+		}
+	};
+
+	Ok(output.into())
+}
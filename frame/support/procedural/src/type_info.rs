@@ -0,0 +1,239 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of the `TypeInfo` derive macro.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+	Attribute, Data, DeriveInput, Error, Fields, Ident, Lit, Meta, NestedMeta, Result,
+	parse_quote, spanned::Spanned,
+};
+use frame_support_procedural_tools::generate_crate_access_2018;
+
+pub fn derive_type_info(input: TokenStream) -> Result<TokenStream> {
+	let mut input: DeriveInput = syn::parse(input)?;
+
+	let scrate = generate_crate_access_2018()?;
+	let skipped = skipped_type_params(&input.attrs)?;
+
+	let mut params = Vec::new();
+	for param in input.generics.type_params_mut() {
+		if skipped.contains(&param.ident) {
+			param.bounds.push(parse_quote!('static));
+		} else {
+			param.bounds.push(parse_quote!(#scrate::metadata::TypeInfo));
+			param.bounds.push(parse_quote!('static));
+			params.push(param.ident.clone());
+		}
+	}
+
+	// The fields that use the skipped type parameters, e.g. through associated types, still need
+	// to describe themselves.
+	let bounded_fields = match &input.data {
+		Data::Struct(data) => data.fields.iter().collect::<Vec<_>>(),
+		Data::Enum(data) => data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+		Data::Union(_) => Vec::new(),
+	};
+	let mut field_bounds = Vec::new();
+	for field in bounded_fields {
+		if !has_codec_flag(&field.attrs, "skip") && uses_any(&field.ty, &skipped) {
+			let ty = &field.ty;
+			field_bounds.push(quote!( #ty: #scrate::metadata::TypeInfo + 'static ));
+		}
+	}
+	if !field_bounds.is_empty() {
+		let where_clause = input.generics.make_where_clause();
+		for bound in field_bounds {
+			where_clause.predicates.push(syn::parse2(bound)?);
+		}
+	}
+
+	let def = match &input.data {
+		Data::Struct(data) => {
+			let fields = fields(&scrate, &data.fields)?;
+			quote!( #scrate::metadata::TypeDef::Composite(#fields) )
+		},
+		Data::Enum(data) => {
+			let mut variants = Vec::new();
+			let mut index = 0u8;
+			for variant in data.variants.iter().filter(|v| !has_codec_flag(&v.attrs, "skip")) {
+				let name = variant.ident.to_string();
+				let fields = fields(&scrate, &variant.fields)?;
+				let variant_index = match codec_index(&variant.attrs)? {
+					Some(index) => index,
+					None => discriminant(variant)?.unwrap_or(index),
+				};
+				let docs = docs(&variant.attrs);
+				variants.push(quote! {
+					#scrate::metadata::Variant {
+						name: #scrate::metadata::DecodeDifferent::Encode(#name),
+						fields: #fields,
+						index: #variant_index,
+						documentation: #scrate::metadata::DecodeDifferent::Encode(&[ #( #docs ),* ]),
+					}
+				});
+				index = variant_index.wrapping_add(1);
+			}
+			quote!( #scrate::metadata::TypeDef::Variant(
+				<[_]>::into_vec(#scrate::sp_std::boxed::Box::new([ #( #variants ),* ]))
+			))
+		},
+		Data::Union(_) => return Err(Error::new(input.span(), "`TypeInfo` can not be derived for unions")),
+	};
+
+	let name = &input.ident;
+	let str_name = name.to_string();
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let output = quote! {
+		impl #impl_generics #scrate::metadata::TypeInfo for #name #ty_generics #where_clause {
+			fn type_info(registry: &mut #scrate::metadata::Registry) -> #scrate::metadata::Type {
+				#scrate::metadata::Type {
+					path: #scrate::metadata::type_path(module_path!(), #str_name),
+					params: <[_]>::into_vec(#scrate::sp_std::boxed::Box::new([
+						#( registry.register::<#params>() ),*
+					])),
+					def: #def,
+				}
+			}
+		}
+	};
+
+	Ok(output.into())
+}
+
+/// Generate the list of `Field`s, skipping fields marked with `#[codec(skip)]`.
+fn fields(scrate: &TokenStream2, fields: &Fields) -> Result<TokenStream2> {
+	let fields = fields.iter()
+		.filter(|f| !has_codec_flag(&f.attrs, "skip"))
+		.map(|field| {
+			let ty = &field.ty;
+			let index = if has_codec_flag(&field.attrs, "compact") {
+				quote!( registry.register::<#scrate::codec::Compact<#ty>>() )
+			} else {
+				quote!( registry.register::<#ty>() )
+			};
+			match &field.ident {
+				Some(ident) => {
+					let name = ident.to_string();
+					quote!( #scrate::metadata::Field::named(#name, #index) )
+				},
+				None => quote!( #scrate::metadata::Field::unnamed(#index) ),
+			}
+		});
+
+	Ok(quote!( <[_]>::into_vec(#scrate::sp_std::boxed::Box::new([ #( #fields ),* ])) ))
+}
+
+/// Returns whether the type refers to any of the given type parameters.
+fn uses_any(ty: &syn::Type, params: &[Ident]) -> bool {
+	fn visit(tokens: TokenStream2, params: &[Ident]) -> bool {
+		tokens.into_iter().any(|token| match token {
+			proc_macro2::TokenTree::Ident(ident) => params.contains(&ident),
+			proc_macro2::TokenTree::Group(group) => visit(group.stream(), params),
+			_ => false,
+		})
+	}
+
+	!params.is_empty() && visit(quote!( #ty ), params)
+}
+
+/// Returns the type parameters listed in `#[type_info(skip_type_params(..))]`.
+fn skipped_type_params(attrs: &[Attribute]) -> Result<Vec<Ident>> {
+	let mut skipped = Vec::new();
+	for attr in attrs.iter().filter(|a| a.path.is_ident("type_info")) {
+		let error = || Error::new(attr.span(), "expected `#[type_info(skip_type_params(..))]`");
+		let list = match attr.parse_meta()? {
+			Meta::List(list) => list,
+			_ => return Err(error()),
+		};
+		for nested in list.nested.iter() {
+			match nested {
+				NestedMeta::Meta(Meta::List(inner)) if inner.path.is_ident("skip_type_params") => {
+					for param in inner.nested.iter() {
+						match param {
+							NestedMeta::Meta(Meta::Path(path)) if path.get_ident().is_some() =>
+								skipped.push(path.get_ident().expect("Checked above; qed").clone()),
+							_ => return Err(error()),
+						}
+					}
+				},
+				_ => return Err(error()),
+			}
+		}
+	}
+	Ok(skipped)
+}
+
+/// Returns whether the attributes contain `#[codec(flag)]`.
+fn has_codec_flag(attrs: &[Attribute], flag: &str) -> bool {
+	codec_metas(attrs).any(|meta| match meta {
+		Meta::Path(path) => path.is_ident(flag),
+		_ => false,
+	})
+}
+
+/// Returns the index given by `#[codec(index = "..")]`, if any.
+fn codec_index(attrs: &[Attribute]) -> Result<Option<u8>> {
+	for meta in codec_metas(attrs) {
+		if let Meta::NameValue(nv) = meta {
+			if nv.path.is_ident("index") {
+				return match &nv.lit {
+					Lit::Str(s) => s.value().parse().map(Some)
+						.map_err(|_| Error::new(s.span(), "expected a `u8` index")),
+					Lit::Int(i) => i.base10_parse().map(Some),
+					lit => Err(Error::new(lit.span(), "expected a `u8` index")),
+				};
+			}
+		}
+	}
+	Ok(None)
+}
+
+/// Returns the explicit discriminant of the variant, if any.
+fn discriminant(variant: &syn::Variant) -> Result<Option<u8>> {
+	match &variant.discriminant {
+		Some((_, syn::Expr::Lit(syn::ExprLit { lit: Lit::Int(i), .. }))) => i.base10_parse().map(Some),
+		Some((_, expr)) => Err(Error::new(expr.span(), "expected a `u8` discriminant")),
+		None => Ok(None),
+	}
+}
+
+fn codec_metas<'a>(attrs: &'a [Attribute]) -> impl Iterator<Item = Meta> + 'a {
+	attrs.iter()
+		.filter(|a| a.path.is_ident("codec"))
+		.filter_map(|a| match a.parse_meta() {
+			Ok(Meta::List(list)) => Some(list.nested.into_iter()),
+			_ => None,
+		})
+		.flatten()
+		.filter_map(|nested| match nested {
+			NestedMeta::Meta(meta) => Some(meta),
+			_ => None,
+		})
+}
+
+fn docs(attrs: &[Attribute]) -> Vec<Lit> {
+	attrs.iter()
+		.filter_map(|a| a.parse_meta().ok())
+		.filter_map(|meta| match meta {
+			Meta::NameValue(nv) if nv.path.is_ident("doc") => Some(nv.lit),
+			_ => None,
+		})
+		.collect()
+}
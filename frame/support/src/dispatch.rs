@@ -174,6 +174,29 @@ impl<T> Parameter for T where T: Codec + EncodeLike + Clone + Eq + fmt::Debug {}
 /// # fn main() {}
 /// ```
 ///
+/// ### Transactional Function Example
+///
+/// Transactional function discards all changes to storage if it returns `Err`, or commits if
+/// `Ok`, via the #\[transactional\] attribute. Note the attribute must be after #\[weight\].
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate frame_support;
+/// # use frame_support::transactional;
+/// # use frame_support::weights::SimpleDispatchInfo;
+/// # use frame_system::{self as system, Trait};
+/// decl_module! {
+/// 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+/// 		#[weight = SimpleDispatchInfo::default()]
+/// 		#[transactional]
+/// 		fn my_short_function(origin) {
+///				// Your implementation
+/// 		}
+/// 	}
+/// }
+/// # fn main() {}
+/// ```
+///
 /// ### Privileged Function Example
 ///
 /// A privileged function checks that the origin of the call is `ROOT`.
@@ -815,6 +838,7 @@ macro_rules! decl_module {
 		[ $( $dispatchables:tt )* ]
 		$(#[doc = $doc_attr:tt])*
 		#[weight = $weight:expr]
+		$(#[$fn_attr:meta])*
 		$fn_vis:vis fn $fn_name:ident(
 			$origin:ident $( , $(#[$codec_attr:ident])* $param_name:ident : $param:ty )* $(,)?
 		) $( -> $result:ty )* { $( $impl:tt )* }
@@ -838,6 +862,7 @@ macro_rules! decl_module {
 				$( $dispatchables )*
 				$(#[doc = $doc_attr])*
 				#[weight = $weight]
+				$(#[$fn_attr])*
 				$fn_vis fn $fn_name(
 					$origin $( , $(#[$codec_attr])* $param_name : $param )*
 				) $( -> $result )* { $( $impl )* }
@@ -1199,12 +1224,12 @@ macro_rules! decl_module {
 		$origin_ty:ty;
 		$error_type:ty;
 		$ignore:ident;
-		$(#[doc = $doc_attr:tt])*
+		$(#[$fn_attr:meta])*
 		$vis:vis fn $name:ident (
 			$origin:ident $(, $param:ident : $param_ty:ty )*
 		) { $( $impl:tt )* }
 	) => {
-		$(#[doc = $doc_attr])*
+		$(#[$fn_attr])*
 		#[allow(unreachable_code)]
 		$vis fn $name(
 			$origin: $origin_ty $(, $param: $param_ty )*
@@ -1227,12 +1252,12 @@ macro_rules! decl_module {
 		$origin_ty:ty;
 		$error_type:ty;
 		$ignore:ident;
-		$(#[doc = $doc_attr:tt])*
+		$(#[$fn_attr:meta])*
 		$vis:vis fn $name:ident (
 			$origin:ident $(, $param:ident : $param_ty:ty )*
 		) -> $result:ty { $( $impl:tt )* }
 	) => {
-		$(#[doc = $doc_attr])*
+		$(#[$fn_attr])*
 		$vis fn $name($origin: $origin_ty $(, $param: $param_ty )* ) -> $result {
 			use $crate::sp_std::if_std;
 			if_std! {
@@ -1369,6 +1394,7 @@ macro_rules! decl_module {
 			$(
 				$(#[doc = $doc_attr:tt])*
 				#[weight = $weight:expr]
+				$(#[$fn_attr:meta])*
 				$fn_vis:vis fn $fn_name:ident(
 					$from:ident $( , $(#[$codec_attr:ident])* $param_name:ident : $param:ty)*
 				) $( -> $result:ty )* { $( $impl:tt )* }
@@ -1445,6 +1471,7 @@ macro_rules! decl_module {
 					$error_type;
 					$from;
 					$(#[doc = $doc_attr])*
+					$(#[$fn_attr])*
 					$fn_vis fn $fn_name (
 						$from $(, $param_name : $param )*
 					) $( -> $result )* { $( $impl )* }
@@ -1717,6 +1744,33 @@ macro_rules! impl_outer_dispatch {
 				}
 			}
 		}
+		$crate::__metadata_v12! {
+			// The bounds are higher-ranked so that they are only checked when the impl is used, i.e.
+			// only runtimes that generate metadata V12 require their calls to implement `TypeInfo`.
+			impl $crate::metadata::TypeInfo for $call_type
+				where $(
+					for<'a> $crate::dispatch::CallableCallFor<$camelcase, $runtime>:
+						$crate::metadata::TypeInfo + 'static,
+				)*
+			{
+				fn type_info(registry: &mut $crate::metadata::Registry) -> $crate::metadata::Type {
+					let mut variants = $crate::dispatch::Vec::new();
+					$(
+						let call = registry.register::<$crate::dispatch::CallableCallFor<$camelcase, $runtime>>();
+						variants.push($crate::metadata::Variant::new(
+							stringify!($camelcase),
+							$crate::dispatch::Vec::from(&[$crate::metadata::Field::unnamed(call)][..]),
+							variants.len() as u8,
+						));
+					)*
+					$crate::metadata::Type {
+						path: $crate::metadata::type_path(module_path!(), stringify!($call_type)),
+						params: $crate::dispatch::Vec::new(),
+						def: $crate::metadata::TypeDef::Variant(variants),
+					}
+				}
+			}
+		}
 		impl $crate::dispatch::Dispatchable for $call_type {
 			type Origin = $origin;
 			type Trait = $call_type;
@@ -1807,9 +1861,109 @@ macro_rules! __dispatch_impl_metadata {
 				$crate::__call_to_functions!($($rest)*)
 			}
 		}
+
+		$crate::__metadata_v12! {
+			$crate::__dispatch_impl_metadata_v12! {
+				$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>
+				{ $( $other_where_bounds )* }
+				$($rest)*
+			}
+		}
 	}
 }
 
+/// Implement metadata V12 for dispatch, registering all argument types in the type registry.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __dispatch_impl_metadata_v12 {
+	(
+		$mod_type:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>
+		{ $( $other_where_bounds:tt )* }
+		$call_type:ident $origin_type:ty
+		{
+			$(
+				$(#[doc = $doc_attr:tt])*
+				fn $fn_name:ident($from:ident
+					$(
+						, $(#[$codec_attr:ident])* $param_name:ident : $param:ty
+					)*
+				);
+			)*
+		}
+	) => {
+		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $mod_type<$trait_instance $(, $instance)?>
+			where $( $other_where_bounds )*
+		{
+			#[doc(hidden)]
+			pub fn call_functions_v12(
+				registry: &mut $crate::metadata::Registry,
+			) -> $crate::dispatch::Vec<$crate::metadata::FunctionMetadataV12>
+				where $( $( $param: $crate::metadata::TypeInfo + 'static, )* )*
+			{
+				let mut functions = $crate::dispatch::Vec::new();
+				$(
+					let mut arguments = $crate::dispatch::Vec::new();
+					$(
+						arguments.push($crate::metadata::FunctionArgumentMetadataV12 {
+							name: $crate::dispatch::DecodeDifferent::Encode(stringify!($param_name)),
+							ty: $crate::__dispatch_impl_metadata_v12!(
+								@register registry; $(#[$codec_attr])* $param
+							),
+						});
+					)*
+					functions.push($crate::metadata::FunctionMetadataV12 {
+						name: $crate::dispatch::DecodeDifferent::Encode(stringify!($fn_name)),
+						arguments,
+						documentation: $crate::dispatch::DecodeDifferent::Encode(&[ $( $doc_attr ),* ]),
+					});
+				)*
+				functions
+			}
+		}
+
+		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $crate::metadata::TypeInfo
+			for $call_type<$trait_instance $(, $instance)?>
+			where
+				$( $( $param: $crate::metadata::TypeInfo + 'static, )* )*
+				$( $other_where_bounds )*
+		{
+			fn type_info(registry: &mut $crate::metadata::Registry) -> $crate::metadata::Type {
+				let mut variants = $crate::dispatch::Vec::new();
+				$(
+					let mut fields = $crate::dispatch::Vec::new();
+					$(
+						fields.push($crate::metadata::Field::named(
+							stringify!($param_name),
+							$crate::__dispatch_impl_metadata_v12!(
+								@register registry; $(#[$codec_attr])* $param
+							),
+						));
+					)*
+					variants.push($crate::metadata::Variant {
+						name: $crate::dispatch::DecodeDifferent::Encode(stringify!($fn_name)),
+						fields,
+						index: variants.len() as u8,
+						documentation: $crate::dispatch::DecodeDifferent::Encode(&[ $( $doc_attr ),* ]),
+					});
+				)*
+				$crate::metadata::Type {
+					path: $crate::metadata::type_path(module_path!(), stringify!($call_type)),
+					params: $crate::dispatch::Vec::new(),
+					def: $crate::metadata::TypeDef::Variant(variants),
+				}
+			}
+		}
+	};
+
+	(@register $registry:ident; #[compact] $param:ty) => {
+		$registry.register::<$crate::codec::Compact<$param>>()
+	};
+
+	(@register $registry:ident; $param:ty) => {
+		$registry.register::<$param>()
+	};
+}
+
 /// Implement metadata for module constants.
 #[macro_export]
 #[doc(hidden)]
@@ -1928,6 +2082,27 @@ macro_rules! __impl_module_constants_metadata {
 					),*
 				]
 			}
+
+			$crate::__metadata_v12! {
+				#[doc(hidden)]
+				pub fn module_constants_metadata_v12(
+					registry: &mut $crate::metadata::Registry,
+				) -> $crate::dispatch::Vec<$crate::metadata::ModuleConstantMetadataV12>
+					where $( $type: $crate::metadata::TypeInfo + 'static, )*
+				{
+					let types: &[$crate::metadata::TypeIndex] = &[ $( registry.register::<$type>() ),* ];
+					Self::module_constants_metadata()
+						.iter()
+						.zip(types.iter())
+						.map(|(constant, ty)| $crate::metadata::ModuleConstantMetadataV12 {
+							name: constant.name.clone(),
+							ty: *ty,
+							value: constant.value.clone(),
+							documentation: constant.documentation.clone(),
+						})
+						.collect()
+				}
+			}
 		}
 	}
 }
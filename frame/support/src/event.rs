@@ -19,6 +19,15 @@
 
 pub use frame_metadata::{EventMetadata, DecodeDifferent, OuterEventMetadata, FnEncode};
 
+/// The metadata V12 of the events of a module, implemented by `decl_event!`.
+#[doc(hidden)]
+pub trait EventsMetadataV12 {
+	/// Returns the metadata of all events, registering their argument types in `registry`.
+	fn events_metadata_v12(
+		registry: &mut crate::metadata::Registry,
+	) -> crate::sp_std::vec::Vec<crate::metadata::EventMetadataV12>;
+}
+
 /// Implement the `Event` for a module.
 ///
 /// # Simple Event Example:
@@ -144,6 +153,37 @@ macro_rules! decl_event {
 			pub fn metadata() -> &'static [ $crate::event::EventMetadata ] {
 				$crate::__events_to_metadata!(; $( $events )* )
 			}
+
+			$crate::__metadata_v12! {
+				#[allow(dead_code)]
+				#[doc(hidden)]
+				pub fn metadata_v12(
+					registry: &mut $crate::metadata::Registry,
+				) -> $crate::sp_std::vec::Vec<$crate::metadata::EventMetadataV12> {
+					$crate::__events_to_metadata_v12!(registry;; $( $events )* )
+				}
+			}
+		}
+		$crate::__metadata_v12! {
+			impl $crate::event::EventsMetadataV12 for Event {
+				fn events_metadata_v12(
+					registry: &mut $crate::metadata::Registry,
+				) -> $crate::sp_std::vec::Vec<$crate::metadata::EventMetadataV12> {
+					Self::metadata_v12(registry)
+				}
+			}
+
+			impl $crate::metadata::TypeInfo for Event {
+				fn type_info(registry: &mut $crate::metadata::Registry) -> $crate::metadata::Type {
+					let events = Self::metadata_v12(registry);
+					$crate::metadata::__event_type(
+						module_path!(),
+						"Event",
+						$crate::sp_std::vec::Vec::new(),
+						events,
+					)
+				}
+			}
 		}
 	}
 }
@@ -292,6 +332,45 @@ macro_rules! __decl_generic_event {
 			pub fn metadata() -> &'static [$crate::event::EventMetadata] {
 				$crate::__events_to_metadata!(; $( $events )* )
 			}
+
+			$crate::__metadata_v12! {
+				#[allow(dead_code)]
+				#[doc(hidden)]
+				pub fn metadata_v12(
+					registry: &mut $crate::metadata::Registry,
+				) -> $crate::sp_std::vec::Vec<$crate::metadata::EventMetadataV12>
+					where $( $generic_param: $crate::metadata::TypeInfo + 'static ),*
+				{
+					$crate::__events_to_metadata_v12!(registry;; $( $events )* )
+				}
+			}
+		}
+		$crate::__metadata_v12! {
+			impl<$( $generic_param ),* $(, $instance)?> $crate::event::EventsMetadataV12
+				for RawEvent<$( $generic_param ),* $(, $instance)?>
+				where $( $generic_param: $crate::metadata::TypeInfo + 'static ),*
+			{
+				fn events_metadata_v12(
+					registry: &mut $crate::metadata::Registry,
+				) -> $crate::sp_std::vec::Vec<$crate::metadata::EventMetadataV12> {
+					Self::metadata_v12(registry)
+				}
+			}
+
+			impl<$( $generic_param ),* $(, $instance)?> $crate::metadata::TypeInfo
+				for RawEvent<$( $generic_param ),* $(, $instance)?>
+				where
+					$( $generic_param: $crate::metadata::TypeInfo + 'static, )*
+					$( $instance: 'static, )?
+			{
+				fn type_info(registry: &mut $crate::metadata::Registry) -> $crate::metadata::Type {
+					let params = <[_]>::into_vec($crate::sp_std::boxed::Box::new([
+						$( registry.register::<$generic_param>() ),*
+					]));
+					let events = Self::metadata_v12(registry);
+					$crate::metadata::__event_type(module_path!(), "RawEvent", params, events)
+				}
+			}
 		}
 	};
 	(@cannot_parse $ty:ty) => {
@@ -329,6 +408,39 @@ macro_rules! __events_to_metadata {
 	}
 }
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __events_to_metadata_v12 {
+	(
+		$registry:ident;
+		$( $metadata:expr ),*;
+		$( #[doc = $doc_attr:tt] )*
+		$event:ident $( ( $( $param:path ),* $(,)? ) )*,
+		$( $rest:tt )*
+	) => {
+		$crate::__events_to_metadata_v12!(
+			$registry;
+			$( $metadata, )*
+			$crate::metadata::EventMetadataV12 {
+				name: $crate::event::DecodeDifferent::Encode(stringify!($event)),
+				arguments: <[_]>::into_vec($crate::sp_std::boxed::Box::new([
+					$( $( $registry.register::<$param>() ),* )*
+				])),
+				documentation: $crate::event::DecodeDifferent::Encode(&[
+					$( $doc_attr ),*
+				]),
+			};
+			$( $rest )*
+		)
+	};
+	(
+		$registry:ident;
+		$( $metadata:expr ),*;
+	) => {
+		<[_]>::into_vec($crate::sp_std::boxed::Box::new([ $( $metadata ),* ]))
+	}
+}
+
 /// Constructs an Event type for a runtime. This is usually called automatically by the
 /// construct_runtime macro.
 #[macro_export]
@@ -471,6 +583,38 @@ macro_rules! impl_outer_event {
 					}
 				}
 			)*
+			$crate::__metadata_v12! {
+				// The bounds are higher-ranked so that they are only checked when the impl is used,
+				// i.e. only runtimes that generate metadata V12 require their events to implement
+				// `TypeInfo`.
+				impl $crate::metadata::TypeInfo for $name
+					where $(
+						for<'a> $module_name::Event < $( $generic_param )? $(, $module_name::$generic_instance )? >:
+							$crate::metadata::TypeInfo + 'static,
+					)*
+				{
+					fn type_info(
+						registry: &mut $crate::metadata::Registry,
+					) -> $crate::metadata::Type {
+						let mut variants = $crate::sp_std::vec::Vec::new();
+						$(
+							let event = registry.register::<
+								$module_name::Event < $( $generic_param )? $(, $module_name::$generic_instance )? >
+							>();
+							variants.push($crate::metadata::Variant::new(
+								stringify!([< $module_name $(_ $generic_instance )? >]),
+								$crate::sp_std::vec::Vec::from(&[$crate::metadata::Field::unnamed(event)][..]),
+								variants.len() as u8,
+							));
+						)*
+						$crate::metadata::Type {
+							path: $crate::metadata::type_path(module_path!(), stringify!($name)),
+							params: $crate::sp_std::vec::Vec::new(),
+							def: $crate::metadata::TypeDef::Variant(variants),
+						}
+					}
+				}
+			}
 		}
 		$crate::__impl_outer_event_json_metadata!(
 			$runtime;
@@ -530,6 +674,19 @@ macro_rules! __impl_outer_event_json_metadata {
 				{
 					$module_name::Event ::< $( $generic_params ),* > ::metadata()
 				}
+
+				$crate::__metadata_v12! {
+					#[allow(dead_code)]
+					pub fn [< __module_events_v12_ $module_name $( _ $instance )? >] (
+						registry: &mut $crate::metadata::Registry,
+					) -> $crate::sp_std::vec::Vec<$crate::metadata::EventMetadataV12>
+						where for<'a> $module_name::Event ::< $( $generic_params ),* >:
+							$crate::event::EventsMetadataV12
+					{
+						<$module_name::Event ::< $( $generic_params ),* > as $crate::event::EventsMetadataV12>
+							::events_metadata_v12(registry)
+					}
+				}
 			)*
 		}
 	}
@@ -147,7 +147,7 @@ macro_rules! ord_parameter_types {
 }
 
 #[doc(inline)]
-pub use frame_support_procedural::{decl_storage, construct_runtime};
+pub use frame_support_procedural::{decl_storage, construct_runtime, transactional};
 
 /// Return Err of the expression: `return Err($expression);`.
 ///
@@ -222,10 +222,7 @@ macro_rules! assert_ok {
 	}
 }
 
-/// The void type - it cannot exist.
-// Oh rust, you crack me up...
-#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
-pub enum Void {}
+pub use sp_core::Void;
 
 #[cfg(feature = "std")]
 #[doc(hidden)]
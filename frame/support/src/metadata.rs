@@ -18,8 +18,64 @@ pub use frame_metadata::{
 	DecodeDifferent, FnEncode, RuntimeMetadata, ModuleMetadata, RuntimeMetadataLastVersion,
 	DefaultByteGetter, RuntimeMetadataPrefixed, StorageEntryMetadata, StorageMetadata,
 	StorageEntryType, StorageEntryModifier, DefaultByte, StorageHasher, ModuleErrorMetadata,
-	ExtrinsicMetadata,
+	ExtrinsicMetadata, RuntimeMetadataV12, ModuleMetadataV12, StorageMetadataV12,
+	StorageEntryMetadataV12, StorageEntryTypeV12, ModuleConstantMetadataV12, FunctionMetadataV12,
+	FunctionArgumentMetadataV12, EventMetadataV12,
+	registry::{
+		TypeInfo, TypeIndex, Registry, PortableRegistry, Type, TypeDef, TypeDefPrimitive, Field,
+		Variant, type_path,
+	},
 };
+pub use frame_support_procedural::TypeInfo;
+
+/// Expands the given items only if metadata V12 is enabled through the `metadata-v12` feature.
+///
+/// Generating metadata V12 requires every type used by a module to implement [`TypeInfo`], so
+/// it is only generated on request.
+#[macro_export]
+#[doc(hidden)]
+#[cfg(feature = "metadata-v12")]
+macro_rules! __metadata_v12 {
+	( $( $items:tt )* ) => { $( $items )* }
+}
+
+/// Expands the given items only if metadata V12 is enabled through the `metadata-v12` feature.
+///
+/// Generating metadata V12 requires every type used by a module to implement [`TypeInfo`], so
+/// it is only generated on request.
+#[macro_export]
+#[doc(hidden)]
+#[cfg(not(feature = "metadata-v12"))]
+macro_rules! __metadata_v12 {
+	( $( $items:tt )* ) => {}
+}
+
+/// Describe an event enum of the given `name` from the metadata of its events.
+///
+/// The events are expected in the order of their declaration, which defines their encoding.
+#[doc(hidden)]
+pub fn __event_type(
+	module_path: &'static str,
+	name: &'static str,
+	params: crate::dispatch::Vec<TypeIndex>,
+	events: crate::dispatch::Vec<EventMetadataV12>,
+) -> Type {
+	Type {
+		path: type_path(module_path, name),
+		params,
+		def: TypeDef::Variant(
+			events.into_iter()
+				.enumerate()
+				.map(|(index, event)| Variant {
+					name: event.name,
+					fields: event.arguments.into_iter().map(Field::unnamed).collect(),
+					index: index as u8,
+					documentation: event.documentation,
+				})
+				.collect()
+		),
+	}
+}
 
 /// Implements the metadata support for the given runtime and all its modules.
 ///
@@ -57,6 +113,12 @@ pub use frame_metadata::{
 /// ```
 ///
 /// In this example, just `MODULE3` implements the `Storage` trait.
+///
+/// `metadata()` returns metadata V12, that describes all used types in a portable registry, if the
+/// crate calling this macro has a `metadata-v12` feature enabled, which should enable the feature
+/// of the same name of `frame-support`. This requires all types used by the modules to implement
+/// [`TypeInfo`]. The version is selected per runtime crate, so that other runtimes in the same
+/// build are not affected by the feature unification of cargo.
 #[macro_export]
 macro_rules! impl_runtime_metadata {
 	(
@@ -64,22 +126,46 @@ macro_rules! impl_runtime_metadata {
 			$( $rest:tt )*
 	) => {
 		impl $runtime {
+			#[cfg(not(feature = "metadata-v12"))]
 			pub fn metadata() -> $crate::metadata::RuntimeMetadataPrefixed {
 				$crate::metadata::RuntimeMetadataLastVersion {
 						modules: $crate::__runtime_modules_to_metadata!($runtime;; $( $rest )*),
-						extrinsic: $crate::metadata::ExtrinsicMetadata {
-							version: <$ext as $crate::sp_runtime::traits::ExtrinsicMetadata>::VERSION,
-							signed_extensions: <
-									<
-										$ext as $crate::sp_runtime::traits::ExtrinsicMetadata
-									>::SignedExtensions as $crate::sp_runtime::traits::SignedExtension
-								>::identifier()
-									.into_iter()
-									.map($crate::metadata::DecodeDifferent::Encode)
-									.collect(),
-						},
+						extrinsic: $crate::__runtime_extrinsic_metadata!($ext),
 				}.into()
 			}
+
+			$crate::__metadata_v12! {
+				#[cfg(feature = "metadata-v12")]
+				pub fn metadata() -> $crate::metadata::RuntimeMetadataPrefixed {
+					let mut registry = $crate::metadata::Registry::new();
+					let modules = $crate::__runtime_modules_to_metadata_v12!(
+						$runtime; registry;; $( $rest )*
+					);
+					$crate::metadata::RuntimeMetadataV12 {
+						types: registry.into_portable(),
+						modules,
+						extrinsic: $crate::__runtime_extrinsic_metadata!($ext),
+					}.into()
+				}
+			}
+		}
+	}
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_extrinsic_metadata {
+	( $ext:ident ) => {
+		$crate::metadata::ExtrinsicMetadata {
+			version: <$ext as $crate::sp_runtime::traits::ExtrinsicMetadata>::VERSION,
+			signed_extensions: <
+					<
+						$ext as $crate::sp_runtime::traits::ExtrinsicMetadata
+					>::SignedExtensions as $crate::sp_runtime::traits::SignedExtension
+				>::identifier()
+					.into_iter()
+					.map($crate::metadata::DecodeDifferent::Encode)
+					.collect(),
 		}
 	}
 }
@@ -237,12 +323,117 @@ macro_rules! __runtime_modules_to_metadata_calls_storage {
 	};
 }
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_v12 {
+	(
+		$runtime:ident;
+		$registry:ident;
+		$( $metadata:expr ),*;
+		$mod:ident::$module:ident $( < $instance:ident > )? as $name:ident $(with)+ $($kw:ident)*,
+		$( $rest:tt )*
+	) => {
+		$crate::__runtime_modules_to_metadata_v12!(
+			$runtime;
+			$registry;
+			$( $metadata, )* $crate::metadata::ModuleMetadataV12 {
+				name: $crate::metadata::DecodeDifferent::Encode(stringify!($name)),
+				storage: $crate::__runtime_modules_to_metadata_v12_part!(
+					Storage; $mod, $module $( <$instance> )?, $runtime, $registry, $(with $kw)*
+				),
+				calls: $crate::__runtime_modules_to_metadata_v12_part!(
+					Call; $mod, $module $( <$instance> )?, $runtime, $registry, $(with $kw)*
+				),
+				event: $crate::__runtime_modules_to_metadata_v12_part!(
+					Event; $mod, $module $( <$instance> )?, $runtime, $registry, $(with $kw)*
+				),
+				constants: $mod::$module::<$runtime $(, $mod::$instance )?>
+					::module_constants_metadata_v12(&mut $registry),
+				errors: $crate::metadata::DecodeDifferent::Encode(
+					<$mod::$module::<$runtime $(, $mod::$instance )?> as $crate::metadata::ModuleErrorMetadata>
+						::metadata()
+				),
+			};
+			$( $rest )*
+		)
+	};
+	(
+		$runtime:ident;
+		$registry:ident;
+		$( $metadata:expr ),*;
+	) => {
+		<[_]>::into_vec($crate::sp_std::boxed::Box::new([ $( $metadata ),* ]))
+	};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_v12_part {
+	(
+		Storage;
+		$mod:ident,
+		$module:ident $( <$instance:ident> )?,
+		$runtime:ident,
+		$registry:ident,
+		with Storage
+		$(with $kws:ident)*
+	) => {
+		Some($mod::$module::<$runtime $(, $mod::$instance )?>::storage_metadata_v12(&mut $registry))
+	};
+	(
+		Call;
+		$mod:ident,
+		$module:ident $( <$instance:ident> )?,
+		$runtime:ident,
+		$registry:ident,
+		with Call
+		$(with $kws:ident)*
+	) => {
+		Some($mod::$module::<$runtime $(, $mod::$instance )?>::call_functions_v12(&mut $registry))
+	};
+	(
+		Event;
+		$mod:ident,
+		$module:ident $( <$instance:ident> )?,
+		$runtime:ident,
+		$registry:ident,
+		with Event
+		$(with $kws:ident)*
+	) => {
+		Some($crate::paste::expr!{
+			$runtime:: [< __module_events_v12_ $mod $(_ $instance)?>](&mut $registry)
+		})
+	};
+	(
+		$part:ident;
+		$mod:ident,
+		$module:ident $( <$instance:ident> )?,
+		$runtime:ident,
+		$registry:ident,
+		with $_:ident
+		$(with $kws:ident)*
+	) => {
+		$crate::__runtime_modules_to_metadata_v12_part!(
+			$part; $mod, $module $( <$instance> )?, $runtime, $registry, $(with $kws)*
+		)
+	};
+	(
+		$part:ident;
+		$mod:ident,
+		$module:ident $( <$instance:ident> )?,
+		$runtime:ident,
+		$registry:ident,
+	) => {
+		None
+	};
+}
 
 #[cfg(test)]
 // Do not complain about unused `dispatch` and `dispatch_aux`.
 #[allow(dead_code)]
 mod tests {
 	use super::*;
+	#[cfg_attr(feature = "metadata-v12", allow(unused_imports))]
 	use frame_metadata::{
 		EventMetadata, StorageEntryModifier, StorageEntryType, FunctionMetadata, StorageEntryMetadata,
 		ModuleMetadata, RuntimeMetadataPrefixed, DefaultByte, ModuleConstantMetadata, DefaultByteGetter,
@@ -256,7 +447,7 @@ mod tests {
 	struct TestExtension;
 	impl sp_runtime::traits::SignedExtension for TestExtension {
 		type AccountId = u32;
-		type Call = u32;
+		type Call = ();
 		type AdditionalSigned = u32;
 		type DispatchInfo = ();
 		type Pre = ();
@@ -270,7 +461,7 @@ mod tests {
 	struct TestExtension2;
 	impl sp_runtime::traits::SignedExtension for TestExtension2 {
 		type AccountId = u32;
-		type Call = u32;
+		type Call = ();
 		type AdditionalSigned = u32;
 		type DispatchInfo = ();
 		type Pre = ();
@@ -474,6 +665,7 @@ mod tests {
 	}
 
 	#[test]
+	#[cfg(not(feature = "metadata-v12"))]
 	fn runtime_metadata() {
 		let expected_metadata: RuntimeMetadataLastVersion = RuntimeMetadataLastVersion {
 			modules: DecodeDifferent::Encode(&[
@@ -608,4 +800,27 @@ mod tests {
 
 		pretty_assertions::assert_eq!(expected_metadata, metadata_decoded.unwrap());
 	}
+
+	#[test]
+	#[cfg(feature = "metadata-v12")]
+	fn runtime_metadata_v12() {
+		let metadata_encoded = TestRuntime::metadata().encode();
+		let metadata = RuntimeMetadataPrefixed::decode(&mut &metadata_encoded[..]).unwrap();
+
+		let metadata = match metadata.1 {
+			RuntimeMetadata::V12(metadata) => metadata,
+			_ => panic!("Expected metadata V12"),
+		};
+		let names = metadata.modules.iter().map(|m| m.name.clone()).collect::<Vec<_>>();
+		assert_eq!(names, vec![
+			DecodeDifferent::Decoded("System".into()),
+			DecodeDifferent::Decoded("Module".into()),
+			DecodeDifferent::Decoded("Module2".into()),
+		]);
+		let event = &metadata.modules[2].event.as_ref().unwrap()[0];
+		assert_eq!(
+			metadata.types.resolve(event.arguments[0]).map(|t| &t.def),
+			Some(&TypeDef::Primitive(TypeDefPrimitive::U32)),
+		);
+	}
 }
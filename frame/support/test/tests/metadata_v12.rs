@@ -0,0 +1,251 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(feature = "metadata-v12")]
+#![recursion_limit="128"]
+
+use codec::{Encode, Decode};
+use sp_runtime::{generic, traits::BlakeTwo256};
+use sp_core::{H256, sr25519};
+use frame_support::metadata::{
+	DecodeDifferent, PortableRegistry, Registry, RuntimeMetadata, StorageEntryTypeV12,
+	TypeDef, TypeDefPrimitive, TypeIndex, TypeInfo,
+};
+
+mod system;
+
+mod module {
+	use super::*;
+
+	pub trait Trait: system::Trait {
+		type Balance: codec::Codec + codec::EncodeLike + Default + Clone + Eq + std::fmt::Debug;
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Default, TypeInfo)]
+	pub struct Ledger<Balance> {
+		#[codec(compact)]
+		pub total: Balance,
+		pub locked: Vec<(u32, Balance)>,
+	}
+
+	frame_support::decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+			/// The maximum number of locks.
+			const MaxLocks: u32 = 8;
+
+			/// Transfer some balance.
+			#[weight = frame_support::weights::SimpleDispatchInfo::default()]
+			fn transfer(_origin, dest: T::AccountId, #[compact] value: T::Balance) {}
+
+			#[weight = frame_support::weights::SimpleDispatchInfo::default()]
+			fn set_ledger(_origin, ledger: Ledger<T::Balance>) {}
+		}
+	}
+
+	frame_support::decl_storage! {
+		trait Store for Module<T: Trait> as Module {
+			pub Total: T::Balance;
+			pub Ledgers: map hasher(blake2_128_concat) T::AccountId => Option<Ledger<T::Balance>>;
+			pub Allowances:
+				double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) T::AccountId
+				=> T::Balance;
+		}
+	}
+
+	frame_support::decl_event! {
+		pub enum Event<T> where AccountId = <T as system::Trait>::AccountId, Balance = <T as Trait>::Balance {
+			/// Some balance was transferred.
+			Transferred(AccountId, AccountId, Balance),
+			Reset,
+		}
+	}
+}
+
+impl module::Trait for Runtime {
+	type Balance = u128;
+}
+
+pub type Signature = sr25519::Signature;
+pub type BlockNumber = u64;
+
+impl system::Trait for Runtime {
+	type Hash = H256;
+	type Origin = Origin;
+	type BlockNumber = BlockNumber;
+	type AccountId = u64;
+	type Event = Event;
+	type ModuleToIndex = ModuleToIndex;
+}
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module, Call, Event<T>},
+		Example: module::{Module, Call, Storage, Event<T>},
+	}
+);
+
+pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
+pub type Block = generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<u32, Call, Signature, ()>;
+
+fn resolve(registry: &PortableRegistry, index: TypeIndex) -> &TypeDef {
+	&registry.resolve(index).expect("All referenced types are in the registry").def
+}
+
+#[test]
+fn derived_type_info_works() {
+	let mut registry = Registry::new();
+	let ledger = registry.register::<module::Ledger<u128>>();
+	let u128_index = registry.register::<u128>();
+	let registry = registry.into_portable();
+
+	let ledger = registry.resolve(ledger).unwrap();
+	assert_eq!(ledger.params, vec![u128_index]);
+	assert_eq!(ledger.path.last(), Some(&DecodeDifferent::Encode("Ledger")));
+	let fields = match &ledger.def {
+		TypeDef::Composite(fields) => fields,
+		def => panic!("Unexpected def {:?}", def),
+	};
+	assert_eq!(fields.len(), 2);
+	assert_eq!(resolve(&registry, fields[0].ty), &TypeDef::Compact(u128_index));
+	match resolve(&registry, fields[1].ty) {
+		TypeDef::Sequence(tuple) => match resolve(&registry, *tuple) {
+			TypeDef::Tuple(types) => assert_eq!(types[1], u128_index),
+			def => panic!("Unexpected def {:?}", def),
+		},
+		def => panic!("Unexpected def {:?}", def),
+	}
+}
+
+#[test]
+fn call_metadata_registers_argument_types() {
+	let mut registry = Registry::new();
+	let functions = module::Module::<Runtime>::call_functions_v12(&mut registry);
+	let registry = registry.into_portable();
+
+	assert_eq!(functions.len(), 2);
+	assert_eq!(functions[0].name, DecodeDifferent::Encode("transfer"));
+	assert_eq!(functions[0].documentation, DecodeDifferent::Encode(&[" Transfer some balance."][..]));
+	assert_eq!(
+		resolve(&registry, functions[0].arguments[0].ty),
+		&TypeDef::Primitive(TypeDefPrimitive::U64),
+	);
+	match resolve(&registry, functions[0].arguments[1].ty) {
+		TypeDef::Compact(balance) => assert_eq!(
+			resolve(&registry, *balance),
+			&TypeDef::Primitive(TypeDefPrimitive::U128),
+		),
+		def => panic!("Unexpected def {:?}", def),
+	}
+}
+
+#[test]
+fn call_type_info_matches_encoding() {
+	let mut registry = Registry::new();
+	let call = registry.register::<Call>();
+	let registry = registry.into_portable();
+
+	let modules = match resolve(&registry, call) {
+		TypeDef::Variant(variants) => variants,
+		def => panic!("Unexpected def {:?}", def),
+	};
+	let module_call = &modules[1];
+	assert_eq!(module_call.index, 1);
+	assert_eq!(Call::Example(module::Call::set_ledger(Default::default())).encode()[0], 1);
+
+	let calls = match resolve(&registry, module_call.fields[0].ty) {
+		TypeDef::Variant(variants) => variants,
+		def => panic!("Unexpected def {:?}", def),
+	};
+	assert_eq!(calls[1].name, DecodeDifferent::Encode("set_ledger"));
+	assert_eq!(calls[1].index, 1);
+	assert_eq!(module::Call::<Runtime>::set_ledger(Default::default()).encode()[0], 1);
+}
+
+#[test]
+fn storage_metadata_registers_types() {
+	let mut registry = Registry::new();
+	let storage = module::Module::<Runtime>::storage_metadata_v12(&mut registry);
+	let u64_index = registry.register::<u64>();
+	let u128_index = registry.register::<u128>();
+	let ledger = registry.register::<module::Ledger<u128>>();
+
+	assert_eq!(storage.prefix, DecodeDifferent::Encode("Module"));
+	assert_eq!(storage.entries[0].ty, StorageEntryTypeV12::Plain(u128_index));
+	match &storage.entries[1].ty {
+		StorageEntryTypeV12::Map { key, value, .. } => {
+			assert_eq!(*key, u64_index);
+			assert_eq!(*value, ledger);
+		},
+		ty => panic!("Unexpected type {:?}", ty),
+	}
+	match &storage.entries[2].ty {
+		StorageEntryTypeV12::DoubleMap { key1, key2, value, .. } => {
+			assert_eq!((*key1, *key2, *value), (u64_index, u64_index, u128_index));
+		},
+		ty => panic!("Unexpected type {:?}", ty),
+	}
+}
+
+#[test]
+fn event_and_constant_metadata_register_types() {
+	let mut registry = Registry::new();
+	let events = module::Event::<Runtime>::metadata_v12(&mut registry);
+	let constants = module::Module::<Runtime>::module_constants_metadata_v12(&mut registry);
+	let u32_index = registry.register::<u32>();
+	let u64_index = registry.register::<u64>();
+	let u128_index = registry.register::<u128>();
+
+	assert_eq!(events.len(), 2);
+	assert_eq!(events[0].arguments, vec![u64_index, u64_index, u128_index]);
+	assert!(events[1].arguments.is_empty());
+	assert_eq!(constants[0].name, DecodeDifferent::Encode("MaxLocks"));
+	assert_eq!(constants[0].ty, u32_index);
+}
+
+#[test]
+fn runtime_metadata_is_v12_and_decodes() {
+	let encoded = Runtime::metadata().encode();
+	let metadata = frame_support::metadata::RuntimeMetadataPrefixed::decode(&mut &encoded[..])
+		.expect("Metadata decodes");
+
+	let metadata = match metadata.1 {
+		RuntimeMetadata::V12(metadata) => metadata,
+		_ => panic!("Expected metadata V12"),
+	};
+	assert_eq!(metadata.modules.len(), 2);
+
+	let module = &metadata.modules[1];
+	assert_eq!(module.name, DecodeDifferent::Encode("Example"));
+	assert_eq!(module.calls.as_ref().map(|c| c.len()), Some(2));
+	let event = &module.event.as_ref().expect("Module has events")[0];
+	assert_eq!(
+		metadata.types.resolve(event.arguments[2]).map(|t| &t.def),
+		Some(&TypeDef::Primitive(TypeDefPrimitive::U128)),
+	);
+	let storage = module.storage.as_ref().expect("Module has storage");
+	assert_eq!(storage.entries.len(), 3);
+	let ledger = module.calls.as_ref().unwrap()[1].arguments[0].ty;
+	match &metadata.types.resolve(ledger).unwrap().def {
+		TypeDef::Composite(fields) =>
+			assert_eq!(fields[1].name, Some(DecodeDifferent::Encode("locked"))),
+		def => panic!("Unexpected def {:?}", def),
+	}
+}
@@ -0,0 +1,215 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use codec::{Encode, Decode, EncodeLike};
+use frame_support::{
+	StorageMap, StorageValue, transactional,
+	dispatch::DispatchResult,
+	storage::{with_transaction, TransactionOutcome::*},
+};
+use sp_io::TestExternalities;
+use sp_runtime::DispatchError;
+use std::result;
+
+pub trait Trait {
+	type Origin;
+	type BlockNumber: Encode + Decode + EncodeLike + Default + Clone;
+}
+
+frame_support::decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		#[weight = frame_support::weights::SimpleDispatchInfo::default()]
+		#[transactional]
+		fn value_commits(_origin, v: u32) {
+			Value::set(v);
+		}
+
+		#[weight = frame_support::weights::SimpleDispatchInfo::default()]
+		#[transactional]
+		fn value_rollbacks(_origin, v: u32) -> DispatchResult {
+			Value::set(v);
+			Err(DispatchError::Other("nah"))
+		}
+	}
+}
+
+frame_support::decl_storage!{
+	trait Store for Module<T: Trait> as StorageTransactions {
+		pub Value: u32;
+		pub Map: map hasher(twox_64_concat) String => u32;
+	}
+}
+
+struct Runtime;
+
+impl Trait for Runtime {
+	type Origin = u32;
+	type BlockNumber = u32;
+}
+
+#[test]
+fn storage_transaction_basic_commit() {
+	TestExternalities::default().execute_with(|| {
+		assert_eq!(Value::get(), 0);
+		assert!(!Map::contains_key("val0"));
+
+		with_transaction(|| {
+			Value::set(99);
+			Map::insert("val0", 99);
+			assert_eq!(Value::get(), 99);
+			assert_eq!(Map::get("val0"), 99);
+			Commit(())
+		});
+
+		assert_eq!(Value::get(), 99);
+		assert_eq!(Map::get("val0"), 99);
+	});
+}
+
+#[test]
+fn storage_transaction_basic_rollback() {
+	TestExternalities::default().execute_with(|| {
+		assert_eq!(Value::get(), 0);
+		assert_eq!(Map::get("val0"), 0);
+
+		with_transaction(|| {
+			Value::set(99);
+			Map::insert("val0", 99);
+			assert_eq!(Value::get(), 99);
+			assert_eq!(Map::get("val0"), 99);
+			Rollback(())
+		});
+
+		assert_eq!(Value::get(), 0);
+		assert_eq!(Map::get("val0"), 0);
+	});
+}
+
+#[test]
+fn storage_transaction_rollback_then_commit() {
+	TestExternalities::default().execute_with(|| {
+		Value::set(1);
+		Map::insert("val1", 1);
+
+		with_transaction(|| {
+			Value::set(2);
+			Map::insert("val1", 2);
+			Map::insert("val2", 2);
+
+			with_transaction(|| {
+				Value::set(3);
+				Map::insert("val1", 3);
+				Map::insert("val2", 3);
+				Map::insert("val3", 3);
+
+				assert_eq!(Value::get(), 3);
+				assert_eq!(Map::get("val1"), 3);
+				assert_eq!(Map::get("val2"), 3);
+				assert_eq!(Map::get("val3"), 3);
+
+				Rollback(())
+			});
+
+			assert_eq!(Value::get(), 2);
+			assert_eq!(Map::get("val1"), 2);
+			assert_eq!(Map::get("val2"), 2);
+			assert_eq!(Map::get("val3"), 0);
+
+			Commit(())
+		});
+
+		assert_eq!(Value::get(), 2);
+		assert_eq!(Map::get("val1"), 2);
+		assert_eq!(Map::get("val2"), 2);
+		assert_eq!(Map::get("val3"), 0);
+	});
+}
+
+#[test]
+fn storage_transaction_commit_then_rollback() {
+	TestExternalities::default().execute_with(|| {
+		Value::set(1);
+		Map::insert("val1", 1);
+
+		with_transaction(|| {
+			Value::set(2);
+			Map::insert("val1", 2);
+			Map::insert("val2", 2);
+
+			with_transaction(|| {
+				Value::set(3);
+				Map::insert("val1", 3);
+				Map::insert("val2", 3);
+				Map::insert("val3", 3);
+
+				assert_eq!(Value::get(), 3);
+				assert_eq!(Map::get("val1"), 3);
+				assert_eq!(Map::get("val2"), 3);
+				assert_eq!(Map::get("val3"), 3);
+
+				Commit(())
+			});
+
+			assert_eq!(Value::get(), 3);
+			assert_eq!(Map::get("val1"), 3);
+			assert_eq!(Map::get("val2"), 3);
+			assert_eq!(Map::get("val3"), 3);
+
+			Rollback(())
+		});
+
+		assert_eq!(Value::get(), 1);
+		assert_eq!(Map::get("val1"), 1);
+		assert_eq!(Map::get("val2"), 0);
+		assert_eq!(Map::get("val3"), 0);
+	});
+}
+
+#[test]
+fn transactional_annotation() {
+	#[transactional]
+	fn value_commits(v: u32) -> result::Result<u32, &'static str> {
+		Value::set(v);
+		Ok(v)
+	}
+
+	#[transactional]
+	fn value_rollbacks(v: u32) -> result::Result<u32, &'static str> {
+		Value::set(v);
+		Err("nah")?;
+		Ok(v)
+	}
+
+	TestExternalities::default().execute_with(|| {
+		assert_eq!(value_commits(2), Ok(2));
+		assert_eq!(Value::get(), 2);
+
+		assert_eq!(value_rollbacks(3), Err("nah"));
+		assert_eq!(Value::get(), 2);
+	});
+}
+
+#[test]
+fn transactional_annotation_in_decl_module() {
+	TestExternalities::default().execute_with(|| {
+		let origin = 0;
+		assert_eq!(<Module<Runtime>>::value_commits(origin, 2), Ok(()));
+		assert_eq!(Value::get(), 2);
+
+		assert_eq!(<Module<Runtime>>::value_rollbacks(origin, 3), Err(DispatchError::Other("nah")));
+		assert_eq!(Value::get(), 2);
+	});
+}
@@ -90,6 +90,53 @@ fn create_tips<T: Trait>(t: u32, hash: T::Hash, value: BalanceOf<T>) -> Result<(
 	Ok(())
 }
 
+// Create the pre-requisite information needed to create a treasury `propose_bounty`.
+fn setup_bounty<T: Trait>(u: u32, d: u32) -> (
+	T::AccountId,
+	T::AccountId,
+	BalanceOf<T>,
+	BalanceOf<T>,
+	Vec<u8>,
+) {
+	let caller = account("caller", u, SEED);
+	let value: BalanceOf<T> = T::BountyValueMinimum::get().saturating_mul(100.into());
+	let fee = value / 2.into();
+	let deposit = T::BountyDepositBase::get()
+		+ T::BountyDepositPerByte::get() * d.into()
+		+ T::Currency::minimum_balance();
+	let _ = T::Currency::make_free_balance_be(&caller, deposit);
+	let curator = account("curator", u, SEED);
+	let _ = T::Currency::make_free_balance_be(&curator, fee + T::Currency::minimum_balance());
+	let description = vec![0; d as usize];
+	(caller, curator, fee, value, description)
+}
+
+// Create a funded bounty, returning its index along with the proposed curator and fee.
+fn create_funded_bounty<T: Trait>() -> Result<(BountyIndex, T::AccountId, BalanceOf<T>), &'static str> {
+	let (caller, curator, fee, value, description) = setup_bounty::<T>(0, MAX_BYTES);
+	Treasury::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, description)?;
+	let bounty_id = BountyCount::get() - 1;
+	Treasury::<T>::approve_bounty(RawOrigin::Root.into(), bounty_id)?;
+	Treasury::<T>::on_initialize(T::BlockNumber::zero());
+	Ok((bounty_id, curator, fee))
+}
+
+// Create a funded bounty with an active curator, returning its index and the curator.
+fn create_active_bounty<T: Trait>() -> Result<(BountyIndex, T::AccountId), &'static str> {
+	let (bounty_id, curator, fee) = create_funded_bounty::<T>()?;
+	let curator_lookup = T::Lookup::unlookup(curator.clone());
+	Treasury::<T>::propose_curator(RawOrigin::Root.into(), bounty_id, curator_lookup, fee)?;
+	Treasury::<T>::accept_curator(RawOrigin::Signed(curator.clone()).into(), bounty_id)?;
+	Ok((bounty_id, curator))
+}
+
+// Make sure the pot is funded.
+fn setup_pot_account<T: Trait>() {
+	let pot_account = Treasury::<T>::account_id();
+	let value = T::Currency::minimum_balance().saturating_mul(1_000_000_000.into());
+	let _ = T::Currency::make_free_balance_be(&pot_account, value);
+}
+
 // Create proposals that are approved for use in `on_initialize`.
 fn create_approved_proposals<T: Trait>(n: u32) -> Result<(), &'static str> {
 	for i in 0 .. n {
@@ -207,6 +254,70 @@ benchmarks! {
 		let caller = account("caller", t, SEED);
 	}: _(RawOrigin::Signed(caller), hash)
 
+	propose_bounty {
+		let d in 0 .. MAX_BYTES;
+		let (caller, _, _, value, description) = setup_bounty::<T>(0, d);
+	}: _(RawOrigin::Signed(caller), value, description)
+
+	approve_bounty {
+		let (caller, _, _, value, description) = setup_bounty::<T>(0, MAX_BYTES);
+		Treasury::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, description)?;
+		let bounty_id = BountyCount::get() - 1;
+	}: _(RawOrigin::Root, bounty_id)
+
+	propose_curator {
+		setup_pot_account::<T>();
+		let (bounty_id, curator, fee) = create_funded_bounty::<T>()?;
+		let curator_lookup = T::Lookup::unlookup(curator);
+	}: _(RawOrigin::Root, bounty_id, curator_lookup, fee)
+
+	// Worst case when the curator is inactive and any sender unassigns them.
+	unassign_curator {
+		setup_pot_account::<T>();
+		let (bounty_id, _) = create_active_bounty::<T>()?;
+		let now = frame_system::Module::<T>::block_number();
+		frame_system::Module::<T>::set_block_number(now + T::BountyUpdatePeriod::get() + 1.into());
+		let caller = account("caller", 0, SEED);
+	}: _(RawOrigin::Signed(caller), bounty_id)
+
+	accept_curator {
+		setup_pot_account::<T>();
+		let (bounty_id, curator, fee) = create_funded_bounty::<T>()?;
+		let curator_lookup = T::Lookup::unlookup(curator.clone());
+		Treasury::<T>::propose_curator(RawOrigin::Root.into(), bounty_id, curator_lookup, fee)?;
+	}: _(RawOrigin::Signed(curator), bounty_id)
+
+	award_bounty {
+		setup_pot_account::<T>();
+		let (bounty_id, curator) = create_active_bounty::<T>()?;
+		let beneficiary = T::Lookup::unlookup(account("beneficiary", 0, SEED));
+	}: _(RawOrigin::Signed(curator), bounty_id, beneficiary)
+
+	claim_bounty {
+		setup_pot_account::<T>();
+		let (bounty_id, curator) = create_active_bounty::<T>()?;
+		let beneficiary = T::Lookup::unlookup(account("beneficiary", 0, SEED));
+		Treasury::<T>::award_bounty(RawOrigin::Signed(curator.clone()).into(), bounty_id, beneficiary)?;
+		let now = frame_system::Module::<T>::block_number();
+		frame_system::Module::<T>::set_block_number(now + T::BountyDepositPayoutDelay::get());
+	}: _(RawOrigin::Signed(curator), bounty_id)
+
+	close_bounty_proposed {
+		let (caller, _, _, value, description) = setup_bounty::<T>(0, 0);
+		Treasury::<T>::propose_bounty(RawOrigin::Signed(caller).into(), value, description)?;
+		let bounty_id = BountyCount::get() - 1;
+	}: close_bounty(RawOrigin::Root, bounty_id)
+
+	close_bounty_active {
+		setup_pot_account::<T>();
+		let (bounty_id, _) = create_active_bounty::<T>()?;
+	}: close_bounty(RawOrigin::Root, bounty_id)
+
+	extend_bounty_expiry {
+		setup_pot_account::<T>();
+		let (bounty_id, curator) = create_active_bounty::<T>()?;
+	}: _(RawOrigin::Signed(curator), bounty_id, Vec::new())
+
 	on_initialize {
 		let p in 0 .. 100;
 		let pot_account = Treasury::<T>::account_id();
@@ -235,6 +346,16 @@ mod tests {
 			assert_ok!(test_benchmark_tip_new::<Test>());
 			assert_ok!(test_benchmark_tip::<Test>());
 			assert_ok!(test_benchmark_close_tip::<Test>());
+			assert_ok!(test_benchmark_propose_bounty::<Test>());
+			assert_ok!(test_benchmark_approve_bounty::<Test>());
+			assert_ok!(test_benchmark_propose_curator::<Test>());
+			assert_ok!(test_benchmark_unassign_curator::<Test>());
+			assert_ok!(test_benchmark_accept_curator::<Test>());
+			assert_ok!(test_benchmark_award_bounty::<Test>());
+			assert_ok!(test_benchmark_claim_bounty::<Test>());
+			assert_ok!(test_benchmark_close_bounty_proposed::<Test>());
+			assert_ok!(test_benchmark_close_bounty_active::<Test>());
+			assert_ok!(test_benchmark_extend_bounty_expiry::<Test>());
 			assert_ok!(test_benchmark_on_initialize::<Test>());
 		});
 	}
@@ -43,6 +43,22 @@
 //! countdown period, the median of all declared tips is paid to the reported beneficiary, along
 //! with any finders fee, in case of a public (and bonded) original report.
 //!
+//! ### Bounties
+//!
+//! A bounty is a reward for completing a specified body of work, or a specified set of objectives,
+//! that needs to be executed for a predefined treasury amount to be paid out. A curator is assigned
+//! after the bounty is approved and funded by the council, to be delegated with the responsibility
+//! of assigning a payout address once the specified set of objectives is completed.
+//!
+//! After the council has activated a bounty, it delegates the work that requires expertise to a
+//! curator in exchange for a deposit. Once the curator accepts the bounty, they get to close the
+//! active bounty. Closing the active bounty enacts a delayed payout to the payout address, the
+//! curator fee and the return of the curator deposit. The delay allows for intervention through
+//! regular democracy. The curator has to update the bounty within `BountyUpdatePeriod` or anyone
+//! may unassign them, forfeiting their deposit.
+//!
+//! Splitting a bounty into child bounties, each with a curator of its own, is not supported.
+//!
 //! ### Terminology
 //!
 //! - **Proposal:** A suggestion to allocate funds from the pot to a beneficiary.
@@ -63,6 +79,23 @@
 //! - **Finders Fee:** Some proportion of the tip amount that is paid to the reporter of the tip,
 //!   rather than the main beneficiary.
 //!
+//! Bounty protocol:
+//! - **Bounty spending proposal:** A proposal to reward a predefined body of work upon completion by
+//!   the treasury.
+//! - **Proposer:** An account proposing a bounty spending.
+//! - **Curator:** An account managing the bounty and assigning a payout address receiving the
+//!   reward for the completion of work.
+//! - **Deposit:** The amount held on deposit for placing a bounty proposal plus the amount held on
+//!   deposit per byte within the bounty description.
+//! - **Curator deposit:** The payment from a candidate willing to curate an approved bounty. The
+//!   deposit is returned when/if the bounty is completed.
+//! - **Bounty value:** The total amount that should be paid to the payout address if the bounty
+//!   is rewarded.
+//! - **Payout address:** The account to which the total or part of the bounty is assigned to.
+//! - **Payout delay:** The delay period for which a bounty beneficiary needs to wait before
+//!   claiming.
+//! - **Curator fee:** The reserved upfront payment for a curator for work related to the bounty.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
@@ -81,6 +114,19 @@
 //! - `tip` - Declare or redeclare an amount to tip for a particular reason.
 //! - `close_tip` - Close and pay out a tip.
 //!
+//! Bounty protocol:
+//! - `propose_bounty` - Propose a specific treasury amount to be earmarked for a predefined set of
+//!   tasks and stake the required deposit.
+//! - `approve_bounty` - Accept a specific treasury amount to be earmarked for a predefined body of
+//!   work.
+//! - `propose_curator` - Assign an account to a bounty as candidate curator.
+//! - `accept_curator` - Accept a bounty assignment from the council, setting a curator deposit.
+//! - `extend_bounty_expiry` - Extend the expiry block number of the bounty and stay active.
+//! - `award_bounty` - Close and pay out the specified amount for the completed work.
+//! - `claim_bounty` - Claim a specific bounty amount from the payout address.
+//! - `unassign_curator` - Unassign an accepted curator from a specific earmark.
+//! - `close_bounty` - Cancel the earmark for a specific treasury amount and close the bounty.
+//!
 //! ## GenesisConfig
 //!
 //! The Treasury module depends on the [`GenesisConfig`](./struct.GenesisConfig.html).
@@ -91,14 +137,18 @@
 use serde::{Serialize, Deserialize};
 use sp_std::prelude::*;
 use frame_support::{decl_module, decl_storage, decl_event, ensure, print, decl_error, Parameter};
+use frame_support::metadata::TypeInfo;
+use frame_support::dispatch::DispatchResult;
 use frame_support::traits::{
-	Currency, Get, Imbalance, OnUnbalanced, ExistenceRequirement::KeepAlive,
+	Currency, Get, Imbalance, OnUnbalanced, ExistenceRequirement::{KeepAlive, AllowDeath},
 	ReservableCurrency, WithdrawReason
 };
 use sp_runtime::{Permill, ModuleId, Percent, RuntimeDebug, traits::{
 	Zero, StaticLookup, AccountIdConversion, Saturating, Hash, BadOrigin
 }};
-use frame_support::weights::{Weight, WeighData, SimpleDispatchInfo};
+use frame_support::weights::{
+	Weight, WeighData, SimpleDispatchInfo, FunctionOf, DispatchClass, ROCKS_DB_WEIGHT,
+};
 use frame_support::traits::{Contains, EnsureOrigin};
 use codec::{Encode, Decode};
 use frame_system::{self as system, ensure_signed, ensure_root};
@@ -113,6 +163,9 @@ type NegativeImbalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_sy
 /// The treasury's module id, used for deriving its sovereign account ID.
 const MODULE_ID: ModuleId = ModuleId(*b"py/trsry");
 
+/// The maximum length of a tip reason or a bounty description.
+const MAX_SENSIBLE_REASON_LENGTH: usize = 16384;
+
 pub trait Trait: frame_system::Trait {
 	/// The staking balance.
 	type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
@@ -156,6 +209,25 @@ pub trait Trait: frame_system::Trait {
 
 	/// Percentage of spare funds (if any) that are burnt per spend period.
 	type Burn: Get<Permill>;
+
+	/// The amount held on deposit for placing a bounty proposal.
+	type BountyDepositBase: Get<BalanceOf<Self>>;
+
+	/// The amount held on deposit per byte within the bounty description.
+	type BountyDepositPerByte: Get<BalanceOf<Self>>;
+
+	/// The delay period for which a bounty beneficiary needs to wait before claiming the payout.
+	type BountyDepositPayoutDelay: Get<Self::BlockNumber>;
+
+	/// The period within which an active bounty must be updated by its curator.
+	type BountyUpdatePeriod: Get<Self::BlockNumber>;
+
+	/// Percentage of the curator fee that will be reserved upfront as deposit for bounty curator.
+	type BountyCuratorDeposit: Get<Permill>;
+
+	/// Minimum value for a bounty. As a funded bounty is held in an account of its own, this
+	/// should be at least the existential deposit.
+	type BountyValueMinimum: Get<BalanceOf<Self>>;
 }
 
 /// An index of a proposal. Just a `u32`.
@@ -163,7 +235,7 @@ pub type ProposalIndex = u32;
 
 /// A spending proposal.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub struct Proposal<AccountId, Balance> {
 	/// The account proposing it.
 	proposer: AccountId,
@@ -175,9 +247,62 @@ pub struct Proposal<AccountId, Balance> {
 	bond: Balance,
 }
 
+/// An index of a bounty. Just a `u32`.
+pub type BountyIndex = u32;
+
+/// A bounty proposal.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct Bounty<AccountId, Balance, BlockNumber> {
+	/// The account proposing it.
+	proposer: AccountId,
+	/// The (total) amount that should be paid if the bounty is rewarded.
+	value: Balance,
+	/// The curator fee. Included in value.
+	fee: Balance,
+	/// The deposit of curator.
+	curator_deposit: Balance,
+	/// The amount held on deposit (reserved) for making this proposal.
+	bond: Balance,
+	/// The status of this bounty.
+	status: BountyStatus<AccountId, BlockNumber>,
+}
+
+/// The status of a bounty proposal.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum BountyStatus<AccountId, BlockNumber> {
+	/// The bounty is proposed and waiting for approval.
+	Proposed,
+	/// The bounty is approved and waiting to become active at next spend period.
+	Approved,
+	/// The bounty is funded and waiting for curator assignment.
+	Funded,
+	/// A curator has been proposed by the `ApproveOrigin`. Waiting for acceptance from the
+	/// curator.
+	CuratorProposed {
+		/// The assigned curator of this bounty.
+		curator: AccountId,
+	},
+	/// The bounty is active and waiting to be awarded.
+	Active {
+		/// The curator of this bounty.
+		curator: AccountId,
+		/// An update from the curator is due by this block, else they are considered inactive.
+		update_due: BlockNumber,
+	},
+	/// The bounty is awarded and waiting to be released after a delay.
+	PendingPayout {
+		/// The curator of this bounty.
+		curator: AccountId,
+		/// The beneficiary of the bounty.
+		beneficiary: AccountId,
+		/// When the bounty can be claimed.
+		unlock_at: BlockNumber,
+	},
+}
+
 /// An open tipping "motion". Retains all details of a tip including information on the finder
 /// and the members who have voted.
-#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct OpenTip<
 	AccountId: Parameter,
 	Balance: Parameter,
@@ -221,6 +346,22 @@ decl_storage! {
 		/// Simple preimage lookup from the reason's hash to the original data. Again, has an
 		/// insecure enumerable hash since the key is guaranteed to be the result of a secure hash.
 		pub Reasons get(fn reasons): map hasher(identity) T::Hash => Option<Vec<u8>>;
+
+		/// Number of bounty proposals that have been made.
+		pub BountyCount get(fn bounty_count): BountyIndex;
+
+		/// Bounties that have been made.
+		pub Bounties get(fn bounties):
+			map hasher(twox_64_concat) BountyIndex
+			=> Option<Bounty<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+
+		/// The description of each bounty.
+		pub BountyDescriptions get(fn bounty_descriptions):
+			map hasher(twox_64_concat) BountyIndex
+			=> Option<Vec<u8>>;
+
+		/// Bounty indices that have been approved but not yet funded.
+		pub BountyApprovals get(fn bounty_approvals): Vec<BountyIndex>;
 	}
 	add_extra_genesis {
 		build(|_config| {
@@ -262,6 +403,26 @@ decl_event!(
 		TipClosed(Hash, AccountId, Balance),
 		/// A tip suggestion has been retracted.
 		TipRetracted(Hash),
+		/// New bounty proposal.
+		BountyProposed(BountyIndex),
+		/// A bounty proposal was rejected; funds were slashed.
+		BountyRejected(BountyIndex, Balance),
+		/// A bounty proposal is funded and became active.
+		BountyBecameActive(BountyIndex),
+		/// A curator has been proposed for a bounty.
+		BountyCuratorProposed(BountyIndex, AccountId),
+		/// The curator of a bounty has been unassigned.
+		BountyCuratorUnassigned(BountyIndex),
+		/// A curator has accepted a bounty.
+		BountyCuratorAccepted(BountyIndex, AccountId),
+		/// A bounty is awarded to a beneficiary.
+		BountyAwarded(BountyIndex, AccountId),
+		/// A bounty is claimed by beneficiary.
+		BountyClaimed(BountyIndex, Balance, AccountId),
+		/// A bounty is cancelled.
+		BountyCanceled(BountyIndex),
+		/// A bounty expiry is extended.
+		BountyExtended(BountyIndex),
 	}
 );
 
@@ -270,7 +431,7 @@ decl_error! {
 	pub enum Error for Module<T: Trait> {
 		/// Proposer's balance is too low.
 		InsufficientProposersBalance,
-		/// No proposal at that index.
+		/// No proposal or bounty at that index.
 		InvalidProposalIndex,
 		/// The reason given is just too big.
 		ReasonTooBig,
@@ -282,8 +443,20 @@ decl_error! {
 		NotFinder,
 		/// The tip cannot be claimed/closed because there are not enough tippers yet.
 		StillOpen,
-		/// The tip cannot be claimed/closed because it's still in the countdown period.
+		/// The tip cannot be claimed/closed because it's still in the countdown period, or the
+		/// bounty cannot be claimed or its curator unassigned yet.
 		Premature,
+		/// The bounty value is too low.
+		InvalidValue,
+		/// Invalid bounty fee.
+		InvalidFee,
+		/// The bounty status is unexpected.
+		UnexpectedStatus,
+		/// Require bounty curator.
+		RequireCurator,
+		/// A bounty payout is pending.
+		/// To cancel the bounty, you must unassign and slash the curator.
+		PendingPayout,
 	}
 }
 
@@ -314,6 +487,24 @@ decl_module! {
 		/// The amount held on deposit per byte within the tip report reason.
 		const TipReportDepositPerByte: BalanceOf<T> = T::TipReportDepositPerByte::get();
 
+		/// The amount held on deposit for placing a bounty proposal.
+		const BountyDepositBase: BalanceOf<T> = T::BountyDepositBase::get();
+
+		/// The amount held on deposit per byte within the bounty description.
+		const BountyDepositPerByte: BalanceOf<T> = T::BountyDepositPerByte::get();
+
+		/// The delay period for which a bounty beneficiary needs to wait before claiming the payout.
+		const BountyDepositPayoutDelay: T::BlockNumber = T::BountyDepositPayoutDelay::get();
+
+		/// The period within which an active bounty must be updated by its curator.
+		const BountyUpdatePeriod: T::BlockNumber = T::BountyUpdatePeriod::get();
+
+		/// Percentage of the curator fee that will be reserved upfront as deposit for bounty curator.
+		const BountyCuratorDeposit: Permill = T::BountyCuratorDeposit::get();
+
+		/// Minimum value for a bounty.
+		const BountyValueMinimum: BalanceOf<T> = T::BountyValueMinimum::get();
+
 		type Error = Error<T>;
 
 		fn deposit_event() = default;
@@ -409,7 +600,6 @@ decl_module! {
 		fn report_awesome(origin, reason: Vec<u8>, who: T::AccountId) {
 			let finder = ensure_signed(origin)?;
 
-			const MAX_SENSIBLE_REASON_LENGTH: usize = 16384;
 			ensure!(reason.len() <= MAX_SENSIBLE_REASON_LENGTH, Error::<T>::ReasonTooBig);
 
 			let reason_hash = T::Hashing::hash(&reason[..]);
@@ -552,13 +742,482 @@ decl_module! {
 			Self::payout_tip(tip);
 		}
 
+		/// Propose a new bounty.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// Payment: `BountyDepositBase` will be reserved from the origin account, as well as
+		/// `BountyDepositPerByte` for each byte in `description`. It will be unreserved upon
+		/// approval, or slashed when rejected.
+		///
+		/// - `value`: The total payment amount of this bounty, curator fee included.
+		/// - `description`: The description of this bounty.
+		///
+		/// Emits `BountyProposed` if successful.
+		///
+		/// # <weight>
+		/// - `O(D)` where `D` is the length of `description`.
+		/// - One balance operation.
+		/// - Two storage insertions (codec `O(D)`) and one mutation.
+		/// - One event.
+		/// - 100,000 + 1,000 per byte of `description`.
+		/// # </weight>
+		#[weight = FunctionOf(
+			|args: (&BalanceOf<T>, &Vec<u8>)| 100_000u32.saturating_add(
+				1_000u32.saturating_mul(args.1.len() as Weight)
+			),
+			DispatchClass::Normal,
+			true
+		)]
+		fn propose_bounty(
+			origin,
+			#[compact] value: BalanceOf<T>,
+			description: Vec<u8>,
+		) {
+			let proposer = ensure_signed(origin)?;
+
+			ensure!(description.len() <= MAX_SENSIBLE_REASON_LENGTH, Error::<T>::ReasonTooBig);
+			ensure!(value >= T::BountyValueMinimum::get(), Error::<T>::InvalidValue);
+
+			let bond = T::BountyDepositBase::get()
+				+ T::BountyDepositPerByte::get() * (description.len() as u32).into();
+			T::Currency::reserve(&proposer, bond)
+				.map_err(|_| Error::<T>::InsufficientProposersBalance)?;
+
+			let index = Self::bounty_count();
+			BountyCount::put(index + 1);
+
+			let bounty = Bounty {
+				proposer,
+				value,
+				fee: Zero::zero(),
+				curator_deposit: Zero::zero(),
+				bond,
+				status: BountyStatus::Proposed,
+			};
+			Bounties::<T>::insert(index, &bounty);
+			BountyDescriptions::insert(index, description);
+
+			Self::deposit_event(RawEvent::BountyProposed(index));
+		}
+
+		/// Approve a bounty proposal. At a later time, the bounty will be funded and become active
+		/// and the original deposit will be returned.
+		///
+		/// The dispatch origin for this call must be `ApproveOrigin` or _Root_.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Limited storage reads.
+		/// - Two DB changes.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedOperational(100_000)]
+		fn approve_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			T::ApproveOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidProposalIndex)?;
+				ensure!(bounty.status == BountyStatus::Proposed, Error::<T>::UnexpectedStatus);
+
+				bounty.status = BountyStatus::Approved;
+				BountyApprovals::mutate(|v| v.push(bounty_id));
+
+				Ok(())
+			})?;
+		}
+
+		/// Assign a curator to a funded bounty.
+		///
+		/// The dispatch origin for this call must be `ApproveOrigin` or _Root_.
+		///
+		/// - `curator`: The account proposed to curate the bounty.
+		/// - `fee`: The fee paid to the curator out of the bounty value once it is claimed. Must
+		///   be lower than the bounty value.
+		///
+		/// Emits `BountyCuratorProposed` if successful.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Up to one account lookup.
+		/// - One storage mutation.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedOperational(100_000)]
+		fn propose_curator(
+			origin,
+			#[compact] bounty_id: BountyIndex,
+			curator: <T::Lookup as StaticLookup>::Source,
+			#[compact] fee: BalanceOf<T>,
+		) {
+			T::ApproveOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			let curator = T::Lookup::lookup(curator)?;
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidProposalIndex)?;
+				match bounty.status {
+					BountyStatus::Funded => {},
+					_ => return Err(Error::<T>::UnexpectedStatus.into()),
+				};
+
+				ensure!(fee < bounty.value, Error::<T>::InvalidFee);
+
+				bounty.status = BountyStatus::CuratorProposed { curator: curator.clone() };
+				bounty.fee = fee;
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::BountyCuratorProposed(bounty_id, curator));
+		}
+
+		/// Unassign curator from a bounty.
+		///
+		/// This function can only be called by the `RejectOrigin` or _Root_, or a signed origin.
+		///
+		/// If this function is called by the `RejectOrigin` or _Root_, we assume that the curator
+		/// is malicious or inactive. As a result, we will slash the curator when possible.
+		///
+		/// If the origin is the curator, we take this as a sign they are unable to do their job
+		/// and they willingly give up. We could slash them, but for now we allow them to recover
+		/// their deposit and exit without issue. (We may want to change this if it is abused.)
+		///
+		/// Finally, the origin can be anyone if and only if the curator is "inactive". This allows
+		/// anyone in the community to call out that a curator is not doing their due diligence,
+		/// and we should pick a new curator. In this case the curator should also be slashed.
+		///
+		/// Emits `BountyCuratorUnassigned` if successful.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Up to one balance operation.
+		/// - One storage mutation.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		fn unassign_curator(origin, #[compact] bounty_id: BountyIndex) {
+			let maybe_sender = ensure_signed(origin.clone())
+				.map(Some)
+				.or_else(|_| T::RejectOrigin::try_origin(origin)
+					.map(|_| None)
+					.or_else(|origin| ensure_root(origin).map(|_| None))
+				)?;
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidProposalIndex)?;
+
+				let slash_curator = |curator: &T::AccountId, curator_deposit: &mut BalanceOf<T>| {
+					let imbalance = T::Currency::slash_reserved(curator, *curator_deposit).0;
+					T::ProposalRejection::on_unbalanced(imbalance);
+					*curator_deposit = Zero::zero();
+				};
+
+				match bounty.status {
+					BountyStatus::Proposed | BountyStatus::Approved | BountyStatus::Funded => {
+						// No curator to unassign at this point.
+						return Err(Error::<T>::UnexpectedStatus.into())
+					},
+					BountyStatus::CuratorProposed { ref curator } => {
+						// A curator has been proposed, but not accepted yet.
+						// Either `RejectOrigin` or the proposed curator can unassign the curator.
+						ensure!(maybe_sender.map_or(true, |sender| sender == *curator), BadOrigin);
+					},
+					BountyStatus::Active { ref curator, ref update_due } => {
+						// The bounty is active.
+						match maybe_sender {
+							// If the `RejectOrigin` is calling this function, slash the curator.
+							None => slash_curator(curator, &mut bounty.curator_deposit),
+							Some(sender) => {
+								// If the sender is not the curator, and the curator is inactive,
+								// slash the curator.
+								if sender != *curator {
+									let block_number = system::Module::<T>::block_number();
+									ensure!(*update_due < block_number, Error::<T>::Premature);
+									slash_curator(curator, &mut bounty.curator_deposit);
+								} else {
+									// Else this is the curator, willingly giving up their role.
+									// Give back their deposit.
+									let _ = T::Currency::unreserve(curator, bounty.curator_deposit);
+									bounty.curator_deposit = Zero::zero();
+								}
+							},
+						}
+					},
+					BountyStatus::PendingPayout { ref curator, .. } => {
+						// The bounty is pending payout, so only the `RejectOrigin` can unassign a
+						// curator. By doing so, they are claiming the curator is acting maliciously,
+						// so we slash the curator.
+						ensure!(maybe_sender.is_none(), BadOrigin);
+						slash_curator(curator, &mut bounty.curator_deposit);
+					},
+				};
+
+				bounty.status = BountyStatus::Funded;
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::BountyCuratorUnassigned(bounty_id));
+		}
+
+		/// Accept the curator role for a bounty.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the signing account must be the
+		/// curator proposed for this bounty.
+		///
+		/// Payment: `BountyCuratorDeposit` of the curator fee will be reserved from the origin
+		/// account. It is returned once the bounty is claimed, and may be slashed if the curator
+		/// is unassigned by `RejectOrigin` or for being inactive.
+		///
+		/// Emits `BountyCuratorAccepted` if successful.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One balance operation.
+		/// - One storage mutation.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		fn accept_curator(origin, #[compact] bounty_id: BountyIndex) {
+			let signer = ensure_signed(origin)?;
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidProposalIndex)?;
+
+				match bounty.status {
+					BountyStatus::CuratorProposed { ref curator } => {
+						ensure!(signer == *curator, Error::<T>::RequireCurator);
+					},
+					_ => return Err(Error::<T>::UnexpectedStatus.into()),
+				};
+
+				let deposit = T::BountyCuratorDeposit::get() * bounty.fee;
+				T::Currency::reserve(&signer, deposit)?;
+				bounty.curator_deposit = deposit;
+
+				let update_due = system::Module::<T>::block_number() + T::BountyUpdatePeriod::get();
+				bounty.status = BountyStatus::Active { curator: signer.clone(), update_due };
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::BountyCuratorAccepted(bounty_id, signer));
+		}
+
+		/// Award a bounty to a beneficiary account. The beneficiary will be able to claim the funds
+		/// after a delay.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the signing account must be the
+		/// curator of this bounty.
+		///
+		/// - `bounty_id`: Bounty ID to award.
+		/// - `beneficiary`: The beneficiary account whom will receive the payout.
+		///
+		/// Emits `BountyAwarded` if successful.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Up to one account lookup.
+		/// - One storage mutation.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		fn award_bounty(
+			origin,
+			#[compact] bounty_id: BountyIndex,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+		) {
+			let signer = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidProposalIndex)?;
+				match &bounty.status {
+					BountyStatus::Active { curator, .. } => {
+						ensure!(signer == *curator, Error::<T>::RequireCurator);
+					},
+					_ => return Err(Error::<T>::UnexpectedStatus.into()),
+				}
+				bounty.status = BountyStatus::PendingPayout {
+					curator: signer,
+					beneficiary: beneficiary.clone(),
+					unlock_at: system::Module::<T>::block_number() + T::BountyDepositPayoutDelay::get(),
+				};
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::BountyAwarded(bounty_id, beneficiary));
+		}
+
+		/// Claim the payout from an awarded bounty after the payout delay.
+		///
+		/// The dispatch origin for this call must be _Signed_; anyone may trigger the payout.
+		///
+		/// The curator fee is paid to the curator along with the return of their deposit, and the
+		/// rest of the bounty funds to the beneficiary.
+		///
+		/// - `bounty_id`: Bounty ID to claim.
+		///
+		/// Emits `BountyClaimed` if successful.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Up to three balance operations.
+		/// - Two storage removals.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		fn claim_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			ensure_signed(origin)?;
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.take().ok_or(Error::<T>::InvalidProposalIndex)?;
+				if let BountyStatus::PendingPayout { curator, beneficiary, unlock_at } = bounty.status {
+					ensure!(system::Module::<T>::block_number() >= unlock_at, Error::<T>::Premature);
+
+					let bounty_account = Self::bounty_account_id(bounty_id);
+					let balance = T::Currency::free_balance(&bounty_account);
+					// just to be safe.
+					let fee = bounty.fee.min(balance);
+					let payout = balance.saturating_sub(fee);
+
+					let _ = T::Currency::unreserve(&curator, bounty.curator_deposit);
+					// these should go through given they are at most the free balance of the
+					// bounty account, but still we only make a best-effort.
+					let _ = T::Currency::transfer(&bounty_account, &curator, fee, AllowDeath);
+					let _ = T::Currency::transfer(&bounty_account, &beneficiary, payout, AllowDeath);
+					BountyDescriptions::remove(bounty_id);
+
+					Self::deposit_event(RawEvent::BountyClaimed(bounty_id, payout, beneficiary));
+					Ok(())
+				} else {
+					Err(Error::<T>::UnexpectedStatus.into())
+				}
+			})?;
+		}
+
+		/// Cancel a proposed or active bounty. All the funds will be sent to treasury and
+		/// the curator deposit will be unreserved if possible.
+		///
+		/// Only `RejectOrigin` or _Root_ is able to cancel a bounty. A bounty that has been
+		/// approved but not funded yet cannot be cancelled, nor can one pending payout.
+		///
+		/// - `bounty_id`: Bounty ID to cancel.
+		///
+		/// Emits `BountyRejected` for a bounty proposal and `BountyCanceled` otherwise.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - Up to two balance operations.
+		/// - Two storage removals.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedOperational(100_000)]
+		fn close_bounty(origin, #[compact] bounty_id: BountyIndex) {
+			T::RejectOrigin::try_origin(origin)
+				.map(|_| ())
+				.or_else(ensure_root)?;
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.take().ok_or(Error::<T>::InvalidProposalIndex)?;
+
+				match bounty.status {
+					BountyStatus::Proposed => {
+						// The reject origin would like to cancel a proposed bounty.
+						BountyDescriptions::remove(bounty_id);
+						let value = bounty.bond;
+						let imbalance = T::Currency::slash_reserved(&bounty.proposer, value).0;
+						T::ProposalRejection::on_unbalanced(imbalance);
+
+						Self::deposit_event(RawEvent::BountyRejected(bounty_id, value));
+						return Ok(())
+					},
+					BountyStatus::Approved => {
+						// The bounty is about to be funded; it can be cancelled once it is.
+						return Err(Error::<T>::UnexpectedStatus.into())
+					},
+					BountyStatus::Funded | BountyStatus::CuratorProposed { .. } => {
+						// Nothing extra to do besides returning the funds below.
+					},
+					BountyStatus::Active { curator, .. } => {
+						// Cancelled by the council, refund the deposit as the curator is not at
+						// fault.
+						let _ = T::Currency::unreserve(&curator, bounty.curator_deposit);
+					},
+					BountyStatus::PendingPayout { .. } => {
+						// The bounty is awarded; the curator must be unassigned and slashed
+						// through `unassign_curator` to revert the award before cancelling.
+						return Err(Error::<T>::PendingPayout.into())
+					},
+				}
+
+				let bounty_account = Self::bounty_account_id(bounty_id);
+				BountyDescriptions::remove(bounty_id);
+
+				let balance = T::Currency::free_balance(&bounty_account);
+				// should not fail, but we only make a best-effort.
+				let _ = T::Currency::transfer(&bounty_account, &Self::account_id(), balance, AllowDeath);
+
+				Self::deposit_event(RawEvent::BountyCanceled(bounty_id));
+				Ok(())
+			})?;
+		}
+
+		/// Extend the expiry time of an active bounty.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the signing account must be the
+		/// curator of this bounty.
+		///
+		/// - `bounty_id`: Bounty ID to extend.
+		/// - `_remark`: Additional information about the progress of the bounty.
+		///
+		/// Emits `BountyExtended` if successful.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// - One storage mutation.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		fn extend_bounty_expiry(origin, #[compact] bounty_id: BountyIndex, _remark: Vec<u8>) {
+			let signer = ensure_signed(origin)?;
+
+			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
+				let bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidProposalIndex)?;
+
+				match bounty.status {
+					BountyStatus::Active { ref curator, ref mut update_due } => {
+						ensure!(*curator == signer, Error::<T>::RequireCurator);
+						*update_due = (system::Module::<T>::block_number() + T::BountyUpdatePeriod::get())
+							.max(*update_due);
+					},
+					_ => return Err(Error::<T>::UnexpectedStatus.into()),
+				}
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(RawEvent::BountyExtended(bounty_id));
+		}
+
 		fn on_initialize(n: T::BlockNumber) -> Weight {
 			// Check to see if we should spend some funds!
-			if (n % T::SpendPeriod::get()).is_zero() {
-				Self::spend_funds();
-			}
-
-			SimpleDispatchInfo::default().weigh_data(())
+			let spend_weight = if (n % T::SpendPeriod::get()).is_zero() {
+				let approvals = Self::spend_funds();
+				// Every approval reads its proposal or bounty and, if it is funded, writes it and the
+				// balances of the proposer and the beneficiary. The pot and both lists of approvals
+				// are read and written once.
+				ROCKS_DB_WEIGHT.reads_writes(3, 3)
+					.saturating_mul(approvals)
+					.saturating_add(ROCKS_DB_WEIGHT.reads_writes(3, 3))
+			} else {
+				0
+			};
+
+			SimpleDispatchInfo::default().weigh_data(()).saturating_add(spend_weight)
 		}
 	}
 }
@@ -574,6 +1233,13 @@ impl<T: Trait> Module<T> {
 		MODULE_ID.into_account()
 	}
 
+	/// The account ID of a bounty account.
+	pub fn bounty_account_id(id: BountyIndex) -> T::AccountId {
+		// only use a two byte prefix to support 16 byte account ids (used by the tests):
+		// "modl" ++ "py/trsry" ++ "bt" is 14 bytes, leaving two bytes for the bounty index.
+		MODULE_ID.into_sub_account((b"bt", id))
+	}
+
 	/// The needed bond for a proposal whose spend is `value`.
 	fn calculate_bond(value: BalanceOf<T>) -> BalanceOf<T> {
 		T::ProposalBondMinimum::get().max(T::ProposalBond::get() * value)
@@ -649,15 +1315,17 @@ impl<T: Trait> Module<T> {
 		let _ = T::Currency::transfer(&treasury, &tip.who, payout, KeepAlive);
 	}
 
-	// Spend some money!
-	fn spend_funds() {
+	// Spend some money! Returns the number of approved proposals and bounties that were processed.
+	fn spend_funds() -> Weight {
 		let mut budget_remaining = Self::pot();
 		Self::deposit_event(RawEvent::Spending(budget_remaining));
 
+		let mut approvals: Weight = 0;
 		let mut missed_any = false;
 		let mut imbalance = <PositiveImbalanceOf<T>>::zero();
 		Approvals::mutate(|v| {
 			v.retain(|&index| {
+				approvals = approvals.saturating_add(1);
 				// Should always be true, but shouldn't panic if false or we're screwed.
 				if let Some(p) = Self::proposals(index) {
 					if p.value <= budget_remaining {
@@ -682,6 +1350,39 @@ impl<T: Trait> Module<T> {
 			});
 		});
 
+		BountyApprovals::mutate(|v| {
+			v.retain(|&index| {
+				approvals = approvals.saturating_add(1);
+				Bounties::<T>::mutate(index, |bounty| {
+					// Should always be true, but shouldn't panic if false or we're screwed.
+					if let Some(bounty) = bounty {
+						if bounty.value <= budget_remaining {
+							budget_remaining -= bounty.value;
+
+							bounty.status = BountyStatus::Funded;
+
+							// return their deposit.
+							let _ = T::Currency::unreserve(&bounty.proposer, bounty.bond);
+
+							// fund the bounty account.
+							imbalance.subsume(T::Currency::deposit_creating(
+								&Self::bounty_account_id(index),
+								bounty.value,
+							));
+
+							Self::deposit_event(RawEvent::BountyBecameActive(index));
+							false
+						} else {
+							missed_any = true;
+							true
+						}
+					} else {
+						false
+					}
+				})
+			});
+		});
+
 		if !missed_any {
 			// burn some proportion of the remaining budget if we run a surplus.
 			let burn = (T::Burn::get() * budget_remaining).min(budget_remaining);
@@ -706,6 +1407,8 @@ impl<T: Trait> Module<T> {
 		}
 
 		Self::deposit_event(RawEvent::Rollover(budget_remaining));
+
+		approvals
 	}
 
 	/// Return the amount of money in the pot.
@@ -50,7 +50,7 @@ impl frame_system::Trait for Test {
 	type Call = ();
 	type Hash = H256;
 	type Hashing = BlakeTwo256;
-	type AccountId = u64;
+	type AccountId = u128;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
 	type Event = ();
@@ -75,17 +75,17 @@ impl pallet_balances::Trait for Test {
 	type AccountStore = System;
 }
 thread_local! {
-	static TEN_TO_FOURTEEN: RefCell<Vec<u64>> = RefCell::new(vec![10,11,12,13,14]);
+	static TEN_TO_FOURTEEN: RefCell<Vec<u128>> = RefCell::new(vec![10,11,12,13,14]);
 }
 pub struct TenToFourteen;
-impl Contains<u64> for TenToFourteen {
-	fn sorted_members() -> Vec<u64> {
+impl Contains<u128> for TenToFourteen {
+	fn sorted_members() -> Vec<u128> {
 		TEN_TO_FOURTEEN.with(|v| {
 			v.borrow().clone()
 		})
 	}
 	#[cfg(feature = "runtime-benchmarks")]
-	fn add(new: &u64) {
+	fn add(new: &u128) {
 		TEN_TO_FOURTEEN.with(|v| {
 			let mut members = v.borrow_mut();
 			members.push(*new);
@@ -102,11 +102,17 @@ parameter_types! {
 	pub const TipFindersFee: Percent = Percent::from_percent(20);
 	pub const TipReportDepositBase: u64 = 1;
 	pub const TipReportDepositPerByte: u64 = 1;
+	pub const BountyDepositBase: u64 = 80;
+	pub const BountyDepositPerByte: u64 = 1;
+	pub const BountyDepositPayoutDelay: u64 = 3;
+	pub const BountyUpdatePeriod: u64 = 20;
+	pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+	pub const BountyValueMinimum: u64 = 1;
 }
 impl Trait for Test {
 	type Currency = pallet_balances::Module<Test>;
-	type ApproveOrigin = frame_system::EnsureRoot<u64>;
-	type RejectOrigin = frame_system::EnsureRoot<u64>;
+	type ApproveOrigin = frame_system::EnsureRoot<u128>;
+	type RejectOrigin = frame_system::EnsureRoot<u128>;
 	type Tippers = TenToFourteen;
 	type TipCountdown = TipCountdown;
 	type TipFindersFee = TipFindersFee;
@@ -118,6 +124,12 @@ impl Trait for Test {
 	type ProposalBondMinimum = ProposalBondMinimum;
 	type SpendPeriod = SpendPeriod;
 	type Burn = Burn;
+	type BountyDepositBase = BountyDepositBase;
+	type BountyDepositPerByte = BountyDepositPerByte;
+	type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+	type BountyUpdatePeriod = BountyUpdatePeriod;
+	type BountyCuratorDeposit = BountyCuratorDeposit;
+	type BountyValueMinimum = BountyValueMinimum;
 }
 type System = frame_system::Module<Test>;
 type Balances = pallet_balances::Module<Test>;
@@ -142,7 +154,7 @@ fn genesis_config_works() {
 }
 
 fn tip_hash() -> H256 {
-	BlakeTwo256::hash_of(&(BlakeTwo256::hash(b"awesome.dot"), 3u64))
+	BlakeTwo256::hash_of(&(BlakeTwo256::hash(b"awesome.dot"), 3u128))
 }
 
 #[test]
@@ -191,7 +203,7 @@ fn report_awesome_from_beneficiary_and_tip_works() {
 		assert_ok!(Treasury::report_awesome(Origin::signed(0), b"awesome.dot".to_vec(), 0));
 		assert_eq!(Balances::reserved_balance(0), 12);
 		assert_eq!(Balances::free_balance(0), 88);
-		let h = BlakeTwo256::hash_of(&(BlakeTwo256::hash(b"awesome.dot"), 0u64));
+		let h = BlakeTwo256::hash_of(&(BlakeTwo256::hash(b"awesome.dot"), 0u128));
 		assert_ok!(Treasury::tip(Origin::signed(10), h.clone(), 10));
 		assert_ok!(Treasury::tip(Origin::signed(11), h.clone(), 10));
 		assert_ok!(Treasury::tip(Origin::signed(12), h.clone(), 10));
@@ -479,3 +491,429 @@ fn inexistent_account_works() {
 		assert_eq!(Balances::free_balance(3), 99); // Balance of `3` has changed
 	});
 }
+
+// Create a bounty of 50 proposed by account 0, approve it and fund it from the treasury.
+fn create_funded_bounty() {
+	Balances::make_free_balance_be(&Treasury::account_id(), 101);
+	assert_ok!(Treasury::propose_bounty(Origin::signed(0), 50, b"12345".to_vec()));
+	assert_ok!(Treasury::approve_bounty(Origin::ROOT, 0));
+	<Treasury as OnInitialize<u64>>::on_initialize(2);
+}
+
+#[test]
+fn propose_bounty_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		assert_eq!(Treasury::pot(), 100);
+
+		assert_ok!(Treasury::propose_bounty(Origin::signed(0), 10, b"1234567890".to_vec()));
+
+		let deposit: u64 = 80 + 10;
+		assert_eq!(Balances::reserved_balance(0), deposit);
+		assert_eq!(Balances::free_balance(0), 100 - deposit);
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 0,
+			curator_deposit: 0,
+			value: 10,
+			bond: deposit,
+			status: BountyStatus::Proposed,
+		});
+		assert_eq!(Treasury::bounty_descriptions(0).unwrap(), b"1234567890".to_vec());
+		assert_eq!(Treasury::bounty_count(), 1);
+	});
+}
+
+#[test]
+fn propose_bounty_validation_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		assert_eq!(Treasury::pot(), 100);
+
+		assert_noop!(
+			Treasury::propose_bounty(Origin::signed(1), 0, [0; 16385].to_vec()),
+			Error::<Test>::ReasonTooBig
+		);
+		assert_noop!(
+			Treasury::propose_bounty(Origin::signed(1), 10, b"12345678901234567890".to_vec()),
+			Error::<Test>::InsufficientProposersBalance
+		);
+		assert_noop!(
+			Treasury::propose_bounty(Origin::signed(1), 0, b"12345678901234567890".to_vec()),
+			Error::<Test>::InvalidValue
+		);
+	});
+}
+
+#[test]
+fn close_bounty_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		assert_noop!(Treasury::close_bounty(Origin::ROOT, 0), Error::<Test>::InvalidProposalIndex);
+
+		assert_ok!(Treasury::propose_bounty(Origin::signed(0), 10, b"12345".to_vec()));
+
+		assert_noop!(Treasury::close_bounty(Origin::signed(0), 0), BadOrigin);
+		assert_ok!(Treasury::close_bounty(Origin::ROOT, 0));
+
+		let deposit: u64 = 80 + 5;
+		assert_eq!(Balances::reserved_balance(0), 0);
+		assert_eq!(Balances::free_balance(0), 100 - deposit);
+
+		assert_eq!(Treasury::bounties(0), None);
+		assert!(!Bounties::<Test>::contains_key(0));
+		assert_eq!(Treasury::bounty_descriptions(0), None);
+	});
+}
+
+#[test]
+fn propose_bounty_weight_depends_on_description_length() {
+	use frame_support::weights::GetDispatchInfo;
+
+	let short = Call::<Test>::propose_bounty(10, b"12345".to_vec()).get_dispatch_info();
+	let long = Call::<Test>::propose_bounty(10, b"1234567890".to_vec()).get_dispatch_info();
+	assert_eq!(long.weight - short.weight, 5 * 1_000);
+}
+
+#[test]
+fn spend_funds_weight_counts_approved_bounties() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+
+		let idle_weight = <Treasury as OnInitialize<u64>>::on_initialize(1);
+		let empty_weight = <Treasury as OnInitialize<u64>>::on_initialize(2);
+		assert_eq!(empty_weight, idle_weight + ROCKS_DB_WEIGHT.reads_writes(3, 3));
+
+		assert_ok!(Treasury::propose_bounty(Origin::signed(0), 10, b"1".to_vec()));
+		assert_ok!(Treasury::approve_bounty(Origin::ROOT, 0));
+		assert_ok!(Treasury::propose_bounty(Origin::signed(1), 10, b"2".to_vec()));
+		assert_ok!(Treasury::approve_bounty(Origin::ROOT, 1));
+
+		assert_eq!(
+			<Treasury as OnInitialize<u64>>::on_initialize(4),
+			empty_weight + 2 * ROCKS_DB_WEIGHT.reads_writes(3, 3),
+		);
+		assert_eq!(Treasury::bounty_approvals(), vec![]);
+	});
+}
+
+#[test]
+fn approve_bounty_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+		assert_noop!(Treasury::approve_bounty(Origin::ROOT, 0), Error::<Test>::InvalidProposalIndex);
+
+		assert_ok!(Treasury::propose_bounty(Origin::signed(0), 50, b"12345".to_vec()));
+
+		assert_noop!(Treasury::approve_bounty(Origin::signed(1), 0), BadOrigin);
+		assert_ok!(Treasury::approve_bounty(Origin::ROOT, 0));
+
+		let deposit: u64 = 80 + 5;
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 0,
+			value: 50,
+			curator_deposit: 0,
+			bond: deposit,
+			status: BountyStatus::Approved,
+		});
+		assert_eq!(Treasury::bounty_approvals(), vec![0]);
+
+		assert_noop!(Treasury::approve_bounty(Origin::ROOT, 0), Error::<Test>::UnexpectedStatus);
+		assert_noop!(Treasury::close_bounty(Origin::ROOT, 0), Error::<Test>::UnexpectedStatus);
+
+		// deposit not returned yet
+		assert_eq!(Balances::reserved_balance(0), deposit);
+		assert_eq!(Balances::free_balance(0), 100 - deposit);
+
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+
+		// return deposit
+		assert_eq!(Balances::reserved_balance(0), 0);
+		assert_eq!(Balances::free_balance(0), 100);
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 0,
+			curator_deposit: 0,
+			value: 50,
+			bond: deposit,
+			status: BountyStatus::Funded,
+		});
+		assert_eq!(Treasury::pot(), 100 - 50 - 25); // burn 25
+		assert_eq!(Balances::free_balance(Treasury::bounty_account_id(0)), 50);
+		assert_eq!(Treasury::bounty_approvals(), Vec::<BountyIndex>::new());
+	});
+}
+
+#[test]
+fn approved_bounty_waits_for_enough_funds() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&Treasury::account_id(), 101);
+
+		assert_ok!(Treasury::propose_bounty(Origin::signed(0), 150, b"12345".to_vec()));
+		assert_ok!(Treasury::approve_bounty(Origin::ROOT, 0));
+
+		<Treasury as OnInitialize<u64>>::on_initialize(2);
+		assert_eq!(Treasury::pot(), 100); // Pot hasn't changed
+		assert_eq!(Treasury::bounty_approvals(), vec![0]);
+		assert_eq!(Treasury::bounties(0).unwrap().status, BountyStatus::Approved);
+
+		let _ = Balances::deposit_into_existing(&Treasury::account_id(), 100).unwrap();
+		<Treasury as OnInitialize<u64>>::on_initialize(4);
+		assert_eq!(Treasury::bounties(0).unwrap().status, BountyStatus::Funded);
+		assert_eq!(Balances::free_balance(Treasury::bounty_account_id(0)), 150);
+		assert_eq!(Treasury::pot(), 25);
+	});
+}
+
+#[test]
+fn assign_curator_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			Treasury::propose_curator(Origin::ROOT, 0, 4, 4),
+			Error::<Test>::InvalidProposalIndex
+		);
+
+		create_funded_bounty();
+
+		assert_noop!(Treasury::propose_curator(Origin::signed(1), 0, 4, 4), BadOrigin);
+		assert_noop!(Treasury::propose_curator(Origin::ROOT, 0, 4, 50), Error::<Test>::InvalidFee);
+
+		assert_ok!(Treasury::propose_curator(Origin::ROOT, 0, 4, 4));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 0,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::CuratorProposed {
+				curator: 4,
+			},
+		});
+
+		assert_noop!(Treasury::accept_curator(Origin::signed(1), 0), Error::<Test>::RequireCurator);
+		assert_noop!(
+			Treasury::accept_curator(Origin::signed(4), 0),
+			pallet_balances::Error::<Test, _>::InsufficientBalance
+		);
+
+		Balances::make_free_balance_be(&4, 10);
+
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 2,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::Active {
+				curator: 4,
+				update_due: 21,
+			},
+		});
+
+		assert_eq!(Balances::free_balance(&4), 8);
+		assert_eq!(Balances::reserved_balance(&4), 2);
+	});
+}
+
+#[test]
+fn unassign_curator_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		create_funded_bounty();
+		Balances::make_free_balance_be(&4, 10);
+
+		assert_noop!(Treasury::unassign_curator(Origin::signed(4), 0), Error::<Test>::UnexpectedStatus);
+
+		// A proposed curator can be unassigned by themselves.
+		assert_ok!(Treasury::propose_curator(Origin::ROOT, 0, 4, 4));
+		assert_noop!(Treasury::unassign_curator(Origin::signed(1), 0), BadOrigin);
+		assert_ok!(Treasury::unassign_curator(Origin::signed(4), 0));
+		assert_eq!(Treasury::bounties(0).unwrap().status, BountyStatus::Funded);
+
+		// An active curator giving up gets their deposit back.
+		assert_ok!(Treasury::propose_curator(Origin::ROOT, 0, 4, 4));
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+		assert_noop!(Treasury::unassign_curator(Origin::signed(1), 0), Error::<Test>::Premature);
+		assert_ok!(Treasury::unassign_curator(Origin::signed(4), 0));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 0,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::Funded,
+		});
+		assert_eq!(Balances::free_balance(&4), 10);
+		assert_eq!(Balances::reserved_balance(&4), 0);
+
+		// An active curator unassigned by the reject origin is slashed.
+		assert_ok!(Treasury::propose_curator(Origin::ROOT, 0, 4, 4));
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+		assert_ok!(Treasury::unassign_curator(Origin::ROOT, 0));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 0,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::Funded,
+		});
+		assert_eq!(Balances::free_balance(&4), 8);
+		assert_eq!(Balances::reserved_balance(&4), 0); // slashed 2
+	});
+}
+
+#[test]
+fn award_and_claim_bounty_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		create_funded_bounty();
+		Balances::make_free_balance_be(&4, 10);
+
+		assert_ok!(Treasury::propose_curator(Origin::ROOT, 0, 4, 4));
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+
+		assert_eq!(Balances::free_balance(4), 8); // inital 10 - 2 deposit
+
+		assert_noop!(Treasury::award_bounty(Origin::signed(1), 0, 3), Error::<Test>::RequireCurator);
+
+		assert_ok!(Treasury::award_bounty(Origin::signed(4), 0, 3));
+
+		assert_eq!(Treasury::bounties(0).unwrap(), Bounty {
+			proposer: 0,
+			fee: 4,
+			curator_deposit: 2,
+			value: 50,
+			bond: 85,
+			status: BountyStatus::PendingPayout {
+				curator: 4,
+				beneficiary: 3,
+				unlock_at: 4,
+			},
+		});
+
+		assert_noop!(Treasury::claim_bounty(Origin::signed(1), 0), Error::<Test>::Premature);
+		assert_noop!(Treasury::close_bounty(Origin::ROOT, 0), Error::<Test>::PendingPayout);
+
+		System::set_block_number(4);
+
+		assert_ok!(Treasury::claim_bounty(Origin::signed(1), 0));
+
+		assert_eq!(Balances::free_balance(4), 14); // initial 10 + fee 4
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_eq!(Balances::free_balance(3), 46);
+		assert_eq!(Balances::free_balance(Treasury::bounty_account_id(0)), 0);
+
+		assert_eq!(Treasury::bounties(0), None);
+		assert_eq!(Treasury::bounty_descriptions(0), None);
+	});
+}
+
+#[test]
+fn unassign_curator_reverts_pending_payout() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		create_funded_bounty();
+		Balances::make_free_balance_be(&4, 10);
+
+		assert_ok!(Treasury::propose_curator(Origin::ROOT, 0, 4, 4));
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+		assert_ok!(Treasury::award_bounty(Origin::signed(4), 0, 3));
+
+		// Only the reject origin may step in once the bounty is awarded.
+		assert_noop!(Treasury::unassign_curator(Origin::signed(4), 0), BadOrigin);
+		assert_ok!(Treasury::unassign_curator(Origin::ROOT, 0));
+
+		assert_eq!(Treasury::bounties(0).unwrap().status, BountyStatus::Funded);
+		assert_eq!(Balances::free_balance(4), 8);
+		assert_eq!(Balances::reserved_balance(4), 0); // slashed 2
+
+		System::set_block_number(4);
+		assert_noop!(Treasury::claim_bounty(Origin::signed(1), 0), Error::<Test>::UnexpectedStatus);
+	});
+}
+
+#[test]
+fn close_active_bounty_returns_funds() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		create_funded_bounty();
+		Balances::make_free_balance_be(&4, 10);
+		assert_eq!(Treasury::pot(), 25);
+
+		assert_ok!(Treasury::propose_curator(Origin::ROOT, 0, 4, 4));
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+
+		assert_ok!(Treasury::close_bounty(Origin::ROOT, 0));
+
+		// The curator is not at fault and gets their deposit back.
+		assert_eq!(Balances::free_balance(4), 10);
+		assert_eq!(Balances::reserved_balance(4), 0);
+
+		assert_eq!(Treasury::pot(), 75);
+		assert_eq!(Balances::free_balance(Treasury::bounty_account_id(0)), 0);
+
+		assert_eq!(Treasury::bounties(0), None);
+		assert_eq!(Treasury::bounty_descriptions(0), None);
+	});
+}
+
+#[test]
+fn extend_expiry_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		create_funded_bounty();
+		Balances::make_free_balance_be(&4, 10);
+
+		assert_noop!(
+			Treasury::extend_bounty_expiry(Origin::signed(1), 0, Vec::new()),
+			Error::<Test>::UnexpectedStatus
+		);
+
+		assert_ok!(Treasury::propose_curator(Origin::ROOT, 0, 4, 4));
+		assert_ok!(Treasury::accept_curator(Origin::signed(4), 0));
+
+		assert_noop!(
+			Treasury::extend_bounty_expiry(Origin::signed(1), 0, Vec::new()),
+			Error::<Test>::RequireCurator
+		);
+
+		System::set_block_number(10);
+
+		assert_ok!(Treasury::extend_bounty_expiry(Origin::signed(4), 0, Vec::new()));
+		assert_eq!(Treasury::bounties(0).unwrap().status, BountyStatus::Active {
+			curator: 4,
+			update_due: 30,
+		});
+
+		// The curator is still active until the extended expiry.
+		System::set_block_number(25);
+		assert_noop!(Treasury::unassign_curator(Origin::signed(1), 0), Error::<Test>::Premature);
+
+		// Anyone can unassign an inactive curator, who gets slashed.
+		System::set_block_number(31);
+		assert_ok!(Treasury::unassign_curator(Origin::signed(1), 0));
+
+		assert_eq!(Treasury::bounties(0).unwrap().status, BountyStatus::Funded);
+		assert_eq!(Balances::free_balance(4), 8);
+		assert_eq!(Balances::reserved_balance(4), 0); // slashed 2
+	});
+}
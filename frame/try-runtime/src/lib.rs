@@ -0,0 +1,36 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API used by the `try-runtime` command to dry-run runtime upgrades.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::weights::Weight;
+
+sp_api::decl_runtime_apis! {
+	/// Runtime api for testing the execution of a runtime upgrade against existing state.
+	///
+	/// Changes made to storage by these calls are never meant to be committed.
+	pub trait TryRuntime {
+		/// Execute all the `OnRuntimeUpgrade` hooks of the runtime, returning the weight they
+		/// consumed.
+		fn on_runtime_upgrade() -> Weight;
+
+		/// Execute the given block without checking the resulting state root, returning the
+		/// weight consumed by the block.
+		fn execute_block_no_check(block: Block) -> Weight;
+	}
+}
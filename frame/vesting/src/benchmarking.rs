@@ -40,7 +40,7 @@ fn add_locks<T: Trait>(l: u32) {
 	}
 }
 
-fn setup<T: Trait>(b: u32) -> T::AccountId {
+fn setup<T: Trait>(b: u32, s: u32) -> T::AccountId {
 		let locked = 1;
 		let per_block = 1;
 		let starting_block = 0;
@@ -48,13 +48,15 @@ fn setup<T: Trait>(b: u32) -> T::AccountId {
 		let caller = account("caller", 0, SEED);
 		System::<T>::set_block_number(0.into());
 
-		// Add schedule to avoid `NotVesting` error.
-		let _ = Vesting::<T>::add_vesting_schedule(
-			&caller,
-			locked.into(),
-			per_block.into(),
-			starting_block.into(),
-		);
+		// Add `s` schedules, the weights charge for `MaxVestingSchedules` of them.
+		for _ in 0..s {
+			let _ = Vesting::<T>::add_vesting_schedule(
+				&caller,
+				locked.into(),
+				per_block.into(),
+				starting_block.into(),
+			);
+		}
 
 		// Set lock and block number to take different code paths.
 		let reasons = WithdrawReason::Transfer | WithdrawReason::Reserve;
@@ -73,22 +75,25 @@ benchmarks! {
 
 	vest_locked {
 		let l in ...;
+		let s in 1 .. T::MaxVestingSchedules::get();
 
-		let caller = setup::<T>(0u32);
+		let caller = setup::<T>(0u32, s);
 
 	}: vest(RawOrigin::Signed(caller))
 
 	vest_not_locked {
 		let l in ...;
+		let s in 1 .. T::MaxVestingSchedules::get();
 
-		let caller = setup::<T>(1u32);
+		let caller = setup::<T>(1u32, s);
 
 	}: vest(RawOrigin::Signed(caller))
 
 	vest_other_locked {
 		let l in ...;
+		let s in 1 .. T::MaxVestingSchedules::get();
 
-		let other: T::AccountId = setup::<T>(0u32);
+		let other: T::AccountId = setup::<T>(0u32, s);
 		let other_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(other.clone());
 
 		let caller = account("caller", 0, SEED);
@@ -97,8 +102,9 @@ benchmarks! {
 
 	vest_other_not_locked {
 		let l in ...;
+		let s in 1 .. T::MaxVestingSchedules::get();
 
-		let other: T::AccountId = setup::<T>(1u32);
+		let other: T::AccountId = setup::<T>(1u32, s);
 		let other_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(other.clone());
 
 		let caller = account("caller", 0, SEED);
@@ -123,6 +129,36 @@ benchmarks! {
 		let _ = T::Currency::make_free_balance_be(&from, transfer_amount * 10.into());
 
 	}: _(RawOrigin::Signed(from), to_lookup, vesting_schedule)
+
+	force_vested_transfer {
+		let u in 0 .. 1000;
+
+		let from = account("from", u, SEED);
+		let from_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(from.clone());
+		let to = account("to", u, SEED);
+		let to_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(to);
+
+		let transfer_amount = T::MinVestedTransfer::get();
+
+		let vesting_schedule = VestingInfo {
+			locked: transfer_amount,
+			per_block: 1.into(),
+			starting_block: 0.into(),
+		};
+
+		let _ = T::Currency::make_free_balance_be(&from, transfer_amount * 10.into());
+
+	}: _(RawOrigin::Root, from_lookup, to_lookup, vesting_schedule)
+
+	merge_schedules {
+		let l in ...;
+		let s in 2 .. T::MaxVestingSchedules::get();
+
+		// Merging the first and the last of the schedules moves all of them.
+		let caller = setup::<T>(0u32, s);
+		let last = s - 1;
+
+	}: _(RawOrigin::Signed(caller), 0, last)
 }
 
 #[cfg(test)]
@@ -139,6 +175,8 @@ mod tests {
 			assert_ok!(test_benchmark_vest_other_locked::<Test>());
 			assert_ok!(test_benchmark_vest_other_not_locked::<Test>());
 			assert_ok!(test_benchmark_vested_transfer::<Test>());
+			assert_ok!(test_benchmark_force_vested_transfer::<Test>());
+			assert_ok!(test_benchmark_merge_schedules::<Test>());
 		});
 	}
 }
@@ -25,6 +25,9 @@
 //! module ensures that there is a lock in place preventing the balance to drop below the *unvested*
 //! amount for any reason other than transaction fee payment.
 //!
+//! An account may have up to `MaxVestingSchedules` schedules at once, in which case the locked
+//! amount is the sum of what remains unvested under each of them.
+//!
 //! As the amount vested increases over time, the amount unvested reduces. However, locks remain in
 //! place and explicit action is needed on behalf of the user to ensure that the amount locked is
 //! equivalent to the amount remaining to be vested. This is done through a dispatchable function,
@@ -40,6 +43,9 @@
 //! - `vest` - Update the lock, reducing it in line with the amount "vested" so far.
 //! - `vest_other` - Update the lock of another account, reducing it in line with the amount
 //!   "vested" so far.
+//! - `vested_transfer` - Transfer funds to an account under a new vesting schedule.
+//! - `force_vested_transfer` - Root-only variant of `vested_transfer` from any source account.
+//! - `merge_schedules` - Merge two of the sender's vesting schedules into one.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Trait`]: ./trait.Trait.html
@@ -50,15 +56,16 @@ use sp_std::prelude::*;
 use sp_std::fmt::Debug;
 use codec::{Encode, Decode};
 use sp_runtime::{DispatchResult, RuntimeDebug, traits::{
-	StaticLookup, Zero, AtLeast32Bit, MaybeSerializeDeserialize, Convert
+	StaticLookup, Zero, One, AtLeast32Bit, MaybeSerializeDeserialize, Convert, Saturating, Bounded,
 }};
-use frame_support::{decl_module, decl_event, decl_storage, decl_error, ensure};
+use frame_support::{decl_module, decl_event, decl_storage, decl_error, ensure, metadata::TypeInfo};
+use frame_support::storage::IterableStorageMap;
 use frame_support::traits::{
 	Currency, LockableCurrency, VestingSchedule, WithdrawReason, LockIdentifier,
 	ExistenceRequirement, Get
 };
-use frame_support::weights::SimpleDispatchInfo;
-use frame_system::{self as system, ensure_signed};
+use frame_support::weights::{SimpleDispatchInfo, Weight, ROCKS_DB_WEIGHT};
+use frame_system::{self as system, ensure_signed, ensure_root};
 
 mod benchmarking;
 
@@ -76,12 +83,52 @@ pub trait Trait: frame_system::Trait {
 
 	/// The minimum amount transferred to call `vested_transfer`.
 	type MinVestedTransfer: Get<BalanceOf<Self>>;
+
+	/// The maximum number of vesting schedules an account may have at once.
+	type MaxVestingSchedules: Get<u32>;
 }
 
 const VESTING_ID: LockIdentifier = *b"vesting ";
 
+/// Weight of updating the lock of an account, charging the worst case of `MaxVestingSchedules`
+/// schedules.
+///
+/// The per-schedule cost is an estimate until the `s` component of the `vest_*` benchmarks has
+/// been measured; the schedules and the balance lock are read and written once.
+fn vest_weight<T: Trait>() -> Weight {
+	50_000u32
+		.saturating_add(1_000u32.saturating_mul(T::MaxVestingSchedules::get()))
+		.saturating_add(ROCKS_DB_WEIGHT.reads_writes(2, 2))
+}
+
+/// Weight of merging two schedules of an account, charging the worst case of
+/// `MaxVestingSchedules` schedules.
+///
+/// Estimated like `vest_weight`, see the `s` component of the `merge_schedules` benchmark; the
+/// schedules are written once more than by `vest`.
+fn merge_schedules_weight<T: Trait>() -> Weight {
+	60_000u32
+		.saturating_add(1_000u32.saturating_mul(T::MaxVestingSchedules::get()))
+		.saturating_add(ROCKS_DB_WEIGHT.reads_writes(2, 3))
+}
+
+// A value placed in storage that represents the current version of the Vesting storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+enum Releases {
+	V1_0_0,
+	V2_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
 /// Struct to encode the vesting schedule of an individual account.
-#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub struct VestingInfo<Balance, BlockNumber> {
 	/// Locked amount at genesis.
 	pub locked: Balance,
@@ -111,6 +158,26 @@ impl<
 			Zero::zero()
 		}
 	}
+
+	/// The first block, as a balance, at which nothing is locked anymore.
+	///
+	/// A schedule that never unlocks anything ends at the maximum balance value.
+	pub fn ending_block_as_balance<
+		BlockNumberToBalance: Convert<BlockNumber, Balance>
+	>(&self) -> Balance {
+		if self.per_block.is_zero() {
+			return Bounded::max_value()
+		}
+		let starting_block = BlockNumberToBalance::convert(self.starting_block);
+		let duration = if self.per_block >= self.locked {
+			One::one()
+		} else {
+			let remainder: Balance = self.locked % self.per_block;
+			let rounding = if remainder.is_zero() { Zero::zero() } else { One::one() };
+			self.locked / self.per_block + rounding
+		};
+		starting_block.saturating_add(duration)
+	}
 }
 
 decl_storage! {
@@ -118,12 +185,16 @@ decl_storage! {
 		/// Information regarding the vesting of a given account.
 		pub Vesting get(fn vesting):
 			map hasher(blake2_128_concat) T::AccountId
-			=> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>>;
+			=> Option<Vec<VestingInfo<BalanceOf<T>, T::BlockNumber>>>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with the latest version, as determined by the genesis build.
+		StorageVersion build(|_: &GenesisConfig<T>| Releases::V2_0_0): Releases;
 	}
 	add_extra_genesis {
 		config(vesting): Vec<(T::AccountId, T::BlockNumber, T::BlockNumber, BalanceOf<T>)>;
 		build(|config: &GenesisConfig<T>| {
-			use sp_runtime::traits::Saturating;
 			// Generate initial vesting configuration
 			// * who - Account which we are generating vesting configuration for
 			// * begin - Block when the account will start to vest
@@ -135,13 +206,13 @@ decl_storage! {
 				// Total genesis `balance` minus `liquid` equals funds locked for vesting
 				let locked = balance.saturating_sub(liquid);
 				let length_as_balance = T::BlockNumberToBalance::convert(length);
-				let per_block = locked / length_as_balance.max(sp_runtime::traits::One::one());
+				let per_block = locked / length_as_balance.max(One::one());
 
-				Vesting::<T>::insert(who, VestingInfo {
+				Vesting::<T>::insert(who, vec![VestingInfo {
 					locked: locked,
 					per_block: per_block,
 					starting_block: begin
-				});
+				}]);
 				let reasons = WithdrawReason::Transfer | WithdrawReason::Reserve;
 				T::Currency::set_lock(VESTING_ID, who, locked, reasons);
 			}
@@ -164,10 +235,13 @@ decl_error! {
 	pub enum Error for Module<T: Trait> {
 		/// The account given is not vesting.
 		NotVesting,
-		/// An existing vesting schedule already exists for this account that cannot be clobbered.
-		ExistingVestingSchedule,
+		/// The account already has `MaxVestingSchedules` vesting schedules and cannot be given
+		/// another one.
+		AtMaxVestingSchedules,
 		/// Amount being transferred is too low to create a vesting schedule.
 		AmountLow,
+		/// An index was out of bounds of the vesting schedules.
+		ScheduleIndexOutOfBounds,
 	}
 }
 
@@ -179,8 +253,23 @@ decl_module! {
 		/// The minimum amount to be transferred to create a new vesting schedule.
 		const MinVestedTransfer: BalanceOf<T> = T::MinVestedTransfer::get();
 
+		/// The maximum number of vesting schedules an account may have at once.
+		const MaxVestingSchedules: u32 = T::MaxVestingSchedules::get();
+
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get() == Releases::V1_0_0 {
+				StorageVersion::put(Releases::V2_0_0);
+				let migrated = Self::migrate_to_multiple_schedules();
+				// Each schedule is read and written back as a list, the storage version is read
+				// and written.
+				ROCKS_DB_WEIGHT.reads_writes(migrated.saturating_add(1), migrated.saturating_add(1))
+			} else {
+				ROCKS_DB_WEIGHT.reads(1)
+			}
+		}
+
 		/// Unlock any vested funds of the sender account.
 		///
 		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
@@ -189,12 +278,12 @@ decl_module! {
 		/// Emits either `VestingCompleted` or `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules, bounded by `MaxVestingSchedules`.
 		/// - One balance-lock operation.
-		/// - One storage read (codec `O(1)`) and up to one removal.
+		/// - One storage read (codec `O(S)`) and one write or removal.
 		/// - One event.
 		/// # </weight>
-		#[weight = SimpleDispatchInfo::default()]
+		#[weight = SimpleDispatchInfo::FixedNormal(vest_weight::<T>())]
 		fn vest(origin) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			Self::update_lock(who)
@@ -210,13 +299,15 @@ decl_module! {
 		/// Emits either `VestingCompleted` or `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules, bounded by `MaxVestingSchedules`.
 		/// - Up to one account lookup.
 		/// - One balance-lock operation.
-		/// - One storage read (codec `O(1)`) and up to one removal.
+		/// - One storage read (codec `O(S)`) and one write or removal.
 		/// - One event.
 		/// # </weight>
-		#[weight = SimpleDispatchInfo::default()]
+		#[weight = SimpleDispatchInfo::FixedNormal(
+			vest_weight::<T>().saturating_add(ROCKS_DB_WEIGHT.reads(1))
+		)]
 		fn vest_other(origin, target: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
 			ensure_signed(origin)?;
 			Self::update_lock(T::Lookup::lookup(target)?)
@@ -227,12 +318,15 @@ decl_module! {
 		/// The dispatch origin for this call must be _Signed_.
 		///
 		/// - `target`: The account that should be transferred the vested funds.
-		/// - `amount`: The amount of funds to transfer and will be vested.
-		/// - `schedule`: The vesting schedule attached to the transfer.
+		/// - `schedule`: The vesting schedule attached to the transfer. Its `locked` amount is
+		///   transferred and must be at least `MinVestedTransfer`.
+		///
+		/// Fails if `target` already has `MaxVestingSchedules` vesting schedules.
 		///
-		/// Emits `VestingCreated`.
+		/// Emits `VestingUpdated`.
 		///
 		/// # <weight>
+		/// - `O(S)` where `S` is the number of vesting schedules, bounded by `MaxVestingSchedules`.
 		/// - Creates a new storage entry, but is protected by a minimum transfer
 		///	   amount needed to succeed.
 		/// # </weight>
@@ -243,28 +337,181 @@ decl_module! {
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			let transactor = ensure_signed(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+			let target = T::Lookup::lookup(target)?;
+			Self::do_vested_transfer(&transactor, &target, schedule)
+		}
 
-			let who = T::Lookup::lookup(target)?;
-			ensure!(!Vesting::<T>::contains_key(&who), Error::<T>::ExistingVestingSchedule);
+		/// Force a vested transfer.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// - `source`: The account whose funds should be transferred.
+		/// - `target`: The account that should be transferred the vested funds.
+		/// - `schedule`: The vesting schedule attached to the transfer. Its `locked` amount is
+		///   transferred and must be at least `MinVestedTransfer`.
+		///
+		/// Fails if `target` already has `MaxVestingSchedules` vesting schedules.
+		///
+		/// Emits `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of vesting schedules, bounded by `MaxVestingSchedules`.
+		/// - Up to two account lookups.
+		/// - Creates a new storage entry, but is protected by a minimum transfer
+		///	   amount needed to succeed.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedOperational(1_000_000)]
+		pub fn force_vested_transfer(
+			origin,
+			source: <T::Lookup as StaticLookup>::Source,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let source = T::Lookup::lookup(source)?;
+			let target = T::Lookup::lookup(target)?;
+			Self::do_vested_transfer(&source, &target, schedule)
+		}
 
-			T::Currency::transfer(&transactor, &who, schedule.locked, ExistenceRequirement::AllowDeath)?;
+		/// Merge two vesting schedules of the sender into one.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
+		/// locked under this module.
+		///
+		/// - `schedule1_index`: Index of the first schedule to merge.
+		/// - `schedule2_index`: Index of the second schedule to merge.
+		///
+		/// The merged schedule locks whatever is still unvested under both schedules, starts at
+		/// the later of the current block and both starting blocks, and ends together with the
+		/// later of the two schedules. A schedule that has already fully vested is simply dropped.
+		/// Merging a schedule with itself is a no-op.
+		///
+		/// NOTE: The merged schedule is appended after the remaining ones, so the indices of the
+		/// other schedules may change.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of vesting schedules, bounded by `MaxVestingSchedules`.
+		/// - One balance-lock operation.
+		/// - One storage read (codec `O(S)`) and one write or removal.
+		/// - One event.
+		/// # </weight>
+		#[weight = SimpleDispatchInfo::FixedNormal(merge_schedules_weight::<T>())]
+		fn merge_schedules(origin, schedule1_index: u32, schedule2_index: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			if schedule1_index == schedule2_index {
+				return Ok(())
+			}
+			let (schedule1_index, schedule2_index) = (schedule1_index as usize, schedule2_index as usize);
 
-			Self::add_vesting_schedule(&who, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
+			let mut schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+			ensure!(
+				schedule1_index < schedules.len() && schedule2_index < schedules.len(),
+				Error::<T>::ScheduleIndexOutOfBounds,
+			);
 
-			Ok(())
+			// Remove the higher index first so the lower one stays valid.
+			let schedule1 = schedules.remove(schedule1_index.max(schedule2_index));
+			let schedule2 = schedules.remove(schedule1_index.min(schedule2_index));
+
+			let now = <frame_system::Module<T>>::block_number();
+			if let Some(merged) = Self::merge_vesting_info(now, schedule1, schedule2) {
+				schedules.push(merged);
+			}
+			Vesting::<T>::insert(&who, schedules);
+
+			Self::update_lock(who)
 		}
 	}
 }
 
 impl<T: Trait> Module<T> {
+	/// Migrate the single vesting schedule of every account to the multiple schedules layout.
+	///
+	/// Returns the number of migrated schedules.
+	pub fn migrate_to_multiple_schedules() -> Weight {
+		let migrated = sp_std::cell::Cell::new(0 as Weight);
+		Vesting::<T>::translate::<VestingInfo<BalanceOf<T>, T::BlockNumber>, _>(|_, schedule| {
+			migrated.set(migrated.get().saturating_add(1));
+			Some(vec![schedule])
+		});
+		migrated.get()
+	}
+
+	/// Total amount locked at block `n` over all of the given `schedules`.
+	fn locked_at(
+		schedules: &[VestingInfo<BalanceOf<T>, T::BlockNumber>],
+		n: T::BlockNumber,
+	) -> BalanceOf<T> {
+		schedules.iter().fold(Zero::zero(), |locked: BalanceOf<T>, schedule| {
+			locked.saturating_add(schedule.locked_at::<T::BlockNumberToBalance>(n))
+		})
+	}
+
+	/// Merge two schedules into one that locks what remains of both at block `now`.
+	///
+	/// Returns `None` if both schedules have fully vested.
+	fn merge_vesting_info(
+		now: T::BlockNumber,
+		schedule1: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		schedule2: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>> {
+		let locked1 = schedule1.locked_at::<T::BlockNumberToBalance>(now);
+		let locked2 = schedule2.locked_at::<T::BlockNumberToBalance>(now);
+		match (locked1.is_zero(), locked2.is_zero()) {
+			(true, true) => return None,
+			(true, false) => return Some(schedule2),
+			(false, true) => return Some(schedule1),
+			(false, false) => {},
+		}
+
+		let locked = locked1.saturating_add(locked2);
+		let ending_block = schedule1.ending_block_as_balance::<T::BlockNumberToBalance>()
+			.max(schedule2.ending_block_as_balance::<T::BlockNumberToBalance>());
+		let starting_block = now.max(schedule1.starting_block).max(schedule2.starting_block);
+
+		let duration = ending_block.saturating_sub(T::BlockNumberToBalance::convert(starting_block));
+		let per_block = if duration.is_zero() {
+			locked
+		} else {
+			(locked / duration).max(One::one())
+		};
+
+		Some(VestingInfo { locked, per_block, starting_block })
+	}
+
+	/// Transfer `schedule.locked` from `source` to `target` and vest it under `schedule`.
+	fn do_vested_transfer(
+		source: &T::AccountId,
+		target: &T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> DispatchResult {
+		ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+
+		let schedule_count = Self::vesting(target).map_or(0, |schedules| schedules.len());
+		ensure!(
+			schedule_count < T::MaxVestingSchedules::get() as usize,
+			Error::<T>::AtMaxVestingSchedules,
+		);
+
+		T::Currency::transfer(source, target, schedule.locked, ExistenceRequirement::AllowDeath)?;
+
+		Self::add_vesting_schedule(target, schedule.locked, schedule.per_block, schedule.starting_block)
+			.expect("user has fewer than `MaxVestingSchedules` vesting schedules; q.e.d.");
+
+		Ok(())
+	}
+
 	/// (Re)set or remove the module's currency lock on `who`'s account in accordance with their
-	/// current unvested amount.
+	/// current unvested amount, dropping any schedules that have fully vested.
 	fn update_lock(who: T::AccountId) -> DispatchResult {
-		let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+		let schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
 		let now = <frame_system::Module<T>>::block_number();
-		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+		let schedules: Vec<_> = schedules.into_iter()
+			.filter(|schedule| !schedule.locked_at::<T::BlockNumberToBalance>(now).is_zero())
+			.collect();
+		let locked_now = Self::locked_at(&schedules, now);
 
 		if locked_now.is_zero() {
 			T::Currency::remove_lock(VESTING_ID, &who);
@@ -273,6 +520,7 @@ impl<T: Trait> Module<T> {
 		} else {
 			let reasons = WithdrawReason::Transfer | WithdrawReason::Reserve;
 			T::Currency::set_lock(VESTING_ID, &who, locked_now, reasons);
+			Vesting::<T>::insert(&who, schedules);
 			Self::deposit_event(RawEvent::VestingUpdated(who, locked_now));
 		}
 		Ok(())
@@ -287,9 +535,9 @@ impl<T: Trait> VestingSchedule<T::AccountId> for Module<T> where
 
 	/// Get the amount that is currently being vested and cannot be transferred out of this account.
 	fn vesting_balance(who: &T::AccountId) -> Option<BalanceOf<T>> {
-		if let Some(v) = Self::vesting(who) {
+		if let Some(schedules) = Self::vesting(who) {
 			let now = <frame_system::Module<T>>::block_number();
-			let locked_now = v.locked_at::<T::BlockNumberToBalance>(now);
+			let locked_now = Self::locked_at(&schedules, now);
 			Some(T::Currency::free_balance(who).min(locked_now))
 		} else {
 			None
@@ -298,7 +546,7 @@ impl<T: Trait> VestingSchedule<T::AccountId> for Module<T> where
 
 	/// Adds a vesting schedule to a given account.
 	///
-	/// If there already exists a vesting schedule for the given account, an `Err` is returned
+	/// If the account already has `MaxVestingSchedules` vesting schedules, an `Err` is returned
 	/// and nothing is updated.
 	///
 	/// On success, a linearly reducing amount of funds will be locked. In order to realise any
@@ -313,21 +561,23 @@ impl<T: Trait> VestingSchedule<T::AccountId> for Module<T> where
 		starting_block: T::BlockNumber
 	) -> DispatchResult {
 		if locked.is_zero() { return Ok(()) }
-		if Vesting::<T>::contains_key(who) {
-			Err(Error::<T>::ExistingVestingSchedule)?
-		}
-		let vesting_schedule = VestingInfo {
+		let mut schedules = Self::vesting(who).unwrap_or_default();
+		ensure!(
+			schedules.len() < T::MaxVestingSchedules::get() as usize,
+			Error::<T>::AtMaxVestingSchedules,
+		);
+		schedules.push(VestingInfo {
 			locked,
 			per_block,
 			starting_block
-		};
-		Vesting::<T>::insert(who, vesting_schedule);
+		});
+		Vesting::<T>::insert(who, schedules);
 		// it can't fail, but even if somehow it did, we don't really care.
 		let _ = Self::update_lock(who.clone());
 		Ok(())
 	}
 
-	/// Remove a vesting schedule for a given account.
+	/// Remove all vesting schedules of a given account.
 	fn remove_vesting_schedule(who: &T::AccountId) {
 		Vesting::<T>::remove(who);
 		// it can't fail, but even if somehow it did, we don't really care.
@@ -350,7 +600,7 @@ mod tests {
 	use sp_runtime::{
 		Perbill,
 		testing::Header,
-		traits::{BlakeTwo256, IdentityLookup, Identity},
+		traits::{BlakeTwo256, IdentityLookup, Identity, BadOrigin},
 	};
 
 	impl_outer_origin! {
@@ -398,12 +648,14 @@ mod tests {
 	}
 	parameter_types! {
 		pub const MinVestedTransfer: u64 = 256 * 2;
+		pub const MaxVestingSchedules: u32 = 3;
 	}
 	impl Trait for Test {
 		type Event = ();
 		type Currency = Balances;
 		type BlockNumberToBalance = Identity;
 		type MinVestedTransfer = MinVestedTransfer;
+		type MaxVestingSchedules = MaxVestingSchedules;
 	}
 	type System = frame_system::Module<Test>;
 	type Balances = pallet_balances::Module<Test>;
@@ -484,9 +736,9 @@ mod tests {
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&1), Some(user1_vesting_schedule)); // Account 1 has a vesting schedule
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule)); // Account 2 has a vesting schedule
-				assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule)); // Account 12 has a vesting schedule
+				assert_eq!(Vesting::vesting(&1), Some(vec![user1_vesting_schedule])); // Account 1 has a vesting schedule
+				assert_eq!(Vesting::vesting(&2), Some(vec![user2_vesting_schedule])); // Account 2 has a vesting schedule
+				assert_eq!(Vesting::vesting(&12), Some(vec![user12_vesting_schedule])); // Account 12 has a vesting schedule
 
 				// Account 1 has only 128 units vested from their illiquid 256 * 5 units at block 1
 				assert_eq!(Vesting::vesting_balance(&1), Some(128 * 9));
@@ -607,7 +859,7 @@ mod tests {
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule));
+				assert_eq!(Vesting::vesting(&12), Some(vec![user12_vesting_schedule]));
 
 				// Account 12 can still send liquid funds
 				assert_ok!(Balances::transfer(Some(12).into(), 3, 256 * 5));
@@ -634,7 +886,7 @@ mod tests {
 				};
 				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule));
 				// Now account 4 should have vesting.
-				assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+				assert_eq!(Vesting::vesting(&4), Some(vec![new_vesting_schedule]));
 				// Ensure the transfer happened correctly.
 				let user3_free_balance_updated = Balances::free_balance(&3);
 				assert_eq!(user3_free_balance_updated, 256 * 25);
@@ -673,33 +925,232 @@ mod tests {
 					per_block: 256, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+				assert_eq!(Vesting::vesting(&2), Some(vec![user2_vesting_schedule]));
+
+				// Fails due to too low transfer amount.
+				let new_vesting_schedule_too_low = VestingInfo {
+					locked: 256 * 1,
+					per_block: 64,
+					starting_block: 10,
+				};
+				assert_noop!(
+					Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule_too_low),
+					Error::<Test>::AmountLow,
+				);
+
+				// Verify no currency transfer happened.
+				assert_eq!(user2_free_balance, 256 * 20);
+				assert_eq!(user4_free_balance, 256 * 40);
+			});
+	}
 
-				// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+	#[test]
+	fn vested_transfer_adds_schedules_up_to_max() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let user2_vesting_schedule = VestingInfo {
+					locked: 256 * 20,
+					per_block: 256, // Vesting over 20 blocks
+					starting_block: 10,
+				};
 				let new_vesting_schedule = VestingInfo {
 					locked: 256 * 5,
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
+				// Account 2 already has a vesting schedule and can be given more.
+				assert_ok!(Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule));
+				assert_ok!(Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule));
+				assert_eq!(
+					Vesting::vesting(&2),
+					Some(vec![user2_vesting_schedule, new_vesting_schedule, new_vesting_schedule]),
+				);
+				assert_eq!(Balances::free_balance(&2), 256 * 30);
+				assert_eq!(Balances::free_balance(&4), 256 * 30);
+
+				// The lock covers all schedules.
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 30));
+				System::set_block_number(20);
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 10 + 2 * 64 * 10));
+				assert_ok!(Vesting::vest(Some(2).into()));
+				assert_ok!(Balances::transfer(Some(2).into(), 3, 256 * 30 - (256 * 10 + 2 * 64 * 10)));
+
+				// Account 2 is at the maximum number of schedules.
 				assert_noop!(
 					Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule),
-					Error::<Test>::ExistingVestingSchedule,
+					Error::<Test>::AtMaxVestingSchedules,
+				);
+				assert_noop!(
+					Vesting::add_vesting_schedule(&2, 256 * 5, 64, 10),
+					Error::<Test>::AtMaxVestingSchedules,
 				);
 
-				// Fails due to too low transfer amount.
+				// Fully vested schedules are dropped, making room for new ones.
+				System::set_block_number(30);
+				assert_ok!(Vesting::vest(Some(2).into()));
+				assert_eq!(Vesting::vesting(&2), None);
+				let later_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64, // Vesting over 20 blocks
+					starting_block: 40,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(4).into(), 2, later_vesting_schedule));
+				assert_eq!(Vesting::vesting(&2), Some(vec![later_vesting_schedule]));
+			});
+	}
+
+	#[test]
+	fn force_vested_transfer_works() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let new_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64, // Vesting over 20 blocks
+					starting_block: 10,
+				};
+				assert_noop!(
+					Vesting::force_vested_transfer(Some(4).into(), 3, 4, new_vesting_schedule),
+					BadOrigin,
+				);
+				assert_ok!(Vesting::force_vested_transfer(Origin::ROOT, 3, 4, new_vesting_schedule));
+				assert_eq!(Vesting::vesting(&4), Some(vec![new_vesting_schedule]));
+				assert_eq!(Balances::free_balance(&3), 256 * 25);
+				assert_eq!(Balances::free_balance(&4), 256 * 45);
+				assert_eq!(Vesting::vesting_balance(&4), Some(256 * 5));
+
+				// Still subject to the minimum transfer amount.
 				let new_vesting_schedule_too_low = VestingInfo {
 					locked: 256 * 1,
 					per_block: 64,
 					starting_block: 10,
 				};
 				assert_noop!(
-					Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule_too_low),
+					Vesting::force_vested_transfer(Origin::ROOT, 3, 4, new_vesting_schedule_too_low),
 					Error::<Test>::AmountLow,
 				);
+			});
+	}
 
-				// Verify no currency transfer happened.
-				assert_eq!(user2_free_balance, 256 * 20);
-				assert_eq!(user4_free_balance, 256 * 40);
+	#[test]
+	fn merge_schedules_works() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let new_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64, // Vesting over 20 blocks
+					starting_block: 10,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule));
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 25));
+
+				assert_ok!(Vesting::merge_schedules(Some(2).into(), 0, 1));
+				let merged_schedule = VestingInfo {
+					locked: 256 * 25,
+					per_block: 320, // Vesting over 20 blocks
+					starting_block: 10,
+				};
+				assert_eq!(Vesting::vesting(&2), Some(vec![merged_schedule]));
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 25));
+
+				System::set_block_number(30);
+				assert_eq!(Vesting::vesting_balance(&2), Some(0));
+			});
+	}
+
+	#[test]
+	fn merge_ongoing_schedules_keeps_locked_amount() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let new_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 128, // Vesting over 10 blocks
+					starting_block: 5,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule));
+
+				System::set_block_number(12);
+				let locked = 256 * 20 - 2 * 256 + 256 * 5 - 7 * 128;
+				assert_eq!(Vesting::vesting_balance(&2), Some(locked));
+
+				// The merged schedule starts now and ends with the longer schedule, at block 30.
+				assert_ok!(Vesting::merge_schedules(Some(2).into(), 1, 0));
+				let merged_schedule = VestingInfo {
+					locked,
+					per_block: locked / 18,
+					starting_block: 12,
+				};
+				assert_eq!(Vesting::vesting(&2), Some(vec![merged_schedule]));
+				assert_eq!(Vesting::vesting_balance(&2), Some(locked));
+
+				// Merging a fully vested schedule just drops it.
+				assert_ok!(Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule));
+				System::set_block_number(15);
+				assert_ok!(Vesting::merge_schedules(Some(2).into(), 0, 1));
+				assert_eq!(Vesting::vesting(&2), Some(vec![merged_schedule]));
+			});
+	}
+
+	#[test]
+	fn merge_schedules_correctly_fails() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				assert_noop!(
+					Vesting::merge_schedules(Some(3).into(), 0, 1),
+					Error::<Test>::NotVesting,
+				);
+				assert_noop!(
+					Vesting::merge_schedules(Some(2).into(), 0, 1),
+					Error::<Test>::ScheduleIndexOutOfBounds,
+				);
+				// Merging a schedule with itself does nothing.
+				assert_ok!(Vesting::merge_schedules(Some(2).into(), 0, 0));
+				assert_eq!(Vesting::vesting(&2).map(|schedules| schedules.len()), Some(1));
+			});
+	}
+
+	#[test]
+	fn migration_to_multiple_schedules_works() {
+		use frame_support::{Blake2_128Concat, StorageHasher, migration::put_storage_value};
+		use frame_support::traits::OnRuntimeUpgrade;
+
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let old_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64,
+					starting_block: 10,
+				};
+				// Only old schedules may be around when migrating.
+				Vesting::remove_vesting_schedule(&1);
+				Vesting::remove_vesting_schedule(&2);
+				Vesting::remove_vesting_schedule(&12);
+				put_storage_value(b"Vesting", b"Vesting", &Blake2_128Concat::hash(&3u64.encode()), old_schedule);
+				StorageVersion::put(Releases::V1_0_0);
+
+				assert_eq!(
+					<Vesting as OnRuntimeUpgrade>::on_runtime_upgrade(),
+					ROCKS_DB_WEIGHT.reads_writes(2, 2),
+				);
+
+				assert_eq!(StorageVersion::get(), Releases::V2_0_0);
+				assert_eq!(Vesting::vesting(&3), Some(vec![old_schedule]));
+				assert_eq!(Vesting::vesting_balance(&3), Some(256 * 5));
+
+				// Migrating again is a no-op.
+				assert_eq!(<Vesting as OnRuntimeUpgrade>::on_runtime_upgrade(), ROCKS_DB_WEIGHT.reads(1));
+				assert_eq!(Vesting::vesting(&3), Some(vec![old_schedule]));
 			});
 	}
 }
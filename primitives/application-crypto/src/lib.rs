@@ -30,6 +30,8 @@ pub use sp_core::crypto::{CryptoTypeId, KeyTypeId, key_types};
 #[doc(hidden)]
 pub use codec;
 #[doc(hidden)]
+pub use frame_metadata;
+#[doc(hidden)]
 #[cfg(feature = "std")]
 pub use serde;
 #[doc(hidden)]
@@ -265,6 +267,17 @@ macro_rules! app_crypto_public_common {
 			type Generic = $public;
 		}
 
+		impl $crate::frame_metadata::TypeInfo for Public {
+			fn type_info(
+				registry: &mut $crate::frame_metadata::Registry,
+			) -> $crate::frame_metadata::Type {
+				$crate::frame_metadata::Type::wrapper(
+					$crate::frame_metadata::registry::type_path(module_path!(), "Public"),
+					registry.register::<$public>(),
+				)
+			}
+		}
+
 		impl $crate::RuntimeAppPublic for Public where $public: $crate::RuntimePublic<Signature=$sig> {
 			const ID: $crate::KeyTypeId = $key_type;
 			type Signature = Signature;
@@ -430,6 +443,17 @@ macro_rules! app_crypto_signature_common {
 		impl $crate::AppSignature for Signature {
 			type Generic = $sig;
 		}
+
+		impl $crate::frame_metadata::TypeInfo for Signature {
+			fn type_info(
+				registry: &mut $crate::frame_metadata::Registry,
+			) -> $crate::frame_metadata::Type {
+				$crate::frame_metadata::Type::wrapper(
+					$crate::frame_metadata::registry::type_path(module_path!(), "Signature"),
+					registry.register::<$sig>(),
+				)
+			}
+		}
 	}
 }
 
@@ -0,0 +1,104 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Running
+//! Running this fuzzer can be done with `cargo hfuzz run fixed_point`. `honggfuzz` CLI options can
+//! be used by setting `HFUZZ_RUN_ARGS`, such as `-n 4` to use 4 threads.
+//!
+//! # Debugging a panic
+//! Once a panic is found, it can be debugged with
+//! `cargo hfuzz run-debug fixed_point hfuzz_workspace/fixed_point/*.fuzz`.
+
+use honggfuzz::fuzz;
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+use sp_arithmetic::{FixedPointNumber, Fixed128, FixedU128, traits::Saturating};
+
+fn main() {
+	loop {
+		fuzz!(|data: (u128, u128, i128, u64)| {
+			let (a, b, signed, int) = data;
+			let div = BigUint::from(FixedU128::accuracy());
+			let ratio = |n: u128, d: u128| if d == 0 {
+				None
+			} else {
+				Some(BigUint::from(n) * &div / BigUint::from(d))
+			};
+
+			// rational construction.
+			let result = FixedU128::checked_from_rational(a, b).map(|r| r.into_inner());
+			assert_close("checked_from_rational", result, ratio(a, b), u128::max_value());
+
+			// multiplication and division of two numbers.
+			let (x, y) = (FixedU128::from_inner(a), FixedU128::from_inner(b));
+			let truth = BigUint::from(a) * BigUint::from(b) / &div;
+			let result = x.checked_mul(&y).map(|r| r.into_inner());
+			assert_close("checked_mul", result, Some(truth), u128::max_value());
+
+			let result = x.checked_div(&y).map(|r| r.into_inner());
+			assert_close("checked_div", result, ratio(a, b), u128::max_value());
+
+			// multiplication with an integer.
+			let truth = BigUint::from(a) * BigUint::from(int) / &div;
+			let result = x.checked_mul_int(int);
+			assert_close("checked_mul_int", result.map(u128::from), Some(truth), u64::max_value().into());
+			if result.is_none() {
+				assert_eq!(x.saturating_mul_int(int), u64::max_value());
+			}
+
+			// signed numbers behave like the unsigned ones, up to the sign.
+			let x = Fixed128::from_inner(signed);
+			let magnitude = |n: i128| n.checked_abs().map(|n| n as u128).unwrap_or(1 << 127);
+			let truth = BigUint::from(magnitude(signed)) * BigUint::from(int) / &div;
+			let result = x.checked_mul_int(i128::from(int));
+			if let Some(result) = result {
+				assert!(result >= 0 || signed < 0);
+			}
+			assert_close(
+				"signed checked_mul_int",
+				result.map(magnitude),
+				Some(truth),
+				if signed < 0 { 1 << 127 } else { i128::max_value() as u128 },
+			);
+
+			// the square of the root never exceeds the number.
+			if let Some(root) = x.checked_sqrt() {
+				assert!(!x.is_negative());
+				assert!(root.saturating_mul(root) <= x);
+			}
+		})
+	}
+}
+
+/// Asserts that `result` is within one part of `truth`, or that it is `None` if `truth`
+/// exceeds `max`.
+fn assert_close(name: &str, result: Option<u128>, truth: Option<BigUint>, max: u128) {
+	let fits = |truth: &BigUint| truth <= &BigUint::from(max);
+	match (result, truth) {
+		(Some(result), Some(ref truth)) if fits(truth) => {
+			let truth = truth.to_u128().expect("truth does not exceed max; qed");
+			let diff = if result > truth { result - truth } else { truth - result };
+			if diff > 1 {
+				panic!("{}: expected {}, got {}", name, truth, result);
+			}
+		},
+		(None, None) => {},
+		(None, Some(ref truth)) if !fits(truth) => {},
+		// rounding might push values right at the bound over it.
+		(None, Some(ref truth)) if truth == &BigUint::from(max) => {},
+		(result, truth) => panic!("{}: expected {:?}, got {:?}", name, truth, result),
+	}
+}
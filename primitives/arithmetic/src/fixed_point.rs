@@ -0,0 +1,933 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generic fixed point numbers and their concrete 128 bit implementations.
+
+use sp_std::{ops, fmt::Debug, convert::{TryFrom, TryInto}};
+use codec::{Encode, Decode};
+use crate::{
+	helpers_128bit::multiply_by_rational,
+	PerThing,
+	traits::{
+		SaturatedConversion, CheckedSub, CheckedAdd, CheckedMul, CheckedDiv, CheckedNeg,
+		Bounded, Saturating, UniqueSaturatedInto, Zero, One, IntegerSquareRoot,
+	},
+};
+
+#[cfg(feature = "std")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer, de};
+
+/// Integer types that can be used to interact with `FixedPointNumber` implementations.
+pub trait FixedPointOperand: Copy + Clone + Bounded + Zero + Saturating
+	+ PartialOrd + UniqueSaturatedInto<u128> + TryFrom<u128> + CheckedNeg {}
+
+impl FixedPointOperand for i128 {}
+impl FixedPointOperand for u128 {}
+impl FixedPointOperand for i64 {}
+impl FixedPointOperand for u64 {}
+impl FixedPointOperand for i32 {}
+impl FixedPointOperand for u32 {}
+impl FixedPointOperand for i16 {}
+impl FixedPointOperand for u16 {}
+impl FixedPointOperand for i8 {}
+impl FixedPointOperand for u8 {}
+
+/// Something that implements a decimal fixed point number.
+///
+/// The precision is given by `Self::DIV`, i.e. `1 / DIV` can be represented.
+///
+/// Each type can store numbers from `Self::Inner::min_value() / Self::DIV`
+/// to `Self::Inner::max_value() / Self::DIV`.
+/// This is also referred to as the _accuracy_ of the type in the documentation.
+pub trait FixedPointNumber:
+	Sized + Copy + Default + Debug
+	+ Saturating + Bounded
+	+ Eq + PartialEq + Ord + PartialOrd
+	+ CheckedSub + CheckedAdd + CheckedMul + CheckedDiv
+	+ ops::Add + ops::Sub + ops::Div + ops::Mul
+{
+	/// The underlying data type used for this fixed point number.
+	type Inner: Debug + One + CheckedMul + CheckedDiv + FixedPointOperand;
+
+	/// Precision of this fixed point implementation. It should be a power of `10`.
+	const DIV: Self::Inner;
+
+	/// Indicates if this fixed point implementation is signed or not.
+	const SIGNED: bool;
+
+	/// Precision of this fixed point implementation.
+	fn accuracy() -> Self::Inner {
+		Self::DIV
+	}
+
+	/// Builds this type from an integer number.
+	fn from_inner(int: Self::Inner) -> Self;
+
+	/// Consumes `self` and returns the inner raw value.
+	fn into_inner(self) -> Self::Inner;
+
+	/// Creates self from an integer number `int`.
+	///
+	/// Returns `Self::max` or `Self::min` if `int` exceeds accuracy.
+	fn saturating_from_integer<N: FixedPointOperand>(int: N) -> Self {
+		let mut n: I129 = int.into();
+		n.value = n.value.saturating_mul(Self::DIV.saturated_into());
+		Self::from_inner(from_i129(n).unwrap_or_else(|| to_bound(int, 0)))
+	}
+
+	/// Creates `self` from an integer number `int`.
+	///
+	/// Returns `None` if `int` exceeds accuracy.
+	fn checked_from_integer(int: Self::Inner) -> Option<Self> {
+		int.checked_mul(&Self::DIV).map(Self::from_inner)
+	}
+
+	/// Creates `self` from a rational number. Equal to `n / d`.
+	///
+	/// Panics if `d = 0`. Returns `Self::max` or `Self::min` if `n / d` exceeds accuracy.
+	fn saturating_from_rational<N: FixedPointOperand, D: FixedPointOperand>(n: N, d: D) -> Self {
+		if d == D::zero() {
+			panic!("attempt to divide by zero")
+		}
+		Self::checked_from_rational(n, d).unwrap_or_else(|| to_bound(n, d))
+	}
+
+	/// Creates `self` from a rational number. Equal to `n / d`.
+	///
+	/// Returns `None` if `d == 0` or `n / d` exceeds accuracy.
+	fn checked_from_rational<N: FixedPointOperand, D: FixedPointOperand>(n: N, d: D) -> Option<Self> {
+		if d == D::zero() {
+			return None
+		}
+
+		let n: I129 = n.into();
+		let d: I129 = d.into();
+		let negative = n.negative != d.negative;
+
+		multiply_by_rational(n.value, Self::DIV.unique_saturated_into(), d.value).ok()
+			.and_then(|value| from_i129(I129 { value, negative }))
+			.map(Self::from_inner)
+	}
+
+	/// Checked multiplication for integer type `N`. Equal to `self * n`.
+	///
+	/// Returns `None` if the result does not fit in `N`.
+	fn checked_mul_int<N: FixedPointOperand>(self, n: N) -> Option<N> {
+		let lhs: I129 = self.into_inner().into();
+		let rhs: I129 = n.into();
+		let negative = lhs.negative != rhs.negative;
+
+		multiply_by_rational(lhs.value, rhs.value, Self::DIV.unique_saturated_into()).ok()
+			.and_then(|value| from_i129(I129 { value, negative }))
+	}
+
+	/// Saturating multiplication for integer type `N`. Equal to `self * n`.
+	///
+	/// Returns `N::min` or `N::max` if the result does not fit in `N`.
+	fn saturating_mul_int<N: FixedPointOperand>(self, n: N) -> N {
+		self.checked_mul_int(n).unwrap_or_else(|| to_bound(self.into_inner(), n))
+	}
+
+	/// Checked division for integer type `N`. Equal to `self / d`.
+	///
+	/// Returns `None` if the result does not fit in `N` or `d == 0`.
+	fn checked_div_int<N: FixedPointOperand>(self, d: N) -> Option<N> {
+		let lhs: I129 = self.into_inner().into();
+		let rhs: I129 = d.into();
+		let negative = lhs.negative != rhs.negative;
+
+		lhs.value.checked_div(rhs.value)
+			.and_then(|n| n.checked_div(Self::DIV.unique_saturated_into()))
+			.and_then(|value| from_i129(I129 { value, negative }))
+	}
+
+	/// Saturating division for integer type `N`. Equal to `self / d`.
+	///
+	/// Panics if `d == 0`. Returns `N::min` or `N::max` if the result does not fit in `N`.
+	fn saturating_div_int<N: FixedPointOperand>(self, d: N) -> N {
+		if d == N::zero() {
+			panic!("attempt to divide by zero")
+		}
+		self.checked_div_int(d).unwrap_or_else(|| to_bound(self.into_inner(), d))
+	}
+
+	/// Saturating multiplication for integer type `N`, adding the result back.
+	/// Equal to `self * n + n`.
+	///
+	/// Returns `N::min` or `N::max` if the multiplication or final result does not fit in `N`.
+	fn saturating_mul_acc_int<N: FixedPointOperand>(self, n: N) -> N {
+		if self.is_negative() && n > N::zero() {
+			n.saturating_sub(Self::zero().saturating_sub(self).saturating_mul_int(n))
+		} else {
+			self.saturating_mul_int(n).saturating_add(n)
+		}
+	}
+
+	/// Saturating absolute value.
+	///
+	/// Returns `Self::max` if `self == Self::min`.
+	fn saturating_abs(self) -> Self {
+		let inner = self.into_inner();
+		if inner >= Self::Inner::zero() {
+			self
+		} else {
+			Self::from_inner(inner.checked_neg().unwrap_or_else(Self::Inner::max_value))
+		}
+	}
+
+	/// Takes the reciprocal (inverse). Equal to `1 / self`.
+	///
+	/// Returns `None` if `self = 0`.
+	fn reciprocal(self) -> Option<Self> {
+		Self::one().checked_div(&self)
+	}
+
+	/// Checked square root. Equal to `sqrt(self)`, rounded down to the accuracy of the type.
+	///
+	/// Returns `None` if `self` is negative.
+	fn checked_sqrt(self) -> Option<Self> {
+		if self.is_negative() {
+			return None
+		}
+		if self.is_zero() {
+			return Some(self)
+		}
+
+		// `sqrt(inner / DIV) = sqrt(inner * DIV) / DIV`, so the root of `inner * DIV` is computed
+		// with Newton's method, starting from above the root.
+		let inner: u128 = self.into_inner().unique_saturated_into();
+		let div: u128 = Self::DIV.unique_saturated_into();
+		let mut root = (inner.integer_sqrt() + 1).saturating_mul(div.integer_sqrt() + 1);
+		loop {
+			// `inner * div / root <= root` as long as `root` is not below the root, hence fits.
+			let next = root.checked_add(multiply_by_rational(inner, div, root).ok()?)? / 2;
+			if next >= root {
+				break
+			}
+			root = next;
+		}
+
+		root.try_into().ok().map(Self::from_inner)
+	}
+
+	/// Checked exponentiation. Equal to `self ^ exp`.
+	///
+	/// Returns `None` if the result does not fit in the accuracy of the type.
+	fn checked_pow(self, exp: usize) -> Option<Self> {
+		let mut result = Self::one();
+		let mut base = self;
+		let mut exp = exp;
+		while exp > 0 {
+			if exp & 1 == 1 {
+				result = result.checked_mul(&base)?;
+			}
+			exp >>= 1;
+			if exp > 0 {
+				base = base.checked_mul(&base)?;
+			}
+		}
+		Some(result)
+	}
+
+	/// Returns zero.
+	fn zero() -> Self {
+		Self::from_inner(Self::Inner::zero())
+	}
+
+	/// Checks if the number is zero.
+	fn is_zero(&self) -> bool {
+		self.into_inner() == Self::Inner::zero()
+	}
+
+	/// Returns one.
+	fn one() -> Self {
+		Self::from_inner(Self::DIV)
+	}
+
+	/// Checks if the number is one.
+	fn is_one(&self) -> bool {
+		self.into_inner() == Self::DIV
+	}
+
+	/// Checks if the number is positive.
+	fn is_positive(self) -> bool {
+		self.into_inner() > Self::Inner::zero()
+	}
+
+	/// Checks if the number is negative.
+	fn is_negative(self) -> bool {
+		self.into_inner() < Self::Inner::zero()
+	}
+
+	/// Returns the integer part.
+	fn trunc(self) -> Self {
+		self.into_inner().checked_div(&Self::DIV)
+			.expect("panics only if DIV is zero, DIV is not zero; qed")
+			.checked_mul(&Self::DIV)
+			.map(Self::from_inner)
+			.expect("can not overflow since fixed number is >= integer part; qed")
+	}
+
+	/// Returns the fractional part.
+	///
+	/// Note: the returned fraction will be non-negative for negative numbers,
+	/// except in the case where the integer part is zero.
+	fn frac(self) -> Self {
+		let integer = self.trunc();
+		let fractional = self.saturating_sub(integer);
+		if integer == Self::zero() {
+			fractional
+		} else {
+			fractional.saturating_abs()
+		}
+	}
+
+	/// Returns the smallest integer greater than or equal to a number.
+	///
+	/// Saturates to `Self::max` (truncated) if the result does not fit.
+	fn ceil(self) -> Self {
+		if self.is_positive() && !self.frac().is_zero() {
+			self.saturating_add(Self::one()).trunc()
+		} else {
+			self.trunc()
+		}
+	}
+
+	/// Returns the largest integer less than or equal to a number.
+	///
+	/// Saturates to `Self::min` (truncated) if the result does not fit.
+	fn floor(self) -> Self {
+		if self.is_negative() && !self.frac().is_zero() {
+			self.saturating_sub(Self::one()).trunc()
+		} else {
+			self.trunc()
+		}
+	}
+
+	/// Returns the number rounded to the nearest integer. Rounds half-way cases away from 0.0.
+	///
+	/// Saturates to `Self::min` or `Self::max` (truncated) if the result does not fit.
+	fn round(self) -> Self {
+		let n = self.frac().saturating_abs().saturating_mul(Self::saturating_from_integer(10));
+		if n < Self::saturating_from_integer(5) {
+			self.trunc()
+		} else if self.is_positive() {
+			self.saturating_add(Self::one()).trunc()
+		} else {
+			self.saturating_sub(Self::one()).trunc()
+		}
+	}
+}
+
+/// Data type used as intermediate storage in some computations to avoid overflow.
+struct I129 {
+	value: u128,
+	negative: bool,
+}
+
+impl<N: FixedPointOperand> From<N> for I129 {
+	fn from(n: N) -> I129 {
+		if n < N::zero() {
+			let value: u128 = n.checked_neg()
+				.map(|n| n.unique_saturated_into())
+				.unwrap_or_else(|| N::max_value().unique_saturated_into().saturating_add(1));
+			I129 { value, negative: true }
+		} else {
+			I129 { value: n.unique_saturated_into(), negative: false }
+		}
+	}
+}
+
+/// Transforms an `I129` to `N` if it is possible.
+fn from_i129<N: FixedPointOperand>(n: I129) -> Option<N> {
+	let max_plus_one: u128 = N::max_value().unique_saturated_into().saturating_add(1);
+	if n.negative && N::min_value() < N::zero() && n.value == max_plus_one {
+		Some(N::min_value())
+	} else {
+		let unsigned_inner = N::try_from(n.value).ok()?;
+		if n.negative {
+			unsigned_inner.checked_neg()
+		} else {
+			Some(unsigned_inner)
+		}
+	}
+}
+
+/// Returns `R::max` if the sign of `n * m` is positive, `R::min` otherwise.
+fn to_bound<N: FixedPointOperand, D: FixedPointOperand, R: Bounded>(n: N, m: D) -> R {
+	if (n < N::zero()) != (m < D::zero()) {
+		R::min_value()
+	} else {
+		R::max_value()
+	}
+}
+
+macro_rules! implement_fixed {
+	(
+		$name:ident,
+		$test_mod:ident,
+		$inner_type:ty,
+		$signed:tt,
+		$div:tt,
+		$title:expr $(,)?
+	) => {
+		/// A fixed point number representation in the range.
+		///
+		#[doc = $title]
+		#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+		pub struct $name($inner_type);
+
+		impl From<$inner_type> for $name {
+			fn from(int: $inner_type) -> Self {
+				$name::saturating_from_integer(int)
+			}
+		}
+
+		impl<P: PerThing> From<P> for $name {
+			fn from(p: P) -> Self {
+				let accuracy: u128 = P::ACCURACY.saturated_into();
+				let value: u128 = p.deconstruct().saturated_into();
+				$name::saturating_from_rational(value, accuracy)
+			}
+		}
+
+		impl FixedPointNumber for $name {
+			type Inner = $inner_type;
+
+			const DIV: Self::Inner = $div;
+			const SIGNED: bool = $signed;
+
+			fn from_inner(inner: Self::Inner) -> Self {
+				Self(inner)
+			}
+
+			fn into_inner(self) -> Self::Inner {
+				self.0
+			}
+		}
+
+		impl Saturating for $name {
+			fn saturating_add(self, rhs: Self) -> Self {
+				Self(self.0.saturating_add(rhs.0))
+			}
+
+			fn saturating_sub(self, rhs: Self) -> Self {
+				Self(self.0.saturating_sub(rhs.0))
+			}
+
+			fn saturating_mul(self, rhs: Self) -> Self {
+				self.checked_mul(&rhs).unwrap_or_else(|| to_bound(self.0, rhs.0))
+			}
+
+			fn saturating_pow(self, exp: usize) -> Self {
+				FixedPointNumber::checked_pow(self, exp).unwrap_or_else(|| {
+					if self.is_negative() && exp % 2 == 1 {
+						Self::min_value()
+					} else {
+						Self::max_value()
+					}
+				})
+			}
+		}
+
+		/// Note that this is a standard, _potentially-panicking_, implementation. Use `Saturating`
+		/// trait for safe addition.
+		impl ops::Add for $name {
+			type Output = Self;
+
+			fn add(self, rhs: Self) -> Self::Output {
+				Self(self.0 + rhs.0)
+			}
+		}
+
+		/// Note that this is a standard, _potentially-panicking_, implementation. Use `Saturating`
+		/// trait for safe subtraction.
+		impl ops::Sub for $name {
+			type Output = Self;
+
+			fn sub(self, rhs: Self) -> Self::Output {
+				Self(self.0 - rhs.0)
+			}
+		}
+
+		/// Note that this is a standard, _potentially-panicking_, implementation. Use `Saturating`
+		/// trait for safe multiplication.
+		impl ops::Mul for $name {
+			type Output = Self;
+
+			fn mul(self, rhs: Self) -> Self::Output {
+				self.checked_mul(&rhs)
+					.unwrap_or_else(|| panic!("attempt to multiply with overflow"))
+			}
+		}
+
+		/// Note that this is a standard, _potentially-panicking_, implementation. Use `CheckedDiv`
+		/// trait for safe division.
+		impl ops::Div for $name {
+			type Output = Self;
+
+			fn div(self, rhs: Self) -> Self::Output {
+				if rhs.0 == 0 {
+					panic!("attempt to divide by zero")
+				}
+				self.checked_div(&rhs)
+					.unwrap_or_else(|| panic!("attempt to divide with overflow"))
+			}
+		}
+
+		impl CheckedSub for $name {
+			fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+				self.0.checked_sub(rhs.0).map(Self)
+			}
+		}
+
+		impl CheckedAdd for $name {
+			fn checked_add(&self, rhs: &Self) -> Option<Self> {
+				self.0.checked_add(rhs.0).map(Self)
+			}
+		}
+
+		impl CheckedMul for $name {
+			fn checked_mul(&self, other: &Self) -> Option<Self> {
+				let lhs: I129 = self.0.into();
+				let rhs: I129 = other.0.into();
+				let negative = lhs.negative != rhs.negative;
+
+				multiply_by_rational(lhs.value, rhs.value, Self::DIV as u128).ok()
+					.and_then(|value| from_i129(I129 { value, negative }))
+					.map(Self)
+			}
+		}
+
+		impl CheckedDiv for $name {
+			fn checked_div(&self, other: &Self) -> Option<Self> {
+				if other.0 == 0 {
+					return None
+				}
+
+				let lhs: I129 = self.0.into();
+				let rhs: I129 = other.0.into();
+				let negative = lhs.negative != rhs.negative;
+
+				multiply_by_rational(lhs.value, Self::DIV as u128, rhs.value).ok()
+					.and_then(|value| from_i129(I129 { value, negative }))
+					.map(Self)
+			}
+		}
+
+		impl Bounded for $name {
+			fn min_value() -> Self {
+				Self(<Self as FixedPointNumber>::Inner::min_value())
+			}
+
+			fn max_value() -> Self {
+				Self(<Self as FixedPointNumber>::Inner::max_value())
+			}
+		}
+
+		impl sp_std::fmt::Debug for $name {
+			#[cfg(feature = "std")]
+			fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+				let integral = {
+					let int = self.0 / Self::DIV;
+					let signum_for_zero = if int == 0 && self.is_negative() { "-" } else { "" };
+					format!("{}{}", signum_for_zero, int)
+				};
+				let precision = Self::DIV.to_string().len() - 1;
+				let fractional = format!(
+					"{:0>weight$}",
+					((self.0 % Self::DIV) as i128).abs(),
+					weight = precision,
+				);
+				write!(f, "{}({}.{})", stringify!($name), integral, fractional)
+			}
+
+			#[cfg(not(feature = "std"))]
+			fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+				Ok(())
+			}
+		}
+
+		#[cfg(feature = "std")]
+		impl sp_std::fmt::Display for $name {
+			fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+				write!(f, "{}", self.0)
+			}
+		}
+
+		#[cfg(feature = "std")]
+		impl sp_std::str::FromStr for $name {
+			type Err = &'static str;
+
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				let inner: <Self as FixedPointNumber>::Inner = s.parse()
+					.map_err(|_| "invalid string input for fixed point number")?;
+				Ok(Self::from_inner(inner))
+			}
+		}
+
+		// Manual impl `Serialize` as serde_json does not support i128.
+		// TODO: remove impl if issue https://github.com/serde-rs/json/issues/548 fixed.
+		#[cfg(feature = "std")]
+		impl Serialize for $name {
+			fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+			where
+				S: Serializer,
+			{
+				serializer.serialize_str(&self.to_string())
+			}
+		}
+
+		// Manual impl `Deserialize` as serde_json does not support i128.
+		// TODO: remove impl if issue https://github.com/serde-rs/json/issues/548 fixed.
+		#[cfg(feature = "std")]
+		impl<'de> Deserialize<'de> for $name {
+			fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+			where
+				D: Deserializer<'de>,
+			{
+				use sp_std::str::FromStr;
+				let s = String::deserialize(deserializer)?;
+				$name::from_str(&s).map_err(|err_str| de::Error::custom(err_str))
+			}
+		}
+
+		#[cfg(test)]
+		mod $test_mod {
+			use super::*;
+
+			fn max() -> $name {
+				$name::max_value()
+			}
+
+			fn min() -> $name {
+				$name::min_value()
+			}
+
+			fn precision() -> usize {
+				$name::accuracy().to_string().len() - 1
+			}
+
+			#[test]
+			fn from_integer_works() {
+				let inner_max = <$name as FixedPointNumber>::Inner::max_value();
+				let inner_min = <$name as FixedPointNumber>::Inner::min_value();
+				let accuracy = $name::accuracy();
+
+				// Cases where integer fits.
+				let a = $name::saturating_from_integer(42);
+				assert_eq!(a.into_inner(), 42 * accuracy);
+
+				let a = $name::checked_from_integer(42);
+				assert_eq!(a.unwrap().into_inner(), 42 * accuracy);
+
+				// Max/min integers that fit.
+				let a = $name::saturating_from_integer(inner_max / accuracy);
+				assert_eq!(a.into_inner(), (inner_max / accuracy) * accuracy);
+				let a = $name::saturating_from_integer(inner_min / accuracy);
+				assert_eq!(a.into_inner(), (inner_min / accuracy) * accuracy);
+
+				// Cases where integer doesn't fit, so it saturates.
+				let a = $name::saturating_from_integer(inner_max / accuracy + 1);
+				assert_eq!(a.into_inner(), inner_max);
+				assert_eq!($name::checked_from_integer(inner_max / accuracy + 1), None);
+
+				if $name::SIGNED {
+					let a = $name::saturating_from_integer(inner_min / accuracy - 1);
+					assert_eq!(a.into_inner(), inner_min);
+					assert_eq!($name::checked_from_integer(inner_min / accuracy - 1), None);
+
+					let a = $name::saturating_from_integer(-42i32);
+					assert_eq!(a.into_inner(), 0 - 42 * accuracy);
+				}
+			}
+
+			#[test]
+			fn from_rational_works() {
+				let accuracy = $name::accuracy();
+
+				let a = $name::saturating_from_rational(5, 2);
+				assert_eq!(a.into_inner(), 5 * accuracy / 2);
+				assert_eq!(a, $name::saturating_from_rational(10, 4));
+
+				let a = $name::checked_from_rational(1, 3).unwrap();
+				assert_eq!(a.into_inner(), accuracy / 3);
+
+				// Dividing by zero.
+				assert_eq!($name::checked_from_rational(5, 0), None);
+
+				// Saturates.
+				assert_eq!($name::checked_from_rational(u128::max_value(), 1), None);
+				assert_eq!($name::saturating_from_rational(u128::max_value(), 1), max());
+
+				if $name::SIGNED {
+					let a = $name::saturating_from_rational(-5, 2);
+					assert_eq!(a.into_inner(), 0 - 5 * accuracy / 2);
+					assert_eq!($name::saturating_from_rational(5, -2), a);
+					assert_eq!($name::saturating_from_rational(-5, -2), $name::saturating_from_rational(5, 2));
+					assert_eq!($name::saturating_from_rational(i128::min_value(), 1), min());
+				} else {
+					// Negative numbers can not be represented.
+					assert_eq!($name::checked_from_rational(-5, 2), None);
+					assert_eq!($name::saturating_from_rational(-5, 2), min());
+				}
+			}
+
+			#[test]
+			#[should_panic(expected = "attempt to divide by zero")]
+			fn saturating_from_rational_by_zero_panics() {
+				let _ = $name::saturating_from_rational(1, 0);
+			}
+
+			#[test]
+			fn mul_int_works() {
+				let a = $name::saturating_from_rational(3, 2);
+				assert_eq!(a.checked_mul_int(10u32), Some(15));
+				assert_eq!(a.saturating_mul_int(10u32), 15);
+				assert_eq!(a.checked_mul_int(u128::max_value()), None);
+				assert_eq!(a.saturating_mul_int(u128::max_value()), u128::max_value());
+				assert_eq!(a.checked_mul_int(i128::max_value()), None);
+				assert_eq!(a.saturating_mul_int(i128::min_value()), i128::min_value());
+
+				assert_eq!($name::zero().saturating_mul_int(u128::max_value()), 0);
+				assert_eq!(max().saturating_mul_int(2u8), u8::max_value());
+
+				if $name::SIGNED {
+					let a = $name::saturating_from_rational(-3, 2);
+					assert_eq!(a.checked_mul_int(10i32), Some(-15));
+					assert_eq!(a.checked_mul_int(-10i32), Some(15));
+					assert_eq!(a.checked_mul_int(10u32), None);
+					assert_eq!(a.saturating_mul_int(10u32), 0);
+					assert_eq!(a.saturating_mul_int(i128::max_value()), i128::min_value());
+				}
+			}
+
+			#[test]
+			fn mul_acc_int_works() {
+				let a = $name::saturating_from_rational(1, 2);
+				assert_eq!(a.saturating_mul_acc_int(10u32), 15);
+				assert_eq!(a.saturating_mul_acc_int(u64::max_value()), u64::max_value());
+				assert_eq!($name::zero().saturating_mul_acc_int(42u8), 42);
+
+				if $name::SIGNED {
+					let a = $name::saturating_from_rational(-1, 2);
+					assert_eq!(a.saturating_mul_acc_int(10u32), 5);
+					assert_eq!(min().saturating_mul_acc_int(10u32), 0);
+				}
+			}
+
+			#[test]
+			fn div_int_works() {
+				let a = $name::saturating_from_integer(100);
+				assert_eq!(a.checked_div_int(10u8), Some(10));
+				assert_eq!(a.checked_div_int(0u8), None);
+				assert_eq!(a.saturating_div_int(3u64), 33);
+				assert_eq!($name::saturating_from_rational(1, 2).checked_div_int(1u8), Some(0));
+
+				if $name::SIGNED {
+					let a = $name::saturating_from_integer(-100);
+					assert_eq!(a.checked_div_int(10i8), Some(-10));
+					assert_eq!(a.checked_div_int(-10i8), Some(10));
+					assert_eq!(a.checked_div_int(10u8), None);
+					assert_eq!(a.saturating_div_int(10u8), 0);
+				}
+			}
+
+			#[test]
+			#[should_panic(expected = "attempt to divide by zero")]
+			fn saturating_div_int_by_zero_panics() {
+				let _ = $name::one().saturating_div_int(0u32);
+			}
+
+			#[test]
+			fn checked_mul_and_div_work() {
+				let a = $name::saturating_from_rational(3, 2);
+				let b = $name::saturating_from_integer(4);
+				assert_eq!(a.checked_mul(&b), Some($name::saturating_from_integer(6)));
+				assert_eq!(a * b, $name::saturating_from_integer(6));
+				assert_eq!(max().checked_mul(&b), None);
+				assert_eq!(max().saturating_mul(b), max());
+
+				assert_eq!(b.checked_div(&a), Some($name::saturating_from_rational(8, 3)));
+				assert_eq!(b / a, $name::saturating_from_rational(8, 3));
+				assert_eq!(a.checked_div(&$name::zero()), None);
+				assert_eq!(max().checked_div(&$name::saturating_from_rational(1, 2)), None);
+
+				if $name::SIGNED {
+					let c = $name::saturating_from_integer(-4);
+					assert_eq!(a.checked_mul(&c), Some($name::saturating_from_integer(-6)));
+					assert_eq!(c.checked_div(&a), Some($name::saturating_from_rational(-8, 3)));
+					assert_eq!(max().saturating_mul(c), min());
+				}
+			}
+
+			#[test]
+			#[should_panic(expected = "attempt to divide by zero")]
+			fn div_by_zero_panics() {
+				let _ = $name::one() / $name::zero();
+			}
+
+			#[test]
+			fn saturating_ops_work() {
+				assert_eq!(max().saturating_add($name::one()), max());
+				assert_eq!(min().saturating_sub($name::one()), min());
+				assert_eq!($name::one().saturating_add($name::one()), $name::saturating_from_integer(2));
+				assert_eq!(max().saturating_abs(), max());
+				if $name::SIGNED {
+					assert_eq!(min().saturating_abs(), max());
+					assert_eq!(
+						$name::saturating_from_integer(-2).saturating_abs(),
+						$name::saturating_from_integer(2),
+					);
+				}
+			}
+
+			#[test]
+			fn pow_works() {
+				let a = $name::saturating_from_integer(2);
+				assert_eq!(a.checked_pow(0), Some($name::one()));
+				assert_eq!(a.checked_pow(10), Some($name::saturating_from_integer(1024)));
+				assert_eq!(a.checked_pow(1000), None);
+				assert_eq!(a.saturating_pow(1000), max());
+
+				let a = $name::saturating_from_rational(1, 2);
+				assert_eq!(a.checked_pow(2), Some($name::saturating_from_rational(1, 4)));
+
+				if $name::SIGNED {
+					let a = $name::saturating_from_integer(-2);
+					assert_eq!(a.checked_pow(3), Some($name::saturating_from_integer(-8)));
+					assert_eq!(a.saturating_pow(1001), min());
+					assert_eq!(a.saturating_pow(1000), max());
+				}
+			}
+
+			#[test]
+			fn sqrt_works() {
+				assert_eq!($name::zero().checked_sqrt(), Some($name::zero()));
+				assert_eq!($name::one().checked_sqrt(), Some($name::one()));
+				assert_eq!(
+					$name::saturating_from_integer(16).checked_sqrt(),
+					Some($name::saturating_from_integer(4)),
+				);
+				assert_eq!(
+					$name::saturating_from_rational(1, 4).checked_sqrt(),
+					Some($name::saturating_from_rational(1, 2)),
+				);
+
+				// sqrt(2) rounded down to the accuracy.
+				let root = $name::saturating_from_integer(2).checked_sqrt().unwrap();
+				assert_eq!(root.into_inner(), 1_414_213_562_373_095_048);
+
+				// The largest number has a root too.
+				let root = max().checked_sqrt().unwrap();
+				assert!(root.saturating_mul(root) <= max());
+
+				if $name::SIGNED {
+					assert_eq!($name::saturating_from_integer(-4).checked_sqrt(), None);
+				}
+			}
+
+			#[test]
+			fn reciprocal_works() {
+				assert_eq!($name::zero().reciprocal(), None);
+				assert_eq!(
+					$name::saturating_from_integer(4).reciprocal(),
+					Some($name::saturating_from_rational(1, 4)),
+				);
+			}
+
+			#[test]
+			fn rounding_works() {
+				let a = $name::saturating_from_rational(5, 2);
+				assert_eq!(a.trunc(), $name::saturating_from_integer(2));
+				assert_eq!(a.frac(), $name::saturating_from_rational(1, 2));
+				assert_eq!(a.ceil(), $name::saturating_from_integer(3));
+				assert_eq!(a.floor(), $name::saturating_from_integer(2));
+				assert_eq!(a.round(), $name::saturating_from_integer(3));
+				assert_eq!($name::saturating_from_rational(12, 5).round(), $name::saturating_from_integer(2));
+				assert_eq!($name::saturating_from_integer(2).ceil(), $name::saturating_from_integer(2));
+
+				if $name::SIGNED {
+					let a = $name::saturating_from_rational(-5, 2);
+					assert_eq!(a.trunc(), $name::saturating_from_integer(-2));
+					assert_eq!(a.frac(), $name::saturating_from_rational(1, 2));
+					assert_eq!(a.ceil(), $name::saturating_from_integer(-2));
+					assert_eq!(a.floor(), $name::saturating_from_integer(-3));
+					assert_eq!(a.round(), $name::saturating_from_integer(-3));
+					assert_eq!($name::saturating_from_integer(-2).floor(), $name::saturating_from_integer(-2));
+					assert_eq!(
+						$name::saturating_from_rational(-1, 2).frac(),
+						$name::saturating_from_rational(-1, 2),
+					);
+				}
+			}
+
+			#[test]
+			fn from_perthing_works() {
+				assert_eq!($name::from(crate::Perbill::from_percent(50)), $name::saturating_from_rational(1, 2));
+				assert_eq!($name::from(crate::Percent::one()), $name::one());
+			}
+
+			#[test]
+			fn string_and_debug_format_works() {
+				use sp_std::str::FromStr;
+
+				let a = $name::saturating_from_rational(5, 2);
+				assert_eq!($name::from_str(&a.to_string()), Ok(a));
+				assert_eq!($name::from_str("not a number"), Err("invalid string input for fixed point number"));
+				assert_eq!(
+					format!("{:?}", a),
+					format!("{}(2.{:0<weight$})", stringify!($name), 5, weight = precision()),
+				);
+
+				if $name::SIGNED {
+					let a = $name::saturating_from_rational(-1, 2);
+					assert_eq!($name::from_str(&a.to_string()), Ok(a));
+					assert_eq!(
+						format!("{:?}", a),
+						format!("{}(-0.{:0<weight$})", stringify!($name), 5, weight = precision()),
+					);
+				}
+			}
+
+			#[test]
+			fn codec_works() {
+				let a = $name::saturating_from_rational(7, 3);
+				assert_eq!($name::decode(&mut &a.encode()[..]), Ok(a));
+			}
+		}
+	}
+}
+
+implement_fixed!(
+	Fixed128,
+	test_fixed128,
+	i128,
+	true,
+	1_000_000_000_000_000_000,
+	"_Fixed Point 128 bits signed, range = \
+		[-170141183460.469231731687303715884105728, 170141183460.469231731687303715884105727]_",
+);
+
+implement_fixed!(
+	FixedU128,
+	test_fixed_u128,
+	u128,
+	false,
+	1_000_000_000_000_000_000,
+	"_Fixed Point 128 bits unsigned, range = \
+		[0.000000000000000000, 340282366920.938463463374607431768211455]_",
+);
@@ -37,9 +37,11 @@ pub mod helpers_128bit;
 pub mod traits;
 mod per_things;
 mod fixed64;
+mod fixed_point;
 mod rational128;
 
 pub use fixed64::Fixed64;
+pub use fixed_point::{FixedPointNumber, FixedPointOperand, Fixed128, FixedU128};
 pub use per_things::{PerThing, Percent, PerU16, Permill, Perbill, Perquintill};
 pub use rational128::Rational128;
 
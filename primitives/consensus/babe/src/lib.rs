@@ -27,9 +27,10 @@ pub use sp_consensus_vrf::schnorrkel::{
 };
 
 use codec::{Encode, Decode};
-use sp_std::vec::Vec;
-use sp_runtime::{ConsensusEngineId, RuntimeDebug};
-use crate::digests::NextEpochDescriptor;
+use frame_metadata::{TypeInfo, Registry, Type, TypeDef, Field, registry::type_path};
+use sp_std::prelude::*;
+use sp_runtime::{ConsensusEngineId, RuntimeDebug, traits::Header};
+use crate::digests::{NextEpochDescriptor, RawPreDigest};
 
 mod app {
 	use sp_application_crypto::{app_crypto, key_types::BABE, sr25519};
@@ -51,6 +52,9 @@ pub type AuthoritySignature = app::Signature;
 /// the main Babe module. If that ever changes, then this must, too.
 pub type AuthorityId = app::Public;
 
+/// Key type for BABE module.
+pub const KEY_TYPE: sp_application_crypto::KeyTypeId = sp_application_crypto::key_types::BABE;
+
 /// The `ConsensusEngineId` of BABE.
 pub const BABE_ENGINE_ID: ConsensusEngineId = *b"BABE";
 
@@ -129,8 +133,141 @@ impl sp_consensus::SlotData for BabeConfiguration {
 	const SLOT_KEY: &'static [u8] = b"babe_configuration";
 }
 
+/// Represents an equivocation proof. An equivocation happens when a validator
+/// produces more than one block on the same slot. The proof of equivocation
+/// are the given distinct headers that were signed by the validator and which
+/// include the slot number.
+#[derive(Clone, Decode, Encode, PartialEq, Eq, RuntimeDebug)]
+pub struct EquivocationProof<H> {
+	/// The public key of the equivocator.
+	pub offender: AuthorityId,
+	/// The slot number at which the equivocation happened.
+	pub slot_number: SlotNumber,
+	/// The first header involved in the equivocation.
+	pub first_header: H,
+	/// The second header involved in the equivocation.
+	pub second_header: H,
+}
+
+impl<H: TypeInfo + 'static> TypeInfo for EquivocationProof<H> {
+	fn type_info(registry: &mut Registry) -> Type {
+		let header = registry.register::<H>();
+		Type {
+			path: type_path(module_path!(), "EquivocationProof"),
+			params: vec![header],
+			def: TypeDef::Composite(vec![
+				Field::named("offender", registry.register::<AuthorityId>()),
+				Field::named("slot_number", registry.register::<SlotNumber>()),
+				Field::named("first_header", header),
+				Field::named("second_header", header),
+			]),
+		}
+	}
+}
+
+/// Verifies the equivocation proof by making sure that: both headers have
+/// different hashes, are targetting the same slot, and have valid signatures by
+/// the same authority.
+pub fn check_equivocation_proof<H>(proof: EquivocationProof<H>) -> Result<(), ()>
+where
+	H: Header,
+{
+	use sp_application_crypto::RuntimeAppPublic;
+
+	let find_pre_digest = |header: &H| {
+		header
+			.digest()
+			.logs()
+			.iter()
+			.filter_map(|log| log.as_pre_runtime())
+			.filter_map(|(id, mut data)| if id == BABE_ENGINE_ID {
+				RawPreDigest::decode(&mut data).ok()
+			} else {
+				None
+			})
+			.next()
+	};
+
+	let verify_seal_signature = |mut header: H, offender: &AuthorityId| {
+		// the seal must be the last digest item and it isn't included in the
+		// pre-hash of the header.
+		let seal = header.digest_mut().pop()?;
+		let signature = seal
+			.as_seal()
+			.and_then(|(id, mut data)| if id == BABE_ENGINE_ID {
+				AuthoritySignature::decode(&mut data).ok()
+			} else {
+				None
+			})?;
+
+		let pre_hash = header.hash();
+
+		if offender.verify(&pre_hash, &signature) {
+			Some(())
+		} else {
+			None
+		}
+	};
+
+	let verify_proof = || {
+		// we must have different headers for the equivocation to be valid
+		if proof.first_header.hash() == proof.second_header.hash() {
+			return None;
+		}
+
+		let first_pre_digest = find_pre_digest(&proof.first_header)?;
+		let second_pre_digest = find_pre_digest(&proof.second_header)?;
+
+		// both headers must be targetting the same slot and it must
+		// be the same as the one in the proof.
+		if proof.slot_number != first_pre_digest.slot_number() ||
+			first_pre_digest.slot_number() != second_pre_digest.slot_number()
+		{
+			return None;
+		}
+
+		// both headers must have been authored by the same authority
+		if first_pre_digest.authority_index() != second_pre_digest.authority_index() {
+			return None;
+		}
+
+		// we finally verify that the expected authority has signed both headers and
+		// that the signature is valid.
+		verify_seal_signature(proof.first_header, &proof.offender)?;
+		verify_seal_signature(proof.second_header, &proof.offender)?;
+
+		Some(())
+	};
+
+	verify_proof().ok_or(())
+}
+
+/// An opaque type used to represent the key ownership proof at the runtime API
+/// boundary. The inner value is an encoded representation of the actual key
+/// ownership proof which will be parameterized when defining the runtime. At
+/// the runtime API boundary this type is unknown and as such we keep this
+/// opaque representation, implementors of the runtime API will have to make
+/// sure that all usages of `OpaqueKeyOwnershipProof` refer to the same type.
+#[derive(Decode, Encode, PartialEq)]
+pub struct OpaqueKeyOwnershipProof(Vec<u8>);
+
+impl OpaqueKeyOwnershipProof {
+	/// Create a new `OpaqueKeyOwnershipProof` using the given encoded
+	/// representation.
+	pub fn new(inner: Vec<u8>) -> OpaqueKeyOwnershipProof {
+		OpaqueKeyOwnershipProof(inner)
+	}
+
+	/// Try to decode this `OpaqueKeyOwnershipProof` into the given concrete key
+	/// ownership proof type.
+	pub fn decode<T: Decode>(self) -> Option<T> {
+		Decode::decode(&mut &self.0[..]).ok()
+	}
+}
+
 sp_api::decl_runtime_apis! {
 	/// API necessary for block authorship with BABE.
+	#[api_version(2)]
 	pub trait BabeApi {
 		/// Return the configuration for BABE. Currently,
 		/// only the value provided by this type at genesis will be used.
@@ -140,5 +277,34 @@ sp_api::decl_runtime_apis! {
 
 		/// Returns the slot number that started the current epoch.
 		fn current_epoch_start() -> SlotNumber;
+
+		/// Generates a proof of key ownership for the given authority in the
+		/// current epoch. An example usage of this module is coupled with the
+		/// session historical module to prove that a given authority key is
+		/// tied to a given staking identity during a specific session. Proofs
+		/// of key ownership are necessary for submitting equivocation reports.
+		/// NOTE: even though the API takes a `slot_number` as parameter the current
+		/// implementations ignores this parameter and instead relies on this
+		/// method being called at the correct block height, i.e. any point at
+		/// which the epoch for the given slot is live on-chain. Future
+		/// implementations will instead use indexed data through an offchain
+		/// worker, not requiring older states to be available.
+		fn generate_key_ownership_proof(
+			slot_number: SlotNumber,
+			authority_id: AuthorityId,
+		) -> Option<OpaqueKeyOwnershipProof>;
+
+		/// Submits an unsigned extrinsic to report an equivocation. The caller
+		/// must provide the equivocation proof and a key ownership proof
+		/// (should be obtained using `generate_key_ownership_proof`). The
+		/// extrinsic will be unsigned and should only be accepted for local
+		/// authorship (not to be broadcast to the network). This method returns
+		/// `None` when creation of the extrinsic fails, e.g. if equivocation
+		/// reporting is disabled for the given runtime (i.e. this method is
+		/// hardcoded to return `None`). Only useful in an offchain context.
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: EquivocationProof<Block::Header>,
+			key_owner_proof: OpaqueKeyOwnershipProof,
+		) -> Option<()>;
 	}
 }
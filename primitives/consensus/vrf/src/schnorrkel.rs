@@ -17,6 +17,7 @@
 //! Schnorrkel-based VRF.
 
 use codec::{Encode, Decode};
+use frame_metadata::{TypeInfo, Registry, Type, registry::type_path};
 use sp_runtime::RuntimeDebug;
 use sp_std::ops::{Deref, DerefMut};
 #[cfg(feature = "std")]
@@ -46,6 +47,15 @@ pub const RANDOMNESS_LENGTH: usize = VRF_OUTPUT_LENGTH;
 #[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
 pub struct RawVRFOutput(pub [u8; VRF_OUTPUT_LENGTH]);
 
+impl TypeInfo for RawVRFOutput {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type::wrapper(
+			type_path(module_path!(), "RawVRFOutput"),
+			registry.register::<[u8; VRF_OUTPUT_LENGTH]>(),
+		)
+	}
+}
+
 impl Deref for RawVRFOutput {
 	type Target = [u8; VRF_OUTPUT_LENGTH];
 	fn deref(&self) -> &Self::Target { &self.0 }
@@ -28,6 +28,9 @@ pub mod storage;
 #[cfg(feature = "std")]
 pub mod testing;
 
+/// Local Storage Prefix used by the Offchain Worker API and by offchain indexing.
+pub const STORAGE_PREFIX: &[u8] = b"storage";
+
 /// Offchain workers local storage.
 pub trait OffchainStorage: Clone + Send + Sync {
 	/// Persist a value in storage under given key and prefix.
@@ -46,6 +49,60 @@ pub trait OffchainStorage: Clone + Send + Sync {
 		old_value: Option<&[u8]>,
 		new_value: &[u8],
 	) -> bool;
+
+	/// Persist a value in fork-aware storage under given key and prefix,
+	/// in the context of the given block.
+	fn local_set(&mut self, at: &ForkContext, prefix: &[u8], key: &[u8], value: &[u8]);
+
+	/// Retrieve a value from fork-aware storage under given key and prefix,
+	/// as seen in the context of the given block.
+	fn local_get(&self, at: &ForkContext, prefix: &[u8], key: &[u8]) -> Option<Vec<u8>>;
+
+	/// Replace the value in fork-aware storage if given old_value matches the
+	/// one seen in the context of the given block.
+	///
+	/// Returns `true` if the value has been set and false otherwise.
+	fn local_compare_and_set(
+		&mut self,
+		at: &ForkContext,
+		prefix: &[u8],
+		key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	) -> bool;
+
+	/// Finalize fork-aware storage up to the block with the given number.
+	///
+	/// Values set at blocks up to `number` for which `is_canonical(number, hash)`
+	/// holds are moved to the finalized state (in block order), values set at any
+	/// other block up to `number` are discarded.
+	fn local_finalize(&mut self, number: u64, is_canonical: &dyn Fn(u64, &[u8]) -> bool);
+}
+
+/// The block context of a fork-aware (`StorageKind::LOCAL`) storage access.
+///
+/// Values set in the context of a block are only visible to that block and
+/// its descendants, until the block gets finalized.
+#[derive(Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct ForkContext {
+	/// The number of the block.
+	pub number: u64,
+	/// The hashes of the block and of all of its non-finalized ancestors,
+	/// starting with the block itself. An empty ancestry refers to the
+	/// finalized state.
+	pub ancestry: Vec<Vec<u8>>,
+}
+
+impl ForkContext {
+	/// The context of the finalized state.
+	pub fn finalized() -> Self {
+		Self::default()
+	}
+
+	/// The hash of the block, or `None` for the finalized state.
+	pub fn block_hash(&self) -> Option<&[u8]> {
+		self.ancestry.first().map(|h| &h[..])
+	}
 }
 
 /// A type of supported crypto.
@@ -17,12 +17,16 @@
 //! In-memory implementation of offchain workers database.
 
 use std::collections::hash_map::{HashMap, Entry};
-use crate::offchain::OffchainStorage;
+use crate::offchain::{ForkContext, OffchainStorage};
 
 /// In-memory storage for offchain workers.
 #[derive(Debug, Clone, Default)]
 pub struct InMemOffchainStorage {
 	storage: HashMap<Vec<u8>, Vec<u8>>,
+	/// Fork-aware values of the finalized state.
+	local_finalized: HashMap<Vec<u8>, Vec<u8>>,
+	/// Fork-aware values set at non-finalized blocks, with the block number.
+	local_blocks: HashMap<Vec<u8>, (u64, HashMap<Vec<u8>, Vec<u8>>)>,
 }
 
 impl OffchainStorage for InMemOffchainStorage {
@@ -57,4 +61,65 @@ impl OffchainStorage for InMemOffchainStorage {
 			_ => false,
 		}
 	}
+
+	fn local_set(&mut self, at: &ForkContext, prefix: &[u8], key: &[u8], value: &[u8]) {
+		let key = prefix.iter().chain(key).cloned().collect();
+
+		match at.block_hash() {
+			Some(hash) => {
+				self.local_blocks
+					.entry(hash.to_vec())
+					.or_insert_with(|| (at.number, HashMap::new()))
+					.1
+					.insert(key, value.to_vec());
+			},
+			None => {
+				self.local_finalized.insert(key, value.to_vec());
+			},
+		}
+	}
+
+	fn local_get(&self, at: &ForkContext, prefix: &[u8], key: &[u8]) -> Option<Vec<u8>> {
+		let key: Vec<u8> = prefix.iter().chain(key).cloned().collect();
+
+		at.ancestry.iter()
+			.filter_map(|hash| self.local_blocks.get(hash))
+			.find_map(|(_, values)| values.get(&key))
+			.or_else(|| self.local_finalized.get(&key))
+			.cloned()
+	}
+
+	fn local_compare_and_set(
+		&mut self,
+		at: &ForkContext,
+		prefix: &[u8],
+		key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	) -> bool {
+		let is_set = self.local_get(at, prefix, key).as_ref().map(|v| &v[..]) == old_value;
+
+		if is_set {
+			self.local_set(at, prefix, key, new_value);
+		}
+
+		is_set
+	}
+
+	fn local_finalize(&mut self, number: u64, is_canonical: &dyn Fn(u64, &[u8]) -> bool) {
+		let mut finalized = self.local_blocks.iter()
+			.filter(|(_, (block_number, _))| *block_number <= number)
+			.map(|(hash, (block_number, _))| (*block_number, hash.clone()))
+			.collect::<Vec<_>>();
+		finalized.sort();
+
+		for (block_number, hash) in finalized {
+			let (_, values) = self.local_blocks.remove(&hash)
+				.expect("hash was collected from `local_blocks` above; qed");
+
+			if is_canonical(block_number, &hash) {
+				self.local_finalized.extend(values);
+			}
+		}
+	}
 }
@@ -149,6 +149,12 @@ pub trait Externalities: ExtensionStore {
 		value: Option<Vec<u8>>,
 	);
 
+	/// Set or clear an entry (`key`) of the offchain database.
+	///
+	/// The change is written to the offchain database when the block is imported, and only if
+	/// offchain indexing is enabled for the execution. Otherwise it is ignored.
+	fn set_offchain_storage(&mut self, key: &[u8], value: Option<&[u8]>);
+
 	/// Get the identity of the chain.
 	fn chain_id(&self) -> u64;
 
@@ -177,6 +183,27 @@ pub trait Externalities: ExtensionStore {
 	/// The returned hash is defined by the `Block` and is SCALE encoded.
 	fn storage_changes_root(&mut self, parent: &[u8]) -> Result<Option<Vec<u8>>, ()>;
 
+	/// Start a new nested storage transaction.
+	///
+	/// All changes made from now on can be either committed or rolled back as a whole. Every
+	/// call must be matched by a call to either `storage_rollback_transaction` or
+	/// `storage_commit_transaction`.
+	fn storage_start_transaction(&mut self);
+
+	/// Rollback the last storage transaction started by `storage_start_transaction`.
+	///
+	/// Any changes made during that transaction are discarded.
+	///
+	/// Returns an error when there is no open transaction.
+	fn storage_rollback_transaction(&mut self) -> Result<(), ()>;
+
+	/// Commit the last storage transaction started by `storage_start_transaction`.
+	///
+	/// Any changes made during that transaction are kept.
+	///
+	/// Returns an error when there is no open transaction.
+	fn storage_commit_transaction(&mut self) -> Result<(), ()>;
+
 	/// !!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!
 	/// Benchmarking related functionality and shouldn't be used anywhere else!
 	/// !!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!
@@ -24,9 +24,11 @@ extern crate alloc;
 #[cfg(feature = "std")]
 use serde::Serialize;
 use codec::{Encode, Decode, Input, Codec};
-use sp_runtime::{ConsensusEngineId, RuntimeDebug};
+use frame_metadata::{TypeInfo, Registry, Type, TypeDef, Field, Variant, registry::type_path};
+use sp_runtime::{ConsensusEngineId, RuntimeDebug, traits::NumberFor};
 use sp_std::borrow::Cow;
-use sp_std::vec::Vec;
+use sp_std::marker::PhantomData;
+use sp_std::prelude::*;
 
 mod app {
 	use sp_application_crypto::{app_crypto, key_types::GRANDPA, ed25519};
@@ -44,6 +46,9 @@ pub type AuthorityId = app::Public;
 /// Signature for a Grandpa authority.
 pub type AuthoritySignature = app::Signature;
 
+/// Key type for GRANDPA module.
+pub const KEY_TYPE: sp_application_crypto::KeyTypeId = sp_application_crypto::key_types::GRANDPA;
+
 /// The `ConsensusEngineId` of GRANDPA.
 pub const GRANDPA_ENGINE_ID: ConsensusEngineId = *b"FRNK";
 
@@ -157,6 +162,268 @@ impl<N: Codec> ConsensusLog<N> {
 	}
 }
 
+/// Proof of voter misbehavior on a given set id. Misbehavior/equivocation in
+/// GRANDPA happens when a voter votes on the same round (either at prevote or
+/// precommit stage) for different blocks. Proving is achieved by collecting the
+/// signed messages of conflicting votes.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug)]
+pub struct EquivocationProof<H, N> {
+	set_id: SetId,
+	equivocation: Equivocation<H, N>,
+}
+
+impl<H, N> EquivocationProof<H, N> {
+	/// Create a new `EquivocationProof` for the given set id and using the
+	/// given equivocation as proof.
+	pub fn new(set_id: SetId, equivocation: Equivocation<H, N>) -> Self {
+		EquivocationProof {
+			set_id,
+			equivocation,
+		}
+	}
+
+	/// Returns the set id at which the equivocation occurred.
+	pub fn set_id(&self) -> SetId {
+		self.set_id
+	}
+
+	/// Returns the round number at which the equivocation occurred.
+	pub fn round(&self) -> RoundNumber {
+		match self.equivocation {
+			Equivocation::Prevote(ref equivocation) => equivocation.round_number,
+			Equivocation::Precommit(ref equivocation) => equivocation.round_number,
+		}
+	}
+
+	/// Returns the authority id of the equivocator.
+	pub fn offender(&self) -> &AuthorityId {
+		self.equivocation.offender()
+	}
+}
+
+/// Wrapper object for GRANDPA equivocation proofs, useful for unifying prevote
+/// and precommit equivocations under a common type.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug)]
+pub enum Equivocation<H, N> {
+	/// Proof of equivocation at prevote stage.
+	Prevote(grandpa::Equivocation<AuthorityId, grandpa::Prevote<H, N>, AuthoritySignature>),
+	/// Proof of equivocation at precommit stage.
+	Precommit(grandpa::Equivocation<AuthorityId, grandpa::Precommit<H, N>, AuthoritySignature>),
+}
+
+impl<H, N> From<grandpa::Equivocation<AuthorityId, grandpa::Prevote<H, N>, AuthoritySignature>>
+	for Equivocation<H, N>
+{
+	fn from(
+		equivocation: grandpa::Equivocation<AuthorityId, grandpa::Prevote<H, N>, AuthoritySignature>,
+	) -> Self {
+		Equivocation::Prevote(equivocation)
+	}
+}
+
+impl<H, N> From<grandpa::Equivocation<AuthorityId, grandpa::Precommit<H, N>, AuthoritySignature>>
+	for Equivocation<H, N>
+{
+	fn from(
+		equivocation: grandpa::Equivocation<AuthorityId, grandpa::Precommit<H, N>, AuthoritySignature>,
+	) -> Self {
+		Equivocation::Precommit(equivocation)
+	}
+}
+
+impl<H: TypeInfo + 'static, N: TypeInfo + 'static> TypeInfo for EquivocationProof<H, N> {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type {
+			path: type_path(module_path!(), "EquivocationProof"),
+			params: vec![registry.register::<H>(), registry.register::<N>()],
+			def: TypeDef::Composite(vec![
+				Field::named("set_id", registry.register::<SetId>()),
+				Field::named("equivocation", registry.register::<Equivocation<H, N>>()),
+			]),
+		}
+	}
+}
+
+impl<H: TypeInfo + 'static, N: TypeInfo + 'static> TypeInfo for Equivocation<H, N> {
+	fn type_info(registry: &mut Registry) -> Type {
+		let prevote = registry
+			.register::<GrandpaEquivocationInfo<GrandpaVoteInfo<H, N, PrevoteKind>>>();
+		let precommit = registry
+			.register::<GrandpaEquivocationInfo<GrandpaVoteInfo<H, N, PrecommitKind>>>();
+		Type {
+			path: type_path(module_path!(), "Equivocation"),
+			params: vec![registry.register::<H>(), registry.register::<N>()],
+			def: TypeDef::Variant(vec![
+				Variant::new("Prevote", vec![Field::unnamed(prevote)], 0),
+				Variant::new("Precommit", vec![Field::unnamed(precommit)], 1),
+			]),
+		}
+	}
+}
+
+/// The kind of a vote described by `GrandpaVoteInfo`.
+trait VoteKind {
+	const NAME: &'static str;
+}
+
+enum PrevoteKind {}
+enum PrecommitKind {}
+
+impl VoteKind for PrevoteKind {
+	const NAME: &'static str = "Prevote";
+}
+
+impl VoteKind for PrecommitKind {
+	const NAME: &'static str = "Precommit";
+}
+
+/// Describes `grandpa::Prevote` and `grandpa::Precommit` in the metadata, as the foreign crate
+/// does not implement `TypeInfo`.
+struct GrandpaVoteInfo<H, N, K>(PhantomData<(H, N, K)>);
+
+impl<H, N, K> TypeInfo for GrandpaVoteInfo<H, N, K> where
+	H: TypeInfo + 'static,
+	N: TypeInfo + 'static,
+	K: VoteKind + 'static,
+{
+	fn type_info(registry: &mut Registry) -> Type {
+		let hash = registry.register::<H>();
+		let number = registry.register::<N>();
+		Type {
+			path: type_path("finality_grandpa", K::NAME),
+			params: vec![hash, number],
+			def: TypeDef::Composite(vec![
+				Field::named("target_hash", hash),
+				Field::named("target_number", number),
+			]),
+		}
+	}
+}
+
+/// Describes `grandpa::Equivocation` of the vote `V` in the metadata.
+struct GrandpaEquivocationInfo<V>(PhantomData<V>);
+
+impl<V: TypeInfo + 'static> TypeInfo for GrandpaEquivocationInfo<V> {
+	fn type_info(registry: &mut Registry) -> Type {
+		let identity = registry.register::<AuthorityId>();
+		let vote = registry.register::<V>();
+		let signed_vote = registry.register::<(V, AuthoritySignature)>();
+		Type {
+			path: type_path("finality_grandpa", "Equivocation"),
+			params: vec![identity, vote, registry.register::<AuthoritySignature>()],
+			def: TypeDef::Composite(vec![
+				Field::named("round_number", registry.register::<RoundNumber>()),
+				Field::named("identity", identity),
+				Field::named("first", signed_vote),
+				Field::named("second", signed_vote),
+			]),
+		}
+	}
+}
+
+impl<H, N> Equivocation<H, N> {
+	/// Returns the authority id of the equivocator.
+	pub fn offender(&self) -> &AuthorityId {
+		match self {
+			Equivocation::Prevote(ref equivocation) => &equivocation.identity,
+			Equivocation::Precommit(ref equivocation) => &equivocation.identity,
+		}
+	}
+}
+
+/// Verifies the equivocation proof by making sure that both votes target
+/// different blocks and that its signatures are valid.
+pub fn check_equivocation_proof<H, N>(report: EquivocationProof<H, N>) -> Result<(), ()>
+where
+	H: Clone + Encode + PartialEq,
+	N: Clone + Encode + PartialEq,
+{
+	// NOTE: the bare `Prevote` and `Precommit` types don't share any trait,
+	// this is implemented as a macro to avoid duplication.
+	macro_rules! check {
+		( $equivocation:expr, $message:expr ) => {
+			// if both votes have the same target the equivocation is invalid.
+			if $equivocation.first.0.target_hash == $equivocation.second.0.target_hash &&
+				$equivocation.first.0.target_number == $equivocation.second.0.target_number
+			{
+				return Err(());
+			}
+
+			// check signatures on both votes are valid
+			check_message_signature(
+				&$message($equivocation.first.0),
+				&$equivocation.identity,
+				&$equivocation.first.1,
+				$equivocation.round_number,
+				report.set_id,
+			)?;
+
+			check_message_signature(
+				&$message($equivocation.second.0),
+				&$equivocation.identity,
+				&$equivocation.second.1,
+				$equivocation.round_number,
+				report.set_id,
+			)?;
+
+			return Ok(());
+		};
+	}
+
+	match report.equivocation {
+		Equivocation::Prevote(equivocation) => {
+			check!(equivocation, grandpa::Message::Prevote);
+		}
+		Equivocation::Precommit(equivocation) => {
+			check!(equivocation, grandpa::Message::Precommit);
+		}
+	}
+}
+
+/// Encode round message localized to a given round and set id.
+pub fn localized_payload<E: Encode>(round: RoundNumber, set_id: SetId, message: &E) -> Vec<u8> {
+	let mut buf = Vec::new();
+	localized_payload_with_buffer(round, set_id, message, &mut buf);
+	buf
+}
+
+/// Encode round message localized to a given round and set id using the given
+/// buffer. The given buffer will be cleared and the resulting encoded payload
+/// will always be written to the start of the buffer.
+pub fn localized_payload_with_buffer<E: Encode>(
+	round: RoundNumber,
+	set_id: SetId,
+	message: &E,
+	buf: &mut Vec<u8>,
+) {
+	buf.clear();
+	(message, round, set_id).encode_to(buf)
+}
+
+/// Check a message signature by encoding the message as a localized payload and
+/// verifying the provided signature using the expected authority id.
+pub fn check_message_signature<H, N>(
+	message: &grandpa::Message<H, N>,
+	id: &AuthorityId,
+	signature: &AuthoritySignature,
+	round: RoundNumber,
+	set_id: SetId,
+) -> Result<(), ()>
+where
+	H: Encode,
+	N: Encode,
+{
+	use sp_application_crypto::RuntimeAppPublic;
+
+	let buf = localized_payload(round, set_id, message);
+
+	if id.verify(&buf, signature) {
+		Ok(())
+	} else {
+		Err(())
+	}
+}
+
 /// WASM function call to check for pending changes.
 pub const PENDING_CHANGE_CALL: &str = "grandpa_pending_change";
 /// WASM function call to get current GRANDPA authorities.
@@ -211,6 +478,29 @@ impl<'a> Decode for VersionedAuthorityList<'a> {
 	}
 }
 
+/// An opaque type used to represent the key ownership proof at the runtime API
+/// boundary. The inner value is an encoded representation of the actual key
+/// ownership proof which will be parameterized when defining the runtime. At
+/// the runtime API boundary this type is unknown and as such we keep this
+/// opaque representation, implementors of the runtime API will have to make
+/// sure that all usages of `OpaqueKeyOwnershipProof` refer to the same type.
+#[derive(Decode, Encode, PartialEq)]
+pub struct OpaqueKeyOwnershipProof(Vec<u8>);
+
+impl OpaqueKeyOwnershipProof {
+	/// Create a new `OpaqueKeyOwnershipProof` using the given encoded
+	/// representation.
+	pub fn new(inner: Vec<u8>) -> OpaqueKeyOwnershipProof {
+		OpaqueKeyOwnershipProof(inner)
+	}
+
+	/// Try to decode this `OpaqueKeyOwnershipProof` into the given concrete key
+	/// ownership proof type.
+	pub fn decode<T: Decode>(self) -> Option<T> {
+		Decode::decode(&mut &self.0[..]).ok()
+	}
+}
+
 sp_api::decl_runtime_apis! {
 	/// APIs for integrating the GRANDPA finality gadget into runtimes.
 	/// This should be implemented on the runtime side.
@@ -221,7 +511,7 @@ sp_api::decl_runtime_apis! {
 	/// applied in the runtime after those N blocks have passed.
 	///
 	/// The consensus protocol will coordinate the handoff externally.
-	#[api_version(2)]
+	#[api_version(3)]
 	pub trait GrandpaApi {
 		/// Get the current GRANDPA authorities and weights. This should not change except
 		/// for when changes are scheduled and the corresponding delay has passed.
@@ -230,5 +520,32 @@ sp_api::decl_runtime_apis! {
 		/// used to finalize descendants of this block (B+1, B+2, ...). The block B itself
 		/// is finalized by the authorities from block B-1.
 		fn grandpa_authorities() -> AuthorityList;
+
+		/// Submits an extrinsic to report an equivocation. The caller must
+		/// provide the equivocation proof and a key ownership proof (should be
+		/// obtained using `generate_key_ownership_proof`). This method will
+		/// sign the extrinsic with any reporting keys available in the keystore
+		/// and will push the transaction to the pool. Only useful in an
+		/// offchain context.
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: EquivocationProof<Block::Hash, NumberFor<Block>>,
+			key_owner_proof: OpaqueKeyOwnershipProof,
+		) -> Option<()>;
+
+		/// Generates a proof of key ownership for the given authority in the
+		/// given set. An example usage of this module is coupled with the
+		/// session historical module to prove that a given authority key is
+		/// tied to a given staking identity during a specific session. Proofs
+		/// of key ownership are necessary for submitting equivocation reports.
+		/// NOTE: even though the API takes a `set_id` as parameter the current
+		/// implementations ignore this parameter and instead rely on this
+		/// method being called at the correct block height, i.e. any point at
+		/// which the given set id is live on-chain. Future implementations will
+		/// instead use indexed data through an offchain worker, not requiring
+		/// older states to be available.
+		fn generate_key_ownership_proof(
+			set_id: SetId,
+			authority_id: AuthorityId,
+		) -> Option<OpaqueKeyOwnershipProof>;
 	}
 }
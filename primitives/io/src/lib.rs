@@ -311,6 +311,46 @@ pub trait Storage {
 			.expect("Invalid child definition");
 		self.next_child_storage_key(storage_key, child_info, key)
 	}
+
+	/// Start a new nested transaction.
+	///
+	/// This allows to either commit or roll back all changes that are made after this call.
+	/// For every transaction there must be a matching call to either `rollback_transaction`
+	/// or `commit_transaction`. This is also effective for all values manipulated using the
+	/// child storage API.
+	///
+	/// # Warning
+	///
+	/// This is a low level API that is potentially dangerous as it can easily result
+	/// in unbalanced transactions. FRAME users should use `frame_support::storage::with_transaction`
+	/// instead.
+	fn start_transaction(&mut self) {
+		self.storage_start_transaction();
+	}
+
+	/// Rollback the last transaction started by `start_transaction`.
+	///
+	/// Any changes made during that transaction are discarded.
+	///
+	/// # Panics
+	///
+	/// Will panic if there is no open transaction.
+	fn rollback_transaction(&mut self) {
+		self.storage_rollback_transaction()
+			.expect("No open transaction that can be rolled back.");
+	}
+
+	/// Commit the last transaction started by `start_transaction`.
+	///
+	/// Any changes made during that transaction are committed.
+	///
+	/// # Panics
+	///
+	/// Will panic if there is no open transaction.
+	fn commit_transaction(&mut self) {
+		self.storage_commit_transaction()
+			.expect("No open transaction that can be committed.");
+	}
 }
 
 /// Interface that provides trie related functionality.
@@ -754,6 +794,25 @@ pub trait Offchain {
 	}
 }
 
+/// Interface that provides functions for indexing data into the offchain database
+/// during block execution.
+///
+/// The data is written to the persistent offchain storage (see `StorageKind::PERSISTENT`)
+/// when the block is imported, but only if the node runs with offchain indexing enabled.
+/// Otherwise these calls are no-ops.
+#[runtime_interface]
+pub trait OffchainIndex {
+	/// Write a key value pair to the offchain database.
+	fn set(&mut self, key: &[u8], value: &[u8]) {
+		self.set_offchain_storage(key, Some(value));
+	}
+
+	/// Remove a key and its associated value from the offchain database.
+	fn clear(&mut self, key: &[u8]) {
+		self.set_offchain_storage(key, None);
+	}
+}
+
 /// Wasm only interface that provides functions for calling into the allocator.
 #[runtime_interface(wasm_only)]
 trait Allocator {
@@ -935,6 +994,7 @@ pub type SubstrateHostFunctions = (
 	storage::HostFunctions,
 	misc::HostFunctions,
 	offchain::HostFunctions,
+	offchain_index::HostFunctions,
 	crypto::HostFunctions,
 	hashing::HostFunctions,
 	allocator::HostFunctions,
@@ -989,6 +1049,26 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn offchain_index_works() {
+		let mut t = TestExternalities::default();
+		t.set_offchain_indexing(true);
+
+		t.execute_with(|| {
+			offchain_index::set(b"kept", b"value");
+			offchain_index::set(b"cleared", b"value");
+			offchain_index::clear(b"cleared");
+		});
+
+		assert_eq!(
+			t.offchain_changes(),
+			vec![
+				(b"cleared".to_vec(), None),
+				(b"kept".to_vec(), Some(b"value".to_vec())),
+			],
+		);
+	}
+
 	#[test]
 	fn clear_prefix_works() {
 		let mut t = BasicExternalities::new(Storage {
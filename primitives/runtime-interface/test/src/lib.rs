@@ -41,6 +41,8 @@ fn call_wasm_method<HF: HostFunctionsT>(binary: &[u8], method: &str) -> TestExte
 		host_functions,
 		false,
 		8,
+		None,
+		None,
 	);
 	executor.call_in_wasm(
 		binary,
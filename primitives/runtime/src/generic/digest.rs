@@ -23,6 +23,7 @@ use sp_std::prelude::*;
 
 use crate::ConsensusEngineId;
 use crate::codec::{Decode, Encode, Input, Error};
+use frame_metadata::{TypeInfo, Registry, Type, TypeDef, Field, Variant, registry::type_path};
 use sp_core::{ChangesTrieConfiguration, RuntimeDebug};
 
 /// Generic header digest.
@@ -70,6 +71,18 @@ impl<Hash: Encode + Decode> Digest<Hash> {
 	}
 }
 
+impl<Hash: Encode + Decode + TypeInfo + 'static> TypeInfo for Digest<Hash> {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type {
+			path: type_path(module_path!(), "Digest"),
+			params: vec![registry.register::<Hash>()],
+			def: TypeDef::Composite(vec![
+				Field::named("logs", registry.register::<Vec<DigestItem<Hash>>>()),
+			]),
+		}
+	}
+}
+
 
 /// Digest item that is able to encode/decode 'system' digest items and
 /// provide opaque access to other items.
@@ -130,6 +143,51 @@ pub enum ChangesTrieSignal {
 	NewConfiguration(Option<ChangesTrieConfiguration>),
 }
 
+/// The variant indices follow `DigestItemType`, which is used to encode the items.
+impl<Hash: TypeInfo + 'static> TypeInfo for DigestItem<Hash> {
+	fn type_info(registry: &mut Registry) -> Type {
+		let engine_id = registry.register::<ConsensusEngineId>();
+		let data = registry.register::<Vec<u8>>();
+		let engine_message = || vec![Field::unnamed(engine_id), Field::unnamed(data)];
+		Type {
+			path: type_path(module_path!(), "DigestItem"),
+			params: vec![registry.register::<Hash>()],
+			def: TypeDef::Variant(vec![
+				Variant::new("Other", vec![Field::unnamed(data)], DigestItemType::Other as u8),
+				Variant::new(
+					"ChangesTrieRoot",
+					vec![Field::unnamed(registry.register::<Hash>())],
+					DigestItemType::ChangesTrieRoot as u8,
+				),
+				Variant::new("Consensus", engine_message(), DigestItemType::Consensus as u8),
+				Variant::new("Seal", engine_message(), DigestItemType::Seal as u8),
+				Variant::new("PreRuntime", engine_message(), DigestItemType::PreRuntime as u8),
+				Variant::new(
+					"ChangesTrieSignal",
+					vec![Field::unnamed(registry.register::<ChangesTrieSignal>())],
+					DigestItemType::ChangesTrieSignal as u8,
+				),
+			]),
+		}
+	}
+}
+
+impl TypeInfo for ChangesTrieSignal {
+	fn type_info(registry: &mut Registry) -> Type {
+		Type {
+			path: type_path(module_path!(), "ChangesTrieSignal"),
+			params: Vec::new(),
+			def: TypeDef::Variant(vec![
+				Variant::new(
+					"NewConfiguration",
+					vec![Field::unnamed(registry.register::<Option<ChangesTrieConfiguration>>())],
+					0,
+				),
+			]),
+		}
+	}
+}
+
 #[cfg(feature = "std")]
 impl<Hash: Encode> serde::Serialize for DigestItem<Hash> {
 	fn serialize<S>(&self, seq: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
@@ -25,8 +25,10 @@ use crate::traits::{
 	MaybeMallocSizeOf,
 };
 use crate::generic::Digest;
+use frame_metadata::{TypeInfo, Registry, Type, TypeDef, Field, registry::type_path};
 use sp_core::U256;
 use sp_std::{
+	prelude::*,
 	convert::TryFrom,
 	fmt::Debug,
 };
@@ -114,6 +116,27 @@ impl<Number, Hash> Encode for Header<Number, Hash> where
 	}
 }
 
+impl<Number, Hash> TypeInfo for Header<Number, Hash> where
+	Number: HasCompact + Copy + Into<U256> + TryFrom<U256> + TypeInfo + 'static,
+	Hash: HashT + 'static,
+	Hash::Output: Decode + TypeInfo + 'static,
+{
+	fn type_info(registry: &mut Registry) -> Type {
+		let hash = registry.register::<Hash::Output>();
+		Type {
+			path: type_path(module_path!(), "Header"),
+			params: vec![registry.register::<Number>(), hash],
+			def: TypeDef::Composite(vec![
+				Field::named("parent_hash", hash),
+				Field::named("number", registry.register::<codec::Compact<Number>>()),
+				Field::named("state_root", hash),
+				Field::named("extrinsics_root", hash),
+				Field::named("digest", registry.register::<Digest<Hash::Output>>()),
+			]),
+		}
+	}
+}
+
 impl<Number, Hash> codec::EncodeLike for Header<Number, Hash> where
 	Number: HasCompact + Copy + Into<U256> + TryFrom<U256>,
 	Hash: HashT,
@@ -70,6 +70,7 @@ pub use sp_core::RuntimeDebug;
 /// Re-export top-level arithmetic stuff.
 pub use sp_arithmetic::{
 	Perquintill, Perbill, Permill, Percent, PerU16, Rational128, Fixed64, PerThing,
+	Fixed128, FixedU128, FixedPointNumber, FixedPointOperand,
 	traits::SaturatedConversion,
 };
 /// Re-export 128 bit helpers.
@@ -384,6 +385,28 @@ pub enum DispatchError {
 	},
 }
 
+impl frame_metadata::TypeInfo for DispatchError {
+	fn type_info(registry: &mut frame_metadata::Registry) -> frame_metadata::Type {
+		use frame_metadata::{Field, Variant};
+
+		let u8_index = registry.register::<u8>();
+		frame_metadata::Type {
+			path: frame_metadata::registry::type_path(module_path!(), "DispatchError"),
+			params: Vec::new(),
+			def: frame_metadata::TypeDef::Variant(vec![
+				Variant::new("Other", Vec::new(), 0),
+				Variant::new("CannotLookup", Vec::new(), 1),
+				Variant::new("BadOrigin", Vec::new(), 2),
+				Variant::new(
+					"Module",
+					vec![Field::named("index", u8_index), Field::named("error", u8_index)],
+					3,
+				),
+			]),
+		}
+	}
+}
+
 /// Result of a `Dispatchable` which contains the `DispatchResult` and additional information
 /// about the `Dispatchable` that is only known post dispatch.
 #[derive(Eq, PartialEq, Clone, Copy, Encode, Decode, RuntimeDebug)]
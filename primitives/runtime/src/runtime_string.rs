@@ -15,6 +15,7 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 use codec::{Encode, Decode};
+use frame_metadata::{TypeInfo, Registry, Type, TypeDef, TypeDefPrimitive};
 use sp_core::RuntimeDebug;
 use sp_std::vec::Vec;
 
@@ -53,6 +54,12 @@ impl Default for RuntimeString {
 	}
 }
 
+impl TypeInfo for RuntimeString {
+	fn type_info(_: &mut Registry) -> Type {
+		Type::builtin(TypeDef::Primitive(TypeDefPrimitive::Str))
+	}
+}
+
 impl PartialEq for RuntimeString {
 	fn eq(&self, other: &Self) -> bool {
 		self.as_ref() == other.as_ref()
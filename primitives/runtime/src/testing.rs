@@ -371,14 +371,21 @@ impl<Origin, Call, Extra, Info> Applyable for TestXt<Call, Extra> where
 		info: Self::DispatchInfo,
 		len: usize,
 	) -> ApplyExtrinsicResult {
-		let maybe_who = if let Some((who, extra)) = self.signature {
-			Extra::pre_dispatch(extra, &who, &self.call, info, len)?;
-			Some(who)
+		let (maybe_who, pre) = if let Some((who, extra)) = self.signature {
+			let pre = Extra::pre_dispatch(extra, &who, &self.call, info.clone(), len)?;
+			(Some(who), pre)
 		} else {
-			Extra::pre_dispatch_unsigned(&self.call, info, len)?;
-			None
+			let pre = Extra::pre_dispatch_unsigned(&self.call, info.clone(), len)?;
+			(None, pre)
 		};
 
-		Ok(self.call.dispatch(maybe_who.into()).map(|_| ()).map_err(|e| e.error))
+		let res = self.call.dispatch(maybe_who.into());
+		let post_info = match &res {
+			Ok(post_info) => *post_info,
+			Err(err) => err.post_info,
+		};
+		let res = res.map(|_| ()).map_err(|e| e.error);
+		Extra::post_dispatch(pre, info, &post_info, len, &res)?;
+		Ok(res)
 	}
 }
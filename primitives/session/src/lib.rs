@@ -26,6 +26,7 @@ use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 use sp_api::ProvideRuntimeApi;
 
 use sp_core::crypto::KeyTypeId;
+use sp_staking::SessionIndex;
 
 sp_api::decl_runtime_apis! {
 	/// Session keys runtime api.
@@ -46,6 +47,34 @@ sp_api::decl_runtime_apis! {
 	}
 }
 
+/// Number of validators in a given session.
+pub type ValidatorCount = u32;
+
+/// Something that knows the session it belongs to, e.g. a proof of session
+/// membership.
+pub trait GetSessionNumber {
+	/// Returns the index of the session.
+	fn session(&self) -> SessionIndex;
+}
+
+/// Something that knows the number of validators of the session it belongs to.
+pub trait GetValidatorCount {
+	/// Returns the number of validators in the session.
+	fn validator_count(&self) -> ValidatorCount;
+}
+
+impl GetSessionNumber for sp_core::Void {
+	fn session(&self) -> SessionIndex {
+		match *self {}
+	}
+}
+
+impl GetValidatorCount for sp_core::Void {
+	fn validator_count(&self) -> ValidatorCount {
+		match *self {}
+	}
+}
+
 /// Generate the initial session keys with the given seeds, at the given block and store them in
 /// the client's keystore.
 #[cfg(feature = "std")]
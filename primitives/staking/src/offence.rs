@@ -17,9 +17,10 @@
 //! Common traits and types that are useful for describing offences for usage in environments
 //! that use staking.
 
-use sp_std::vec::Vec;
+use sp_std::prelude::*;
 
 use codec::{Encode, Decode};
+use frame_metadata::{TypeInfo, Registry, Type, TypeDef, Field, registry::type_path};
 use sp_runtime::Perbill;
 
 use crate::SessionIndex;
@@ -118,10 +119,17 @@ impl sp_runtime::traits::Printable for OffenceError {
 pub trait ReportOffence<Reporter, Offender, O: Offence<Offender>> {
 	/// Report an `offence` and reward given `reporters`.
 	fn report_offence(reporters: Vec<Reporter>, offence: O) -> Result<(), OffenceError>;
+
+	/// Returns true iff all of the given offenders have been previously reported
+	/// at the given time slot. This function is useful to prevent the sending of
+	/// duplicate offence reports.
+	fn is_known_offence(offenders: &[Offender], time_slot: &O::TimeSlot) -> bool;
 }
 
 impl<Reporter, Offender, O: Offence<Offender>> ReportOffence<Reporter, Offender, O> for () {
 	fn report_offence(_reporters: Vec<Reporter>, _offence: O) -> Result<(), OffenceError> { Ok(()) }
+
+	fn is_known_offence(_offenders: &[Offender], _time_slot: &O::TimeSlot) -> bool { true }
 }
 
 /// A trait to take action on an offence.
@@ -177,3 +185,20 @@ pub struct OffenceDetails<Reporter, Offender> {
 	/// particular reporters.
 	pub reporters: Vec<Reporter>,
 }
+
+impl<Reporter, Offender> TypeInfo for OffenceDetails<Reporter, Offender> where
+	Reporter: TypeInfo + 'static,
+	Offender: TypeInfo + 'static,
+{
+	fn type_info(registry: &mut Registry) -> Type {
+		let offender = registry.register::<Offender>();
+		Type {
+			path: type_path(module_path!(), "OffenceDetails"),
+			params: vec![registry.register::<Reporter>(), offender],
+			def: TypeDef::Composite(vec![
+				Field::named("offender", offender),
+				Field::named("reporters", registry.register::<Vec<Reporter>>()),
+			]),
+		}
+	}
+}
@@ -37,12 +37,14 @@ use codec::Encode;
 #[derive(Debug)]
 pub struct BasicExternalities {
 	inner: Storage,
+	/// Snapshots of the storage taken at the start of each open storage transaction.
+	transactions: Vec<Storage>,
 }
 
 impl BasicExternalities {
 	/// Create a new instance of `BasicExternalities`
 	pub fn new(inner: Storage) -> Self {
-		BasicExternalities { inner }
+		BasicExternalities { inner, transactions: Vec::new() }
 	}
 
 	/// Insert key/value
@@ -62,10 +64,10 @@ impl BasicExternalities {
 		storage: &mut sp_core::storage::Storage,
 		f: impl FnOnce() -> R,
 	) -> R {
-		let mut ext = Self { inner: Storage {
+		let mut ext = Self::new(Storage {
 			top: std::mem::replace(&mut storage.top, Default::default()),
 			children: std::mem::replace(&mut storage.children, Default::default()),
-		}};
+		});
 
 		let r = ext.execute_with(f);
 
@@ -103,10 +105,10 @@ impl Default for BasicExternalities {
 
 impl From<BTreeMap<StorageKey, StorageValue>> for BasicExternalities {
 	fn from(hashmap: BTreeMap<StorageKey, StorageValue>) -> Self {
-		BasicExternalities { inner: Storage {
+		BasicExternalities::new(Storage {
 			top: hashmap,
 			children: Default::default(),
-		}}
+		})
 	}
 }
 
@@ -184,6 +186,10 @@ impl Externalities for BasicExternalities {
 		}
 	}
 
+	fn set_offchain_storage(&mut self, _key: &[u8], _value: Option<&[u8]>) {
+		// there is no offchain database to index into.
+	}
+
 	fn kill_child_storage(
 		&mut self,
 		storage_key: ChildStorageKey,
@@ -273,6 +279,19 @@ impl Externalities for BasicExternalities {
 		Ok(None)
 	}
 
+	fn storage_start_transaction(&mut self) {
+		self.transactions.push(self.inner.clone());
+	}
+
+	fn storage_rollback_transaction(&mut self) -> Result<(), ()> {
+		self.inner = self.transactions.pop().ok_or(())?;
+		Ok(())
+	}
+
+	fn storage_commit_transaction(&mut self) -> Result<(), ()> {
+		self.transactions.pop().map(|_| ()).ok_or(())
+	}
+
 	fn wipe(&mut self) {}
 
 	fn commit(&mut self) {}
@@ -351,4 +370,23 @@ mod tests {
 		assert!(storage.top.is_empty());
 		assert!(storage.children.is_empty());
 	}
+
+	#[test]
+	fn storage_transactions_work() {
+		let mut ext = BasicExternalities::default();
+		ext.set_storage(b"doe".to_vec(), b"reindeer".to_vec());
+
+		ext.storage_start_transaction();
+		ext.set_storage(b"dog".to_vec(), b"puppy".to_vec());
+		ext.storage_start_transaction();
+		ext.clear_storage(b"doe");
+		ext.storage_commit_transaction().unwrap();
+		assert_eq!(ext.storage(b"doe"), None);
+		ext.storage_rollback_transaction().unwrap();
+
+		assert_eq!(ext.storage(b"doe"), Some(b"reindeer".to_vec()));
+		assert_eq!(ext.storage(b"dog"), None);
+		assert!(ext.storage_rollback_transaction().is_err());
+		assert!(ext.storage_commit_transaction().is_err());
+	}
 }
@@ -348,6 +348,17 @@ where
 		self.overlay.set_child_storage(storage_key.into_owned(), child_info, key, value);
 	}
 
+	fn set_offchain_storage(&mut self, key: &[u8], value: Option<&[u8]>) {
+		trace!(target: "state-trace", "{:04x}: SetOffchain {}={:?}",
+			self.id,
+			HexDisplay::from(&key),
+			value.map(HexDisplay::from)
+		);
+		let _guard = sp_panic_handler::AbortGuard::force_abort();
+
+		self.overlay.set_offchain_storage(key, value);
+	}
+
 	fn kill_child_storage(
 		&mut self,
 		storage_key: ChildStorageKey,
@@ -519,6 +530,22 @@ where
 		root.map(|r| r.map(|o| o.encode()))
 	}
 
+	fn storage_start_transaction(&mut self) {
+		trace!(target: "state-trace", "{:04x}: StartTransaction", self.id);
+		self.overlay.start_transaction();
+	}
+
+	fn storage_rollback_transaction(&mut self) -> Result<(), ()> {
+		trace!(target: "state-trace", "{:04x}: RollbackTransaction", self.id);
+		self.mark_dirty();
+		self.overlay.rollback_transaction().map_err(|_| ())
+	}
+
+	fn storage_commit_transaction(&mut self) -> Result<(), ()> {
+		trace!(target: "state-trace", "{:04x}: CommitTransaction", self.id);
+		self.overlay.commit_transaction().map_err(|_| ())
+	}
+
 	fn wipe(&mut self) {
 		self.overlay.discard_prospective();
 		self.overlay.drain_storage_changes(&self.backend, None, Default::default(), self.storage_transaction_cache)
@@ -585,9 +612,8 @@ mod tests {
 					extrinsics: Some(vec![1].into_iter().collect())
 				}),
 			].into_iter().collect(),
-			committed: Default::default(),
 			collect_extrinsics: true,
-			stats: Default::default(),
+			..Default::default()
 		}
 	}
 
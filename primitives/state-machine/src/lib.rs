@@ -64,10 +64,11 @@ pub use changes_trie::{
 };
 pub use overlayed_changes::{
 	OverlayedChanges, StorageChanges, StorageTransactionCache, StorageKey, StorageValue,
-	StorageCollection, ChildStorageCollection,
+	StorageCollection, ChildStorageCollection, NoOpenTransaction,
 };
 pub use proving_backend::{
-	create_proof_check_backend, ProofRecorder, ProvingBackend, ProvingBackendRecorder,
+	create_proof_check_backend, create_recording_proof_check_backend, ProofRecorder,
+	ProvingBackend, ProvingBackendRecorder, RecordingProofStorage,
 };
 pub use trie_backend_essence::{TrieBackendStorage, Storage};
 pub use trie_backend::TrieBackend;
@@ -341,6 +342,7 @@ impl<'a, B, H, N, Exec> StateMachine<'a, B, H, N, Exec> where
 		let (result, was_native) = self.execute_aux(true, native_call.take());
 
 		if was_native {
+			self.overlay.transactions.clear();
 			self.overlay.prospective = orig_prospective.clone();
 			let (wasm_result, _) = self.execute_aux(
 				false,
@@ -377,6 +379,7 @@ impl<'a, B, H, N, Exec> StateMachine<'a, B, H, N, Exec> where
 		if !was_native || result.is_ok() {
 			result
 		} else {
+			self.overlay.transactions.clear();
 			self.overlay.prospective = orig_prospective.clone();
 			let (wasm_result, _) = self.execute_aux(
 				false,
@@ -622,6 +625,107 @@ where
 	prove_child_read_on_trie_backend(trie_backend, storage_key, child_info, keys)
 }
 
+/// Generate a proof of the storage values that follow `start_at` (exclusively), or of the values
+/// from the beginning of the storage if `None`. Values are added to the proof until it grows
+/// beyond `size_limit` bytes or there are none left.
+///
+/// Returns the proof and the number of values it contains.
+pub fn prove_range_read_with_size<B, H>(
+	mut backend: B,
+	size_limit: usize,
+	start_at: Option<&[u8]>,
+) -> Result<(StorageProof, u32), Box<dyn Error>>
+where
+	B: Backend<H>,
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let trie_backend = backend.as_trie_backend()
+		.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<dyn Error>)?;
+	prove_range_read_with_size_on_trie_backend(trie_backend, size_limit, start_at)
+}
+
+/// Generate range storage read proof on pre-created trie backend.
+pub fn prove_range_read_with_size_on_trie_backend<S, H>(
+	trie_backend: &TrieBackend<S, H>,
+	size_limit: usize,
+	start_at: Option<&[u8]>,
+) -> Result<(StorageProof, u32), Box<dyn Error>>
+where
+	S: trie_backend_essence::TrieBackendStorage<H>,
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+	let mut count = 0;
+	let mut key = start_at.map(|k| k.to_vec()).unwrap_or_default();
+	while let Some(next) = proving_backend
+		.next_storage_key(&key)
+		.map_err(|e| Box::new(e) as Box<dyn Error>)?
+	{
+		proving_backend
+			.storage(&next)
+			.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+		count += 1;
+		key = next;
+		if proving_backend.estimate_encoded_size() > size_limit {
+			break;
+		}
+	}
+	Ok((proving_backend.extract_proof(), count))
+}
+
+/// Generate a proof of the values of a child trie that follow `start_at` (exclusively), like
+/// `prove_range_read_with_size` does for the top trie.
+pub fn prove_child_range_read_with_size<B, H>(
+	mut backend: B,
+	storage_key: &[u8],
+	child_info: ChildInfo,
+	size_limit: usize,
+	start_at: Option<&[u8]>,
+) -> Result<(StorageProof, u32), Box<dyn Error>>
+where
+	B: Backend<H>,
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let trie_backend = backend.as_trie_backend()
+		.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<dyn Error>)?;
+	prove_child_range_read_with_size_on_trie_backend(trie_backend, storage_key, child_info, size_limit, start_at)
+}
+
+/// Generate child range storage read proof on pre-created trie backend.
+pub fn prove_child_range_read_with_size_on_trie_backend<S, H>(
+	trie_backend: &TrieBackend<S, H>,
+	storage_key: &[u8],
+	child_info: ChildInfo,
+	size_limit: usize,
+	start_at: Option<&[u8]>,
+) -> Result<(StorageProof, u32), Box<dyn Error>>
+where
+	S: trie_backend_essence::TrieBackendStorage<H>,
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+	let mut count = 0;
+	let mut key = start_at.map(|k| k.to_vec()).unwrap_or_default();
+	while let Some(next) = proving_backend
+		.next_child_storage_key(storage_key, child_info, &key)
+		.map_err(|e| Box::new(e) as Box<dyn Error>)?
+	{
+		proving_backend
+			.child_storage(storage_key, child_info, &next)
+			.map_err(|e| Box::new(e) as Box<dyn Error>)?;
+		count += 1;
+		key = next;
+		if proving_backend.estimate_encoded_size() > size_limit {
+			break;
+		}
+	}
+	Ok((proving_backend.extract_proof(), count))
+}
+
 /// Generate storage read proof on pre-created trie backend.
 pub fn prove_read_on_trie_backend<S, H, I>(
 	trie_backend: &TrieBackend<S, H>,
@@ -713,6 +817,72 @@ where
 	Ok(result)
 }
 
+/// Check a range storage read proof, generated by `prove_range_read_with_size` call.
+///
+/// Returns the values contained in the proof, in key order, and whether the proof reaches the
+/// end of the storage.
+pub fn read_range_proof_check<H>(
+	root: H::Out,
+	proof: StorageProof,
+	start_at: Option<&[u8]>,
+) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool), Box<dyn Error>>
+where
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let proving_backend = create_proof_check_backend::<H>(root, proof)?;
+	read_range_proof_check_on_proving_backend(&proving_backend, start_at)
+}
+
+/// Check a range storage read proof of the top trie, or of the given child trie whose root is
+/// `root`, generated by `prove_range_read_with_size` or `prove_child_range_read_with_size`.
+///
+/// Returns the values contained in the proof and whether the proof reaches the end of the trie,
+/// like `read_range_proof_check`, along with the trie nodes of the proof that the values were
+/// read from, keyed as in a database storing the complete state.
+pub fn read_range_proof_check_with_nodes<H>(
+	root: H::Out,
+	proof: StorageProof,
+	child_info: Option<ChildInfo>,
+	start_at: Option<&[u8]>,
+) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool, sp_trie::PrefixedMemoryDB<H>), Box<dyn Error>>
+where
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let proving_backend = create_recording_proof_check_backend::<H>(root, proof, child_info)?;
+	let (values, complete) = read_range_proof_check_on_proving_backend(&proving_backend, start_at)?;
+	Ok((values, complete, proving_backend.into_storage().into_recorded()))
+}
+
+/// Check range storage read proof on pre-created proving backend.
+fn read_range_proof_check_on_proving_backend<S, H>(
+	proving_backend: &TrieBackend<S, H>,
+	start_at: Option<&[u8]>,
+) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool), Box<dyn Error>>
+where
+	S: trie_backend_essence::TrieBackendStorage<H>,
+	H: Hasher,
+	H::Out: Ord + Codec,
+{
+	let mut values = Vec::new();
+	let mut key = start_at.map(|k| k.to_vec()).unwrap_or_default();
+	loop {
+		// A missing trie node means that the proof ends here.
+		let next = match proving_backend.next_storage_key(&key) {
+			Ok(Some(next)) => next,
+			Ok(None) => return Ok((values, true)),
+			Err(_) => return Ok((values, false)),
+		};
+		match proving_backend.storage(&next) {
+			Ok(Some(value)) => values.push((next.clone(), value)),
+			Ok(None) => return Err(Box::new(ExecutionError::InvalidProof)),
+			Err(_) => return Ok((values, false)),
+		}
+		key = next;
+	}
+}
+
 /// Check storage read proof on pre-created proving backend.
 pub fn read_proof_check_on_proving_backend<H>(
 	proving_backend: &TrieBackend<MemoryDB<H>, H>,
@@ -1097,6 +1267,99 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn prove_range_read_and_proof_check_works() {
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+
+		// a zero size limit yields one value per proof.
+		let mut values = Vec::new();
+		let mut start_at: Option<Vec<u8>> = None;
+		loop {
+			let (proof, count) = prove_range_read_with_size_on_trie_backend(
+				&remote_backend,
+				0,
+				start_at.as_deref(),
+			).unwrap();
+			assert!(count <= 1);
+			let (chunk, complete) = read_range_proof_check::<BlakeTwo256>(
+				remote_root,
+				proof,
+				start_at.as_deref(),
+			).unwrap();
+			if complete {
+				values.extend(chunk);
+				break;
+			}
+			assert!(!chunk.is_empty());
+			start_at = chunk.last().map(|(k, _)| k.clone());
+			values.extend(chunk);
+		}
+		assert_eq!(values, remote_backend.pairs());
+
+		// without limit, everything fits in a single proof.
+		let (proof, count) = prove_range_read_with_size_on_trie_backend(
+			&remote_backend,
+			usize::max_value(),
+			None,
+		).unwrap();
+		assert_eq!(count as usize, remote_backend.pairs().len());
+		let (all, complete) = read_range_proof_check::<BlakeTwo256>(remote_root, proof, None).unwrap();
+		assert!(complete);
+		assert_eq!(all, remote_backend.pairs());
+	}
+
+	#[test]
+	fn range_proofs_with_nodes_contain_complete_state() {
+		const CHILD_KEY_1: &[u8] = b":child_storage:default:sub1";
+		const CHILD_INFO_1: ChildInfo<'static> = ChildInfo::new_default(b"unique_id_1");
+		let (mut remote_db, remote_root) = trie_backend::tests::test_db();
+		let remote_backend = trie_backend::TrieBackend::new(remote_db.clone(), remote_root);
+		let child_root = remote_backend.storage(CHILD_KEY_1).unwrap()
+			.map(|r| sp_core::H256::decode(&mut &r[..]).unwrap())
+			.unwrap();
+
+		let mut nodes = sp_trie::PrefixedMemoryDB::<BlakeTwo256>::default();
+		for child in vec![None, Some(CHILD_INFO_1)] {
+			let mut start_at: Option<Vec<u8>> = None;
+			loop {
+				let (proof, _) = match child {
+					None => prove_range_read_with_size_on_trie_backend(
+						&remote_backend,
+						0,
+						start_at.as_deref(),
+					),
+					Some(child_info) => prove_child_range_read_with_size_on_trie_backend(
+						&remote_backend,
+						CHILD_KEY_1,
+						child_info,
+						0,
+						start_at.as_deref(),
+					),
+				}.unwrap();
+				let root = if child.is_some() { child_root } else { remote_root };
+				let (chunk, complete, chunk_nodes) = read_range_proof_check_with_nodes::<BlakeTwo256>(
+					root,
+					proof,
+					child,
+					start_at.as_deref(),
+				).unwrap();
+				nodes.consolidate(chunk_nodes);
+				if complete {
+					break;
+				}
+				start_at = chunk.last().map(|(k, _)| k.clone());
+			}
+		}
+
+		let keys = |db: &mut sp_trie::PrefixedMemoryDB<BlakeTwo256>| db.drain()
+			.into_iter()
+			.filter(|(_, (_, rc))| *rc > 0)
+			.map(|(k, _)| k)
+			.collect::<std::collections::BTreeSet<_>>();
+		assert_eq!(keys(&mut nodes), keys(&mut remote_db));
+	}
+
 	#[test]
 	fn child_storage_uuid() {
 		const CHILD_INFO_1: ChildInfo<'static> = ChildInfo::new_default(b"unique_id_1");
@@ -46,18 +46,32 @@ pub type StorageCollection = Vec<(StorageKey, Option<StorageValue>)>;
 /// In memory arrays of storage values for multiple child tries.
 pub type ChildStorageCollection = Vec<(StorageKey, StorageCollection)>;
 
+/// Error returned when trying to commit or rollback while no transaction is open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoOpenTransaction;
+
 /// The overlayed changes to state to be queried on top of the backend.
 ///
 /// A transaction shares all prospective changes within an inner overlay
 /// that can be cleared.
+///
+/// On top of that, storage transactions can be nested arbitrarily deep within the prospective
+/// changes using [`start_transaction`](Self::start_transaction). Every open transaction must be
+/// closed by either [`commit_transaction`](Self::commit_transaction) or
+/// [`rollback_transaction`](Self::rollback_transaction).
 #[derive(Debug, Default, Clone)]
 pub struct OverlayedChanges {
 	/// Changes that are not yet committed.
 	pub(crate) prospective: OverlayedChangeSet,
 	/// Committed changes.
 	pub(crate) committed: OverlayedChangeSet,
+	/// Undo journals of the currently open storage transactions. The last element belongs to
+	/// the innermost transaction.
+	pub(crate) transactions: Vec<TransactionJournal>,
 	/// True if extrinsics stats must be collected.
 	pub(crate) collect_extrinsics: bool,
+	/// True if offchain database changes must be collected.
+	pub(crate) offchain_indexing: bool,
 	/// Collect statistic on this execution.
 	pub(crate) stats: StateMachineStats,
 }
@@ -81,6 +95,80 @@ pub struct OverlayedChangeSet {
 	pub top: BTreeMap<StorageKey, OverlayedValue>,
 	/// Child storage changes.
 	pub children: HashMap<StorageKey, (BTreeMap<StorageKey, OverlayedValue>, OwnedChildInfo)>,
+	/// Offchain database changes. `None` means that the value was deleted.
+	pub offchain: BTreeMap<StorageKey, Option<StorageValue>>,
+}
+
+/// Child trie entries of a [`TransactionJournal`].
+///
+/// The child info is `None` if the child trie was not part of the prospective change set when
+/// the transaction started.
+type ChildJournal = (Option<OwnedChildInfo>, BTreeMap<StorageKey, Option<OverlayedValue>>);
+
+/// The prospective values overwritten during a storage transaction.
+///
+/// A value is recorded the first time its key is changed within the transaction, so starting a
+/// transaction is free and closing it only touches the keys that were changed. `None` means
+/// that the key was not part of the prospective change set.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct TransactionJournal {
+	/// Previous values of top level storage keys.
+	top: BTreeMap<StorageKey, Option<OverlayedValue>>,
+	/// Previous values of child storage keys, by storage key of the child trie.
+	children: HashMap<StorageKey, ChildJournal>,
+	/// Previous offchain database changes.
+	offchain: BTreeMap<StorageKey, Option<Option<StorageValue>>>,
+}
+
+impl TransactionJournal {
+	/// Record the prospective value of a top level `key`, unless it was already recorded.
+	fn note_top(&mut self, prospective: &BTreeMap<StorageKey, OverlayedValue>, key: &[u8]) {
+		if !self.top.contains_key(key) {
+			self.top.insert(key.to_vec(), prospective.get(key).cloned());
+		}
+	}
+
+	/// Record the child info of the child trie at `storage_key`, unless it was already recorded,
+	/// and return the journal of its keys.
+	fn note_child(
+		&mut self,
+		prospective: &HashMap<StorageKey, (BTreeMap<StorageKey, OverlayedValue>, OwnedChildInfo)>,
+		storage_key: &[u8],
+	) -> &mut BTreeMap<StorageKey, Option<OverlayedValue>> {
+		&mut self.children.entry(storage_key.to_vec())
+			.or_insert_with(|| (prospective.get(storage_key).map(|(_, info)| info.clone()), BTreeMap::new()))
+			.1
+	}
+
+	/// Merge the journal of a committed inner transaction into this one, keeping the older
+	/// values recorded here.
+	fn merge(&mut self, inner: TransactionJournal) {
+		for (key, value) in inner.top {
+			self.top.entry(key).or_insert(value);
+		}
+		for (storage_key, (child_info, keys)) in inner.children {
+			let child = self.children.entry(storage_key).or_insert_with(|| (child_info, BTreeMap::new()));
+			for (key, value) in keys {
+				child.1.entry(key).or_insert(value);
+			}
+		}
+		for (key, value) in inner.offchain {
+			self.offchain.entry(key).or_insert(value);
+		}
+	}
+}
+
+/// Restore the journaled `values` into the given map of overlayed values.
+fn restore_values(
+	map: &mut BTreeMap<StorageKey, OverlayedValue>,
+	values: BTreeMap<StorageKey, Option<OverlayedValue>>,
+) {
+	for (key, value) in values {
+		match value {
+			Some(value) => { map.insert(key, value); },
+			None => { map.remove(&key); },
+		}
+	}
 }
 
 /// A storage changes structure that can be generated by the data collected in [`OverlayedChanges`].
@@ -104,6 +192,11 @@ pub struct StorageChanges<Transaction, H: Hasher, N: BlockNumber> {
 	///
 	/// If changes trie is disabled the value is set to `None`.
 	pub changes_trie_transaction: Option<ChangesTrieTransaction<H, N>>,
+	/// All changes to the offchain database.
+	///
+	/// Only collected when offchain indexing is enabled. A value of `None` means that it was
+	/// deleted.
+	pub offchain_storage_changes: StorageCollection,
 }
 
 impl<Transaction, H: Hasher, N: BlockNumber> StorageChanges<Transaction, H, N> {
@@ -114,6 +207,7 @@ impl<Transaction, H: Hasher, N: BlockNumber> StorageChanges<Transaction, H, N> {
 		Transaction,
 		H::Out,
 		Option<ChangesTrieTransaction<H, N>>,
+		StorageCollection,
 	) {
 		(
 			self.main_storage_changes,
@@ -121,6 +215,7 @@ impl<Transaction, H: Hasher, N: BlockNumber> StorageChanges<Transaction, H, N> {
 			self.transaction,
 			self.transaction_storage_root,
 			self.changes_trie_transaction,
+			self.offchain_storage_changes,
 		)
 	}
 }
@@ -165,6 +260,7 @@ impl<Transaction: Default, H: Hasher, N: BlockNumber> Default for StorageChanges
 			transaction: Default::default(),
 			transaction_storage_root: Default::default(),
 			changes_trie_transaction: None,
+			offchain_storage_changes: Default::default(),
 		}
 	}
 }
@@ -175,6 +271,7 @@ impl FromIterator<(StorageKey, OverlayedValue)> for OverlayedChangeSet {
 		Self {
 			top: iter.into_iter().collect(),
 			children: Default::default(),
+			offchain: Default::default(),
 		}
 	}
 }
@@ -182,13 +279,14 @@ impl FromIterator<(StorageKey, OverlayedValue)> for OverlayedChangeSet {
 impl OverlayedChangeSet {
 	/// Whether the change set is empty.
 	pub fn is_empty(&self) -> bool {
-		self.top.is_empty() && self.children.is_empty()
+		self.top.is_empty() && self.children.is_empty() && self.offchain.is_empty()
 	}
 
 	/// Clear the change set.
 	pub fn clear(&mut self) {
 		self.top.clear();
 		self.children.clear();
+		self.offchain.clear();
 	}
 }
 
@@ -203,6 +301,13 @@ impl OverlayedChanges {
 		self.collect_extrinsics = collect_extrinsics;
 	}
 
+	/// Ask to collect/not to collect the offchain database changes made by the runtime.
+	///
+	/// When disabled, offchain indexing calls made by the runtime are ignored.
+	pub fn set_offchain_indexing(&mut self, offchain_indexing: bool) {
+		self.offchain_indexing = offchain_indexing;
+	}
+
 	/// Returns a double-Option: None if the key is unknown (i.e. and the query should be referred
 	/// to the backend); Some(None) if the key has been deleted. Some(Some(...)) for a key whose
 	/// value has been set.
@@ -246,6 +351,9 @@ impl OverlayedChanges {
 		let size_write = val.as_ref().map(|x| x.len() as u64).unwrap_or(0);
 		self.stats.tally_write_overlay(size_write);
 		let extrinsic_index = self.extrinsic_index();
+		if let Some(journal) = self.transactions.last_mut() {
+			journal.note_top(&self.prospective.top, &key);
+		}
 		let entry = self.prospective.top.entry(key).or_default();
 		entry.value = val;
 
@@ -268,11 +376,20 @@ impl OverlayedChanges {
 		let size_write = val.as_ref().map(|x| x.len() as u64).unwrap_or(0);
 		self.stats.tally_write_overlay(size_write);
 		let extrinsic_index = self.extrinsic_index();
+		let journal = match self.transactions.last_mut() {
+			Some(journal) => Some(journal.note_child(&self.prospective.children, &storage_key)),
+			None => None,
+		};
 		let map_entry = self.prospective.children.entry(storage_key)
 			.or_insert_with(|| (Default::default(), child_info.to_owned()));
 		let updatable = map_entry.1.try_update(child_info);
 		debug_assert!(updatable);
 
+		if let Some(journal) = journal {
+			if !journal.contains_key(&key) {
+				journal.insert(key.clone(), map_entry.0.get(&key).cloned());
+			}
+		}
 		let entry = map_entry.0.entry(key).or_default();
 		entry.value = val;
 
@@ -294,23 +411,34 @@ impl OverlayedChanges {
 		child_info: ChildInfo,
 	) {
 		let extrinsic_index = self.extrinsic_index();
+		let mut journal = match self.transactions.last_mut() {
+			Some(journal) => Some(journal.note_child(&self.prospective.children, storage_key)),
+			None => None,
+		};
 		let map_entry = self.prospective.children.entry(storage_key.to_vec())
 			.or_insert_with(|| (Default::default(), child_info.to_owned()));
 		let updatable = map_entry.1.try_update(child_info);
 		debug_assert!(updatable);
 
-		map_entry.0.values_mut().for_each(|e| {
+		for (key, e) in map_entry.0.iter_mut() {
+			if let Some(journal) = journal.as_mut() {
+				journal.entry(key.clone()).or_insert_with(|| Some(e.clone()));
+			}
+
 			if let Some(extrinsic) = extrinsic_index {
 				e.extrinsics.get_or_insert_with(Default::default)
 					.insert(extrinsic);
 			}
 
 			e.value = None;
-		});
+		}
 
 		if let Some((committed_map, _child_info)) = self.committed.children.get(storage_key) {
 			for (key, value) in committed_map.iter() {
 				if !map_entry.0.contains_key(key) {
+					if let Some(journal) = journal.as_mut() {
+						journal.entry(key.clone()).or_insert(None);
+					}
 					map_entry.0.insert(key.clone(), OverlayedValue {
 						value: None,
 						extrinsics: extrinsic_index.map(|i| {
@@ -333,11 +461,15 @@ impl OverlayedChanges {
 	/// [`discard_prospective`]: #method.discard_prospective
 	pub(crate) fn clear_prefix(&mut self, prefix: &[u8]) {
 		let extrinsic_index = self.extrinsic_index();
+		let mut journal = self.transactions.last_mut();
 
 		// Iterate over all prospective and mark all keys that share
 		// the given prefix as removed (None).
 		for (key, entry) in self.prospective.top.iter_mut() {
 			if key.starts_with(prefix) {
+				if let Some(journal) = journal.as_mut() {
+					journal.top.entry(key.clone()).or_insert_with(|| Some(entry.clone()));
+				}
 				entry.value = None;
 
 				if let Some(extrinsic) = extrinsic_index {
@@ -351,6 +483,9 @@ impl OverlayedChanges {
 		// NOTE that we are making changes in the prospective change set.
 		for key in self.committed.top.keys() {
 			if key.starts_with(prefix) {
+				if let Some(journal) = journal.as_mut() {
+					journal.note_top(&self.prospective.top, key);
+				}
 				let entry = self.prospective.top.entry(key.clone()).or_default();
 				entry.value = None;
 
@@ -369,6 +504,10 @@ impl OverlayedChanges {
 		prefix: &[u8],
 	) {
 		let extrinsic_index = self.extrinsic_index();
+		let mut journal = match self.transactions.last_mut() {
+			Some(journal) => Some(journal.note_child(&self.prospective.children, storage_key)),
+			None => None,
+		};
 		let map_entry = self.prospective.children.entry(storage_key.to_vec())
 			.or_insert_with(|| (Default::default(), child_info.to_owned()));
 		let updatable = map_entry.1.try_update(child_info);
@@ -376,6 +515,9 @@ impl OverlayedChanges {
 
 		for (key, entry) in map_entry.0.iter_mut() {
 			if key.starts_with(prefix) {
+				if let Some(journal) = journal.as_mut() {
+					journal.entry(key.clone()).or_insert_with(|| Some(entry.clone()));
+				}
 				entry.value = None;
 
 				if let Some(extrinsic) = extrinsic_index {
@@ -390,6 +532,11 @@ impl OverlayedChanges {
 			// NOTE that we are making changes in the prospective change set.
 			for key in child_committed.keys() {
 				if key.starts_with(prefix) {
+					if let Some(journal) = journal.as_mut() {
+						if !journal.contains_key(key) {
+							journal.insert(key.clone(), map_entry.0.get(key).cloned());
+						}
+					}
 					let entry = map_entry.0.entry(key.clone()).or_default();
 					entry.value = None;
 
@@ -402,13 +549,95 @@ impl OverlayedChanges {
 		}
 	}
 
+	/// Inserts the given offchain database change into the prospective change set.
+	///
+	/// `None` can be used to delete a value specified by the given key. The change is ignored
+	/// if offchain indexing is disabled.
+	pub(crate) fn set_offchain_storage(&mut self, key: &[u8], val: Option<&[u8]>) {
+		if !self.offchain_indexing {
+			return;
+		}
+
+		if let Some(journal) = self.transactions.last_mut() {
+			if !journal.offchain.contains_key(key) {
+				journal.offchain.insert(key.to_vec(), self.prospective.offchain.get(key).cloned());
+			}
+		}
+		self.prospective.offchain.insert(key.to_vec(), val.map(|v| v.to_vec()));
+	}
+
+	/// Start a new nested storage transaction.
+	///
+	/// All changes made to the prospective change set from now on can be reverted as a whole
+	/// with [`rollback_transaction`](Self::rollback_transaction) or kept with
+	/// [`commit_transaction`](Self::commit_transaction).
+	///
+	/// This is `O(1)`: the previous values are only recorded once they are changed.
+	pub fn start_transaction(&mut self) {
+		self.transactions.push(TransactionJournal::default());
+	}
+
+	/// Rollback the innermost open storage transaction.
+	///
+	/// All changes made since the matching [`start_transaction`](Self::start_transaction) are
+	/// discarded. Returns an error if there is no open transaction.
+	pub fn rollback_transaction(&mut self) -> Result<(), NoOpenTransaction> {
+		let journal = self.transactions.pop().ok_or(NoOpenTransaction)?;
+
+		restore_values(&mut self.prospective.top, journal.top);
+		for (storage_key, (child_info, values)) in journal.children {
+			match child_info {
+				// The child trie was only created within the transaction.
+				None => { self.prospective.children.remove(&storage_key); },
+				Some(child_info) => {
+					let map_entry = self.prospective.children.entry(storage_key)
+						.or_insert_with(|| (Default::default(), child_info.clone()));
+					map_entry.1 = child_info;
+					restore_values(&mut map_entry.0, values);
+				},
+			}
+		}
+		for (key, value) in journal.offchain {
+			match value {
+				Some(value) => { self.prospective.offchain.insert(key, value); },
+				None => { self.prospective.offchain.remove(&key); },
+			}
+		}
+		Ok(())
+	}
+
+	/// Commit the innermost open storage transaction.
+	///
+	/// The changes made since the matching [`start_transaction`](Self::start_transaction) are
+	/// kept as part of the enclosing transaction, or of the prospective changes if this was the
+	/// outermost one. Returns an error if there is no open transaction.
+	pub fn commit_transaction(&mut self) -> Result<(), NoOpenTransaction> {
+		let journal = self.transactions.pop().ok_or(NoOpenTransaction)?;
+		// The enclosing transaction must still be able to revert the committed changes.
+		if let Some(parent) = self.transactions.last_mut() {
+			parent.merge(journal);
+		}
+		Ok(())
+	}
+
+	/// Returns the number of currently open storage transactions.
+	pub fn transaction_depth(&self) -> usize {
+		self.transactions.len()
+	}
+
 	/// Discard prospective changes to state.
+	///
+	/// This also discards all open storage transactions.
 	pub fn discard_prospective(&mut self) {
+		self.transactions.clear();
 		self.prospective.clear();
 	}
 
 	/// Commit prospective changes to state.
+	///
+	/// This also closes all open storage transactions, keeping their changes.
 	pub fn commit_prospective(&mut self) {
+		self.transactions.clear();
 		if self.committed.is_empty() {
 			mem::swap(&mut self.prospective, &mut self.committed);
 		} else {
@@ -422,6 +651,8 @@ impl OverlayedChanges {
 						.extend(prospective_extrinsics);
 				}
 			}
+			let offchain_to_commit = mem::replace(&mut self.prospective.offchain, BTreeMap::new());
+			self.committed.offchain.extend(offchain_to_commit);
 			for (storage_key, (map, child_info)) in self.prospective.children.drain() {
 				let child_content = self.committed.children.entry(storage_key)
 					.or_insert_with(|| (Default::default(), child_info));
@@ -446,6 +677,7 @@ impl OverlayedChanges {
 	fn drain_committed(&mut self) -> (
 		impl Iterator<Item=(StorageKey, Option<StorageValue>)>,
 		impl Iterator<Item=(StorageKey, (impl Iterator<Item=(StorageKey, Option<StorageValue>)>, OwnedChildInfo))>,
+		impl Iterator<Item=(StorageKey, Option<StorageValue>)>,
 	) {
 		assert!(self.prospective.is_empty());
 		(
@@ -455,6 +687,8 @@ impl OverlayedChanges {
 			std::mem::replace(&mut self.committed.children, Default::default())
 				.into_iter()
 				.map(|(sk, (v, ci))| (sk, (v.into_iter().map(|(k, v)| (k, v.value)), ci))),
+			std::mem::replace(&mut self.committed.offchain, Default::default())
+				.into_iter(),
 		)
 	}
 
@@ -503,7 +737,11 @@ impl OverlayedChanges {
 			.take()
 			.expect("Changes trie transaction was generated by `changes_trie_root`; qed");
 
-		let (main_storage_changes, child_storage_changes) = self.drain_committed();
+		let (
+			main_storage_changes,
+			child_storage_changes,
+			offchain_storage_changes,
+		) = self.drain_committed();
 
 		Ok(StorageChanges {
 			main_storage_changes: main_storage_changes.collect(),
@@ -511,6 +749,7 @@ impl OverlayedChanges {
 			transaction,
 			transaction_storage_root,
 			changes_trie_transaction,
+			offchain_storage_changes: offchain_storage_changes.collect(),
 		})
 	}
 
@@ -724,6 +963,127 @@ mod tests {
 		assert!(overlayed.storage(&key).unwrap().is_none());
 	}
 
+	#[test]
+	fn offchain_changes_are_collected_only_when_enabled() {
+		let backend = InMemoryBackend::<Blake2Hasher>::default();
+		let mut overlayed = OverlayedChanges::default();
+
+		overlayed.set_offchain_storage(b"ignored", Some(b"value"));
+		assert!(overlayed.is_empty());
+
+		overlayed.set_offchain_indexing(true);
+		overlayed.set_offchain_storage(b"kept", Some(b"value"));
+		overlayed.set_offchain_storage(b"removed", None);
+		overlayed.commit_prospective();
+
+		// changes of a rolled back transaction are discarded
+		overlayed.start_transaction();
+		overlayed.set_offchain_storage(b"rolled_back", Some(b"value"));
+		overlayed.rollback_transaction().unwrap();
+		overlayed.commit_prospective();
+
+		let changes = overlayed.drain_storage_changes::<_, _, u64>(
+			&backend,
+			None,
+			Default::default(),
+			&mut Default::default(),
+		).unwrap();
+
+		assert_eq!(changes.offchain_storage_changes, vec![
+			(b"kept".to_vec(), Some(b"value".to_vec())),
+			(b"removed".to_vec(), None),
+		]);
+	}
+
+	#[test]
+	fn nested_transactions_work() {
+		let mut overlayed = OverlayedChanges::default();
+
+		let key = vec![42, 69, 169, 142];
+		let other_key = vec![1, 2, 3];
+
+		overlayed.set_storage(key.clone(), Some(vec![1]));
+		overlayed.start_transaction();
+		overlayed.set_storage(key.clone(), Some(vec![2]));
+		overlayed.set_storage(other_key.clone(), Some(vec![2]));
+
+		overlayed.start_transaction();
+		overlayed.set_storage(key.clone(), Some(vec![3]));
+		overlayed.clear_prefix(&[1]);
+		assert_eq!(overlayed.transaction_depth(), 2);
+		assert_eq!(overlayed.storage(&key).unwrap(), Some(&[3][..]));
+		assert!(overlayed.storage(&other_key).unwrap().is_none());
+
+		// Rolling back the inner transaction restores the values of the outer one.
+		overlayed.rollback_transaction().unwrap();
+		assert_eq!(overlayed.storage(&key).unwrap(), Some(&[2][..]));
+		assert_eq!(overlayed.storage(&other_key).unwrap(), Some(&[2][..]));
+
+		overlayed.start_transaction();
+		overlayed.set_storage(key.clone(), Some(vec![4]));
+		overlayed.commit_transaction().unwrap();
+		assert_eq!(overlayed.transaction_depth(), 1);
+		assert_eq!(overlayed.storage(&key).unwrap(), Some(&[4][..]));
+
+		// Rolling back the outer transaction also discards the committed inner transaction.
+		overlayed.rollback_transaction().unwrap();
+		assert_eq!(overlayed.transaction_depth(), 0);
+		assert_eq!(overlayed.storage(&key).unwrap(), Some(&[1][..]));
+		assert!(overlayed.storage(&other_key).is_none());
+
+		assert_eq!(overlayed.rollback_transaction(), Err(NoOpenTransaction));
+		assert_eq!(overlayed.commit_transaction(), Err(NoOpenTransaction));
+	}
+
+	#[test]
+	fn prospective_operations_close_open_transactions() {
+		let mut overlayed = OverlayedChanges::default();
+
+		let key = vec![42, 69, 169, 142];
+
+		overlayed.start_transaction();
+		overlayed.set_storage(key.clone(), Some(vec![1]));
+		overlayed.start_transaction();
+		overlayed.commit_prospective();
+		assert_eq!(overlayed.transaction_depth(), 0);
+		assert_eq!(overlayed.storage(&key).unwrap(), Some(&[1][..]));
+
+		overlayed.start_transaction();
+		overlayed.set_storage(key.clone(), Some(vec![2]));
+		overlayed.discard_prospective();
+		assert_eq!(overlayed.transaction_depth(), 0);
+		assert_eq!(overlayed.storage(&key).unwrap(), Some(&[1][..]));
+	}
+
+	#[test]
+	fn nested_transactions_revert_child_storage() {
+		let child = b"Child1".to_vec();
+		let other_child = b"Child2".to_vec();
+		let child_info = ChildInfo::new_default(b"uniqueid");
+		let other_child_info = ChildInfo::new_default(b"otherid");
+		let mut overlayed = OverlayedChanges::default();
+
+		overlayed.set_child_storage(child.clone(), child_info, vec![10], Some(vec![10]));
+		overlayed.commit_prospective();
+		overlayed.set_child_storage(child.clone(), child_info, vec![20], Some(vec![20]));
+
+		overlayed.start_transaction();
+		overlayed.set_child_storage(child.clone(), child_info, vec![20], Some(vec![21]));
+		overlayed.start_transaction();
+		overlayed.clear_child_storage(&child, child_info);
+		overlayed.set_child_storage(other_child.clone(), other_child_info, vec![1], Some(vec![1]));
+		assert_eq!(overlayed.child_storage(&child, &[10]), Some(None));
+		assert_eq!(overlayed.child_storage(&child, &[20]), Some(None));
+		overlayed.commit_transaction().unwrap();
+
+		// The outer transaction also reverts the changes of the committed inner one.
+		overlayed.rollback_transaction().unwrap();
+		assert_eq!(overlayed.child_storage(&child, &[10]), Some(Some(&[10][..])));
+		assert_eq!(overlayed.child_storage(&child, &[20]), Some(Some(&[20][..])));
+		assert_eq!(overlayed.child_storage(&other_child, &[1]), None);
+		assert!(!overlayed.prospective.children.contains_key(&other_child));
+	}
+
 	#[test]
 	fn overlayed_storage_root_works() {
 		let initial: BTreeMap<_, _> = vec![
@@ -22,8 +22,8 @@ use codec::{Decode, Codec};
 use log::debug;
 use hash_db::{Hasher, HashDB, EMPTY_PREFIX, Prefix};
 use sp_trie::{
-	MemoryDB, default_child_trie_root, read_trie_value_with, read_child_trie_value_with,
-	record_all_keys, StorageProof,
+	MemoryDB, PrefixedMemoryDB, default_child_trie_root, read_trie_value_with,
+	read_child_trie_value_with, record_all_keys, StorageProof,
 };
 pub use sp_trie::Recorder;
 pub use sp_trie::trie_types::{Layout, TrieError};
@@ -151,6 +151,15 @@ impl<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> ProvingBackend<'a, S, H>
 		ProvingBackend(TrieBackend::new(recorder, root))
 	}
 
+	/// Returns the estimated encoded size of the proof recorded so far.
+	pub fn estimate_encoded_size(&self) -> usize {
+		self.0.essence().backend_storage().proof_recorder
+			.read()
+			.values()
+			.map(|v| v.as_ref().map_or(0, |v| v.len()))
+			.sum()
+	}
+
 	/// Extracting the gathered unordered proof.
 	pub fn extract_proof(&self) -> StorageProof {
 		let trie_nodes = self.0.essence().backend_storage().proof_recorder
@@ -309,6 +318,63 @@ where
 	}
 }
 
+/// Proof check storage that records the trie nodes that are read, keyed like in a database
+/// storing the complete state.
+pub struct RecordingProofStorage<H: Hasher> {
+	proof: MemoryDB<H>,
+	keyspace: Vec<u8>,
+	recorded: RwLock<PrefixedMemoryDB<H>>,
+}
+
+impl<H: Hasher> RecordingProofStorage<H> {
+	/// Returns the trie nodes that have been read.
+	pub fn into_recorded(self) -> PrefixedMemoryDB<H> {
+		self.recorded.into_inner()
+	}
+}
+
+impl<H: Hasher> TrieBackendStorage<H> for RecordingProofStorage<H> {
+	type Overlay = PrefixedMemoryDB<H>;
+
+	fn get(&self, key: &H::Out, prefix: Prefix) -> Result<Option<DBValue>, String> {
+		let value = HashDB::get(&self.proof, key, EMPTY_PREFIX);
+		if let Some(value) = &value {
+			let mut keyspaced = Vec::with_capacity(self.keyspace.len() + prefix.0.len());
+			keyspaced.extend_from_slice(&self.keyspace);
+			keyspaced.extend_from_slice(prefix.0);
+			self.recorded.write().emplace(*key, (&keyspaced, prefix.1), value.clone());
+		}
+		Ok(value)
+	}
+}
+
+/// Create proof check backend recording the trie nodes that are read.
+///
+/// `root` is the root of the top trie, or of the given child trie whose nodes are then keyed in
+/// the keyspace of the child trie.
+pub fn create_recording_proof_check_backend<H>(
+	root: H::Out,
+	proof: StorageProof,
+	child_info: Option<ChildInfo>,
+) -> Result<TrieBackend<RecordingProofStorage<H>, H>, Box<dyn Error>>
+where
+	H: Hasher,
+	H::Out: Codec,
+{
+	let db = proof.into_memory_db();
+
+	if db.contains(&root, EMPTY_PREFIX) {
+		let storage = RecordingProofStorage {
+			proof: db,
+			keyspace: child_info.map(|c| c.keyspace().to_vec()).unwrap_or_default(),
+			recorded: Default::default(),
+		};
+		Ok(TrieBackend::new(storage, root))
+	} else {
+		Err(Box::new(ExecutionError::InvalidProof))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::InMemoryBackend;
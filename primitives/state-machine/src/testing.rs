@@ -113,6 +113,20 @@ impl<H: Hasher, N: ChangesTrieBlockNumber> TestExternalities<H, N>
 		self.extensions.register(ext);
 	}
 
+	/// Enable or disable collecting the offchain database changes made by the runtime.
+	pub fn set_offchain_indexing(&mut self, enabled: bool) {
+		self.overlay.set_offchain_indexing(enabled);
+	}
+
+	/// Return all pending offchain database changes.
+	///
+	/// A value of `None` means that it was deleted.
+	pub fn offchain_changes(&self) -> Vec<(StorageKey, Option<StorageValue>)> {
+		let mut changes = self.overlay.committed.offchain.clone();
+		changes.extend(self.overlay.prospective.offchain.clone());
+		changes.into_iter().collect()
+	}
+
 	/// Get mutable reference to changes trie storage.
 	pub fn changes_trie_storage(&mut self) -> &mut ChangesTrieInMemoryStorage<H, N> {
 		&mut self.changes_trie_storage
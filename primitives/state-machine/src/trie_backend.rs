@@ -262,7 +262,7 @@ pub mod tests {
 	const CHILD_UUID_1: &[u8] = b"unique_id_1";
 	const CHILD_INFO_1: ChildInfo<'static> = ChildInfo::new_default(CHILD_UUID_1);
 
-	fn test_db() -> (PrefixedMemoryDB<BlakeTwo256>, H256) {
+	pub(crate) fn test_db() -> (PrefixedMemoryDB<BlakeTwo256>, H256) {
 		let mut root = H256::default();
 		let mut mdb = PrefixedMemoryDB::<BlakeTwo256>::default();
 		{
@@ -44,6 +44,11 @@ pub struct PoolStatus {
 	pub future: usize,
 	/// Sum of bytes of ready transaction encodings.
 	pub future_bytes: usize,
+	/// Number of transactions evicted because the queues were full, since the pool was created.
+	pub evicted: usize,
+	/// Number of transactions evicted because their sender had too many transactions in the pool,
+	/// since the pool was created.
+	pub sender_evicted: usize,
 }
 
 impl PoolStatus {
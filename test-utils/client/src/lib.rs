@@ -88,6 +88,12 @@ impl<Block: BlockT, Executor, G: GenesisInit> TestClientBuilder<Block, Executor,
 		Self::with_backend(backend)
 	}
 
+	/// Create new `TestClientBuilder` with default backend that keeps an index of key changes.
+	pub fn with_key_history() -> Self {
+		let backend = Arc::new(Backend::new_test_with_key_history(std::u32::MAX, std::u64::MAX));
+		Self::with_backend(backend)
+	}
+
 	/// Create new `TestClientBuilder` with default backend and pruning window size
 	pub fn with_pruning_window(keep_blocks: u32) -> Self {
 		let backend = Arc::new(Backend::new_test(keep_blocks, 0));
@@ -81,8 +81,10 @@ where
 		impl_name: "parity-substrate",
 		impl_version: "0.0.0",
 		offchain_worker: Default::default(),
+		offchain_indexing: Default::default(),
 		prometheus_config: Default::default(),
 		pruning: Default::default(),
+		key_history: false,
 		rpc_cors: Default::default(),
 		rpc_http: Default::default(),
 		rpc_ws: Default::default(),
@@ -94,6 +96,8 @@ where
 		transaction_pool: Default::default(),
 		wasm_method: Default::default(),
 		max_runtime_instances: 8,
+		wasm_runtime_cache_path: None,
+		wasm_stack_limit: Default::default(),
 		announce_block: true,
 	};
 